@@ -0,0 +1,203 @@
+// Author: 金书记
+//
+//! sa-token-rust Axum WebSocket 聊天示例
+//!
+//! 展示如何：
+//! 1. 用 WsAuthManager 认证 WebSocket 握手
+//! 2. 用 WsConnectionRegistry 管理多设备连接，并与 OnlineManager 同步在线状态
+//! 3. 用 WebSocketPusher 推送踢人通知，随后以关闭帧（4001）断开连接
+//!    （socket 收发由插件的 `ws::serve_socket` 驱动，需要 `ws` feature）
+//! 4. 登出、踢人、同设备重复连接时自动以对应的关闭码断开
+
+use std::sync::Arc;
+use std::collections::HashMap;
+use axum::{
+    Router,
+    routing::{get, post},
+    Json,
+    extract::{Path, Query, State, ws::{WebSocket, WebSocketUpgrade}},
+    response::{IntoResponse, Response},
+    http::{HeaderMap, StatusCode},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sa_token_plugin_axum::*;
+use sa_token_plugin_axum::ws::{header_map, serve_socket};
+
+/// 应用状态
+#[derive(Clone)]
+pub struct AppState {
+    pub sa_token: SaTokenState,
+    pub ws_auth: Arc<WsAuthManager>,
+    pub registry: Arc<WsConnectionRegistry>,
+    pub online: Arc<OnlineManager>,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_target(false)
+        .compact()
+        .init();
+
+    tracing::info!("🚀 启动 sa-token-rust Axum WebSocket 聊天示例");
+
+    // 1. 在线用户管理器 + 连接注册表，注册表连接变化时自动标记上线/下线
+    let online = Arc::new(OnlineManager::new());
+    let registry = Arc::new(WsConnectionRegistry::new().with_online_manager(online.clone()));
+
+    // 2. 踢人通知通过 WebSocket 推送
+    online.register_pusher(Arc::new(WebSocketPusher::new(registry.clone()))).await;
+
+    // 3. 注册表作为事件监听器：登出 → 4002，踢人 → 4001，顶号 → 4003，封禁 → 4004
+    let sa_token_state = SaTokenState::builder()
+        .storage(Arc::new(MemoryStorage::new()))
+        .token_name("Authorization")
+        .timeout(86400)
+        .online_manager(online.clone())
+        .register_listener(registry.clone())
+        .build();
+
+    let app_state = AppState {
+        ws_auth: Arc::new(WsAuthManager::new(sa_token_state.manager.clone())),
+        sa_token: sa_token_state.clone(),
+        registry,
+        online,
+    };
+
+    let app = Router::new()
+        .route("/api/login", post(login))
+        .route("/api/logout", post(logout))
+        .route("/api/online", get(online_users))
+        // 仅为演示，真实项目中需要管理员权限
+        .route("/api/admin/kick/{login_id}", post(kick_out))
+        .route("/ws", get(ws_handler))
+        .layer(SaTokenLayer::new(sa_token_state))
+        .with_state(app_state);
+
+    let addr = "127.0.0.1:3000";
+    tracing::info!("📡 服务器运行在 http://{}", addr);
+    tracing::info!("   WebSocket: ws://{}/ws?token=<token>&device=web", addr);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
+
+// ==================== 请求/响应结构 ====================
+
+#[derive(Debug, Deserialize)]
+pub struct LoginRequest {
+    pub username: String,
+    pub device: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LoginResponse {
+    pub token: String,
+    pub login_id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OnlineSession {
+    pub login_id: String,
+    pub device: String,
+    pub connect_time: String,
+}
+
+fn error_response(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(json!({ "code": status.as_u16(), "message": message.into() }))).into_response()
+}
+
+// ==================== HTTP 接口 ====================
+
+/// 登录，演示用户名即 login_id
+async fn login(
+    State(state): State<AppState>,
+    Json(req): Json<LoginRequest>,
+) -> Response {
+    let device = req.device.unwrap_or_else(|| "web".to_string());
+    let options = LoginOptions::new().device(device);
+    match state.sa_token.manager.login_with(req.username.clone(), options).await {
+        Ok(token) => Json(LoginResponse {
+            token: token.as_str().to_string(),
+            login_id: req.username,
+        }).into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+/// 登出，该 token 的 WebSocket 连接会以 4002 关闭
+async fn logout(
+    State(state): State<AppState>,
+    SaTokenExtractor(token): SaTokenExtractor,
+) -> Response {
+    match state.sa_token.manager.logout(&token).await {
+        Ok(()) => Json(json!({ "message": "logged out" })).into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+/// 踢人下线，先推送 kick_out 消息，再以 4001 关闭该用户所有连接
+async fn kick_out(
+    State(state): State<AppState>,
+    Path(login_id): Path<String>,
+) -> Response {
+    match state.sa_token.manager.kick_out(&login_id).await {
+        Ok(()) => Json(json!({ "message": format!("{} kicked out", login_id) })).into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+/// 在线用户及其设备
+async fn online_users(State(state): State<AppState>) -> Json<Vec<OnlineSession>> {
+    let mut sessions = Vec::new();
+    for login_id in state.online.get_online_users().await {
+        for user in state.online.get_user_sessions(&login_id).await {
+            sessions.push(OnlineSession {
+                login_id: user.login_id,
+                device: user.device,
+                connect_time: user.connect_time.to_rfc3339(),
+            });
+        }
+    }
+    Json(sessions)
+}
+
+// ==================== WebSocket ====================
+
+/// 握手时认证，token 可来自 Authorization 头或 `?token=` 参数
+async fn ws_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<HashMap<String, String>>,
+) -> Response {
+    let auth = match state.ws_auth.authenticate(&header_map(&headers), &query).await {
+        Ok(auth) => auth,
+        Err(e) => return error_response(StatusCode::UNAUTHORIZED, e.to_string()),
+    };
+    let device = query.get("device").cloned().unwrap_or_else(|| "web".to_string());
+
+    ws.on_upgrade(move |socket| handle_socket(socket, state, auth, device))
+}
+
+async fn handle_socket(socket: WebSocket, state: AppState, auth: WsAuthInfo, device: String) {
+    tracing::info!("🔌 {} 连接 ({})", auth.login_id, device);
+    let login_id = auth.login_id.clone();
+
+    // 注册表 → socket 的推送与关闭帧由 serve_socket 处理；收到的聊天消息广播给所有在线连接
+    let registry = state.registry.clone();
+    serve_socket(socket, state.registry, auth, device, move |connection, text| {
+        let registry = registry.clone();
+        async move {
+            let chat = PushMessage::new(MessageType::Text, text)
+                .with_metadata("from", connection.login_id.as_str())
+                .with_metadata("device", connection.device.as_str());
+            registry.broadcast(chat).await;
+        }
+    }).await;
+
+    tracing::info!("👋 {} 断开", login_id);
+}
@@ -176,7 +176,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let manager = SaTokenConfig::builder()
         .timeout(7200)  // 2小时过期 | 2 hours expiration
         .storage(Arc::new(MemoryStorage::new()))
-        .register_listener(Arc::new(LoggingListener))  // 日志监听器 | Logging listener
+        .register_listener(Arc::new(LoggingListener::new()))  // 日志监听器 | Logging listener
         .register_listener(behavior_listener.clone() as Arc<dyn SaTokenListener>)  // 行为监听器 | Behavior listener
         .register_listener(Arc::new(SecurityMonitorListener))  // 安全监听器 | Security listener
         .register_listener(stats_listener.clone() as Arc<dyn SaTokenListener>)  // 统计监听器 | Statistics listener
@@ -9,7 +9,7 @@
 //! ### 方式1: 独立使用核心库（本示例）
 //! ```ignore
 //! use sa_token_core::config::{SaTokenConfig, TokenStyle};
-//! use sa_token_core::token::TokenGenerator;
+//! use sa_token_core::token::DefaultTokenGenerator;
 //! ```
 //!
 //! ### 方式2: 使用 Web 框架插件（推荐）
@@ -23,7 +23,7 @@
 
 use sa_token_core::{
     config::{SaTokenConfig, TokenStyle},
-    token::TokenGenerator,
+    token::DefaultTokenGenerator,
 };
 
 fn main() {
@@ -37,7 +37,7 @@ fn main() {
     // 1. UUID 风格 | 1. UUID Style
     // ========================================
     println!("1. UUID 风格 | UUID Style:");
-    let token = TokenGenerator::generate_with_login_id(
+    let token = DefaultTokenGenerator::generate_with_login_id(
         &SaTokenConfig { token_style: TokenStyle::Uuid, ..Default::default() },
         test_login_id
     );
@@ -48,7 +48,7 @@ fn main() {
     // 2. Simple UUID 风格 | 2. Simple UUID Style
     // ========================================
     println!("2. Simple UUID 风格（无横杠）| Simple UUID Style (no hyphens):");
-    let token = TokenGenerator::generate_with_login_id(
+    let token = DefaultTokenGenerator::generate_with_login_id(
         &SaTokenConfig { token_style: TokenStyle::SimpleUuid, ..Default::default() },
         test_login_id
     );
@@ -57,7 +57,7 @@ fn main() {
     
     // 3. Random32 风格
     println!("3. Random32 风格:");
-    let token = TokenGenerator::generate_with_login_id(
+    let token = DefaultTokenGenerator::generate_with_login_id(
         &SaTokenConfig { token_style: TokenStyle::Random32, ..Default::default() },
         test_login_id
     );
@@ -66,7 +66,7 @@ fn main() {
     
     // 4. Random64 风格
     println!("4. Random64 风格:");
-    let token = TokenGenerator::generate_with_login_id(
+    let token = DefaultTokenGenerator::generate_with_login_id(
         &SaTokenConfig { token_style: TokenStyle::Random64, ..Default::default() },
         test_login_id
     );
@@ -79,7 +79,7 @@ fn main() {
         token_style: TokenStyle::Hash,
         ..Default::default()
     };
-    let token = TokenGenerator::generate_with_login_id(&config_hash, test_login_id);
+    let token = DefaultTokenGenerator::generate_with_login_id(&config_hash, test_login_id);
     println!("   Token: {}", token.as_str());
     println!("   长度: {} 字符", token.as_str().len());
     println!("   说明: SHA256(login_id + timestamp + UUID)\n");
@@ -90,7 +90,7 @@ fn main() {
         token_style: TokenStyle::Timestamp,
         ..Default::default()
     };
-    let token = TokenGenerator::generate_with_login_id(&config_timestamp, test_login_id);
+    let token = DefaultTokenGenerator::generate_with_login_id(&config_timestamp, test_login_id);
     println!("   Token: {}", token.as_str());
     println!("   长度: {} 字符", token.as_str().len());
     println!("   说明: 毫秒级时间戳_16位随机字符\n");
@@ -101,11 +101,35 @@ fn main() {
         token_style: TokenStyle::Tik,
         ..Default::default()
     };
-    let token = TokenGenerator::generate_with_login_id(&config_tik, test_login_id);
+    let token = DefaultTokenGenerator::generate_with_login_id(&config_tik, test_login_id);
     println!("   Token: {}", token.as_str());
     println!("   长度: {} 字符", token.as_str().len());
     println!("   说明: 8位字母数字混合（URL安全）\n");
     
+    // ========================================
+    // 自定义生成器 | Custom Generator
+    // ========================================
+    println!("自定义生成器 | Custom Generator:");
+    let config_custom = SaTokenConfig {
+        token_style: TokenStyle::custom(|login_id: &str| format!("tk_{}_{}", login_id, chrono::Utc::now().timestamp_micros())),
+        ..Default::default()
+    };
+    let token = DefaultTokenGenerator::generate_with_login_id(&config_custom, test_login_id);
+    println!("   Token: {}", token.as_str());
+    println!("   说明: 实现 TokenGenerator trait 或传入闭包\n");
+    
+    // ========================================
+    // 序列风格 | Sequence Style
+    // ========================================
+    println!("序列风格 | Sequence Style:");
+    let config_sequence = SaTokenConfig {
+        token_style: TokenStyle::sequence("order-"),
+        ..Default::default()
+    };
+    let token = DefaultTokenGenerator::generate_with_login_id(&config_sequence, test_login_id);
+    println!("   Token: {}", token.as_str());
+    println!("   说明: 前缀 + 递增序号，经 SaTokenManager 登录时序号由存储分配\n");
+    
     // 生成多个 Token 验证唯一性
     println!("\n========================================");
     println!("验证新 Token 风格的唯一性");
@@ -113,20 +137,20 @@ fn main() {
     
     println!("Hash 风格 (3次生成):");
     for i in 1..=3 {
-        let token = TokenGenerator::generate_with_login_id(&config_hash, test_login_id);
+        let token = DefaultTokenGenerator::generate_with_login_id(&config_hash, test_login_id);
         println!("  #{}: {}", i, token.as_str());
     }
     
     println!("\nTimestamp 风格 (3次生成):");
     for i in 1..=3 {
-        let token = TokenGenerator::generate_with_login_id(&config_timestamp, test_login_id);
+        let token = DefaultTokenGenerator::generate_with_login_id(&config_timestamp, test_login_id);
         println!("  #{}: {}", i, token.as_str());
         std::thread::sleep(std::time::Duration::from_millis(10));
     }
     
     println!("\nTik 风格 (5次生成 - 展示短小特性):");
     for i in 1..=5 {
-        let token = TokenGenerator::generate_with_login_id(&config_tik, test_login_id);
+        let token = DefaultTokenGenerator::generate_with_login_id(&config_tik, test_login_id);
         println!("  #{}: {}", i, token.as_str());
     }
     
@@ -143,6 +167,8 @@ fn main() {
     println!("Hash       | 64 字符 | SHA256 哈希，包含用户信息");
     println!("Timestamp  | ~30字符 | 包含时间信息，便于追溯");
     println!("Tik        | 8 字符  | 短小精悍，适合分享链接");
+    println!("Sequence   | 变长    | 前缀 + 递增序号");
+    println!("Custom     | 变长    | 自定义 TokenGenerator");
     println!("JWT        | 变长    | 包含完整信息的自包含令牌\n");
     
     println!("========================================");
@@ -65,6 +65,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         connect_time: ws_auth.connect_time,
         last_activity: ws_auth.connect_time,
         metadata: HashMap::new(),
+        login_ip: None,
+        user_agent: None,
     };
     online_manager.mark_online(online_user).await;
     println!("   User1 marked as online");
@@ -85,6 +87,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             connect_time: ws_auth.connect_time,
             last_activity: ws_auth.connect_time,
             metadata: HashMap::new(),
+            login_ip: None,
+            user_agent: None,
         };
         online_manager.mark_online(online_user).await;
         println!("   User{} online", i);
@@ -0,0 +1,132 @@
+// Author: 金书记
+//
+//! 时钟 | Clock
+//!
+//! 过期判断使用的时间源。`SaTokenManager`、`OAuth2Manager` 以及自行计算 TTL 的存储
+//! （内存、数据库）都可以注入 [`Clock`]，测试中换成 [`MockClock`] 后，过期逻辑不再依赖
+//! 真实时间。Redis 的过期由服务端计算，不受时钟影响。
+//!
+//! The time source used for expiry checks. `SaTokenManager`, `OAuth2Manager`
+//! and the storages that do their own TTL math (memory, database) accept a
+//! [`Clock`]; with a [`MockClock`] in tests, expiry no longer depends on the
+//! wall clock. Redis computes expiry server-side and ignores the clock.
+//!
+//! ```rust,ignore
+//! let clock = MockClock::new(Utc::now());
+//! let storage = MemoryStorage::with_clock(Arc::new(clock.clone()));
+//!
+//! storage.set("k", "v", Some(Duration::from_secs(60))).await?;
+//! clock.advance(chrono::Duration::seconds(61));
+//! assert_eq!(storage.get("k").await?, None);
+//! ```
+
+use std::sync::{Arc, Mutex};
+use chrono::{DateTime, Duration, Utc};
+
+/// 时间源 | Time source
+pub trait Clock: Send + Sync {
+    /// 当前时间 | Current time
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// 系统时钟 | System wall clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// 手动控制的时钟，克隆出的句柄共享同一时间 | Manually controlled clock; clones share the same time
+///
+/// `sa_token_core::time` 的测试时钟也基于它：`time::freeze()` 返回的时钟注入 manager 或存储后，
+/// 两边读到的是同一个时间。
+/// The test clock of `sa_token_core::time` is built on it as well: inject the
+/// clock returned by `time::freeze()` and both sides read the same time.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    state: Arc<Mutex<MockTime>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum MockTime {
+    /// 时间停在某一刻 | Time stands still
+    Frozen(DateTime<Utc>),
+    /// 时间照常流逝，但整体拨快 | Time flows, shifted by an offset
+    Offset(Duration),
+}
+
+impl MockClock {
+    /// 创建停在 `start` 的时钟 | Create a clock frozen at `start`
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self::with_state(MockTime::Frozen(start))
+    }
+
+    /// 创建随真实时间流逝、但拨快了 `by` 的时钟 | Create a clock that follows the wall clock shifted by `by`
+    pub fn offset(by: Duration) -> Self {
+        Self::with_state(MockTime::Offset(by))
+    }
+
+    fn with_state(state: MockTime) -> Self {
+        Self { state: Arc::new(Mutex::new(state)) }
+    }
+
+    /// 时钟前进；流逝中的时钟继续流逝 | Move the clock forward; a flowing clock keeps flowing
+    pub fn advance(&self, by: Duration) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        *state = match *state {
+            MockTime::Frozen(at) => MockTime::Frozen(at + by),
+            MockTime::Offset(offset) => MockTime::Offset(offset + by),
+        };
+    }
+
+    /// 把时钟冻结在指定时间 | Freeze the clock at `to`
+    pub fn set(&self, to: DateTime<Utc>) {
+        *self.state.lock().unwrap_or_else(|e| e.into_inner()) = MockTime::Frozen(to);
+    }
+}
+
+impl Default for MockClock {
+    /// 停在创建时刻的时钟 | A clock frozen at the moment of creation
+    fn default() -> Self {
+        Self::new(Utc::now())
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        match *self.state.lock().unwrap_or_else(|e| e.into_inner()) {
+            MockTime::Frozen(at) => at,
+            MockTime::Offset(offset) => Utc::now() + offset,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_clock_advance_is_shared() {
+        let clock = MockClock::default();
+        let start = clock.now();
+        let handle = clock.clone();
+
+        handle.advance(Duration::minutes(5));
+        assert_eq!(clock.now(), start + Duration::minutes(5));
+
+        clock.set(start);
+        assert_eq!(handle.now(), start);
+    }
+
+    #[test]
+    fn test_offset_clock_keeps_flowing() {
+        let clock = MockClock::offset(Duration::days(1));
+        assert!(clock.now() > Utc::now() + Duration::hours(23));
+
+        clock.advance(Duration::days(1));
+        assert!(clock.now() > Utc::now() + Duration::hours(47));
+    }
+}
@@ -49,9 +49,20 @@ pub trait SaRequest {
         None // 默认实现
     }
     
+    /// 获取连接对端的IP（直连的客户端，或前置的反向代理）
+    fn remote_addr(&self) -> Option<String> {
+        None // 默认实现
+    }
+    
     /// 获取客户端IP
+    /// 
+    /// 默认依次取 `X-Forwarded-For` 的第一个地址、`X-Real-IP`，否则取连接对端的IP
     fn get_client_ip(&self) -> Option<String> {
-        None // 默认实现
+        self.get_header("x-forwarded-for")
+            .and_then(|v| v.split(',').next().map(|ip| ip.trim().to_string()))
+            .filter(|ip| !ip.is_empty())
+            .or_else(|| self.get_header("x-real-ip").map(|ip| ip.trim().to_string()))
+            .or_else(|| self.remote_addr())
     }
     
     /// 获取User-Agent
@@ -108,7 +119,7 @@ pub struct CookieOptions {
 }
 
 /// SameSite 属性
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SameSite {
     Strict,
     Lax,
@@ -124,3 +135,33 @@ impl std::fmt::Display for SameSite {
         }
     }
 }
+
+/// 待写回响应的 Cookie | Cookie to write back to the response
+///
+/// 中间件在请求结束后通过 [`SaResponse::set_cookie`] 或 `Set-Cookie` 头写出，
+/// 同一响应中已有的其他 Cookie 不受影响。
+/// Middlewares write it after the handler, through [`SaResponse::set_cookie`]
+/// or an appended `Set-Cookie` header; other cookies on the response are kept.
+#[derive(Debug, Clone)]
+pub struct ResponseCookie {
+    /// Cookie 名称 | Cookie name
+    pub name: String,
+    
+    /// Cookie 值，删除时为空 | Cookie value, empty when deleting
+    pub value: String,
+    
+    /// Cookie 选项 | Cookie options
+    pub options: CookieOptions,
+}
+
+impl ResponseCookie {
+    /// `Set-Cookie` 头的值 | Value of the `Set-Cookie` header
+    pub fn header_value(&self) -> String {
+        crate::utils::build_cookie_string(&self.name, &self.value, self.options.clone())
+    }
+    
+    /// 通过响应适配器写出 | Write through a response adapter
+    pub fn write_to<R: SaResponse>(&self, response: &mut R) {
+        response.set_cookie(&self.name, &self.value, self.options.clone());
+    }
+}
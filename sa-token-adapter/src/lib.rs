@@ -8,13 +8,16 @@
 //! - 存储适配器
 //! - 请求/响应上下文适配器
 //! - 框架集成适配器
+//! - 时钟（过期判断使用的时间源）
 
 pub mod storage;
+pub mod clock;
 pub mod context;
 pub mod framework;
 pub mod utils;
 
-pub use storage::SaStorage;
-pub use context::{SaRequest, SaResponse, CookieOptions, SameSite};
+pub use storage::{SaStorage, KeyTtl, ScanPage};
+pub use clock::{Clock, SystemClock, MockClock};
+pub use context::{SaRequest, SaResponse, CookieOptions, SameSite, ResponseCookie};
 pub use framework::FrameworkAdapter;
-pub use utils::{parse_cookies, parse_query_string, build_cookie_string, extract_bearer_token};
+pub use utils::{parse_cookies, parse_query_string, build_cookie_string, extract_bearer_token, strip_token_scheme, DEFAULT_TOKEN_SCHEMES};
@@ -3,6 +3,7 @@
 //! 存储适配器trait定义
 
 use async_trait::async_trait;
+use std::sync::Arc;
 use std::time::Duration;
 use thiserror::Error;
 
@@ -26,6 +27,52 @@ pub enum StorageError {
     InternalError(String),
 }
 
+/// 键的剩余有效期
+/// 
+/// 区分“永不过期”与“键不存在”，两者在续期等逻辑中的处理方式不同
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyTtl {
+    /// 键存在且永不过期
+    Persistent,
+    /// 键存在，将在给定时间后过期
+    Expires(Duration),
+    /// 键不存在或已过期
+    Missing,
+}
+
+impl KeyTtl {
+    /// 键是否存在
+    pub fn exists(&self) -> bool {
+        !matches!(self, KeyTtl::Missing)
+    }
+    
+    /// 剩余有效期，仅 `Expires` 时有值
+    pub fn remaining(&self) -> Option<Duration> {
+        match self {
+            KeyTtl::Expires(duration) => Some(*duration),
+            _ => None,
+        }
+    }
+}
+
+/// 分页扫描键的一页结果
+/// 
+/// `cursor` 为下一次扫描的游标，为 0 表示扫描结束
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanPage {
+    /// 下一页的游标，0 表示没有更多数据
+    pub cursor: u64,
+    /// 本页匹配的键（同一个键可能在多页中重复出现）
+    pub keys: Vec<String>,
+}
+
+impl ScanPage {
+    /// 是否为最后一页
+    pub fn is_last(&self) -> bool {
+        self.cursor == 0
+    }
+}
+
 /// 存储适配器trait
 /// 
 /// 所有存储实现（内存、Redis、数据库等）都需要实现这个trait
@@ -52,7 +99,9 @@ pub trait SaStorage: Send + Sync {
     async fn expire(&self, key: &str, ttl: Duration) -> StorageResult<()>;
     
     /// 获取剩余过期时间
-    async fn ttl(&self, key: &str) -> StorageResult<Option<Duration>>;
+    /// 
+    /// 键不存在（或已过期）时返回 `KeyTtl::Missing`，永不过期时返回 `KeyTtl::Persistent`
+    async fn ttl(&self, key: &str) -> StorageResult<KeyTtl>;
     
     /// 批量获取
     async fn mget(&self, keys: &[&str]) -> StorageResult<Vec<Option<String>>> {
@@ -79,6 +128,18 @@ pub trait SaStorage: Send + Sync {
         Ok(())
     }
     
+    /// 键不存在（或已过期）时写入，返回是否写入成功
+    /// 
+    /// 用于 nonce 等只能使用一次的键：值与过期时间在一次调用中写入，不会留下永不过期的键。
+    /// 默认实现先判断再写入，并发时不是原子的，各存储应使用后端的原子操作覆盖它。
+    async fn set_nx(&self, key: &str, value: &str, ttl: Option<Duration>) -> StorageResult<bool> {
+        if self.exists(key).await? {
+            return Ok(false);
+        }
+        self.set(key, value, ttl).await?;
+        Ok(true)
+    }
+    
     /// 原子递增
     async fn incr(&self, key: &str) -> StorageResult<i64> {
         let current = self.get(key).await?
@@ -110,4 +171,57 @@ pub trait SaStorage: Send + Sync {
         // 默认实现：不支持模式匹配，返回空列表
         Ok(Vec::new())
     }
+    
+    /// 分页扫描匹配模式的键
+    /// 
+    /// 从游标 0 开始，每次传入上一页返回的游标，直到返回的游标为 0。键很多时用它代替
+    /// `keys`，避免一次性加载全部键或长时间阻塞存储。
+    /// 
+    /// # 参数
+    /// * `pattern` - 匹配模式，支持 * 通配符
+    /// * `cursor` - 游标，首次调用传 0
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// let mut cursor = 0;
+    /// loop {
+    ///     let page = storage.scan_keys("sa:token:*", cursor).await?;
+    ///     for key in &page.keys { /* ... */ }
+    ///     if page.is_last() { break; }
+    ///     cursor = page.cursor;
+    /// }
+    /// ```
+    async fn scan_keys(&self, pattern: &str, cursor: u64) -> StorageResult<ScanPage> {
+        // 默认实现：一页返回 `keys` 的全部结果
+        let _ = cursor;
+        Ok(ScanPage { cursor: 0, keys: self.keys(pattern).await? })
+    }
+    
+    /// 检查后端是否可用，不可用时返回错误
+    /// 
+    /// `StorageSupervisor` 定期调用它判断存储的健康状态。
+    /// Checks that the backend is reachable; `StorageSupervisor` calls it periodically.
+    async fn health_check(&self) -> StorageResult<()> {
+        // 默认实现：查询一个探测键
+        self.exists("sa:health-check").await.map(|_| ())
+    }
+}
+
+/// 订阅到的失效通知的处理函数，参数为要丢弃的键 | Handler of received invalidations, called with the keys to drop
+pub type InvalidationHandler = Arc<dyn Fn(Vec<String>) + Send + Sync>;
+
+/// 跨实例的缓存失效通道 | Cross-instance cache invalidation channel
+/// 
+/// 本地缓存（如 `sa_token_core::l1_cache::CachedStorage`）写入后通过它通知其他实例
+/// 丢弃对应的键。Redis 部署可使用 `sa_token_storage_redis::RedisCacheInvalidation`。
+/// 
+/// Local caches announce their writes through it so other instances drop
+/// the same keys. Redis deployments can use `RedisCacheInvalidation`.
+#[async_trait]
+pub trait CacheInvalidation: Send + Sync {
+    /// 通知所有实例丢弃这些键 | Tell every instance to drop these keys
+    async fn publish(&self, keys: &[String]) -> StorageResult<()>;
+    
+    /// 订阅失效通知，之后收到的每条通知都交给 `handler` | Subscribe; every later notification goes to `handler`
+    async fn subscribe(&self, handler: InvalidationHandler) -> StorageResult<()>;
 }
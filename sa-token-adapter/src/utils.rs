@@ -26,6 +26,7 @@
 //    - 支持所有标准 cookie 属性（Domain、Path、Max-Age、Secure、HttpOnly、SameSite）
 //
 // 4. Header 解析
+//    - strip_token_scheme(): 去掉 token 前的认证方案（Bearer、Token 或自定义前缀，不区分大小写）
 //    - extract_bearer_token(): 从 Authorization 头提取 Bearer token
 //
 // 使用场景 | Use Cases:
@@ -156,6 +157,50 @@ pub fn build_cookie_string(name: &str, value: &str, options: CookieOptions) -> S
     cookie
 }
 
+/// 中文 | English
+/// 默认接受的认证方案 | Authorization schemes accepted by default
+pub const DEFAULT_TOKEN_SCHEMES: &[&str] = &["Bearer"];
+
+/// 中文 | English
+/// 去掉 token 前的认证方案 | Strip the authorization scheme in front of a token
+///
+/// 方案不区分大小写；以字母或数字结尾的方案（如 `Bearer`）与 token 之间必须有空白，
+/// 以符号结尾的自定义前缀（如 `ak.`）可直接接 token。未匹配任何方案时返回 None。
+///
+/// Schemes match case-insensitively. A scheme ending in a letter or digit
+/// (such as `Bearer`) must be followed by whitespace; a custom prefix ending
+/// in a symbol (such as `ak.`) may be followed by the token directly.
+/// Returns None when no scheme matches.
+///
+/// # 示例 | Example
+/// ```
+/// use sa_token_adapter::utils::strip_token_scheme;
+///
+/// assert_eq!(strip_token_scheme("bearer abc", &["Bearer", "Token"]), Some("abc"));
+/// assert_eq!(strip_token_scheme("Token abc", &["Bearer", "Token"]), Some("abc"));
+/// assert_eq!(strip_token_scheme("ak.abc", &["ak."]), Some("abc"));
+/// assert_eq!(strip_token_scheme("Bearerabc", &["Bearer"]), None);
+/// ```
+pub fn strip_token_scheme<'a, S: AsRef<str>>(value: &'a str, schemes: &[S]) -> Option<&'a str> {
+    let value = value.trim();
+    schemes.iter().find_map(|scheme| {
+        let scheme = scheme.as_ref().trim();
+        if scheme.is_empty() {
+            return None;
+        }
+        let head = value.get(..scheme.len())?;
+        if !head.eq_ignore_ascii_case(scheme) {
+            return None;
+        }
+        let rest = &value[scheme.len()..];
+        let needs_space = scheme.ends_with(|c: char| c.is_alphanumeric());
+        if needs_space && !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+            return None;
+        }
+        Some(rest.trim())
+    })
+}
+
 /// 中文 | English
 /// 从 Authorization 头提取 Bearer token | Extract Bearer token from Authorization header
 ///
@@ -176,9 +221,9 @@ pub fn build_cookie_string(name: &str, value: &str, options: CookieOptions) -> S
 /// assert_eq!(invalid, None);
 /// ```
 pub fn extract_bearer_token(auth_header: &str) -> Option<String> {
-    auth_header
-        .strip_prefix("Bearer ")
-        .map(|token| token.trim().to_string())
+    strip_token_scheme(auth_header, DEFAULT_TOKEN_SCHEMES)
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
 }
 
 #[cfg(test)]
@@ -232,6 +277,20 @@ mod tests {
         assert_eq!(extract_bearer_token("Bearer  token_with_spaces  "), Some("token_with_spaces".to_string()));
         assert_eq!(extract_bearer_token("Basic xyz"), None);
         assert_eq!(extract_bearer_token("Bearer"), None);
+        assert_eq!(extract_bearer_token("bearer abc123xyz"), Some("abc123xyz".to_string()));
+        assert_eq!(extract_bearer_token("BEARER\tabc123xyz"), Some("abc123xyz".to_string()));
+    }
+
+    #[test]
+    fn test_strip_token_scheme() {
+        let schemes = ["Bearer", "Token", "sa-"];
+        assert_eq!(strip_token_scheme("  TOKEN  abc  ", &schemes), Some("abc"));
+        assert_eq!(strip_token_scheme("Sa-abc", &schemes), Some("abc"));
+        assert_eq!(strip_token_scheme("Bearer ", &schemes), Some(""));
+        assert_eq!(strip_token_scheme("Basic abc", &schemes), None);
+        assert_eq!(strip_token_scheme("Tokenabc", &schemes), None);
+        // 多字节字符不会在字符中间切开
+        assert_eq!(strip_token_scheme("令牌 abc", &schemes), None);
     }
 }
 
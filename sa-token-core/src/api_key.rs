@@ -0,0 +1,189 @@
+// Author: 金书记
+//
+//! API Key 认证 | API key authentication
+//!
+//! 为脚本、CI 等调用方签发静态 API Key，每个 Key 绑定一个账号与独立的权限列表。
+//! 存储中只保存 Key 的 SHA-256 摘要，Key 本身只在签发时返回一次。
+//!
+//! Issues static API keys for scripts, CI jobs and similar callers. Each key
+//! is bound to an account and carries its own permission list. Only the
+//! SHA-256 digest of a key is stored; the key itself is returned once, at
+//! creation.
+//!
+//! 框架插件把通过校验的 Key 作为另一种登录来源：请求上下文中的 `login_id` 为 Key
+//! 绑定的账号，`StpUtil::has_permission` 等检查使用 Key 自身的权限而不是账号的权限。
+//! Plugins treat a valid key as another login source: the request context
+//! carries the bound account as `login_id`, and checks such as
+//! `StpUtil::has_permission` use the key's own permissions instead of the
+//! account's.
+//!
+//! ```rust,ignore
+//! let api_keys = ApiKeyManager::new(storage.clone());
+//! let key = api_keys.create("deploy-bot", vec!["deploy:*".to_string()], None).await?;
+//!
+//! let info = api_keys.authenticate(headers.get(API_KEY_HEADER).and_then(|v| v.to_str().ok())).await?;
+//! assert!(info.has_permission("deploy:run"));
+//! ```
+
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+use sa_token_adapter::storage::SaStorage;
+use crate::error::{SaTokenError, SaTokenResult};
+use crate::permission::{has_permission_in, AuthzSnapshot};
+use crate::token::{TokenInfo, TokenValue};
+
+/// 携带 API Key 的请求头 | Header carrying the API key
+pub const API_KEY_HEADER: &str = "X-API-Key";
+
+/// 通过 API Key 登录时 `TokenInfo::login_type` 的值 | `TokenInfo::login_type` of API-key logins
+pub const API_KEY_LOGIN_TYPE: &str = "api-key";
+
+fn api_key_storage_key(key: &str) -> String {
+    format!("sa:api-key:{}", hex::encode(Sha256::digest(key.as_bytes())))
+}
+
+/// API Key 绑定的信息 | Information bound to an API key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiKeyInfo {
+    /// 绑定的账号 | Bound account
+    pub login_id: String,
+    /// Key 自身的权限，支持通配符 | The key's own permissions, wildcards allowed
+    pub permissions: Vec<String>,
+    /// 签发时间 | Creation time
+    pub create_time: DateTime<Utc>,
+    /// 过期时间，None 表示永不过期 | Expiry, None for never
+    pub expire_time: Option<DateTime<Utc>>,
+}
+
+impl ApiKeyInfo {
+    /// Key 是否拥有指定权限 | Whether the key grants the permission
+    pub fn has_permission(&self, permission: &str) -> bool {
+        has_permission_in(&self.permissions, permission)
+    }
+
+    /// 转换为请求上下文使用的 TokenInfo，权限快照为 Key 自身的权限
+    ///
+    /// Converts into the TokenInfo used by the request context, with the key's
+    /// permissions as the authz snapshot.
+    pub fn to_token_info(&self) -> TokenInfo {
+        let mut info = TokenInfo::new(TokenValue::new(API_KEY_LOGIN_TYPE), self.login_id.clone());
+        info.login_type = API_KEY_LOGIN_TYPE.to_string();
+        info.create_time = self.create_time;
+        info.expire_time = self.expire_time;
+        info.authz = Some(AuthzSnapshot {
+            permissions: self.permissions.clone(),
+            ..Default::default()
+        });
+        info
+    }
+}
+
+/// API Key 管理器 | API key manager
+#[derive(Clone)]
+pub struct ApiKeyManager {
+    storage: Arc<dyn SaStorage>,
+}
+
+impl ApiKeyManager {
+    pub fn new(storage: Arc<dyn SaStorage>) -> Self {
+        Self { storage }
+    }
+
+    /// 签发新的 API Key，返回 Key 本身（之后无法再次取回）
+    ///
+    /// Issues a new API key and returns it; it cannot be retrieved again later.
+    ///
+    /// # 参数 | Parameters
+    /// - `timeout`: 有效期（秒），None 表示永不过期 | Validity in seconds, None for never
+    pub async fn create(
+        &self,
+        login_id: impl Into<String>,
+        permissions: Vec<String>,
+        timeout: Option<i64>,
+    ) -> SaTokenResult<String> {
+        let key = format!("ak_{}", Uuid::new_v4().simple());
+        self.register(&key, login_id, permissions, timeout).await?;
+        Ok(key)
+    }
+
+    /// 登记已有的 Key（如配置文件中的静态 Key）| Register an existing key, e.g. a static one from config
+    pub async fn register(
+        &self,
+        key: &str,
+        login_id: impl Into<String>,
+        permissions: Vec<String>,
+        timeout: Option<i64>,
+    ) -> SaTokenResult<()> {
+        if key.is_empty() {
+            return Err(SaTokenError::ConfigError("api key must not be empty".to_string()));
+        }
+        let timeout = timeout.filter(|seconds| *seconds > 0);
+        let create_time = crate::time::now();
+        let info = ApiKeyInfo {
+            login_id: login_id.into(),
+            permissions,
+            create_time,
+            expire_time: timeout.map(|seconds| create_time + chrono::Duration::seconds(seconds)),
+        };
+        let json = serde_json::to_string(&info)?;
+        self.storage.set(&api_key_storage_key(key), &json, timeout.map(|seconds| Duration::from_secs(seconds as u64))).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+
+    /// Key 绑定的信息，不存在或已过期时返回 None | Info bound to a key, None when unknown or expired
+    pub async fn get(&self, key: &str) -> SaTokenResult<Option<ApiKeyInfo>> {
+        let Some(json) = self.storage.get(&api_key_storage_key(key)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+        else {
+            return Ok(None);
+        };
+        let info: ApiKeyInfo = serde_json::from_str(&json)?;
+        Ok(info.expire_time.is_none_or(|expire| expire > crate::time::now()).then_some(info))
+    }
+
+    /// 校验请求携带的 Key，缺失或无效时返回 `InvalidApiKey`
+    ///
+    /// Checks the key sent with a request; missing or unknown keys yield `InvalidApiKey`.
+    pub async fn authenticate(&self, key: Option<&str>) -> SaTokenResult<ApiKeyInfo> {
+        match key.filter(|key| !key.is_empty()) {
+            Some(key) => self.get(key).await?.ok_or(SaTokenError::InvalidApiKey),
+            None => Err(SaTokenError::InvalidApiKey),
+        }
+    }
+
+    /// 吊销 Key | Revoke a key
+    pub async fn revoke(&self, key: &str) -> SaTokenResult<()> {
+        self.storage.delete(&api_key_storage_key(key)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_storage_memory::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_create_authenticate_and_revoke() {
+        let storage = Arc::new(MemoryStorage::new());
+        let api_keys = ApiKeyManager::new(storage.clone());
+        let key = api_keys.create("deploy-bot", vec!["deploy:*".to_string()], None).await.unwrap();
+
+        let info = api_keys.authenticate(Some(&key)).await.unwrap();
+        assert_eq!(info.login_id, "deploy-bot");
+        assert!(info.has_permission("deploy:run"));
+        assert!(!info.has_permission("user:delete"));
+        assert_eq!(info.to_token_info().authz.unwrap().permissions, vec!["deploy:*"]);
+
+        // 存储中不保存明文 Key
+        assert!(storage.get(&format!("sa:api-key:{}", key)).await.unwrap().is_none());
+
+        api_keys.revoke(&key).await.unwrap();
+        assert!(matches!(api_keys.authenticate(Some(&key)).await, Err(SaTokenError::InvalidApiKey)));
+        assert!(matches!(api_keys.authenticate(None).await, Err(SaTokenError::InvalidApiKey)));
+    }
+}
@@ -0,0 +1,318 @@
+// Author: 金书记
+//
+//! 审计日志 | Audit log
+//!
+//! 把登录、登出、踢人、顶替、封禁、认证失败与鉴权拒绝等安全事件记录为 [`AuditRecord`]，
+//! 供安全审计页面按账号或时间段分页查询。记录由事件总线驱动，来源 IP 与 User-Agent
+//! 来自框架插件写入请求上下文的信息。
+//!
+//! Records security events (login, logout, kick-out, replacement, bans, auth
+//! failures and authorization denials) as [`AuditRecord`]s that audit screens
+//! page through by account or time range. Records are driven by the event
+//! bus; the client IP and User-Agent come from what the framework plugins put
+//! into the request context.
+//!
+//! ```rust,ignore
+//! let manager = SaTokenConfig::builder()
+//!     .storage(storage.clone())
+//!     .audit_logger(Arc::new(StorageAuditLogger::new(storage.clone())))
+//!     .build();
+//!
+//! let page = StpUtil::audit_logger().unwrap().find_by_login_id("10001", 1, 20).await?;
+//! ```
+
+use std::sync::Arc;
+use std::time::Duration;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+use sa_token_adapter::storage::SaStorage;
+use crate::error::{SaTokenError, SaTokenResult};
+use crate::event::{SaTokenEvent, SaTokenEventType, SaTokenListener};
+
+/// 审计记录的存储 key 前缀 | Storage key prefix of audit records
+pub const AUDIT_KEY_PREFIX: &str = "sa:audit:";
+
+/// 默认保留时长（秒），90 天 | Default retention in seconds, 90 days
+pub const DEFAULT_AUDIT_RETENTION: i64 = 90 * 86400;
+
+/// 一条审计记录 | One audit record
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// 记录 ID | Record ID
+    pub id: String,
+    /// 事件类型 | Event type
+    pub event_type: SaTokenEventType,
+    /// 账号，认证失败时可能为空 | Account; may be empty for auth failures
+    pub login_id: String,
+    /// 登录类型 | Login type
+    pub login_type: String,
+    /// 来源 IP | Client IP
+    pub ip: Option<String>,
+    /// User-Agent
+    pub user_agent: Option<String>,
+    /// 设备 | Device
+    pub device: Option<String>,
+    /// 发生时间 | When it happened
+    pub timestamp: DateTime<Utc>,
+    /// 事件附带的信息，如失败原因、被拒绝的权限 | Event details, e.g. failure reason or denied permission
+    pub detail: Option<Value>,
+}
+
+impl AuditRecord {
+    /// 由事件生成记录；不保存 token，避免审计日志泄露凭证
+    ///
+    /// Builds a record from an event. Tokens are left out so the audit log
+    /// does not leak credentials.
+    pub fn from_event(event: &SaTokenEvent) -> Self {
+        Self {
+            id: Uuid::new_v4().simple().to_string(),
+            event_type: event.event_type.clone(),
+            login_id: event.login_id.clone(),
+            login_type: event.login_type.clone(),
+            ip: event.ip.clone(),
+            user_agent: event.user_agent.clone(),
+            device: event.device.clone(),
+            timestamp: event.timestamp,
+            detail: event.extra.clone(),
+        }
+    }
+}
+
+/// 是否为写入审计日志的事件类型 | Whether events of this type go into the audit log
+pub fn is_audited(event_type: &SaTokenEventType) -> bool {
+    matches!(
+        event_type,
+        SaTokenEventType::Login
+            | SaTokenEventType::Logout
+            | SaTokenEventType::KickOut
+            | SaTokenEventType::Replaced
+            | SaTokenEventType::Banned
+            | SaTokenEventType::Unbanned
+            | SaTokenEventType::AuthFailed
+            | SaTokenEventType::PermissionDenied
+    )
+}
+
+/// 一页审计记录 | One page of audit records
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditPage {
+    /// 本页记录，按时间倒序 | Records on this page, newest first
+    pub records: Vec<AuditRecord>,
+    /// 所有页中符合条件的记录数 | Matching records across all pages
+    pub total: usize,
+    /// 页码，从 1 开始 | Page number, starting at 1
+    pub page: usize,
+    /// 每页条数 | Page size
+    pub page_size: usize,
+}
+
+impl AuditPage {
+    /// 按时间倒序排序后取出指定页 | Sort newest first and cut out the requested page
+    pub fn paginate(mut records: Vec<AuditRecord>, page: usize, page_size: usize) -> Self {
+        let page = page.max(1);
+        records.sort_by(|a, b| b.timestamp.cmp(&a.timestamp).then_with(|| b.id.cmp(&a.id)));
+        let total = records.len();
+        let records = records.into_iter()
+            .skip((page - 1).saturating_mul(page_size))
+            .take(page_size)
+            .collect();
+        Self { records, total, page, page_size }
+    }
+}
+
+/// 审计日志 | Audit logger
+///
+/// 默认实现为 [`StorageAuditLogger`]；写入数据库或日志平台时实现此 trait。
+/// [`StorageAuditLogger`] is the built-in implementation; implement this trait
+/// to write to a database or a log platform.
+#[async_trait]
+pub trait AuditLogger: Send + Sync {
+    /// 写入一条记录 | Write a record
+    async fn record(&self, record: AuditRecord) -> SaTokenResult<()>;
+
+    /// 按账号分页查询，`page` 从 1 开始 | Page through an account's records; `page` starts at 1
+    async fn find_by_login_id(&self, login_id: &str, page: usize, page_size: usize) -> SaTokenResult<AuditPage>;
+
+    /// 按时间段 `[from, to)` 分页查询 | Page through the records in `[from, to)`
+    async fn find_by_time_range(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        page: usize,
+        page_size: usize,
+    ) -> SaTokenResult<AuditPage>;
+}
+
+/// 基于 `SaStorage` 的审计日志 | Audit logger backed by `SaStorage`
+///
+/// 每条记录一个 key：`sa:audit:{login_id}:{毫秒时间戳}:{id}`，按保留时长过期。查询通过
+/// `scan_keys` 遍历，适合中小规模的审计数据；数据量大时请实现写入数据库的 [`AuditLogger`]。
+///
+/// One key per record, `sa:audit:{login_id}:{millis}:{id}`, expiring after the
+/// retention period. Queries walk the keys with `scan_keys`, which suits
+/// modest volumes; implement an [`AuditLogger`] on a database for large ones.
+#[derive(Clone)]
+pub struct StorageAuditLogger {
+    storage: Arc<dyn SaStorage>,
+    retention: i64,
+}
+
+impl StorageAuditLogger {
+    pub fn new(storage: Arc<dyn SaStorage>) -> Self {
+        Self { storage, retention: DEFAULT_AUDIT_RETENTION }
+    }
+
+    /// 保留时长（秒），默认 90 天，<= 0 表示永久保留 | Retention in seconds, 90 days by default; `<= 0` keeps records forever
+    pub fn retention(mut self, seconds: i64) -> Self {
+        self.retention = seconds;
+        self
+    }
+
+    /// 账号部分编码后拼入 key，避免 `:` 与 `*` 影响前缀匹配
+    fn account_prefix(login_id: &str) -> String {
+        format!("{}{}:", AUDIT_KEY_PREFIX, urlencoding::encode(login_id))
+    }
+
+    async fn scan(&self, key_prefix: &str) -> SaTokenResult<Vec<AuditRecord>> {
+        let pattern = format!("{}*", key_prefix);
+        let mut seen = std::collections::HashSet::new();
+        let mut records = Vec::new();
+        let mut cursor = 0;
+        loop {
+            let page = self.storage.scan_keys(&pattern, cursor).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+            let keys: Vec<&str> = page.keys.iter()
+                .filter(|key| seen.insert(key.to_string()))
+                .map(String::as_str)
+                .collect();
+            let values = self.storage.mget(&keys).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+            records.extend(values.into_iter()
+                .flatten()
+                .filter_map(|value| serde_json::from_str::<AuditRecord>(&value).ok()));
+            if page.is_last() {
+                break;
+            }
+            cursor = page.cursor;
+        }
+        Ok(records)
+    }
+}
+
+#[async_trait]
+impl AuditLogger for StorageAuditLogger {
+    async fn record(&self, record: AuditRecord) -> SaTokenResult<()> {
+        let key = format!(
+            "{}{:013}:{}",
+            Self::account_prefix(&record.login_id),
+            record.timestamp.timestamp_millis(),
+            record.id,
+        );
+        let ttl = (self.retention > 0).then(|| Duration::from_secs(self.retention as u64));
+        self.storage.set(&key, &serde_json::to_string(&record)?, ttl).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+
+    async fn find_by_login_id(&self, login_id: &str, page: usize, page_size: usize) -> SaTokenResult<AuditPage> {
+        let records = self.scan(&Self::account_prefix(login_id)).await?;
+        Ok(AuditPage::paginate(records, page, page_size))
+    }
+
+    async fn find_by_time_range(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        page: usize,
+        page_size: usize,
+    ) -> SaTokenResult<AuditPage> {
+        let records = self.scan(AUDIT_KEY_PREFIX).await?
+            .into_iter()
+            .filter(|record| record.timestamp >= from && record.timestamp < to)
+            .collect();
+        Ok(AuditPage::paginate(records, page, page_size))
+    }
+}
+
+/// 把安全相关事件写入审计日志的监听器 | Listener writing security events into the audit log
+///
+/// `SaTokenConfigBuilder::audit_logger` 会自动注册 | Registered by `SaTokenConfigBuilder::audit_logger`
+pub struct AuditListener {
+    logger: Arc<dyn AuditLogger>,
+}
+
+impl AuditListener {
+    pub fn new(logger: Arc<dyn AuditLogger>) -> Self {
+        Self { logger }
+    }
+}
+
+#[async_trait]
+impl SaTokenListener for AuditListener {
+    async fn on_event(&self, event: &SaTokenEvent) {
+        // 远程事件已由产生它的实例记录 | Remote events are audited by the instance that raised them
+        if !is_audited(&event.event_type) || event.is_remote() {
+            return;
+        }
+        if let Err(e) = self.logger.record(AuditRecord::from_event(event)).await {
+            tracing::warn!("Sa-Token: 写入审计日志失败: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_storage_memory::MemoryStorage;
+    use crate::context::SaTokenContext;
+    use crate::{SaTokenConfig, SaTokenManager};
+
+    #[tokio::test]
+    async fn test_login_and_logout_are_audited_with_client() {
+        let storage = Arc::new(MemoryStorage::new());
+        let logger = Arc::new(StorageAuditLogger::new(storage.clone()));
+        let manager = SaTokenManager::new(storage, SaTokenConfig::default())
+            .with_audit_logger(logger.clone());
+
+        let mut ctx = SaTokenContext::new();
+        ctx.capture_client(Some("10.0.0.7"), Some("curl/8.0"));
+        let token = SaTokenContext::scope(ctx, manager.login("10001")).await.unwrap();
+        manager.logout(&token).await.unwrap();
+        manager.login("10002").await.unwrap();
+
+        let page = logger.find_by_login_id("10001", 1, 10).await.unwrap();
+        assert_eq!(page.total, 2);
+        assert_eq!(page.records[0].event_type, SaTokenEventType::Logout);
+        let login = &page.records[1];
+        assert_eq!(login.event_type, SaTokenEventType::Login);
+        assert_eq!((login.ip.as_deref(), login.user_agent.as_deref()), (Some("10.0.0.7"), Some("curl/8.0")));
+
+        let now = crate::time::now();
+        let all = logger.find_by_time_range(now - chrono::Duration::minutes(1), now + chrono::Duration::minutes(1), 1, 2).await.unwrap();
+        assert_eq!((all.total, all.records.len()), (3, 2));
+        let empty = logger.find_by_time_range(now + chrono::Duration::minutes(1), now + chrono::Duration::minutes(2), 1, 10).await.unwrap();
+        assert_eq!(empty.total, 0);
+    }
+
+    #[tokio::test]
+    async fn test_remote_events_are_not_audited_twice() {
+        let storage = Arc::new(MemoryStorage::new());
+        let logger = Arc::new(StorageAuditLogger::new(storage.clone()));
+        let manager = SaTokenManager::new(storage, SaTokenConfig::default())
+            .with_audit_logger(logger.clone());
+
+        let mut remote = SaTokenEvent::kick_out("10001", "t-remote");
+        remote.origin = Some("node-b".to_string());
+        let json = serde_json::to_string(&remote).unwrap();
+        let remote: SaTokenEvent = serde_json::from_str(&json).unwrap();
+        assert!(remote.is_remote());
+        manager.event_bus().publish(remote).await;
+        manager.event_bus().publish(SaTokenEvent::kick_out("10001", "t-local")).await;
+
+        let page = logger.find_by_login_id("10001", 1, 10).await.unwrap();
+        assert_eq!(page.total, 1);
+        assert!(!serde_json::to_string(&SaTokenEvent::logout("10001", "t")).unwrap().contains("origin"));
+    }
+}
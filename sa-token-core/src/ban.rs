@@ -0,0 +1,129 @@
+// Author: 金书记
+//
+//! 账号封禁 | Account Disabling
+//!
+//! 封禁记录保存在存储中并带有过期时间，到期自动解封。封禁 `login` 服务（即 `disable`）
+//! 时，账号无法登录，已签发的 token 立即失效，并发布 `Banned` 事件；其他服务
+//! （如 `comment`）只记录封禁状态，由业务自行调用 `check_disable_by_service` 校验。
+//!
+//! Disable records live in storage with a TTL and expire on their own.
+//! Disabling the `login` service (`disable`) blocks login, invalidates issued
+//! tokens and publishes a `Banned` event. Other services such as `comment`
+//! only record the state; business code checks them with
+//! `check_disable_by_service`.
+//!
+//! ```rust,ignore
+//! // 封禁一天 | Disable for a day
+//! StpUtil::disable("user_123", 86400).await?;
+//! // 禁言一小时，不影响登录 | Mute for an hour, login unaffected
+//! StpUtil::disable_by_service("user_123", "comment", 3600).await?;
+//!
+//! StpUtil::check_disable_by_service("user_123", "comment").await?;
+//! StpUtil::untie_disable("user_123").await?; // 发布 Unbanned 事件
+//! ```
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 登录服务，封禁该服务即封禁账号 | The login service; disabling it bans the account
+pub const DEFAULT_DISABLE_SERVICE: &str = "login";
+
+/// 永久封禁 | Permanent disable
+pub const NEVER_UNTIE: i64 = -1;
+
+/// 封禁记录的存储 key | Storage key of a disable record
+pub(crate) fn disable_key(service: &str, login_id: &str) -> String {
+    format!("sa:disable:{}:{}", service, login_id)
+}
+
+/// 封禁记录 | Disable record
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DisableInfo {
+    pub login_id: String,
+
+    /// 被封禁的服务 | Disabled service
+    pub service: String,
+
+    /// 封禁时间 | Disabled at
+    pub disabled_at: DateTime<Utc>,
+
+    /// 解封时间，永久封禁为 None | Untie time, `None` when permanent
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl DisableInfo {
+    /// 剩余封禁时间（秒），永久封禁返回 `NEVER_UNTIE` | Seconds left, `NEVER_UNTIE` when permanent
+    pub fn remaining_seconds(&self) -> i64 {
+        match self.until {
+            Some(until) => (until - crate::time::now()).num_seconds().max(0),
+            None => NEVER_UNTIE,
+        }
+    }
+
+    /// 是否仍在封禁期内 | Whether the record is still in effect
+    pub fn is_active(&self) -> bool {
+        self.is_active_at(crate::time::now())
+    }
+
+    /// 在给定时刻是否仍在封禁期内 | Whether the record is in effect at `now`
+    pub fn is_active_at(&self, now: DateTime<Utc>) -> bool {
+        self.until.is_none_or(|until| until > now)
+    }
+
+    /// 解封时间的描述，用于错误信息 | Untie time as shown in error messages
+    pub(crate) fn until_display(&self) -> String {
+        match self.until {
+            Some(until) => until.to_rfc3339(),
+            None => "forever".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use sa_token_storage_memory::MemoryStorage;
+    use crate::{SaTokenConfig, SaTokenError, SaTokenManager, TokenStatus};
+    use super::*;
+
+    fn new_manager() -> SaTokenManager {
+        SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default())
+    }
+
+    #[tokio::test]
+    async fn test_disable_blocks_login_and_invalidates_tokens() {
+        let manager = new_manager();
+        let token = manager.login("user_1").await.unwrap();
+
+        manager.disable("user_1", 3600).await.unwrap();
+        assert!(manager.is_disabled("user_1").await);
+        assert!(!manager.is_valid(&token).await);
+        assert_eq!(manager.token_status(&token, 60).await.unwrap().status, TokenStatus::Banned);
+        assert!(matches!(manager.login("user_1").await, Err(SaTokenError::AccountBanned(_))));
+
+        let remaining = manager.until_disabled_time("user_1").await.unwrap().unwrap();
+        assert!(remaining > 3500 && remaining <= 3600);
+
+        manager.untie_disable("user_1").await.unwrap();
+        assert!(!manager.is_disabled("user_1").await);
+        assert!(manager.until_disabled_time("user_1").await.unwrap().is_none());
+        assert!(manager.login("user_1").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_disable_by_service_keeps_login() {
+        let manager = new_manager();
+        let token = manager.login("user_2").await.unwrap();
+
+        manager.disable_by_service("user_2", "comment", NEVER_UNTIE).await.unwrap();
+        assert!(manager.is_valid(&token).await);
+        assert!(!manager.is_disabled("user_2").await);
+        assert!(manager.is_disabled_by_service("user_2", "comment").await);
+        assert!(matches!(
+            manager.check_disable_by_service("user_2", "comment").await,
+            Err(SaTokenError::AccountBanned(until)) if until == "forever"
+        ));
+        assert_eq!(manager.until_disabled_time_by_service("user_2", "comment").await.unwrap(), Some(NEVER_UNTIE));
+        assert!(manager.disable("user_2", 0).await.is_err());
+    }
+}
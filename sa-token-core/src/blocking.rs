@@ -0,0 +1,188 @@
+// Author: 金书记
+//
+//! 同步接口 | Blocking facade
+//!
+//! CLI 工具、构建脚本和同步测试只需要签发/校验 token，不想为此把整个程序改成异步。
+//! [`BlockingFacade`] 自带一个单线程 tokio 运行时，把 `SaTokenManager` 的常用方法
+//! 包装成同步调用。
+//!
+//! CLI tools, build scripts and synchronous tests that only mint or validate
+//! tokens should not have to be structured around async. [`BlockingFacade`]
+//! owns a single-threaded tokio runtime and exposes the common
+//! `SaTokenManager` operations as plain blocking calls.
+//!
+//! 不要在异步上下文（如 `#[tokio::main]` 内）中调用，tokio 不允许在运行时内部再阻塞等待。
+//! Do not call it from async code (e.g. inside `#[tokio::main]`); tokio
+//! refuses to block inside a runtime.
+//!
+//! ```rust,ignore
+//! use sa_token_core::blocking::BlockingFacade;
+//!
+//! let sa = BlockingFacade::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default())?;
+//! let token = sa.login("user_1")?;
+//! assert_eq!(sa.get_login_id(&token)?, "user_1");
+//! ```
+
+use std::future::Future;
+use std::sync::Arc;
+use tokio::runtime::{Builder, Runtime};
+use sa_token_adapter::storage::SaStorage;
+use crate::config::SaTokenConfig;
+use crate::error::{SaTokenError, SaTokenResult};
+use crate::login::LoginOptions;
+use crate::permission::AuthzKind;
+use crate::token::{TokenInfo, TokenValue};
+use crate::SaTokenManager;
+
+/// 同步 Manager 包装 | Blocking wrapper around a `SaTokenManager`
+///
+/// 克隆开销很小，克隆体共享同一个 Manager 与运行时。
+/// Cheap to clone; clones share the manager and the runtime.
+#[derive(Clone)]
+pub struct BlockingFacade {
+    manager: SaTokenManager,
+    runtime: Arc<Runtime>,
+}
+
+impl BlockingFacade {
+    /// 以存储和配置创建 | Create from storage and config
+    pub fn new(storage: Arc<dyn SaStorage>, config: SaTokenConfig) -> SaTokenResult<Self> {
+        Self::from_manager(SaTokenManager::new(storage, config))
+    }
+
+    /// 包装已有的 Manager | Wrap an existing manager
+    pub fn from_manager(manager: SaTokenManager) -> SaTokenResult<Self> {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| SaTokenError::InternalError(format!("Failed to start blocking runtime: {}", e)))?;
+        Ok(Self {
+            manager,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// 底层 Manager | The underlying manager
+    pub fn manager(&self) -> &SaTokenManager {
+        &self.manager
+    }
+
+    /// 在内部运行时上执行任意异步操作 | Run any async operation on the internal runtime
+    ///
+    /// 用于本类未包装的方法，如 `sa.block_on(sa.manager().get_session("user_1"))`。
+    /// For methods not wrapped here, e.g. `sa.block_on(sa.manager().get_session("user_1"))`.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// 登录 | Log in
+    pub fn login(&self, login_id: impl Into<String>) -> SaTokenResult<TokenValue> {
+        self.block_on(self.manager.login(login_id))
+    }
+
+    /// 带选项登录 | Log in with options
+    pub fn login_with(&self, login_id: impl Into<String>, options: LoginOptions) -> SaTokenResult<TokenValue> {
+        self.block_on(self.manager.login_with(login_id, options))
+    }
+
+    /// 注销 token | Log a token out
+    pub fn logout(&self, token: &TokenValue) -> SaTokenResult<()> {
+        self.block_on(self.manager.logout(token))
+    }
+
+    /// 注销该用户的全部 token | Log out every token of a user
+    pub fn logout_by_login_id(&self, login_id: &str) -> SaTokenResult<()> {
+        self.block_on(self.manager.logout_by_login_id(login_id))
+    }
+
+    /// 踢人下线 | Kick a user out
+    pub fn kick_out(&self, login_id: &str) -> SaTokenResult<()> {
+        self.block_on(self.manager.kick_out(login_id))
+    }
+
+    /// token 是否有效 | Whether the token is valid
+    pub fn is_valid(&self, token: &TokenValue) -> bool {
+        self.block_on(self.manager.is_valid(token))
+    }
+
+    /// 获取 token 信息 | Get token info
+    pub fn get_token_info(&self, token: &TokenValue) -> SaTokenResult<TokenInfo> {
+        self.block_on(self.manager.get_token_info(token))
+    }
+
+    /// 获取 token 对应的登录 ID | Login ID of a token
+    pub fn get_login_id(&self, token: &TokenValue) -> SaTokenResult<String> {
+        self.get_token_info(token).map(|info| info.login_id)
+    }
+
+    /// 续期 token | Renew a token
+    pub fn renew_timeout(&self, token: &TokenValue, timeout_seconds: i64) -> SaTokenResult<()> {
+        self.block_on(self.manager.renew_timeout(token, timeout_seconds))
+    }
+
+    /// 设置用户权限 | Set a user's permissions
+    pub fn set_permissions(&self, login_id: &str, permissions: Vec<String>) -> SaTokenResult<()> {
+        self.manager.validate_authz_names(AuthzKind::Permission, &permissions)?;
+        self.block_on(async {
+            self.manager.user_permissions.write().await.insert(login_id.to_string(), permissions);
+            self.manager.notify_authz_changed(login_id).await;
+        });
+        Ok(())
+    }
+
+    /// 设置用户角色 | Set a user's roles
+    pub fn set_roles(&self, login_id: &str, roles: Vec<String>) -> SaTokenResult<()> {
+        self.manager.validate_authz_names(AuthzKind::Role, &roles)?;
+        self.block_on(async {
+            self.manager.user_roles.write().await.insert(login_id.to_string(), roles);
+            self.manager.notify_authz_changed(login_id).await;
+        });
+        Ok(())
+    }
+
+    /// 是否拥有权限 | Whether the user has a permission
+    pub fn has_permission(&self, login_id: &str, permission: &str) -> bool {
+        self.block_on(self.manager.has_permission(login_id, permission))
+    }
+
+    /// 是否拥有角色 | Whether the user has a role
+    pub fn has_role(&self, login_id: &str, role: &str) -> bool {
+        self.block_on(self.manager.has_role(login_id, role))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_storage_memory::MemoryStorage;
+
+    fn facade() -> BlockingFacade {
+        BlockingFacade::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default()).unwrap()
+    }
+
+    #[test]
+    fn test_login_validate_logout() {
+        let sa = facade();
+        let token = sa.login("user_1").unwrap();
+        assert!(sa.is_valid(&token));
+        assert_eq!(sa.get_login_id(&token).unwrap(), "user_1");
+
+        sa.logout(&token).unwrap();
+        assert!(!sa.is_valid(&token));
+        assert!(sa.get_login_id(&token).is_err());
+    }
+
+    #[test]
+    fn test_permissions_and_block_on() {
+        let sa = facade();
+        sa.set_permissions("user_1", vec!["user:*".to_string()]).unwrap();
+        sa.set_roles("user_1", vec!["admin".to_string()]).unwrap();
+        assert!(sa.has_permission("user_1", "user:read"));
+        assert!(sa.has_role("user_1", "admin"));
+        assert!(!sa.has_role("user_2", "admin"));
+
+        let token = sa.clone().login_with("user_1", LoginOptions::new().device("cli")).unwrap();
+        let info = sa.block_on(sa.manager().get_token_info(&token)).unwrap();
+        assert_eq!(info.device.as_deref(), Some("cli"));
+    }
+}
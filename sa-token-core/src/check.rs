@@ -0,0 +1,264 @@
+// Author: 金书记
+//
+//! 路由访问检查 | Route access checks
+//!
+//! 各框架插件的登录、权限、角色中间件都基于 [`SaCheck`]：插件只负责提取 token、
+//! 写入上下文和渲染响应，判定逻辑与拒绝时的状态码、响应体都在这里，保证各插件行为一致。
+//! 拒绝响应经由管理器上的 [`ErrorRenderer`](crate::render::ErrorRenderer) 生成，可自定义。
+//!
+//! Every plugin's login, permission and role middleware is built on
+//! [`SaCheck`]. Plugins only extract the token, set up the context and render
+//! the response; the decision, the status code and the body of a rejection
+//! live here, so all plugins behave the same. Rejections go through the
+//! manager's [`ErrorRenderer`](crate::render::ErrorRenderer) and can be customized.
+//!
+//! ```rust,ignore
+//! // axum
+//! Router::new().route("/admin", get(admin)).layer(SaCheckLayer::new(SaCheck::roles_or(["admin", "ops"])));
+//!
+//! // poem
+//! Route::new().at("/orders", get(orders).with(SaCheckMiddleware::new(state, SaCheck::permissions_and(["order:read", "order:export"]))));
+//! ```
+
+use serde_json::{json, Value};
+use crate::context::SaTokenContext;
+use crate::error::{messages, SaTokenError, SaTokenResult};
+use crate::permission::AuthzKind;
+use crate::StpUtil;
+
+/// 多个权限/角色的组合方式 | How several permissions or roles combine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckMode {
+    /// 全部满足 | All of them
+    And,
+    /// 满足任一 | Any of them
+    Or,
+}
+
+/// 一条路由的访问要求 | Access requirement of a route
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SaCheck {
+    /// 可选登录：有合法 token 时写入上下文，否则照常放行 | Optional login: a valid token fills the context, requests pass either way
+    Optional,
+    /// 必须登录 | Login required
+    Login,
+    /// 必须拥有权限 | Permissions required
+    Permissions(Vec<String>, CheckMode),
+    /// 必须拥有角色 | Roles required
+    Roles(Vec<String>, CheckMode),
+}
+
+impl SaCheck {
+    /// 可选登录 | Optional login
+    pub fn optional() -> Self {
+        Self::Optional
+    }
+
+    /// 必须登录 | Login required
+    pub fn login() -> Self {
+        Self::Login
+    }
+
+    /// 必须拥有指定权限 | A single permission required
+    pub fn permission(permission: impl Into<String>) -> Self {
+        Self::Permissions(vec![permission.into()], CheckMode::And)
+    }
+
+    /// 必须拥有全部权限 | All permissions required
+    pub fn permissions_and<I, S>(permissions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Permissions(permissions.into_iter().map(Into::into).collect(), CheckMode::And)
+    }
+
+    /// 拥有任一权限即可 | Any of the permissions
+    pub fn permissions_or<I, S>(permissions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Permissions(permissions.into_iter().map(Into::into).collect(), CheckMode::Or)
+    }
+
+    /// 必须拥有指定角色 | A single role required
+    pub fn role(role: impl Into<String>) -> Self {
+        Self::Roles(vec![role.into()], CheckMode::And)
+    }
+
+    /// 必须拥有全部角色 | All roles required
+    pub fn roles_and<I, S>(roles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Roles(roles.into_iter().map(Into::into).collect(), CheckMode::And)
+    }
+
+    /// 拥有任一角色即可 | Any of the roles
+    pub fn roles_or<I, S>(roles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Roles(roles.into_iter().map(Into::into).collect(), CheckMode::Or)
+    }
+
+    /// 决策追踪中的阶段名，如 `check_permissions_or(a, b)` | Stage name for the decision trace
+    pub fn stage(&self) -> String {
+        let (kind, items, mode) = match self {
+            Self::Optional => return "check_optional".to_string(),
+            Self::Login => return "check_login".to_string(),
+            Self::Permissions(items, mode) => ("permission", items, mode),
+            Self::Roles(items, mode) => ("role", items, mode),
+        };
+        match (items.len(), mode) {
+            (1, _) => format!("check_{}({})", kind, items[0]),
+            (_, CheckMode::And) => format!("check_{}s_and({})", kind, items.join(", ")),
+            (_, CheckMode::Or) => format!("check_{}s_or({})", kind, items.join(", ")),
+        }
+    }
+
+    /// 按当前登录 ID 检查 | Check against the current login ID
+    ///
+    /// 未登录时返回上下文中记录的 token 校验失败原因（被踢、被顶替、IP 不一致、受限 token 等），
+    /// 没有记录时返回 `NotLogin`；缺少权限/角色时返回 `PermissionsDenied` / `RolesDenied`，
+    /// AND 模式列出缺少的项，OR 模式列出全部候选项。
+    /// Without a login ID, returns the token validation error recorded in the
+    /// context (kicked out, replaced, IP mismatch, restricted token, ...) or
+    /// `NotLogin`. Returns `PermissionsDenied` / `RolesDenied` listing the
+    /// missing items (AND) or all candidates (OR).
+    pub async fn check(&self, login_id: Option<&str>) -> SaTokenResult<()> {
+        let login_id = match (self, login_id) {
+            (Self::Optional, _) => return Ok(()),
+            (_, None) => return Err(Self::not_logged_in()),
+            (Self::Login, Some(_)) => return Ok(()),
+            (_, Some(login_id)) => login_id,
+        };
+
+        match self {
+            Self::Permissions(items, mode) => {
+                let items: Vec<&str> = items.iter().map(String::as_str).collect();
+                match mode {
+                    CheckMode::And => StpUtil::check_permissions_and(login_id, &items).await,
+                    CheckMode::Or if StpUtil::has_permissions_or(login_id, &items).await => Ok(()),
+                    CheckMode::Or => {
+                        StpUtil::record_denial(AuthzKind::Permission, &items.join(" | "));
+                        Err(SaTokenError::PermissionsDenied(to_owned(&items)))
+                    }
+                }
+            }
+            Self::Roles(items, mode) => {
+                let items: Vec<&str> = items.iter().map(String::as_str).collect();
+                match mode {
+                    CheckMode::And => StpUtil::check_roles_and(login_id, &items).await,
+                    CheckMode::Or if StpUtil::has_roles_or(login_id, &items).await => Ok(()),
+                    CheckMode::Or => {
+                        StpUtil::record_denial(AuthzKind::Role, &items.join(" | "));
+                        Err(SaTokenError::RolesDenied(to_owned(&items)))
+                    }
+                }
+            }
+            Self::Optional | Self::Login => Ok(()),
+        }
+    }
+
+    /// 没有登录 ID 时的拒绝原因 | Why a request without a login ID is rejected
+    fn not_logged_in() -> SaTokenError {
+        SaTokenContext::get_current()
+            .map(|ctx| ctx.login_error())
+            .unwrap_or(SaTokenError::NotLogin)
+    }
+
+    /// 拒绝时的状态码与 JSON 响应体 | Status code and JSON body of a rejection
+    ///
+    /// 未登录为 401，权限/角色不足为 403，响应体带 `missing` 字段；请求体守卫的
+    /// 请求体过大为 413、无法解析为 400；被踢、被顶替、IP 不一致、密码过期的受限 token、参数签名、Same-Token、HTTP Basic 与 API Key 校验失败为 401，响应体带具体原因。
+    /// 401 when not logged in, 403 for missing permissions or roles, with a
+    /// `missing` field in the body; 413 and 400 for payloads too large or
+    /// malformed for a payload guard. Kicked-out, replaced, IP-mismatched or password-expired tokens, failed
+    /// parameter signatures, Same-Token, HTTP Basic and API key checks are 401
+    /// with the specific reason as message.
+    pub fn rejection(err: &SaTokenError) -> (u16, Value) {
+        if let SaTokenError::KickedOut(_)
+            | SaTokenError::Replaced
+            | SaTokenError::IpMismatch
+            | SaTokenError::PasswordExpired
+            | SaTokenError::InvalidSign(_)
+            | SaTokenError::InvalidSameToken
+            | SaTokenError::HttpBasicAuthFailed
+            | SaTokenError::InvalidApiKey
+            | SaTokenError::InvalidNonceTimestamp
+            | SaTokenError::NonceAlreadyUsed = err
+        {
+            return (401, json!({ "code": 401, "message": err.to_string() }));
+        }
+        if err.is_auth_error() {
+            return (401, json!({ "code": 401, "message": messages::AUTH_ERROR }));
+        }
+        match err {
+            SaTokenError::PayloadTooLarge(_) => return (413, json!({ "code": 413, "message": err.to_string() })),
+            SaTokenError::InvalidPayload(_) => return (400, json!({ "code": 400, "message": err.to_string() })),
+            _ => {}
+        }
+        let message = match err {
+            SaTokenError::RoleDenied(_) | SaTokenError::RolesDenied(_) => messages::ROLE_REQUIRED,
+            _ => messages::PERMISSION_REQUIRED,
+        };
+        (403, json!({ "code": 403, "message": message, "missing": err.missing() }))
+    }
+}
+
+fn to_owned(items: &[&str]) -> Vec<String> {
+    items.iter().map(|item| item.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constructors_and_stage_names() {
+        assert_eq!(SaCheck::permission("user:read").stage(), "check_permission(user:read)");
+        assert_eq!(SaCheck::roles_or(["admin", "ops"]).stage(), "check_roles_or(admin, ops)");
+        assert_eq!(
+            SaCheck::permissions_and(vec!["a".to_string(), "b".to_string()]),
+            SaCheck::Permissions(vec!["a".to_string(), "b".to_string()], CheckMode::And)
+        );
+        assert_eq!(SaCheck::login().stage(), "check_login");
+    }
+
+    #[tokio::test]
+    async fn test_login_gate_and_rejection_body() {
+        assert!(SaCheck::optional().check(None).await.is_ok());
+        assert!(SaCheck::login().check(Some("user_1")).await.is_ok());
+
+        let err = SaCheck::roles_and(["admin"]).check(None).await.unwrap_err();
+        assert!(matches!(err, SaTokenError::NotLogin));
+        assert_eq!(SaCheck::rejection(&err).0, 401);
+
+        let (status, body) = SaCheck::rejection(&SaTokenError::RolesDenied(vec!["admin".to_string()]));
+        assert_eq!(status, 403);
+        assert_eq!(body["message"], messages::ROLE_REQUIRED);
+        assert_eq!(body["missing"][0], "admin");
+    }
+
+    #[tokio::test]
+    async fn test_missing_login_reports_context_auth_error() {
+        let mut ctx = SaTokenContext::new();
+        ctx.auth_error = Some(std::sync::Arc::new(SaTokenError::KickedOut("Account kicked out".to_string())));
+        let err = SaTokenContext::scope(ctx, SaCheck::permission("user:read").check(None)).await.unwrap_err();
+        assert!(matches!(&err, SaTokenError::KickedOut(reason) if reason == "Account kicked out"));
+
+        let (status, body) = SaCheck::rejection(&err);
+        assert_eq!(status, 401);
+        assert_eq!(body["message"], err.to_string());
+
+        // 非认证错误不会泄露给客户端 | Non-auth errors are not passed on to the client
+        let mut ctx = SaTokenContext::new();
+        ctx.auth_error = Some(std::sync::Arc::new(SaTokenError::StorageError("connection refused".to_string())));
+        let err = SaTokenContext::scope(ctx, SaCheck::login().check(None)).await.unwrap_err();
+        assert!(matches!(err, SaTokenError::NotLogin));
+    }
+}
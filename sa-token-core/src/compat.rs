@@ -0,0 +1,12 @@
+// Author: 金书记
+//
+//! 兼容层 | Compatibility Layer
+//!
+//! 按版本划分的废弃接口与迁移说明。废弃的接口仍然可用，只会产生 `deprecated` 警告，
+//! 方便逐步升级；它们会在下一个不兼容版本中移除。
+//!
+//! Deprecated APIs grouped by the release that introduced them. They keep
+//! working and only emit `deprecated` warnings so upgrades can be done
+//! gradually; they are removed in the next breaking release.
+
+pub mod v01;
@@ -0,0 +1,50 @@
+// Author: 金书记
+//
+//! 0.1 插件接口迁移指南 | Migration Guide for 0.1 Plugin APIs
+//!
+//! 0.1 版本各框架插件的 `SaTokenState` 构造方式并不统一。现在所有插件都收敛到两种方式：
+//!
+//! The `SaTokenState` constructors of the 0.1 plugins diverged per framework.
+//! Every plugin now converges on two entry points:
+//!
+//! - `SaTokenState::builder()...build()`
+//! - `SaTokenState::from_manager(manager)`
+//!
+//! 二者都会初始化全局 `StpUtil`，旧构造函数不会。
+//! Both initialize the global `StpUtil`; the old constructors did not.
+//!
+//! 所有插件现在重新导出同一个 [`SaTokenState`](crate::state::SaTokenState)。poem / rocket 的
+//! `SaTokenState::new(Arc<SaTokenManager>)` 因此已移除，poem 的构建器也不再默认使用内存存储，
+//! 需显式调用 `.storage(...)`。
+//! All plugins now re-export the same [`SaTokenState`](crate::state::SaTokenState).
+//! The poem / rocket `SaTokenState::new(Arc<SaTokenManager>)` is therefore
+//! gone, and the poem builder no longer falls back to memory storage; call
+//! `.storage(...)` explicitly.
+//!
+//! ## 对照表 | Mapping
+//!
+//! | 插件 Plugin | 0.1 接口 Old API | 替代接口 Replacement |
+//! |------|------|------|
+//! | axum / warp / salvo / ntex / tide / gotham | `SaTokenState::new(storage, config)` | `SaTokenState::from_manager(SaTokenManager::new(storage, config))` |
+//! | poem / rocket | `SaTokenState::new(Arc<SaTokenManager>)`（已移除 removed） | `SaTokenState::from_manager(manager)` |
+//! | rocket | `SaTokenState::from_storage_and_config(storage, config)` | `SaTokenState::from_manager(SaTokenManager::new(storage, config))` |
+//!
+//! ## 示例 | Example
+//!
+//! ```rust,ignore
+//! // 0.1
+//! let state = SaTokenState::new(Arc::new(MemoryStorage::new()), config);
+//!
+//! // 现在 | Now
+//! let state = SaTokenState::builder()
+//!     .storage(Arc::new(MemoryStorage::new()))
+//!     .timeout(7200)
+//!     .build();
+//!
+//! // 或者复用已有配置 | Or reuse an existing config
+//! let state = SaTokenState::from_manager(SaTokenManager::new(Arc::new(MemoryStorage::new()), config));
+//! ```
+//!
+//! 逐步升级时可以在模块上临时加 `#[allow(deprecated)]`，按警告逐个替换。
+//! During a gradual upgrade, `#[allow(deprecated)]` on a module silences the
+//! warnings until each call site is migrated.
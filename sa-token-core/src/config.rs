@@ -6,10 +6,27 @@ use std::time::Duration;
 use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 use sa_token_adapter::storage::SaStorage;
-use crate::event::SaTokenListener;
+use sa_token_adapter::clock::Clock;
+use sa_token_adapter::context::{CookieOptions, SameSite};
+use sa_token_adapter::utils::strip_token_scheme;
+use crate::event::{EventDispatchMode, SaTokenListener};
+use crate::policy::{LoginPolicy, SessionValidator, TtlPolicy};
+use crate::extractor::{default_token_sources, TokenExtractor, TokenExtractorSource, TokenSource};
+use crate::error::{SaTokenError, SaTokenResult};
+use crate::migration::LegacySessionImporter;
+use crate::online::OnlineManager;
+use crate::cookie::{validate_cookie_domain, CookieDomainCheck, PublicSuffixList};
+use crate::permission::{AuthzNamingRules, PermissionChecker, RoleChecker};
+use crate::render::ErrorRenderer;
+use crate::audit::AuditLogger;
+use crate::ip::IpCidr;
+use crate::token::TokenGenerator;
 
 /// sa-token 配置
+/// 
+/// 可由 `from_file` / `from_env` 加载（见 `config_loader` 模块），缺省字段取默认值。
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SaTokenConfig {
     /// Token 名称（例如在 header 或 cookie 中的键名）
     pub token_name: String,
@@ -17,8 +34,21 @@ pub struct SaTokenConfig {
     /// Token 有效期（秒），-1 表示永久有效
     pub timeout: i64,
     
+    /// "记住我"登录的 token 有效期（秒），-1 表示永久有效，默认 30 天
+    /// 
+    /// 以 `LoginOptions::remember(true)` / `StpUtil::login_with_remember` 登录时代替
+    /// `timeout`，写回的 Cookie `Max-Age` 与之一致；`remember(false)` 的登录写回会话 Cookie。
+    /// 
+    /// Replaces `timeout` for logins with `remember(true)`, and the written
+    /// cookie's `Max-Age` follows it. Logins with `remember(false)` get a
+    /// session cookie.
+    #[serde(default = "default_remember_timeout")]
+    pub remember_timeout: i64,
+    
     /// Token 最低活跃频率（秒），-1 表示不限制
     /// 
+    /// 大于 0 时，超过该时长没有请求的 token 会被冻结，校验时返回 `TokenFreeze`；
+    /// 每次成功读取 token 信息（中间件校验、`get_token_info` 等）都会刷新活跃时间。
     /// 配合 auto_renew 使用时，表示自动续签的时长
     pub active_timeout: i64,
     
@@ -40,12 +70,43 @@ pub struct SaTokenConfig {
     /// 在多人登录同一账号时，是否共享一个 token
     pub is_share: bool,
     
+    /// 同一账号在同一登录类型下最多同时在线的 token 数，-1 表示不限制（默认）
+    /// 
+    /// 仅在 `is_concurrent = true` 时生效。超出时按 `login_eviction` 顶替多余的旧 token，
+    /// 被顶替的 token 发布 `Replaced` 事件，再访问时返回 `SaTokenError::Replaced`
+    /// （对应 Java 版的 `maxLoginCount`）。
+    /// 
+    /// Only applies with `is_concurrent = true`. Logins beyond the limit replace
+    /// older tokens according to `login_eviction`, like Java sa-token's
+    /// `maxLoginCount`.
+    #[serde(default = "default_unlimited")]
+    pub max_login_count: i64,
+    
+    /// 超出 `max_login_count` 时顶替哪些 token | Which tokens to replace beyond `max_login_count`
+    #[serde(default)]
+    pub login_eviction: LoginEviction,
+    
     /// Token 风格（uuid、simple-uuid、random-32、random-64、random-128）
     pub token_style: TokenStyle,
     
-    /// 是否输出操作日志
+    /// 生成 token 时与已有 token 冲突的最大重试次数，<= 0 表示不检查冲突
+    /// 
+    /// Attempts to generate a token that collides with no existing one; `<= 0`
+    /// skips the check.
+    #[serde(default = "default_max_try_times")]
+    pub max_try_times: i32,
+    
+    /// 是否输出操作日志（开启后自动注册 `LoggingListener`）
     pub is_log: bool,
     
+    /// 每个来源 IP 每个窗口内逐条记录的认证失败次数，超出部分只做汇总（0 表示不限流）
+    #[serde(default = "default_auth_failure_log_limit")]
+    pub auth_failure_log_limit: u32,
+    
+    /// 认证失败日志的汇总窗口（秒）
+    #[serde(default = "default_auth_failure_log_window")]
+    pub auth_failure_log_window: i64,
+    
     /// 是否从 cookie 中读取 token
     pub is_read_cookie: bool,
     
@@ -55,15 +116,47 @@ pub struct SaTokenConfig {
     /// 是否从请求体中读取 token
     pub is_read_body: bool,
     
-    /// token 前缀（例如 "Bearer "）
+    /// token 的查找顺序，默认 Header → Authorization → Cookie → Query
+    #[serde(default = "default_token_sources")]
+    pub token_sources: Vec<TokenSource>,
+    
+    /// token 前缀（例如 "Bearer "），设置后优先于 `token_schemes` 匹配
     pub token_prefix: Option<String>,
     
+    /// 从请求头读取 token 时接受的认证方案（不区分大小写），默认 `["Bearer"]`
+    #[serde(default = "default_token_schemes")]
+    pub token_schemes: Vec<String>,
+    
     /// JWT 密钥（如果使用 JWT）
     pub jwt_secret_key: Option<String>,
     
-    /// JWT 算法（默认 HS256）
+    /// JWT 算法（默认 HS256），RS*/ES*/EdDSA 使用下方的 PEM 密钥
     pub jwt_algorithm: Option<String>,
     
+    /// JWT 私钥（PEM 文本），RS*/ES*/EdDSA 签发时使用
+    #[serde(default)]
+    pub jwt_private_key_pem: Option<String>,
+    
+    /// JWT 公钥（PEM 文本），RS*/ES*/EdDSA 验证时使用，资源服务只需配置公钥
+    #[serde(default)]
+    pub jwt_public_key_pem: Option<String>,
+    
+    /// JWT 私钥文件路径，未设置 `jwt_private_key_pem` 时读取
+    #[serde(default)]
+    pub jwt_private_key_path: Option<String>,
+    
+    /// JWT 公钥文件路径，未设置 `jwt_public_key_pem` 时读取
+    #[serde(default)]
+    pub jwt_public_key_path: Option<String>,
+    
+    /// JWT 密钥 ID（写入 `kid` 头及 JWK）
+    #[serde(default)]
+    pub jwt_key_id: Option<String>,
+    
+    /// 轮换用的 HMAC 密钥，到达生效时间后接替 `jwt_secret_key` 签名，旧密钥仍可验证
+    #[serde(default)]
+    pub jwt_rotation_secrets: Vec<crate::token::jwt::JwtSecret>,
+    
     /// JWT 签发者
     pub jwt_issuer: Option<String>,
     
@@ -79,8 +172,295 @@ pub struct SaTokenConfig {
     /// 是否启用 Refresh Token
     pub enable_refresh_token: bool,
     
-    /// Refresh Token 有效期（秒），默认 7 天
+    /// Refresh Token 有效期（秒），默认 7 天；每次刷新后重新计时（滑动有效期）
     pub refresh_token_timeout: i64,
+    
+    /// Refresh Token 家族的绝对最长寿命（秒），从首次登录签发起计算，刷新不会延长，-1 表示不限制
+    /// 
+    /// Absolute lifetime of a refresh-token family, counted from the first
+    /// issue and never extended by refreshes. -1 means unlimited.
+    #[serde(default = "default_unlimited")]
+    pub refresh_token_max_lifetime: i64,
+    
+    /// 刷新时是否轮换 refresh token，旧 token 再次使用返回 `RefreshTokenRotated`
+    /// 
+    /// Rotate the refresh token on every refresh; reusing the old one yields
+    /// `RefreshTokenRotated`.
+    #[serde(default)]
+    pub refresh_token_rotation: bool,
+    
+    /// 是否开启开发模式免登录（仅 debug 构建或设置了 `SA_TOKEN_DEV_BYPASS` 环境变量时生效）
+    /// 
+    /// Enable dev bypass: all login/permission/role checks pass with a fake login_id.
+    /// Refused in release builds unless `SA_TOKEN_DEV_BYPASS=1` is set.
+    #[serde(default)]
+    pub dev_bypass: bool,
+    
+    /// 开发模式免登录时使用的假 login_id（默认 "dev"）
+    #[serde(default = "default_dev_bypass_login_id")]
+    pub dev_bypass_login_id: String,
+    
+    /// Session 单个 key 的最大字节数（序列化后），-1 表示不限制
+    #[serde(default = "default_unlimited")]
+    pub session_max_value_bytes: i64,
+    
+    /// 整个 Session 的最大字节数（序列化后），-1 表示不限制
+    #[serde(default = "default_unlimited")]
+    pub session_max_bytes: i64,
+    
+    /// 新设备登录时是否通知该账号的其他在线设备（需配置 OnlineManager）
+    #[serde(default)]
+    pub notify_new_sign_in: bool,
+    
+    /// 需要新设备登录通知的登录类型，为空表示所有类型
+    #[serde(default)]
+    pub notify_new_sign_in_login_types: Vec<String>,
+    
+    /// 幂等键结果的保留时间（秒），默认 1 天，-1 表示永久保留
+    /// 
+    /// How long login/refresh responses are kept for idempotency-key replays.
+    #[serde(default = "default_idempotency_retention")]
+    pub idempotency_retention: i64,
+    
+    /// 会话复查（SessionValidator）的间隔（秒），默认 5 分钟
+    /// 
+    /// Minimum interval between two `SessionValidator` calls for the same token.
+    #[serde(default = "default_session_validation_interval")]
+    pub session_validation_interval: i64,
+    
+    /// 是否记录每个请求的鉴权决策日志（调试用，勿在生产环境长期开启）
+    /// 
+    /// Record a per-request decision trace, returned in the `X-Sa-Token-Trace` header.
+    #[serde(default)]
+    pub debug_decision_log: bool,
+    
+    /// 响应 JSON 的字段命名风格（AccessToken 等响应结构体），默认 snake_case
+    /// 
+    /// Field naming of JSON responses such as `AccessToken`, e.g. `accessToken` for camelCase.
+    #[serde(default)]
+    pub json_field_case: JsonFieldCase,
+    
+    /// 登录时预取权限与角色写入 TokenInfo，权限变更事件会使其失效
+    /// 
+    /// Prefetch permissions and roles into the TokenInfo at login; a
+    /// `PermissionChanged` event invalidates the snapshot.
+    #[serde(default)]
+    pub prefetch_authz: bool,
+    
+    /// 踢人下线后的宽限期（秒），0 表示关闭，最长 `MAX_KICK_OUT_GRACE_PERIOD`
+    /// 
+    /// 宽限期内被踢 token 拒绝新请求，但踢人之前已开始的请求仍可继续读取 token 信息，
+    /// 避免正在处理的请求因竞态返回 401。
+    /// 
+    /// Grace window after a kick-out: new requests with the token are rejected,
+    /// requests that started before the kick-out still resolve it.
+    #[serde(default)]
+    pub kick_out_grace_period: i64,
+    
+    /// 当前实例所在可用区，设置后新 token 带上 `{zone}~` 前缀，配合 `RoutingStorage` 就近读取
+    /// 
+    /// Zone this instance runs in. New tokens are prefixed with `{zone}~` so
+    /// `RoutingStorage` can pin them to the zone and read them locally.
+    #[serde(default)]
+    pub locality_hint: Option<String>,
+    
+    /// 权限/角色命名规则，设置权限或角色时校验，None 表示不校验
+    /// 
+    /// Naming rules checked when permissions or roles are set; None skips the check.
+    #[serde(default)]
+    pub authz_naming_rules: Option<AuthzNamingRules>,
+    
+    /// 写入 token Cookie 时使用的 `Domain`，None 表示仅当前主机
+    /// 
+    /// `Domain` attribute of the token cookie; None for host-only cookies.
+    #[serde(default)]
+    pub cookie_domain: Option<String>,
+    
+    /// `cookie_domain` 为公共后缀或 IP 时的处理方式，默认警告
+    /// 
+    /// What to do when `cookie_domain` is a public suffix or an IP address;
+    /// warns by default.
+    #[serde(default)]
+    pub cookie_domain_check: CookieDomainCheck,
+    
+    /// 登录后由插件中间件把 token 写入 Cookie（名称为 `token_name`），登出当前 token 时清除，默认关闭
+    /// 
+    /// 适用于非 JWT 的会话式 token，浏览器端无需手动保存 token。
+    /// 
+    /// Plugin middlewares set the token cookie (named `token_name`) after a
+    /// login in the request and clear it when the request's token logs out.
+    /// Off by default; meant for session-style, non-JWT tokens.
+    #[serde(default)]
+    pub is_write_cookie: bool,
+    
+    /// token Cookie 的 `Path`，默认 `/` | `Path` attribute of the token cookie, `/` by default
+    #[serde(default = "default_cookie_path")]
+    pub cookie_path: Option<String>,
+    
+    /// token Cookie 是否带 `HttpOnly`，默认开启 | Whether the token cookie is `HttpOnly`, on by default
+    #[serde(default = "default_cookie_http_only")]
+    pub cookie_http_only: bool,
+    
+    /// token Cookie 是否带 `Secure`（仅 HTTPS）| Whether the token cookie is `Secure` (HTTPS only)
+    #[serde(default)]
+    pub cookie_secure: bool,
+    
+    /// token Cookie 的 `SameSite`，默认 `Lax` | `SameSite` attribute of the token cookie, `Lax` by default
+    #[serde(default = "default_cookie_same_site")]
+    pub cookie_same_site: Option<SameSite>,
+    
+    /// 并发登录去重窗口（秒），0 表示关闭
+    /// 
+    /// 窗口期内同一账号、登录类型、设备的重复登录（如双击登录按钮）返回同一个 token，
+    /// 只签发一次、只触发一次登录事件。仅在当前进程内去重。
+    /// 
+    /// Repeated logins for the same account, login type and device within the
+    /// window (e.g. a double-clicked login button) return the same token, which
+    /// is issued and announced once. Deduplication is per process.
+    #[serde(default)]
+    pub login_dedup_window: i64,
+    
+    /// 存储后端的选择，供从配置文件/环境变量创建存储时使用（`sa_token::storage::from_config`）
+    /// 
+    /// 通过 `builder().storage(...)` 直接传入存储时忽略该字段。
+    /// 
+    /// Which storage backend to create when configuring from a file or the
+    /// environment (`sa_token::storage::from_config`). Ignored when a storage
+    /// is passed to `builder().storage(...)` directly.
+    #[serde(default)]
+    pub storage: StorageConfig,
+
+    /// 事件监听器的分发方式，默认在调用中同步执行
+    /// 
+    /// How event listeners run; synchronously inside the call by default.
+    /// `spawned` and `queue` keep slow listeners off the login path.
+    #[serde(default)]
+    pub event_dispatch: EventDispatchMode,
+
+    /// 权限段分隔符，默认 `:`；如设为 `.` 则 `a.b.*` 匹配 `a.b.c`
+    /// 
+    /// Separator between permission segments, `:` by default; with `.`,
+    /// `a.b.*` matches `a.b.c`.
+    #[serde(default = "default_permission_separator")]
+    pub permission_separator: String,
+
+    /// Same-Token 的轮换周期（秒），默认一天，<= 0 表示不自动轮换
+    /// 
+    /// Rotation period of the Same-Token in seconds, one day by default;
+    /// `<= 0` never rotates automatically.
+    #[serde(default = "default_same_token_timeout")]
+    pub same_token_timeout: i64,
+
+    /// 把 token 绑定到登录时的 IP，请求 IP 不一致时拒绝该 token（`IpMismatch`）
+    /// 
+    /// Binds tokens to the login IP; requests from another IP are rejected
+    /// with `IpMismatch`. Set `trusted_proxies` when running behind a proxy.
+    #[serde(default)]
+    pub token_bind_ip: bool,
+
+    /// 受信任的反向代理网段（CIDR），只有来自这些地址的 `X-Forwarded-For` 才会被采信
+    /// 
+    /// Trusted reverse proxy networks (CIDR). `X-Forwarded-For` is only
+    /// honoured from these peers; when empty every request's headers are used as is.
+    #[serde(default)]
+    pub trusted_proxies: Vec<String>,
+}
+
+/// 存储后端配置 | Storage backend selection
+/// 
+/// ```toml
+/// [storage]
+/// type = "redis"
+/// url = "redis://:password@localhost:6379/0"
+/// key_prefix = "sa-token:"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum StorageConfig {
+    /// 进程内存储（默认）| In-process memory storage (default)
+    #[default]
+    Memory,
+    /// Redis 存储 | Redis storage
+    Redis {
+        /// 连接地址，如 `redis://localhost:6379/0` | Connection URL
+        url: String,
+        /// 键前缀 | Key prefix
+        #[serde(default = "default_storage_key_prefix")]
+        key_prefix: String,
+    },
+    /// 数据库存储 | Database storage
+    Database {
+        /// 连接地址 | Connection URL
+        url: String,
+    },
+}
+
+fn default_storage_key_prefix() -> String {
+    "sa-token:".to_string()
+}
+
+/// 踢人宽限期上限（秒）| Upper bound of `kick_out_grace_period` (seconds)
+pub const MAX_KICK_OUT_GRACE_PERIOD: i64 = 10;
+
+/// 允许在 release 构建中开启 dev_bypass 的环境变量
+/// 
+/// Environment variable that allows `dev_bypass` outside debug builds.
+pub const DEV_BYPASS_ENV: &str = "SA_TOKEN_DEV_BYPASS";
+
+fn default_dev_bypass_login_id() -> String {
+    "dev".to_string()
+}
+
+fn default_unlimited() -> i64 {
+    -1
+}
+
+fn default_same_token_timeout() -> i64 {
+    crate::same_token::DEFAULT_SAME_TOKEN_TIMEOUT
+}
+
+fn default_remember_timeout() -> i64 {
+    2592000
+}
+
+fn default_max_try_times() -> i32 {
+    12
+}
+
+fn default_token_schemes() -> Vec<String> {
+    sa_token_adapter::utils::DEFAULT_TOKEN_SCHEMES.iter().map(|scheme| scheme.to_string()).collect()
+}
+
+fn default_permission_separator() -> String {
+    crate::permission::DEFAULT_PERMISSION_SEPARATOR.to_string()
+}
+
+fn default_auth_failure_log_limit() -> u32 {
+    crate::event::DEFAULT_AUTH_FAILURE_LOG_LIMIT
+}
+
+fn default_auth_failure_log_window() -> i64 {
+    crate::event::DEFAULT_AUTH_FAILURE_LOG_WINDOW
+}
+
+fn default_idempotency_retention() -> i64 {
+    86400
+}
+
+fn default_cookie_path() -> Option<String> {
+    Some("/".to_string())
+}
+
+fn default_cookie_http_only() -> bool {
+    true
+}
+
+fn default_cookie_same_site() -> Option<SameSite> {
+    Some(SameSite::Lax)
+}
+
+fn default_session_validation_interval() -> i64 {
+    300
 }
 
 impl Default for SaTokenConfig {
@@ -88,24 +468,68 @@ impl Default for SaTokenConfig {
         Self {
             token_name: "sa-token".to_string(),
             timeout: 2592000, // 30天
+            remember_timeout: default_remember_timeout(),
             active_timeout: -1,
             auto_renew: false, // 默认不开启自动续签
             is_concurrent: true,
             is_share: true,
+            max_login_count: -1,
+            login_eviction: LoginEviction::default(),
             token_style: TokenStyle::Uuid,
+            max_try_times: default_max_try_times(),
             is_log: false,
+            auth_failure_log_limit: default_auth_failure_log_limit(),
+            auth_failure_log_window: default_auth_failure_log_window(),
             is_read_cookie: true,
             is_read_header: true,
             is_read_body: false,
+            token_sources: default_token_sources(),
             token_prefix: None,
+            token_schemes: default_token_schemes(),
             jwt_secret_key: None,
             jwt_algorithm: Some("HS256".to_string()),
+            jwt_private_key_pem: None,
+            jwt_public_key_pem: None,
+            jwt_private_key_path: None,
+            jwt_public_key_path: None,
+            jwt_key_id: None,
+            jwt_rotation_secrets: Vec::new(),
             jwt_issuer: None,
             jwt_audience: None,
             enable_nonce: false,
             nonce_timeout: -1,
             enable_refresh_token: false,
             refresh_token_timeout: 604800, // 7 天
+            refresh_token_max_lifetime: -1,
+            refresh_token_rotation: false,
+            dev_bypass: false,
+            dev_bypass_login_id: default_dev_bypass_login_id(),
+            session_max_value_bytes: -1,
+            session_max_bytes: -1,
+            notify_new_sign_in: false,
+            notify_new_sign_in_login_types: Vec::new(),
+            idempotency_retention: default_idempotency_retention(),
+            session_validation_interval: default_session_validation_interval(),
+            debug_decision_log: false,
+            json_field_case: JsonFieldCase::default(),
+            prefetch_authz: false,
+            kick_out_grace_period: 0,
+            locality_hint: None,
+            authz_naming_rules: None,
+            cookie_domain: None,
+            cookie_domain_check: CookieDomainCheck::default(),
+            is_write_cookie: false,
+            cookie_path: default_cookie_path(),
+            cookie_http_only: default_cookie_http_only(),
+            cookie_secure: false,
+            cookie_same_site: default_cookie_same_site(),
+            login_dedup_window: 0,
+            storage: StorageConfig::default(),
+            event_dispatch: EventDispatchMode::Sync,
+            permission_separator: default_permission_separator(),
+            same_token_timeout: default_same_token_timeout(),
+            token_bind_ip: false,
+            trusted_proxies: Vec::new(),
         }
     }
 }
@@ -115,6 +539,17 @@ impl SaTokenConfig {
         SaTokenConfigBuilder::default()
     }
     
+    /// 以当前配置为起点创建构建器（如从文件加载后再设置存储、监听器）
+    /// 
+    /// Starts a builder from this config, e.g. after `from_file`, to add the
+    /// storage and listeners.
+    pub fn into_builder(self) -> SaTokenConfigBuilder {
+        SaTokenConfigBuilder {
+            config: self,
+            ..SaTokenConfigBuilder::default()
+        }
+    }
+    
     pub fn timeout_duration(&self) -> Option<Duration> {
         if self.timeout < 0 {
             None
@@ -122,10 +557,159 @@ impl SaTokenConfig {
             Some(Duration::from_secs(self.timeout as u64))
         }
     }
+    
+    /// "记住我"登录的有效期，None 表示永久有效
+    pub fn remember_timeout_duration(&self) -> Option<Duration> {
+        if self.remember_timeout < 0 {
+            None
+        } else {
+            Some(Duration::from_secs(self.remember_timeout as u64))
+        }
+    }
+    
+    /// 幂等键结果的保留时长，None 表示永久保留
+    pub fn idempotency_retention_duration(&self) -> Option<Duration> {
+        if self.idempotency_retention < 0 {
+            None
+        } else {
+            Some(Duration::from_secs(self.idempotency_retention as u64))
+        }
+    }
+    
+    /// 当前环境是否允许开启 dev_bypass
+    /// 
+    /// Whether dev bypass may be enabled: always in debug builds, otherwise only
+    /// when `SA_TOKEN_DEV_BYPASS` is `1` or `true`.
+    pub fn dev_bypass_allowed() -> bool {
+        if cfg!(debug_assertions) {
+            return true;
+        }
+        std::env::var(DEV_BYPASS_ENV)
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false)
+    }
+    
+    /// 指定登录类型是否需要新设备登录通知
+    pub fn should_notify_new_sign_in(&self, login_type: &str) -> bool {
+        self.notify_new_sign_in
+            && (self.notify_new_sign_in_login_types.is_empty()
+                || self.notify_new_sign_in_login_types.iter().any(|t| t == login_type))
+    }
+    
+    /// 开发模式免登录是否实际生效（已开启且被当前环境允许）
+    /// 
+    /// Whether dev bypass is enabled and allowed in the current environment.
+    pub fn is_dev_bypass_active(&self) -> bool {
+        self.dev_bypass && Self::dev_bypass_allowed()
+    }
+    
+    /// JWT 私钥 PEM：优先 `jwt_private_key_pem`，否则读取 `jwt_private_key_path`
+    pub fn jwt_private_key(&self) -> SaTokenResult<Option<String>> {
+        read_pem(&self.jwt_private_key_pem, &self.jwt_private_key_path)
+    }
+    
+    /// JWT 公钥 PEM：优先 `jwt_public_key_pem`，否则读取 `jwt_public_key_path`
+    pub fn jwt_public_key(&self) -> SaTokenResult<Option<String>> {
+        read_pem(&self.jwt_public_key_pem, &self.jwt_public_key_path)
+    }
+    
+    /// 把密钥文件读入 `jwt_*_key_pem`，避免每次签发都读文件
+    /// 
+    /// Loads the key files into the `jwt_*_key_pem` fields once, so signing
+    /// does not hit the filesystem.
+    pub fn load_jwt_key_files(&mut self) -> SaTokenResult<()> {
+        self.jwt_private_key_pem = self.jwt_private_key()?;
+        self.jwt_public_key_pem = self.jwt_public_key()?;
+        Ok(())
+    }
+    
+    /// 按内置公共后缀列表校验 `cookie_domain`，未设置时直接通过
+    /// 
+    /// Validates `cookie_domain` against the built-in public suffix list;
+    /// passes when unset.
+    pub fn validate_cookie_domain(&self) -> SaTokenResult<()> {
+        match &self.cookie_domain {
+            Some(domain) => validate_cookie_domain(domain, PublicSuffixList::builtin()),
+            None => Ok(()),
+        }
+    }
+    
+    /// 解析 `trusted_proxies`，格式错误的网段记录警告后忽略
+    /// 
+    /// Parses `trusted_proxies`; malformed entries are logged and skipped.
+    pub fn trusted_proxy_cidrs(&self) -> Vec<IpCidr> {
+        self.trusted_proxies.iter()
+            .filter_map(|cidr| {
+                let parsed = IpCidr::parse(cidr);
+                if parsed.is_none() {
+                    tracing::warn!("Sa-Token: 忽略无效的受信任代理网段 | ignoring invalid trusted proxy '{}'", cidr);
+                }
+                parsed
+            })
+            .collect()
+    }
+    
+    /// 去掉请求头中 token 前的前缀：先匹配 `token_prefix`，再匹配 `token_schemes`，
+    /// 均不匹配时原样返回（去除首尾空白）
+    /// 
+    /// Strips the prefix in front of a header token: `token_prefix` first, then
+    /// `token_schemes`; the trimmed value is returned when neither matches.
+    pub fn strip_token_prefix<'a>(&self, value: &'a str) -> &'a str {
+        self.token_prefix.as_slice().iter()
+            .chain(&self.token_schemes)
+            .find_map(|scheme| strip_token_scheme(value, std::slice::from_ref(scheme)))
+            .unwrap_or_else(|| value.trim())
+    }
+    
+    /// token Cookie 的选项，不含 `Max-Age` | Options of the token cookie, without `Max-Age`
+    pub fn cookie_options(&self) -> CookieOptions {
+        CookieOptions {
+            domain: self.cookie_domain.clone(),
+            path: self.cookie_path.clone(),
+            max_age: None,
+            http_only: self.cookie_http_only,
+            secure: self.cookie_secure,
+            same_site: self.cookie_same_site,
+        }
+    }
+    
+    /// 按 `cookie_domain_check` 处理有问题的 `cookie_domain`
+    /// 
+    /// Applies `cookie_domain_check`: `Warn` logs and keeps the domain,
+    /// `Deny` logs an error and clears it so cookies stay host-only.
+    pub(crate) fn apply_cookie_domain_check(&mut self) {
+        if self.cookie_domain_check == CookieDomainCheck::Off {
+            return;
+        }
+        let Err(e) = self.validate_cookie_domain() else {
+            return;
+        };
+        if self.cookie_domain_check == CookieDomainCheck::Deny {
+            tracing::error!("Sa-Token: {}，已忽略该域名 | ignoring it", e);
+            self.cookie_domain = None;
+        } else {
+            tracing::warn!("Sa-Token: {}", e);
+        }
+    }
+}
+
+fn read_pem(pem: &Option<String>, path: &Option<String>) -> SaTokenResult<Option<String>> {
+    match (pem, path) {
+        (Some(pem), _) => Ok(Some(pem.clone())),
+        (None, Some(path)) => std::fs::read_to_string(path)
+            .map(Some)
+            .map_err(|e| SaTokenError::ConfigError(format!("Failed to read JWT key file {}: {}", path, e))),
+        (None, None) => Ok(None),
+    }
 }
 
 /// Token 风格 | Token Style
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+/// 
+/// 除 JWT 外，登录时会检查新 token 是否与存储中已有的 token 重复，重复时重新生成，
+/// 最多 `max_try_times` 次。
+/// Except for JWT, a new token is checked against the storage at login and
+/// regenerated on a collision, up to `max_try_times` times.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TokenStyle {
     /// UUID 风格 | UUID style
     Uuid,
@@ -145,6 +729,117 @@ pub enum TokenStyle {
     Timestamp,
     /// Tik 风格（短小精悍的8位字符）| Tik style (short 8-character token)
     Tik,
+    /// 前缀 + 递增序号（如 `order-1001`），序号由存储的计数器分配，多实例间不重复
+    /// 
+    /// Prefix plus an increasing number (e.g. `order-1001`), allocated from a
+    /// storage counter so instances never hand out the same one. Sequential
+    /// tokens are guessable; use them only where that is acceptable.
+    Sequence { prefix: String },
+    /// 自定义生成器，只能在代码中设置，不能从配置文件读取
+    /// 
+    /// Custom generator; set in code only, it cannot be loaded from a config file.
+    #[serde(serialize_with = "serialize_token_generator", skip_deserializing)]
+    Custom(Arc<dyn TokenGenerator>),
+}
+
+impl TokenStyle {
+    /// 使用自定义生成器 | Use a custom generator
+    pub fn custom(generator: impl TokenGenerator + 'static) -> Self {
+        Self::Custom(Arc::new(generator))
+    }
+    
+    /// 前缀 + 递增序号风格 | Prefix plus sequence style
+    pub fn sequence(prefix: impl Into<String>) -> Self {
+        Self::Sequence { prefix: prefix.into() }
+    }
+}
+
+/// 自定义生成器按名称序列化 | Custom generators serialize as their name
+fn serialize_token_generator<S: serde::Serializer>(
+    generator: &Arc<dyn TokenGenerator>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(generator.name())
+}
+
+/// 响应 JSON 字段命名风格 | JSON Field Naming of Responses
+/// 
+/// 响应结构体（`AccessToken`、错误响应体等）按 snake_case 定义，camelCase 模式下
+/// 超出 `max_login_count` 时的淘汰策略 | Eviction policy beyond `max_login_count`
+/// 
+/// 本次登录签发的 token 总会保留，淘汰只发生在该账号已有的 token 中。
+/// The token of the current login is always kept; only the account's
+/// existing tokens are evicted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoginEviction {
+    /// 顶替最早登录的 token（默认）| Replace the earliest logins (default)
+    #[default]
+    Oldest,
+    /// 顶替最近登录的 token | Replace the most recent earlier logins
+    Newest,
+}
+
+/// 在序列化时递归转换对象的键名，前端无需为每个响应类型单独包装。
+/// 
+/// Response structs are declared in snake_case; `CamelCase` renames object keys
+/// recursively at serialization time. Free-form maps nested in a response
+/// (e.g. `extra_data`) are renamed as well.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JsonFieldCase {
+    /// `access_token`（默认）| `access_token` (default)
+    #[default]
+    SnakeCase,
+    /// `accessToken`
+    CamelCase,
+}
+
+impl JsonFieldCase {
+    /// 转换单个字段名 | Rename a single field
+    pub fn rename(&self, field: &str) -> String {
+        match self {
+            JsonFieldCase::SnakeCase => field.to_string(),
+            JsonFieldCase::CamelCase => {
+                let mut renamed = String::with_capacity(field.len());
+                let mut upper_next = false;
+                for ch in field.chars() {
+                    if ch == '_' && !renamed.is_empty() {
+                        upper_next = true;
+                    } else if upper_next {
+                        renamed.extend(ch.to_uppercase());
+                        upper_next = false;
+                    } else {
+                        renamed.push(ch);
+                    }
+                }
+                renamed
+            }
+        }
+    }
+    
+    /// 按当前风格转换 JSON 值中所有对象的键名 | Rename every object key of a JSON value
+    pub fn apply(&self, value: serde_json::Value) -> serde_json::Value {
+        use serde_json::Value;
+        
+        if *self == JsonFieldCase::SnakeCase {
+            return value;
+        }
+        match value {
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .map(|(key, value)| (self.rename(&key), self.apply(value)))
+                    .collect(),
+            ),
+            Value::Array(items) => Value::Array(items.into_iter().map(|v| self.apply(v)).collect()),
+            other => other,
+        }
+    }
+    
+    /// 序列化响应结构体并应用命名风格 | Serialize a response struct with this naming
+    pub fn to_value<T: Serialize>(&self, value: &T) -> serde_json::Result<serde_json::Value> {
+        serde_json::to_value(value).map(|v| self.apply(v))
+    }
 }
 
 /// 配置构建器
@@ -152,6 +847,18 @@ pub struct SaTokenConfigBuilder {
     config: SaTokenConfig,
     storage: Option<Arc<dyn SaStorage>>,
     listeners: Vec<Arc<dyn SaTokenListener>>,
+    login_policy: Option<Arc<dyn LoginPolicy>>,
+    ttl_policy: Option<Arc<dyn TtlPolicy>>,
+    session_validator: Option<Arc<dyn SessionValidator>>,
+    token_extractors: Vec<Arc<dyn TokenExtractor>>,
+    token_sources: Vec<Arc<dyn TokenExtractorSource>>,
+    legacy_importers: Vec<Arc<dyn LegacySessionImporter>>,
+    online_manager: Option<Arc<OnlineManager>>,
+    permission_checker: Option<Arc<dyn PermissionChecker>>,
+    role_checker: Option<Arc<dyn RoleChecker>>,
+    error_renderer: Option<Arc<dyn ErrorRenderer>>,
+    audit_logger: Option<Arc<dyn AuditLogger>>,
+    clock: Option<Arc<dyn Clock>>,
 }
 
 impl Default for SaTokenConfigBuilder {
@@ -160,6 +867,18 @@ impl Default for SaTokenConfigBuilder {
             config: SaTokenConfig::default(),
             storage: None,
             listeners: Vec::new(),
+            login_policy: None,
+            ttl_policy: None,
+            session_validator: None,
+            token_extractors: Vec::new(),
+            token_sources: Vec::new(),
+            legacy_importers: Vec::new(),
+            online_manager: None,
+            permission_checker: None,
+            role_checker: None,
+            error_renderer: None,
+            audit_logger: None,
+            clock: None,
         }
     }
 }
@@ -175,6 +894,12 @@ impl SaTokenConfigBuilder {
         self
     }
     
+    /// 设置"记住我"登录的 token 有效期（秒）
+    pub fn remember_timeout(mut self, timeout: i64) -> Self {
+        self.config.remember_timeout = timeout;
+        self
+    }
+    
     pub fn active_timeout(mut self, timeout: i64) -> Self {
         self.config.active_timeout = timeout;
         self
@@ -196,16 +921,69 @@ impl SaTokenConfigBuilder {
         self
     }
     
+    /// 设置同一账号最多同时在线的 token 数，-1 表示不限制
+    pub fn max_login_count(mut self, count: i64) -> Self {
+        self.config.max_login_count = count;
+        self
+    }
+    
+    /// 设置超出 `max_login_count` 时的淘汰策略
+    pub fn login_eviction(mut self, eviction: LoginEviction) -> Self {
+        self.config.login_eviction = eviction;
+        self
+    }
+    
     pub fn token_style(mut self, style: TokenStyle) -> Self {
         self.config.token_style = style;
         self
     }
     
+    /// 设置生成 token 时的最大冲突重试次数，<= 0 表示不检查冲突
+    pub fn max_try_times(mut self, times: i32) -> Self {
+        self.config.max_try_times = times;
+        self
+    }
+    
+    /// 设置是否输出操作日志
+    pub fn is_log(mut self, is_log: bool) -> Self {
+        self.config.is_log = is_log;
+        self
+    }
+    
+    /// 设置权限段分隔符，默认 `:`
+    pub fn permission_separator(mut self, separator: impl Into<String>) -> Self {
+        self.config.permission_separator = separator.into();
+        self
+    }
+    
+    /// 设置事件分发方式，如 `EventDispatchMode::queue()`
+    pub fn event_dispatch(mut self, mode: EventDispatchMode) -> Self {
+        self.config.event_dispatch = mode;
+        self
+    }
+    
+    /// 设置认证失败日志限流：每个来源每 `window_secs` 秒逐条记录 `limit` 次（0 表示不限流）
+    pub fn auth_failure_log_limit(mut self, limit: u32, window_secs: i64) -> Self {
+        self.config.auth_failure_log_limit = limit;
+        self.config.auth_failure_log_window = window_secs;
+        self
+    }
+    
     pub fn token_prefix(mut self, prefix: impl Into<String>) -> Self {
         self.config.token_prefix = Some(prefix.into());
         self
     }
     
+    /// 接受的认证方案，如 `["Bearer", "Token"]`，匹配不区分大小写
+    pub fn token_schemes<I, S>(mut self, schemes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.config.token_schemes = schemes.into_iter().map(Into::into).collect();
+        self
+    }
+    
     pub fn jwt_secret_key(mut self, key: impl Into<String>) -> Self {
         self.config.jwt_secret_key = Some(key.into());
         self
@@ -217,6 +995,45 @@ impl SaTokenConfigBuilder {
         self
     }
     
+    /// 设置 JWT 私钥（PEM 文本）
+    pub fn jwt_private_key_pem(mut self, pem: impl Into<String>) -> Self {
+        self.config.jwt_private_key_pem = Some(pem.into());
+        self
+    }
+    
+    /// 设置 JWT 公钥（PEM 文本）
+    pub fn jwt_public_key_pem(mut self, pem: impl Into<String>) -> Self {
+        self.config.jwt_public_key_pem = Some(pem.into());
+        self
+    }
+    
+    /// 设置 JWT 私钥文件路径
+    pub fn jwt_private_key_path(mut self, path: impl Into<String>) -> Self {
+        self.config.jwt_private_key_path = Some(path.into());
+        self
+    }
+    
+    /// 设置 JWT 公钥文件路径
+    pub fn jwt_public_key_path(mut self, path: impl Into<String>) -> Self {
+        self.config.jwt_public_key_path = Some(path.into());
+        self
+    }
+    
+    /// 设置 JWT 密钥 ID
+    pub fn jwt_key_id(mut self, kid: impl Into<String>) -> Self {
+        self.config.jwt_key_id = Some(kid.into());
+        self
+    }
+    
+    /// 添加轮换用的 HMAC 密钥，自 `active_from`（Unix 秒）起用于签名
+    pub fn jwt_rotation_secret(mut self, secret: impl Into<String>, active_from: i64) -> Self {
+        self.config.jwt_rotation_secrets.push(crate::token::jwt::JwtSecret {
+            secret: secret.into(),
+            active_from,
+        });
+        self
+    }
+    
     /// 设置 JWT 签发者
     pub fn jwt_issuer(mut self, issuer: impl Into<String>) -> Self {
         self.config.jwt_issuer = Some(issuer.into());
@@ -253,6 +1070,68 @@ impl SaTokenConfigBuilder {
         self
     }
     
+    /// 设置 Refresh Token 家族的绝对最长寿命（秒），-1 表示不限制
+    pub fn refresh_token_max_lifetime(mut self, seconds: i64) -> Self {
+        self.config.refresh_token_max_lifetime = seconds;
+        self
+    }
+    
+    /// 设置刷新时是否轮换 Refresh Token
+    pub fn refresh_token_rotation(mut self, enabled: bool) -> Self {
+        self.config.refresh_token_rotation = enabled;
+        self
+    }
+    
+    /// 开启开发模式免登录（本地开发用）
+    /// 
+    /// 开启后所有登录/权限/角色检查都会直接通过，当前账号为 `dev_bypass_login_id`。
+    /// release 构建中除非设置了 `SA_TOKEN_DEV_BYPASS=1`，否则该配置会被拒绝。
+    /// 
+    /// Enable dev bypass for local development. Refused in release builds unless
+    /// `SA_TOKEN_DEV_BYPASS=1` is set.
+    pub fn dev_bypass(mut self, enabled: bool) -> Self {
+        self.config.dev_bypass = enabled;
+        self
+    }
+    
+    /// 设置开发模式免登录时的假 login_id
+    pub fn dev_bypass_login_id(mut self, login_id: impl Into<String>) -> Self {
+        self.config.dev_bypass_login_id = login_id.into();
+        self
+    }
+    
+    /// 设置 Session 单个 key 的最大字节数，-1 表示不限制
+    pub fn session_max_value_bytes(mut self, bytes: i64) -> Self {
+        self.config.session_max_value_bytes = bytes;
+        self
+    }
+    
+    /// 设置整个 Session 的最大字节数，-1 表示不限制
+    pub fn session_max_bytes(mut self, bytes: i64) -> Self {
+        self.config.session_max_bytes = bytes;
+        self
+    }
+    
+    /// 设置幂等键结果的保留时间（秒），-1 表示永久保留
+    pub fn idempotency_retention(mut self, seconds: i64) -> Self {
+        self.config.idempotency_retention = seconds;
+        self
+    }
+    
+    /// 开启新设备登录通知
+    /// 
+    /// 账号已有在线会话时再次登录，会通过 OnlineManager 推送提醒并记录到登录历史
+    pub fn notify_new_sign_in(mut self, enabled: bool) -> Self {
+        self.config.notify_new_sign_in = enabled;
+        self
+    }
+    
+    /// 设置需要新设备登录通知的登录类型（不设置表示所有类型）
+    pub fn notify_new_sign_in_login_types(mut self, login_types: Vec<String>) -> Self {
+        self.config.notify_new_sign_in_login_types = login_types;
+        self
+    }
+    
     /// 设置存储方式
     pub fn storage(mut self, storage: Arc<dyn SaStorage>) -> Self {
         self.storage = Some(storage);
@@ -281,6 +1160,224 @@ impl SaTokenConfigBuilder {
         self
     }
     
+    /// 设置登录策略钩子（例如密码过期检查）
+    /// 
+    /// 策略返回 `PasswordExpired` 时，登录只会得到修改密码用的受限 token
+    pub fn login_policy(mut self, policy: Arc<dyn LoginPolicy>) -> Self {
+        self.login_policy = Some(policy);
+        self
+    }
+    
+    /// 设置过期判断使用的时钟
+    /// 
+    /// 默认读取 `sa_token_core::time::now()`；测试中传入 `MockClock` 即可控制 token 的过期
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+    
+    /// 设置 token 有效期策略
+    /// 
+    /// 登录时由策略决定 token 有效期（如新设备更短、受信任设备更长），替代固定的 timeout
+    pub fn ttl_policy(mut self, policy: Arc<dyn TtlPolicy>) -> Self {
+        self.ttl_policy = Some(policy);
+        self
+    }
+    
+    /// 设置会话复查钩子
+    /// 
+    /// token 校验时按 `session_validation_interval` 间隔复查业务规则，不通过时自动登出
+    pub fn session_validator(mut self, validator: Arc<dyn SessionValidator>) -> Self {
+        self.session_validator = Some(validator);
+        self
+    }
+    
+    /// 设置会话复查间隔（秒）
+    pub fn session_validation_interval(mut self, seconds: i64) -> Self {
+        self.config.session_validation_interval = seconds;
+        self
+    }
+    
+    /// 注册自定义 token 提取器
+    /// 
+    /// 插件中间件会按优先级先调用自定义提取器，都未提取到时再使用内置的 Header/Cookie/Query 逻辑
+    pub fn token_extractor(mut self, extractor: Arc<dyn TokenExtractor>) -> Self {
+        self.token_extractors.push(extractor);
+        self
+    }
+    
+    /// 设置 token 的查找顺序
+    /// 
+    /// ```rust,ignore
+    /// .token_sources(vec![TokenSource::Header(Some("X-Access-Token".into())), TokenSource::Cookie(None)])
+    /// ```
+    pub fn token_sources(mut self, sources: Vec<TokenSource>) -> Self {
+        self.config.token_sources = sources;
+        self
+    }
+    
+    /// 注册具名的自定义 token 来源，需在 `token_sources` 中以 `TokenSource::Custom(name)` 列出
+    pub fn token_source(mut self, source: Arc<dyn TokenExtractorSource>) -> Self {
+        self.token_sources.push(source);
+        self
+    }
+    
+    /// 注册旧认证库的会话导入器（actix-identity、tower-sessions 等）
+    /// 
+    /// 遇到未知 token 时尝试从旧库的会话记录导入，成功后以同一个 token 值登录
+    pub fn legacy_importer(mut self, importer: Arc<dyn LegacySessionImporter>) -> Self {
+        self.legacy_importers.push(importer);
+        self
+    }
+    
+    /// 设置在线用户管理器
+    /// 
+    /// 踢人下线时通过管理器注册的推送器通知在线连接（如 `WebSocketPusher`）
+    pub fn online_manager(mut self, manager: Arc<OnlineManager>) -> Self {
+        self.online_manager = Some(manager);
+        self
+    }
+    
+    /// 设置权限检查器（如从数据库按需加载权限），优先于内存中的权限映射
+    pub fn permission_checker(mut self, checker: Arc<dyn PermissionChecker>) -> Self {
+        self.permission_checker = Some(checker);
+        self
+    }
+    
+    /// 设置角色检查器，优先于内存中的角色映射
+    pub fn role_checker(mut self, checker: Arc<dyn RoleChecker>) -> Self {
+        self.role_checker = Some(checker);
+        self
+    }
+    
+    /// 设置插件拒绝请求时的响应渲染器，默认 [`DefaultErrorRenderer`](crate::DefaultErrorRenderer)
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// .error_renderer(Arc::new(ProblemJsonRenderer::new().realm("api")))
+    /// ```
+    pub fn error_renderer(mut self, renderer: Arc<dyn ErrorRenderer>) -> Self {
+        self.error_renderer = Some(renderer);
+        self
+    }
+    
+    /// 设置审计日志，登录、登出、踢人、封禁、鉴权失败等事件会写入其中
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// .audit_logger(Arc::new(StorageAuditLogger::new(storage.clone()).retention(180 * 86400)))
+    /// ```
+    pub fn audit_logger(mut self, logger: Arc<dyn AuditLogger>) -> Self {
+        self.audit_logger = Some(logger);
+        self
+    }
+    
+    /// 设置是否记录请求鉴权决策日志（调试模式）
+    pub fn debug_decision_log(mut self, enabled: bool) -> Self {
+        self.config.debug_decision_log = enabled;
+        self
+    }
+    
+    /// 设置响应 JSON 的字段命名风格（snake_case / camelCase）
+    pub fn json_field_case(mut self, case: JsonFieldCase) -> Self {
+        self.config.json_field_case = case;
+        self
+    }
+    
+    /// 设置是否在登录时预取权限与角色
+    pub fn prefetch_authz(mut self, enabled: bool) -> Self {
+        self.config.prefetch_authz = enabled;
+        self
+    }
+    
+    /// 设置踢人下线后的宽限期（秒），超过 `MAX_KICK_OUT_GRACE_PERIOD` 按上限处理
+    pub fn kick_out_grace_period(mut self, seconds: i64) -> Self {
+        self.config.kick_out_grace_period = seconds;
+        self
+    }
+    
+    /// 设置当前实例所在可用区，新 token 会带上可用区提示
+    pub fn locality_hint(mut self, zone: impl Into<String>) -> Self {
+        self.config.locality_hint = Some(zone.into());
+        self
+    }
+    
+    /// 设置权限/角色命名规则
+    pub fn authz_naming_rules(mut self, rules: AuthzNamingRules) -> Self {
+        self.config.authz_naming_rules = Some(rules);
+        self
+    }
+    
+    /// 设置 token Cookie 的 `Domain`
+    pub fn cookie_domain(mut self, domain: impl Into<String>) -> Self {
+        self.config.cookie_domain = Some(domain.into());
+        self
+    }
+    
+    /// 设置是否在登录后写入 token Cookie
+    pub fn write_cookie(mut self, enabled: bool) -> Self {
+        self.config.is_write_cookie = enabled;
+        self
+    }
+    
+    /// 设置 token Cookie 的 `Path`
+    pub fn cookie_path(mut self, path: impl Into<String>) -> Self {
+        self.config.cookie_path = Some(path.into());
+        self
+    }
+    
+    /// 设置 token Cookie 是否带 `HttpOnly`
+    pub fn cookie_http_only(mut self, http_only: bool) -> Self {
+        self.config.cookie_http_only = http_only;
+        self
+    }
+    
+    /// 设置 token Cookie 是否带 `Secure`
+    pub fn cookie_secure(mut self, secure: bool) -> Self {
+        self.config.cookie_secure = secure;
+        self
+    }
+    
+    /// 设置 token Cookie 的 `SameSite`，None 表示不带该属性
+    pub fn cookie_same_site(mut self, same_site: Option<SameSite>) -> Self {
+        self.config.cookie_same_site = same_site;
+        self
+    }
+    
+    /// 设置 Cookie 域名有问题时的处理方式
+    pub fn cookie_domain_check(mut self, check: CookieDomainCheck) -> Self {
+        self.config.cookie_domain_check = check;
+        self
+    }
+    
+    /// 设置并发登录去重窗口（秒），0 表示关闭
+    pub fn login_dedup_window(mut self, seconds: i64) -> Self {
+        self.config.login_dedup_window = seconds;
+        self
+    }
+    
+    /// 设置 Same-Token 的轮换周期（秒），<= 0 表示不自动轮换
+    pub fn same_token_timeout(mut self, seconds: i64) -> Self {
+        self.config.same_token_timeout = seconds;
+        self
+    }
+    
+    /// 设置是否把 token 绑定到登录时的 IP
+    pub fn token_bind_ip(mut self, enabled: bool) -> Self {
+        self.config.token_bind_ip = enabled;
+        self
+    }
+    
+    /// 设置受信任的反向代理网段（CIDR），如 `["10.0.0.0/8"]`
+    pub fn trusted_proxies<I, S>(mut self, cidrs: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.config.trusted_proxies = cidrs.into_iter().map(Into::into).collect();
+        self
+    }
+    
     /// 构建 SaTokenManager（需要先设置 storage）
     /// 
     /// 自动完成以下操作：
@@ -312,7 +1409,43 @@ impl SaTokenConfigBuilder {
     /// ```
     pub fn build(self) -> crate::SaTokenManager {
         let storage = self.storage.expect("Storage must be set before building SaTokenManager. Use .storage() method.");
-        let manager = crate::SaTokenManager::new(storage, self.config);
+        let mut manager = crate::SaTokenManager::new(storage, self.config);
+        if let Some(policy) = self.login_policy {
+            manager = manager.with_login_policy(policy);
+        }
+        if let Some(policy) = self.ttl_policy {
+            manager = manager.with_ttl_policy(policy);
+        }
+        if let Some(validator) = self.session_validator {
+            manager = manager.with_session_validator(validator);
+        }
+        for extractor in self.token_extractors {
+            manager = manager.with_token_extractor(extractor);
+        }
+        for source in self.token_sources {
+            manager = manager.with_token_source(source);
+        }
+        for importer in self.legacy_importers {
+            manager = manager.with_legacy_importer(importer);
+        }
+        if let Some(online) = self.online_manager {
+            manager = manager.with_online_manager(online);
+        }
+        if let Some(checker) = self.permission_checker {
+            manager.set_permission_checker(checker);
+        }
+        if let Some(checker) = self.role_checker {
+            manager.set_role_checker(checker);
+        }
+        if let Some(renderer) = self.error_renderer {
+            manager = manager.with_error_renderer(renderer);
+        }
+        if let Some(logger) = self.audit_logger {
+            manager = manager.with_audit_logger(logger);
+        }
+        if let Some(clock) = self.clock {
+            manager = manager.with_clock(clock);
+        }
         
         // 同步注册所有监听器
         // Register all listeners synchronously
@@ -335,3 +1468,86 @@ impl SaTokenConfigBuilder {
         self.config
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_storage_memory::MemoryStorage;
+    
+    #[test]
+    fn test_dev_bypass_disabled_by_default() {
+        let manager = crate::SaTokenManager::new(
+            Arc::new(MemoryStorage::new()),
+            SaTokenConfig::default(),
+        );
+        assert!(manager.dev_bypass_login_id().is_none());
+    }
+    
+    #[test]
+    fn test_dev_bypass_login_id() {
+        let config = SaTokenConfig::builder()
+            .dev_bypass(true)
+            .dev_bypass_login_id("local_admin")
+            .build_config();
+        let manager = crate::SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        
+        // 测试在 debug 构建下运行，dev_bypass 总是被允许
+        assert!(SaTokenConfig::dev_bypass_allowed());
+        assert_eq!(manager.dev_bypass_login_id().as_deref(), Some("local_admin"));
+    }
+    
+    #[test]
+    fn test_cookie_domain_check() {
+        let config = SaTokenConfig::builder().cookie_domain(".com").build_config();
+        assert!(config.validate_cookie_domain().is_err());
+        let manager = crate::SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        assert_eq!(manager.config().cookie_domain.as_deref(), Some(".com"));
+        
+        let config = SaTokenConfig::builder()
+            .cookie_domain("github.io")
+            .cookie_domain_check(CookieDomainCheck::Deny)
+            .build_config();
+        let manager = crate::SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        assert!(manager.config().cookie_domain.is_none());
+        
+        let config = SaTokenConfig::builder()
+            .cookie_domain(".example.com")
+            .cookie_domain_check(CookieDomainCheck::Deny)
+            .build_config();
+        let manager = crate::SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        assert_eq!(manager.config().cookie_domain.as_deref(), Some(".example.com"));
+    }
+    
+    #[test]
+    fn test_json_field_case_rename() {
+        assert_eq!(JsonFieldCase::CamelCase.rename("access_token"), "accessToken");
+        assert_eq!(JsonFieldCase::CamelCase.rename("expires_in"), "expiresIn");
+        assert_eq!(JsonFieldCase::CamelCase.rename("scope"), "scope");
+        assert_eq!(JsonFieldCase::CamelCase.rename("_private"), "_private");
+        assert_eq!(JsonFieldCase::SnakeCase.rename("access_token"), "access_token");
+    }
+    
+    #[test]
+    fn test_json_field_case_from_config() {
+        let token = crate::oauth2::AccessToken {
+            access_token: "at".to_string(),
+            token_type: "Bearer".to_string(),
+            expires_in: 3600,
+            refresh_token: Some("rt".to_string()),
+            scope: vec!["read".to_string()],
+        };
+        
+        let config = SaTokenConfig::builder()
+            .json_field_case(JsonFieldCase::CamelCase)
+            .build_config();
+        let manager = crate::SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        let json = manager.response_json(&token).unwrap();
+        assert_eq!(json["accessToken"], "at");
+        assert_eq!(json["refreshToken"], "rt");
+        assert!(json.get("access_token").is_none());
+        
+        // 配置文件中的写法 | As written in config files
+        let case: JsonFieldCase = serde_json::from_str(r#""camel_case""#).unwrap();
+        assert_eq!(case, JsonFieldCase::CamelCase);
+    }
+}
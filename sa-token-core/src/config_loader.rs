@@ -0,0 +1,249 @@
+// Author: 金书记
+//
+//! 从配置文件与环境变量加载配置 | Loading configuration from files and environment
+//!
+//! 部署时无需改代码即可调整 sa-token：`from_file` 按扩展名读取 TOML / YAML / JSON，
+//! `from_env` 读取 `SA_TOKEN_` 前缀的环境变量，`merge_env` 把环境变量叠加在已有配置上。
+//! 缺省的字段取默认值。
+//!
+//! Deployments can configure sa-token without code changes: `from_file` reads
+//! TOML / YAML / JSON by extension, `from_env` reads `SA_TOKEN_`-prefixed
+//! variables, and `merge_env` layers the environment over an existing config.
+//! Missing fields take their defaults.
+//!
+//! 环境变量 | Environment variables:
+//!
+//! - `SA_TOKEN_TIMEOUT=3600` → `timeout`，字段名转大写 | field name in upper case
+//! - `SA_TOKEN_STORAGE__TYPE=redis`、`SA_TOKEN_STORAGE__URL=redis://...` → `storage`，`__` 分隔嵌套字段 | `__` separates nested fields
+//! - 数组与对象写 JSON；字符串数组也可用逗号分隔 | Arrays and objects as JSON; string arrays may be comma-separated
+//! - `SA_TOKEN_DEV_BYPASS` 是 release 构建的安全开关，不映射到 `dev_bypass` 字段 |
+//!   `SA_TOKEN_DEV_BYPASS` is the release-build safety switch and does not map to the `dev_bypass` field
+//!
+//! ```toml
+//! # sa-token.toml
+//! token_name = "Authorization"
+//! timeout = 7200
+//! is_concurrent = false
+//!
+//! [storage]
+//! type = "redis"
+//! url = "redis://localhost:6379/0"
+//! ```
+//!
+//! ```rust,ignore
+//! let config = SaTokenConfig::from_file("sa-token.toml")?.merge_env()?;
+//! let storage = sa_token::storage::from_config(&config.storage).await?;
+//! let manager = config.into_builder().storage(storage).build();
+//! ```
+
+use std::path::Path;
+use serde_json::{Map, Value};
+use crate::config::{SaTokenConfig, TokenStyle, DEV_BYPASS_ENV};
+use crate::error::{SaTokenError, SaTokenResult};
+
+/// 环境变量前缀 | Environment variable prefix
+pub const ENV_PREFIX: &str = "SA_TOKEN_";
+
+/// 嵌套字段分隔符 | Separator between nested field names
+pub const ENV_NESTED_SEPARATOR: &str = "__";
+
+impl SaTokenConfig {
+    /// 从配置文件加载，格式由扩展名决定（`.toml`、`.yaml`/`.yml`、`.json`）
+    ///
+    /// Loads a config file; the format follows the extension. TOML needs the
+    /// `toml` feature (on by default), YAML the `yaml` feature.
+    pub fn from_file(path: impl AsRef<Path>) -> SaTokenResult<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| SaTokenError::ConfigError(format!("cannot read {}: {}", path.display(), e)))?;
+        let extension = path.extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        let value = parse_file(&content, &extension)
+            .map_err(|e| SaTokenError::ConfigError(format!("{}: {}", path.display(), e)))?;
+        serde_json::from_value(value)
+            .map_err(|e| SaTokenError::ConfigError(format!("{}: {}", path.display(), e)))
+    }
+
+    /// 从 `SA_TOKEN_` 前缀的环境变量加载，未设置的字段取默认值
+    ///
+    /// Loads from `SA_TOKEN_`-prefixed environment variables on top of the defaults.
+    pub fn from_env() -> SaTokenResult<Self> {
+        Self::default().merge_env()
+    }
+
+    /// 用环境变量覆盖当前配置中的对应字段
+    ///
+    /// Overrides fields of this config with the matching environment variables.
+    pub fn merge_env(self) -> SaTokenResult<Self> {
+        self.merge_vars(std::env::vars())
+    }
+
+    pub(crate) fn merge_vars(self, vars: impl IntoIterator<Item = (String, String)>) -> SaTokenResult<Self> {
+        // 自定义生成器不能反序列化，未被环境变量覆盖时原样保留
+        let custom_style = matches!(self.token_style, TokenStyle::Custom(_)).then(|| self.token_style.clone());
+        let mut root = serde_json::to_value(&self)?;
+        if custom_style.is_some() {
+            root["token_style"] = Value::Null;
+        }
+        for (key, raw) in vars {
+            if key == DEV_BYPASS_ENV {
+                continue;
+            }
+            let Some(name) = key.strip_prefix(ENV_PREFIX) else {
+                continue;
+            };
+            let path: Vec<String> = name.to_ascii_lowercase()
+                .split(ENV_NESTED_SEPARATOR)
+                .map(str::to_string)
+                .collect();
+
+            if root.get(&path[0]).is_none() {
+                tracing::warn!("Sa-Token: 忽略未知的配置环境变量 {}", key);
+                continue;
+            }
+            set_path(&mut root, &path, &raw);
+        }
+        let keep_custom = root["token_style"].is_null();
+        if keep_custom {
+            root["token_style"] = serde_json::json!("Uuid");
+        }
+        let mut config: Self = serde_json::from_value(root)
+            .map_err(|e| SaTokenError::ConfigError(format!("environment: {}", e)))?;
+        if let Some(style) = custom_style.filter(|_| keep_custom) {
+            config.token_style = style;
+        }
+        Ok(config)
+    }
+}
+
+fn parse_file(content: &str, extension: &str) -> Result<Value, String> {
+    match extension {
+        "json" => serde_json::from_str(content).map_err(|e| e.to_string()),
+        #[cfg(feature = "toml")]
+        "toml" => toml::from_str(content).map_err(|e| e.to_string()),
+        #[cfg(feature = "yaml")]
+        "yaml" | "yml" => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+        #[cfg(not(feature = "toml"))]
+        "toml" => Err("TOML support requires the `toml` feature".to_string()),
+        #[cfg(not(feature = "yaml"))]
+        "yaml" | "yml" => Err("YAML support requires the `yaml` feature".to_string()),
+        other => Err(format!("unsupported config format '{}', expected toml, yaml or json", other)),
+    }
+}
+
+/// 按路径写入一个环境变量的值，中间缺失的对象自动创建
+fn set_path(value: &mut Value, path: &[String], raw: &str) {
+    if !value.is_object() {
+        *value = Value::Object(Map::new());
+    }
+    let Value::Object(fields) = value else {
+        return;
+    };
+    match path {
+        [field] => {
+            let parsed = env_value(fields.get(field), raw);
+            fields.insert(field.clone(), parsed);
+        }
+        [field, rest @ ..] => set_path(fields.entry(field.clone()).or_insert(Value::Null), rest, raw),
+        [] => {}
+    }
+}
+
+/// 按字段当前值的类型解释环境变量的文本
+///
+/// 字符串字段与未设置的可选字段保持原文（除非写成 JSON 对象/数组），
+/// 布尔值额外接受 `1`/`0`，字符串数组可用逗号分隔。
+fn env_value(current: Option<&Value>, raw: &str) -> Value {
+    let trimmed = raw.trim();
+    let as_json = || serde_json::from_str::<Value>(trimmed).ok();
+    match current {
+        Some(Value::Bool(_)) => match trimmed.to_ascii_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => Value::Bool(true),
+            "0" | "false" | "no" | "off" => Value::Bool(false),
+            _ => Value::String(raw.to_string()),
+        },
+        Some(Value::Number(_)) => as_json().unwrap_or_else(|| Value::String(raw.to_string())),
+        Some(Value::Array(_)) => as_json()
+            .filter(Value::is_array)
+            .unwrap_or_else(|| Value::Array(
+                trimmed.split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(|item| Value::String(item.to_string()))
+                    .collect(),
+            )),
+        _ if trimmed.starts_with('{') || trimmed.starts_with('[') => {
+            as_json().unwrap_or_else(|| Value::String(raw.to_string()))
+        }
+        _ => Value::String(raw.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::StorageConfig;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_merge_env_vars() {
+        let config = SaTokenConfig::default().merge_vars(vars(&[
+            ("SA_TOKEN_TIMEOUT", "3600"),
+            ("SA_TOKEN_IS_CONCURRENT", "0"),
+            ("SA_TOKEN_TOKEN_NAME", "12345"),
+            ("SA_TOKEN_JWT_SECRET_KEY", "true"),
+            ("SA_TOKEN_NOTIFY_NEW_SIGN_IN_LOGIN_TYPES", "admin, app"),
+            ("SA_TOKEN_STORAGE__TYPE", "redis"),
+            ("SA_TOKEN_STORAGE__URL", "redis://localhost:6379/0"),
+            ("SA_TOKEN_DEV_BYPASS", "1"),
+            ("SA_TOKEN_UNKNOWN", "x"),
+            ("PATH", "/usr/bin"),
+        ])).unwrap();
+
+        assert_eq!(config.timeout, 3600);
+        assert!(!config.is_concurrent);
+        assert_eq!(config.token_name, "12345");
+        assert_eq!(config.jwt_secret_key.as_deref(), Some("true"));
+        assert_eq!(config.notify_new_sign_in_login_types, vec!["admin", "app"]);
+        assert!(!config.dev_bypass);
+        assert_eq!(config.storage, StorageConfig::Redis {
+            url: "redis://localhost:6379/0".to_string(),
+            key_prefix: "sa-token:".to_string(),
+        });
+
+        let err = SaTokenConfig::default().merge_vars(vars(&[("SA_TOKEN_TIMEOUT", "soon")]));
+        assert!(matches!(err, Err(SaTokenError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_from_file_by_extension() {
+        let dir = std::env::temp_dir().join(format!("sa-token-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let json = dir.join("sa-token.json");
+        std::fs::write(&json, r#"{"timeout": 60, "storage": {"type": "memory"}}"#).unwrap();
+        let config = SaTokenConfig::from_file(&json).unwrap();
+        assert_eq!((config.timeout, config.token_name.as_str()), (60, "sa-token"));
+        assert_eq!(config.storage, StorageConfig::Memory);
+
+        #[cfg(feature = "toml")]
+        {
+            let toml = dir.join("sa-token.toml");
+            std::fs::write(&toml, "token_name = \"X-Token\"\n[storage]\ntype = \"redis\"\nurl = \"redis://cache\"\n").unwrap();
+            let config = SaTokenConfig::from_file(&toml).unwrap();
+            assert_eq!(config.token_name, "X-Token");
+            assert!(matches!(config.storage, StorageConfig::Redis { ref url, .. } if url == "redis://cache"));
+        }
+
+        let ini = dir.join("sa-token.ini");
+        std::fs::write(&ini, "timeout=1").unwrap();
+        assert!(matches!(SaTokenConfig::from_file(&ini), Err(SaTokenError::ConfigError(_))));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -2,15 +2,29 @@
 //
 //! 上下文模块 - 用于在请求处理过程中传递 token 信息
 //! 
-//! 注意：在实际应用中，建议通过框架的请求扩展（如 Axum 的 Extension）
-//! 来传递上下文，而不是使用 thread_local。这里提供的是一个简单的实现。
+//! 框架插件通过 [`SaTokenContext::scope`] 把上下文绑定到处理请求的异步任务上（tokio
+//! `task_local!`），处理函数在 `.await` 之后被调度到其他线程时仍能读取到同一个上下文。
+//! 不在 `scope` 内时（如 Rocket 的 fairing、同步代码）回退到当前线程的 thread_local。
+//! 
+//! Framework plugins bind the context to the task handling the request with
+//! [`SaTokenContext::scope`] (a tokio `task_local!`), so handlers still see it
+//! after resuming on another worker thread. Outside a scope, e.g. Rocket
+//! fairings or sync code, the thread-local fallback is used.
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::cell::RefCell;
+use sa_token_adapter::context::ResponseCookie;
+use chrono::{DateTime, Utc};
+use crate::error::SaTokenError;
 use crate::token::{TokenInfo, TokenValue};
 
 thread_local! {
-    static CONTEXT: RefCell<Option<SaTokenContext>> = RefCell::new(None);
+    static CONTEXT: RefCell<Option<SaTokenContext>> = const { RefCell::new(None) };
+}
+
+#[cfg(feature = "runtime")]
+tokio::task_local! {
+    static TASK_CONTEXT: RefCell<Option<SaTokenContext>>;
 }
 
 /// sa-token 上下文 | sa-token Context
@@ -22,6 +36,12 @@ thread_local! {
 /// - `token`: 当前请求的 token | Current request's token
 /// - `token_info`: Token 详细信息 | Token detailed information
 /// - `login_id`: 登录用户 ID | Logged-in user ID
+/// - `started_at`: 请求开始时间 | Request start time
+/// - `locale` / `timezone`: 请求头中的语言与时区 | Locale and timezone from the request headers
+/// - `route`: 匹配到的路由模式 | Matched route pattern
+/// - `same_token`: 请求携带的 Same-Token | Same-Token sent with the request
+/// - `client_ip` / `user_agent`: 请求来源 IP 与 User-Agent | Client IP and User-Agent of the request
+/// - `auth_error`: 请求 token 校验失败的原因 | Why the request's token failed validation
 #[derive(Debug, Clone)]
 pub struct SaTokenContext {
     /// 当前请求的 token | Current request's token
@@ -32,6 +52,34 @@ pub struct SaTokenContext {
     
     /// 登录 ID | Login ID
     pub login_id: Option<String>,
+    
+    /// 请求开始时间，用于踢人宽限期判断 | Request start time, checked against the kick-out grace period
+    pub started_at: DateTime<Utc>,
+    
+    /// `Accept-Language` 中的首选语言 | Preferred language from `Accept-Language`
+    pub locale: Option<String>,
+    
+    /// `X-Timezone` 中的时区 | Timezone from `X-Timezone`
+    pub timezone: Option<String>,
+    
+    /// 路由模式（如 `/users/{id}`），用于拒绝统计 | Route pattern (e.g. `/users/{id}`), used by the denial metrics
+    pub route: Option<String>,
+    
+    /// `sa-same-token` 请求头的值 | Value of the `sa-same-token` header
+    pub same_token: Option<String>,
+    
+    /// 请求来源 IP | Client IP of the request
+    pub client_ip: Option<String>,
+    
+    /// `User-Agent` 请求头 | The `User-Agent` header
+    pub user_agent: Option<String>,
+    
+    /// token 校验失败的原因（被踢、被顶替、IP 不一致、受限 token 等），`SaCheck` 拒绝时返回它
+    /// | Why the token failed validation (kicked out, replaced, IP mismatch, restricted, ...); returned by `SaCheck` on rejection
+    pub auth_error: Option<Arc<SaTokenError>>,
+    
+    /// 待写回响应的 Cookie，各克隆之间共享 | Cookie to write back, shared between clones
+    response_cookie: Arc<Mutex<Option<ResponseCookie>>>,
 }
 
 impl SaTokenContext {
@@ -40,38 +88,118 @@ impl SaTokenContext {
             token: None,
             token_info: None,
             login_id: None,
+            started_at: crate::time::now(),
+            locale: None,
+            timezone: None,
+            route: None,
+            same_token: None,
+            client_ip: None,
+            user_agent: None,
+            auth_error: None,
+            response_cookie: Arc::new(Mutex::new(None)),
         }
     }
     
+    /// 从请求头中记录语言与时区，格式不合法的值被忽略 | Record locale and timezone from request headers; malformed values are ignored
+    /// 
+    /// 参数为 `Accept-Language` 与 `X-Timezone` 请求头的值。
+    /// Takes the values of the `Accept-Language` and `X-Timezone` headers.
+    pub fn capture_locale(&mut self, accept_language: Option<&str>, timezone: Option<&str>) {
+        self.locale = accept_language.and_then(crate::locale::parse_accept_language);
+        self.timezone = timezone.and_then(crate::locale::parse_timezone);
+    }
+    
+    /// 记录请求携带的 Same-Token，空值被忽略 | Record the request's Same-Token; empty values are ignored
+    pub fn capture_same_token(&mut self, same_token: Option<&str>) {
+        self.same_token = same_token.filter(|value| !value.is_empty()).map(str::to_string);
+    }
+    
+    /// 记录请求来源 IP 与 User-Agent，空值被忽略 | Record the client IP and User-Agent; empty values are ignored
+    pub fn capture_client(&mut self, ip: Option<&str>, user_agent: Option<&str>) {
+        self.client_ip = ip.map(str::trim).filter(|value| !value.is_empty()).map(str::to_string);
+        self.user_agent = user_agent.filter(|value| !value.is_empty()).map(str::to_string);
+    }
+    
+    /// 记录需要写回响应的 Cookie，后记录的覆盖先记录的 | Queue a cookie for the response; a later one replaces an earlier one
+    /// 
+    /// `get_current()` 返回的克隆与插件中间件持有的上下文共享同一个槽位。
+    /// Clones returned by `get_current()` share the slot with the context the
+    /// plugin middleware holds.
+    pub fn set_response_cookie(&self, cookie: ResponseCookie) {
+        *self.response_cookie.lock().unwrap_or_else(|e| e.into_inner()) = Some(cookie);
+    }
+    
+    /// 取出待写回的 Cookie，由插件中间件在处理函数返回后调用 | Take the queued cookie; called by plugin middlewares after the handler
+    pub fn take_response_cookie(&self) -> Option<ResponseCookie> {
+        self.response_cookie.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+    
+    /// 请求没有登录 ID 时的拒绝原因 | Why a request without a login ID is rejected
+    /// 
+    /// 优先返回 `auth_error` 记录的校验失败原因；受限 token（如密码过期后签发的）返回
+    /// `PasswordExpired`，否则返回 `NotLogin`。
+    /// The recorded `auth_error` first, then `PasswordExpired` for a restricted
+    /// token and `NotLogin` otherwise.
+    pub fn login_error(&self) -> SaTokenError {
+        if let Some(err) = self.auth_error.as_ref().and_then(|err| err.clone_auth_error()) {
+            err
+        } else if self.token_info.as_ref().is_some_and(|info| info.is_restricted()) {
+            SaTokenError::PasswordExpired
+        } else {
+            SaTokenError::NotLogin
+        }
+    }
+    
+    /// 在上下文中执行 future | Run a Future Within a Context
+    /// 
+    /// 上下文随 future 所在的任务走，不受线程切换影响；future 结束后自动失效，无需 `clear`。
+    /// The context follows the task across worker threads and ends with the
+    /// future, so no `clear` is needed.
+    /// 
+    /// # 示例 | Example
+    /// ```rust,ignore
+    /// let response = SaTokenContext::scope(ctx, inner.call(request)).await;
+    /// ```
+    #[cfg(feature = "runtime")]
+    pub fn scope<F: std::future::Future>(ctx: SaTokenContext, future: F) -> impl std::future::Future<Output = F::Output> {
+        TASK_CONTEXT.scope(RefCell::new(Some(ctx)), future)
+    }
+    
     /// 设置当前上下文 | Set Current Context
     /// 
+    /// 在 `scope` 内替换任务的上下文，否则写入当前线程。
+    /// Replaces the task's context inside a `scope`, the thread's otherwise.
+    /// 
     /// # 参数 | Parameters
     /// - `ctx`: 要设置的上下文 | Context to set
     pub fn set_current(ctx: SaTokenContext) {
-        CONTEXT.with(|c| {
-            *c.borrow_mut() = Some(ctx);
-        });
+        Self::with_slot(|slot| *slot.borrow_mut() = Some(ctx));
     }
     
     /// 获取当前上下文 | Get Current Context
     /// 
     /// # 返回 | Returns
-    /// 当前线程的上下文，如果不存在则返回 None
-    /// Current thread's context, or None if not exists
+    /// 当前任务（`scope` 内）或当前线程的上下文，如果不存在则返回 None
+    /// Context of the current task (inside a `scope`) or thread, or None if not exists
     pub fn get_current() -> Option<SaTokenContext> {
-        CONTEXT.with(|c| {
-            c.borrow().clone()
-        })
+        Self::with_slot(|slot| slot.borrow().clone())
     }
     
     /// 清除当前上下文 | Clear Current Context
     /// 
-    /// 清除当前线程的上下文信息
-    /// Clear current thread's context information
+    /// 清除当前任务（`scope` 内）或当前线程的上下文信息
+    /// Clear the context of the current task (inside a `scope`) or thread
     pub fn clear() {
-        CONTEXT.with(|c| {
-            *c.borrow_mut() = None;
-        });
+        Self::with_slot(|slot| *slot.borrow_mut() = None);
+    }
+    
+    /// 访问当前生效的上下文槽：`scope` 内为任务槽，否则为线程槽
+    fn with_slot<R>(f: impl FnOnce(&RefCell<Option<SaTokenContext>>) -> R) -> R {
+        #[cfg(feature = "runtime")]
+        if TASK_CONTEXT.try_with(|_| ()).is_ok() {
+            return TASK_CONTEXT.with(f);
+        }
+        CONTEXT.with(f)
     }
 }
 
@@ -80,3 +208,48 @@ impl Default for SaTokenContext {
         Self::new()
     }
 }
+
+#[cfg(all(test, feature = "runtime"))]
+mod tests {
+    use super::*;
+
+    fn ctx_for(login_id: &str) -> SaTokenContext {
+        let mut ctx = SaTokenContext::new();
+        ctx.login_id = Some(login_id.to_string());
+        ctx
+    }
+
+    fn current_login_id() -> Option<String> {
+        SaTokenContext::get_current().and_then(|ctx| ctx.login_id)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_scope_survives_thread_hops() {
+        let tasks: Vec<_> = (0..16).map(|i| tokio::spawn(SaTokenContext::scope(ctx_for(&format!("user_{}", i)), async move {
+            for _ in 0..20 {
+                tokio::task::yield_now().await;
+                assert_eq!(current_login_id(), Some(format!("user_{}", i)));
+            }
+        }))).collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scope_is_isolated_from_thread_local() {
+        SaTokenContext::set_current(ctx_for("thread_user"));
+
+        SaTokenContext::scope(ctx_for("task_user"), async {
+            assert_eq!(current_login_id().as_deref(), Some("task_user"));
+            SaTokenContext::set_current(ctx_for("replaced"));
+            assert_eq!(current_login_id().as_deref(), Some("replaced"));
+            SaTokenContext::clear();
+            assert!(SaTokenContext::get_current().is_none());
+        }).await;
+
+        // scope 内的修改不影响线程上下文
+        assert_eq!(current_login_id().as_deref(), Some("thread_user"));
+        SaTokenContext::clear();
+    }
+}
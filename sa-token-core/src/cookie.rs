@@ -0,0 +1,195 @@
+// Author: 金书记
+//
+//! Cookie 域名校验 | Cookie domain validation
+//!
+//! 浏览器会静默丢弃 `Domain` 属性为公共后缀（如 `.com`、`co.uk`、`github.io`）或与
+//! 当前主机不匹配的 Cookie，表现为"登录成功，下一次请求却是 401"。配置
+//! `cookie_domain` 后，创建 Manager 时按公共后缀列表检查，按 [`CookieDomainCheck`]
+//! 记录警告或拒绝使用该域名。
+//!
+//! Browsers silently drop cookies whose `Domain` is a public suffix (`.com`,
+//! `co.uk`, `github.io`) or does not match the host, which shows up as "login
+//! works but the next request is 401". A configured `cookie_domain` is checked
+//! against the public suffix list when the manager is created, and either
+//! logged or rejected according to [`CookieDomainCheck`].
+//!
+//! ```rust,ignore
+//! let config = SaTokenConfig::builder()
+//!     .cookie_domain(".example.co.uk")
+//!     .cookie_domain_check(CookieDomainCheck::Deny)
+//!     .build_config();
+//!
+//! // 使用完整列表 | With the full list from publicsuffix.org
+//! let list = PublicSuffixList::parse(&std::fs::read_to_string("public_suffix_list.dat")?);
+//! validate_cookie_domain("example.co.uk", &list)?;
+//! ```
+
+use std::collections::HashSet;
+use std::net::IpAddr;
+use std::sync::OnceLock;
+use serde::{Deserialize, Serialize};
+use crate::error::{SaTokenError, SaTokenResult};
+
+/// 内置的常见多级公共后缀 | Built-in common multi-label public suffixes
+///
+/// 只是完整列表的一小部分；单级后缀（`com`、`cn` 等）由默认规则覆盖。
+/// A small subset of the full list; single-label suffixes (`com`, `cn`, ...)
+/// are covered by the default rule.
+const BUILTIN_SUFFIXES: &str = "
+// 国家/地区二级域 | Country second-level domains
+co.uk org.uk me.uk ltd.uk plc.uk net.uk ac.uk gov.uk
+com.cn net.cn org.cn gov.cn edu.cn ac.cn
+com.hk org.hk net.hk edu.hk gov.hk com.tw org.tw net.tw edu.tw
+com.au net.au org.au edu.au gov.au co.nz org.nz net.nz
+co.jp ne.jp or.jp ac.jp go.jp co.kr or.kr ne.kr
+com.sg com.my com.ph com.vn co.id co.in net.in org.in co.th
+com.br com.mx com.ar co.za com.tr
+// 托管平台 | Hosting platforms
+github.io gitlab.io pages.dev workers.dev vercel.app netlify.app web.app
+firebaseapp.com appspot.com herokuapp.com azurewebsites.net cloudfront.net
+blogspot.com onrender.com fly.dev ngrok.io ngrok-free.app
+*.compute.amazonaws.com
+";
+
+/// 配置的 Cookie 域名有问题时的处理方式 | What to do with a bad cookie domain
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CookieDomainCheck {
+    /// 不检查 | Do not check
+    Off,
+    /// 记录警告，仍使用该域名（默认）| Log a warning and keep the domain (default)
+    #[default]
+    Warn,
+    /// 记录错误并忽略该域名，Cookie 退回为仅当前主机 | Log an error and drop the domain, falling back to host-only cookies
+    Deny,
+}
+
+/// 公共后缀列表 | Public suffix list
+///
+/// 支持 publicsuffix.org 的 `public_suffix_list.dat` 格式：`//` 注释、`*.` 通配和
+/// `!` 例外规则。未命中任何规则时按默认规则 `*` 处理，即任何单级域名都是公共后缀。
+/// Reads the `public_suffix_list.dat` format from publicsuffix.org: `//`
+/// comments, `*.` wildcards and `!` exceptions. Without a matching rule the
+/// default `*` rule applies, so every single-label name is a public suffix.
+#[derive(Debug, Clone, Default)]
+pub struct PublicSuffixList {
+    rules: HashSet<String>,
+    wildcards: HashSet<String>,
+    exceptions: HashSet<String>,
+}
+
+impl PublicSuffixList {
+    /// 解析列表文本 | Parse list text
+    pub fn parse(text: &str) -> Self {
+        let mut list = Self::default();
+        for line in text.lines() {
+            let line = line.trim();
+            if line.starts_with("//") {
+                continue;
+            }
+            for rule in line.split_whitespace() {
+                let rule = rule.trim_matches('.').to_ascii_lowercase();
+                if let Some(rest) = rule.strip_prefix('!') {
+                    list.exceptions.insert(rest.to_string());
+                } else if let Some(rest) = rule.strip_prefix("*.") {
+                    list.wildcards.insert(rest.to_string());
+                } else if !rule.is_empty() {
+                    list.rules.insert(rule);
+                }
+            }
+        }
+        list
+    }
+
+    /// 内置的常见后缀子集 | Built-in subset of common suffixes
+    pub fn builtin() -> &'static Self {
+        static BUILTIN: OnceLock<PublicSuffixList> = OnceLock::new();
+        BUILTIN.get_or_init(|| Self::parse(BUILTIN_SUFFIXES))
+    }
+
+    /// 域名本身是否为公共后缀 | Whether the domain itself is a public suffix
+    ///
+    /// 忽略大小写与首尾的 `.`。
+    /// Case-insensitive; leading and trailing dots are ignored.
+    pub fn is_public_suffix(&self, domain: &str) -> bool {
+        let domain = domain.trim_matches('.').to_ascii_lowercase();
+        if domain.is_empty() || !domain.contains('.') {
+            return true;
+        }
+        if self.exceptions.contains(&domain) {
+            return false;
+        }
+        if self.rules.contains(&domain) {
+            return true;
+        }
+        domain.split_once('.').is_some_and(|(_, parent)| self.wildcards.contains(parent))
+    }
+}
+
+/// 校验 Cookie 的 `Domain` 属性 | Validate a cookie `Domain` attribute
+///
+/// 拒绝空值、IP 地址和公共后缀，错误为 `ConfigError`。
+/// Rejects empty values, IP addresses and public suffixes with a `ConfigError`.
+pub fn validate_cookie_domain(domain: &str, list: &PublicSuffixList) -> SaTokenResult<()> {
+    let host = domain.trim_start_matches('.');
+    let invalid = |reason: &str| {
+        SaTokenError::ConfigError(format!("Cookie domain '{}' {}, browsers will drop the cookie", domain, reason))
+    };
+
+    if host.is_empty() {
+        return Err(invalid("is empty"));
+    }
+    if host.parse::<IpAddr>().is_ok() {
+        return Err(invalid("is an IP address; leave it unset for host-only cookies"));
+    }
+    if list.is_public_suffix(host) {
+        return Err(invalid("is a public suffix"));
+    }
+    Ok(())
+}
+
+/// 请求主机是否在 Cookie 域名范围内 | Whether a request host falls under a cookie domain
+///
+/// 按 RFC 6265 的域名匹配：主机等于该域名，或是它的子域名。主机中的端口会被忽略。
+/// Domain matching per RFC 6265: the host equals the domain or is a
+/// subdomain of it. A port on the host is ignored.
+pub fn domain_matches(domain: &str, host: &str) -> bool {
+    let domain = domain.trim_matches('.').to_ascii_lowercase();
+    let host = host.rsplit_once(':')
+        .filter(|(_, port)| port.chars().all(|c| c.is_ascii_digit()))
+        .map_or(host, |(host, _)| host)
+        .trim_end_matches('.')
+        .to_ascii_lowercase();
+    host == domain || host.strip_suffix(&domain).is_some_and(|rest| rest.ends_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_public_suffix_rules() {
+        let list = PublicSuffixList::parse("// comment\ncom\nco.uk\n*.ck\n!www.ck\n");
+        assert!(list.is_public_suffix(".com"));
+        assert!(list.is_public_suffix("CO.UK"));
+        assert!(list.is_public_suffix("localhost"));
+        assert!(list.is_public_suffix("anything.ck"));
+        assert!(!list.is_public_suffix("www.ck"));
+        assert!(!list.is_public_suffix("example.co.uk"));
+
+        let builtin = PublicSuffixList::builtin();
+        assert!(validate_cookie_domain(".example.com", builtin).is_ok());
+        for bad in [".com", "co.uk", "github.io", "", "127.0.0.1", "eu-west-1.compute.amazonaws.com"] {
+            assert!(matches!(validate_cookie_domain(bad, builtin), Err(SaTokenError::ConfigError(_))), "{}", bad);
+        }
+        assert!(validate_cookie_domain("me.github.io", builtin).is_ok());
+    }
+
+    #[test]
+    fn test_domain_matches_host() {
+        assert!(domain_matches(".example.com", "example.com"));
+        assert!(domain_matches("example.com", "api.Example.com:8080"));
+        assert!(!domain_matches("example.com", "badexample.com"));
+        assert!(!domain_matches("app.example.com", "example.com"));
+    }
+}
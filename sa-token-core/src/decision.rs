@@ -0,0 +1,162 @@
+// Author: 金书记
+//
+//! 请求鉴权决策日志（调试模式）| Per-request Decision Log (debug mode)
+//!
+//! 开启 `debug_decision_log` 后，插件中间件会为每个请求记录鉴权流程中的每一步
+//! （token 来源、提取结果、校验结果、执行的检查），通过 `X-Sa-Token-Trace` 响应头返回，
+//! 也可以按请求 ID 在 [`DecisionLog`] 中查询，方便排查"为什么被 403"。
+//!
+//! With `debug_decision_log` enabled, plugin middlewares record every step of
+//! the auth pipeline (token source, extraction, validation, checks evaluated).
+//! The trace is returned in the `X-Sa-Token-Trace` response header and kept in
+//! the [`DecisionLog`] under the request id.
+//!
+//! ```text
+//! X-Request-Id: 5f0c...
+//! X-Sa-Token-Trace: token_source=header; validation=ok; login_id=user_1; check_permission(user:delete)=denied
+//! ```
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+
+/// 返回决策日志摘要的响应头 | Response header carrying the trace summary
+pub const DECISION_TRACE_HEADER: &str = "X-Sa-Token-Trace";
+
+/// 请求 ID 头，请求中携带时沿用，否则自动生成 | Request id header, generated when absent
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// 默认保留的请求数 | Number of requests kept by default
+pub const DEFAULT_DECISION_LOG_CAPACITY: usize = 1000;
+
+/// 决策步骤 | Decision Step
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecisionStep {
+    /// 阶段，如 `token_source`、`validation`、`check_permission(user:read)`
+    pub stage: String,
+    /// 结果，如 `header`、`ok`、`denied`
+    pub result: String,
+}
+
+/// 单个请求的决策日志 | Decision trace of one request
+///
+/// 克隆后共享同一份记录，可放入请求扩展供后续中间件追加
+/// Clones share the same steps, so it can travel in request extensions
+#[derive(Debug, Clone)]
+pub struct DecisionTrace {
+    request_id: String,
+    steps: Arc<Mutex<Vec<DecisionStep>>>,
+}
+
+impl DecisionTrace {
+    /// 创建决策日志，未提供请求 ID 时自动生成
+    pub fn new(request_id: Option<String>) -> Self {
+        Self {
+            request_id: request_id.unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string()),
+            steps: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// 请求 ID | Request id
+    pub fn request_id(&self) -> &str {
+        &self.request_id
+    }
+
+    /// 记录一个步骤 | Record a step
+    pub fn record(&self, stage: impl Into<String>, result: impl Into<String>) {
+        self.steps.lock().unwrap_or_else(|e| e.into_inner()).push(DecisionStep {
+            stage: stage.into(),
+            result: result.into(),
+        });
+    }
+
+    /// 已记录的步骤 | Recorded steps
+    pub fn steps(&self) -> Vec<DecisionStep> {
+        self.steps.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// 响应头使用的单行摘要 | One-line summary used as header value
+    pub fn header_value(&self) -> String {
+        self.steps()
+            .iter()
+            .map(|step| format!("{}={}", step.stage, step.result))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+/// 最近请求的决策日志 | Decision traces of recent requests
+///
+/// 有界保存，超出容量时丢弃最早的记录
+/// Bounded, the oldest trace is dropped when full
+pub struct DecisionLog {
+    capacity: usize,
+    entries: Mutex<VecDeque<(String, Vec<DecisionStep>)>>,
+}
+
+impl DecisionLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// 保存请求的决策日志 | Store the trace of a finished request
+    pub fn store(&self, trace: &DecisionTrace) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        while entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back((trace.request_id.clone(), trace.steps()));
+    }
+
+    /// 按请求 ID 查询 | Look up by request id
+    pub fn get(&self, request_id: &str) -> Option<Vec<DecisionStep>> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .rev()
+            .find(|(id, _)| id == request_id)
+            .map(|(_, steps)| steps.clone())
+    }
+}
+
+impl Default for DecisionLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_DECISION_LOG_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_header_value() {
+        let trace = DecisionTrace::new(Some("req-1".to_string()));
+        let shared = trace.clone();
+        trace.record("token_source", "header");
+        shared.record("check_permission(user:delete)", "denied");
+
+        assert_eq!(trace.request_id(), "req-1");
+        assert_eq!(trace.header_value(), "token_source=header; check_permission(user:delete)=denied");
+    }
+
+    #[test]
+    fn test_log_is_bounded() {
+        let log = DecisionLog::new(2);
+        for id in ["a", "b", "c"] {
+            let trace = DecisionTrace::new(Some(id.to_string()));
+            trace.record("validation", "ok");
+            log.store(&trace);
+        }
+
+        assert!(log.get("a").is_none());
+        assert_eq!(log.get("c").unwrap()[0].result, "ok");
+    }
+}
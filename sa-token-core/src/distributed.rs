@@ -174,9 +174,9 @@
 //!  │                     │                   │  返回会话数据      │
 //!  │                     │                   ├──────────────────▶│
 //!  │                     │                   │                   │
-//!  │                     │                   │  8. refresh_session()
-//!  │                     │                   │  刷新会话          │
-//!  │                     │                   │  (update last_access)
+//!  │                     │                   │  8. touch()       │
+//!  │                     │                   │  滑动续期          │
+//!  │                     │                   │  (extend TTL only)│
 //!  │                     │                   │◀──────────────────│
 //!  │                     │                   │                   │
 //!  │  9. Response        │                   │                   │
@@ -283,6 +283,17 @@
 //!         Ok(())
 //!     }
 //!     
+//!     // Slide the TTL without rewriting the payload
+//!     async fn touch_session(&self, session_id: &str, ttl: Option<Duration>)
+//!         -> Result<(), SaTokenError>
+//!     {
+//!         let mut conn = self.client.get_async_connection().await?;
+//!         let key = format!("distributed:session:{}", session_id);
+//!         let ttl = ttl.map(|t| t.as_secs() as i64).unwrap_or(-1);
+//!         let exists: bool = conn.expire(&key, ttl).await?;
+//!         if exists { Ok(()) } else { Err(SaTokenError::SessionNotFound) }
+//!     }
+//!     
 //!     // ... other methods
 //! }
 //! ```
@@ -377,9 +388,13 @@
 //! // 访问会话
 //! let session = manager.get_session(&request.session_id).await?;
 //!
-//! // 4. Refresh to keep session alive
-//! // 刷新以保持会话活跃
-//! manager.refresh_session(&session.session_id).await?;
+//! // 4. Slide the TTL to keep session alive (no payload rewrite)
+//! // 滑动延长有效期以保持会话活跃（不重写会话数据）
+//! manager.touch(&session.session_id).await?;
+//!
+//! // Or in one call: verify + get + touch
+//! // 或一步完成：验证 + 获取 + 续期
+//! let session = manager.access_session("service-b", request.secret, &request.session_id).await?;
 //! ```
 //!
 //! ### 4. Multi-Device Logout | 多设备登出
@@ -541,6 +556,26 @@ pub trait DistributedSessionStorage: Send + Sync {
     /// # Arguments | 参数
     /// * `login_id` - User login ID | 用户登录 ID
     async fn get_sessions_by_login_id(&self, login_id: &str) -> Result<Vec<DistributedSession>, SaTokenError>;
+    
+    /// Extend a session's TTL without rewriting its payload
+    /// 仅延长 Session 的 TTL，不重写 Session 数据
+    ///
+    /// The default implementation re-saves the whole session. Backends with
+    /// native expiry (Redis `EXPIRE`, an `expires_at` column) should override it.
+    /// 默认实现会重新保存整个 Session，支持原生过期的后端（Redis `EXPIRE`、
+    /// `expires_at` 字段）应当覆盖此方法。
+    ///
+    /// # Arguments | 参数
+    /// * `session_id` - Session identifier | Session 标识符
+    /// * `ttl` - New time-to-live | 新的生存时间
+    ///
+    /// # Returns | 返回值
+    /// * `Err(SessionNotFound)` - Session does not exist | Session 不存在
+    async fn touch_session(&self, session_id: &str, ttl: Option<Duration>) -> Result<(), SaTokenError> {
+        let session = self.get_session(session_id).await?
+            .ok_or(SaTokenError::SessionNotFound)?;
+        self.save_session(session, ttl).await
+    }
 }
 
 /// Distributed session manager
@@ -672,8 +707,8 @@ impl DistributedSessionManager {
             login_id,
             token,
             service_id: self.service_id.clone(),
-            create_time: Utc::now(),
-            last_access: Utc::now(),
+            create_time: crate::time::now(),
+            last_access: crate::time::now(),
             attributes: HashMap::new(),
         };
 
@@ -743,10 +778,61 @@ impl DistributedSessionManager {
     /// ```
     pub async fn refresh_session(&self, session_id: &str) -> Result<(), SaTokenError> {
         let mut session = self.get_session(session_id).await?;
-        session.last_access = Utc::now();
+        session.last_access = crate::time::now();
         self.update_session(session).await
     }
 
+    /// Extend a session's TTL without rewriting its payload (sliding expiration)
+    /// 仅延长 Session 的 TTL，不重写数据（滑动过期）
+    ///
+    /// Unlike [`refresh_session`](Self::refresh_session), `last_access` is not
+    /// updated, which keeps write volume low in busy service meshes.
+    /// 与 `refresh_session` 不同，不会更新 `last_access`，在调用频繁的服务网格中可大幅减少写入量。
+    ///
+    /// # Arguments | 参数
+    /// * `session_id` - Session identifier | Session 标识符
+    ///
+    /// # Example | 示例
+    /// ```rust,ignore
+    /// manager.touch("session-id-123").await?;
+    /// ```
+    pub async fn touch(&self, session_id: &str) -> Result<(), SaTokenError> {
+        self.storage.touch_session(session_id, Some(self.session_timeout)).await
+    }
+
+    /// Access a session from another service
+    /// 其他服务访问 Session
+    ///
+    /// Verifies the calling service, loads the session and slides its TTL
+    /// with [`touch`](Self::touch).
+    /// 验证调用方服务身份，读取 Session 并通过 `touch` 滑动延长有效期。
+    ///
+    /// # Arguments | 参数
+    /// * `service_id` - Calling service identifier | 调用方服务标识符
+    /// * `secret` - Calling service secret key | 调用方服务密钥
+    /// * `session_id` - Session identifier | Session 标识符
+    ///
+    /// # Returns | 返回值
+    /// * `Ok(DistributedSession)` - Session found | 找到 Session
+    /// * `Err(PermissionDenied)` - Invalid credentials | 凭证无效
+    /// * `Err(SessionNotFound)` - Session not found | 未找到 Session
+    ///
+    /// # Example | 示例
+    /// ```rust,ignore
+    /// let session = manager.access_session("service-b", secret, &session_id).await?;
+    /// ```
+    pub async fn access_session(
+        &self,
+        service_id: &str,
+        secret: &str,
+        session_id: &str,
+    ) -> Result<DistributedSession, SaTokenError> {
+        self.verify_service(service_id, secret).await?;
+        let session = self.get_session(session_id).await?;
+        self.touch(session_id).await?;
+        Ok(session)
+    }
+
     /// Set a session attribute
     /// 设置 Session 属性
     ///
@@ -767,7 +853,7 @@ impl DistributedSessionManager {
     ) -> Result<(), SaTokenError> {
         let mut session = self.get_session(session_id).await?;
         session.attributes.insert(key, value);
-        session.last_access = Utc::now();
+        session.last_access = crate::time::now();
         self.update_session(session).await
     }
 
@@ -815,7 +901,7 @@ impl DistributedSessionManager {
     ) -> Result<(), SaTokenError> {
         let mut session = self.get_session(session_id).await?;
         session.attributes.remove(key);
-        session.last_access = Utc::now();
+        session.last_access = crate::time::now();
         self.update_session(session).await
     }
 
@@ -1010,6 +1096,17 @@ impl DistributedSessionStorage for InMemoryDistributedStorage {
         
         Ok(result)
     }
+
+    /// TTL is ignored in memory storage, so touching only checks existence
+    /// 内存存储忽略 TTL，touch 只检查 Session 是否存在
+    async fn touch_session(&self, session_id: &str, _ttl: Option<Duration>) -> Result<(), SaTokenError> {
+        let sessions = self.sessions.read().await;
+        if sessions.contains_key(session_id) {
+            Ok(())
+        } else {
+            Err(SaTokenError::SessionNotFound)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1071,7 +1168,7 @@ mod tests {
             service_id: "service2".to_string(),
             service_name: "Service 2".to_string(),
             secret_key: "secret123".to_string(),
-            created_at: Utc::now(),
+            created_at: crate::time::now(),
             permissions: vec!["read".to_string(), "write".to_string()],
         };
 
@@ -1084,6 +1181,83 @@ mod tests {
         assert!(result.is_err());
     }
 
+    /// Counts full writes and TTL-only touches | 统计整体写入与续期次数
+    struct CountingStorage {
+        inner: InMemoryDistributedStorage,
+        saves: std::sync::atomic::AtomicUsize,
+        touches: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl DistributedSessionStorage for CountingStorage {
+        async fn save_session(&self, session: DistributedSession, ttl: Option<Duration>) -> Result<(), SaTokenError> {
+            self.saves.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            self.inner.save_session(session, ttl).await
+        }
+
+        async fn get_session(&self, session_id: &str) -> Result<Option<DistributedSession>, SaTokenError> {
+            self.inner.get_session(session_id).await
+        }
+
+        async fn delete_session(&self, session_id: &str) -> Result<(), SaTokenError> {
+            self.inner.delete_session(session_id).await
+        }
+
+        async fn get_sessions_by_login_id(&self, login_id: &str) -> Result<Vec<DistributedSession>, SaTokenError> {
+            self.inner.get_sessions_by_login_id(login_id).await
+        }
+
+        async fn touch_session(&self, session_id: &str, ttl: Option<Duration>) -> Result<(), SaTokenError> {
+            self.touches.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            self.inner.touch_session(session_id, ttl).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_access_session_touches_without_rewrite() {
+        use std::sync::atomic::Ordering;
+
+        let storage = Arc::new(CountingStorage {
+            inner: InMemoryDistributedStorage::new(),
+            saves: Default::default(),
+            touches: Default::default(),
+        });
+        let manager = DistributedSessionManager::new(
+            storage.clone(),
+            "service1".to_string(),
+            Duration::from_secs(3600),
+        );
+        manager.register_service(ServiceCredential {
+            service_id: "service2".to_string(),
+            service_name: "Service 2".to_string(),
+            secret_key: "secret123".to_string(),
+            created_at: crate::time::now(),
+            permissions: vec![],
+        }).await;
+
+        let session = manager.create_session("user4".to_string(), "token4".to_string()).await.unwrap();
+        for _ in 0..3 {
+            let accessed = manager.access_session("service2", "secret123", &session.session_id).await.unwrap();
+            assert_eq!(accessed.login_id, "user4");
+        }
+
+        assert_eq!(storage.saves.load(Ordering::SeqCst), 1);
+        assert_eq!(storage.touches.load(Ordering::SeqCst), 3);
+        assert!(manager.access_session("service2", "wrong", &session.session_id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_touch_missing_session() {
+        let storage = Arc::new(InMemoryDistributedStorage::new());
+        let manager = DistributedSessionManager::new(
+            storage,
+            "service1".to_string(),
+            Duration::from_secs(3600),
+        );
+
+        assert!(matches!(manager.touch("missing").await, Err(SaTokenError::SessionNotFound)));
+    }
+
     #[tokio::test]
     async fn test_delete_all_sessions() {
         let storage = Arc::new(InMemoryDistributedStorage::new());
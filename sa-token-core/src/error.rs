@@ -25,6 +25,15 @@ pub enum SaTokenError {
     #[error("Token is inactive")]
     TokenInactive,
     
+    #[error("Token is frozen after being idle longer than active_timeout")]
+    TokenFreeze,
+    
+    #[error("Password has expired, only password change is allowed")]
+    PasswordExpired,
+    
+    #[error("Session is no longer valid: {0}")]
+    SessionInvalidated(String),
+    
     // ============ Authorization Errors | 授权错误 ============
     #[error("Permission denied")]
     PermissionDenied,
@@ -35,6 +44,24 @@ pub enum SaTokenError {
     #[error("Role denied: missing role '{0}'")]
     RoleDenied(String),
     
+    #[error("Permission denied: missing permissions [{}]", .0.join(", "))]
+    PermissionsDenied(Vec<String>),
+    
+    #[error("Role denied: missing roles [{}]", .0.join(", "))]
+    RolesDenied(Vec<String>),
+    
+    #[error("Invalid authorization name: {0}")]
+    InvalidAuthzName(String),
+    
+    #[error("Request payload exceeds {0} bytes")]
+    PayloadTooLarge(usize),
+    
+    #[error("Invalid request payload: {0}")]
+    InvalidPayload(String),
+    
+    #[error("{0}")]
+    ReadOnlyMode(String),
+    
     // ============ Account Status Errors | 账户状态错误 ============
     #[error("Account is banned until {0}")]
     AccountBanned(String),
@@ -42,10 +69,28 @@ pub enum SaTokenError {
     #[error("Account is kicked out")]
     AccountKickedOut,
     
+    /// 被 `kick_out_with_reason` 踢下线的 token，携带踢人原因 | Token kicked out with a reason
+    #[error("Kicked out: {0}")]
+    KickedOut(String),
+    
+    /// 不允许并发登录时被新登录顶替的 token | Token replaced by a newer login
+    #[error("Token has been replaced by a newer login")]
+    Replaced,
+    
+    /// 开启 `token_bind_ip` 时，请求 IP 与登录 IP 不一致 | Request IP differs from the login IP under `token_bind_ip`
+    #[error("Request IP does not match the IP the token was issued to")]
+    IpMismatch,
+    
     // ============ Session Errors | Session 错误 ============
     #[error("Session not found")]
     SessionNotFound,
     
+    #[error("Session quota exceeded: {0}")]
+    SessionQuotaExceeded(String),
+    
+    #[error("Invalid session patch: {0}")]
+    InvalidSessionPatch(String),
+    
     // ============ Nonce Errors | Nonce 错误 ============
     #[error("Nonce has been used, possible replay attack detected")]
     NonceAlreadyUsed,
@@ -56,6 +101,24 @@ pub enum SaTokenError {
     #[error("Nonce timestamp is invalid or expired")]
     InvalidNonceTimestamp,
     
+    // ============ Sign Errors | 签名错误 ============
+    #[error("Invalid request signature: {0}")]
+    InvalidSign(String),
+    
+    #[error("Missing or invalid Same-Token")]
+    InvalidSameToken,
+    
+    // ============ Alternative Auth Errors | 其他认证方式错误 ============
+    #[error("HTTP Basic authentication failed")]
+    HttpBasicAuthFailed,
+    
+    #[error("Missing or invalid API key")]
+    InvalidApiKey,
+    
+    // ============ Idempotency Errors | 幂等错误 ============
+    #[error("A request with idempotency key '{0}' is already in progress")]
+    IdempotencyKeyInProgress(String),
+    
     // ============ Refresh Token Errors | 刷新令牌错误 ============
     #[error("Refresh token not found or expired")]
     RefreshTokenNotFound,
@@ -69,6 +132,14 @@ pub enum SaTokenError {
     #[error("Invalid expire time format in refresh token")]
     RefreshTokenInvalidExpireTime,
     
+    /// 滑动有效期或家族最长寿命已到，需要重新登录 | Expired, re-login required
+    #[error("Refresh token expired, re-login required")]
+    RefreshTokenExpired,
+    
+    /// 已被轮换，应使用新的 refresh token 重试 | Rotated, retry with the new refresh token
+    #[error("Refresh token has been rotated, retry with the new refresh token")]
+    RefreshTokenRotated,
+    
     // ============ Token Validation Errors | Token 验证错误 ============
     #[error("Token is empty")]
     TokenEmpty,
@@ -76,6 +147,10 @@ pub enum SaTokenError {
     #[error("Token is too short")]
     TokenTooShort,
     
+    /// 连续生成的 token 都与已有 token 重复 | Every generated token collided with an existing one
+    #[error("Failed to generate a unique token after {0} attempts")]
+    TokenGenerateFailed(i32),
+    
     #[error("Login ID is not a valid number")]
     LoginIdNotNumber,
     
@@ -92,6 +167,9 @@ pub enum SaTokenError {
     #[error("Redirect URI mismatch")]
     OAuth2RedirectUriMismatch,
     
+    #[error("PKCE code verifier does not match the code challenge")]
+    OAuth2PkceMismatch,
+    
     #[error("Authorization code not found or expired")]
     OAuth2CodeNotFound,
     
@@ -107,6 +185,9 @@ pub enum SaTokenError {
     #[error("Invalid scope data")]
     OAuth2InvalidScope,
     
+    #[error("Grant type not allowed for this client")]
+    OAuth2UnsupportedGrantType,
+    
     // ============ SSO Errors | SSO 单点登录错误 ============
     #[error("SSO ticket not found or invalid")]
     InvalidTicket,
@@ -120,6 +201,9 @@ pub enum SaTokenError {
     #[error("SSO session not found")]
     SsoSessionNotFound,
     
+    #[error("SSO discovery failed: {0}")]
+    SsoDiscoveryFailed(String),
+    
     // ============ System Errors | 系统错误 ============
     #[error("Storage error: {0}")]
     StorageError(String),
@@ -160,10 +244,38 @@ impl SaTokenError {
             | Self::TokenNotFound 
             | Self::TokenExpired 
             | Self::TokenInactive 
+            | Self::TokenFreeze
+            | Self::PasswordExpired
+            | Self::SessionInvalidated(_)
             | Self::InvalidToken(_)
+            | Self::KickedOut(_)
+            | Self::Replaced
+            | Self::IpMismatch
         )
     }
     
+    /// Copy an authentication error
+    /// 
+    /// Returns a copy for the variants `is_auth_error` accepts and `None` for
+    /// the rest, which are not `Clone`. Lets the plugins carry a token
+    /// validation error in `SaTokenContext` until a route check reports it.
+    pub fn clone_auth_error(&self) -> Option<Self> {
+        Some(match self {
+            Self::NotLogin => Self::NotLogin,
+            Self::TokenNotFound => Self::TokenNotFound,
+            Self::TokenExpired => Self::TokenExpired,
+            Self::TokenInactive => Self::TokenInactive,
+            Self::TokenFreeze => Self::TokenFreeze,
+            Self::PasswordExpired => Self::PasswordExpired,
+            Self::SessionInvalidated(reason) => Self::SessionInvalidated(reason.clone()),
+            Self::InvalidToken(reason) => Self::InvalidToken(reason.clone()),
+            Self::KickedOut(reason) => Self::KickedOut(reason.clone()),
+            Self::Replaced => Self::Replaced,
+            Self::IpMismatch => Self::IpMismatch,
+            _ => return None,
+        })
+    }
+    
     /// Check if the error is an authorization error
     /// 
     /// Returns `true` for errors related to permissions or roles
@@ -173,8 +285,29 @@ impl SaTokenError {
             Self::PermissionDenied 
             | Self::PermissionDeniedDetail(_) 
             | Self::RoleDenied(_)
+            | Self::PermissionsDenied(_)
+            | Self::RolesDenied(_)
         )
     }
+    
+    /// Get the missing permissions/roles carried by an authorization error
+    /// 
+    /// Returns an empty list for errors that don't carry any detail, so callers
+    /// can always render the result (e.g. as a `missing` field in a JSON body).
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// let err = SaTokenError::PermissionsDenied(vec!["user:read".into(), "user:write".into()]);
+    /// assert_eq!(err.missing(), vec!["user:read", "user:write"]);
+    /// ```
+    pub fn missing(&self) -> Vec<String> {
+        match self {
+            Self::PermissionDeniedDetail(item) | Self::RoleDenied(item) => vec![item.clone()],
+            Self::PermissionsDenied(items) | Self::RolesDenied(items) => items.clone(),
+            _ => Vec::new(),
+        }
+    }
 }
 
 /// Application-level error messages
@@ -205,4 +338,7 @@ pub mod messages {
     
     /// Role required
     pub const ROLE_REQUIRED: &str = "Role required";
+    
+    /// Read-only mode
+    pub const READ_ONLY_MODE: &str = "Read-only mode";
 }
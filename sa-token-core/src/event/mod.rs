@@ -125,7 +125,8 @@
 //!               ├─ KickOut ────▶ on_kick_out(...)
 //!               ├─ RenewTimeout ▶ on_renew_timeout(...)
 //!               ├─ Replaced ───▶ on_replaced(...)
-//!               └─ Banned ─────▶ on_banned(...)
+//!               ├─ Banned ─────▶ on_banned(...)
+//!               └─ Unbanned ───▶ on_unbanned(...)
 //! 
 //! Notes | 注意：
 //! - Listeners execute in registration order
@@ -187,7 +188,8 @@
 //! ### Performance Considerations | 性能考虑
 //! 
 //! 1. **Async Execution | 异步执行**: All listener methods are async, but execute sequentially
-//!    所有监听器方法都是异步的，但按顺序执行
+//!    所有监听器方法都是异步的，但按顺序执行；`EventDispatchMode::Spawned` / `Queue`
+//!    可把监听器移出调用路径 | `EventDispatchMode::Spawned` / `Queue` move them off the calling path
 //! 2. **Read-Write Lock | 读写锁**: Multiple events can be published concurrently (read lock), registration requires exclusive access (write lock)
 //!    多个事件可以并发发布（读锁），注册需要独占（写锁）
 //! 3. **Zero-Copy | 零拷贝**: Event objects are passed by reference, avoiding unnecessary cloning
@@ -239,7 +241,9 @@
 //! ```
 
 use async_trait::async_trait;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::sync::RwLock;
 use chrono::{DateTime, Utc};
 use serde::{Serialize, Deserialize};
@@ -259,6 +263,24 @@ pub enum SaTokenEventType {
     Replaced,
     /// 被封禁事件
     Banned,
+    /// 解除封禁事件
+    Unbanned,
+    /// OAuth2 令牌被撤销事件（token 字段为被撤销的令牌）
+    OAuth2TokenRevoked,
+    /// 权限或角色变更事件（使登录时预取的权限失效）
+    PermissionChanged,
+    /// 认证失败事件（token 无效、过期等，失败原因在 extra.reason 中）
+    AuthFailed,
+    /// 配置热更新事件（变化的字段名在 extra.changed 中）
+    ConfigChanged,
+    /// Token 换发事件（token 为新 token，旧 token 在 extra.old_token 中）
+    TokenRenewed,
+    /// 权限或角色校验被拒绝事件（extra 中为 kind、name 与 route）
+    PermissionDenied,
+    /// 存储不可用事件（由 `StorageSupervisor` 发布，错误信息在 extra.error 中）
+    StorageDown,
+    /// 存储恢复可用事件（由 `StorageSupervisor` 发布）
+    StorageRecovered,
 }
 
 /// 事件数据
@@ -276,6 +298,18 @@ pub struct SaTokenEvent {
     pub timestamp: DateTime<Utc>,
     /// 额外数据（用于扩展）
     pub extra: Option<serde_json::Value>,
+    /// 设备标识，设备级踢人等操作只作用于该设备的 token
+    #[serde(default)]
+    pub device: Option<String>,
+    /// 请求来源 IP（如可获取）
+    #[serde(default)]
+    pub ip: Option<String>,
+    /// 请求的 User-Agent（如可获取）
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    /// 转发该事件的远程实例 ID；本实例产生的事件为 None（见 `RedisEventBridge`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
 }
 
 impl SaTokenEvent {
@@ -286,8 +320,12 @@ impl SaTokenEvent {
             login_id: login_id.into(),
             token: token.into(),
             login_type: "default".to_string(),
-            timestamp: Utc::now(),
+            timestamp: crate::time::now(),
             extra: None,
+            device: None,
+            ip: None,
+            user_agent: None,
+            origin: None,
         }
     }
 
@@ -298,8 +336,12 @@ impl SaTokenEvent {
             login_id: login_id.into(),
             token: token.into(),
             login_type: "default".to_string(),
-            timestamp: Utc::now(),
+            timestamp: crate::time::now(),
             extra: None,
+            device: None,
+            ip: None,
+            user_agent: None,
+            origin: None,
         }
     }
 
@@ -310,8 +352,12 @@ impl SaTokenEvent {
             login_id: login_id.into(),
             token: token.into(),
             login_type: "default".to_string(),
-            timestamp: Utc::now(),
+            timestamp: crate::time::now(),
             extra: None,
+            device: None,
+            ip: None,
+            user_agent: None,
+            origin: None,
         }
     }
 
@@ -322,8 +368,12 @@ impl SaTokenEvent {
             login_id: login_id.into(),
             token: token.into(),
             login_type: "default".to_string(),
-            timestamp: Utc::now(),
+            timestamp: crate::time::now(),
             extra: None,
+            device: None,
+            ip: None,
+            user_agent: None,
+            origin: None,
         }
     }
 
@@ -334,8 +384,12 @@ impl SaTokenEvent {
             login_id: login_id.into(),
             token: token.into(),
             login_type: "default".to_string(),
-            timestamp: Utc::now(),
+            timestamp: crate::time::now(),
             extra: None,
+            device: None,
+            ip: None,
+            user_agent: None,
+            origin: None,
         }
     }
 
@@ -346,8 +400,161 @@ impl SaTokenEvent {
             login_id: login_id.into(),
             token: String::new(),
             login_type: "default".to_string(),
-            timestamp: Utc::now(),
+            timestamp: crate::time::now(),
             extra: None,
+            device: None,
+            ip: None,
+            user_agent: None,
+            origin: None,
+        }
+    }
+    
+    /// 创建解除封禁事件
+    pub fn unbanned(login_id: impl Into<String>) -> Self {
+        Self {
+            event_type: SaTokenEventType::Unbanned,
+            login_id: login_id.into(),
+            token: String::new(),
+            login_type: "default".to_string(),
+            timestamp: crate::time::now(),
+            extra: None,
+            device: None,
+            ip: None,
+            user_agent: None,
+            origin: None,
+        }
+    }
+    
+    /// 创建 OAuth2 令牌撤销事件
+    pub fn oauth2_token_revoked(token: impl Into<String>) -> Self {
+        Self {
+            event_type: SaTokenEventType::OAuth2TokenRevoked,
+            login_id: String::new(),
+            token: token.into(),
+            login_type: "oauth2".to_string(),
+            timestamp: crate::time::now(),
+            extra: None,
+            device: None,
+            ip: None,
+            user_agent: None,
+            origin: None,
+        }
+    }
+
+    /// 创建权限变更事件
+    pub fn permission_changed(login_id: impl Into<String>) -> Self {
+        Self {
+            event_type: SaTokenEventType::PermissionChanged,
+            login_id: login_id.into(),
+            token: String::new(),
+            login_type: "default".to_string(),
+            timestamp: crate::time::now(),
+            extra: None,
+            device: None,
+            ip: None,
+            user_agent: None,
+            origin: None,
+        }
+    }
+
+    /// 创建认证失败事件
+    pub fn auth_failed(token: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            event_type: SaTokenEventType::AuthFailed,
+            login_id: String::new(),
+            token: token.into(),
+            login_type: "default".to_string(),
+            timestamp: crate::time::now(),
+            extra: Some(serde_json::json!({ "reason": reason.into() })),
+            device: None,
+            ip: None,
+            user_agent: None,
+            origin: None,
+        }
+    }
+
+    /// 创建配置热更新事件
+    pub fn config_changed(changed: Vec<String>) -> Self {
+        Self {
+            event_type: SaTokenEventType::ConfigChanged,
+            login_id: String::new(),
+            token: String::new(),
+            login_type: "default".to_string(),
+            timestamp: crate::time::now(),
+            extra: Some(serde_json::json!({ "changed": changed })),
+            device: None,
+            ip: None,
+            user_agent: None,
+            origin: None,
+        }
+    }
+
+    /// 创建 token 换发事件
+    pub fn token_renewed(login_id: impl Into<String>, token: impl Into<String>, old_token: impl Into<String>) -> Self {
+        Self {
+            event_type: SaTokenEventType::TokenRenewed,
+            login_id: login_id.into(),
+            token: token.into(),
+            login_type: "default".to_string(),
+            timestamp: crate::time::now(),
+            extra: Some(serde_json::json!({ "old_token": old_token.into() })),
+            device: None,
+            ip: None,
+            user_agent: None,
+            origin: None,
+        }
+    }
+
+    /// 创建权限/角色校验被拒绝事件
+    pub fn permission_denied(
+        login_id: impl Into<String>,
+        kind: crate::permission::AuthzKind,
+        name: impl Into<String>,
+        route: Option<&str>,
+    ) -> Self {
+        Self {
+            event_type: SaTokenEventType::PermissionDenied,
+            login_id: login_id.into(),
+            token: String::new(),
+            login_type: "default".to_string(),
+            timestamp: crate::time::now(),
+            extra: Some(serde_json::json!({ "kind": kind.to_string(), "name": name.into(), "route": route })),
+            device: None,
+            ip: None,
+            user_agent: None,
+            origin: None,
+        }
+    }
+
+    /// 创建存储不可用事件
+    pub fn storage_down(error: impl Into<String>) -> Self {
+        Self {
+            event_type: SaTokenEventType::StorageDown,
+            login_id: String::new(),
+            token: String::new(),
+            login_type: "default".to_string(),
+            timestamp: crate::time::now(),
+            extra: Some(serde_json::json!({ "error": error.into() })),
+            device: None,
+            ip: None,
+            user_agent: None,
+            origin: None,
+        }
+    }
+
+    /// 创建存储恢复可用事件
+    pub fn storage_recovered() -> Self {
+        Self {
+            event_type: SaTokenEventType::StorageRecovered,
+            login_id: String::new(),
+            token: String::new(),
+            login_type: "default".to_string(),
+            timestamp: crate::time::now(),
+            extra: None,
+            device: None,
+            ip: None,
+            user_agent: None,
+            origin: None,
         }
     }
 
@@ -362,6 +569,35 @@ impl SaTokenEvent {
         self.extra = Some(extra);
         self
     }
+
+    /// 设置 token
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = token.into();
+        self
+    }
+
+    /// 设置设备标识
+    pub fn with_device(mut self, device: impl Into<String>) -> Self {
+        self.device = Some(device.into());
+        self
+    }
+
+    /// 设置请求来源 IP
+    pub fn with_ip(mut self, ip: impl Into<String>) -> Self {
+        self.ip = Some(ip.into());
+        self
+    }
+
+    /// 设置 User-Agent
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// 是否为其他实例经事件桥转发来的事件
+    pub fn is_remote(&self) -> bool {
+        self.origin.is_some()
+    }
 }
 
 /// 事件监听器 trait | Event Listener Trait
@@ -459,6 +695,30 @@ pub trait SaTokenListener: Send + Sync {
         let _ = (login_id, login_type);
     }
 
+    /// 解除封禁事件 | Unbanned Event
+    /// 
+    /// 当账号被手动解封时触发（到期自动解封不会触发）
+    /// Triggered when an account is untied manually (not when the ban expires)
+    /// 
+    /// # 参数 | Parameters
+    /// - `login_id`: 登录 ID | Login ID
+    /// - `login_type`: 登录类型 | Login type
+    async fn on_unbanned(&self, login_id: &str, login_type: &str) {
+        let _ = (login_id, login_type);
+    }
+
+    /// 认证失败事件 | Auth Failed Event
+    /// 
+    /// 当请求携带的 token 无效、过期等导致认证失败时触发
+    /// Triggered when the token sent with a request is invalid, expired, etc.
+    /// 
+    /// # 参数 | Parameters
+    /// - `reason`: 失败原因 | Why authentication failed
+    /// - `ip`: 请求来源 IP（如可获取）| Client IP, if known
+    async fn on_auth_failed(&self, reason: &str, ip: Option<&str>) {
+        let _ = (reason, ip);
+    }
+
     /// 通用事件处理（所有事件都会触发此方法）
     /// Generic Event Handler (triggered by all events)
     /// 
@@ -469,26 +729,156 @@ pub trait SaTokenListener: Send + Sync {
     }
 }
 
+/// 默认的事件队列容量 | Default capacity of the event queue
+pub const DEFAULT_EVENT_QUEUE_CAPACITY: usize = 1024;
+
+fn default_event_queue_capacity() -> usize {
+    DEFAULT_EVENT_QUEUE_CAPACITY
+}
+
+/// 事件分发方式 | How the bus runs listeners
+///
+/// ```toml
+/// [event_dispatch]
+/// mode = "queue"
+/// capacity = 4096
+/// overflow = "drop_newest"
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum EventDispatchMode {
+    /// 在 `publish` 中依次执行，全部完成后返回（默认）
+    ///
+    /// Listeners run inside `publish`, which returns once all of them finished (default).
+    #[default]
+    Sync,
+
+    /// 每个事件在独立的 tokio 任务中执行，`publish` 立即返回；事件之间不保证顺序
+    ///
+    /// Each event runs in its own tokio task and `publish` returns at once;
+    /// events are not ordered relative to each other.
+    Spawned,
+
+    /// 事件进入有界队列，由单个后台任务按发布顺序执行
+    ///
+    /// Events go through a bounded queue drained by one background task, in publish order.
+    Queue {
+        /// 队列容量 | Queue capacity
+        #[serde(default = "default_event_queue_capacity")]
+        capacity: usize,
+        /// 队列满时的处理方式 | What to do when the queue is full
+        #[serde(default)]
+        overflow: QueueOverflow,
+    },
+}
+
+impl EventDispatchMode {
+    /// 默认容量、队列满时等待的队列模式 | Queue mode with the default capacity that waits when full
+    pub fn queue() -> Self {
+        Self::Queue { capacity: DEFAULT_EVENT_QUEUE_CAPACITY, overflow: QueueOverflow::Block }
+    }
+}
+
+/// 事件队列满时的处理方式 | Overflow policy of the event queue
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueOverflow {
+    /// `publish` 等待队列腾出空间（背压）| `publish` waits for room (back pressure)
+    #[default]
+    Block,
+    /// 丢弃新事件并计数 | Drop the new event and count it
+    DropNewest,
+}
+
+/// 队列中的任务 | Item of the event queue
+#[cfg(feature = "runtime")]
+enum Queued {
+    Event(Box<SaTokenEvent>),
+    Flush(tokio::sync::oneshot::Sender<()>),
+}
+
+/// 当前的分发方式；队列模式下持有队列发送端，首次发布时启动后台任务
+struct Dispatcher {
+    mode: EventDispatchMode,
+    #[cfg(feature = "runtime")]
+    queue: Option<tokio::sync::mpsc::Sender<Queued>>,
+}
+
+type ListenerList = Arc<RwLock<Vec<Arc<dyn SaTokenListener>>>>;
+
 /// 事件总线 - 管理所有监听器并分发事件
+///
+/// 分发方式见 [`EventDispatchMode`]。`Spawned` / `Queue` 需要 tokio 运行时（`runtime` feature），
+/// 否则按 `Sync` 执行。用 `register_inline` 注册的监听器无论哪种方式都在 `publish` 中执行，
+/// 适合必须在调用返回前生效的逻辑（如缓存失效）。
+///
+/// See [`EventDispatchMode`] for how listeners run; `Spawned` and `Queue`
+/// need the tokio runtime (`runtime` feature) and fall back to `Sync`
+/// otherwise. Listeners added with `register_inline` always run inside
+/// `publish`, for work that must be done before the call returns.
 #[derive(Clone)]
 pub struct SaTokenEventBus {
-    listeners: Arc<RwLock<Vec<Arc<dyn SaTokenListener>>>>,
+    listeners: ListenerList,
+    inline_listeners: ListenerList,
+    dispatcher: Arc<RwLock<Dispatcher>>,
+    dropped: Arc<AtomicU64>,
 }
 
 impl SaTokenEventBus {
     /// 创建新的事件总线
     pub fn new() -> Self {
+        Self::with_dispatch_mode(EventDispatchMode::Sync)
+    }
+
+    /// 以指定分发方式创建 | Create with a dispatch mode
+    pub fn with_dispatch_mode(mode: EventDispatchMode) -> Self {
         Self {
             listeners: Arc::new(RwLock::new(Vec::new())),
+            inline_listeners: Arc::new(RwLock::new(Vec::new())),
+            dispatcher: Arc::new(RwLock::new(Dispatcher {
+                mode,
+                #[cfg(feature = "runtime")]
+                queue: None,
+            })),
+            dropped: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// 切换分发方式，对之后发布的事件生效；旧队列中的事件仍会执行完
+    ///
+    /// Switch the dispatch mode for later events; events already queued still run.
+    pub fn set_dispatch_mode(&self, mode: EventDispatchMode) {
+        let mut dispatcher = self.dispatcher.write().unwrap();
+        dispatcher.mode = mode;
+        #[cfg(feature = "runtime")]
+        {
+            dispatcher.queue = None;
         }
     }
 
+    /// 当前分发方式 | Current dispatch mode
+    pub fn dispatch_mode(&self) -> EventDispatchMode {
+        self.dispatcher.read().unwrap().mode
+    }
+
+    /// 队列满而被丢弃的事件数 | Events dropped because the queue was full
+    pub fn dropped_events(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
     /// 注册监听器
     /// Register a listener
     pub fn register(&self, listener: Arc<dyn SaTokenListener>) {
         let mut listeners = self.listeners.write().unwrap();
         listeners.push(listener);
     }
+
+    /// 注册始终在 `publish` 中执行的监听器，先于普通监听器执行
+    /// Register a listener that always runs inside `publish`, before the other listeners
+    pub fn register_inline(&self, listener: Arc<dyn SaTokenListener>) {
+        let mut listeners = self.inline_listeners.write().unwrap();
+        listeners.push(listener);
+    }
     
     /// 异步注册监听器（为了保持 API 兼容性）
     /// Register a listener asynchronously (for API compatibility)
@@ -499,6 +889,7 @@ impl SaTokenEventBus {
     /// 移除所有监听器
     /// Clear all listeners
     pub fn clear(&self) {
+        self.inline_listeners.write().unwrap().clear();
         let mut listeners = self.listeners.write().unwrap();
         listeners.clear();
     }
@@ -506,45 +897,150 @@ impl SaTokenEventBus {
     /// 获取监听器数量
     /// Get listener count
     pub fn listener_count(&self) -> usize {
+        let inline = self.inline_listeners.read().unwrap().len();
         let listeners = self.listeners.read().unwrap();
-        listeners.len()
+        inline + listeners.len()
     }
 
     /// 发布事件
     /// Publish an event to all listeners
-    pub async fn publish(&self, event: SaTokenEvent) {
+    /// 
+    /// 在请求上下文中发布且未设置来源的事件，补上上下文中的 IP 与 User-Agent。
+    /// Events published within a request context get the context's IP and
+    /// User-Agent when they carry none.
+    pub async fn publish(&self, mut event: SaTokenEvent) {
+        if let Some(ctx) = crate::context::SaTokenContext::get_current() {
+            event.ip = event.ip.or(ctx.client_ip);
+            event.user_agent = event.user_agent.or(ctx.user_agent);
+        }
+        
         // 克隆监听器列表以避免持有锁时异步等待
         // Clone listener list to avoid holding lock during async operations
-        let listeners = {
-            let guard = self.listeners.read().unwrap();
-            guard.clone()
-        };
-        
-        for listener in listeners.iter() {
-            // 触发通用事件处理
-            listener.on_event(&event).await;
-            
-            // 根据事件类型触发特定处理
-            match event.event_type {
-                SaTokenEventType::Login => {
-                    listener.on_login(&event.login_id, &event.token, &event.login_type).await;
-                }
-                SaTokenEventType::Logout => {
-                    listener.on_logout(&event.login_id, &event.token, &event.login_type).await;
-                }
-                SaTokenEventType::KickOut => {
-                    listener.on_kick_out(&event.login_id, &event.token, &event.login_type).await;
-                }
-                SaTokenEventType::RenewTimeout => {
-                    listener.on_renew_timeout(&event.login_id, &event.token, &event.login_type).await;
+        let inline = self.inline_listeners.read().unwrap().clone();
+        dispatch(&inline, &event).await;
+
+        #[cfg(feature = "runtime")]
+        match self.dispatch_mode() {
+            EventDispatchMode::Sync => {}
+            EventDispatchMode::Spawned => {
+                let listeners = self.listeners.read().unwrap().clone();
+                tokio::spawn(async move { dispatch(&listeners, &event).await });
+                return;
+            }
+            EventDispatchMode::Queue { overflow, .. } => {
+                let sender = self.queue_sender();
+                match overflow {
+                    QueueOverflow::Block => {
+                        let _ = sender.send(Queued::Event(Box::new(event))).await;
+                    }
+                    QueueOverflow::DropNewest => {
+                        if sender.try_send(Queued::Event(Box::new(event))).is_err() {
+                            let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                            tracing::warn!(dropped, "Sa-Token: 事件队列已满，丢弃事件");
+                        }
+                    }
                 }
-                SaTokenEventType::Replaced => {
-                    listener.on_replaced(&event.login_id, &event.token, &event.login_type).await;
+                return;
+            }
+        }
+
+        let listeners = self.listeners.read().unwrap().clone();
+        dispatch(&listeners, &event).await;
+    }
+
+    /// 等待此前进入队列的事件执行完毕；非队列模式立即返回
+    ///
+    /// Wait until the events queued so far have been handled; returns at once
+    /// in the other modes.
+    pub async fn flush(&self) {
+        #[cfg(feature = "runtime")]
+        {
+            let sender = self.dispatcher.read().unwrap().queue.clone();
+            if let Some(sender) = sender {
+                let (done, wait) = tokio::sync::oneshot::channel();
+                if sender.send(Queued::Flush(done)).await.is_ok() {
+                    let _ = wait.await;
                 }
-                SaTokenEventType::Banned => {
-                    listener.on_banned(&event.login_id, &event.login_type).await;
+            }
+        }
+    }
+
+    /// 队列发送端，首次使用时启动按顺序执行的后台任务
+    #[cfg(feature = "runtime")]
+    fn queue_sender(&self) -> tokio::sync::mpsc::Sender<Queued> {
+        let mut dispatcher = self.dispatcher.write().unwrap();
+        let capacity = match dispatcher.mode {
+            EventDispatchMode::Queue { capacity, .. } => capacity.max(1),
+            _ => DEFAULT_EVENT_QUEUE_CAPACITY,
+        };
+        if let Some(sender) = &dispatcher.queue {
+            return sender.clone();
+        }
+
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(capacity);
+        let listeners = self.listeners.clone();
+        tokio::spawn(async move {
+            while let Some(item) = receiver.recv().await {
+                match item {
+                    Queued::Event(event) => {
+                        let listeners = listeners.read().unwrap().clone();
+                        dispatch(&listeners, &event).await;
+                    }
+                    Queued::Flush(done) => {
+                        let _ = done.send(());
+                    }
                 }
             }
+        });
+        dispatcher.queue = Some(sender.clone());
+        sender
+    }
+}
+
+/// 依次调用监听器的通用回调与对应事件类型的回调
+async fn dispatch(listeners: &[Arc<dyn SaTokenListener>], event: &SaTokenEvent) {
+    for listener in listeners {
+        // 触发通用事件处理
+        listener.on_event(event).await;
+        
+        // 根据事件类型触发特定处理
+        match event.event_type {
+            SaTokenEventType::Login => {
+                listener.on_login(&event.login_id, &event.token, &event.login_type).await;
+            }
+            SaTokenEventType::Logout => {
+                listener.on_logout(&event.login_id, &event.token, &event.login_type).await;
+            }
+            SaTokenEventType::KickOut => {
+                listener.on_kick_out(&event.login_id, &event.token, &event.login_type).await;
+            }
+            SaTokenEventType::RenewTimeout => {
+                listener.on_renew_timeout(&event.login_id, &event.token, &event.login_type).await;
+            }
+            SaTokenEventType::Replaced => {
+                listener.on_replaced(&event.login_id, &event.token, &event.login_type).await;
+            }
+            SaTokenEventType::Banned => {
+                listener.on_banned(&event.login_id, &event.login_type).await;
+            }
+            SaTokenEventType::Unbanned => {
+                listener.on_unbanned(&event.login_id, &event.login_type).await;
+            }
+            SaTokenEventType::AuthFailed => {
+                let reason = event.extra.as_ref()
+                    .and_then(|extra| extra.get("reason"))
+                    .and_then(|reason| reason.as_str())
+                    .unwrap_or_default();
+                listener.on_auth_failed(reason, event.ip.as_deref()).await;
+            }
+            // 仅通过 on_event 分发 | Dispatched through on_event only
+            SaTokenEventType::OAuth2TokenRevoked
+            | SaTokenEventType::PermissionChanged
+            | SaTokenEventType::ConfigChanged
+            | SaTokenEventType::TokenRenewed
+            | SaTokenEventType::PermissionDenied
+            | SaTokenEventType::StorageDown
+            | SaTokenEventType::StorageRecovered => {}
         }
     }
 }
@@ -555,8 +1051,143 @@ impl Default for SaTokenEventBus {
     }
 }
 
-/// 简单的日志监听器示例
-pub struct LoggingListener;
+/// 默认的认证失败日志窗口（秒）
+pub const DEFAULT_AUTH_FAILURE_LOG_WINDOW: i64 = 60;
+
+/// 默认每个来源每个窗口内逐条记录的认证失败数
+pub const DEFAULT_AUTH_FAILURE_LOG_LIMIT: u32 = 5;
+
+/// 同时跟踪的来源数上限，超出后归入同一个汇总桶
+const MAX_TRACKED_SOURCES: usize = 10_000;
+
+/// 超出跟踪上限的来源使用的汇总桶
+const OVERFLOW_SOURCE: &str = "*";
+
+/// 某个来源在当前窗口内的失败统计
+struct FailureWindow {
+    started: i64,
+    failures: u64,
+    suppressed: u64,
+}
+
+/// 日志监听器 | Logging Listener
+///
+/// 以 tracing 输出各类事件。认证失败（如扫描器批量尝试 token）按来源 IP 限流：
+/// 每个窗口内每个来源只逐条记录前 `limit` 次，其余只计数，窗口结束时输出一条
+/// “窗口内来自 X 的 N 次失败”汇总，避免日志被刷爆。
+///
+/// Logs events through tracing. Auth failures (e.g. a bot scanning tokens) are
+/// rate limited per client IP: only the first `limit` failures per source and
+/// window are logged one by one, the rest are counted and reported as a single
+/// "N failures from X in the last window" summary when the window ends.
+pub struct LoggingListener {
+    window_secs: i64,
+    limit: u32,
+    failures: Mutex<HashMap<String, FailureWindow>>,
+    last_sweep: AtomicU64,
+    suppressed_total: AtomicU64,
+}
+
+impl LoggingListener {
+    /// 使用默认限流参数创建 | Create with the default rate limit
+    pub fn new() -> Self {
+        Self::with_failure_limit(DEFAULT_AUTH_FAILURE_LOG_LIMIT, DEFAULT_AUTH_FAILURE_LOG_WINDOW)
+    }
+
+    /// 按配置的 `auth_failure_log_limit` / `auth_failure_log_window` 创建
+    /// Create from the configured `auth_failure_log_limit` / `auth_failure_log_window`
+    #[cfg(feature = "runtime")]
+    pub fn from_config(config: &crate::config::SaTokenConfig) -> Self {
+        Self::with_failure_limit(config.auth_failure_log_limit, config.auth_failure_log_window)
+    }
+
+    /// 设置认证失败限流 | Set the auth failure rate limit
+    ///
+    /// # 参数 | Parameters
+    /// - `limit`: 每个来源每个窗口内逐条记录的次数，0 表示不限流 | Failures logged individually per source and window, 0 to log all
+    /// - `window_secs`: 窗口长度（秒）| Window length in seconds
+    pub fn with_failure_limit(limit: u32, window_secs: i64) -> Self {
+        Self {
+            window_secs: window_secs.max(1),
+            limit,
+            failures: Mutex::new(HashMap::new()),
+            last_sweep: AtomicU64::new(0),
+            suppressed_total: AtomicU64::new(0),
+        }
+    }
+
+    /// 因限流未逐条记录的认证失败总数 | Auth failures not logged individually because of the rate limit
+    pub fn suppressed_failures(&self) -> u64 {
+        self.suppressed_total.load(Ordering::Relaxed)
+    }
+
+    /// 记录一次失败，返回是否应逐条输出 | Count a failure and decide whether to log it individually
+    fn admit_failure(&self, source: &str) -> bool {
+        let now = crate::time::now().timestamp();
+        self.sweep(now);
+        if self.limit == 0 {
+            return true;
+        }
+
+        let mut failures = self.failures.lock().unwrap();
+        let key = if failures.len() >= MAX_TRACKED_SOURCES && !failures.contains_key(source) {
+            OVERFLOW_SOURCE
+        } else {
+            source
+        };
+        let window = failures.entry(key.to_string()).or_insert(FailureWindow { started: now, failures: 0, suppressed: 0 });
+        if now - window.started >= self.window_secs {
+            self.report_window(key, window);
+            *window = FailureWindow { started: now, failures: 0, suppressed: 0 };
+        }
+
+        window.failures += 1;
+        if window.failures <= u64::from(self.limit) {
+            return true;
+        }
+        window.suppressed += 1;
+        self.suppressed_total.fetch_add(1, Ordering::Relaxed);
+        if window.suppressed == 1 {
+            tracing::warn!(ip = %key, limit = self.limit, window_secs = self.window_secs, "认证失败过多，本窗口内其余失败只做汇总");
+        }
+        false
+    }
+
+    /// 每秒至多一次，输出并清理已结束的窗口 | At most once a second, report and drop finished windows
+    fn sweep(&self, now: i64) {
+        let now_secs = now.max(0) as u64;
+        if self.last_sweep.swap(now_secs, Ordering::Relaxed) >= now_secs {
+            return;
+        }
+        let mut failures = self.failures.lock().unwrap();
+        failures.retain(|source, window| {
+            let finished = now - window.started >= self.window_secs;
+            if finished {
+                self.report_window(source, window);
+            }
+            !finished
+        });
+    }
+
+    /// 有被抑制的失败时输出窗口汇总 | Log the window summary when failures were suppressed
+    fn report_window(&self, source: &str, window: &FailureWindow) {
+        if window.suppressed > 0 {
+            tracing::warn!(
+                ip = %source,
+                failures = window.failures,
+                suppressed = window.suppressed,
+                window_secs = self.window_secs,
+                "认证失败汇总"
+            );
+        }
+    }
+}
+
+impl Default for LoggingListener {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 #[async_trait]
 impl SaTokenListener for LoggingListener {
@@ -612,6 +1243,25 @@ impl SaTokenListener for LoggingListener {
             "用户被封禁"
         );
     }
+
+    async fn on_unbanned(&self, login_id: &str, login_type: &str) {
+        tracing::info!(
+            login_id = %login_id,
+            login_type = %login_type,
+            "用户解除封禁"
+        );
+    }
+
+    async fn on_auth_failed(&self, reason: &str, ip: Option<&str>) {
+        let source = ip.unwrap_or("unknown");
+        if self.admit_failure(source) {
+            tracing::warn!(
+                ip = %source,
+                reason = %reason,
+                "认证失败"
+            );
+        }
+    }
 }
 
 #[cfg(test)]
@@ -633,7 +1283,7 @@ mod tests {
     #[async_trait]
     impl SaTokenListener for TestListener {
         async fn on_login(&self, _login_id: &str, _token: &str, _login_type: &str) {
-            let mut count = self.login_count.write().await;
+            let mut count = self.login_count.write().unwrap();
             *count += 1;
         }
     }
@@ -644,17 +1294,100 @@ mod tests {
         let listener = Arc::new(TestListener::new());
         let login_count = Arc::clone(&listener.login_count);
         
-        bus.register(listener).await;
+        bus.register(listener);
         
         // 发布登录事件
         let event = SaTokenEvent::login("user_123", "token_abc");
         bus.publish(event).await;
         
         // 验证监听器被调用
-        let count = login_count.read().await;
+        let count = login_count.read().unwrap();
         assert_eq!(*count, 1);
     }
 
+    /// 等待放行后才记录登录的慢监听器
+    struct GatedListener {
+        gate: Arc<tokio::sync::Semaphore>,
+        seen: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl SaTokenListener for GatedListener {
+        async fn on_login(&self, login_id: &str, _token: &str, _login_type: &str) {
+            self.gate.acquire().await.unwrap().forget();
+            self.seen.lock().unwrap().push(login_id.to_string());
+        }
+    }
+
+    fn gated_listener() -> Arc<GatedListener> {
+        Arc::new(GatedListener { gate: Arc::new(tokio::sync::Semaphore::new(0)), seen: Mutex::new(Vec::new()) })
+    }
+
+    #[tokio::test]
+    async fn test_queue_dispatch_keeps_order_off_the_hot_path() {
+        let bus = SaTokenEventBus::with_dispatch_mode(EventDispatchMode::queue());
+        let slow = gated_listener();
+        let inline = Arc::new(TestListener::new());
+        bus.register(slow.clone());
+        bus.register_inline(inline.clone());
+
+        for login_id in ["a", "b", "c"] {
+            bus.publish(SaTokenEvent::login(login_id, "t")).await;
+        }
+        // publish 未等待慢监听器，内联监听器已执行
+        assert!(slow.seen.lock().unwrap().is_empty());
+        assert_eq!(*inline.login_count.read().unwrap(), 3);
+
+        slow.gate.add_permits(3);
+        bus.flush().await;
+        assert_eq!(*slow.seen.lock().unwrap(), vec!["a", "b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn test_queue_overflow_drops_newest() {
+        let bus = SaTokenEventBus::with_dispatch_mode(EventDispatchMode::Queue {
+            capacity: 1,
+            overflow: QueueOverflow::DropNewest,
+        });
+        let slow = gated_listener();
+        bus.register(slow.clone());
+
+        for i in 0..5 {
+            bus.publish(SaTokenEvent::login(i.to_string(), "t")).await;
+        }
+        assert!(bus.dropped_events() >= 3);
+
+        slow.gate.add_permits(5);
+        bus.flush().await;
+        assert_eq!(slow.seen.lock().unwrap().len() as u64 + bus.dropped_events(), 5);
+        assert_eq!(slow.seen.lock().unwrap()[0], "0");
+    }
+
+    #[tokio::test]
+    async fn test_logging_listener_rate_limits_auth_failures() {
+        let listener = LoggingListener::with_failure_limit(2, 60);
+        let bus = SaTokenEventBus::new();
+        let listener = Arc::new(listener);
+        bus.register(listener.clone());
+
+        for _ in 0..5 {
+            bus.publish(SaTokenEvent::auth_failed("bad", "invalid token").with_ip("10.0.0.1")).await;
+        }
+        assert_eq!(listener.suppressed_failures(), 3);
+
+        // 其他来源单独计数
+        assert!(listener.admit_failure("10.0.0.2"));
+
+        // 新窗口重新逐条记录
+        crate::time::advance(chrono::Duration::seconds(61));
+        assert!(listener.admit_failure("10.0.0.1"));
+        assert!(listener.failures.lock().unwrap().get("10.0.0.2").is_none());
+        crate::time::reset();
+
+        let unlimited = LoggingListener::with_failure_limit(0, 60);
+        assert!((0..10).all(|_| unlimited.admit_failure("10.0.0.1")));
+    }
+
     #[test]
     fn test_event_creation() {
         let event = SaTokenEvent::login("user_123", "token_abc");
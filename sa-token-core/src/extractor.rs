@@ -0,0 +1,325 @@
+// Author: 金书记
+//
+//! Token 提取 | Token extraction
+//!
+//! 提取顺序由 `SaTokenConfig::token_sources` 配置，默认是 Header → Authorization →
+//! Cookie → Query，每个来源可以单独指定名称。所有插件都通过
+//! `SaTokenManager::extract_token` 走同一套逻辑。
+//!
+//! The lookup order comes from `SaTokenConfig::token_sources`, by default
+//! header → Authorization → cookie → query, each with its own name. Every
+//! plugin goes through `SaTokenManager::extract_token`.
+//!
+//! ```rust,ignore
+//! let manager = SaTokenConfig::builder()
+//!     .storage(storage)
+//!     .token_sources(vec![
+//!         TokenSource::Header(Some("X-Access-Token".into())),
+//!         TokenSource::Custom("ws-protocol".into()),
+//!         TokenSource::Query(Some("access_token".into())),
+//!     ])
+//!     .token_source(Arc::new(WsProtocolSource))
+//!     .build();
+//! ```
+//!
+//! 扩展方式有两种 | Two extension points:
+//!
+//! - [`TokenExtractorSource`]：具名来源，在 `token_sources` 中用 `TokenSource::Custom(name)`
+//!   排入顺序 | a named source placed in the order with `TokenSource::Custom(name)`
+//! - [`TokenExtractor`]：按优先级在所有配置来源之前执行，适合 token 藏在签名载荷或
+//!   私有请求头格式中的场景 | runs by priority before every configured source,
+//!   for tokens hidden in signed payloads or proprietary header schemes
+//!
+//! ```rust,ignore
+//! struct ApiKeyExtractor;
+//!
+//! impl TokenExtractor for ApiKeyExtractor {
+//!     fn priority(&self) -> i32 { -10 }
+//!
+//!     fn extract(&self, request: &dyn TokenRequest, _token_name: &str) -> Option<String> {
+//!         request.header("X-Api-Key").and_then(|key| key.strip_prefix("ak.").map(String::from))
+//!     }
+//! }
+//!
+//! let manager = SaTokenConfig::builder()
+//!     .storage(storage)
+//!     .token_extractor(Arc::new(ApiKeyExtractor))
+//!     .build();
+//! ```
+
+use serde::{Deserialize, Serialize};
+use sa_token_adapter::context::SaRequest;
+
+/// Token 来源 | Where to look for the token
+///
+/// `None` 表示使用配置的 `token_name` | `None` means the configured `token_name`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "source", content = "name", rename_all = "snake_case")]
+pub enum TokenSource {
+    /// 请求头，自动去掉 `token_prefix` / `token_schemes` 中的前缀（默认 `Bearer`，不区分大小写）
+    /// | Request header; the `token_prefix` / `token_schemes` prefix (`Bearer` by default, case-insensitive) is stripped
+    Header(Option<String>),
+    /// Cookie
+    Cookie(Option<String>),
+    /// 查询参数 | Query parameter
+    Query(Option<String>),
+    /// JSON 请求体字段，需要请求适配器能读取已解析的请求体，内置中间件不缓冲请求体
+    ///
+    /// JSON body field. Needs a request adapter exposing the parsed body;
+    /// the built-in middlewares do not buffer request bodies.
+    Body(Option<String>),
+    /// 以 [`TokenExtractorSource::name`] 注册的自定义来源 | Custom source registered under this name
+    Custom(String),
+}
+
+impl TokenSource {
+    /// 来源类别，用于决策日志 | Source kind, as recorded in decision traces
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Header(_) => "header",
+            Self::Cookie(_) => "cookie",
+            Self::Query(_) => "query",
+            Self::Body(_) => "body",
+            Self::Custom(_) => "custom",
+        }
+    }
+}
+
+/// 默认提取顺序：Header → Authorization → Cookie → Query | Default lookup order
+pub fn default_token_sources() -> Vec<TokenSource> {
+    vec![
+        TokenSource::Header(None),
+        TokenSource::Header(Some("Authorization".to_string())),
+        TokenSource::Cookie(None),
+        TokenSource::Query(None),
+    ]
+}
+
+/// 提取器可读取的请求信息 | Request data visible to extractors
+///
+/// 框架无关且对象安全，实现了 [`SaRequest`] 的插件请求适配器都可直接使用
+/// Framework independent and object safe, every plugin's [`SaRequest`] adapter implements it
+pub trait TokenRequest {
+    /// 请求头 | Request header
+    fn header(&self, name: &str) -> Option<String>;
+
+    /// Cookie
+    fn cookie(&self, name: &str) -> Option<String>;
+
+    /// 查询参数 | Query parameter
+    fn param(&self, name: &str) -> Option<String>;
+
+    /// 请求路径 | Request path
+    fn path(&self) -> String;
+
+    /// 请求方法 | Request method
+    fn method(&self) -> String;
+
+    /// JSON 请求体中的字符串字段，默认不支持 | String field of a JSON body, unsupported by default
+    fn body_field(&self, _name: &str) -> Option<String> {
+        None
+    }
+}
+
+impl<T: SaRequest> TokenRequest for T {
+    fn header(&self, name: &str) -> Option<String> {
+        self.get_header(name)
+    }
+
+    fn cookie(&self, name: &str) -> Option<String> {
+        self.get_cookie(name)
+    }
+
+    fn param(&self, name: &str) -> Option<String> {
+        self.get_param(name)
+    }
+
+    fn path(&self) -> String {
+        self.get_path()
+    }
+
+    fn method(&self) -> String {
+        self.get_method()
+    }
+
+    fn body_field(&self, name: &str) -> Option<String> {
+        self.get_body_json::<serde_json::Value>()
+            .and_then(|body| body.get(name)?.as_str().map(String::from))
+    }
+}
+
+/// 自定义 Token 提取器 | Custom token extractor
+pub trait TokenExtractor: Send + Sync {
+    /// 优先级，数值越小越先执行，默认 0 | Lower runs first, defaults to 0
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    /// 从请求中提取 token，无法提取时返回 None 交给下一个提取器
+    /// Extract the token, `None` hands over to the next extractor
+    ///
+    /// `token_name` 为配置中的 token 名称 | `token_name` is the configured token name
+    fn extract(&self, request: &dyn TokenRequest, token_name: &str) -> Option<String>;
+}
+
+/// 具名的自定义 Token 来源 | Named custom token source
+///
+/// 注册后在 `token_sources` 中以 `TokenSource::Custom(name)` 指定位置，未列入顺序的来源不会被调用。
+/// Placed in `token_sources` as `TokenSource::Custom(name)`; sources left out of the order are never called.
+///
+/// ```rust,ignore
+/// /// WebSocket 子协议携带 token：`Sec-WebSocket-Protocol: access_token, <token>`
+/// struct WsProtocolSource;
+///
+/// impl TokenExtractorSource for WsProtocolSource {
+///     fn name(&self) -> &str { "ws-protocol" }
+///
+///     fn extract(&self, request: &dyn TokenRequest) -> Option<String> {
+///         let protocols = request.header("Sec-WebSocket-Protocol")?;
+///         let mut parts = protocols.split(',').map(str::trim);
+///         parts.find(|p| *p == "access_token")?;
+///         parts.next().map(String::from)
+///     }
+/// }
+/// ```
+pub trait TokenExtractorSource: Send + Sync {
+    /// 来源名称，与 `TokenSource::Custom` 对应 | Name matched by `TokenSource::Custom`
+    fn name(&self) -> &str;
+
+    /// 从请求中提取 token | Extract the token
+    fn extract(&self, request: &dyn TokenRequest) -> Option<String>;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use sa_token_adapter::storage::SaStorage;
+    use sa_token_storage_memory::MemoryStorage;
+    use crate::{SaTokenConfig, SaTokenManager};
+    use super::*;
+
+    struct MockRequest;
+
+    impl TokenRequest for MockRequest {
+        fn header(&self, name: &str) -> Option<String> {
+            match name {
+                "X-Api-Key" => Some("ak.key-token".to_string()),
+                "X-Signed" => Some("sig.signed-token".to_string()),
+                "X-Auth" => Some("TOKEN scheme-token".to_string()),
+                _ => None,
+            }
+        }
+
+        fn cookie(&self, _name: &str) -> Option<String> {
+            None
+        }
+
+        fn param(&self, _name: &str) -> Option<String> {
+            None
+        }
+
+        fn path(&self) -> String {
+            "/api".to_string()
+        }
+
+        fn method(&self) -> String {
+            "GET".to_string()
+        }
+    }
+
+    struct HeaderExtractor {
+        header: &'static str,
+        prefix: &'static str,
+        priority: i32,
+    }
+
+    impl TokenExtractor for HeaderExtractor {
+        fn priority(&self) -> i32 {
+            self.priority
+        }
+
+        fn extract(&self, request: &dyn TokenRequest, _token_name: &str) -> Option<String> {
+            request
+                .header(self.header)
+                .and_then(|value| value.strip_prefix(self.prefix).map(String::from))
+        }
+    }
+
+    fn new_manager() -> SaTokenManager {
+        let storage: Arc<dyn SaStorage> = Arc::new(MemoryStorage::new());
+        SaTokenManager::new(storage, SaTokenConfig::default())
+    }
+
+    #[test]
+    fn test_extractors_run_by_priority() {
+        let manager = new_manager()
+            .with_token_extractor(Arc::new(HeaderExtractor { header: "X-Api-Key", prefix: "ak.", priority: 10 }))
+            .with_token_extractor(Arc::new(HeaderExtractor { header: "X-Signed", prefix: "sig.", priority: -10 }));
+
+        assert_eq!(manager.extract_custom_token(&MockRequest).as_deref(), Some("signed-token"));
+    }
+
+    #[test]
+    fn test_falls_through_when_extractor_declines() {
+        let manager = new_manager()
+            .with_token_extractor(Arc::new(HeaderExtractor { header: "X-Signed", prefix: "other.", priority: -10 }))
+            .with_token_extractor(Arc::new(HeaderExtractor { header: "X-Api-Key", prefix: "ak.", priority: 10 }));
+        assert_eq!(manager.extract_custom_token(&MockRequest).as_deref(), Some("key-token"));
+
+        assert!(new_manager().extract_custom_token(&MockRequest).is_none());
+    }
+
+    struct SignedSource;
+
+    impl TokenExtractorSource for SignedSource {
+        fn name(&self) -> &str {
+            "signed"
+        }
+
+        fn extract(&self, request: &dyn TokenRequest) -> Option<String> {
+            request.header("X-Signed")
+        }
+    }
+
+    #[test]
+    fn test_configured_source_order() {
+        let storage: Arc<dyn SaStorage> = Arc::new(MemoryStorage::new());
+        let config = SaTokenConfig {
+            token_sources: vec![
+                TokenSource::Cookie(None),
+                TokenSource::Custom("signed".to_string()),
+                TokenSource::Header(Some("X-Api-Key".to_string())),
+            ],
+            ..SaTokenConfig::default()
+        };
+        let manager = SaTokenManager::new(storage.clone(), config.clone());
+        assert_eq!(manager.extract_token(&MockRequest), Some(("ak.key-token".to_string(), "header")));
+
+        let manager = SaTokenManager::new(storage, config).with_token_source(Arc::new(SignedSource));
+        assert_eq!(manager.extract_token(&MockRequest), Some(("sig.signed-token".to_string(), "custom")));
+        assert_eq!(new_manager().extract_token(&MockRequest), None);
+
+        let source: TokenSource = serde_json::from_str(r#"{"source":"header","name":"X-Token"}"#).unwrap();
+        assert_eq!(source, TokenSource::Header(Some("X-Token".to_string())));
+    }
+
+    #[test]
+    fn test_header_schemes() {
+        let storage: Arc<dyn SaStorage> = Arc::new(MemoryStorage::new());
+        let config = SaTokenConfig::builder()
+            .token_sources(vec![TokenSource::Header(Some("X-Auth".to_string())), TokenSource::Header(Some("X-Api-Key".to_string()))])
+            .build_config();
+        // 默认只接受 Bearer，其他方案原样保留
+        let manager = SaTokenManager::new(storage.clone(), config.clone());
+        assert_eq!(manager.extract_token(&MockRequest), Some(("TOKEN scheme-token".to_string(), "header")));
+
+        let config = SaTokenConfig { token_schemes: vec!["Bearer".into(), "Token".into()], ..config };
+        let manager = SaTokenManager::new(storage.clone(), config.clone());
+        assert_eq!(manager.extract_token(&MockRequest), Some(("scheme-token".to_string(), "header")));
+
+        assert_eq!(config.strip_token_prefix("bearer abc"), "abc");
+        let config = SaTokenConfig { token_prefix: Some("AK.".into()), ..config };
+        assert_eq!(config.strip_token_prefix("ak.key-token"), "key-token");
+        assert_eq!(config.strip_token_prefix("Token abc"), "abc");
+    }
+}
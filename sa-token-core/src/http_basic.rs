@@ -0,0 +1,107 @@
+// Author: 金书记
+//
+//! HTTP Basic 认证 | HTTP Basic authentication
+//!
+//! 对应 Java 版的 `SaHttpBasicUtil`，用于运维接口等只需一个固定账号的场景：校验
+//! `Authorization: Basic base64(username:password)`，失败时返回 `HttpBasicAuthFailed`，
+//! 框架插件在 401 响应中带上 `WWW-Authenticate: Basic realm="..."`，浏览器会弹出登录框。
+//!
+//! Equivalent of Java's `SaHttpBasicUtil`, for ops endpoints guarded by a
+//! single fixed account. Checks `Authorization: Basic base64(username:password)`
+//! and returns `HttpBasicAuthFailed` on failure; the plugins add
+//! `WWW-Authenticate: Basic realm="..."` to the 401 so browsers prompt for
+//! credentials.
+//!
+//! ```rust,ignore
+//! let basic = HttpBasicAuth::new("ops", "s3cret").realm("ops");
+//! let username = basic.check(headers.get("Authorization").and_then(|v| v.to_str().ok()))?;
+//! ```
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use crate::error::{SaTokenError, SaTokenResult};
+
+/// 默认 realm，与 Java 版一致 | Default realm, same as the Java version
+pub const DEFAULT_HTTP_BASIC_REALM: &str = "Sa-Token";
+
+/// HTTP Basic 认证校验器 | HTTP Basic authentication checker
+#[derive(Debug, Clone)]
+pub struct HttpBasicAuth {
+    username: String,
+    password: String,
+    realm: String,
+}
+
+impl HttpBasicAuth {
+    /// 使用固定账号创建 | Create with a fixed account
+    pub fn new(username: impl Into<String>, password: impl Into<String>) -> Self {
+        Self {
+            username: username.into(),
+            password: password.into(),
+            realm: DEFAULT_HTTP_BASIC_REALM.to_string(),
+        }
+    }
+
+    /// `WWW-Authenticate` 中的 realm，默认 `Sa-Token` | Realm of `WWW-Authenticate`, `Sa-Token` by default
+    pub fn realm(mut self, realm: impl Into<String>) -> Self {
+        self.realm = realm.into();
+        self
+    }
+
+    /// 失败时应返回的 `WWW-Authenticate` 值 | `WWW-Authenticate` value to send on failure
+    pub fn challenge(&self) -> String {
+        format!("Basic realm=\"{}\"", self.realm)
+    }
+
+    /// 校验 `Authorization` 请求头，成功时返回用户名 | Check the `Authorization` header; returns the username
+    pub fn check(&self, authorization: Option<&str>) -> SaTokenResult<String> {
+        let (username, password) = authorization
+            .and_then(parse_http_basic)
+            .ok_or(SaTokenError::HttpBasicAuthFailed)?;
+        // 用户名与密码都比较完，避免通过耗时区分哪一项错误
+        let username_ok = constant_time_eq(username.as_bytes(), self.username.as_bytes());
+        let password_ok = constant_time_eq(password.as_bytes(), self.password.as_bytes());
+        if username_ok & password_ok {
+            Ok(username)
+        } else {
+            Err(SaTokenError::HttpBasicAuthFailed)
+        }
+    }
+}
+
+/// 解析 `Basic base64(username:password)`，格式不合法时返回 None
+///
+/// Parses `Basic base64(username:password)`; None when malformed.
+pub fn parse_http_basic(authorization: &str) -> Option<(String, String)> {
+    let (scheme, encoded) = authorization.trim().split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case("Basic") {
+        return None;
+    }
+    let decoded = String::from_utf8(STANDARD.decode(encoded.trim()).ok()?).ok()?;
+    let (username, password) = decoded.split_once(':')?;
+    Some((username.to_string(), password.to_string()))
+}
+
+/// 生成 `Authorization` 请求头的值，供调用方与测试使用 | Build an `Authorization` value, for callers and tests
+pub fn http_basic_header(username: &str, password: &str) -> String {
+    format!("Basic {}", STANDARD.encode(format!("{}:{}", username, password)))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_http_basic() {
+        let basic = HttpBasicAuth::new("ops", "pa:ss").realm("ops");
+        assert_eq!(basic.check(Some(&http_basic_header("ops", "pa:ss"))).unwrap(), "ops");
+        assert!(matches!(basic.check(Some(&http_basic_header("ops", "wrong"))), Err(SaTokenError::HttpBasicAuthFailed)));
+        assert!(basic.check(Some("Bearer abc")).is_err());
+        assert!(basic.check(Some("Basic !!!")).is_err());
+        assert!(basic.check(None).is_err());
+        assert_eq!(basic.challenge(), "Basic realm=\"ops\"");
+    }
+}
@@ -0,0 +1,157 @@
+// Author: 金书记
+//
+//! 幂等键模块 | Idempotency Keys
+//!
+//! 移动网络下客户端常会重试登录/刷新请求。携带相同幂等键（`Idempotency-Key` 请求头）
+//! 的重复请求直接返回第一次的结果，而不会再创建新的会话。
+//!
+//! Clients on flaky networks retry login/refresh calls. Requests carrying the same
+//! idempotency key get the stored response of the first call instead of creating
+//! another session.
+//!
+//! ## 使用示例 | Usage Example
+//!
+//! ```rust,ignore
+//! let key = headers.get(IDEMPOTENCY_KEY_HEADER).and_then(|v| v.to_str().ok());
+//! let token = match key {
+//!     Some(key) => manager.login_idempotent("user_123", key).await?,
+//!     None => manager.login("user_123").await?,
+//! };
+//! ```
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use serde::{de::DeserializeOwned, Serialize};
+use sa_token_adapter::storage::SaStorage;
+use crate::error::{SaTokenError, SaTokenResult};
+
+/// 客户端传递幂等键使用的请求头 | Request header carrying the idempotency key
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// 处理中锁的过期时间，防止请求中途崩溃导致幂等键永久不可用
+const IN_PROGRESS_LOCK_TTL: Duration = Duration::from_secs(30);
+
+/// 基于存储的幂等键记录 | Storage-backed idempotency store
+#[derive(Clone)]
+pub struct IdempotencyStore {
+    storage: Arc<dyn SaStorage>,
+    retention: Option<Duration>,
+}
+
+impl IdempotencyStore {
+    /// 创建幂等键记录
+    ///
+    /// # 参数 | Parameters
+    /// - `storage`: 存储后端 | Storage backend
+    /// - `retention`: 结果保留时长，None 表示永久保留 | How long results are kept, None keeps forever
+    pub fn new(storage: Arc<dyn SaStorage>, retention: Option<Duration>) -> Self {
+        Self { storage, retention }
+    }
+
+    /// 以幂等方式执行 `f`
+    ///
+    /// 同一 `scope` 下相同 `key` 的请求只会执行一次 `f`，之后直接返回保存的结果。
+    /// `f` 失败时不保存结果，客户端可以用同一个 key 重试。
+    /// 相同 key 的请求仍在处理中时返回 `IdempotencyKeyInProgress`。
+    ///
+    /// Runs `f` at most once per `(scope, key)` and replays its stored result
+    /// afterwards. Failures are not stored, so the same key can be retried.
+    pub async fn run<T, F, Fut>(&self, scope: &str, key: &str, f: F) -> SaTokenResult<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = SaTokenResult<T>>,
+    {
+        let result_key = format!("sa:idempotency:{}:{}", scope, key);
+        if let Some(stored) = self.get(&result_key).await? {
+            tracing::debug!("Idempotency: 重放已保存的结果，key: {}", key);
+            return Ok(stored);
+        }
+
+        let lock_key = format!("sa:idempotency:lock:{}:{}", scope, key);
+        let holders = self.storage.incr(&lock_key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        if holders > 1 {
+            // 锁竞争期间结果可能刚刚写入
+            if let Some(stored) = self.get(&result_key).await? {
+                return Ok(stored);
+            }
+            return Err(SaTokenError::IdempotencyKeyInProgress(key.to_string()));
+        }
+        self.storage.expire(&lock_key, IN_PROGRESS_LOCK_TTL).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+
+        let result = f().await;
+        if let Ok(value) = &result {
+            let json = serde_json::to_string(value)?;
+            self.storage.set(&result_key, &json, self.retention).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        }
+        self.storage.delete(&lock_key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+
+        result
+    }
+
+    /// 丢弃保存的结果，之后相同 key 的请求会重新执行
+    ///
+    /// Drops the stored result so the next request with the key runs again.
+    pub async fn forget(&self, scope: &str, key: &str) -> SaTokenResult<()> {
+        self.storage.delete(&format!("sa:idempotency:{}:{}", scope, key)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+
+    async fn get<T: DeserializeOwned>(&self, result_key: &str) -> SaTokenResult<Option<T>> {
+        let value = self.storage.get(result_key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        match value {
+            Some(value) => Ok(Some(serde_json::from_str(&value)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use sa_token_storage_memory::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_replays_first_result() {
+        let store = IdempotencyStore::new(Arc::new(MemoryStorage::new()), None);
+        let calls = AtomicUsize::new(0);
+
+        for _ in 0..3 {
+            let value: usize = store
+                .run("login:user_1", "key-1", || async {
+                    Ok(calls.fetch_add(1, Ordering::SeqCst))
+                })
+                .await
+                .unwrap();
+            assert_eq!(value, 0);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let other: usize = store.run("login:user_1", "key-2", || async { Ok(7) }).await.unwrap();
+        assert_eq!(other, 7);
+    }
+
+    #[tokio::test]
+    async fn test_failure_is_not_stored() {
+        let store = IdempotencyStore::new(Arc::new(MemoryStorage::new()), None);
+
+        let err = store
+            .run::<String, _, _>("refresh", "key-1", || async { Err(SaTokenError::RefreshTokenNotFound) })
+            .await;
+        assert!(err.is_err());
+
+        let value: String = store.run("refresh", "key-1", || async { Ok("ok".to_string()) }).await.unwrap();
+        assert_eq!(value, "ok");
+
+        store.forget("refresh", "key-1").await.unwrap();
+        let value: String = store.run("refresh", "key-1", || async { Ok("again".to_string()) }).await.unwrap();
+        assert_eq!(value, "again");
+    }
+}
@@ -0,0 +1,164 @@
+// Author: 金书记
+//
+//! 客户端 IP 解析 | Client IP resolution
+//!
+//! 反向代理把原始客户端地址写在 `X-Forwarded-For` 中，每经过一层代理在末尾追加一个地址。
+//! 这些请求头可以被客户端伪造，因此配置了受信任代理（CIDR 列表）时，只有连接对端是受信
+//! 任代理才读取请求头，并从右向左跳过受信任代理，取第一个不受信任的地址作为客户端 IP。
+//! 未配置受信任代理时保持原有行为：取 `X-Forwarded-For` 的第一个地址、`X-Real-IP`，
+//! 否则取连接对端地址。
+//!
+//! Reverse proxies put the original client address into `X-Forwarded-For`,
+//! each hop appending one entry. Clients can forge these headers, so once
+//! trusted proxies (CIDRs) are configured the headers are only read when the
+//! peer is a trusted proxy, and the list is walked from the right, skipping
+//! trusted proxies; the first untrusted address is the client. Without
+//! trusted proxies the previous behaviour stays: the first
+//! `X-Forwarded-For` entry, then `X-Real-IP`, then the peer address.
+//!
+//! ```rust,ignore
+//! let trusted = [IpCidr::parse("10.0.0.0/8").unwrap()];
+//! let ip = resolve_client_ip(Some("10.0.0.2"), Some("203.0.113.9, 10.0.0.7"), None, &trusted);
+//! assert_eq!(ip.as_deref(), Some("203.0.113.9"));
+//! ```
+
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+/// IP 网段，如 `10.0.0.0/8`、`fd00::/8`；不带前缀长度时表示单个地址
+///
+/// IP network such as `10.0.0.0/8` or `fd00::/8`; without a prefix length it is a single address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpCidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl IpCidr {
+    /// 解析网段，格式错误时返回 None | Parse a network; None when malformed
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let (addr, prefix) = match value.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix.parse::<u8>().ok()?)),
+            None => (value, None),
+        };
+        let network: IpAddr = addr.parse().ok()?;
+        let max = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(max);
+        (prefix <= max).then_some(Self { network, prefix })
+    }
+
+    /// 地址是否在网段内，IPv4 映射的 IPv6 地址按 IPv4 比较
+    ///
+    /// Whether the address is inside the network; IPv4-mapped IPv6 addresses compare as IPv4.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, canonical(ip)) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                prefix_eq(u32::from(network) as u128, u32::from(ip) as u128, self.prefix, 32)
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                prefix_eq(u128::from(network), u128::from(ip), self.prefix, 128)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpCidr {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::parse(value).ok_or_else(|| format!("invalid CIDR '{}'", value))
+    }
+}
+
+impl fmt::Display for IpCidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix)
+    }
+}
+
+fn prefix_eq(network: u128, ip: u128, prefix: u8, bits: u32) -> bool {
+    let shift = bits - prefix as u32;
+    shift >= bits || (network >> shift) == (ip >> shift)
+}
+
+fn canonical(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+        v4 => v4,
+    }
+}
+
+/// 解析地址，接受 `ip`、`ip:port` 与 `[ipv6]:port` | Parse `ip`, `ip:port` or `[ipv6]:port`
+pub fn parse_ip(value: &str) -> Option<IpAddr> {
+    let value = value.trim();
+    value.parse::<IpAddr>().ok()
+        .or_else(|| value.parse::<std::net::SocketAddr>().ok().map(|addr| addr.ip()))
+        .map(canonical)
+}
+
+/// 两个地址是否相同（忽略端口与 IPv4 映射）| Whether two addresses are the same, ignoring ports and IPv4 mapping
+pub fn same_ip(a: &str, b: &str) -> bool {
+    match (parse_ip(a), parse_ip(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => a.trim() == b.trim(),
+    }
+}
+
+/// 按受信任代理解析客户端 IP | Resolve the client IP with the trusted proxies
+///
+/// `remote` 为连接对端地址，`forwarded_for` 与 `real_ip` 为请求头的原始值。
+/// `remote` is the peer address; `forwarded_for` and `real_ip` are the raw header values.
+pub fn resolve_client_ip(
+    remote: Option<&str>,
+    forwarded_for: Option<&str>,
+    real_ip: Option<&str>,
+    trusted: &[IpCidr],
+) -> Option<String> {
+    let forwarded: Vec<&str> = forwarded_for
+        .map(|value| value.split(',').map(str::trim).filter(|ip| !ip.is_empty()).collect())
+        .unwrap_or_default();
+    let real_ip = real_ip.map(str::trim).filter(|ip| !ip.is_empty());
+
+    if trusted.is_empty() {
+        return forwarded.first().copied().or(real_ip).or(remote).map(str::to_string);
+    }
+
+    let is_trusted = |ip: &str| parse_ip(ip).is_some_and(|ip| trusted.iter().any(|cidr| cidr.contains(ip)));
+    // 直连的不是受信任代理时，请求头不可信 | Headers cannot be trusted unless the peer is a trusted proxy
+    if let Some(remote) = remote.filter(|remote| !is_trusted(remote)) {
+        return Some(remote.to_string());
+    }
+    if !forwarded.is_empty() {
+        let client = forwarded.iter().rev()
+            .find(|ip| !is_trusted(ip))
+            .or(forwarded.first());
+        return client.map(|ip| ip.to_string());
+    }
+    real_ip.or(remote).map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trusted_proxies_skip_forged_entries() {
+        let trusted: Vec<IpCidr> = ["10.0.0.0/8", "fd00::/8"].iter().map(|c| c.parse().unwrap()).collect();
+        assert!(trusted[0].contains("10.1.2.3".parse().unwrap()));
+        assert!(trusted[0].contains("::ffff:10.1.2.3".parse().unwrap()));
+        assert!(!trusted[0].contains("11.0.0.1".parse().unwrap()));
+        assert!(IpCidr::parse("10.0.0.0/33").is_none());
+
+        // 客户端伪造的最左侧地址被忽略，取受信任代理之前的地址
+        let xff = Some("1.1.1.1, 203.0.113.9, 10.0.0.7");
+        assert_eq!(resolve_client_ip(Some("10.0.0.2"), xff, None, &trusted).as_deref(), Some("203.0.113.9"));
+        // 直连的不是受信任代理时忽略请求头
+        assert_eq!(resolve_client_ip(Some("198.51.100.4"), xff, Some("1.1.1.1"), &trusted).as_deref(), Some("198.51.100.4"));
+        // 未配置受信任代理时保持原有行为
+        assert_eq!(resolve_client_ip(Some("10.0.0.2"), xff, None, &[]).as_deref(), Some("1.1.1.1"));
+        assert!(same_ip("203.0.113.9:443", "::ffff:203.0.113.9"));
+    }
+}
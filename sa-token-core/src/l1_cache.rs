@@ -0,0 +1,375 @@
+// Author: 金书记
+//
+//! 本地一级缓存 | Local L1 Cache
+//!
+//! 每次请求校验 token 都要访问一次 Redis。`CachedStorage` 在任意存储前加一层进程内的
+//! LRU 缓存：读取命中时不再访问后端，条目只保留很短的 `ttl`（默认 5 秒）；本实例的
+//! 写入、删除、续期先写后端，再丢弃本地条目（write-through 失效）。
+//!
+//! Every token check costs a Redis round trip. `CachedStorage` puts an
+//! in-process LRU in front of any storage: cache hits skip the backend and
+//! entries live for a short `ttl` (5 seconds by default). Writes, deletes and
+//! renewals on this instance go to the backend first and then drop the local
+//! entry (write-through invalidation).
+//!
+//! 多实例部署时配置 [`CacheInvalidation`] 通道（如 Redis pub/sub），本实例的写入会
+//! 通知其他实例丢弃同一个键，登出、踢人无需等待 `ttl` 即可在所有实例上生效。通道断开
+//! 期间，其他实例上的旧值最多保留 `ttl`。
+//!
+//! With a [`CacheInvalidation`] channel (e.g. Redis pub/sub), writes here
+//! tell the other instances to drop the same key, so logouts and kick-outs
+//! apply everywhere without waiting for `ttl`. While the channel is down,
+//! stale entries elsewhere live at most `ttl`.
+//!
+//! 只缓存存在的值，不缓存"键不存在"，其他实例新签发的 token 可以立即使用。
+//! Only present values are cached, never misses, so tokens issued on other
+//! instances work at once.
+//!
+//! ```rust,ignore
+//! let redis: Arc<dyn SaStorage> = Arc::new(RedisStorage::new(url, "sa").await?);
+//! let cached = Arc::new(
+//!     CachedStorage::new(redis)
+//!         .capacity(50_000)
+//!         .ttl(Duration::from_secs(3))
+//!         .invalidation(Arc::new(RedisCacheInvalidation::new(url, "sa:cache-invalidate").await?)),
+//! );
+//! cached.listen_invalidations().await?;
+//!
+//! let manager = SaTokenConfig::builder().storage(cached.clone()).build();
+//! ```
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use async_trait::async_trait;
+use hashlink::LruCache;
+use sa_token_adapter::storage::{CacheInvalidation, KeyTtl, SaStorage, ScanPage, StorageResult};
+
+/// 默认最多缓存的键数 | Default number of cached keys
+pub const DEFAULT_L1_CAPACITY: usize = 10_000;
+
+/// 缓存条目的默认保留时长 | Default lifetime of a cached entry
+pub const DEFAULT_L1_TTL: Duration = Duration::from_secs(5);
+
+/// 缓存命中统计 | Cache hit statistics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// 由本地缓存应答的读取次数 | Reads answered by the local cache
+    pub hits: u64,
+    /// 访问后端的读取次数 | Reads that went to the backend
+    pub misses: u64,
+}
+
+struct CacheEntry {
+    value: String,
+    expires_at: Instant,
+}
+
+/// 带本地 LRU 缓存的存储 | Storage with a local LRU cache in front
+pub struct CachedStorage {
+    inner: Arc<dyn SaStorage>,
+    cache: Mutex<LruCache<String, CacheEntry>>,
+    ttl: Duration,
+    invalidation: Option<Arc<dyn CacheInvalidation>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl CachedStorage {
+    /// 包装后端存储 | Wrap a backend storage
+    pub fn new(inner: Arc<dyn SaStorage>) -> Self {
+        Self {
+            inner,
+            cache: Mutex::new(LruCache::new(DEFAULT_L1_CAPACITY)),
+            ttl: DEFAULT_L1_TTL,
+            invalidation: None,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// 设置最多缓存的键数，超出时淘汰最久未使用的键 | Max cached keys; the least recently used go first
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.cache = Mutex::new(LruCache::new(capacity.max(1)));
+        self
+    }
+
+    /// 设置缓存条目的保留时长 | Set how long an entry stays cached
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// 设置跨实例的失效通道 | Set the cross-instance invalidation channel
+    pub fn invalidation(mut self, channel: Arc<dyn CacheInvalidation>) -> Self {
+        self.invalidation = Some(channel);
+        self
+    }
+
+    /// 订阅其他实例的失效通知，缓存释放后收到的通知被忽略
+    ///
+    /// Subscribes to invalidations from other instances; notifications after
+    /// the cache is dropped are ignored.
+    pub async fn listen_invalidations(self: &Arc<Self>) -> StorageResult<()> {
+        let Some(channel) = &self.invalidation else { return Ok(()) };
+        let cache = Arc::downgrade(self);
+        channel.subscribe(Arc::new(move |keys: Vec<String>| {
+            if let Some(cache) = cache.upgrade() {
+                for key in &keys {
+                    cache.invalidate(key);
+                }
+            }
+        })).await
+    }
+
+    /// 丢弃本地缓存的一个键 | Drop one key from the local cache
+    pub fn invalidate(&self, key: &str) {
+        self.entries().remove(key);
+    }
+
+    /// 丢弃全部本地缓存 | Drop the whole local cache
+    pub fn invalidate_all(&self) {
+        self.entries().clear();
+    }
+
+    /// 命中统计快照 | Snapshot of the hit statistics
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// 被包装的后端存储 | The wrapped backend
+    pub fn inner(&self) -> &Arc<dyn SaStorage> {
+        &self.inner
+    }
+
+    fn entries(&self) -> MutexGuard<'_, LruCache<String, CacheEntry>> {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 未过期的缓存值 | Cached value that has not expired
+    fn cached(&self, key: &str) -> Option<String> {
+        let mut entries = self.entries();
+        match entries.get(key) {
+            Some(entry) if entry.expires_at > Instant::now() => Some(entry.value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn store(&self, key: &str, value: String, ttl: Option<Duration>) {
+        let ttl = ttl.map_or(self.ttl, |ttl| ttl.min(self.ttl));
+        self.entries().insert(key.to_string(), CacheEntry { value, expires_at: Instant::now() + ttl });
+    }
+
+    /// 后端写入成功后丢弃本地条目，并通知其他实例 | After a backend write, drop the keys here and tell the other instances
+    async fn written(&self, keys: &[&str]) {
+        {
+            let mut entries = self.entries();
+            for key in keys {
+                entries.remove(*key);
+            }
+        }
+        if let Some(channel) = &self.invalidation {
+            let keys: Vec<String> = keys.iter().map(|key| key.to_string()).collect();
+            if let Err(e) = channel.publish(&keys).await {
+                tracing::warn!("CachedStorage: 发布缓存失效通知失败: {}", e);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl SaStorage for CachedStorage {
+    async fn get(&self, key: &str) -> StorageResult<Option<String>> {
+        if let Some(value) = self.cached(key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(Some(value));
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let value = self.inner.get(key).await?;
+        if let Some(value) = &value {
+            self.store(key, value.clone(), None);
+        }
+        Ok(value)
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Option<Duration>) -> StorageResult<()> {
+        self.inner.set(key, value, ttl).await?;
+        self.written(&[key]).await;
+        Ok(())
+    }
+
+    async fn set_nx(&self, key: &str, value: &str, ttl: Option<Duration>) -> StorageResult<bool> {
+        let written = self.inner.set_nx(key, value, ttl).await?;
+        if written {
+            self.written(&[key]).await;
+        }
+        Ok(written)
+    }
+
+    async fn delete(&self, key: &str) -> StorageResult<()> {
+        self.inner.delete(key).await?;
+        self.written(&[key]).await;
+        Ok(())
+    }
+
+    async fn exists(&self, key: &str) -> StorageResult<bool> {
+        if self.cached(key).is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(true);
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        self.inner.exists(key).await
+    }
+
+    async fn expire(&self, key: &str, ttl: Duration) -> StorageResult<()> {
+        self.inner.expire(key, ttl).await?;
+        self.written(&[key]).await;
+        Ok(())
+    }
+
+    async fn ttl(&self, key: &str) -> StorageResult<KeyTtl> {
+        self.inner.ttl(key).await
+    }
+
+    async fn mget(&self, keys: &[&str]) -> StorageResult<Vec<Option<String>>> {
+        let mut values: Vec<Option<String>> = keys.iter().map(|key| self.cached(key)).collect();
+        let missing: Vec<&str> = keys.iter().zip(&values)
+            .filter(|(_, value)| value.is_none())
+            .map(|(key, _)| *key)
+            .collect();
+        self.hits.fetch_add((keys.len() - missing.len()) as u64, Ordering::Relaxed);
+        if missing.is_empty() {
+            return Ok(values);
+        }
+        self.misses.fetch_add(missing.len() as u64, Ordering::Relaxed);
+
+        let mut fetched = self.inner.mget(&missing).await?.into_iter();
+        for (key, slot) in keys.iter().zip(values.iter_mut()) {
+            if slot.is_none() {
+                *slot = fetched.next().flatten();
+                if let Some(value) = slot {
+                    self.store(key, value.clone(), None);
+                }
+            }
+        }
+        Ok(values)
+    }
+
+    async fn mset(&self, items: &[(&str, &str)], ttl: Option<Duration>) -> StorageResult<()> {
+        self.inner.mset(items, ttl).await?;
+        let keys: Vec<&str> = items.iter().map(|(key, _)| *key).collect();
+        self.written(&keys).await;
+        Ok(())
+    }
+
+    async fn mdel(&self, keys: &[&str]) -> StorageResult<()> {
+        self.inner.mdel(keys).await?;
+        self.written(keys).await;
+        Ok(())
+    }
+
+    async fn incr(&self, key: &str) -> StorageResult<i64> {
+        let value = self.inner.incr(key).await?;
+        self.written(&[key]).await;
+        Ok(value)
+    }
+
+    async fn decr(&self, key: &str) -> StorageResult<i64> {
+        let value = self.inner.decr(key).await?;
+        self.written(&[key]).await;
+        Ok(value)
+    }
+
+    /// 只清空后端与本实例的缓存，其他实例的缓存在 `ttl` 后过期
+    ///
+    /// Clears the backend and this instance's cache; other instances' entries
+    /// expire after `ttl`.
+    async fn clear(&self) -> StorageResult<()> {
+        self.inner.clear().await?;
+        self.invalidate_all();
+        Ok(())
+    }
+
+    async fn keys(&self, pattern: &str) -> StorageResult<Vec<String>> {
+        self.inner.keys(pattern).await
+    }
+
+    async fn scan_keys(&self, pattern: &str, cursor: u64) -> StorageResult<ScanPage> {
+        self.inner.scan_keys(pattern, cursor).await
+    }
+
+    async fn health_check(&self) -> StorageResult<()> {
+        self.inner.health_check().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_adapter::storage::InvalidationHandler;
+    use sa_token_storage_memory::MemoryStorage;
+
+    /// 进程内的失效通道，模拟 Redis pub/sub | In-process channel standing in for Redis pub/sub
+    #[derive(Default)]
+    struct LocalChannel {
+        handlers: Mutex<Vec<InvalidationHandler>>,
+    }
+
+    #[async_trait]
+    impl CacheInvalidation for LocalChannel {
+        async fn publish(&self, keys: &[String]) -> StorageResult<()> {
+            for handler in self.handlers.lock().unwrap().iter() {
+                handler(keys.to_vec());
+            }
+            Ok(())
+        }
+
+        async fn subscribe(&self, handler: InvalidationHandler) -> StorageResult<()> {
+            self.handlers.lock().unwrap().push(handler);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hits_and_write_through_invalidation() {
+        let cached = CachedStorage::new(Arc::new(MemoryStorage::new()));
+        cached.set("sa:token:a", "user_1", None).await.unwrap();
+
+        assert_eq!(cached.get("sa:token:a").await.unwrap().as_deref(), Some("user_1"));
+        assert_eq!(cached.get("sa:token:a").await.unwrap().as_deref(), Some("user_1"));
+        assert_eq!(cached.stats(), CacheStats { hits: 1, misses: 1 });
+
+        cached.delete("sa:token:a").await.unwrap();
+        assert_eq!(cached.get("sa:token:a").await.unwrap(), None);
+
+        let expiring = CachedStorage::new(Arc::new(MemoryStorage::new())).ttl(Duration::ZERO);
+        expiring.set("k", "v", None).await.unwrap();
+        expiring.get("k").await.unwrap();
+        expiring.get("k").await.unwrap();
+        assert_eq!(expiring.stats().hits, 0);
+    }
+
+    #[tokio::test]
+    async fn test_writes_invalidate_other_instances() {
+        let backend: Arc<dyn SaStorage> = Arc::new(MemoryStorage::new());
+        let channel = Arc::new(LocalChannel::default());
+        let node_a = Arc::new(CachedStorage::new(backend.clone()).invalidation(channel.clone()));
+        let node_b = Arc::new(CachedStorage::new(backend).invalidation(channel));
+        node_a.listen_invalidations().await.unwrap();
+        node_b.listen_invalidations().await.unwrap();
+
+        node_a.set("sa:token:a", "user_1", None).await.unwrap();
+        assert!(node_b.get("sa:token:a").await.unwrap().is_some());
+
+        // A 上登出后，B 不必等缓存过期 | After a logout on A, B does not wait for its entry to expire
+        node_a.delete("sa:token:a").await.unwrap();
+        assert_eq!(node_b.get("sa:token:a").await.unwrap(), None);
+    }
+}
@@ -11,6 +11,17 @@
 //! - 权限验证：基于角色/权限的访问控制
 //! - 账号管理：登录、登出、踢人下线、封禁等
 //! 
+//! ## Feature
+//! 
+//! - `runtime`（默认）：完整的服务端功能，依赖 tokio 和存储适配器
+//! - `toml`（默认）/ `yaml`：`SaTokenConfig::from_file` 支持的配置文件格式（JSON 始终可用）
+//! - `wasm`：关闭默认 feature 后，只保留 JWT 校验、声明解析与权限匹配，
+//!   可编译到 `wasm32-unknown-unknown`，供边缘节点或浏览器在本地预校验 token
+//! 
+//! ```toml
+//! sa-token-core = { version = "0.1", default-features = false, features = ["wasm"] }
+//! ```
+//! 
 //! ## 使用示例
 //! 
 //! ```rust,ignore
@@ -24,43 +35,192 @@ pub mod token;
 pub mod session;
 pub mod permission;
 pub mod context;
+pub mod propagation;
+pub mod event;
+pub mod policy;
+pub mod security_headers;
+pub mod read_only;
+pub mod compat;
+pub mod time;
+pub mod cookie;
+pub mod locale;
+pub mod metrics;
+pub mod ip;
+#[cfg(feature = "runtime")]
 pub mod config;
+#[cfg(feature = "runtime")]
+pub mod config_loader;
+#[cfg(feature = "runtime")]
 pub mod util;
-pub mod event;
+#[cfg(feature = "runtime")]
+pub mod stp_logic;
+#[cfg(feature = "runtime")]
 pub mod nonce;
+#[cfg(feature = "runtime")]
 pub mod refresh;
+#[cfg(feature = "runtime")]
 pub mod oauth2;
+#[cfg(feature = "runtime")]
 pub mod ws;
+#[cfg(feature = "runtime")]
+pub mod ws_registry;
+#[cfg(feature = "runtime")]
+pub mod sse;
+#[cfg(feature = "runtime")]
 pub mod online;
+#[cfg(feature = "runtime")]
 pub mod distributed;
+#[cfg(feature = "runtime")]
 pub mod sso;
+#[cfg(feature = "runtime")]
+pub mod idempotency;
+#[cfg(feature = "runtime")]
+pub mod decision;
+#[cfg(feature = "runtime")]
+pub mod extractor;
+#[cfg(feature = "runtime")]
+pub mod migration;
+#[cfg(feature = "runtime")]
+pub mod login;
+#[cfg(feature = "runtime")]
+pub mod status;
+#[cfg(feature = "runtime")]
+pub mod prefetch;
+#[cfg(feature = "runtime")]
+pub mod ban;
+#[cfg(feature = "runtime")]
+pub mod temp;
+#[cfg(feature = "runtime")]
+pub mod routing;
+#[cfg(feature = "runtime")]
+pub mod supervisor;
+#[cfg(feature = "l1-cache")]
+pub mod l1_cache;
+#[cfg(feature = "runtime")]
+pub mod check;
+#[cfg(feature = "runtime")]
+pub mod render;
+#[cfg(feature = "runtime")]
+pub mod blocking;
+#[cfg(feature = "runtime")]
+pub mod sign;
+#[cfg(feature = "runtime")]
+pub mod same_token;
+#[cfg(feature = "runtime")]
+pub mod api_key;
+#[cfg(feature = "runtime")]
+pub mod audit;
+#[cfg(feature = "runtime")]
+pub mod state;
+pub mod http_basic;
 
 pub mod error;
+#[cfg(feature = "runtime")]
 mod manager;
 
 pub use error::{SaTokenError, SaTokenResult};
-pub use manager::SaTokenManager;
-pub use config::SaTokenConfig;
+#[cfg(feature = "runtime")]
+pub use manager::{SaTokenManager, account_key, DEFAULT_LOGIN_TYPE};
+#[cfg(feature = "runtime")]
+pub use stp_logic::StpLogic;
+#[cfg(feature = "runtime")]
+pub use config::{SaTokenConfig, JsonFieldCase, LoginEviction, StorageConfig};
+#[cfg(feature = "runtime")]
 pub use util::{StpUtil, LoginId};
+#[cfg(feature = "runtime")]
+pub use login::{LoginOptions, LoginResult};
+#[cfg(feature = "runtime")]
+pub use state::{SaTokenState, SaTokenStateBuilder};
 pub use context::SaTokenContext;
+pub use propagation::{PropagatedContext, SIGNED_CONTEXT_HEADER, SAME_TOKEN_HEADER};
+pub use policy::{
+    LoginContext, LoginPolicy, LoginPolicyDecision, SessionValidation, SessionValidator,
+    TtlPolicy, PASSWORD_CHANGE_SCOPE
+};
+pub use security_headers::SecurityHeaders;
+pub use cookie::{CookieDomainCheck, PublicSuffixList};
+pub use locale::{ACCEPT_LANGUAGE_HEADER, TIMEZONE_HEADER, SESSION_LOCALE_KEY, SESSION_TIMEZONE_KEY};
+pub use metrics::{DenialCount, DenialMetrics};
+pub use ip::{IpCidr, resolve_client_ip};
+pub use read_only::{ReadOnlyMode, READ_ONLY_OVERRIDE_PERMISSION};
+#[cfg(feature = "runtime")]
+pub use idempotency::{IdempotencyStore, IDEMPOTENCY_KEY_HEADER};
+#[cfg(feature = "runtime")]
+pub use decision::{DecisionLog, DecisionStep, DecisionTrace, DECISION_TRACE_HEADER, REQUEST_ID_HEADER};
+#[cfg(feature = "runtime")]
+pub use extractor::{TokenExtractor, TokenExtractorSource, TokenRequest, TokenSource};
+#[cfg(feature = "runtime")]
+pub use migration::{LegacySessionImporter, ImportedSession, ActixIdentityImporter, TowerSessionsImporter};
+#[cfg(feature = "runtime")]
+pub use status::{TokenStatus, TokenStatusReport, TokenStatusWatcher, StatusWatchOptions};
+#[cfg(feature = "runtime")]
+pub use prefetch::AuthzInvalidationListener;
+#[cfg(feature = "runtime")]
+pub use check::{SaCheck, CheckMode};
+#[cfg(feature = "runtime")]
+pub use render::{ErrorRenderer, ErrorResponse, DefaultErrorRenderer, ProblemJsonRenderer, JSON_CONTENT_TYPE, PROBLEM_JSON_CONTENT_TYPE};
+#[cfg(feature = "runtime")]
+pub use permission::{PayloadGuard, ThresholdGuard, DEFAULT_PAYLOAD_LIMIT};
+#[cfg(feature = "runtime")]
+pub use ban::{DisableInfo, DEFAULT_DISABLE_SERVICE, NEVER_UNTIE};
+#[cfg(feature = "runtime")]
+pub use supervisor::StorageSupervisor;
+#[cfg(feature = "l1-cache")]
+pub use l1_cache::{CachedStorage, CacheStats};
 
 // 重新导出核心类型
-pub use token::{TokenInfo, TokenValue, JwtManager, JwtClaims, JwtAlgorithm};
-pub use session::SaSession;
-pub use permission::{PermissionChecker, RoleChecker};
+pub use token::{TokenInfo, TokenValue, TokenGenerator, DefaultTokenGenerator, JwtManager, JwtClaims, JwtAlgorithm, JwtKeyStore, JwtSecret};
+pub use session::{SaSession, SessionUsage};
+pub use permission::{
+    PermissionChecker, RoleChecker, AuthzSnapshot, permission_matches, has_permission_in, PermissionMatcher, DEFAULT_PERMISSION_SEPARATOR,
+    AuthzKind, AuthzNamingRules, LintIssue, LintKind, lint_names,
+};
 pub use event::{
     SaTokenEvent, SaTokenEventType, SaTokenListener, 
-    SaTokenEventBus, LoggingListener
+    SaTokenEventBus, LoggingListener, EventDispatchMode, QueueOverflow
 };
+#[cfg(feature = "runtime")]
 pub use nonce::NonceManager;
-pub use refresh::RefreshTokenManager;
-pub use oauth2::{OAuth2Manager, OAuth2Client, AuthorizationCode, AccessToken, OAuth2TokenInfo};
+#[cfg(feature = "runtime")]
+pub use refresh::{RefreshTokenManager, RefreshResult};
+#[cfg(feature = "runtime")]
+pub use temp::TempTokenManager;
+#[cfg(feature = "runtime")]
+pub use same_token::{SameTokenManager, DEFAULT_SAME_TOKEN_TIMEOUT};
+#[cfg(feature = "runtime")]
+pub use audit::{AuditLogger, AuditRecord, AuditPage, AuditListener, StorageAuditLogger, DEFAULT_AUDIT_RETENTION};
+#[cfg(feature = "runtime")]
+pub use api_key::{ApiKeyManager, ApiKeyInfo, API_KEY_HEADER, API_KEY_LOGIN_TYPE};
+pub use http_basic::{HttpBasicAuth, parse_http_basic, http_basic_header, DEFAULT_HTTP_BASIC_REALM};
+#[cfg(feature = "runtime")]
+pub use sign::{SignManager, SIGN_PARAM, SIGN_TIMESTAMP_PARAM, SIGN_NONCE_PARAM, DEFAULT_SIGN_TIMESTAMP_WINDOW};
+#[cfg(feature = "runtime")]
+pub use routing::{RoutingStorage, RoutingStats, locality_hint, LOCALITY_HINT_SEPARATOR};
+#[cfg(feature = "runtime")]
+pub use sa_token_adapter::clock::{Clock, SystemClock, MockClock};
+#[cfg(feature = "runtime")]
+pub use time::DefaultClock;
+#[cfg(feature = "runtime")]
+pub use oauth2::{OAuth2Manager, OAuth2Client, AuthorizationCode, AccessToken, OAuth2TokenInfo, PkceMethod, GRANT_TYPE_CLIENT_CREDENTIALS, GRANT_TYPE_PASSWORD,
+    IntrospectionResponse, OAUTH2_INTROSPECT_PATH, OAUTH2_REVOKE_PATH,
+};
+#[cfg(feature = "runtime")]
 pub use ws::{WsAuthManager, WsAuthInfo, WsTokenExtractor, DefaultWsTokenExtractor};
-pub use online::{OnlineManager, OnlineUser, PushMessage, MessageType, MessagePusher, InMemoryPusher};
+#[cfg(feature = "runtime")]
+pub use ws_registry::{WsConnectionRegistry, WsConnection, WsOutbound, WsCloseReason, WebSocketPusher};
+#[cfg(feature = "runtime")]
+pub use sse::{SsePusher, SseSubscription, SseFrame, DEFAULT_SSE_HEARTBEAT, SSE_MESSAGE_EVENT};
+#[cfg(feature = "runtime")]
+pub use online::{OnlineManager, OnlineUser, OnlinePage, PushMessage, MessageType, MessagePusher, InMemoryPusher, LoginRecord};
+#[cfg(feature = "runtime")]
 pub use distributed::{
     DistributedSessionManager, DistributedSession, DistributedSessionStorage,
     ServiceCredential, InMemoryDistributedStorage
 };
+#[cfg(feature = "runtime")]
 pub use sso::{
-    SsoServer, SsoClient, SsoManager, SsoTicket, SsoSession, SsoConfig
+    SsoServer, SsoClient, SsoManager, SsoTicket, SsoSession, SsoConfig,
+    SsoDiscoveryDocument, SsoPublicKey, SSO_DISCOVERY_PATH,
+    SsoCredentialValidator, SsoLoginResult,
+    SSO_AUTH_PATH, SSO_DO_LOGIN_PATH, SSO_CHECK_TICKET_PATH, SSO_SIGNOUT_PATH,
 };
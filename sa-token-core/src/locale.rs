@@ -0,0 +1,105 @@
+// Author: 金书记
+//
+//! 客户端语言与时区 | Client locale and timezone
+//!
+//! 框架插件的认证层从 `Accept-Language` 与 [`TIMEZONE_HEADER`] 请求头中读取语言和时区，
+//! 写入 `SaTokenContext`；登录时保存到账号 Session 的 [`SESSION_LOCALE_KEY`] /
+//! [`SESSION_TIMEZONE_KEY`] 下，下游服务通过 `StpUtil::get_locale_current()` 等方法
+//! 读取，无需各自解析请求头。
+//!
+//! The plugins' token layers read the locale from `Accept-Language` and the
+//! timezone from [`TIMEZONE_HEADER`] into the `SaTokenContext`. Logging in
+//! saves them to the account session under [`SESSION_LOCALE_KEY`] and
+//! [`SESSION_TIMEZONE_KEY`], so downstream code calls
+//! `StpUtil::get_locale_current()` instead of parsing headers itself.
+//!
+//! ```rust,ignore
+//! // 前端：fetch("/login", { headers: { "X-Timezone": Intl.DateTimeFormat().resolvedOptions().timeZone } })
+//! let token = StpUtil::login("user_1").await?;
+//!
+//! // 之后的任意请求 | Any later request
+//! let locale = StpUtil::get_locale_current().await?.unwrap_or_else(|| "en".to_string());
+//! ```
+
+/// 语言请求头 | Locale request header
+pub const ACCEPT_LANGUAGE_HEADER: &str = "Accept-Language";
+
+/// 时区请求头，值为 IANA 时区名（如 `Asia/Shanghai`）或 UTC 偏移（如 `+08:00`）
+///
+/// Timezone request header: an IANA name (`Asia/Shanghai`) or a UTC offset (`+08:00`).
+pub const TIMEZONE_HEADER: &str = "X-Timezone";
+
+/// Session 中保存语言的键 | Session key holding the locale
+pub const SESSION_LOCALE_KEY: &str = "locale";
+
+/// Session 中保存时区的键 | Session key holding the timezone
+pub const SESSION_TIMEZONE_KEY: &str = "timezone";
+
+/// 语言标签、时区名的最大长度 | Longest locale tag or timezone name accepted
+const MAX_LEN: usize = 64;
+
+/// 取 `Accept-Language` 中权重最高的语言 | Preferred language of an `Accept-Language` value
+///
+/// 忽略 `*` 与 `q=0` 的条目，权重相同时取先出现的。
+/// Skips `*` and `q=0` entries; ties go to the earlier entry.
+pub fn parse_accept_language(value: &str) -> Option<String> {
+    let mut best: Option<(&str, f32)> = None;
+    for entry in value.split(',') {
+        let mut parts = entry.split(';');
+        let tag = parts.next().unwrap_or_default().trim();
+        let q = parts
+            .find_map(|param| param.trim().strip_prefix("q="))
+            .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok());
+        let Some(q) = q else {
+            continue;
+        };
+        if tag.is_empty() || tag == "*" || q <= 0.0 || !is_language_tag(tag) {
+            continue;
+        }
+        if best.is_none_or(|(_, best_q)| q > best_q) {
+            best = Some((tag, q));
+        }
+    }
+    best.map(|(tag, _)| tag.to_string())
+}
+
+/// 校验时区请求头的值 | Validate a timezone header value
+///
+/// 只检查格式（IANA 名称或 `±HH:MM` 偏移），不核对时区数据库。
+/// Checks the shape only (an IANA name or a `±HH:MM` offset), not the tz database.
+pub fn parse_timezone(value: &str) -> Option<String> {
+    let value = value.trim();
+    let valid = !value.is_empty()
+        && value.len() <= MAX_LEN
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || "/_+-:".contains(c))
+        && value.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '+' || c == '-');
+    valid.then(|| value.to_string())
+}
+
+fn is_language_tag(tag: &str) -> bool {
+    tag.len() <= MAX_LEN
+        && tag.split('-').all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_accept_language() {
+        assert_eq!(parse_accept_language("zh-CN,zh;q=0.9,en;q=0.8").as_deref(), Some("zh-CN"));
+        assert_eq!(parse_accept_language("en;q=0.5, fr-CA;q=0.8, *").as_deref(), Some("fr-CA"));
+        assert_eq!(parse_accept_language("de;q=0, *;q=0.1"), None);
+        assert_eq!(parse_accept_language("<script>"), None);
+        assert_eq!(parse_accept_language(""), None);
+    }
+
+    #[test]
+    fn test_parse_timezone() {
+        assert_eq!(parse_timezone(" Asia/Shanghai ").as_deref(), Some("Asia/Shanghai"));
+        assert_eq!(parse_timezone("+08:00").as_deref(), Some("+08:00"));
+        assert_eq!(parse_timezone("America/Argentina/Buenos_Aires").as_deref(), Some("America/Argentina/Buenos_Aires"));
+        assert_eq!(parse_timezone("../etc/passwd"), None);
+        assert_eq!(parse_timezone(""), None);
+    }
+}
@@ -0,0 +1,256 @@
+// Author: 金书记
+//
+//! 登录参数 | Login Options
+//!
+//! ```rust,ignore
+//! use serde_json::json;
+//!
+//! let token = StpUtil::login_with_options("user_123", LoginOptions::new()
+//!     .login_type("admin")
+//!     .device("pc")
+//!     .timeout(3600)
+//!     .claim("role", json!("admin"))
+//!     .claim("permissions", json!(["user:read", "user:write"]))
+//! ).await?;
+//! ```
+//!
+//! 使用 `TokenStyle::Jwt` 时，自定义声明写入 JWT 的 `extra` 字段，登录类型、设备、
+//! 有效期分别写入 `login_type`、`device`、`exp`；其他风格的 token 忽略自定义声明。
+//!
+//! With `TokenStyle::Jwt`, custom claims go into the `extra` claim and login
+//! type, device and timeout into `login_type`, `device` and `exp`. Other token
+//! styles ignore the custom claims.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+use tokio::sync::OnceCell;
+use crate::session::SaSession;
+use crate::token::{TokenInfo, TokenValue};
+
+/// 登录参数 | Login options
+#[derive(Debug, Clone, Default)]
+pub struct LoginOptions {
+    /// 登录类型，None 为 `default` | Login type, `default` when `None`
+    pub login_type: Option<String>,
+
+    /// 设备标识 | Device identifier
+    pub device: Option<String>,
+
+    /// 覆盖配置中的 timeout（秒）| Overrides the configured timeout (seconds)
+    pub timeout: Option<i64>,
+
+    /// JWT 自定义声明 | Custom JWT claims
+    pub claims: HashMap<String, Value>,
+
+    /// 写入 TokenInfo 的额外数据 | Extra data kept in the TokenInfo
+    pub extra_data: Option<Value>,
+
+    /// "记住我"：`Some(true)` 使用 `remember_timeout` 与持久 Cookie，`Some(false)` 写回会话 Cookie
+    ///
+    /// Remember me: `Some(true)` uses `remember_timeout` and a persistent
+    /// cookie, `Some(false)` writes a session cookie.
+    pub remember: Option<bool>,
+}
+
+impl LoginOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置登录类型 | Set login type
+    pub fn login_type(mut self, login_type: impl Into<String>) -> Self {
+        self.login_type = Some(login_type.into());
+        self
+    }
+
+    /// 设置设备标识 | Set device
+    pub fn device(mut self, device: impl Into<String>) -> Self {
+        self.device = Some(device.into());
+        self
+    }
+
+    /// 设置本次登录的有效期（秒）| Set the timeout of this login (seconds)
+    pub fn timeout(mut self, seconds: i64) -> Self {
+        self.timeout = Some(seconds);
+        self
+    }
+
+    /// 添加一个 JWT 自定义声明 | Add a custom JWT claim
+    pub fn claim(mut self, key: impl Into<String>, value: Value) -> Self {
+        self.claims.insert(key.into(), value);
+        self
+    }
+
+    /// 批量设置 JWT 自定义声明 | Set custom JWT claims
+    pub fn claims(mut self, claims: HashMap<String, Value>) -> Self {
+        self.claims.extend(claims);
+        self
+    }
+
+    /// 设置额外数据 | Set extra data
+    pub fn extra_data(mut self, data: Value) -> Self {
+        self.extra_data = Some(data);
+        self
+    }
+
+    /// 设置是否"记住我" | Set whether to remember the login
+    pub fn remember(mut self, remember: bool) -> Self {
+        self.remember = Some(remember);
+        self
+    }
+}
+
+/// 登录结果 | Login result
+///
+/// 由 `login_ex` 返回，免去登录后再读取一次 TokenInfo 的存储往返。
+/// Returned by `login_ex`, saving the storage round trip to read the
+/// TokenInfo back after logging in.
+#[derive(Debug, Clone)]
+pub struct LoginResult {
+    /// 新签发的 token | Issued token
+    pub token: TokenValue,
+
+    /// 已写入存储的 token 信息 | Token info as stored
+    pub token_info: TokenInfo,
+
+    /// 账号会话，新建的会话尚未保存 | Account session; a new one is not saved yet
+    pub session: SaSession,
+
+    /// 登录前是否还没有会话 | Whether the account had no session before this login
+    pub is_new_session: bool,
+
+    /// 不允许并发登录时被顶替下线的 token | Tokens logged out because concurrent login is off
+    pub replaced_tokens: Vec<TokenValue>,
+}
+
+/// 并发登录去重 | In-flight login deduplication
+///
+/// 同一登录键在窗口期内共享一个结果槽位：第一个调用者签发 token，并发或随后到达的
+/// 调用者等待并拿到同一个 token。签发失败不会被缓存。
+/// Logins with the same key share one result slot for the window: the first
+/// caller issues the token, concurrent or later callers wait for it and get
+/// the same one. Failures are not cached.
+#[derive(Default)]
+pub(crate) struct LoginDedup {
+    slots: Mutex<HashMap<String, (DateTime<Utc>, LoginSlot)>>,
+}
+
+/// 一次登录的结果槽位 | Result slot of one login
+pub(crate) type LoginSlot = Arc<OnceCell<TokenValue>>;
+
+impl LoginDedup {
+    /// 取得登录键对应的槽位，顺带清理超出窗口期的槽位 | Slot for a login key; expired slots are dropped
+    pub(crate) fn slot(&self, key: &str, window: Duration) -> LoginSlot {
+        let now = crate::time::now();
+        let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+        slots.retain(|_, (started_at, _)| now - *started_at < window);
+        slots.entry(key.to_string())
+            .or_insert_with(|| (now, Arc::new(OnceCell::new())))
+            .1
+            .clone()
+    }
+
+    /// 丢弃槽位，下一次登录重新签发 | Drop a slot so the next login issues a new token
+    pub(crate) fn forget(&self, key: &str) {
+        self.slots.lock().unwrap_or_else(|e| e.into_inner()).remove(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use serde_json::json;
+    use sa_token_storage_memory::MemoryStorage;
+    use crate::config::TokenStyle;
+    use crate::token::JwtManager;
+    use crate::{SaTokenConfig, SaTokenManager};
+    use super::*;
+
+    fn jwt_manager() -> SaTokenManager {
+        let config = SaTokenConfig::builder()
+            .token_style(TokenStyle::Jwt)
+            .jwt_secret_key("login-options-secret")
+            .build_config();
+        SaTokenManager::new(Arc::new(MemoryStorage::new()), config)
+    }
+
+    #[tokio::test]
+    async fn test_jwt_carries_custom_claims() {
+        let manager = jwt_manager();
+        let token = manager.login_with("user_1", LoginOptions::new()
+            .login_type("admin")
+            .device("pc")
+            .claim("role", json!("admin"))
+            .claim("permissions", json!(["user:read"]))
+        ).await.unwrap();
+
+        let claims = JwtManager::new("login-options-secret").validate(token.as_str()).unwrap();
+        assert_eq!(claims.login_id, "user_1");
+        assert_eq!(claims.login_type.as_deref(), Some("admin"));
+        assert_eq!(claims.device.as_deref(), Some("pc"));
+        assert_eq!(claims.get_claim("role"), Some(&json!("admin")));
+        assert_eq!(claims.get_claim("permissions"), Some(&json!(["user:read"])));
+
+        let info = manager.get_token_info(&token).await.unwrap();
+        assert_eq!(info.login_type, "admin");
+        assert_eq!(info.device.as_deref(), Some("pc"));
+    }
+
+    #[tokio::test]
+    async fn test_renewed_jwt_keeps_login_options() {
+        let manager = jwt_manager();
+        let token = manager.login_with("user_1", LoginOptions::new()
+            .login_type("admin")
+            .device("pc")
+            .timeout(600)
+            .claim("role", json!("admin"))
+        ).await.unwrap();
+
+        let renewed = manager.renew_token(&token).await.unwrap();
+        let claims = JwtManager::new("login-options-secret").validate(renewed.as_str()).unwrap();
+        assert_eq!(claims.login_type.as_deref(), Some("admin"));
+        assert_eq!(claims.device.as_deref(), Some("pc"));
+        assert_eq!(claims.get_claim("role"), Some(&json!("admin")));
+        // exp 沿用旧 token 的剩余时间，而不是配置的 timeout
+        let remaining = claims.remaining_time().unwrap();
+        assert!(remaining > 0 && remaining <= 600);
+    }
+
+    #[tokio::test]
+    async fn test_timeout_override() {
+        let manager = jwt_manager();
+        let token = manager.login_with("user_2", LoginOptions::new().timeout(60)).await.unwrap();
+
+        let claims = JwtManager::new("login-options-secret").validate(token.as_str()).unwrap();
+        let remaining = claims.remaining_time().unwrap();
+        assert!(remaining > 0 && remaining <= 60);
+
+        let info = manager.get_token_info(&token).await.unwrap();
+        let expire_in = (info.expire_time.unwrap() - crate::time::now()).num_seconds();
+        assert!(expire_in > 0 && expire_in <= 60);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_logins_are_deduplicated() {
+        let config = SaTokenConfig::builder().login_dedup_window(2).build_config();
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+
+        let (a, b) = tokio::join!(manager.login("user_1"), manager.login("user_1"));
+        let token = a.unwrap();
+        assert_eq!(token, b.unwrap());
+        let pc = manager.login_with("user_1", LoginOptions::new().device("pc")).await.unwrap();
+        assert_ne!(pc, token);
+
+        // 已注销的 token 不再复用 | A logged-out token is not handed out again
+        manager.logout(&token).await.unwrap();
+        let relogin = manager.login("user_1").await.unwrap();
+        assert_ne!(relogin, token);
+        assert!(manager.is_valid(&relogin).await);
+
+        crate::time::advance(Duration::seconds(3));
+        assert_ne!(manager.login("user_1").await.unwrap(), relogin);
+        crate::time::reset();
+    }
+}
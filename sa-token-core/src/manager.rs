@@ -6,44 +6,291 @@ use std::sync::Arc;
 use std::collections::HashMap;
 use chrono::{DateTime, Duration, Utc};
 use tokio::sync::RwLock;
-use sa_token_adapter::storage::SaStorage;
-use crate::config::SaTokenConfig;
+use sa_token_adapter::storage::{KeyTtl, SaStorage};
+use sa_token_adapter::clock::Clock;
+use sa_token_adapter::context::{CookieOptions, ResponseCookie, SaRequest};
+use crate::config::{LoginEviction, SaTokenConfig, TokenStyle, MAX_KICK_OUT_GRACE_PERIOD};
+use crate::context::SaTokenContext;
 use crate::error::{SaTokenError, SaTokenResult};
-use crate::token::{TokenInfo, TokenValue, TokenGenerator};
-use crate::session::SaSession;
+use crate::token::{TokenInfo, TokenValue, DefaultTokenGenerator, JwtManager};
+use crate::session::{SaSession, SessionUsage};
 use crate::event::{SaTokenEventBus, SaTokenEvent};
-use crate::online::OnlineManager;
+use crate::online::{OnlineManager, LoginRecord};
 use crate::distributed::DistributedSessionManager;
+use crate::idempotency::IdempotencyStore;
+use crate::permission::{lint_names, PermissionMatcher, AuthzKind, AuthzSnapshot, LintIssue, PermissionChecker, RoleChecker};
+use crate::prefetch::{authz_version_key, AuthzInvalidationListener};
+use crate::read_only::{is_mutating_method, ReadOnlyMode};
+use crate::decision::{DecisionLog, DecisionTrace};
+use crate::metrics::{DenialCount, DenialMetrics};
+use crate::extractor::{TokenExtractor, TokenExtractorSource, TokenRequest, TokenSource};
+use crate::migration::LegacySessionImporter;
+use crate::login::{LoginDedup, LoginOptions, LoginResult};
+use crate::ban::{disable_key, DisableInfo, DEFAULT_DISABLE_SERVICE};
+use crate::locale::{SESSION_LOCALE_KEY, SESSION_TIMEZONE_KEY};
+use crate::status::{StatusWatchOptions, TokenStatus, TokenStatusReport, TokenStatusWatcher};
+use crate::render::{DefaultErrorRenderer, ErrorRenderer, ErrorResponse};
+use crate::same_token::SameTokenManager;
+use crate::ip::{resolve_client_ip, same_ip};
+use crate::audit::{AuditListener, AuditLogger};
+use crate::policy::{LoginContext, LoginPolicy, LoginPolicyDecision, SessionValidation, SessionValidator, TtlPolicy, PASSWORD_CHANGE_SCOPE};
+
+/// 登录历史最多保留的条数
+const LOGIN_HISTORY_LIMIT: usize = 20;
+
+/// 只读模式设置的存储 key
+const READ_ONLY_MODE_KEY: &str = "sa:read-only-mode";
+
+/// 踢人宽限期内保留的 token 记录（墓碑）
+#[derive(serde::Serialize, serde::Deserialize)]
+struct RevokedToken {
+    revoked_at: DateTime<Utc>,
+    token_info: TokenInfo,
+}
+
+/// 永久有效的 token 写入 Cookie 时使用的 `Max-Age`（约 68 年）
+const PERMANENT_COOKIE_MAX_AGE: i64 = i32::MAX as i64;
+
+fn revoked_token_key(token: &str) -> String {
+    format!("sa:token-revoked:{}", token)
+}
+
+/// 被踢 token 的踢人原因标记，保留到 token 原本的过期时间
+fn kicked_token_key(token: &str) -> String {
+    format!("sa:token-kicked:{}", token)
+}
+
+/// 被新登录顶替的 token 的标记，保留到 token 原本的过期时间
+fn replaced_token_key(token: &str) -> String {
+    format!("sa:token-replaced:{}", token)
+}
+
+/// 永久 token 失效后，踢人/顶替标记的保留时长（秒）
+const TOKEN_MARKER_TTL: i64 = 7 * 24 * 3600;
+
+/// 两份配置中取值不同的字段名（按序列化后的字段比较）
+fn changed_config_fields(previous: &SaTokenConfig, current: &SaTokenConfig) -> Vec<String> {
+    let (Ok(serde_json::Value::Object(previous)), Ok(serde_json::Value::Object(current))) =
+        (serde_json::to_value(previous), serde_json::to_value(current))
+    else {
+        return Vec::new();
+    };
+    current.iter()
+        .filter(|(field, value)| previous.get(*field) != Some(value))
+        .map(|(field, _)| field.clone())
+        .collect()
+}
+
+/// 审计日志中的操作人：当前请求上下文的登录 ID，无上下文时为 system
+fn audit_operator() -> String {
+    SaTokenContext::get_current()
+        .and_then(|ctx| ctx.login_id)
+        .unwrap_or_else(|| "system".to_string())
+}
+
+/// login_id -> token 映射的存储 key，非默认登录类型时带上登录类型避免冲突
+pub(crate) fn login_token_key(login_id: &str, login_type: &str) -> String {
+    if !login_type.is_empty() && login_type != "default" {
+        format!("sa:login:token:{}:{}", login_id, login_type)
+    } else {
+        format!("sa:login:token:{}", login_id)
+    }
+}
+
+/// 默认登录类型 | The default login type
+pub const DEFAULT_LOGIN_TYPE: &str = "default";
+
+/// 账号在其登录类型下的 ID：默认类型保持原样，其他类型为 `{login_type}:{login_id}`
+/// 
+/// Session、权限/角色映射与封禁记录以该 ID 为键，不同账号体系中的同名账号互不影响。
+/// Sessions, permission/role maps and bans are keyed by it, so equal IDs in
+/// different account systems stay apart.
+pub fn account_key(login_id: &str, login_type: &str) -> String {
+    if login_type.is_empty() || login_type == DEFAULT_LOGIN_TYPE {
+        login_id.to_string()
+    } else {
+        format!("{}:{}", login_type, login_id)
+    }
+}
+
+/// 把 `extra` 中尚未出现的元素追加到 `items`
+fn merge_unique(items: &mut Vec<String>, extra: Option<&Vec<String>>) {
+    for item in extra.into_iter().flatten() {
+        if !items.contains(item) {
+            items.push(item.clone());
+        }
+    }
+}
+
+/// 设备 -> token 索引的存储 key，按账号在其登录类型下的 ID 区分，不同账号体系的同名账号互不顶替
+pub(crate) fn device_token_key(login_id: &str, login_type: &str, device: &str) -> String {
+    format!("sa:login:device:{}:{}", account_key(login_id, login_type), device)
+}
+
+/// 账号在其登录类型下的 token 索引（JSON 数组），同时在线数量限制（`max_login_count`）用它代替扫描全部 token
+fn login_tokens_index_key(login_id: &str, login_type: &str) -> String {
+    format!("sa:login:tokens:{}", account_key(login_id, login_type))
+}
+
+/// token 最后活跃时间（Unix 秒）的存储键
+fn activity_key(token: &TokenValue) -> String {
+    format!("sa:token-active:{}", token.as_str())
+}
 
 /// sa-token 管理器
 #[derive(Clone)]
 pub struct SaTokenManager {
     pub(crate) storage: Arc<dyn SaStorage>,
-    pub config: SaTokenConfig,
+    /// 当前配置，`update_config` 整体替换，各克隆共享
+    config: Arc<std::sync::RwLock<Arc<SaTokenConfig>>>,
     /// 用户权限映射 user_id -> permissions
     pub(crate) user_permissions: Arc<RwLock<HashMap<String, Vec<String>>>>,
     /// 用户角色映射 user_id -> roles
     pub(crate) user_roles: Arc<RwLock<HashMap<String, Vec<String>>>>,
+    /// 自定义权限检查器，优先于内存映射
+    permission_checker: Arc<std::sync::RwLock<Option<Arc<dyn PermissionChecker>>>>,
+    /// 自定义角色检查器，优先于内存映射
+    role_checker: Arc<std::sync::RwLock<Option<Arc<dyn RoleChecker>>>>,
     /// 事件总线
     pub(crate) event_bus: SaTokenEventBus,
     /// 在线用户管理器
     online_manager: Option<Arc<OnlineManager>>,
     /// 分布式 Session 管理器
     distributed_manager: Option<Arc<DistributedSessionManager>>,
+    /// 登录策略钩子
+    login_policy: Option<Arc<dyn LoginPolicy>>,
+    /// token 有效期策略
+    ttl_policy: Option<Arc<dyn TtlPolicy>>,
+    /// 会话复查钩子
+    session_validator: Option<Arc<dyn SessionValidator>>,
+    /// 请求鉴权决策日志（调试模式）
+    decision_log: Arc<DecisionLog>,
+    /// 鉴权拒绝统计
+    denial_metrics: Arc<DenialMetrics>,
+    /// 自定义 token 提取器（按优先级排序）
+    token_extractors: Vec<Arc<dyn TokenExtractor>>,
+    /// 具名的自定义 token 来源，由 `config.token_sources` 决定调用顺序
+    custom_token_sources: Vec<Arc<dyn TokenExtractorSource>>,
+    /// 旧认证库的会话导入器
+    legacy_importers: Vec<Arc<dyn LegacySessionImporter>>,
+    /// token 状态监听（长轮询）
+    status_watcher: Arc<TokenStatusWatcher>,
+    /// 并发登录去重
+    login_dedup: Arc<LoginDedup>,
+    /// 插件拒绝请求时的响应渲染器
+    error_renderer: Arc<dyn ErrorRenderer>,
+    /// 审计日志
+    audit_logger: Option<Arc<dyn AuditLogger>>,
+    /// 过期判断使用的时钟
+    clock: Arc<dyn Clock>,
 }
 
 impl SaTokenManager {
     /// 创建新的管理器实例
-    pub fn new(storage: Arc<dyn SaStorage>, config: SaTokenConfig) -> Self {
+    pub fn new(storage: Arc<dyn SaStorage>, mut config: SaTokenConfig) -> Self {
+        Self::prepare_config(&mut config);
+        
+        let event_bus = SaTokenEventBus::with_dispatch_mode(config.event_dispatch);
+        let status_watcher = Arc::new(TokenStatusWatcher::new());
+        // 状态推送与权限缓存失效必须在调用返回前完成，不受分发方式影响
+        event_bus.register_inline(status_watcher.clone());
+        event_bus.register_inline(Arc::new(AuthzInvalidationListener::new(storage.clone())));
+        if config.is_log {
+            event_bus.register(Arc::new(crate::event::LoggingListener::from_config(&config)));
+        }
+        
         Self { 
             storage, 
-            config,
+            config: Arc::new(std::sync::RwLock::new(Arc::new(config))),
             user_permissions: Arc::new(RwLock::new(HashMap::new())),
             user_roles: Arc::new(RwLock::new(HashMap::new())),
-            event_bus: SaTokenEventBus::new(),
+            permission_checker: Arc::new(std::sync::RwLock::new(None)),
+            role_checker: Arc::new(std::sync::RwLock::new(None)),
+            event_bus,
             online_manager: None,
             distributed_manager: None,
+            login_policy: None,
+            ttl_policy: None,
+            session_validator: None,
+            decision_log: Arc::new(DecisionLog::default()),
+            denial_metrics: Arc::new(DenialMetrics::default()),
+            token_extractors: Vec::new(),
+            custom_token_sources: Vec::new(),
+            legacy_importers: Vec::new(),
+            status_watcher,
+            login_dedup: Arc::new(LoginDedup::default()),
+            error_renderer: Arc::new(DefaultErrorRenderer),
+            audit_logger: None,
+            clock: Arc::new(crate::time::DefaultClock),
+        }
+    }
+    
+    /// 校验并补全配置：拒绝 release 构建中的 dev_bypass、加载 JWT 密钥文件、检查 Cookie 域名
+    fn prepare_config(config: &mut SaTokenConfig) {
+        if config.dev_bypass {
+            if SaTokenConfig::dev_bypass_allowed() {
+                tracing::warn!(
+                    "Sa-Token: dev_bypass 已开启，所有鉴权检查都将以 login_id={} 通过，切勿用于生产环境",
+                    config.dev_bypass_login_id
+                );
+            } else {
+                tracing::error!(
+                    "Sa-Token: release 构建中拒绝开启 dev_bypass（如确需开启请设置 {}=1）",
+                    crate::config::DEV_BYPASS_ENV
+                );
+                config.dev_bypass = false;
+            }
+        }
+        
+        if let Err(e) = config.load_jwt_key_files() {
+            tracing::error!("Sa-Token: {}", e);
+        }
+        config.apply_cookie_domain_check();
+    }
+    
+    /// 当前配置的快照 | Snapshot of the current configuration
+    /// 
+    /// 返回的快照不随 `update_config` 变化，每次使用时重新获取即可读到最新值。
+    /// The snapshot does not follow later `update_config` calls; call again to
+    /// see the latest values.
+    pub fn config(&self) -> Arc<SaTokenConfig> {
+        self.config.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+    
+    /// 运行时替换配置，无需重启服务 | Replace the configuration at runtime
+    /// 
+    /// 新配置对之后的调用立即生效（`timeout`、`active_timeout`、`is_concurrent`、
+    /// `token_name` 等），所有共享该管理器的克隆（包括 `StpUtil` 与插件状态）同时生效；
+    /// 已签发 token 的过期时间不变。创建管理器时就已确定的部分不受影响，如 `is_log`
+    /// 注册的日志监听器。替换后发布 `ConfigChanged` 事件，`extra.changed` 为变化的字段名。
+    /// 
+    /// The new values apply to every later call on this manager and all of its
+    /// clones (including `StpUtil` and plugin states). Tokens already issued
+    /// keep their expiry. Things wired up at construction, such as the
+    /// `is_log` listener, are not affected. Publishes a `ConfigChanged` event
+    /// whose `extra.changed` lists the changed field names.
+    /// 
+    /// # 示例 | Example
+    /// ```rust,ignore
+    /// let mut config = (*manager.config()).clone();
+    /// config.timeout = 3600;
+    /// config.is_concurrent = false;
+    /// manager.update_config(config).await;
+    /// ```
+    pub async fn update_config(&self, mut config: SaTokenConfig) {
+        Self::prepare_config(&mut config);
+        let config = Arc::new(config);
+        let previous = std::mem::replace(
+            &mut *self.config.write().unwrap_or_else(|e| e.into_inner()),
+            config.clone(),
+        );
+        
+        if previous.event_dispatch != config.event_dispatch {
+            self.event_bus.set_dispatch_mode(config.event_dispatch);
         }
+        let changed = changed_config_fields(&previous, &config);
+        tracing::info!("Sa-Token: 配置已更新，变化的字段: {:?}", changed);
+        self.event_bus.publish(SaTokenEvent::config_changed(changed)).await;
     }
     
     pub fn with_online_manager(mut self, manager: Arc<OnlineManager>) -> Self {
@@ -56,6 +303,321 @@ impl SaTokenManager {
         self
     }
     
+    /// 设置登录策略钩子（例如密码过期检查）
+    pub fn with_login_policy(mut self, policy: Arc<dyn LoginPolicy>) -> Self {
+        self.login_policy = Some(policy);
+        self
+    }
+    
+    /// 设置过期判断使用的时钟，测试中可传入 `MockClock` 控制时间
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    /// 管理器的当前时间，token 的签发、过期与活跃判断都以它为准
+    /// 
+    /// The manager's current time; token issue, expiry and activity checks use it.
+    pub fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+    
+    /// 设置 token 有效期策略（按风险信号动态决定有效期）
+    pub fn with_ttl_policy(mut self, policy: Arc<dyn TtlPolicy>) -> Self {
+        self.ttl_policy = Some(policy);
+        self
+    }
+    
+    /// 设置会话复查钩子（按间隔复查业务规则，不通过时自动登出）
+    pub fn with_session_validator(mut self, validator: Arc<dyn SessionValidator>) -> Self {
+        self.session_validator = Some(validator);
+        self
+    }
+    
+    /// 注册自定义 token 提取器，插件中间件会在内置提取逻辑之前按优先级调用
+    /// 
+    /// Registers a custom token extractor consulted by priority before the built-in logic.
+    pub fn with_token_extractor(mut self, extractor: Arc<dyn TokenExtractor>) -> Self {
+        self.token_extractors.push(extractor);
+        // 稳定排序，同优先级保持注册顺序
+        self.token_extractors.sort_by_key(|e| e.priority());
+        self
+    }
+    
+    /// 注册具名的自定义 token 来源，需在 `token_sources` 中以 `TokenSource::Custom(name)` 列出
+    /// 
+    /// Registers a named token source; list it in `token_sources` as `TokenSource::Custom(name)`.
+    pub fn with_token_source(mut self, source: Arc<dyn TokenExtractorSource>) -> Self {
+        self.custom_token_sources.push(source);
+        self
+    }
+    
+    /// 注册旧认证库的会话导入器，遇到未知 token 时尝试导入
+    /// 
+    /// Registers a legacy session importer consulted for unknown tokens.
+    pub fn with_legacy_importer(mut self, importer: Arc<dyn LegacySessionImporter>) -> Self {
+        self.legacy_importers.push(importer);
+        self
+    }
+    
+    /// 设置插件拒绝请求时的响应渲染器（状态码、响应头、响应体）
+    /// 
+    /// Sets the renderer plugins use for rejections (status, headers, body).
+    pub fn with_error_renderer(mut self, renderer: Arc<dyn ErrorRenderer>) -> Self {
+        self.error_renderer = renderer;
+        self
+    }
+    
+    /// 设置审计日志，并注册把安全相关事件写入其中的监听器
+    /// 
+    /// Sets the audit logger and registers the listener that records security events into it.
+    pub fn with_audit_logger(mut self, logger: Arc<dyn AuditLogger>) -> Self {
+        self.event_bus.register(Arc::new(AuditListener::new(logger.clone())));
+        self.audit_logger = Some(logger);
+        self
+    }
+    
+    /// 审计日志，未配置时为 None | The audit logger, None when not configured
+    pub fn audit_logger(&self) -> Option<&Arc<dyn AuditLogger>> {
+        self.audit_logger.as_ref()
+    }
+    
+    /// 依次调用自定义提取器，返回第一个提取到的 token
+    pub fn extract_custom_token(&self, request: &dyn TokenRequest) -> Option<String> {
+        let token_name = &self.config().token_name;
+        self.token_extractors
+            .iter()
+            .find_map(|extractor| extractor.extract(request, token_name))
+    }
+    
+    /// 从请求中提取 token，返回 token 及来源类别（`custom`、`header`、`cookie`、`query`、`body`）
+    /// 
+    /// 先按优先级调用自定义提取器，再按 `config.token_sources` 的顺序查找；
+    /// `is_read_header` / `is_read_cookie` / `is_read_body` 为 false 时跳过对应来源。
+    /// 
+    /// Extracts the token and its source kind: custom extractors by priority
+    /// first, then `config.token_sources` in order, skipping the kinds
+    /// disabled by `is_read_header` / `is_read_cookie` / `is_read_body`.
+    pub fn extract_token(&self, request: &dyn TokenRequest) -> Option<(String, &'static str)> {
+        if let Some(token) = self.extract_custom_token(request) {
+            return Some((token, "custom"));
+        }
+        
+        let config = self.config();
+        let token_name = config.token_name.as_str();
+        config.token_sources.iter().find_map(|source| {
+            let token = match source {
+                TokenSource::Header(name) if config.is_read_header => request
+                    .header(name.as_deref().unwrap_or(token_name))
+                    .map(|value| config.strip_token_prefix(&value).to_string()),
+                TokenSource::Cookie(name) if config.is_read_cookie => {
+                    request.cookie(name.as_deref().unwrap_or(token_name))
+                }
+                TokenSource::Query(name) => request.param(name.as_deref().unwrap_or(token_name)),
+                TokenSource::Body(name) if config.is_read_body => {
+                    request.body_field(name.as_deref().unwrap_or(token_name))
+                }
+                TokenSource::Custom(name) => self.custom_token_sources
+                    .iter()
+                    .find(|custom| custom.name() == name)
+                    .and_then(|custom| custom.extract(request)),
+                _ => None,
+            };
+            token.filter(|token| !token.is_empty()).map(|token| (token, source.kind()))
+        })
+    }
+    
+    /// 按配置的字段命名风格（`json_field_case`）序列化响应结构体
+    /// 
+    /// Serializes a response struct (e.g. `AccessToken`) using the configured field naming.
+    pub fn response_json<T: serde::Serialize>(&self, value: &T) -> serde_json::Result<serde_json::Value> {
+        self.config().json_field_case.to_value(value)
+    }
+    
+    /// 账号当前的权限版本，每次权限变更事件加一
+    pub async fn authz_version(&self, login_id: &str) -> SaTokenResult<i64> {
+        let value = self.storage.get(&authz_version_key(login_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        Ok(value.and_then(|v| v.parse().ok()).unwrap_or(0))
+    }
+    
+    /// 读取账号当前的权限与角色快照
+    pub async fn prefetch_authz(&self, login_id: &str) -> SaTokenResult<AuthzSnapshot> {
+        let version = self.authz_version(login_id).await?;
+        let permissions = self.get_permissions(login_id).await;
+        let roles = self.get_roles(login_id).await;
+        Ok(AuthzSnapshot { permissions, roles, version })
+    }
+    
+    /// 注册权限检查器（如从数据库按需加载权限），鉴权时先询问检查器，再回退到内存映射
+    /// 
+    /// Registers a permission checker, e.g. one loading permissions lazily from
+    /// a database. Checks consult it before falling back to the in-memory maps.
+    pub fn set_permission_checker(&self, checker: Arc<dyn PermissionChecker>) {
+        *self.permission_checker.write().unwrap_or_else(|e| e.into_inner()) = Some(checker);
+    }
+    
+    /// 注册角色检查器，鉴权时先询问检查器，再回退到内存映射
+    /// 
+    /// Registers a role checker, consulted before the in-memory maps.
+    pub fn set_role_checker(&self, checker: Arc<dyn RoleChecker>) {
+        *self.role_checker.write().unwrap_or_else(|e| e.into_inner()) = Some(checker);
+    }
+    
+    fn permission_checker(&self) -> Option<Arc<dyn PermissionChecker>> {
+        self.permission_checker.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+    
+    fn role_checker(&self) -> Option<Arc<dyn RoleChecker>> {
+        self.role_checker.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+    
+    /// 账号是否拥有指定权限：先询问权限检查器，未授予或出错时回退到内存映射
+    /// 
+    /// 不考虑 dev_bypass 与预取快照，请求中的鉴权请使用 `StpUtil::has_permission`。
+    pub async fn has_permission(&self, login_id: &str, permission: &str) -> bool {
+        if let Some(checker) = self.permission_checker() {
+            match checker.has_permission(login_id, permission).await {
+                Ok(true) => return true,
+                Ok(false) => {}
+                Err(e) => tracing::warn!("Sa-Token: 权限检查器出错，回退到内存映射，login_id: {}, error: {}", login_id, e),
+            }
+        }
+        let matcher = self.permission_matcher();
+        self.user_permissions.read().await.get(login_id)
+            .is_some_and(|permissions| matcher.matches_any(permissions, permission))
+    }
+    
+    /// 按配置的 `permission_separator` 匹配权限的匹配器
+    /// 
+    /// Permission matcher using the configured `permission_separator`.
+    pub fn permission_matcher(&self) -> PermissionMatcher {
+        PermissionMatcher::new(self.config().permission_separator.as_str())
+    }
+    
+    /// 账号是否拥有指定角色：先询问角色检查器，未授予或出错时回退到内存映射
+    pub async fn has_role(&self, login_id: &str, role: &str) -> bool {
+        if let Some(checker) = self.role_checker() {
+            match checker.has_role(login_id, role).await {
+                Ok(true) => return true,
+                Ok(false) => {}
+                Err(e) => tracing::warn!("Sa-Token: 角色检查器出错，回退到内存映射，login_id: {}, error: {}", login_id, e),
+            }
+        }
+        self.user_roles.read().await.get(login_id)
+            .is_some_and(|roles| roles.iter().any(|r| r == role))
+    }
+    
+    /// 账号的所有权限：权限检查器返回的权限与内存映射合并去重
+    pub async fn get_permissions(&self, login_id: &str) -> Vec<String> {
+        let mut permissions = match self.permission_checker() {
+            Some(checker) => checker.get_permissions(login_id).await.unwrap_or_else(|e| {
+                tracing::warn!("Sa-Token: 权限检查器出错，login_id: {}, error: {}", login_id, e);
+                Vec::new()
+            }),
+            None => Vec::new(),
+        };
+        merge_unique(&mut permissions, self.user_permissions.read().await.get(login_id));
+        permissions
+    }
+    
+    /// 账号的所有角色：角色检查器返回的角色与内存映射合并去重
+    pub async fn get_roles(&self, login_id: &str) -> Vec<String> {
+        let mut roles = match self.role_checker() {
+            Some(checker) => checker.get_roles(login_id).await.unwrap_or_else(|e| {
+                tracing::warn!("Sa-Token: 角色检查器出错，login_id: {}, error: {}", login_id, e);
+                Vec::new()
+            }),
+            None => Vec::new(),
+        };
+        merge_unique(&mut roles, self.user_roles.read().await.get(login_id));
+        roles
+    }
+    
+    /// 按配置的命名规则校验权限或角色名称，未配置时直接通过
+    pub fn validate_authz_names(&self, kind: AuthzKind, names: &[String]) -> SaTokenResult<()> {
+        match &self.config().authz_naming_rules {
+            Some(rules) => rules.validate(kind, names),
+            None => Ok(()),
+        }
+    }
+    
+    /// 检查内存中所有账号的权限与角色，列出可疑条目（末尾冒号、重复通配符、被通配符覆盖等）
+    /// 
+    /// Lints the in-memory permission and role maps, sorted by login ID.
+    pub async fn lint_permission_model(&self) -> Vec<LintIssue> {
+        let mut issues = Vec::new();
+        for (kind, map) in [(AuthzKind::Permission, &self.user_permissions), (AuthzKind::Role, &self.user_roles)] {
+            let map = map.read().await;
+            let mut login_ids: Vec<&String> = map.keys().collect();
+            login_ids.sort();
+            for login_id in login_ids {
+                issues.extend(lint_names(kind, &map[login_id]).into_iter().map(|issue| LintIssue {
+                    login_id: Some(login_id.clone()),
+                    ..issue
+                }));
+            }
+        }
+        issues
+    }
+    
+    /// 发布权限变更事件，使该账号已预取的权限快照失效
+    /// 
+    /// Publishes a `PermissionChanged` event, invalidating prefetched snapshots of the account.
+    pub async fn notify_authz_changed(&self, login_id: &str) {
+        self.event_bus.publish(SaTokenEvent::permission_changed(login_id)).await;
+    }
+    
+    /// 开始记录请求的决策日志，未开启 `debug_decision_log` 时返回 None
+    /// 
+    /// Starts a decision trace for a request, `None` unless `debug_decision_log` is on.
+    pub fn start_decision_trace(&self, request_id: Option<String>) -> Option<DecisionTrace> {
+        self.config().debug_decision_log.then(|| DecisionTrace::new(request_id))
+    }
+    
+    /// 请求结束后保存决策日志，之后可按请求 ID 查询
+    pub fn finish_decision_trace(&self, trace: &DecisionTrace) {
+        self.decision_log.store(trace);
+    }
+    
+    /// 最近请求的决策日志
+    pub fn decision_log(&self) -> &DecisionLog {
+        &self.decision_log
+    }
+    
+    /// 记录一次权限/角色拒绝，`route` 为 None 时记为 `unknown`，并发布 `PermissionDenied` 事件
+    pub fn record_denial(&self, route: Option<&str>, kind: AuthzKind, name: &str) {
+        self.denial_metrics.record(route, kind, name);
+        
+        // 拒绝在同步代码中记录，事件交给后台任务发布；任务中读不到请求上下文，来源需先补上
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let ctx = SaTokenContext::get_current().unwrap_or_default();
+        let mut event = SaTokenEvent::permission_denied(ctx.login_id.unwrap_or_default(), kind, name, route);
+        event.ip = ctx.client_ip;
+        event.user_agent = ctx.user_agent;
+        let event_bus = self.event_bus.clone();
+        runtime.spawn(async move { event_bus.publish(event).await });
+    }
+    
+    /// 拒绝次数最多的（路由、权限/角色）组合，用于排查角色分配问题
+    /// 
+    /// The most denied (route, permission or role) pairs, for spotting broken role assignments.
+    pub fn top_denials(&self, limit: usize) -> Vec<DenialCount> {
+        self.denial_metrics.top(limit)
+    }
+    
+    /// 用配置的渲染器把鉴权错误渲染为响应 | Renders an auth error with the configured renderer
+    pub fn render_error(&self, err: &SaTokenError) -> ErrorResponse {
+        self.error_renderer.render(err)
+    }
+    
+    /// 拒绝统计表
+    pub fn denial_metrics(&self) -> &DenialMetrics {
+        &self.denial_metrics
+    }
+    
     pub fn online_manager(&self) -> Option<&Arc<OnlineManager>> {
         self.online_manager.as_ref()
     }
@@ -64,14 +626,222 @@ impl SaTokenManager {
         self.distributed_manager.as_ref()
     }
     
+    /// 开发模式免登录的假 login_id（仅在 dev_bypass 生效时返回）
+    /// 
+    /// Fake login_id used when dev bypass is active, `None` otherwise.
+    pub fn dev_bypass_login_id(&self) -> Option<String> {
+        let config = self.config();
+        if config.is_dev_bypass_active() {
+            Some(config.dev_bypass_login_id.clone())
+        } else {
+            None
+        }
+    }
+    
     /// 获取事件总线的引用
     pub fn event_bus(&self) -> &SaTokenEventBus {
         &self.event_bus
     }
     
+    /// 上报一次认证失败（由框架插件在请求 token 校验失败时调用）
+    /// 
+    /// Report an auth failure; framework plugins call this when the token of a
+    /// request fails validation. Published as `SaTokenEventType::AuthFailed`.
+    pub async fn report_auth_failure(&self, token: &str, reason: &str, ip: Option<&str>) {
+        let mut event = SaTokenEvent::auth_failed(token, reason);
+        if let Some(ip) = ip {
+            event = event.with_ip(ip);
+        }
+        self.event_bus.publish(event).await;
+    }
+    
+    /// token 状态监听器（已注册到事件总线）
+    pub fn status_watcher(&self) -> &Arc<TokenStatusWatcher> {
+        &self.status_watcher
+    }
+    
+    /// 使用本管理器存储与 `same_token_timeout` 的 Same-Token 管理器
+    /// 
+    /// Same-Token manager on this manager's storage, rotating every `same_token_timeout` seconds.
+    pub fn same_token_manager(&self) -> SameTokenManager {
+        SameTokenManager::new(self.storage.clone(), self.config().same_token_timeout)
+    }
+    
     /// 登录：为指定账号创建 token
     pub async fn login(&self, login_id: impl Into<String>) -> SaTokenResult<TokenValue> {
-        self.login_with_options(login_id, None, None, None, None, None).await
+        self.login_with(login_id, LoginOptions::default()).await
+    }
+    
+    /// 按设备登录：同一账号在不同设备上的会话互不影响
+    /// 
+    /// 不允许并发登录（`is_concurrent = false`）时，只顶替同一设备上的旧 token
+    /// 
+    /// # 示例 | Example
+    /// ```rust,ignore
+    /// let web = manager.login_with_device("user_123", "web").await?;
+    /// let app = manager.login_with_device("user_123", "mobile").await?;
+    /// manager.logout_by_device("user_123", "web").await?; // app 仍然有效
+    /// ```
+    pub async fn login_with_device(&self, login_id: impl Into<String>, device: impl Into<String>) -> SaTokenResult<TokenValue> {
+        self.login_with(login_id, LoginOptions::new().device(device)).await
+    }
+    
+    /// 登录：按登录参数创建 token（登录类型、设备、有效期、JWT 自定义声明）
+    /// 
+    /// 使用 `TokenStyle::Jwt` 时，登录参数会写入生成的 JWT 声明。
+    /// 开启 `login_dedup_window` 时，窗口期内同一账号、登录类型、设备的重复登录返回
+    /// 同一个 token，后到的调用者的其他参数被忽略；该 token 已注销时重新签发。
+    /// 
+    /// With `login_dedup_window` set, repeated logins for the same account,
+    /// login type and device within the window return the same token and the
+    /// later callers' other options are ignored. A token already logged out is
+    /// not reused.
+    /// 
+    /// # 示例 | Example
+    /// ```rust,ignore
+    /// let token = manager.login_with("user_123", LoginOptions::new()
+    ///     .device("pc")
+    ///     .claim("role", json!("admin"))
+    /// ).await?;
+    /// ```
+    pub async fn login_with(&self, login_id: impl Into<String>, options: LoginOptions) -> SaTokenResult<TokenValue> {
+        let login_id = login_id.into();
+        if self.config().login_dedup_window <= 0 {
+            let token_info = self.token_info_for_login(login_id, options).await?;
+            return self.login_with_token_info(token_info).await;
+        }
+        
+        let key = format!(
+            "{}:{}:{}",
+            options.login_type.as_deref().unwrap_or("default"),
+            options.device.as_deref().unwrap_or_default(),
+            login_id,
+        );
+        let slot = self.login_dedup.slot(&key, Duration::seconds(self.config().login_dedup_window));
+        let mut issued = false;
+        let token = slot.get_or_try_init(|| async {
+            issued = true;
+            self.login_with_token_info(self.token_info_for_login(login_id.clone(), options.clone()).await?).await
+        }).await?.clone();
+        
+        if issued || self.is_valid(&token).await {
+            return Ok(token);
+        }
+        self.login_dedup.forget(&key);
+        self.login_with_token_info(self.token_info_for_login(login_id, options).await?).await
+    }
+    
+    /// 登录并返回完整的登录结果（token 信息、会话、被顶替的 token）
+    /// 
+    /// Like `login_with`, but returns the stored TokenInfo, the account session
+    /// and the tokens replaced when `is_concurrent` is off.
+    /// 
+    /// # 示例 | Example
+    /// ```rust,ignore
+    /// let result = manager.login_ex("user_123", LoginOptions::new().device("pc")).await?;
+    /// for replaced in &result.replaced_tokens {
+    ///     tracing::info!("顶替下线: {}", replaced);
+    /// }
+    /// ```
+    pub async fn login_ex(&self, login_id: impl Into<String>, options: LoginOptions) -> SaTokenResult<LoginResult> {
+        let login_id = login_id.into();
+        let account = account_key(&login_id, options.login_type.as_deref().unwrap_or(DEFAULT_LOGIN_TYPE));
+        let is_new_session = !self.storage.exists(&format!("sa:session:{}", account)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        
+        let token_info = self.token_info_for_login(login_id, options).await?;
+        let (token_info, replaced_tokens) = self.issue_token(token_info).await?;
+        let session = self.get_session(&account).await?;
+        
+        Ok(LoginResult {
+            token: token_info.token.clone(),
+            token_info,
+            session,
+            is_new_session,
+            replaced_tokens,
+        })
+    }
+    
+    /// 按登录参数构造 TokenInfo
+    async fn token_info_for_login(&self, login_id: String, mut options: LoginOptions) -> SaTokenResult<TokenInfo> {
+        // "记住我"登录未指定有效期时使用 remember_timeout（JWT 的 exp 同样如此）
+        if options.remember == Some(true) && options.timeout.is_none() {
+            options.timeout = Some(self.config().remember_timeout);
+        }
+        let token = self.generate_token(&login_id, &options).await?;
+        
+        let mut token_info = TokenInfo::new(token, login_id);
+        token_info.create_time = self.now();
+        token_info.last_active_time = token_info.create_time;
+        token_info.login_type = options.login_type.unwrap_or_else(|| "default".to_string());
+        token_info.device = options.device;
+        token_info.extra_data = options.extra_data;
+        token_info.remember = options.remember;
+        if let Some(timeout) = options.timeout.filter(|t| *t > 0) {
+            token_info.expire_time = Some(self.now() + Duration::seconds(timeout));
+        }
+        Ok(token_info)
+    }
+    
+    /// 按配置的风格生成新 token
+    /// 
+    /// `Sequence` 风格从存储的计数器分配序号；`max_try_times > 0` 时，与已有 token 重复的
+    /// 结果会被丢弃并重新生成（JWT 自带签名与时间戳，不检查）。
+    /// 
+    /// `Sequence` numbers come from a storage counter. With `max_try_times > 0`,
+    /// a token that collides with an existing one is discarded and generated
+    /// again (JWTs are not checked).
+    async fn generate_token(&self, login_id: &str, options: &LoginOptions) -> SaTokenResult<TokenValue> {
+        let config = self.config();
+        let check = config.max_try_times > 0 && !matches!(config.token_style, TokenStyle::Jwt);
+        let attempts = config.max_try_times.max(1);
+        
+        for _ in 0..attempts {
+            let token = match &config.token_style {
+                TokenStyle::Sequence { prefix } => {
+                    let seq = self.storage.incr(&format!("sa:token-seq:{}", prefix)).await
+                        .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+                    DefaultTokenGenerator::generate_sequence(&config, prefix, seq)
+                }
+                _ => DefaultTokenGenerator::try_generate_with_options(&config, login_id, options)?,
+            };
+            if !check {
+                return Ok(token);
+            }
+            let taken = self.storage.exists(&format!("sa:token:{}", token.as_str())).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+            if !taken {
+                return Ok(token);
+            }
+            tracing::warn!("Sa-Token: 生成的 token 与已有 token 重复，重新生成");
+        }
+        Err(SaTokenError::TokenGenerateFailed(attempts))
+    }
+    
+    /// 幂等登录：相同幂等键的重复请求返回第一次签发的 token，不会创建新的会话
+    /// 
+    /// 保存的 token 已登出、被踢或过期时不再重放，而是重新登录。
+    /// 
+    /// Retried requests carrying the same idempotency key get the token issued by
+    /// the first call. Results are kept for `config.idempotency_retention` seconds.
+    /// A stored token that has since been logged out, kicked or expired is not
+    /// replayed; the login runs again instead.
+    pub async fn login_idempotent(
+        &self,
+        login_id: impl Into<String>,
+        idempotency_key: &str,
+    ) -> SaTokenResult<TokenValue> {
+        let login_id = login_id.into();
+        let scope = format!("login:{}", login_id);
+        let store = IdempotencyStore::new(self.storage.clone(), self.config().idempotency_retention_duration());
+        let token: TokenValue = store.run(&scope, idempotency_key, || self.login(login_id.clone())).await?;
+        if self.is_valid(&token).await {
+            return Ok(token);
+        }
+        
+        tracing::debug!("Manager: 幂等键 {} 保存的 token 已失效，重新登录", idempotency_key);
+        store.forget(&scope, idempotency_key).await?;
+        store.run(&scope, idempotency_key, || self.login(login_id.clone())).await
     }
     
     /// 登录：为指定账号创建 token（支持自定义 TokenInfo 字段）
@@ -107,7 +877,7 @@ impl SaTokenManager {
         let login_id = login_id.into();
         
         // 生成 token（支持 JWT）
-        let token = TokenGenerator::generate_with_login_id(&self.config, &login_id);
+        let token = self.generate_token(&login_id, &LoginOptions::default()).await?;
         
         // 创建 token 信息
         let mut token_info = TokenInfo::new(token.clone(), login_id.clone());
@@ -168,12 +938,27 @@ impl SaTokenManager {
     /// 
     /// let token = manager.login_with_token_info(token_info).await?;
     /// ```
-    pub async fn login_with_token_info(&self, mut token_info: TokenInfo) -> SaTokenResult<TokenValue> {
+    pub async fn login_with_token_info(&self, token_info: TokenInfo) -> SaTokenResult<TokenValue> {
+        let (token_info, _) = self.issue_token(token_info).await?;
+        Ok(token_info.token)
+    }
+    
+    /// 签发 token，返回写入存储的 TokenInfo 与被顶替下线的 token
+    async fn issue_token(&self, mut token_info: TokenInfo) -> SaTokenResult<(TokenInfo, Vec<TokenValue>)> {
         let login_id = token_info.login_id.clone();
         
+        // 确保登录类型不为空
+        if token_info.login_type.is_empty() {
+            token_info.login_type = DEFAULT_LOGIN_TYPE.to_string();
+        }
+        let account = account_key(&login_id, &token_info.login_type);
+        
+        // 被封禁的账号不能登录
+        self.check_disable(&account).await?;
+        
         // 如果 token_info 中没有 token，则生成一个
         let token = if token_info.token.as_str().is_empty() {
-            TokenGenerator::generate_with_login_id(&self.config, &login_id)
+            self.generate_token(&login_id, &LoginOptions::default()).await?
         } else {
             token_info.token.clone()
         };
@@ -182,51 +967,301 @@ impl SaTokenManager {
         token_info.token = token.clone();
         
         // 更新最后活跃时间为当前时间
-        token_info.update_active_time();
+        token_info.last_active_time = self.now();
         
-        // 如果过期时间为 None，使用配置的过期时间
-        let now = Utc::now();
-        if token_info.expire_time.is_none() {
-            if let Some(timeout) = self.config.timeout_duration() {
-                token_info.expire_time = Some(now + Duration::from_std(timeout).unwrap());
+        // 如果过期时间为 None，由有效期策略决定，未设置策略时使用配置的过期时间
+        let now = self.now();
+        let mut timeout = if token_info.remember == Some(true) {
+            self.config().remember_timeout_duration()
+        } else {
+            self.config().timeout_duration()
+        };
+        if let Some(expire_time) = token_info.expire_time {
+            // 自定义过期时间时，存储的过期时间与之保持一致
+            timeout = (expire_time - now).to_std().ok();
+        } else {
+            if let Some(policy) = &self.ttl_policy {
+                let ctx = LoginContext {
+                    login_id: login_id.clone(),
+                    login_type: token_info.login_type.clone(),
+                    device: token_info.device.clone(),
+                    extra_data: token_info.extra_data.clone(),
+                    default_ttl: timeout,
+                };
+                timeout = Some(policy.ttl_for(&ctx).await);
+            }
+            if let Some(ttl) = timeout {
+                // 策略返回的时长超出可表示范围时按永久有效处理，不因策略的返回值 panic
+                match Duration::from_std(ttl).ok().and_then(|ttl| now.checked_add_signed(ttl)) {
+                    Some(expire_time) => token_info.expire_time = Some(expire_time),
+                    None => {
+                        tracing::warn!("Manager: 有效期 {:?} 超出可表示范围，按永久有效处理", ttl);
+                        timeout = None;
+                    }
+                }
             }
         }
         
-        // 确保登录类型不为空
-        if token_info.login_type.is_empty() {
-            token_info.login_type = "default".to_string();
+        // 登录策略：密码过期时只签发修改密码用的受限 token
+        let decision = match &self.login_policy {
+            Some(policy) => policy.check(&login_id, &token_info.login_type).await,
+            None => LoginPolicyDecision::Allow,
+        };
+        if decision == LoginPolicyDecision::PasswordExpired {
+            tracing::info!("Manager: 账号 {} 密码已过期，签发受限 token", login_id);
+            token_info.scope = Some(vec![PASSWORD_CHANGE_SCOPE.to_string()]);
+        }
+        
+        if self.config().prefetch_authz {
+            token_info.authz = Some(self.prefetch_authz(&account).await?);
+        }
+        
+        // 记录登录请求的来源 IP 与 User-Agent（由框架插件写入请求上下文）
+        if let Some(ctx) = SaTokenContext::get_current() {
+            token_info.login_ip = token_info.login_ip.take().or(ctx.client_ip);
+            token_info.user_agent = token_info.user_agent.take().or(ctx.user_agent);
         }
         
+        // 同一设备上一次登录的 token，需要在写入新的设备索引之前读取
+        let device_key = token_info.device.as_deref().map(|device| device_token_key(&login_id, &token_info.login_type, device));
+        let previous_device_token = match &device_key {
+            Some(device_key) => self.storage.get(device_key).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?,
+            None => None,
+        };
+        
+        // 新设备登录通知：需要在写入新的 login_id 映射之前判断是否已有在线会话
+        let notify_new_sign_in = self.config().should_notify_new_sign_in(&token_info.login_type)
+            && self.has_active_session(&login_id, &token_info.login_type).await;
+        
         // 存储 token 信息
         let key = format!("sa:token:{}", token.as_str());
         let value = serde_json::to_string(&token_info)
             .map_err(|e| SaTokenError::SerializationError(e))?;
         
-        self.storage.set(&key, &value, self.config.timeout_duration()).await
+        self.storage.set(&key, &value, timeout).await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
         
         // 保存 login_id 到 token 的映射（用于根据 login_id 查找 token）
         // 如果 login_type 不为空，使用包含 login_type 的 key 格式避免冲突
         // If login_type is not empty, use key format with login_type to avoid conflicts
-        let login_token_key = if !token_info.login_type.is_empty() && token_info.login_type != "default" {
-            format!("sa:login:token:{}:{}", login_id, token_info.login_type)
-        } else {
-            format!("sa:login:token:{}", login_id)
-        };
-        self.storage.set(&login_token_key, token.as_str(), self.config.timeout_duration()).await
+        let login_token_key = login_token_key(&login_id, &token_info.login_type);
+        self.storage.set(&login_token_key, token.as_str(), timeout).await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
         
+        // 保存设备到 token 的索引（用于按设备查找、登出）
+        if let Some(device_key) = &device_key {
+            self.storage.set(device_key, token.as_str(), timeout).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        }
+        
         // 如果不允许并发登录，踢掉之前的 token
-        if !self.config.is_concurrent {
-            self.logout_by_login_id(&login_id).await?;
+        let mut replaced_tokens = Vec::new();
+        if !self.config().is_concurrent {
+            if token_info.device.is_some() {
+                // 按设备登录时只顶替同一设备上的旧 token，其他设备的会话不受影响
+                if let Some(previous) = previous_device_token.filter(|previous| previous != token.as_str()) {
+                    let previous = TokenValue::new(previous);
+                    self.replace_token(&login_id, &previous).await?;
+                    replaced_tokens.push(previous);
+                }
+            } else {
+                // 只顶替同一账号体系（登录类型）中的 token
+                for (previous, _) in self.login_type_tokens(&login_id, &token_info.login_type).await {
+                    if previous != token && self.replace_token(&login_id, &previous).await.is_ok() {
+                        replaced_tokens.push(previous);
+                    }
+                }
+            }
+        } else if self.config().max_login_count > 0 {
+            // 超出同时在线数量时，按淘汰策略顶替多余的旧 token（对应 Java 版的 maxLoginCount）
+            // 只读取该账号的 token 索引，不扫描全部 token
+            let mut previous_tokens: Vec<_> = self.indexed_login_tokens(&login_id, &token_info.login_type).await?
+                .into_iter()
+                .filter(|(previous, _)| previous != &token)
+                .collect();
+            let excess = (previous_tokens.len() + 1).saturating_sub(self.config().max_login_count as usize);
+            previous_tokens.sort_by_key(|(_, previous_info)| previous_info.create_time);
+            if self.config().login_eviction == LoginEviction::Newest {
+                previous_tokens.reverse();
+            }
+            let mut kept = previous_tokens.split_off(excess);
+            for (previous, previous_info) in previous_tokens {
+                if self.replace_token(&login_id, &previous).await.is_ok() {
+                    replaced_tokens.push(previous);
+                } else {
+                    kept.push((previous, previous_info));
+                }
+            }
+            kept.push((token.clone(), token_info.clone()));
+            self.save_login_tokens_index(&login_id, &token_info.login_type, &kept).await?;
+        }
+        
+        if notify_new_sign_in {
+            self.record_new_sign_in(&token_info).await;
         }
         
+        // 登录请求携带的语言与时区写入账号 Session
+        if let Some(ctx) = SaTokenContext::get_current()
+            && (ctx.locale.is_some() || ctx.timezone.is_some())
+        {
+            self.set_client_locale(&account, ctx.locale.as_deref(), ctx.timezone.as_deref()).await?;
+        }
+        
+        // 开启 Cookie 写回时，由插件中间件把新 token 写入响应；未"记住我"的登录写会话 Cookie
+        let max_age = token_info.expire_time
+            .map(|expire_time| (expire_time - now).num_seconds().max(0))
+            .unwrap_or(PERMANENT_COOKIE_MAX_AGE);
+        self.queue_token_cookie(token.as_str(), Some(max_age).filter(|_| token_info.remember != Some(false)));
+        
         // 触发登录事件
         let event = SaTokenEvent::login(login_id.clone(), token.as_str())
             .with_login_type(&token_info.login_type);
         self.event_bus.publish(event).await;
         
-        Ok(token)
+        Ok((token_info, replaced_tokens))
+    }
+    
+    /// 账号是否已有在线会话（OnlineManager 中有记录，或已存在有效 token）
+    async fn has_active_session(&self, login_id: &str, login_type: &str) -> bool {
+        let online = match &self.online_manager {
+            Some(online_mgr) => online_mgr.is_online(login_id).await,
+            None => false,
+        };
+        if online {
+            return true;
+        }
+        
+        match self.storage.get(&login_token_key(login_id, login_type)).await {
+            Ok(Some(existing)) => self.is_valid(&TokenValue::new(existing)).await,
+            _ => false,
+        }
+    }
+    
+    /// 记录新设备登录并通知其他在线设备（失败只记录日志，不影响登录）
+    async fn record_new_sign_in(&self, token_info: &TokenInfo) {
+        let record = LoginRecord {
+            login_id: token_info.login_id.clone(),
+            login_type: token_info.login_type.clone(),
+            device: token_info.device.clone(),
+            login_time: self.now(),
+        };
+        
+        let mut history = self.get_login_history(&record.login_id).await.unwrap_or_default();
+        history.insert(0, record.clone());
+        history.truncate(LOGIN_HISTORY_LIMIT);
+        let key = format!("sa:login:history:{}", record.login_id);
+        match serde_json::to_string(&history) {
+            Ok(value) => {
+                if let Err(e) = self.storage.set(&key, &value, None).await {
+                    tracing::warn!("Manager: 写入登录历史失败，login_id: {}, 错误: {}", record.login_id, e);
+                }
+            }
+            Err(e) => tracing::warn!("Manager: 序列化登录历史失败: {}", e),
+        }
+        
+        let Some(online_mgr) = &self.online_manager else {
+            return;
+        };
+        if let Err(e) = online_mgr.new_sign_in_notify(&record).await {
+            tracing::warn!("Manager: 新设备登录通知发送失败，login_id: {}, 错误: {}", record.login_id, e);
+        }
+    }
+    
+    /// 获取登录历史（最新的在前，最多保留 20 条）
+    pub async fn get_login_history(&self, login_id: &str) -> SaTokenResult<Vec<LoginRecord>> {
+        let key = format!("sa:login:history:{}", login_id);
+        let value = self.storage.get(&key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        match value {
+            Some(value) => serde_json::from_str(&value).map_err(SaTokenError::SerializationError),
+            None => Ok(Vec::new()),
+        }
+    }
+    
+    /// 开启只读模式（保存在存储中，所有实例同时生效）
+    pub async fn enable_read_only_mode(&self, mode: ReadOnlyMode) -> SaTokenResult<()> {
+        let value = serde_json::to_string(&mode)?;
+        self.storage.set(READ_ONLY_MODE_KEY, &value, None).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        tracing::warn!("Manager: 已开启只读模式: {}", mode.message());
+        Ok(())
+    }
+    
+    /// 关闭只读模式
+    pub async fn disable_read_only_mode(&self) -> SaTokenResult<()> {
+        self.storage.delete(READ_ONLY_MODE_KEY).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        tracing::info!("Manager: 已关闭只读模式");
+        Ok(())
+    }
+    
+    /// 当前的只读模式设置，未开启时返回 None
+    pub async fn read_only_mode(&self) -> SaTokenResult<Option<ReadOnlyMode>> {
+        let value = self.storage.get(READ_ONLY_MODE_KEY).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        match value {
+            Some(value) => Ok(Some(serde_json::from_str(&value)?)),
+            None => Ok(None),
+        }
+    }
+    
+    /// 只读模式检查：写操作被拒绝时返回 `ReadOnlyMode` 错误
+    /// 
+    /// Rejects mutating methods while read-only mode targets the caller,
+    /// unless the caller holds the override permission.
+    pub async fn check_read_only(&self, login_id: Option<&str>, method: &str) -> SaTokenResult<()> {
+        if !is_mutating_method(method) {
+            return Ok(());
+        }
+        let Some(mode) = self.read_only_mode().await? else {
+            return Ok(());
+        };
+        if self.dev_bypass_login_id().is_some() {
+            return Ok(());
+        }
+        
+        let restricted = match login_id {
+            Some(login_id) => {
+                if self.has_permission(login_id, &mode.override_permission).await {
+                    return Ok(());
+                }
+                let mut restricted = mode.roles.is_empty();
+                for role in &mode.roles {
+                    if self.has_role(login_id, role).await {
+                        restricted = true;
+                        break;
+                    }
+                }
+                restricted
+            }
+            None => mode.roles.is_empty(),
+        };
+        
+        if restricted {
+            Err(SaTokenError::ReadOnlyMode(mode.message()))
+        } else {
+            Ok(())
+        }
+    }
+    
+    /// 开启 `is_write_cookie` 时，把 token Cookie 记到当前请求上下文
+    /// 
+    /// `max_age` 为 `Some(0)` 表示删除，`None` 写入会话 Cookie（浏览器关闭即失效）。
+    fn queue_token_cookie(&self, value: &str, max_age: Option<i64>) {
+        if !self.config().is_write_cookie {
+            return;
+        }
+        if let Some(ctx) = SaTokenContext::get_current() {
+            ctx.set_response_cookie(ResponseCookie {
+                name: self.config().token_name.clone(),
+                value: value.to_string(),
+                options: CookieOptions {
+                    max_age,
+                    ..self.config().cookie_options()
+                },
+            });
+        }
     }
     
     /// 登出：删除指定 token
@@ -252,6 +1287,7 @@ impl SaTokenManager {
         tracing::debug!("Manager: 删除 token，key: {}", key);
         self.storage.delete(&key).await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        let _ = self.storage.delete(&activity_key(token)).await;
         tracing::debug!("Manager: token 已从存储中删除");
         
         // 触发登出事件
@@ -266,6 +1302,21 @@ impl SaTokenManager {
                 tracing::debug!("Manager: 标记用户下线，login_id: {}", info.login_id);
                 online_mgr.mark_offline(&info.login_id, token.as_str()).await;
             }
+            
+            // 设备索引仍指向该 token 时一并删除
+            if let Some(device) = &info.device {
+                let device_key = device_token_key(&info.login_id, &info.login_type, device);
+                if let Ok(Some(current)) = self.storage.get(&device_key).await
+                    && current == token.as_str()
+                {
+                    let _ = self.storage.delete(&device_key).await;
+                }
+            }
+        }
+        
+        // 登出的是当前请求的 token 时清除 Cookie
+        if SaTokenContext::get_current().is_some_and(|ctx| ctx.token.as_ref() == Some(token)) {
+            self.queue_token_cookie("", Some(0));
         }
         
         tracing::debug!("Manager: logout 完成，token: {}", token);
@@ -274,159 +1325,2053 @@ impl SaTokenManager {
     
     /// 根据登录 ID 登出所有 token
     pub async fn logout_by_login_id(&self, login_id: &str) -> SaTokenResult<()> {
+        for (token, _) in self.login_tokens(login_id).await {
+            // 调用登出方法（logout 方法内部会处理删除映射和在线用户管理）
+            let _ = self.logout(&token).await;
+        }
+        
+        Ok(())
+    }
+    
+    /// 登出账号在指定登录类型（账号体系）下的所有 token，其他账号体系不受影响
+    pub async fn logout_by_login_type(&self, login_id: &str, login_type: &str) -> SaTokenResult<()> {
+        for (token, _) in self.login_type_tokens(login_id, login_type).await {
+            self.logout(&token).await?;
+        }
+        Ok(())
+    }
+    
+    /// 登出账号在指定设备上的所有 token，其他设备不受影响
+    pub async fn logout_by_device(&self, login_id: &str, device: &str) -> SaTokenResult<()> {
+        for (token, _) in self.device_tokens(login_id, DEFAULT_LOGIN_TYPE, device).await {
+            self.logout(&token).await?;
+        }
+        Ok(())
+    }
+    
+    /// 获取账号在指定设备上最近一次登录的 token
+    pub async fn get_token_by_device(&self, login_id: &str, device: &str) -> SaTokenResult<TokenValue> {
+        self.storage.get(&device_token_key(login_id, DEFAULT_LOGIN_TYPE, device)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+            .map(TokenValue::new)
+            .ok_or(SaTokenError::TokenNotFound)
+    }
+    
+    /// 查找账号在指定登录类型、指定设备上的所有 token
+    pub(crate) async fn device_tokens(&self, login_id: &str, login_type: &str, device: &str) -> Vec<(TokenValue, TokenInfo)> {
+        self.login_type_tokens(login_id, login_type).await
+            .into_iter()
+            .filter(|(_, token_info)| token_info.device.as_deref() == Some(device))
+            .collect()
+    }
+    
+    /// 登录 ID 在指定登录类型下的所有 token
+    async fn login_type_tokens(&self, login_id: &str, login_type: &str) -> Vec<(TokenValue, TokenInfo)> {
+        self.login_tokens(login_id).await
+            .into_iter()
+            .filter(|(_, token_info)| token_info.login_type == login_type)
+            .collect()
+    }
+    
+    /// 从账号的 token 索引读取仍然有效的 token，已失效的条目直接跳过
+    async fn indexed_login_tokens(&self, login_id: &str, login_type: &str) -> SaTokenResult<Vec<(TokenValue, TokenInfo)>> {
+        let index = self.storage.get(&login_tokens_index_key(login_id, login_type)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+            .and_then(|value| serde_json::from_str::<Vec<String>>(&value).ok())
+            .unwrap_or_default();
+        if index.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        let keys: Vec<String> = index.iter().map(|token| format!("sa:token:{}", token)).collect();
+        let key_refs: Vec<&str> = keys.iter().map(String::as_str).collect();
+        let values = self.storage.mget(&key_refs).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        
+        Ok(index.into_iter()
+            .zip(values)
+            .filter_map(|(token, value)| {
+                let token_info = serde_json::from_str::<TokenInfo>(&value?).ok()?;
+                (token_info.login_id == login_id && token_info.login_type == login_type)
+                    .then(|| (TokenValue::new(token), token_info))
+            })
+            .collect())
+    }
+    
+    /// 写回账号的 token 索引，保留到其中最晚过期的 token 过期为止
+    async fn save_login_tokens_index(&self, login_id: &str, login_type: &str, tokens: &[(TokenValue, TokenInfo)]) -> SaTokenResult<()> {
+        let now = self.now();
+        let ttl = tokens.iter()
+            .map(|(_, token_info)| token_info.expire_time)
+            .collect::<Option<Vec<_>>>()
+            .and_then(|expire_times| expire_times.into_iter().max())
+            .map(|expire_time| (expire_time - now).to_std().unwrap_or_default());
+        let index: Vec<&str> = tokens.iter().map(|(token, _)| token.as_str()).collect();
+        let value = serde_json::to_string(&index)
+            .map_err(SaTokenError::SerializationError)?;
+        self.storage.set(&login_tokens_index_key(login_id, login_type), &value, ttl).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+    
+    /// 查找登录 ID 下的所有 token
+    async fn login_tokens(&self, login_id: &str) -> Vec<(TokenValue, TokenInfo)> {
         // 获取所有 token 键的前缀
         let token_prefix = "sa:token:";
+        let mut tokens = Vec::new();
         
         // 获取所有 token 键
         if let Ok(keys) = self.storage.keys(&format!("{}*", token_prefix)).await {
             // 遍历所有 token 键
             for key in keys {
                 // 获取 token 值
-                if let Ok(Some(token_info_str)) = self.storage.get(&key).await {
+                if let Ok(Some(token_info_str)) = self.storage.get(&key).await
                     // 反序列化 token 信息
-                    if let Ok(token_info) = serde_json::from_str::<TokenInfo>(&token_info_str) {
-                        // 如果 login_id 匹配，则登出该 token
-                        if token_info.login_id == login_id {
-                            // 提取 token 字符串（从键中移除前缀）
-                            let token_str = key[token_prefix.len()..].to_string();
-                            let token = TokenValue::new(token_str);
-                            
-                            // 调用登出方法（logout 方法内部会处理删除映射和在线用户管理）
-                            let _ = self.logout(&token).await;
-                        }
-                    }
+                    && let Ok(token_info) = serde_json::from_str::<TokenInfo>(&token_info_str)
+                    && token_info.login_id == login_id
+                {
+                    // 提取 token 字符串（从键中移除前缀）
+                    let token = TokenValue::new(&key[token_prefix.len()..]);
+                    tokens.push((token, token_info));
                 }
             }
         }
         
-        Ok(())
+        tokens
+    }
+    
+    /// 按配置的受信任代理（`trusted_proxies`）解析请求的客户端 IP
+    /// 
+    /// Resolves the client IP of a request, honouring `X-Forwarded-For` only
+    /// from the configured `trusted_proxies`.
+    pub fn client_ip<R: SaRequest>(&self, request: &R) -> Option<String> {
+        self.resolve_client_ip(
+            request.remote_addr().as_deref(),
+            request.get_header("x-forwarded-for").as_deref(),
+            request.get_header("x-real-ip").as_deref(),
+        )
+    }
+    
+    /// 由连接对端地址与代理请求头解析客户端 IP，规则见 [`crate::ip`]
+    pub fn resolve_client_ip(&self, remote: Option<&str>, forwarded_for: Option<&str>, real_ip: Option<&str>) -> Option<String> {
+        resolve_client_ip(remote, forwarded_for, real_ip, &self.config().trusted_proxy_cidrs())
+    }
+    
+    /// 开启 `token_bind_ip` 时校验请求 IP 与登录 IP 是否一致
+    /// 
+    /// 登录时未记录 IP 的 token 不受限制；无法确定请求 IP 时按不一致处理。
+    /// 
+    /// With `token_bind_ip`, checks that the request comes from the login IP.
+    /// Tokens without a recorded IP pass; an unknown request IP counts as a mismatch.
+    pub fn check_ip_binding(&self, token_info: &TokenInfo, client_ip: Option<&str>) -> SaTokenResult<()> {
+        if !self.config().token_bind_ip {
+            return Ok(());
+        }
+        match (&token_info.login_ip, client_ip) {
+            (None, _) => Ok(()),
+            (Some(login_ip), Some(client_ip)) if same_ip(login_ip, client_ip) => Ok(()),
+            _ => Err(SaTokenError::IpMismatch),
+        }
+    }
+    
+    /// 获取请求携带的 token 的信息，并校验 IP 绑定（框架插件的认证中间件使用）
+    /// 
+    /// Token info for a token sent with a request from `client_ip`, with the
+    /// IP binding checked. Used by the plugins' authentication middlewares.
+    pub async fn get_token_info_from(&self, token: &TokenValue, client_ip: Option<&str>) -> SaTokenResult<TokenInfo> {
+        let token_info = self.get_token_info(token).await?;
+        self.check_ip_binding(&token_info, client_ip)?;
+        Ok(token_info)
+    }
+    
+    /// 校验请求携带的 token 并写入请求上下文，返回登录 ID（框架插件的认证中间件使用）
+    /// 
+    /// 校验通过时写入 `token`、`token_info` 与 `login_id`。受限 token（如密码过期后签发的）
+    /// 不写入 `login_id`：登录检查会以 `PasswordExpired` 拒绝它，只能通过
+    /// `StpUtil::check_scope_current` 访问对应接口。校验失败时把原因（被踢、被顶替、IP 不一致等）
+    /// 记入 `auth_error` 并上报认证失败事件，`SaCheck` 拒绝时返回该原因。
+    /// 
+    /// Validates the request's token against `ctx.client_ip` and fills the
+    /// context. Restricted tokens get no `login_id`, so route checks reject
+    /// them with `PasswordExpired`; failures are reported as auth failures and
+    /// recorded in `auth_error`, which `SaCheck` returns instead of `NotLogin`.
+    pub async fn authenticate_context(&self, token: &TokenValue, ctx: &mut SaTokenContext) -> Option<String> {
+        let result = match self.get_token_info_started_at(token, ctx.started_at).await {
+            Ok(token_info) => self.check_ip_binding(&token_info, ctx.client_ip.as_deref()).map(|_| token_info),
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(token_info) => {
+                ctx.token = Some(token.clone());
+                ctx.login_id = (!token_info.is_restricted()).then(|| token_info.login_id.clone());
+                ctx.token_info = Some(Arc::new(token_info));
+                ctx.login_id.clone()
+            }
+            Err(e) => {
+                self.report_auth_failure(token.as_str(), &e.to_string(), ctx.client_ip.as_deref()).await;
+                ctx.auth_error = Some(Arc::new(e));
+                None
+            }
+        }
     }
     
     /// 获取 token 信息
+    /// 
+    /// 当前请求上下文的 token 在踢人宽限期内被踢出时，若请求开始于踢人之前，仍返回原 token 信息
     pub async fn get_token_info(&self, token: &TokenValue) -> SaTokenResult<TokenInfo> {
+        let started_at = SaTokenContext::get_current()
+            .filter(|ctx| ctx.token.as_ref() == Some(token))
+            .map(|ctx| ctx.started_at);
+        self.get_token_info_inner(token, started_at).await
+    }
+    
+    /// 获取 token 信息，`started_at` 为请求开始时间，用于踢人宽限期判断
+    /// 
+    /// Like `get_token_info`, for callers that track the request start time
+    /// themselves instead of through `SaTokenContext`.
+    pub async fn get_token_info_started_at(&self, token: &TokenValue, started_at: DateTime<Utc>) -> SaTokenResult<TokenInfo> {
+        self.get_token_info_inner(token, Some(started_at)).await
+    }
+    
+    async fn get_token_info_inner(&self, token: &TokenValue, started_at: Option<DateTime<Utc>>) -> SaTokenResult<TokenInfo> {
         let key = format!("sa:token:{}", token.as_str());
         let value = self.storage.get(&key).await
-            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
-            .ok_or(SaTokenError::TokenNotFound)?;
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
         
-        let token_info: TokenInfo = serde_json::from_str(&value)
-            .map_err(|e| SaTokenError::SerializationError(e))?;
+        if value.is_none()
+            && let Some(started_at) = started_at
+            && let Some(token_info) = self.revoked_token_in_grace(token, started_at).await?
+        {
+            return Ok(token_info);
+        }
         
-        // 检查是否过期
-        if token_info.is_expired() {
-            // 删除过期的 token
-            self.logout(token).await?;
-            return Err(SaTokenError::TokenExpired);
+        if value.is_none()
+            && let Some(reason) = self.storage.get(&kicked_token_key(token.as_str())).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+        {
+            return Err(SaTokenError::KickedOut(reason));
         }
         
-        // 如果开启了自动续签，则自动续签
-        // 注意：为了避免递归调用 get_token_info，这里直接更新过期时间
-        if self.config.auto_renew {
-            let renew_timeout = if self.config.active_timeout > 0 {
-                self.config.active_timeout
-            } else {
-                self.config.timeout
-            };
-            
-            // 直接续签（不递归调用 get_token_info）
-            let _ = self.renew_timeout_internal(token, renew_timeout, &token_info).await;
+        if value.is_none()
+            && self.storage.exists(&replaced_token_key(token.as_str())).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+        {
+            return Err(SaTokenError::Replaced);
         }
         
-        Ok(token_info)
-    }
-    
-    /// 检查 token 是否有效
-    pub async fn is_valid(&self, token: &TokenValue) -> bool {
-        self.get_token_info(token).await.is_ok()
+        if value.is_none() && !self.legacy_importers.is_empty() {
+            // 导入时会登录，登录流程可能再次读取 token，需要装箱打断递归
+            return Box::pin(self.import_legacy_session(token)).await;
+        }
+        
+        self.resolve_token_info(token, value).await
     }
     
-    /// 获取 session
-    pub async fn get_session(&self, login_id: &str) -> SaTokenResult<SaSession> {
-        let key = format!("sa:session:{}", login_id);
-        let value = self.storage.get(&key).await
+    /// 批量读取 token 信息，一次 `mget` 完成，适用于管理后台
+    /// 
+    /// 与 `get_token_info_batch` 不同，这里只读查看：不自动续签、不复查会话，也不删除过期 token，
+    /// 管理员查看不会延长用户的会话。不存在、已过期或无法解析的 token 返回 None，结果顺序与入参一致。
+    /// 
+    /// Reads many tokens in one `mget` round trip, for admin dashboards. Unlike
+    /// `get_token_info_batch` this is a read-only inspection: no auto-renew,
+    /// session revalidation or expired-token cleanup, so looking at a token
+    /// never extends it. Missing, expired or unreadable tokens yield `None`, in
+    /// the same order as `tokens`.
+    pub async fn get_token_infos(&self, tokens: &[TokenValue]) -> SaTokenResult<Vec<Option<TokenInfo>>> {
+        if tokens.is_empty() {
+            return Ok(Vec::new());
+        }
+        let keys: Vec<String> = tokens.iter().map(|token| format!("sa:token:{}", token.as_str())).collect();
+        let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+        let values = self.storage.mget(&keys).await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
         
-        if let Some(value) = value {
-            let session: SaSession = serde_json::from_str(&value)
-                .map_err(|e| SaTokenError::SerializationError(e))?;
-            Ok(session)
-        } else {
-            Ok(SaSession::new(login_id))
+        Ok(values.into_iter()
+            .map(|value| value
+                .and_then(|value| serde_json::from_str::<TokenInfo>(&value).ok())
+                .filter(|token_info| !token_info.is_expired_at(self.now())))
+            .collect())
+    }
+    
+    /// 首次访问时从旧认证库导入会话，以同一个 token 值登录
+    async fn import_legacy_session(&self, token: &TokenValue) -> SaTokenResult<TokenInfo> {
+        for importer in &self.legacy_importers {
+            let Some(imported) = importer.import(token.as_str()).await? else {
+                continue;
+            };
+            
+            let login_id = imported.login_id.clone();
+            let mut token_info = TokenInfo::new(token.clone(), login_id.clone());
+            if let Some(login_type) = imported.login_type {
+                token_info.login_type = login_type;
+            }
+            token_info.expire_time = imported.expire_time;
+            self.login_with_token_info(token_info).await?;
+            
+            if !imported.data.is_empty() {
+                let mut session = self.get_session(&login_id).await?;
+                session.data.extend(imported.data);
+                self.save_session(&session).await?;
+            }
+            importer.imported(token.as_str()).await?;
+            tracing::info!("Manager: 已从 {} 导入旧会话，login_id: {}", importer.name(), login_id);
+            
+            let key = format!("sa:token:{}", token.as_str());
+            let value = self.storage.get(&key).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+            return self.resolve_token_info(token, value).await;
         }
+        Err(SaTokenError::TokenNotFound)
     }
     
-    /// 保存 session
-    pub async fn save_session(&self, session: &SaSession) -> SaTokenResult<()> {
-        let key = format!("sa:session:{}", session.id);
-        let value = serde_json::to_string(session)
-            .map_err(|e| SaTokenError::SerializationError(e))?;
+    /// 读取 token 当前状态，不触发自动续签
+    /// 
+    /// Reads the current status of a token without auto-renewing it.
+    /// `expiring_within` is the window (seconds) reported as `ExpiringSoon`.
+    pub async fn token_status(&self, token: &TokenValue, expiring_within: i64) -> SaTokenResult<TokenStatusReport> {
+        if let Some(status) = self.status_watcher.recent_status(token.as_str()) {
+            return Ok(TokenStatusReport::terminal(status));
+        }
         
-        self.storage.set(&key, &value, None).await
+        let key = format!("sa:token:{}", token.as_str());
+        let value = self.storage.get(&key).await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        let Some(value) = value else {
+            return Ok(TokenStatusReport::terminal(TokenStatus::Invalid));
+        };
+        let token_info: TokenInfo = serde_json::from_str(&value)?;
         
-        Ok(())
+        let expires_in = token_info.expire_time.map(|time| (time - self.now()).num_seconds());
+        let status = match expires_in {
+            Some(seconds) if seconds <= 0 => TokenStatus::Expired,
+            Some(seconds) if seconds <= expiring_within => TokenStatus::ExpiringSoon,
+            _ => TokenStatus::Active,
+        };
+        Ok(TokenStatusReport { status, login_id: Some(token_info.login_id), expires_in })
     }
     
-    /// 删除 session
-    pub async fn delete_session(&self, login_id: &str) -> SaTokenResult<()> {
-        let key = format!("sa:session:{}", login_id);
-        self.storage.delete(&key).await
+    /// 长轮询 token 状态：状态非正常时立即返回，否则等待踢出、封禁、顶替、登出或进入即将过期窗口，
+    /// 超时后返回当前状态
+    /// 
+    /// Long-polls the token status. Returns at once unless the token is active,
+    /// otherwise waits for a kick-out, ban, replacement, logout or the expiring
+    /// window, and reports the current status on timeout.
+    pub async fn watch_token_status(&self, token: &TokenValue, options: StatusWatchOptions) -> SaTokenResult<TokenStatusReport> {
+        // 先订阅再读取，避免错过两者之间的变化
+        let receiver = self.status_watcher.subscribe();
+        let report = self.token_status(token, options.expiring_within).await?;
+        if report.status != TokenStatus::Active {
+            return Ok(report);
+        }
+        
+        let wait = match report.expires_in {
+            Some(seconds) => {
+                let until_expiring = std::time::Duration::from_secs((seconds - options.expiring_within).max(0) as u64);
+                options.timeout.min(until_expiring)
+            }
+            None => options.timeout,
+        };
+        let login_id = report.login_id.clone().unwrap_or_default();
+        match self.status_watcher.wait_for_change(receiver, token.as_str(), &login_id, wait).await {
+            Some(status) => Ok(TokenStatusReport { status, login_id: report.login_id, expires_in: None }),
+            None => self.token_status(token, options.expiring_within).await,
+        }
+    }
+    
+    /// 批量获取 token 信息（一次 mget 读取所有 token）
+    /// 
+    /// 返回结果与传入顺序一致，不存在、已过期或无法解析的 token 对应 `None`
+    /// 
+    /// Batch variant of `get_token_info` backed by a single storage `mget`.
+    /// Results keep the input order; missing, expired or corrupt tokens map to `None`.
+    pub async fn get_token_info_batch(&self, tokens: &[TokenValue]) -> SaTokenResult<Vec<Option<TokenInfo>>> {
+        let keys: Vec<String> = tokens.iter()
+            .map(|token| format!("sa:token:{}", token.as_str()))
+            .collect();
+        let key_refs: Vec<&str> = keys.iter().map(|k| k.as_str()).collect();
+        let values = self.storage.mget(&key_refs).await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
-        Ok(())
+        
+        let mut results = Vec::with_capacity(tokens.len());
+        for (token, value) in tokens.iter().zip(values) {
+            results.push(self.resolve_token_info(token, value).await.ok());
+        }
+        Ok(results)
     }
     
-    /// 续期 token（重置过期时间）
-    pub async fn renew_timeout(
-        &self,
-        token: &TokenValue,
-        timeout_seconds: i64,
-    ) -> SaTokenResult<()> {
-        let token_info = self.get_token_info(token).await?;
-        self.renew_timeout_internal(token, timeout_seconds, &token_info).await
+    /// 批量检查 token 是否有效（存储出错时全部视为无效）
+    /// 
+    /// Batch variant of `is_valid`; a storage error marks every token invalid.
+    pub async fn is_valid_batch(&self, tokens: &[TokenValue]) -> Vec<bool> {
+        match self.get_token_info_batch(tokens).await {
+            Ok(infos) => infos.iter().map(Option::is_some).collect(),
+            Err(_) => vec![false; tokens.len()],
+        }
     }
     
-    /// 内部续期方法（避免递归调用 get_token_info）
-    async fn renew_timeout_internal(
-        &self,
-        token: &TokenValue,
-        timeout_seconds: i64,
-        token_info: &TokenInfo,
-    ) -> SaTokenResult<()> {
-        let mut new_token_info = token_info.clone();
-        
-        // 设置新的过期时间
-        use chrono::{Utc, Duration};
-        let new_expire_time = Utc::now() + Duration::seconds(timeout_seconds);
-        new_token_info.expire_time = Some(new_expire_time);
+    /// 解析存储中读取到的 token 信息，处理过期与自动续签
+    async fn resolve_token_info(&self, token: &TokenValue, value: Option<String>) -> SaTokenResult<TokenInfo> {
+        let value = value.ok_or(SaTokenError::TokenNotFound)?;
         
-        // 保存更新后的 token 信息
-        let key = format!("sa:token:{}", token.as_str());
-        let value = serde_json::to_string(&new_token_info)
+        let mut token_info: TokenInfo = serde_json::from_str(&value)
             .map_err(|e| SaTokenError::SerializationError(e))?;
         
-        let timeout = std::time::Duration::from_secs(timeout_seconds as u64);
-        self.storage.set(&key, &value, Some(timeout)).await
-            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        // 检查是否过期
+        if token_info.is_expired_at(self.now()) {
+            // 删除过期的 token
+            self.logout(token).await?;
+            return Err(SaTokenError::TokenExpired);
+        }
         
-        Ok(())
-    }
-    
-    /// 踢人下线
-    pub async fn kick_out(&self, login_id: &str) -> SaTokenResult<()> {
-        let token_result = self.storage.get(&format!("sa:login:token:{}", login_id)).await;
+        // 超过 active_timeout 未活动的 token 被冻结（不删除）
+        if self.config().active_timeout > 0 {
+            self.check_idle(token, &token_info).await?;
+        }
         
-        if let Some(online_mgr) = &self.online_manager {
-            let _ = online_mgr.kick_out_notify(login_id, "Account kicked out".to_string()).await;
+        // 权限已变更时丢弃登录时预取的快照，鉴权回退到实时查询
+        if let Some(snapshot) = &token_info.authz
+            && snapshot.version != self.authz_version(&token_info.login_id).await?
+        {
+            token_info.authz = None;
         }
         
-        self.logout_by_login_id(login_id).await?;
-        self.delete_session(login_id).await?;
+        if let Some(validator) = &self.session_validator {
+            self.revalidate_session(token, &token_info, validator.as_ref()).await?;
+        }
+        
+        // 校验通过即视为一次活动，刷新最后活跃时间（active_timeout）
+        if self.config().active_timeout > 0 {
+            if let Err(e) = self.update_last_activity(token).await {
+                tracing::warn!("Manager: 记录最后活跃时间失败: {}", e);
+            }
+            token_info.last_active_time = self.now();
+        }
+        
+        // 如果开启了自动续签，则自动续签（永不过期的 token 保持不变）
+        // 注意：为了避免递归调用 get_token_info，这里直接更新过期时间
+        if self.config().auto_renew && token_info.expire_time.is_some() {
+            let renew_timeout = if self.config().active_timeout > 0 {
+                self.config().active_timeout
+            } else {
+                self.config().timeout
+            };
+            
+            // 直接续签（不递归调用 get_token_info）
+            let _ = self.renew_timeout_internal(token, renew_timeout, &token_info).await;
+        }
+        
+        Ok(token_info)
+    }
+    
+    /// 按间隔调用 SessionValidator 复查会话，不通过时登出该 token
+    async fn revalidate_session(
+        &self,
+        token: &TokenValue,
+        token_info: &TokenInfo,
+        validator: &dyn SessionValidator,
+    ) -> SaTokenResult<()> {
+        let key = format!("sa:session-check:{}", token.as_str());
+        let checked = self.storage.exists(&key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        if checked {
+            return Ok(());
+        }
+        
+        match validator.validate(token_info).await {
+            SessionValidation::Valid => {
+                let interval = std::time::Duration::from_secs(self.config().session_validation_interval.max(0) as u64);
+                self.storage.set(&key, "1", Some(interval)).await
+                    .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+                Ok(())
+            }
+            SessionValidation::Invalid(reason) => {
+                tracing::info!("Manager: 会话复查未通过，login_id: {}, 原因: {}", token_info.login_id, reason);
+                self.logout(token).await?;
+                Err(SaTokenError::SessionInvalidated(reason))
+            }
+        }
+    }
+    
+    /// 检查 token 是否因长时间未活动被冻结
+    /// 
+    /// 未配置 `active_timeout`（<= 0）时总是通过；距最后活跃时间超过 `active_timeout`
+    /// 秒时返回 `TokenFreeze`。每次成功读取 token 信息（`get_token_info` 等）都会刷新最后活跃时间，
+    /// 没有记录时以登录（或上次续签）时间为准。本方法只检查，不刷新。
+    /// 
+    /// Always passes when `active_timeout` is not set. Returns `TokenFreeze`
+    /// when the token has been idle longer than `active_timeout` seconds.
+    pub async fn check_activity(&self, token: &TokenValue) -> SaTokenResult<()> {
+        if self.config().active_timeout <= 0 {
+            return Ok(());
+        }
+        let value = self.storage.get(&format!("sa:token:{}", token.as_str())).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+            .ok_or(SaTokenError::TokenNotFound)?;
+        let token_info: TokenInfo = serde_json::from_str(&value)?;
+        self.check_idle(token, &token_info).await
+    }
+    
+    /// 记录 token 的最后活跃时间（`get_token_info` 校验通过时自动调用）
+    /// 
+    /// 未配置 `active_timeout` 时不写入任何数据。记录的有效期为 `active_timeout`，
+    /// 过期后回退到登录时间，同样会判定为冻结。
+    /// 
+    /// Records the token as active now; `get_token_info` calls it whenever a
+    /// token passes validation. A no-op without `active_timeout`.
+    pub async fn update_last_activity(&self, token: &TokenValue) -> SaTokenResult<()> {
+        if self.config().active_timeout <= 0 {
+            return Ok(());
+        }
+        let ttl = std::time::Duration::from_secs(self.config().active_timeout as u64);
+        self.storage.set(&activity_key(token), &self.now().timestamp().to_string(), Some(ttl)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+    
+    /// 按最后活跃时间判断 token 是否冻结
+    async fn check_idle(&self, token: &TokenValue, token_info: &TokenInfo) -> SaTokenResult<()> {
+        let recorded = self.storage.get(&activity_key(token)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+            .and_then(|value| value.parse::<i64>().ok());
+        let last_active = recorded.unwrap_or_else(|| token_info.last_active_time.timestamp());
+        
+        if self.now().timestamp() - last_active > self.config().active_timeout {
+            return Err(SaTokenError::TokenFreeze);
+        }
+        Ok(())
+    }
+    
+    /// 检查 token 是否有效
+    pub async fn is_valid(&self, token: &TokenValue) -> bool {
+        self.get_token_info(token).await.is_ok()
+    }
+    
+    /// 把语言与时区写入账号 Session，None 表示保留原值
+    /// 
+    /// Saves the locale and timezone to the account session under
+    /// `SESSION_LOCALE_KEY` / `SESSION_TIMEZONE_KEY`; `None` keeps the stored value.
+    pub async fn set_client_locale(&self, login_id: &str, locale: Option<&str>, timezone: Option<&str>) -> SaTokenResult<()> {
+        let mut session = self.get_session(login_id).await?;
+        if let Some(locale) = locale {
+            session.set(SESSION_LOCALE_KEY, locale)?;
+        }
+        if let Some(timezone) = timezone {
+            session.set(SESSION_TIMEZONE_KEY, timezone)?;
+        }
+        self.save_session(&session).await
+    }
+    
+    /// 账号 Session 中保存的语言 | Locale saved in the account session
+    pub async fn get_locale(&self, login_id: &str) -> SaTokenResult<Option<String>> {
+        Ok(self.get_session(login_id).await?.get(SESSION_LOCALE_KEY))
+    }
+    
+    /// 账号 Session 中保存的时区 | Timezone saved in the account session
+    pub async fn get_timezone(&self, login_id: &str) -> SaTokenResult<Option<String>> {
+        Ok(self.get_session(login_id).await?.get(SESSION_TIMEZONE_KEY))
+    }
+    
+    /// 获取 session
+    /// 
+    /// 开启 `auto_renew` 时，读取已存在的 session 会把它的有效期重置为 `timeout`（滑动过期）
+    /// 
+    /// With `auto_renew` on, reading an existing session slides its TTL back to `timeout`.
+    pub async fn get_session(&self, login_id: &str) -> SaTokenResult<SaSession> {
+        let key = format!("sa:session:{}", login_id);
+        let value = self.storage.get(&key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        
+        if let Some(value) = value {
+            let mut session: SaSession = serde_json::from_str(&value)
+                .map_err(|e| SaTokenError::SerializationError(e))?;
+            session.remove_expired();
+            if self.config().auto_renew && let Some(ttl) = self.session_ttl() {
+                self.storage.expire(&key, ttl).await
+                    .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+            }
+            Ok(session)
+        } else {
+            Ok(SaSession::new(login_id))
+        }
+    }
+    
+    /// 保存 session
+    /// 
+    /// session 与 token 使用相同的有效期（`timeout`），-1 表示永久保存；已过期的临时 key 不会写入
+    /// 
+    /// Sessions live as long as tokens (`timeout`, -1 for no expiry). Expired
+    /// `set_with_ttl` keys are dropped before writing.
+    pub async fn save_session(&self, session: &SaSession) -> SaTokenResult<()> {
+        let pruned;
+        let session = if session.key_expires.is_empty() {
+            session
+        } else {
+            let mut copy = session.clone();
+            copy.remove_expired();
+            pruned = copy;
+            &pruned
+        };
+        let key = format!("sa:session:{}", session.id);
+        let value = serde_json::to_string(session)
+            .map_err(|e| SaTokenError::SerializationError(e))?;
+        
+        self.check_session_quota(session, value.len())?;
+        
+        self.storage.set(&key, &value, self.session_ttl()).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        
+        self.bump_session_revision(&session.id).await
+    }
+    
+    /// session 在存储中的有效期，timeout 小于等于 0 时永久保存
+    fn session_ttl(&self) -> Option<std::time::Duration> {
+        (self.config().timeout > 0).then(|| std::time::Duration::from_secs(self.config().timeout as u64))
+    }
+    
+    /// 读取 session 的原始 JSON（管理后台排查用），不存在返回 None
+    /// 
+    /// 存储中的内容不是合法 JSON 时以字符串形式返回，便于查看损坏的数据。读取会记录审计日志。
+    /// 
+    /// Raw session JSON for support tooling. Content that isn't valid JSON is
+    /// returned as a JSON string so corrupted state can still be inspected.
+    pub async fn get_session_raw(&self, login_id: &str) -> SaTokenResult<Option<serde_json::Value>> {
+        let key = format!("sa:session:{}", login_id);
+        let value = self.storage.get(&key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        
+        tracing::info!(target: "sa_token::audit", operator = %audit_operator(), login_id = %login_id, "session inspected");
+        Ok(value.map(|raw| serde_json::from_str(&raw).unwrap_or(serde_json::Value::String(raw))))
+    }
+    
+    /// 按 RFC 6902 JSON Patch 修改 session（管理后台修复数据用），返回修改后的 session
+    /// 
+    /// 修改结果必须仍是合法的 session 且 id 不变，并受 session 大小限制约束。修改会记录审计日志。
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// let session = manager.patch_session("user_123", &json!([
+    ///     { "op": "replace", "path": "/cart", "value": [] },
+    ///     { "op": "remove", "path": "/broken_key" }
+    /// ])).await?;
+    /// ```
+    pub async fn patch_session(&self, login_id: &str, patch: &serde_json::Value) -> SaTokenResult<SaSession> {
+        let operations: json_patch::Patch = serde_json::from_value(patch.clone())
+            .map_err(|e| SaTokenError::InvalidSessionPatch(e.to_string()))?;
+        
+        let key = format!("sa:session:{}", login_id);
+        let raw = self.storage.get(&key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        let mut document = match raw {
+            Some(raw) => serde_json::from_str(&raw).unwrap_or(serde_json::Value::String(raw)),
+            None => serde_json::to_value(SaSession::new(login_id))?,
+        };
+        
+        json_patch::patch(&mut document, &operations)
+            .map_err(|e| SaTokenError::InvalidSessionPatch(e.to_string()))?;
+        let session: SaSession = serde_json::from_value(document)
+            .map_err(|e| SaTokenError::InvalidSessionPatch(format!("result is not a valid session: {}", e)))?;
+        if session.id != login_id {
+            return Err(SaTokenError::InvalidSessionPatch("session id must not change".to_string()));
+        }
+        
+        self.save_session(&session).await?;
+        tracing::info!(target: "sa_token::audit", operator = %audit_operator(), login_id = %login_id, patch = %patch, "session patched");
+        Ok(session)
+    }
+    
+    /// 删除 session
+    pub async fn delete_session(&self, login_id: &str) -> SaTokenResult<()> {
+        let key = format!("sa:session:{}", login_id);
+        self.storage.delete(&key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        self.bump_session_revision(login_id).await
+    }
+    
+    /// 统计 session 占用空间（总大小及每个 key 的大小）
+    /// 
+    /// Report the serialized size of a session and of each of its keys.
+    pub async fn session_usage(&self, login_id: &str) -> SaTokenResult<SessionUsage> {
+        Ok(self.get_session(login_id).await?.usage())
+    }
+    
+    /// 检查 session 是否超出配置的大小限制
+    fn check_session_quota(&self, session: &SaSession, total_bytes: usize) -> SaTokenResult<()> {
+        if self.config().session_max_value_bytes >= 0 {
+            let limit = self.config().session_max_value_bytes as usize;
+            for (key, value) in &session.data {
+                let size = value.to_string().len();
+                if size > limit {
+                    return Err(SaTokenError::SessionQuotaExceeded(format!(
+                        "key '{}' of session '{}' is {} bytes (limit {})",
+                        key, session.id, size, limit
+                    )));
+                }
+            }
+        }
         
-        if let Ok(Some(token_str)) = token_result {
-            let event = SaTokenEvent::kick_out(login_id, token_str);
+        if self.config().session_max_bytes >= 0 {
+            let limit = self.config().session_max_bytes as usize;
+            if total_bytes > limit {
+                return Err(SaTokenError::SessionQuotaExceeded(format!(
+                    "session '{}' is {} bytes (limit {})",
+                    session.id, total_bytes, limit
+                )));
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// 获取 session 版本号（每次写入或删除 session 时递增，从未写入过为 0）
+    /// 
+    /// 可用于实现 ETag / If-None-Match，无需读取并哈希整个 session
+    /// 
+    /// Session revision counter, incremented on every session write or delete
+    /// (0 if never written). Cheap to read, suitable as an ETag.
+    pub async fn session_revision(&self, login_id: &str) -> SaTokenResult<u64> {
+        let key = format!("sa:session-rev:{}", login_id);
+        let value = self.storage.get(&key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        Ok(value.and_then(|v| v.parse::<u64>().ok()).unwrap_or(0))
+    }
+    
+    async fn bump_session_revision(&self, login_id: &str) -> SaTokenResult<()> {
+        let key = format!("sa:session-rev:{}", login_id);
+        self.storage.incr(&key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        Ok(())
+    }
+    
+    /// 续期 token（重置过期时间）
+    pub async fn renew_timeout(
+        &self,
+        token: &TokenValue,
+        timeout_seconds: i64,
+    ) -> SaTokenResult<()> {
+        let token_info = self.get_token_info(token).await?;
+        self.renew_timeout_internal(token, timeout_seconds, &token_info).await
+    }
+    
+    /// 内部续期方法（避免递归调用 get_token_info）
+    async fn renew_timeout_internal(
+        &self,
+        token: &TokenValue,
+        timeout_seconds: i64,
+        token_info: &TokenInfo,
+    ) -> SaTokenResult<()> {
+        let key = format!("sa:token:{}", token.as_str());
+        
+        // token 已不在存储中（并发登出或已过期）时不再写回，避免把已删除的 token 复活
+        let ttl = self.storage.ttl(&key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        if ttl == KeyTtl::Missing {
+            return Err(SaTokenError::TokenNotFound);
+        }
+        
+        // 设置新的过期时间，timeout_seconds <= 0 表示永不过期
+        let timeout = (timeout_seconds > 0).then(|| std::time::Duration::from_secs(timeout_seconds as u64));
+        let mut new_token_info = token_info.clone();
+        new_token_info.expire_time = timeout.map(|_| self.now() + Duration::seconds(timeout_seconds));
+        new_token_info.last_active_time = self.now();
+        
+        // 保存更新后的 token 信息
+        let value = serde_json::to_string(&new_token_info)
+            .map_err(|e| SaTokenError::SerializationError(e))?;
+        self.storage.set(&key, &value, timeout).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        
+        // login_id -> token 映射指向该 token 且会过期时同步续期，避免映射先于 token 过期
+        let mapping_key = login_token_key(&token_info.login_id, &token_info.login_type);
+        if let Some(timeout) = timeout
+            && let Ok(Some(current)) = self.storage.get(&mapping_key).await
+            && current == token.as_str()
+            && let Ok(KeyTtl::Expires(_)) = self.storage.ttl(&mapping_key).await
+        {
+            self.storage.expire(&mapping_key, timeout).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 换发 token：为同一账号签发新 token 并使旧 token 失效，防止会话固定攻击
+    /// 
+    /// 新 token 沿用旧 token 的登录类型、设备、额外数据与过期时间，账号 Session 数据保持不变；
+    /// 同一旧 token 的并发换发只有一个成功，其余返回 `TokenNotFound`。旧 token 是当前请求的 token
+    /// 时，新 token 写入响应 Cookie。完成后发布 `TokenRenewed` 事件（`extra.old_token` 为旧 token）。
+    /// 
+    /// Issues a new token for the same account and invalidates the old one, for
+    /// use after a password change or privilege escalation. Login type, device,
+    /// extra data and expiry carry over and the account session is untouched.
+    /// Only one of several concurrent renewals of the same token succeeds.
+    /// 
+    /// # 示例 | Example
+    /// ```rust,ignore
+    /// // 修改密码之后 | after a password change
+    /// let token = manager.renew_token(&old_token).await?;
+    /// ```
+    pub async fn renew_token(&self, old_token: &TokenValue) -> SaTokenResult<TokenValue> {
+        let token_info = self.get_token_info(old_token).await?;
+        
+        // 认领旧 token，并发换发时只有第一个调用者继续
+        let claim_key = format!("sa:token-renewing:{}", old_token.as_str());
+        let claims = self.storage.incr(&claim_key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        let _ = self.storage.expire(&claim_key, std::time::Duration::from_secs(60)).await;
+        if claims != 1 {
+            return Err(SaTokenError::TokenNotFound);
+        }
+        
+        let now = self.now();
+        let token = self.generate_token(&token_info.login_id, &self.renew_options(old_token, &token_info, now)).await?;
+        let mut new_token_info = token_info.clone();
+        new_token_info.token = token.clone();
+        new_token_info.create_time = now;
+        new_token_info.last_active_time = self.now();
+        let timeout = new_token_info.expire_time
+            .and_then(|expire_time| (expire_time - now).to_std().ok());
+        
+        // 先写入新 token 与索引，再删除旧 token，换发过程中总有一个 token 可用
+        let value = serde_json::to_string(&new_token_info)?;
+        self.storage.set(&format!("sa:token:{}", token.as_str()), &value, timeout).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        let mapping_key = login_token_key(&token_info.login_id, &token_info.login_type);
+        if self.storage.get(&mapping_key).await.ok().flatten().as_deref() == Some(old_token.as_str()) {
+            self.storage.set(&mapping_key, token.as_str(), timeout).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        }
+        if let Some(device) = &token_info.device {
+            let device_key = device_token_key(&token_info.login_id, &token_info.login_type, device);
+            if self.storage.get(&device_key).await.ok().flatten().as_deref() == Some(old_token.as_str()) {
+                self.storage.set(&device_key, token.as_str(), timeout).await
+                    .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+            }
+        }
+        // 同时在线数量限制依赖 token 索引，新 token 接替旧 token 的位置
+        let mut indexed = self.indexed_login_tokens(&token_info.login_id, &token_info.login_type).await?;
+        if let Some(entry) = indexed.iter_mut().find(|(indexed_token, _)| indexed_token == old_token) {
+            *entry = (token.clone(), new_token_info.clone());
+            self.save_login_tokens_index(&token_info.login_id, &token_info.login_type, &indexed).await?;
+        }
+        
+        self.storage.delete(&format!("sa:token:{}", old_token.as_str())).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        let _ = self.storage.delete(&activity_key(old_token)).await;
+        
+        if SaTokenContext::get_current().is_some_and(|ctx| ctx.token.as_ref() == Some(old_token)) {
+            let max_age = timeout.map(|timeout| timeout.as_secs() as i64).unwrap_or(PERMANENT_COOKIE_MAX_AGE);
+            self.queue_token_cookie(token.as_str(), Some(max_age).filter(|_| token_info.remember != Some(false)));
+        }
+        
+        let event = SaTokenEvent::token_renewed(&token_info.login_id, token.as_str(), old_token.as_str())
+            .with_login_type(&token_info.login_type);
+        self.event_bus.publish(event).await;
+        
+        Ok(token)
+    }
+    
+    /// 换发 token 的登录参数：沿用旧 token 的登录类型、设备与 JWT 自定义声明，有效期为剩余时间
+    fn renew_options(&self, old_token: &TokenValue, token_info: &TokenInfo, now: DateTime<Utc>) -> LoginOptions {
+        let mut options = LoginOptions::new().login_type(&token_info.login_type);
+        options.device = token_info.device.clone();
+        options.timeout = Some(match token_info.expire_time {
+            Some(expire_time) => (expire_time - now).num_seconds().max(1),
+            None => -1,
+        });
+        let config = self.config();
+        if matches!(config.token_style, TokenStyle::Jwt)
+            && let Ok(claims) = JwtManager::from_config(&config)
+                .and_then(|jwt| jwt.decode_without_validation(old_token.as_str()))
+        {
+            options.claims = claims.get_claims().clone();
+        }
+        options
+    }
+    
+    /// 为被踢 token 写入宽限期墓碑
+    async fn write_kick_out_tombstones(&self, tokens: &[(TokenValue, TokenInfo)]) -> SaTokenResult<()> {
+        let grace = self.config().kick_out_grace_period.min(MAX_KICK_OUT_GRACE_PERIOD);
+        if grace <= 0 {
+            return Ok(());
+        }
+        let ttl = std::time::Duration::from_secs(grace as u64);
+        for (token, token_info) in tokens {
+            let revoked = RevokedToken { revoked_at: self.now(), token_info: token_info.clone() };
+            self.storage.set(&revoked_token_key(token.as_str()), &serde_json::to_string(&revoked)?, Some(ttl)).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        }
+        Ok(())
+    }
+    
+    /// 踢人宽限期内，踢人之前开始的请求仍可读取被踢 token 的信息
+    async fn revoked_token_in_grace(&self, token: &TokenValue, started_at: DateTime<Utc>) -> SaTokenResult<Option<TokenInfo>> {
+        let value = self.storage.get(&revoked_token_key(token.as_str())).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        let Some(value) = value else {
+            return Ok(None);
+        };
+        let revoked: RevokedToken = serde_json::from_str(&value)?;
+        Ok((started_at < revoked.revoked_at).then_some(revoked.token_info))
+    }
+    
+    /// 写入失效 token 的标记（踢人原因、被顶替），有效期为 token 剩余的有效期
+    async fn write_token_marker(&self, key: &str, token_info: &TokenInfo, value: &str) -> SaTokenResult<()> {
+        let ttl = match token_info.expire_time {
+            Some(expire_time) => (expire_time - self.now()).num_seconds(),
+            None => TOKEN_MARKER_TTL,
+        };
+        if ttl <= 0 {
+            return Ok(());
+        }
+        self.storage.set(key, value, Some(std::time::Duration::from_secs(ttl as u64))).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+    
+    /// 顶替旧 token：写入顶替标记，发布 `Replaced` 事件后登出
+    /// 
+    /// 旧 token 之后再访问时返回 `SaTokenError::Replaced`（对应 Java 版的 -4 被顶下线）
+    async fn replace_token(&self, login_id: &str, previous: &TokenValue) -> SaTokenResult<()> {
+        let stored = self.storage.get(&format!("sa:token:{}", previous.as_str())).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+            .and_then(|value| serde_json::from_str::<TokenInfo>(&value).ok());
+        if let Some(token_info) = &stored {
+            self.write_token_marker(&replaced_token_key(previous.as_str()), token_info, login_id).await?;
+            let event = SaTokenEvent::replaced(login_id, previous.as_str())
+                .with_login_type(&token_info.login_type);
             self.event_bus.publish(event).await;
         }
+        self.logout(previous).await
+    }
+    
+    /// 踢人下线
+    /// 
+    /// 配置了 `kick_out_grace_period` 时，被踢 token 会留下短期墓碑，踢人之前已开始的请求在宽限期内仍可通过校验
+    pub async fn kick_out(&self, login_id: &str) -> SaTokenResult<()> {
+        self.kick_out_notifying(login_id, "Account kicked out".to_string()).await
+    }
+    
+    /// 带原因踢人下线
+    /// 
+    /// 被踢的 token 之后再访问时返回 `SaTokenError::KickedOut(reason)`（而不是 `TokenNotFound`），
+    /// 直到 token 原本的过期时间；注册了 `OnlineManager` 时会把原因随踢人消息推送给客户端。
+    /// 
+    /// Later requests with a kicked token get `SaTokenError::KickedOut(reason)`
+    /// instead of `TokenNotFound` until the token would have expired; the reason
+    /// is also pushed as a KickOut message through a registered `OnlineManager`.
+    pub async fn kick_out_with_reason(&self, login_id: &str, reason: &str) -> SaTokenResult<()> {
+        for (token, token_info) in self.login_tokens(login_id).await {
+            self.write_token_marker(&kicked_token_key(token.as_str()), &token_info, reason).await?;
+        }
+        self.kick_out_notifying(login_id, reason.to_string()).await
+    }
+    
+    async fn kick_out_notifying(&self, login_id: &str, notice: String) -> SaTokenResult<()> {
+        let tokens = self.login_tokens(login_id).await;
+        self.write_kick_out_tombstones(&tokens).await?;
+        
+        if let Some(online_mgr) = &self.online_manager {
+            let _ = online_mgr.kick_out_notify(login_id, notice).await;
+        }
+        
+        // 先为每个 token 发布踢出事件，状态监听者据此区分踢出与随后的登出
+        for (token, token_info) in &tokens {
+            let event = SaTokenEvent::kick_out(login_id, token.as_str()).with_login_type(&token_info.login_type);
+            self.event_bus.publish(event).await;
+        }
+        
+        self.logout_by_login_id(login_id).await?;
+        self.delete_session(login_id).await?;
         
         Ok(())
     }
+    
+    /// 在指定登录类型（账号体系）中踢人下线，并删除该体系下的账号 Session
+    /// 
+    /// `kick_out` 踢掉账号在所有登录类型下的 token；多账号体系中同名 ID 属于不同的人时使用本方法。
+    pub async fn kick_out_by_login_type(&self, login_id: &str, login_type: &str) -> SaTokenResult<()> {
+        let tokens = self.login_type_tokens(login_id, login_type).await;
+        self.write_kick_out_tombstones(&tokens).await?;
+        
+        for (token, _) in &tokens {
+            let event = SaTokenEvent::kick_out(login_id, token.as_str()).with_login_type(login_type);
+            self.event_bus.publish(event).await;
+            self.logout(token).await?;
+        }
+        
+        self.delete_session(&account_key(login_id, login_type)).await
+    }
+    
+    /// 踢人下线（仅指定设备），其他设备上的会话不受影响
+    pub async fn kick_out_by_device(&self, login_id: &str, device: &str) -> SaTokenResult<()> {
+        let tokens = self.device_tokens(login_id, DEFAULT_LOGIN_TYPE, device).await;
+        self.write_kick_out_tombstones(&tokens).await?;
+        
+        for (token, _) in &tokens {
+            let event = SaTokenEvent::kick_out(login_id, token.as_str()).with_device(device);
+            self.event_bus.publish(event).await;
+            self.logout(token).await?;
+        }
+        
+        Ok(())
+    }
+    
+    // ==================== 账号封禁 ====================
+    
+    /// 封禁账号（`seconds` 为 -1 时永久封禁）
+    /// 
+    /// 封禁期间无法登录，已签发的 token 立即失效，并发布 `Banned` 事件
+    pub async fn disable(&self, login_id: &str, seconds: i64) -> SaTokenResult<()> {
+        self.disable_by_service(login_id, DEFAULT_DISABLE_SERVICE, seconds).await
+    }
+    
+    /// 封禁账号的指定服务（如 `comment`），`seconds` 为 -1 时永久封禁
+    /// 
+    /// 只有 `login` 服务会阻止登录并使 token 失效，其他服务由业务通过 `check_disable_by_service` 校验
+    pub async fn disable_by_service(&self, login_id: &str, service: &str, seconds: i64) -> SaTokenResult<()> {
+        self.save_disable(login_id, service, seconds).await?;
+        
+        if service == DEFAULT_DISABLE_SERVICE {
+            // 先发布封禁事件，状态监听者据此区分封禁与随后的登出
+            self.publish_banned(login_id, DEFAULT_LOGIN_TYPE).await;
+            self.logout_by_login_id(login_id).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 在指定登录类型（账号体系）中封禁账号，`seconds` 为 -1 时永久封禁
+    /// 
+    /// 封禁记录以 `account_key` 为键，只有该体系下的 token 会被登出，其他体系中的同名账号不受影响。
+    pub async fn disable_by_login_type(&self, login_id: &str, login_type: &str, seconds: i64) -> SaTokenResult<()> {
+        self.save_disable(&account_key(login_id, login_type), DEFAULT_DISABLE_SERVICE, seconds).await?;
+        self.publish_banned(login_id, login_type).await;
+        self.logout_by_login_type(login_id, login_type).await
+    }
+    
+    /// 写入封禁记录
+    async fn save_disable(&self, account: &str, service: &str, seconds: i64) -> SaTokenResult<()> {
+        if seconds == 0 || seconds < -1 {
+            return Err(SaTokenError::ConfigError(format!("invalid disable time: {}", seconds)));
+        }
+        
+        let now = self.now();
+        let info = DisableInfo {
+            login_id: account.to_string(),
+            service: service.to_string(),
+            disabled_at: now,
+            until: (seconds > 0).then(|| now + Duration::seconds(seconds)),
+        };
+        let ttl = (seconds > 0).then(|| std::time::Duration::from_secs(seconds as u64));
+        self.storage.set(&disable_key(service, account), &serde_json::to_string(&info)?, ttl).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+    
+    /// 发布 `Banned` 事件，带上账号在该登录类型下最近一次登录的 token
+    async fn publish_banned(&self, login_id: &str, login_type: &str) {
+        let mut event = SaTokenEvent::banned(login_id).with_login_type(login_type);
+        if let Ok(Some(token)) = self.storage.get(&login_token_key(login_id, login_type)).await {
+            event = event.with_token(token);
+        }
+        self.event_bus.publish(event).await;
+    }
+    
+    /// 获取封禁记录，未封禁或已到期返回 None
+    pub async fn get_disable_info(&self, login_id: &str, service: &str) -> SaTokenResult<Option<DisableInfo>> {
+        let value = self.storage.get(&disable_key(service, login_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        let Some(value) = value else {
+            return Ok(None);
+        };
+        let info: DisableInfo = serde_json::from_str(&value)?;
+        Ok(info.is_active_at(self.now()).then_some(info))
+    }
+    
+    /// 账号是否被封禁（读取失败时视为未封禁，需要严格校验时使用 `check_disable`）
+    pub async fn is_disabled(&self, login_id: &str) -> bool {
+        self.is_disabled_by_service(login_id, DEFAULT_DISABLE_SERVICE).await
+    }
+    
+    /// 账号的指定服务是否被封禁
+    pub async fn is_disabled_by_service(&self, login_id: &str, service: &str) -> bool {
+        matches!(self.get_disable_info(login_id, service).await, Ok(Some(_)))
+    }
+    
+    /// 校验账号未被封禁，封禁时返回 `AccountBanned`
+    pub async fn check_disable(&self, login_id: &str) -> SaTokenResult<()> {
+        self.check_disable_by_service(login_id, DEFAULT_DISABLE_SERVICE).await
+    }
+    
+    /// 校验账号的指定服务未被封禁
+    pub async fn check_disable_by_service(&self, login_id: &str, service: &str) -> SaTokenResult<()> {
+        match self.get_disable_info(login_id, service).await? {
+            Some(info) => Err(SaTokenError::AccountBanned(info.until_display())),
+            None => Ok(()),
+        }
+    }
+    
+    /// 剩余封禁时间（秒），永久封禁返回 -1，未封禁返回 None
+    pub async fn until_disabled_time(&self, login_id: &str) -> SaTokenResult<Option<i64>> {
+        self.until_disabled_time_by_service(login_id, DEFAULT_DISABLE_SERVICE).await
+    }
+    
+    /// 指定服务的剩余封禁时间（秒），永久封禁返回 -1，未封禁返回 None
+    pub async fn until_disabled_time_by_service(&self, login_id: &str, service: &str) -> SaTokenResult<Option<i64>> {
+        Ok(self.get_disable_info(login_id, service).await?.map(|info| info.remaining_seconds()))
+    }
+    
+    /// 解除封禁，发布 `Unbanned` 事件
+    pub async fn untie_disable(&self, login_id: &str) -> SaTokenResult<()> {
+        self.untie_disable_by_service(login_id, DEFAULT_DISABLE_SERVICE).await
+    }
+    
+    /// 解除指定服务的封禁
+    pub async fn untie_disable_by_service(&self, login_id: &str, service: &str) -> SaTokenResult<()> {
+        self.storage.delete(&disable_key(service, login_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        
+        if service == DEFAULT_DISABLE_SERVICE {
+            self.event_bus.publish(SaTokenEvent::unbanned(login_id)).await;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_adapter::storage::{KeyTtl, StorageResult};
+    use sa_token_storage_memory::MemoryStorage;
+    
+    fn test_manager() -> SaTokenManager {
+        SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default())
+    }
+    
+    #[tokio::test]
+    async fn test_injected_clock_drives_token_expiry() {
+        // 注入测试时钟本身，manager 与 `time::now()` 读到同一个时间
+        let clock = crate::time::freeze();
+        let config = SaTokenConfig::builder().timeout(3600).build_config();
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config)
+            .with_clock(Arc::new(clock.clone()));
+        
+        let token = manager.login("user_1").await.unwrap();
+        let info = manager.get_token_info(&token).await.unwrap();
+        assert_eq!(info.create_time, clock.now());
+        
+        clock.advance(Duration::seconds(3599));
+        assert!(manager.is_valid(&token).await);
+        assert!(!info.is_expired());
+        
+        crate::time::advance(Duration::seconds(2));
+        assert!(!manager.is_valid(&token).await);
+        assert!(info.is_expired());
+        crate::time::reset();
+    }
+    
+    #[tokio::test]
+    async fn test_max_login_count_evicts_by_policy() {
+        for (eviction, evicted) in [(LoginEviction::Oldest, 0), (LoginEviction::Newest, 1)] {
+            let clock = sa_token_adapter::clock::MockClock::default();
+            let config = SaTokenConfig::builder()
+                .max_login_count(2)
+                .login_eviction(eviction)
+                .build_config();
+            let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config)
+                .with_clock(Arc::new(clock.clone()));
+            
+            let mut tokens = Vec::new();
+            for _ in 0..2 {
+                tokens.push(manager.login("user_1").await.unwrap());
+                clock.advance(Duration::seconds(1));
+            }
+            let result = manager.login_ex("user_1", LoginOptions::new()).await.unwrap();
+            assert_eq!(result.replaced_tokens, [tokens[evicted].clone()]);
+            
+            assert!(matches!(manager.get_token_info(&tokens[evicted]).await, Err(SaTokenError::Replaced)));
+            assert!(manager.is_valid(&tokens[1 - evicted]).await);
+            assert!(manager.is_valid(&result.token).await);
+        }
+    }
+    
+    /// 不支持按模式查找键的存储，用来确认登录时不会扫描全部 token
+    struct NoScanStorage(MemoryStorage);
+    
+    #[async_trait::async_trait]
+    impl SaStorage for NoScanStorage {
+        async fn get(&self, key: &str) -> StorageResult<Option<String>> {
+            self.0.get(key).await
+        }
+        async fn set(&self, key: &str, value: &str, ttl: Option<std::time::Duration>) -> StorageResult<()> {
+            self.0.set(key, value, ttl).await
+        }
+        async fn delete(&self, key: &str) -> StorageResult<()> {
+            self.0.delete(key).await
+        }
+        async fn exists(&self, key: &str) -> StorageResult<bool> {
+            self.0.exists(key).await
+        }
+        async fn expire(&self, key: &str, ttl: std::time::Duration) -> StorageResult<()> {
+            self.0.expire(key, ttl).await
+        }
+        async fn ttl(&self, key: &str) -> StorageResult<KeyTtl> {
+            self.0.ttl(key).await
+        }
+        async fn clear(&self) -> StorageResult<()> {
+            self.0.clear().await
+        }
+        async fn keys(&self, pattern: &str) -> StorageResult<Vec<String>> {
+            panic!("unexpected key scan: {}", pattern)
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_max_login_count_uses_account_token_index() {
+        let config = SaTokenConfig::builder().max_login_count(2).build_config();
+        let manager = SaTokenManager::new(Arc::new(NoScanStorage(MemoryStorage::new())), config);
+        
+        let first = manager.login("user_1").await.unwrap();
+        let second = manager.login("user_1").await.unwrap();
+        let other = manager.login("user_2").await.unwrap();
+        
+        // 登出的 token 从索引中剔除，不占名额 | Logged-out tokens drop out of the index and free their slot
+        manager.logout(&second).await.unwrap();
+        let third = manager.login_ex("user_1", LoginOptions::new()).await.unwrap();
+        assert!(third.replaced_tokens.is_empty());
+        
+        let fourth = manager.login_ex("user_1", LoginOptions::new()).await.unwrap();
+        assert_eq!(fourth.replaced_tokens, [first]);
+        assert!(manager.is_valid(&third.token).await);
+        assert!(manager.is_valid(&other).await);
+        
+        let index = manager.storage.get(&login_tokens_index_key("user_1", DEFAULT_LOGIN_TYPE)).await.unwrap().unwrap();
+        let index: Vec<String> = serde_json::from_str(&index).unwrap();
+        assert_eq!(index, [third.token.as_str(), fourth.token.as_str()]);
+    }
+    
+    #[tokio::test]
+    async fn test_renewed_token_keeps_its_login_count_slot() {
+        let config = SaTokenConfig::builder().max_login_count(1).build_config();
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        
+        let first = manager.login("user_1").await.unwrap();
+        let renewed = manager.renew_token(&first).await.unwrap();
+        let second = manager.login_ex("user_1", LoginOptions::new()).await.unwrap();
+        
+        assert!(!manager.is_valid(&renewed).await);
+        assert!(manager.is_valid(&second.token).await);
+        assert_eq!(second.replaced_tokens, [renewed]);
+    }
+    
+    #[tokio::test]
+    async fn test_login_with_remember_sets_cookie_max_age() {
+        let config = SaTokenConfig::builder()
+            .timeout(3600)
+            .remember_timeout(7 * 86400)
+            .write_cookie(true)
+            .build_config();
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config)
+            .with_clock(Arc::new(sa_token_adapter::clock::MockClock::default()));
+        
+        for (remember, max_age) in [(true, Some(7 * 86400)), (false, None)] {
+            let ctx = SaTokenContext::new();
+            let token = SaTokenContext::scope(ctx.clone(), 
+                manager.login_with("user_1", LoginOptions::new().remember(remember))
+            ).await.unwrap();
+            
+            let cookie = ctx.take_response_cookie().unwrap();
+            assert_eq!(cookie.value, token.as_str());
+            assert_eq!(cookie.options.max_age, max_age);
+            
+            let token_info = manager.get_token_info(&token).await.unwrap();
+            let ttl = (token_info.expire_time.unwrap() - token_info.create_time).num_seconds();
+            assert_eq!(ttl, if remember { 7 * 86400 } else { 3600 });
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_custom_and_sequence_token_styles() {
+        let config = SaTokenConfig::builder()
+            .token_style(TokenStyle::custom(|_: &str| "fixed-token".to_string()))
+            .max_try_times(3)
+            .build_config();
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        assert_eq!(manager.login("user_1").await.unwrap().as_str(), "fixed-token");
+        // 与已有 token 重复，重试后仍然重复
+        assert!(matches!(manager.login("user_2").await, Err(SaTokenError::TokenGenerateFailed(3))));
+        
+        let config = SaTokenConfig::builder().token_style(TokenStyle::sequence("t-")).build_config();
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        assert_eq!(manager.login("user_1").await.unwrap().as_str(), "t-1");
+        assert_eq!(manager.login("user_2").await.unwrap().as_str(), "t-2");
+        
+        assert_eq!(DefaultTokenGenerator::generate_random(128).as_str().len(), 128);
+    }
+    
+    #[tokio::test]
+    async fn test_is_valid_batch_keeps_input_order() {
+        let manager = test_manager();
+        let t1 = manager.login("user_1").await.unwrap();
+        let t2 = manager.login("user_2").await.unwrap();
+        let missing = TokenValue::new("not-a-token");
+        
+        let result = manager.is_valid_batch(&[t1.clone(), missing, t2.clone()]).await;
+        assert_eq!(result, vec![true, false, true]);
+        
+        manager.logout(&t1).await.unwrap();
+        assert_eq!(manager.is_valid_batch(&[t1, t2]).await, vec![false, true]);
+    }
+    
+    #[tokio::test]
+    async fn test_update_config_applies_to_clones_and_publishes_event() {
+        struct Changes(std::sync::Mutex<Vec<serde_json::Value>>);
+        
+        #[async_trait::async_trait]
+        impl crate::event::SaTokenListener for Changes {
+            async fn on_event(&self, event: &SaTokenEvent) {
+                if event.event_type == crate::event::SaTokenEventType::ConfigChanged {
+                    self.0.lock().unwrap().push(event.extra.clone().unwrap());
+                }
+            }
+        }
+        
+        let manager = test_manager();
+        let plugin_state = manager.clone();
+        let changes = Arc::new(Changes(std::sync::Mutex::new(Vec::new())));
+        manager.event_bus.register(changes.clone());
+        
+        let mut config = (*manager.config()).clone();
+        config.timeout = 60;
+        config.token_name = "X-Token".to_string();
+        manager.update_config(config).await;
+        
+        assert_eq!(plugin_state.config().token_name, "X-Token");
+        let token = plugin_state.login("user_1").await.unwrap();
+        let info = plugin_state.get_token_info(&token).await.unwrap();
+        assert_eq!(info.expire_time.map(|t| (t - info.create_time).num_seconds()), Some(60));
+        
+        let recorded = changes.0.lock().unwrap();
+        assert_eq!(recorded.len(), 1);
+        let mut changed: Vec<&str> = recorded[0]["changed"].as_array().unwrap()
+            .iter().filter_map(|v| v.as_str()).collect();
+        changed.sort();
+        assert_eq!(changed, vec!["timeout", "token_name"]);
+    }
+    
+    #[tokio::test]
+    async fn test_kick_out_grace_period_tolerates_in_flight_requests() {
+        let config = SaTokenConfig::builder().kick_out_grace_period(5).build_config();
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        let token = manager.login("user_1").await.unwrap();
+        
+        let mut ctx = SaTokenContext::new();
+        ctx.token = Some(token.clone());
+        let started_at = ctx.started_at;
+        SaTokenContext::set_current(ctx);
+        
+        manager.kick_out("user_1").await.unwrap();
+        
+        // 踢人之前开始的请求仍能读取
+        assert_eq!(manager.get_token_info(&token).await.unwrap().login_id, "user_1");
+        assert!(manager.get_token_info_started_at(&token, started_at).await.is_ok());
+        SaTokenContext::clear();
+        
+        // 新请求被拒绝
+        assert!(manager.get_token_info(&token).await.is_err());
+        assert!(manager.get_token_info_started_at(&token, crate::time::now()).await.is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_kick_out_with_reason_marks_old_token() {
+        let online = Arc::new(OnlineManager::new());
+        let pusher = Arc::new(crate::online::InMemoryPusher::new());
+        online.register_pusher(pusher.clone()).await;
+        let manager = test_manager().with_online_manager(online);
+        let token = manager.login("user_1").await.unwrap();
+        
+        manager.kick_out_with_reason("user_1", "Logged in elsewhere").await.unwrap();
+        
+        match manager.get_token_info(&token).await {
+            Err(SaTokenError::KickedOut(reason)) => assert_eq!(reason, "Logged in elsewhere"),
+            other => panic!("unexpected {:?}", other.map(|info| info.login_id)),
+        }
+        let messages = pusher.get_messages("user_1").await;
+        assert_eq!(messages.last().unwrap().message_type, crate::online::MessageType::KickOut);
+        assert_eq!(messages.last().unwrap().content, "Logged in elsewhere");
+        
+        // 普通踢人不留原因标记
+        let token = manager.login("user_2").await.unwrap();
+        manager.kick_out("user_2").await.unwrap();
+        assert!(matches!(manager.get_token_info(&token).await, Err(SaTokenError::TokenNotFound)));
+    }
+    
+    /// 记录收到的踢出事件 | Records the kick-out events it receives
+    #[derive(Default)]
+    struct KickOutRecorder(std::sync::Mutex<Vec<(String, String)>>);
+    
+    #[async_trait::async_trait]
+    impl crate::event::SaTokenListener for KickOutRecorder {
+        async fn on_kick_out(&self, _login_id: &str, token: &str, login_type: &str) {
+            self.0.lock().unwrap().push((token.to_string(), login_type.to_string()));
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_kick_out_publishes_an_event_per_token() {
+        let manager = test_manager();
+        let recorder = Arc::new(KickOutRecorder::default());
+        manager.event_bus().register_inline(recorder.clone());
+        let user = manager.login("user_1").await.unwrap();
+        let admin = manager.login_with("user_1", LoginOptions::new().login_type("admin")).await.unwrap();
+        
+        manager.kick_out("user_1").await.unwrap();
+        
+        let mut events = recorder.0.lock().unwrap().clone();
+        events.sort();
+        let mut expected = vec![
+            (user.as_str().to_string(), DEFAULT_LOGIN_TYPE.to_string()),
+            (admin.as_str().to_string(), "admin".to_string()),
+        ];
+        expected.sort();
+        assert_eq!(events, expected);
+    }
+    
+    #[tokio::test]
+    async fn test_renew_token_keeps_session_and_invalidates_old() {
+        let manager = test_manager();
+        let old = manager.login_with_device("user_1", "web").await.unwrap();
+        let mut session = manager.get_session("user_1").await.unwrap();
+        session.set("cart", 3).unwrap();
+        manager.save_session(&session).await.unwrap();
+        let old_info = manager.get_token_info(&old).await.unwrap();
+        
+        let token = manager.renew_token(&old).await.unwrap();
+        assert_ne!(token, old);
+        assert!(!manager.is_valid(&old).await);
+        let info = manager.get_token_info(&token).await.unwrap();
+        assert_eq!((info.login_id.as_str(), info.device.as_deref()), ("user_1", Some("web")));
+        assert_eq!(info.expire_time, old_info.expire_time);
+        assert_eq!(manager.get_token_by_device("user_1", "web").await.unwrap(), token);
+        assert_eq!(manager.get_session("user_1").await.unwrap().get::<i32>("cart"), Some(3));
+        
+        // 旧 token 不能再次换发
+        assert!(manager.renew_token(&old).await.is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_login_records_client_ip_and_user_agent() {
+        let manager = test_manager();
+        let mut ctx = SaTokenContext::new();
+        ctx.capture_client(Some("203.0.113.9"), Some("Mozilla/5.0"));
+        let token = SaTokenContext::scope(ctx.clone(), manager.login("user_1")).await.unwrap();
+        let info = manager.get_token_info(&token).await.unwrap();
+        assert_eq!((info.login_ip.as_deref(), info.user_agent.as_deref()), (Some("203.0.113.9"), Some("Mozilla/5.0")));
+        assert!(manager.get_token_info(&manager.login("user_2").await.unwrap()).await.unwrap().login_ip.is_none());
+        
+        // 在线列表中的连接同样带上来源
+        let online = OnlineManager::new();
+        SaTokenContext::scope(ctx, online.mark_online(crate::online::OnlineUser {
+            login_id: "user_1".to_string(),
+            token: token.as_str().to_string(),
+            device: "web".to_string(),
+            connect_time: crate::time::now(),
+            last_activity: crate::time::now(),
+            metadata: HashMap::new(),
+            login_ip: None,
+            user_agent: None,
+        })).await;
+        let page = online.list_online(1, 10, None, None).await.unwrap();
+        assert_eq!(page.users[0].login_ip.as_deref(), Some("203.0.113.9"));
+    }
+    
+    #[tokio::test]
+    async fn test_token_bound_to_login_ip() {
+        let config = SaTokenConfig { token_bind_ip: true, trusted_proxies: vec!["10.0.0.0/8".to_string()], ..Default::default() };
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        let mut ctx = SaTokenContext::new();
+        ctx.capture_client(Some("203.0.113.9"), None);
+        let token = SaTokenContext::scope(ctx, manager.login("user_1")).await.unwrap();
+        
+        assert!(manager.get_token_info_from(&token, Some("203.0.113.9")).await.is_ok());
+        assert!(matches!(manager.get_token_info_from(&token, Some("198.51.100.4")).await, Err(SaTokenError::IpMismatch)));
+        assert!(matches!(manager.get_token_info_from(&token, None).await, Err(SaTokenError::IpMismatch)));
+        
+        // 只有受信任代理转发的 X-Forwarded-For 才被采信
+        let via_proxy = manager.resolve_client_ip(Some("10.0.0.2"), Some("203.0.113.9"), None);
+        assert!(manager.get_token_info_from(&token, via_proxy.as_deref()).await.is_ok());
+        let forged = manager.resolve_client_ip(Some("198.51.100.4"), Some("203.0.113.9"), None);
+        assert_eq!(forged.as_deref(), Some("198.51.100.4"));
+        
+        // 登录时未记录 IP 的 token 不受限制
+        let unbound = manager.login("user_2").await.unwrap();
+        assert!(manager.get_token_info_from(&unbound, Some("198.51.100.4")).await.is_ok());
+    }
+    
+    #[tokio::test]
+    async fn test_get_token_infos_in_one_batch() {
+        let manager = test_manager();
+        let a = manager.login("user_a").await.unwrap();
+        let b = manager.login("user_b").await.unwrap();
+        let short = manager.login_with("user_c", LoginOptions::new().timeout(60)).await.unwrap();
+        manager.logout(&b).await.unwrap();
+        
+        crate::time::advance(Duration::seconds(120));
+        let infos = manager.get_token_infos(&[a.clone(), b, TokenValue::new("missing"), short.clone()]).await.unwrap();
+        crate::time::reset();
+        
+        let login_ids: Vec<Option<&str>> = infos.iter().map(|info| info.as_ref().map(|i| i.login_id.as_str())).collect();
+        assert_eq!(login_ids, vec![Some("user_a"), None, None, None]);
+        // 只读查看不会删除过期 token
+        assert!(manager.storage.exists(&format!("sa:token:{}", short.as_str())).await.unwrap());
+        assert!(manager.get_token_infos(&[]).await.unwrap().is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_login_ex_reports_replaced_tokens() {
+        let config = SaTokenConfig::builder().is_concurrent(false).build_config();
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        
+        let first = manager.login_ex("user_1", LoginOptions::new().login_type("admin")).await.unwrap();
+        assert!(first.is_new_session);
+        assert!(first.replaced_tokens.is_empty());
+        assert_eq!(first.token_info.login_type, "admin");
+        assert_eq!(first.session.id, "admin:user_1");
+        manager.save_session(&first.session).await.unwrap();
+        
+        // 其他账号体系的登录不会顶替
+        let other = manager.login_ex("user_1", LoginOptions::new()).await.unwrap();
+        assert!(other.is_new_session && other.replaced_tokens.is_empty());
+        
+        let second = manager.login_ex("user_1", LoginOptions::new().login_type("admin")).await.unwrap();
+        assert!(!second.is_new_session);
+        assert_eq!(second.replaced_tokens, vec![first.token.clone()]);
+        assert!(!manager.is_valid(&first.token).await);
+        // 被顶替的 token 返回 Replaced，状态监听者收到的是 Replaced 而不是 LoggedOut
+        assert!(matches!(manager.get_token_info(&first.token).await, Err(SaTokenError::Replaced)));
+        assert_eq!(manager.status_watcher().recent_status(first.token.as_str()), Some(TokenStatus::Replaced));
+        assert!(manager.is_valid(&second.token).await);
+        assert_eq!(manager.get_token_info(&second.token).await.unwrap().token, second.token_info.token);
+    }
+    
+    #[tokio::test]
+    async fn test_device_scoped_login_and_logout() {
+        let config = SaTokenConfig::builder().is_concurrent(false).build_config();
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        
+        let web = manager.login_with_device("user_1", "web").await.unwrap();
+        let mobile = manager.login_with_device("user_1", "mobile").await.unwrap();
+        assert!(manager.is_valid(&web).await);
+        assert_eq!(manager.get_token_by_device("user_1", "mobile").await.unwrap(), mobile);
+        
+        // 同一设备再次登录只顶替该设备的旧 token
+        let web2 = manager.login_with_device("user_1", "web").await.unwrap();
+        assert!(!manager.is_valid(&web).await);
+        assert!(manager.is_valid(&web2).await);
+        assert!(manager.is_valid(&mobile).await);
+        
+        manager.logout_by_device("user_1", "web").await.unwrap();
+        assert!(!manager.is_valid(&web2).await);
+        assert!(manager.get_token_by_device("user_1", "web").await.is_err());
+        
+        manager.kick_out_by_device("user_1", "mobile").await.unwrap();
+        assert!(!manager.is_valid(&mobile).await);
+        assert_eq!(manager.token_status(&mobile, 60).await.unwrap().status, TokenStatus::Kicked);
+    }
+    
+    #[tokio::test]
+    async fn test_patch_session_repairs_corrupted_value() {
+        let manager = test_manager();
+        let mut session = manager.get_session("user_1").await.unwrap();
+        session.set("cart", "not-a-list").unwrap();
+        session.set("theme", "dark").unwrap();
+        manager.save_session(&session).await.unwrap();
+        
+        let patched = manager.patch_session("user_1", &serde_json::json!([
+            { "op": "replace", "path": "/cart", "value": [] },
+            { "op": "remove", "path": "/theme" }
+        ])).await.unwrap();
+        assert_eq!(patched.get::<Vec<String>>("cart"), Some(Vec::new()));
+        assert!(!patched.has("theme"));
+        
+        let raw = manager.get_session_raw("user_1").await.unwrap().unwrap();
+        assert_eq!(raw["cart"], serde_json::json!([]));
+        assert_eq!(manager.session_revision("user_1").await.unwrap(), 2);
+    }
+    
+    #[tokio::test]
+    async fn test_patch_session_rejects_invalid_result() {
+        let manager = test_manager();
+        assert!(manager.get_session_raw("user_2").await.unwrap().is_none());
+        
+        // 存储中是无法解析的内容时，原样以字符串返回，并可整体替换
+        manager.storage.set("sa:session:user_2", "{broken", None).await.unwrap();
+        assert_eq!(manager.get_session_raw("user_2").await.unwrap(), Some(serde_json::json!("{broken")));
+        
+        let id_change = serde_json::json!([{ "op": "replace", "path": "", "value": { "id": "other", "create_time": crate::time::now() } }]);
+        assert!(matches!(manager.patch_session("user_2", &id_change).await, Err(SaTokenError::InvalidSessionPatch(_))));
+        let not_patch = serde_json::json!({ "op": "remove" });
+        assert!(matches!(manager.patch_session("user_2", &not_patch).await, Err(SaTokenError::InvalidSessionPatch(_))));
+        
+        let reset = serde_json::json!([{ "op": "replace", "path": "", "value": { "id": "user_2", "create_time": crate::time::now() } }]);
+        assert!(manager.patch_session("user_2", &reset).await.is_ok());
+        assert!(manager.get_session("user_2").await.is_ok());
+    }
+    
+    struct DbChecker;
+    
+    #[async_trait::async_trait]
+    impl PermissionChecker for DbChecker {
+        async fn has_permission(&self, login_id: &str, permission: &str) -> SaTokenResult<bool> {
+            Ok(login_id == "db_user" && permission == "order:refund")
+        }
+        
+        async fn get_permissions(&self, login_id: &str) -> SaTokenResult<Vec<String>> {
+            Ok(if login_id == "db_user" { vec!["order:refund".into()] } else { Vec::new() })
+        }
+    }
+    
+    #[async_trait::async_trait]
+    impl RoleChecker for DbChecker {
+        async fn has_role(&self, _login_id: &str, _role: &str) -> SaTokenResult<bool> {
+            Err(SaTokenError::StorageError("db down".into()))
+        }
+        
+        async fn get_roles(&self, _login_id: &str) -> SaTokenResult<Vec<String>> {
+            Err(SaTokenError::StorageError("db down".into()))
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_registered_checkers_before_in_memory_maps() {
+        let manager = test_manager();
+        manager.user_permissions.write().await.insert("db_user".into(), vec!["user:read".into()]);
+        manager.user_roles.write().await.insert("db_user".into(), vec!["admin".into()]);
+        assert!(!manager.has_permission("db_user", "order:refund").await);
+        
+        manager.set_permission_checker(Arc::new(DbChecker));
+        manager.set_role_checker(Arc::new(DbChecker));
+        assert!(manager.has_permission("db_user", "order:refund").await);
+        assert!(manager.has_permission("db_user", "user:read").await);
+        assert!(!manager.has_permission("other", "order:refund").await);
+        assert_eq!(manager.get_permissions("db_user").await, vec!["order:refund", "user:read"]);
+        
+        // 检查器出错时回退到内存映射
+        assert!(manager.has_role("db_user", "admin").await);
+        assert_eq!(manager.get_roles("db_user").await, vec!["admin"]);
+    }
+    
+    #[tokio::test]
+    async fn test_naming_rules_and_permission_model_lint() {
+        let config = SaTokenConfig::builder()
+            .authz_naming_rules(crate::AuthzNamingRules::default().reserve_prefix("sys:"))
+            .build_config();
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        assert!(manager.validate_authz_names(AuthzKind::Permission, &["user:read".into()]).is_ok());
+        assert!(matches!(
+            manager.validate_authz_names(AuthzKind::Permission, &["sys:reboot".into()]),
+            Err(SaTokenError::InvalidAuthzName(_))
+        ));
+        assert!(test_manager().validate_authz_names(AuthzKind::Permission, &["sys:reboot".into()]).is_ok());
+        
+        manager.user_permissions.write().await.insert("u2".into(), vec!["order:".into()]);
+        manager.user_permissions.write().await.insert("u1".into(), vec!["admin:*".into(), "admin:ban".into()]);
+        manager.user_roles.write().await.insert("u1".into(), vec!["ops".into(), "ops".into()]);
+        
+        let issues = manager.lint_permission_model().await;
+        let summary: Vec<String> = issues.iter().map(ToString::to_string).collect();
+        assert_eq!(summary, vec![
+            "[u1] permission 'admin:ban' is already granted by 'admin:*'",
+            "[u2] permission 'order:' ends with ':' and only matches literally",
+            "[u1] role 'ops' is listed more than once",
+        ]);
+    }
+    
+    #[tokio::test]
+    async fn test_renew_respects_storage_ttl_semantics() {
+        let config = SaTokenConfig::builder().timeout(60).build_config();
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        let token = manager.login("user_1").await.unwrap();
+        
+        manager.renew_timeout(&token, 3600).await.unwrap();
+        let remaining = manager.storage.ttl("sa:login:token:user_1").await.unwrap().remaining().unwrap();
+        assert!(remaining > std::time::Duration::from_secs(3500));
+        
+        // 并发登出后续期不会把 token 写回
+        let token_info = manager.get_token_info(&token).await.unwrap();
+        manager.logout(&token).await.unwrap();
+        assert!(matches!(
+            manager.renew_timeout_internal(&token, 3600, &token_info).await,
+            Err(SaTokenError::TokenNotFound)
+        ));
+        assert!(!manager.is_valid(&token).await);
+        
+        // timeout 为 -1 时续期为永不过期
+        let token = manager.login("user_2").await.unwrap();
+        manager.renew_timeout(&token, -1).await.unwrap();
+        assert_eq!(manager.storage.ttl(&format!("sa:token:{}", token.as_str())).await.unwrap(), KeyTtl::Persistent);
+        assert!(manager.get_token_info(&token).await.unwrap().expire_time.is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_get_token_info_batch() {
+        let manager = test_manager();
+        let token = manager.login("user_1").await.unwrap();
+        
+        let infos = manager
+            .get_token_info_batch(&[TokenValue::new("unknown"), token])
+            .await
+            .unwrap();
+        assert!(infos[0].is_none());
+        assert_eq!(infos[1].as_ref().unwrap().login_id, "user_1");
+    }
+    
+    #[tokio::test]
+    async fn test_session_quota_per_key_and_total() {
+        let config = SaTokenConfig::builder()
+            .session_max_value_bytes(16)
+            .session_max_bytes(160)
+            .build_config();
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        
+        let mut session = manager.get_session("user_1").await.unwrap();
+        session.set("name", "alice").unwrap();
+        manager.save_session(&session).await.unwrap();
+        
+        session.set("avatar", "x".repeat(32)).unwrap();
+        let err = manager.save_session(&session).await.unwrap_err();
+        assert!(matches!(err, SaTokenError::SessionQuotaExceeded(_)));
+        
+        session.remove("avatar");
+        for i in 0..10 {
+            session.set(format!("k{}", i), "0123456789").unwrap();
+        }
+        let err = manager.save_session(&session).await.unwrap_err();
+        assert!(matches!(err, SaTokenError::SessionQuotaExceeded(_)));
+    }
+    
+    #[tokio::test]
+    async fn test_session_ttl_slides_on_read_with_auto_renew() {
+        let config = SaTokenConfig::builder().timeout(600).auto_renew(true).build_config();
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        
+        let mut session = SaSession::new("user_1");
+        session.set_with_ttl("captcha", "x7k2", 60).unwrap();
+        manager.save_session(&session).await.unwrap();
+        
+        manager.storage.expire("sa:session:user_1", std::time::Duration::from_secs(5)).await.unwrap();
+        let session = manager.get_session("user_1").await.unwrap();
+        assert_eq!(session.get::<String>("captcha").as_deref(), Some("x7k2"));
+        let remaining = manager.storage.ttl("sa:session:user_1").await.unwrap().remaining().unwrap();
+        assert!(remaining > std::time::Duration::from_secs(590));
+        
+        // timeout 为 -1 时永久保存
+        let config = SaTokenConfig::builder().timeout(-1).build_config();
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        manager.save_session(&SaSession::new("user_2")).await.unwrap();
+        assert_eq!(manager.storage.ttl("sa:session:user_2").await.unwrap(), sa_token_adapter::storage::KeyTtl::Persistent);
+    }
+    
+    #[tokio::test]
+    async fn test_session_usage_reports_largest_first() {
+        let manager = test_manager();
+        let mut session = manager.get_session("user_1").await.unwrap();
+        session.set("small", 1).unwrap();
+        session.set("large", "x".repeat(100)).unwrap();
+        manager.save_session(&session).await.unwrap();
+        
+        let usage = manager.session_usage("user_1").await.unwrap();
+        assert_eq!(usage.keys[0], ("large".to_string(), 102));
+        assert_eq!(usage.keys[1], ("small".to_string(), 1));
+        assert!(usage.total_bytes > 103);
+    }
+    
+    struct ExpiredFor(&'static str);
+    
+    #[async_trait::async_trait]
+    impl LoginPolicy for ExpiredFor {
+        async fn check(&self, login_id: &str, _login_type: &str) -> LoginPolicyDecision {
+            if login_id == self.0 {
+                LoginPolicyDecision::PasswordExpired
+            } else {
+                LoginPolicyDecision::Allow
+            }
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_login_policy_issues_restricted_token() {
+        let manager = test_manager().with_login_policy(Arc::new(ExpiredFor("stale_user")));
+        
+        let token = manager.login("stale_user").await.unwrap();
+        let info = manager.get_token_info(&token).await.unwrap();
+        assert!(info.is_restricted());
+        assert!(info.allows_scope(PASSWORD_CHANGE_SCOPE));
+        assert!(!info.allows_scope("user:profile"));
+        
+        let token = manager.login("fresh_user").await.unwrap();
+        assert!(!manager.get_token_info(&token).await.unwrap().is_restricted());
+    }
+    
+    #[tokio::test]
+    async fn test_authenticate_context_keeps_restricted_tokens_logged_out() {
+        let manager = test_manager().with_login_policy(Arc::new(ExpiredFor("stale_user")));
+        
+        let token = manager.login("fresh_user").await.unwrap();
+        let mut ctx = SaTokenContext::new();
+        assert_eq!(manager.authenticate_context(&token, &mut ctx).await.as_deref(), Some("fresh_user"));
+        assert_eq!(ctx.login_id.as_deref(), Some("fresh_user"));
+        
+        // 受限 token 保留 token 信息供 check_scope_current 使用，但没有 login_id
+        let restricted = manager.login("stale_user").await.unwrap();
+        let mut ctx = SaTokenContext::new();
+        assert!(manager.authenticate_context(&restricted, &mut ctx).await.is_none());
+        assert!(ctx.login_id.is_none() && ctx.token_info.is_some());
+        let err = SaTokenContext::scope(ctx, crate::check::SaCheck::login().check(None)).await.unwrap_err();
+        assert!(matches!(err, SaTokenError::PasswordExpired));
+    }
+    
+    #[tokio::test]
+    async fn test_authenticate_context_records_why_a_token_failed() {
+        async fn authenticate(manager: &SaTokenManager, token: &TokenValue, ip: Option<&str>) -> SaTokenContext {
+            let mut ctx = SaTokenContext::new();
+            ctx.capture_client(ip, None);
+            manager.authenticate_context(token, &mut ctx).await;
+            ctx
+        }
+        fn auth_error(ctx: &SaTokenContext) -> Option<String> {
+            ctx.auth_error.as_ref().map(|err| err.to_string())
+        }
+        
+        let config = SaTokenConfig { is_concurrent: false, token_bind_ip: true, ..Default::default() };
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        let mut login_ctx = SaTokenContext::new();
+        login_ctx.capture_client(Some("203.0.113.9"), None);
+        
+        let token = SaTokenContext::scope(login_ctx.clone(), manager.login("user_1")).await.unwrap();
+        let ctx = authenticate(&manager, &token, Some("203.0.113.9")).await;
+        assert_eq!(ctx.login_id.as_deref(), Some("user_1"));
+        assert!(ctx.auth_error.is_none());
+        
+        let ctx = authenticate(&manager, &token, Some("198.51.100.4")).await;
+        assert!(ctx.login_id.is_none());
+        assert_eq!(auth_error(&ctx), Some(SaTokenError::IpMismatch.to_string()));
+        
+        let current = SaTokenContext::scope(login_ctx, manager.login("user_1")).await.unwrap();
+        let ctx = authenticate(&manager, &token, Some("203.0.113.9")).await;
+        assert_eq!(auth_error(&ctx), Some(SaTokenError::Replaced.to_string()));
+        
+        manager.kick_out_with_reason("user_1", "Signed in elsewhere").await.unwrap();
+        let ctx = authenticate(&manager, &current, Some("203.0.113.9")).await;
+        let err = SaTokenContext::scope(ctx, crate::check::SaCheck::login().check(None)).await.unwrap_err();
+        assert!(matches!(err, SaTokenError::KickedOut(reason) if reason == "Signed in elsewhere"));
+    }
+    
+    #[tokio::test]
+    async fn test_new_sign_in_notifies_existing_devices() {
+        let config = SaTokenConfig::builder().notify_new_sign_in(true).build_config();
+        let online = Arc::new(OnlineManager::new());
+        let pusher = Arc::new(crate::online::InMemoryPusher::new());
+        online.register_pusher(pusher.clone()).await;
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config)
+            .with_online_manager(online);
+        
+        // 首次登录没有其他会话，不通知
+        manager.login("user_1").await.unwrap();
+        assert!(pusher.get_messages("user_1").await.is_empty());
+        assert!(manager.get_login_history("user_1").await.unwrap().is_empty());
+        
+        manager.login_with_options("user_1", None, Some("Chrome on Windows".to_string()), None, None, None)
+            .await
+            .unwrap();
+        let messages = pusher.get_messages("user_1").await;
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].content, "New sign-in from Chrome on Windows");
+        
+        let history = manager.get_login_history("user_1").await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].device.as_deref(), Some("Chrome on Windows"));
+    }
+    
+    struct ShortTtlForNewDevices;
+    
+    #[async_trait::async_trait]
+    impl TtlPolicy for ShortTtlForNewDevices {
+        async fn ttl_for(&self, ctx: &LoginContext) -> std::time::Duration {
+            match ctx.device.as_deref() {
+                Some("trusted-laptop") => ctx.default_ttl.unwrap(),
+                Some("kiosk") => std::time::Duration::MAX,
+                _ => std::time::Duration::from_secs(600),
+            }
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_ttl_policy_controls_expire_time() {
+        let manager = test_manager().with_ttl_policy(Arc::new(ShortTtlForNewDevices));
+        
+        let token = manager
+            .login_with_options("user_1", None, Some("new-phone".into()), None, None, None)
+            .await
+            .unwrap();
+        let info = manager.get_token_info(&token).await.unwrap();
+        let ttl = info.expire_time.unwrap() - info.create_time;
+        assert_eq!(ttl.num_seconds(), 600);
+        
+        let token = manager
+            .login_with_options("user_1", None, Some("trusted-laptop".into()), None, None, None)
+            .await
+            .unwrap();
+        let info = manager.get_token_info(&token).await.unwrap();
+        assert!(info.expire_time.unwrap() - info.create_time > Duration::days(29));
+        
+        // 超出范围的有效期按永久有效处理
+        let token = manager
+            .login_with_options("user_1", None, Some("kiosk".into()), None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(manager.get_token_info(&token).await.unwrap().expire_time, None);
+        assert!(manager.is_valid(&token).await);
+    }
+    
+    #[tokio::test]
+    async fn test_login_idempotent_reuses_token() {
+        let manager = test_manager();
+        let first = manager.login_idempotent("user_1", "retry-1").await.unwrap();
+        let retried = manager.login_idempotent("user_1", "retry-1").await.unwrap();
+        assert_eq!(first, retried);
+        
+        let other = manager.login_idempotent("user_2", "retry-1").await.unwrap();
+        assert_ne!(first, other);
+        
+        // 登出后的重试拿到新的有效 token，而不是已失效的旧 token
+        manager.logout(&first).await.unwrap();
+        let after_logout = manager.login_idempotent("user_1", "retry-1").await.unwrap();
+        assert_ne!(after_logout, first);
+        assert!(manager.is_valid(&after_logout).await);
+        assert_eq!(manager.login_idempotent("user_1", "retry-1").await.unwrap(), after_logout);
+    }
+    
+    #[tokio::test]
+    async fn test_read_only_mode_rejects_mutations_for_restricted_roles() {
+        let manager = test_manager();
+        manager.user_roles.write().await.insert("operator".into(), vec!["ops".into()]);
+        manager.user_roles.write().await.insert("lead".into(), vec!["ops".into()]);
+        manager.user_permissions.write().await
+            .insert("lead".into(), vec![crate::read_only::READ_ONLY_OVERRIDE_PERMISSION.into()]);
+        
+        manager.enable_read_only_mode(ReadOnlyMode::new().roles(["ops"]).reason("incident")).await.unwrap();
+        assert!(manager.check_read_only(Some("operator"), "GET").await.is_ok());
+        let err = manager.check_read_only(Some("operator"), "POST").await.unwrap_err();
+        assert!(matches!(err, SaTokenError::ReadOnlyMode(ref msg) if msg == "Read-only mode: incident"));
+        assert!(manager.check_read_only(Some("lead"), "DELETE").await.is_ok());
+        assert!(manager.check_read_only(Some("customer"), "POST").await.is_ok());
+        
+        manager.disable_read_only_mode().await.unwrap();
+        assert!(manager.check_read_only(Some("operator"), "POST").await.is_ok());
+    }
+    
+    struct DeletedUsers(std::sync::Mutex<Vec<String>>, std::sync::atomic::AtomicUsize);
+    
+    #[async_trait::async_trait]
+    impl SessionValidator for DeletedUsers {
+        async fn validate(&self, token_info: &TokenInfo) -> SessionValidation {
+            self.1.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if self.0.lock().unwrap().contains(&token_info.login_id) {
+                SessionValidation::Invalid("user deleted".into())
+            } else {
+                SessionValidation::Valid
+            }
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_session_validator_cached_and_logs_out() {
+        let validator = Arc::new(DeletedUsers(Default::default(), Default::default()));
+        let manager = test_manager().with_session_validator(validator.clone());
+        let token = manager.login("user_1").await.unwrap();
+        
+        assert!(manager.is_valid(&token).await);
+        assert!(manager.is_valid(&token).await);
+        assert_eq!(validator.1.load(std::sync::atomic::Ordering::SeqCst), 1);
+        
+        // 缓存过期后才会再次复查 | Re-checked only once the cached result expires
+        validator.0.lock().unwrap().push("user_1".into());
+        assert!(manager.is_valid(&token).await);
+        manager.storage.delete(&format!("sa:session-check:{}", token.as_str())).await.unwrap();
+        let err = manager.get_token_info(&token).await.unwrap_err();
+        assert!(matches!(err, SaTokenError::SessionInvalidated(_)));
+        assert!(!manager.is_valid(&token).await);
+    }
+    
+    #[tokio::test]
+    async fn test_idle_token_is_frozen_until_activity_recorded() {
+        let config = SaTokenConfig::builder().active_timeout(600).build_config();
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        let token = manager.login("user_1").await.unwrap();
+        
+        crate::time::advance(Duration::seconds(500));
+        manager.update_last_activity(&token).await.unwrap();
+        crate::time::advance(Duration::seconds(500));
+        assert!(manager.check_activity(&token).await.is_ok());
+        assert!(manager.is_valid(&token).await);
+        
+        crate::time::advance(Duration::seconds(601));
+        assert!(matches!(manager.check_activity(&token).await, Err(SaTokenError::TokenFreeze)));
+        assert!(matches!(manager.get_token_info(&token).await, Err(SaTokenError::TokenFreeze)));
+        crate::time::reset();
+        
+        // 未配置 active_timeout 时不记录也不冻结 | No tracking without active_timeout
+        let manager = test_manager();
+        let token = manager.login("user_2").await.unwrap();
+        manager.update_last_activity(&token).await.unwrap();
+        assert!(!manager.storage.exists(&activity_key(&token)).await.unwrap());
+        crate::time::advance(Duration::days(1));
+        assert!(manager.check_activity(&token).await.is_ok());
+        crate::time::reset();
+    }
+    
+    #[tokio::test]
+    async fn test_validation_counts_as_activity() {
+        for auto_renew in [false, true] {
+            let config = SaTokenConfig::builder()
+                .timeout(86400)
+                .active_timeout(600)
+                .auto_renew(auto_renew)
+                .build_config();
+            let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+            let token = manager.login("user_1").await.unwrap();
+            
+            // 每次校验都刷新活跃时间，持续使用的 token 不会被冻结 | Each validation refreshes activity, so a token in use never freezes
+            for _ in 0..3 {
+                crate::time::advance(Duration::seconds(500));
+                let info = manager.get_token_info(&token).await.unwrap();
+                assert_eq!(info.last_active_time.timestamp(), crate::time::now().timestamp());
+            }
+            
+            // 开启自动续签时续签时长即 active_timeout，闲置后先过期 | With auto_renew the renewal length is active_timeout, so an idle token expires first
+            crate::time::advance(Duration::seconds(601));
+            let err = manager.get_token_info(&token).await.unwrap_err();
+            if auto_renew {
+                assert!(matches!(err, SaTokenError::TokenExpired));
+            } else {
+                assert!(matches!(err, SaTokenError::TokenFreeze));
+            }
+            crate::time::reset();
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_login_saves_request_locale_to_session() {
+        let manager = test_manager();
+        let mut ctx = SaTokenContext::new();
+        ctx.capture_locale(Some("zh-CN,zh;q=0.9,en;q=0.8"), Some("Asia/Shanghai"));
+        SaTokenContext::scope(ctx, manager.login("user_1")).await.unwrap();
+        
+        assert_eq!(manager.get_locale("user_1").await.unwrap().as_deref(), Some("zh-CN"));
+        assert_eq!(manager.get_timezone("user_1").await.unwrap().as_deref(), Some("Asia/Shanghai"));
+        
+        // 没有请求头的登录不覆盖已保存的值 | A login without the headers keeps the saved values
+        manager.login("user_1").await.unwrap();
+        manager.set_client_locale("user_1", Some("en-US"), None).await.unwrap();
+        assert_eq!(manager.get_locale("user_1").await.unwrap().as_deref(), Some("en-US"));
+        assert_eq!(manager.get_timezone("user_1").await.unwrap().as_deref(), Some("Asia/Shanghai"));
+        assert!(manager.get_locale("user_2").await.unwrap().is_none());
+    }
 }
@@ -0,0 +1,140 @@
+// Author: 金书记
+//
+//! 鉴权拒绝统计 | Authorization denial metrics
+//!
+//! 按（路由模式、类别、权限/角色）统计 403 次数。某个接口被拒绝得特别多，通常意味着
+//! 角色分配有误或前端展示了用户无权使用的入口。通过 `SaTokenManager::top_denials()`
+//! 查询排行，可挂在管理后台。
+//!
+//! Counts 403s per (route pattern, kind, permission or role). An endpoint that
+//! is denied far more than others usually points at a broken role assignment
+//! or a UI offering actions the user cannot take. Query the ranking with
+//! `SaTokenManager::top_denials()`, e.g. from an admin page.
+//!
+//! ```rust,ignore
+//! for denial in manager.top_denials(10) {
+//!     println!("{} {} {}={}", denial.count, denial.route, denial.kind, denial.name);
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use crate::permission::AuthzKind;
+
+/// 统计的最大条目数，超出后不再记录新的组合 | Most entries tracked; new combinations are dropped beyond it
+///
+/// 未匹配路由模式的插件以原始路径记录，防止带 ID 的路径撑爆内存。
+/// Plugins without route patterns record raw paths, so IDs in paths must not grow the map without bound.
+pub const DEFAULT_DENIAL_METRICS_CAPACITY: usize = 10_000;
+
+/// 无法确定路由时使用的路由名 | Route name used when the route is unknown
+pub const UNKNOWN_ROUTE: &str = "unknown";
+
+/// 一条拒绝统计 | One denial counter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DenialCount {
+    /// 路由模式，如 `/users/{id}` | Route pattern, e.g. `/users/{id}`
+    pub route: String,
+    /// 权限或角色 | Permission or role
+    pub kind: AuthzKind,
+    /// 缺少的权限/角色名 | Missing permission or role name
+    pub name: String,
+    /// 拒绝次数 | Number of denials
+    pub count: u64,
+}
+
+/// 拒绝统计表 | Denial counters
+#[derive(Debug)]
+pub struct DenialMetrics {
+    counts: Mutex<HashMap<(String, AuthzKind, String), u64>>,
+    capacity: usize,
+}
+
+impl Default for DenialMetrics {
+    fn default() -> Self {
+        Self::new(DEFAULT_DENIAL_METRICS_CAPACITY)
+    }
+}
+
+impl DenialMetrics {
+    /// 创建统计表 | Create with the given capacity
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            counts: Mutex::new(HashMap::new()),
+            capacity,
+        }
+    }
+
+    /// 记录一次拒绝 | Record one denial
+    pub fn record(&self, route: Option<&str>, kind: AuthzKind, name: &str) {
+        let key = (route.unwrap_or(UNKNOWN_ROUTE).to_string(), kind, name.to_string());
+        let mut counts = self.counts.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(count) = counts.get_mut(&key) {
+            *count += 1;
+        } else if counts.len() < self.capacity {
+            counts.insert(key, 1);
+        }
+    }
+
+    /// 按次数从高到低取前 `limit` 条 | The `limit` most frequent denials
+    pub fn top(&self, limit: usize) -> Vec<DenialCount> {
+        let counts = self.counts.lock().unwrap_or_else(|e| e.into_inner());
+        let mut top: Vec<DenialCount> = counts.iter()
+            .map(|((route, kind, name), count)| DenialCount {
+                route: route.clone(),
+                kind: *kind,
+                name: name.clone(),
+                count: *count,
+            })
+            .collect();
+        top.sort_by(|a, b| {
+            b.count.cmp(&a.count)
+                .then_with(|| a.route.cmp(&b.route))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        top.truncate(limit);
+        top
+    }
+
+    /// 清空统计 | Clear all counters
+    pub fn reset(&self) {
+        self.counts.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_top_denials_order() {
+        let metrics = DenialMetrics::default();
+        for _ in 0..3 {
+            metrics.record(Some("/users/{id}"), AuthzKind::Permission, "user:delete");
+        }
+        metrics.record(Some("/admin"), AuthzKind::Role, "admin");
+        metrics.record(None, AuthzKind::Permission, "user:delete");
+        metrics.record(None, AuthzKind::Permission, "user:delete");
+
+        let top = metrics.top(2);
+        assert_eq!(top.len(), 2);
+        assert_eq!((top[0].route.as_str(), top[0].count), ("/users/{id}", 3));
+        assert_eq!((top[1].route.as_str(), top[1].count), (UNKNOWN_ROUTE, 2));
+
+        metrics.reset();
+        assert!(metrics.top(10).is_empty());
+    }
+
+    #[test]
+    fn test_capacity_drops_new_keys() {
+        let metrics = DenialMetrics::new(1);
+        metrics.record(Some("/a"), AuthzKind::Permission, "p");
+        metrics.record(Some("/b/1"), AuthzKind::Permission, "p");
+        metrics.record(Some("/a"), AuthzKind::Permission, "p");
+
+        let top = metrics.top(10);
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].count, 2);
+    }
+}
@@ -0,0 +1,317 @@
+// Author: 金书记
+//
+//! 从其他 Rust 认证库迁移 | Migration from other Rust auth crates
+//!
+//! 已有应用切换到 sa-token 时，用户手里还持有旧库签发的会话 cookie。注册
+//! [`LegacySessionImporter`] 后，`get_token_info` 遇到不认识的 token 会依次询问导入器，
+//! 导入成功则以同一个 token 值创建 sa-token 登录，并把旧会话数据写入 [`SaSession`]，
+//! 之后的请求直接走 sa-token 的校验流程，用户无需重新登录。
+//!
+//! When an existing application switches to sa-token, users still hold session
+//! cookies issued by the previous crate. With a [`LegacySessionImporter`]
+//! registered, `get_token_info` asks the importers about unknown tokens; an
+//! imported session becomes a sa-token login under the same token value, and
+//! the legacy data is copied into the [`SaSession`]. Later requests take the
+//! regular sa-token path, so nobody is logged out by the migration.
+//!
+//! 内置导入器读取旧库以 JSON 保存在共享存储（如 Redis）中的记录：
+//! Built-in importers read records the previous crate stored as JSON in a shared store:
+//!
+//! - [`ActixIdentityImporter`]：actix-identity + actix-session（`RedisSessionStore`）
+//! - [`TowerSessionsImporter`]：tower-sessions（JSON 编码的 `Record`，如 axum-login）
+//!
+//! 以 MessagePack 等二进制格式保存的记录，请自行实现 [`LegacySessionImporter`]。
+//! Records kept in binary formats such as MessagePack need a custom importer.
+//!
+//! ```rust,ignore
+//! // actix-session 使用的 Redis，key 没有前缀
+//! let legacy = Arc::new(RedisStorage::new("redis://localhost:6379/0", "").await?);
+//!
+//! let manager = SaTokenConfig::builder()
+//!     .storage(storage)
+//!     .legacy_importer(Arc::new(ActixIdentityImporter::new(legacy).remove_after_import(true)))
+//!     .build();
+//! ```
+//!
+//! [`SaSession`]: crate::session::SaSession
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sa_token_adapter::storage::SaStorage;
+use crate::error::{SaTokenError, SaTokenResult};
+
+/// actix-identity 保存用户 ID 的会话键 | Session key actix-identity stores the user id under
+pub const ACTIX_IDENTITY_USER_ID_KEY: &str = "actix_identity.user_id";
+
+/// axum-login 保存登录数据的会话键 | Session key axum-login stores its data under
+pub const AXUM_LOGIN_DATA_KEY: &str = "axum-login.data";
+
+/// 从旧库导入的会话 | Session imported from a legacy crate
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportedSession {
+    /// 登录 ID | Login id
+    pub login_id: String,
+
+    /// 登录类型，None 使用默认类型 | Login type, `None` for the default type
+    pub login_type: Option<String>,
+
+    /// 旧会话的过期时间，None 使用配置的 timeout | Expiry of the legacy session
+    pub expire_time: Option<DateTime<Utc>>,
+
+    /// 需要写入 SaSession 的数据 | Data copied into the SaSession
+    pub data: HashMap<String, Value>,
+}
+
+/// 旧会话导入器 | Legacy session importer
+#[async_trait]
+pub trait LegacySessionImporter: Send + Sync {
+    /// 导入器名称（用于日志）| Importer name, used in logs
+    fn name(&self) -> &str;
+
+    /// 按请求携带的 token（旧 cookie 的值）查找旧会话，找不到返回 None
+    /// Look up the legacy session for the presented token, `None` when unknown
+    async fn import(&self, token: &str) -> SaTokenResult<Option<ImportedSession>>;
+
+    /// 导入成功后调用，可删除旧记录，默认保留
+    /// Called after a successful import, e.g. to delete the legacy record
+    async fn imported(&self, _token: &str) -> SaTokenResult<()> {
+        Ok(())
+    }
+}
+
+/// actix-identity 会话导入器 | actix-identity session importer
+///
+/// actix-session 的 `RedisSessionStore` 以 session key 为键，保存 JSON 对象，
+/// 每个值本身又是 JSON 编码的字符串；用户 ID 位于 `actix_identity.user_id`。
+/// 使用 `CookieContentSecurity::Private`（默认）时，cookie 值是加密后的 session key，
+/// 需要在注册自定义提取器时先解密再交给 sa-token。
+///
+/// actix-session's `RedisSessionStore` keeps a JSON object per session key whose
+/// values are JSON-encoded strings; the user id lives under `actix_identity.user_id`.
+/// With the default private cookie security the cookie carries the encrypted key,
+/// so decrypt it (e.g. in a custom `TokenExtractor`) before it reaches sa-token.
+pub struct ActixIdentityImporter {
+    storage: Arc<dyn SaStorage>,
+    key_prefix: String,
+    remove_after_import: bool,
+}
+
+impl ActixIdentityImporter {
+    /// 创建导入器，`storage` 指向 actix-session 使用的存储
+    pub fn new(storage: Arc<dyn SaStorage>) -> Self {
+        Self {
+            storage,
+            key_prefix: String::new(),
+            remove_after_import: false,
+        }
+    }
+
+    /// 自定义 `cache_keygen` 时设置的 key 前缀 | Key prefix of a custom `cache_keygen`
+    pub fn key_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.key_prefix = prefix.into();
+        self
+    }
+
+    /// 导入后删除旧记录 | Delete the legacy record once imported
+    pub fn remove_after_import(mut self, remove: bool) -> Self {
+        self.remove_after_import = remove;
+        self
+    }
+}
+
+#[async_trait]
+impl LegacySessionImporter for ActixIdentityImporter {
+    fn name(&self) -> &str {
+        "actix-identity"
+    }
+
+    async fn import(&self, token: &str) -> SaTokenResult<Option<ImportedSession>> {
+        let Some(raw) = load(self.storage.as_ref(), &format!("{}{}", self.key_prefix, token)).await? else {
+            return Ok(None);
+        };
+        let state: HashMap<String, String> = serde_json::from_str(&raw)?;
+
+        let decode = |value: &String| serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.clone()));
+        let Some(login_id) = state.get(ACTIX_IDENTITY_USER_ID_KEY).map(decode).and_then(|v| value_to_id(&v)) else {
+            return Ok(None);
+        };
+        let data = state
+            .iter()
+            .filter(|(key, _)| !key.starts_with("actix_identity."))
+            .map(|(key, value)| (key.clone(), decode(value)))
+            .collect();
+
+        Ok(Some(ImportedSession { login_id, data, ..Default::default() }))
+    }
+
+    async fn imported(&self, token: &str) -> SaTokenResult<()> {
+        if self.remove_after_import {
+            remove(self.storage.as_ref(), &format!("{}{}", self.key_prefix, token)).await?;
+        }
+        Ok(())
+    }
+}
+
+/// tower-sessions 会话导入器 | tower-sessions session importer
+///
+/// 读取 JSON 编码的 `Record { id, data, expiry_date }`，登录 ID 取自 `data` 中的
+/// `login_id_key`（默认 axum-login 的 `axum-login.data`，其中的 `user_id`）。
+///
+/// Reads JSON-encoded `Record { id, data, expiry_date }` and takes the login id
+/// from `data[login_id_key]` (axum-login's `axum-login.data.user_id` by default).
+pub struct TowerSessionsImporter {
+    storage: Arc<dyn SaStorage>,
+    key_prefix: String,
+    login_id_key: String,
+    remove_after_import: bool,
+}
+
+impl TowerSessionsImporter {
+    /// 创建导入器，`storage` 指向 tower-sessions 使用的存储
+    pub fn new(storage: Arc<dyn SaStorage>) -> Self {
+        Self {
+            storage,
+            key_prefix: String::new(),
+            login_id_key: AXUM_LOGIN_DATA_KEY.to_string(),
+            remove_after_import: false,
+        }
+    }
+
+    /// 会话存储的 key 前缀 | Key prefix of the session store
+    pub fn key_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.key_prefix = prefix.into();
+        self
+    }
+
+    /// 保存登录 ID 的会话键 | Session key holding the login id
+    ///
+    /// 值可以是字符串/数字，或带 `user_id` 字段的对象
+    /// The value may be a string, a number or an object with a `user_id` field
+    pub fn login_id_key(mut self, key: impl Into<String>) -> Self {
+        self.login_id_key = key.into();
+        self
+    }
+
+    /// 导入后删除旧记录 | Delete the legacy record once imported
+    pub fn remove_after_import(mut self, remove: bool) -> Self {
+        self.remove_after_import = remove;
+        self
+    }
+}
+
+#[async_trait]
+impl LegacySessionImporter for TowerSessionsImporter {
+    fn name(&self) -> &str {
+        "tower-sessions"
+    }
+
+    async fn import(&self, token: &str) -> SaTokenResult<Option<ImportedSession>> {
+        let Some(raw) = load(self.storage.as_ref(), &format!("{}{}", self.key_prefix, token)).await? else {
+            return Ok(None);
+        };
+        let record: Value = serde_json::from_str(&raw)?;
+
+        let expire_time = record
+            .get("expiry_date")
+            .and_then(Value::as_str)
+            .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+            .map(|date| date.with_timezone(&Utc));
+        if expire_time.is_some_and(|expire| expire <= crate::time::now()) {
+            return Ok(None);
+        }
+
+        let mut data: HashMap<String, Value> = match record.get("data") {
+            Some(Value::Object(map)) => map.clone().into_iter().collect(),
+            _ => HashMap::new(),
+        };
+        let Some(login_id) = data.remove(&self.login_id_key).and_then(|v| {
+            value_to_id(&v).or_else(|| v.get("user_id").and_then(value_to_id))
+        }) else {
+            return Ok(None);
+        };
+
+        Ok(Some(ImportedSession { login_id, expire_time, data, ..Default::default() }))
+    }
+
+    async fn imported(&self, token: &str) -> SaTokenResult<()> {
+        if self.remove_after_import {
+            remove(self.storage.as_ref(), &format!("{}{}", self.key_prefix, token)).await?;
+        }
+        Ok(())
+    }
+}
+
+async fn load(storage: &dyn SaStorage, key: &str) -> SaTokenResult<Option<String>> {
+    storage.get(key).await.map_err(|e| SaTokenError::StorageError(e.to_string()))
+}
+
+async fn remove(storage: &dyn SaStorage, key: &str) -> SaTokenResult<()> {
+    storage.delete(key).await.map_err(|e| SaTokenError::StorageError(e.to_string()))
+}
+
+fn value_to_id(value: &Value) -> Option<String> {
+    match value {
+        Value::String(id) if !id.is_empty() => Some(id.clone()),
+        Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sa_token_storage_memory::MemoryStorage;
+    use crate::{SaTokenConfig, SaTokenManager, TokenValue};
+    use super::*;
+
+    #[tokio::test]
+    async fn test_actix_identity_imported_on_first_access() {
+        let legacy: Arc<dyn SaStorage> = Arc::new(MemoryStorage::new());
+        legacy.set(
+            "legacy-key",
+            r#"{"actix_identity.user_id":"\"user_1\"","actix_identity.last_visit_unix_timestamp":"1700000000","theme":"\"dark\""}"#,
+            None,
+        ).await.unwrap();
+
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default())
+            .with_legacy_importer(Arc::new(ActixIdentityImporter::new(legacy.clone()).remove_after_import(true)));
+        let token = TokenValue::new("legacy-key");
+
+        let info = manager.get_token_info(&token).await.unwrap();
+        assert_eq!(info.login_id, "user_1");
+        let session = manager.get_session("user_1").await.unwrap();
+        assert_eq!(session.get::<String>("theme").as_deref(), Some("dark"));
+        assert!(!session.has("actix_identity.last_visit_unix_timestamp"));
+
+        // 旧记录已删除，后续请求直接走 sa-token | Legacy record gone, sa-token serves it now
+        assert!(legacy.get("legacy-key").await.unwrap().is_none());
+        assert!(manager.is_valid(&token).await);
+        assert!(manager.get_token_info(&TokenValue::new("unknown")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tower_sessions_record() {
+        let legacy: Arc<dyn SaStorage> = Arc::new(MemoryStorage::new());
+        legacy.set(
+            "tower:abc",
+            r#"{"id":"abc","data":{"axum-login.data":{"user_id":42,"auth_hash":[1,2]},"cart":3},"expiry_date":"2999-01-01T00:00:00Z"}"#,
+            None,
+        ).await.unwrap();
+        legacy.set(
+            "tower:old",
+            r#"{"id":"old","data":{"axum-login.data":{"user_id":7}},"expiry_date":"2000-01-01T00:00:00Z"}"#,
+            None,
+        ).await.unwrap();
+
+        let importer = TowerSessionsImporter::new(legacy).key_prefix("tower:");
+        let imported = importer.import("abc").await.unwrap().unwrap();
+        assert_eq!(imported.login_id, "42");
+        assert_eq!(imported.data.get("cart"), Some(&Value::from(3)));
+        assert!(imported.expire_time.is_some());
+
+        // 已过期的记录不导入 | Expired records are ignored
+        assert!(importer.import("old").await.unwrap().is_none());
+    }
+}
@@ -207,7 +207,7 @@
 //! ```
 
 use std::sync::Arc;
-use chrono::{DateTime, Utc};
+use chrono::DateTime;
 use sa_token_adapter::storage::SaStorage;
 use crate::error::{SaTokenError, SaTokenResult};
 use uuid::Uuid;
@@ -260,7 +260,7 @@ impl NonceManager {
     /// // Returns: "nonce_1701234567890_a1b2c3d4e5f6..."
     /// ```
     pub fn generate(&self) -> String {
-        format!("nonce_{}_{}", Utc::now().timestamp_millis(), Uuid::new_v4().simple())
+        format!("nonce_{}_{}", crate::time::now().timestamp_millis(), Uuid::new_v4().simple())
     }
 
     /// Store and mark nonce as used | 存储并标记 nonce 为已使用
@@ -292,7 +292,7 @@ impl NonceManager {
         let key = format!("sa:nonce:{}", nonce);
         let value = serde_json::json!({
             "login_id": login_id,
-            "created_at": Utc::now().to_rfc3339(),
+            "created_at": crate::time::now().to_rfc3339(),
         }).to_string();
 
         // Set TTL to automatically expire the nonce
@@ -483,7 +483,7 @@ impl NonceManager {
 
         // Calculate time difference
         // 计算时间差
-        let now = Utc::now();
+        let now = crate::time::now();
         let diff = (now - nonce_time).num_seconds().abs();
 
         // Check if within time window
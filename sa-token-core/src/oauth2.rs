@@ -107,6 +107,7 @@
 //! oauth2:code:{authorization_code}  - Authorization code | 授权码 (TTL: 10 min)
 //! oauth2:token:{access_token}       - Token info | 令牌信息 (TTL: 1 hour)
 //! oauth2:refresh:{refresh_token}    - Refresh token | 刷新令牌 (TTL: 30 days)
+//! oauth2:consent:{user}:{client}    - Approved scopes | 已同意的权限范围 (TTL: optional)
 //! ```
 //!
 //! ### Security Validations | 安全验证
@@ -174,12 +175,41 @@
 //! SerializationError            - JSON encode/decode failed | JSON序列化失败
 //! ```
 
+use std::future::Future;
 use std::sync::Arc;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use chrono::{DateTime, Utc, Duration};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 use sa_token_adapter::storage::SaStorage;
 use crate::error::{SaTokenError, SaTokenResult};
+use crate::event::{SaTokenEvent, SaTokenEventBus};
+
+pub mod testkit;
+pub mod introspection;
+
+pub use introspection::{IntrospectionCache, IntrospectionCacheStats};
+
+/// Time source used for expiration checks, now shared with `SaTokenManager` and the storages
+/// 过期判断使用的时间源，现与 `SaTokenManager` 及存储共用
+///
+/// Defaults to [`DefaultClock`](crate::time::DefaultClock); tests swap in [`testkit::FakeClock`].
+/// 默认为 [`DefaultClock`](crate::time::DefaultClock)，测试时可替换为 [`testkit::FakeClock`]。
+pub use sa_token_adapter::clock::{Clock, SystemClock};
+
+/// Token introspection endpoint path (RFC 7662) | 令牌内省接口路径
+pub const OAUTH2_INTROSPECT_PATH: &str = "/oauth2/introspect";
+
+/// Token revocation endpoint path (RFC 7009) | 令牌撤销接口路径
+pub const OAUTH2_REVOKE_PATH: &str = "/oauth2/revoke";
+
+/// `client_credentials` grant type name | 客户端凭据模式的授权类型名
+pub const GRANT_TYPE_CLIENT_CREDENTIALS: &str = "client_credentials";
+
+/// `password` grant type name | 密码模式的授权类型名
+pub const GRANT_TYPE_PASSWORD: &str = "password";
 
 /// OAuth2 Client Information | OAuth2 客户端信息
 /// 
@@ -230,6 +260,73 @@ pub struct AuthorizationCode {
     
     /// Code expiration timestamp | 授权码过期时间戳
     pub expires_at: DateTime<Utc>,
+    
+    /// PKCE code challenge, if the client sent one | PKCE 质询值（客户端提供时）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code_challenge: Option<String>,
+    
+    /// PKCE challenge method | PKCE 质询方式
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code_challenge_method: Option<PkceMethod>,
+}
+
+/// PKCE Challenge Method (RFC 7636) | PKCE 质询方式
+/// 
+/// Public clients (SPAs, mobile apps) cannot keep a client secret, so they bind
+/// the authorization code to a random `code_verifier` and send only its
+/// challenge in the authorization request.
+/// 公共客户端（单页应用、移动端）无法保管客户端密钥，改为用随机的 `code_verifier`
+/// 绑定授权码，授权请求中只发送其质询值。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PkceMethod {
+    /// `BASE64URL(SHA256(code_verifier))`, recommended | 推荐
+    #[serde(rename = "S256")]
+    S256,
+    /// Challenge equals the verifier | 质询值即验证码
+    #[serde(rename = "plain")]
+    Plain,
+}
+
+impl PkceMethod {
+    /// Parse the `code_challenge_method` parameter, defaulting to `plain` when absent
+    /// 解析 `code_challenge_method` 参数，缺省时为 `plain`
+    pub fn parse(method: Option<&str>) -> Option<Self> {
+        match method {
+            None | Some("plain") => Some(Self::Plain),
+            Some("S256") => Some(Self::S256),
+            Some(_) => None,
+        }
+    }
+
+    /// Parameter value | 参数值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::S256 => "S256",
+            Self::Plain => "plain",
+        }
+    }
+
+    /// Compute the challenge of a verifier | 计算验证码对应的质询值
+    pub fn challenge(&self, code_verifier: &str) -> String {
+        match self {
+            Self::S256 => URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes())),
+            Self::Plain => code_verifier.to_string(),
+        }
+    }
+
+    /// Check a verifier against a challenge | 校验验证码与质询值是否匹配
+    /// 
+    /// The verifier must be 43-128 characters of `[A-Za-z0-9-._~]`.
+    /// 验证码须为 43-128 位，仅含 `[A-Za-z0-9-._~]`。
+    pub fn verify(&self, code_challenge: &str, code_verifier: &str) -> bool {
+        let well_formed = (43..=128).contains(&code_verifier.len())
+            && code_verifier.chars().all(|c| c.is_ascii_alphanumeric() || "-._~".contains(c));
+        well_formed && constant_time_eq(self.challenge(code_verifier).as_bytes(), code_challenge.as_bytes())
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
 }
 
 /// Access Token Response | 访问令牌响应
@@ -282,6 +379,45 @@ pub struct OAuth2TokenInfo {
     pub refresh_token: Option<String>,
 }
 
+/// Token introspection response (RFC 7662 §2.2) | 令牌内省响应
+/// 
+/// Unknown, expired and revoked tokens all report `active: false` with no
+/// other fields, so callers cannot probe which tokens ever existed.
+/// 未知、过期、已撤销的令牌都只返回 `active: false`，不附带其他字段，避免泄露令牌是否存在过。
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct IntrospectionResponse {
+    /// Whether the token is currently active | 令牌当前是否有效
+    pub active: bool,
+    
+    /// Space-separated granted scopes | 以空格分隔的权限范围
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    
+    /// Client the token was issued to | 令牌所属客户端
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    
+    /// Expiration as a Unix timestamp in seconds | 过期时间（Unix 秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exp: Option<i64>,
+    
+    /// Subject (user ID) of the token | 令牌主体（用户 ID）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+}
+
+impl From<OAuth2TokenInfo> for IntrospectionResponse {
+    fn from(info: OAuth2TokenInfo) -> Self {
+        Self {
+            active: true,
+            scope: Some(info.scope.join(" ")),
+            client_id: Some(info.client_id),
+            exp: Some(info.expires_at.timestamp()),
+            sub: Some(info.user_id),
+        }
+    }
+}
+
 /// OAuth2 Manager | OAuth2 管理器
 /// 
 /// Core manager for OAuth2 authorization code flow operations.
@@ -308,6 +444,19 @@ pub struct OAuth2Manager {
     /// Refresh token TTL in seconds (default: 2592000 = 30 days)
     /// 刷新令牌有效期（秒）（默认：2592000 = 30 天）
     refresh_token_ttl: i64,
+    
+    /// Consent record TTL in seconds, `None` keeps consents until revoked
+    /// 授权同意记录有效期（秒），`None` 表示保留到被撤销为止
+    consent_ttl: Option<i64>,
+    
+    /// Time source for issue and expiration times | 签发与过期判断使用的时间源
+    clock: Arc<dyn Clock>,
+    
+    /// Optional in-process cache for `verify_access_token` | 可选的访问令牌校验缓存
+    introspection_cache: Option<Arc<IntrospectionCache>>,
+    
+    /// Bus receiving `OAuth2TokenRevoked` events | 接收令牌撤销事件的事件总线
+    event_bus: Option<SaTokenEventBus>,
 }
 
 impl OAuth2Manager {
@@ -327,9 +476,44 @@ impl OAuth2Manager {
             code_ttl: 600,        // 10 minutes
             token_ttl: 3600,      // 1 hour
             refresh_token_ttl: 2592000, // 30 days
+            consent_ttl: None,
+            clock: Arc::new(crate::time::DefaultClock),
+            introspection_cache: None,
+            event_bus: None,
         }
     }
 
+    /// Use a custom time source (e.g. [`testkit::FakeClock`] in tests)
+    /// 使用自定义时间源（例如测试中的 [`testkit::FakeClock`]）
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Cache verified access tokens in process | 在进程内缓存校验通过的访问令牌
+    ///
+    /// See [`introspection`] for invalidation across instances.
+    /// 多实例间的失效方式见 [`introspection`]。
+    ///
+    /// # Arguments | 参数
+    /// * `ttl_secs` - How long a verified token is served from the cache | 缓存有效期（秒）
+    /// * `capacity` - Maximum number of cached tokens | 最大缓存令牌数
+    pub fn with_introspection_cache(mut self, ttl_secs: i64, capacity: usize) -> Self {
+        self.introspection_cache = Some(Arc::new(IntrospectionCache::new(ttl_secs, capacity)));
+        self
+    }
+
+    /// Publish `OAuth2TokenRevoked` events on this bus | 在该事件总线上发布令牌撤销事件
+    pub fn with_event_bus(mut self, event_bus: SaTokenEventBus) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    /// The introspection cache, if enabled | 访问令牌校验缓存（如已开启）
+    pub fn introspection_cache(&self) -> Option<Arc<IntrospectionCache>> {
+        self.introspection_cache.clone()
+    }
+
     /// Set custom TTL values for codes and tokens
     /// 设置授权码和令牌的自定义 TTL 值
     /// 
@@ -350,6 +534,16 @@ impl OAuth2Manager {
         self
     }
 
+    /// Expire saved consents after `ttl_secs` | 授权同意记录在 `ttl_secs` 秒后过期
+    /// 
+    /// After expiry the user sees the consent screen again. By default
+    /// consents are kept until [`revoke_consent`](Self::revoke_consent).
+    /// 过期后用户需重新确认授权；默认一直保留，直到调用 `revoke_consent`。
+    pub fn with_consent_ttl(mut self, ttl_secs: i64) -> Self {
+        self.consent_ttl = Some(ttl_secs);
+        self
+    }
+
     /// Register a new OAuth2 client | 注册新的 OAuth2 客户端
     /// 
     /// Stores client information in the backend for future authentication.
@@ -444,7 +638,7 @@ impl OAuth2Manager {
         redirect_uri: String,
         scope: Vec<String>,
     ) -> AuthorizationCode {
-        let now = Utc::now();
+        let now = self.clock.now();
         let code = format!("code_{}", Uuid::new_v4().simple());
         
         AuthorizationCode {
@@ -455,6 +649,33 @@ impl OAuth2Manager {
             scope,
             created_at: now,
             expires_at: now + Duration::seconds(self.code_ttl),
+            code_challenge: None,
+            code_challenge_method: None,
+        }
+    }
+
+    /// Generate an authorization code bound to a PKCE challenge | 生成绑定 PKCE 质询的授权码
+    /// 
+    /// The token request must then carry the matching `code_verifier`, see
+    /// [`exchange_code_for_token_with_verifier`](Self::exchange_code_for_token_with_verifier).
+    /// 之后的令牌请求必须携带匹配的 `code_verifier`。
+    /// 
+    /// # Arguments | 参数
+    /// * `code_challenge` - `code_challenge` request parameter | 授权请求中的质询值
+    /// * `method` - Parsed `code_challenge_method`, see [`PkceMethod::parse`] | 质询方式
+    pub fn generate_authorization_code_with_pkce(
+        &self,
+        client_id: String,
+        user_id: String,
+        redirect_uri: String,
+        scope: Vec<String>,
+        code_challenge: String,
+        method: PkceMethod,
+    ) -> AuthorizationCode {
+        AuthorizationCode {
+            code_challenge: Some(code_challenge),
+            code_challenge_method: Some(method),
+            ..self.generate_authorization_code(client_id, user_id, redirect_uri, scope)
         }
     }
 
@@ -506,7 +727,7 @@ impl OAuth2Manager {
             .map_err(|e| SaTokenError::SerializationError(e))?;
         
         // Check expiration and auto-cleanup if expired
-        if Utc::now() > auth_code.expires_at {
+        if self.clock.now() > auth_code.expires_at {
             self.storage.delete(&key).await.ok();
             return Err(SaTokenError::TokenExpired);
         }
@@ -565,6 +786,7 @@ impl OAuth2Manager {
     /// * `Err(OAuth2CodeNotFound)` if code not found or expired | 授权码未找到或已过期时
     /// * `Err(OAuth2ClientIdMismatch)` if client ID doesn't match | 客户端 ID 不匹配时
     /// * `Err(OAuth2RedirectUriMismatch)` if redirect URI doesn't match | 回调 URI 不匹配时
+    /// * `Err(OAuth2PkceMismatch)` if the code was issued with a PKCE challenge | 授权码绑定了 PKCE 质询时
     /// 
     /// # Security | 安全性
     /// The authorization code is consumed (deleted) after use to prevent replay attacks.
@@ -575,6 +797,29 @@ impl OAuth2Manager {
         client_id: &str,
         client_secret: &str,
         redirect_uri: &str,
+    ) -> SaTokenResult<AccessToken> {
+        self.exchange_code_for_token_with_verifier(code, client_id, client_secret, redirect_uri, None).await
+    }
+
+    /// Exchange authorization code for access token with PKCE | 携带 PKCE 验证码换取访问令牌
+    /// 
+    /// Same as [`exchange_code_for_token`](Self::exchange_code_for_token), plus
+    /// the `code_verifier` check. A code issued with a challenge requires a
+    /// matching verifier, and a verifier sent for a code without a challenge
+    /// is rejected as well, so a PKCE flow cannot be downgraded.
+    /// 在 `exchange_code_for_token` 的基础上校验 `code_verifier`。绑定了质询的授权码必须
+    /// 提供匹配的验证码；未绑定质询的授权码收到验证码同样拒绝，防止降级。
+    /// 
+    /// # Errors | 错误
+    /// * `Err(OAuth2PkceMismatch)` if the verifier is missing, malformed or does not match
+    ///   | 验证码缺失、格式错误或不匹配时
+    pub async fn exchange_code_for_token_with_verifier(
+        &self,
+        code: &str,
+        client_id: &str,
+        client_secret: &str,
+        redirect_uri: &str,
+        code_verifier: Option<&str>,
     ) -> SaTokenResult<AccessToken> {
         // 1. Verify client credentials
         if !self.verify_client(client_id, client_secret).await? {
@@ -594,7 +839,19 @@ impl OAuth2Manager {
             return Err(SaTokenError::OAuth2RedirectUriMismatch);
         }
 
-        // 5. Generate and return access token
+        // 5. Verify the PKCE code verifier
+        let pkce_ok = match (&auth_code.code_challenge, code_verifier) {
+            (Some(challenge), Some(verifier)) => auth_code.code_challenge_method
+                .unwrap_or(PkceMethod::Plain)
+                .verify(challenge, verifier),
+            (None, None) => true,
+            _ => false,
+        };
+        if !pkce_ok {
+            return Err(SaTokenError::OAuth2PkceMismatch);
+        }
+
+        // 6. Generate and return access token
         self.generate_access_token(&auth_code.client_id, &auth_code.user_id, auth_code.scope).await
     }
 
@@ -624,9 +881,19 @@ impl OAuth2Manager {
         user_id: &str,
         scope: Vec<String>,
     ) -> SaTokenResult<AccessToken> {
-        let now = Utc::now();
+        self.issue_token(client_id, user_id, scope, true).await
+    }
+
+    async fn issue_token(
+        &self,
+        client_id: &str,
+        user_id: &str,
+        scope: Vec<String>,
+        with_refresh_token: bool,
+    ) -> SaTokenResult<AccessToken> {
+        let now = self.clock.now();
         let access_token = format!("at_{}", Uuid::new_v4().simple());
-        let refresh_token = format!("rt_{}", Uuid::new_v4().simple());
+        let refresh_token = with_refresh_token.then(|| format!("rt_{}", Uuid::new_v4().simple()));
 
         // Create token info for storage
         let token_info = OAuth2TokenInfo {
@@ -636,7 +903,7 @@ impl OAuth2Manager {
             scope: scope.clone(),
             created_at: now,
             expires_at: now + Duration::seconds(self.token_ttl),
-            refresh_token: Some(refresh_token.clone()),
+            refresh_token: refresh_token.clone(),
         };
 
         // Store access token with TTL
@@ -649,23 +916,25 @@ impl OAuth2Manager {
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
 
         // Store refresh token with longer TTL
-        let refresh_key = format!("oauth2:refresh:{}", refresh_token);
-        let refresh_value = serde_json::json!({
-            "user_id": user_id,
-            "client_id": client_id,
-            "scope": scope,
-        }).to_string();
-        
-        let refresh_ttl = Some(std::time::Duration::from_secs(self.refresh_token_ttl as u64));
-        self.storage.set(&refresh_key, &refresh_value, refresh_ttl).await
-            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        if let Some(refresh_token) = &refresh_token {
+            let refresh_key = format!("oauth2:refresh:{}", refresh_token);
+            let refresh_value = serde_json::json!({
+                "user_id": user_id,
+                "client_id": client_id,
+                "scope": scope,
+            }).to_string();
+            
+            let refresh_ttl = Some(std::time::Duration::from_secs(self.refresh_token_ttl as u64));
+            self.storage.set(&refresh_key, &refresh_value, refresh_ttl).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        }
 
         // Return the access token response
         Ok(AccessToken {
             access_token,
             token_type: "Bearer".to_string(),
             expires_in: self.token_ttl,
-            refresh_token: Some(refresh_token),
+            refresh_token,
             scope,
         })
     }
@@ -687,6 +956,11 @@ impl OAuth2Manager {
     /// Expired tokens are automatically cleaned up from storage.
     /// 过期的令牌会自动从存储中清理。
     pub async fn verify_access_token(&self, access_token: &str) -> SaTokenResult<OAuth2TokenInfo> {
+        let now = self.clock.now();
+        if let Some(info) = self.introspection_cache.as_ref().and_then(|cache| cache.get(access_token, now)) {
+            return Ok(info);
+        }
+        
         let key = format!("oauth2:token:{}", access_token);
         let value = self.storage.get(&key).await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?
@@ -696,14 +970,41 @@ impl OAuth2Manager {
             .map_err(|e| SaTokenError::SerializationError(e))?;
         
         // Check expiration and auto-cleanup if expired
-        if Utc::now() > token_info.expires_at {
+        if now > token_info.expires_at {
             self.storage.delete(&key).await.ok();
             return Err(SaTokenError::TokenExpired);
         }
         
+        if let Some(cache) = &self.introspection_cache {
+            cache.insert(access_token, token_info.clone(), now);
+        }
+        
         Ok(token_info)
     }
 
+    /// Introspect an access token (RFC 7662) | 令牌内省
+    /// 
+    /// Unlike [`verify_access_token`](Self::verify_access_token), an invalid
+    /// token is not an error: it yields an inactive response. Only storage
+    /// failures are returned as `Err`.
+    /// 与 `verify_access_token` 不同，无效令牌不视为错误，而是返回 `active: false`；
+    /// 只有存储故障才返回 `Err`。
+    /// 
+    /// # Example | 示例
+    /// ```ignore
+    /// let response = oauth2.introspect(&token).await?;
+    /// if response.active {
+    ///     println!("{:?} scopes: {:?}", response.sub, response.scope);
+    /// }
+    /// ```
+    pub async fn introspect(&self, token: &str) -> SaTokenResult<IntrospectionResponse> {
+        match self.verify_access_token(token).await {
+            Ok(info) => Ok(info.into()),
+            Err(SaTokenError::StorageError(e)) => Err(SaTokenError::StorageError(e)),
+            Err(_) => Ok(IntrospectionResponse::default()),
+        }
+    }
+
     /// Refresh access token using refresh token | 使用刷新令牌刷新访问令牌
     /// 
     /// Issues a new access token (and optionally a new refresh token) when the old one expires.
@@ -766,10 +1067,104 @@ impl OAuth2Manager {
         self.generate_access_token(client_id, user_id, scope).await
     }
 
+    /// Client credentials grant (RFC 6749 §4.4) | 客户端凭据模式
+    /// 
+    /// Issues a token to the client itself, for service-to-service calls with
+    /// no user involved. The token's `user_id` is the client ID and no refresh
+    /// token is issued; the client simply requests a new token.
+    /// 为客户端自身签发令牌，用于没有用户参与的服务间调用。令牌的 `user_id` 为客户端 ID，
+    /// 不签发刷新令牌，过期后重新申请即可。
+    /// 
+    /// # Arguments | 参数
+    /// * `scope` - Requested scopes; empty means all scopes of the client | 请求的权限范围，为空时授予客户端的全部范围
+    /// 
+    /// # Errors | 错误
+    /// * `Err(OAuth2InvalidCredentials)` if credentials invalid | 凭据无效时
+    /// * `Err(OAuth2UnsupportedGrantType)` if `client_credentials` is not in the client's `grant_types` | 客户端未开通该授权类型时
+    /// * `Err(OAuth2InvalidScope)` if a scope is not permitted for the client | 请求了客户端不允许的范围时
+    pub async fn client_credentials_token(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+        scope: Vec<String>,
+    ) -> SaTokenResult<AccessToken> {
+        let (client, scope) = self.authorize_grant(client_id, client_secret, GRANT_TYPE_CLIENT_CREDENTIALS, scope).await?;
+        self.issue_token(&client.client_id, &client.client_id, scope, false).await
+    }
+
+    /// Resource owner password grant (RFC 6749 §4.3) | 密码模式
+    /// 
+    /// Checking the username and password is left to `verifier`, which
+    /// resolves to the user ID on success and `None` on bad credentials.
+    /// Only enable the `password` grant for first-party clients.
+    /// 用户名密码的校验交给 `verifier`：校验通过返回用户 ID，凭据错误返回 `None`。
+    /// 仅应为自有（第一方）客户端开通密码模式。
+    /// 
+    /// # Arguments | 参数
+    /// * `scope` - Requested scopes; empty means all scopes of the client | 请求的权限范围，为空时授予客户端的全部范围
+    /// * `verifier` - Async credential check: `(username, password) -> Option<user_id>` | 异步凭据校验
+    /// 
+    /// # Errors | 错误
+    /// * `Err(OAuth2InvalidCredentials)` if client or user credentials invalid | 客户端或用户凭据无效时
+    /// * `Err(OAuth2UnsupportedGrantType)` if `password` is not in the client's `grant_types` | 客户端未开通该授权类型时
+    /// * `Err(OAuth2InvalidScope)` if a scope is not permitted for the client | 请求了客户端不允许的范围时
+    /// 
+    /// # Example | 示例
+    /// ```ignore
+    /// let token = oauth2.password_grant("app_001", "secret", "alice", "p@ss", vec![], |name, pwd| async move {
+    ///     Ok(users.check(&name, &pwd).await?.map(|user| user.id))
+    /// }).await?;
+    /// ```
+    pub async fn password_grant<F, Fut>(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+        username: &str,
+        password: &str,
+        scope: Vec<String>,
+        verifier: F,
+    ) -> SaTokenResult<AccessToken>
+    where
+        F: FnOnce(String, String) -> Fut,
+        Fut: Future<Output = SaTokenResult<Option<String>>>,
+    {
+        let (client, scope) = self.authorize_grant(client_id, client_secret, GRANT_TYPE_PASSWORD, scope).await?;
+        let user_id = verifier(username.to_string(), password.to_string()).await?
+            .ok_or(SaTokenError::OAuth2InvalidCredentials)?;
+        self.issue_token(&client.client_id, &user_id, scope, true).await
+    }
+
+    /// Verify the client, its grant type whitelist and the requested scope
+    /// 校验客户端凭据、授权类型白名单与请求的权限范围
+    async fn authorize_grant(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+        grant_type: &str,
+        scope: Vec<String>,
+    ) -> SaTokenResult<(OAuth2Client, Vec<String>)> {
+        let client = self.get_client(client_id).await?;
+        if client.client_secret != client_secret {
+            return Err(SaTokenError::OAuth2InvalidCredentials);
+        }
+        if !client.grant_types.iter().any(|g| g == grant_type) {
+            return Err(SaTokenError::OAuth2UnsupportedGrantType);
+        }
+        if scope.is_empty() {
+            let scope = client.scope.clone();
+            return Ok((client, scope));
+        }
+        if !self.validate_scope(&client, &scope) {
+            return Err(SaTokenError::OAuth2InvalidScope);
+        }
+        Ok((client, scope))
+    }
+
     /// Revoke an access token or refresh token | 撤销访问令牌或刷新令牌
     /// 
     /// Deletes the token from storage, making it immediately invalid.
-    /// 从存储中删除令牌，使其立即失效。
+    /// Revoking an access token also revokes the refresh token issued with it.
+    /// 从存储中删除令牌，使其立即失效。撤销访问令牌时，一并撤销与其同时签发的刷新令牌。
     /// 
     /// # Arguments | 参数
     /// * `token` - Token to revoke (access or refresh) | 要撤销的令牌（访问或刷新）
@@ -782,12 +1177,122 @@ impl OAuth2Manager {
         let access_key = format!("oauth2:token:{}", token);
         let refresh_key = format!("oauth2:refresh:{}", token);
         
+        let linked_refresh = self.storage.get(&access_key).await.ok().flatten()
+            .and_then(|value| serde_json::from_str::<OAuth2TokenInfo>(&value).ok())
+            .and_then(|info| info.refresh_token);
+        if let Some(linked_refresh) = linked_refresh {
+            self.storage.delete(&format!("oauth2:refresh:{}", linked_refresh)).await.ok();
+        }
+        
         self.storage.delete(&access_key).await.ok();
         self.storage.delete(&refresh_key).await.ok();
         
+        if let Some(cache) = &self.introspection_cache {
+            cache.invalidate(token);
+        }
+        if let Some(event_bus) = &self.event_bus {
+            event_bus.publish(SaTokenEvent::oauth2_token_revoked(token)).await;
+        }
+        
         Ok(())
     }
 
+    /// Revoke a token on behalf of a client (RFC 7009) | 代表客户端撤销令牌
+    /// 
+    /// Authenticates the client and checks that the token was issued to it.
+    /// Unknown tokens succeed silently, as the RFC requires.
+    /// 校验客户端凭据，并确认令牌属于该客户端；按 RFC 要求，未知令牌直接视为撤销成功。
+    /// 
+    /// # Errors | 错误
+    /// * `Err(OAuth2InvalidCredentials)` if credentials invalid | 凭据无效时
+    /// * `Err(OAuth2ClientIdMismatch)` if the token belongs to another client | 令牌属于其他客户端时
+    pub async fn revoke_client_token(&self, client_id: &str, client_secret: &str, token: &str) -> SaTokenResult<()> {
+        if !self.verify_client(client_id, client_secret).await? {
+            return Err(SaTokenError::OAuth2InvalidCredentials);
+        }
+        
+        let owner = match self.storage.get(&format!("oauth2:token:{}", token)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+        {
+            Some(value) => serde_json::from_str::<OAuth2TokenInfo>(&value).ok().map(|info| info.client_id),
+            None => self.storage.get(&format!("oauth2:refresh:{}", token)).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+                .and_then(|value| serde_json::from_str::<serde_json::Value>(&value).ok())
+                .and_then(|data| data["client_id"].as_str().map(str::to_string)),
+        };
+        match owner {
+            Some(owner) if owner != client_id => Err(SaTokenError::OAuth2ClientIdMismatch),
+            Some(_) => self.revoke_token(token).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Remember that a user approved scopes for a client | 保存用户对客户端的授权同意
+    /// 
+    /// Scopes are merged with those approved before, so approving `read` and
+    /// later `write` leaves both approved.
+    /// 与之前同意过的范围合并：先同意 `read`、后同意 `write`，两者都视为已同意。
+    /// 
+    /// # Storage | 存储
+    /// `oauth2:consent:{user_id}:{client_id}` (TTL: consent TTL, see [`with_consent_ttl`](Self::with_consent_ttl))
+    pub async fn save_consent(&self, user_id: &str, client_id: &str, scopes: &[String]) -> SaTokenResult<()> {
+        let mut approved = self.get_consent(user_id, client_id).await?;
+        for scope in scopes {
+            if !approved.contains(scope) {
+                approved.push(scope.clone());
+            }
+        }
+        
+        let value = serde_json::to_string(&approved)
+            .map_err(SaTokenError::SerializationError)?;
+        let ttl = self.consent_ttl.map(|secs| std::time::Duration::from_secs(secs.max(0) as u64));
+        self.storage.set(&Self::consent_key(user_id, client_id), &value, ttl).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+
+    /// Whether the user already approved all `scopes` for the client | 用户是否已同意全部 `scopes`
+    /// 
+    /// The authorize endpoint skips the consent screen when this returns `true`.
+    /// 授权接口在返回 `true` 时可跳过授权确认页。
+    /// 
+    /// # Example | 示例
+    /// ```ignore
+    /// if !oauth2.has_consent(&user_id, &client_id, &scope).await? {
+    ///     return render_consent_page(&client_id, &scope);
+    /// }
+    /// let code = oauth2.generate_authorization_code(client_id, user_id, redirect_uri, scope);
+    /// ```
+    pub async fn has_consent(&self, user_id: &str, client_id: &str, scopes: &[String]) -> SaTokenResult<bool> {
+        Ok(self.load_consent(user_id, client_id).await?
+            .is_some_and(|approved| scopes.iter().all(|scope| approved.contains(scope))))
+    }
+
+    /// Scopes the user approved for the client | 用户已同意的权限范围
+    pub async fn get_consent(&self, user_id: &str, client_id: &str) -> SaTokenResult<Vec<String>> {
+        Ok(self.load_consent(user_id, client_id).await?.unwrap_or_default())
+    }
+
+    async fn load_consent(&self, user_id: &str, client_id: &str) -> SaTokenResult<Option<Vec<String>>> {
+        self.storage.get(&Self::consent_key(user_id, client_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+            .map(|value| serde_json::from_str(&value).map_err(SaTokenError::SerializationError))
+            .transpose()
+    }
+
+    /// Forget the user's consent for the client | 撤销用户对客户端的授权同意
+    /// 
+    /// The next authorization shows the consent screen again. Tokens already
+    /// issued stay valid; revoke them with [`revoke_token`](Self::revoke_token).
+    /// 下次授权将重新显示确认页；已签发的令牌不受影响，需另行调用 `revoke_token`。
+    pub async fn revoke_consent(&self, user_id: &str, client_id: &str) -> SaTokenResult<()> {
+        self.storage.delete(&Self::consent_key(user_id, client_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+
+    fn consent_key(user_id: &str, client_id: &str) -> String {
+        format!("oauth2:consent:{}:{}", user_id, client_id)
+    }
+
     /// Validate redirect URI against client's whitelist | 根据客户端白名单验证回调 URI
     /// 
     /// Security check to prevent redirect URI hijacking.
@@ -865,6 +1370,37 @@ mod tests {
         assert_eq!(token_info.client_id, "test_client");
     }
 
+    #[tokio::test]
+    async fn test_pkce_code_verifier() {
+        let kit = testkit::OAuth2TestKit::new();
+        let client = kit.client("app_001").register().await;
+        let verifier = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+        let challenge = PkceMethod::S256.challenge(verifier);
+        assert_eq!(challenge, "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM");
+
+        let exchange = |code: AuthorizationCode, verifier: Option<&'static str>| {
+            let manager = kit.manager();
+            async move {
+                manager.exchange_code_for_token_with_verifier(
+                    &code.code, "app_001", testkit::TEST_CLIENT_SECRET, testkit::TEST_REDIRECT_URI, verifier,
+                ).await
+            }
+        };
+
+        let code = kit.code(&client).pkce(challenge.clone(), PkceMethod::S256).issue().await;
+        assert!(matches!(exchange(code, Some("wrong-verifier-wrong-verifier-wrong-verifier")).await, Err(SaTokenError::OAuth2PkceMismatch)));
+        let code = kit.code(&client).pkce(challenge.clone(), PkceMethod::S256).issue().await;
+        assert!(matches!(exchange(code, None).await, Err(SaTokenError::OAuth2PkceMismatch)));
+        let code = kit.code(&client).issue().await;
+        assert!(matches!(exchange(code, Some(verifier)).await, Err(SaTokenError::OAuth2PkceMismatch)));
+
+        let code = kit.code(&client).pkce(challenge, PkceMethod::S256).issue().await;
+        assert!(exchange(code, Some(verifier)).await.is_ok());
+        let code = kit.code(&client).pkce(verifier.to_string(), PkceMethod::parse(None).unwrap()).issue().await;
+        assert!(exchange(code, Some(verifier)).await.is_ok());
+        assert_eq!(PkceMethod::parse(Some("S512")), None);
+    }
+
     #[tokio::test]
     async fn test_refresh_token() {
         let storage = Arc::new(MemoryStorage::new());
@@ -895,5 +1431,93 @@ mod tests {
 
         assert_ne!(new_token.access_token, token.access_token);
     }
+
+    #[tokio::test]
+    async fn test_introspect_and_revoke() {
+        let kit = testkit::OAuth2TestKit::new();
+        let oauth2 = kit.manager();
+        let client = kit.client("app_001").scope(["read", "write"]).register().await;
+        kit.client("app_002").register().await;
+        let token = kit.token(&client).issue().await;
+        let refresh_token = token.refresh_token.clone().unwrap();
+
+        let response = oauth2.introspect(&token.access_token).await.unwrap();
+        assert!(response.active);
+        assert_eq!(response.scope.as_deref(), Some("read write"));
+        assert_eq!(response.sub.as_deref(), Some("test_user"));
+        assert_eq!(
+            serde_json::to_value(oauth2.introspect("at_unknown").await.unwrap()).unwrap(),
+            serde_json::json!({ "active": false })
+        );
+
+        assert!(matches!(
+            oauth2.revoke_client_token("app_002", testkit::TEST_CLIENT_SECRET, &token.access_token).await,
+            Err(SaTokenError::OAuth2ClientIdMismatch)
+        ));
+        oauth2.revoke_client_token("app_001", testkit::TEST_CLIENT_SECRET, "at_unknown").await.unwrap();
+        oauth2.revoke_client_token("app_001", testkit::TEST_CLIENT_SECRET, &token.access_token).await.unwrap();
+        assert!(!oauth2.introspect(&token.access_token).await.unwrap().active);
+        assert!(matches!(
+            oauth2.refresh_access_token(&refresh_token, "app_001", testkit::TEST_CLIENT_SECRET).await,
+            Err(SaTokenError::OAuth2RefreshTokenNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_consent_store() {
+        let kit = testkit::OAuth2TestKit::new();
+        let oauth2 = kit.manager();
+        let read = vec!["read".to_string()];
+        let read_write = vec!["read".to_string(), "write".to_string()];
+
+        assert!(!oauth2.has_consent("user_1", "app_001", &[]).await.unwrap());
+        oauth2.save_consent("user_1", "app_001", &read).await.unwrap();
+        assert!(oauth2.has_consent("user_1", "app_001", &read).await.unwrap());
+        assert!(!oauth2.has_consent("user_1", "app_001", &read_write).await.unwrap());
+        assert!(!oauth2.has_consent("user_1", "app_002", &read).await.unwrap());
+
+        oauth2.save_consent("user_1", "app_001", &["write".to_string()]).await.unwrap();
+        assert!(oauth2.has_consent("user_1", "app_001", &read_write).await.unwrap());
+
+        oauth2.revoke_consent("user_1", "app_001").await.unwrap();
+        assert!(!oauth2.has_consent("user_1", "app_001", &read).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_client_credentials_and_password_grants() {
+        let kit = testkit::OAuth2TestKit::new();
+        let oauth2 = kit.manager();
+        kit.client("service").grant_types([GRANT_TYPE_CLIENT_CREDENTIALS]).register().await;
+        kit.client("web").register().await;
+
+        let token = oauth2.client_credentials_token("service", testkit::TEST_CLIENT_SECRET, vec![]).await.unwrap();
+        assert!(token.refresh_token.is_none());
+        let info = oauth2.verify_access_token(&token.access_token).await.unwrap();
+        assert_eq!(info.user_id, "service");
+        assert!(matches!(
+            oauth2.client_credentials_token("web", testkit::TEST_CLIENT_SECRET, vec![]).await,
+            Err(SaTokenError::OAuth2UnsupportedGrantType)
+        ));
+        assert!(matches!(
+            oauth2.client_credentials_token("service", testkit::TEST_CLIENT_SECRET, vec!["admin".to_string()]).await,
+            Err(SaTokenError::OAuth2InvalidScope)
+        ));
+
+        kit.client("mobile").grant_types([GRANT_TYPE_PASSWORD, "refresh_token"]).register().await;
+        let verifier = |name: String, pwd: String| async move {
+            Ok((name == "alice" && pwd == "p@ss").then(|| "user_1".to_string()))
+        };
+        let token = oauth2.password_grant("mobile", testkit::TEST_CLIENT_SECRET, "alice", "p@ss", vec![], verifier).await.unwrap();
+        assert!(token.refresh_token.is_some());
+        assert_eq!(oauth2.verify_access_token(&token.access_token).await.unwrap().user_id, "user_1");
+        assert!(matches!(
+            oauth2.password_grant("mobile", testkit::TEST_CLIENT_SECRET, "alice", "wrong", vec![], verifier).await,
+            Err(SaTokenError::OAuth2InvalidCredentials)
+        ));
+        assert!(matches!(
+            oauth2.password_grant("service", testkit::TEST_CLIENT_SECRET, "alice", "p@ss", vec![], verifier).await,
+            Err(SaTokenError::OAuth2UnsupportedGrantType)
+        ));
+    }
 }
 
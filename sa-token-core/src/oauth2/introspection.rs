@@ -0,0 +1,227 @@
+// Author: 金书记
+//
+//! Access token introspection cache | 访问令牌校验缓存
+//!
+//! Resource servers call [`OAuth2Manager::verify_access_token`] on every
+//! protected request. With the cache enabled, verified tokens are kept in
+//! process for a short TTL so most requests skip the storage round trip.
+//! 资源服务器的每个受保护请求都会校验访问令牌。开启缓存后，校验通过的令牌会在进程内
+//! 保留一小段时间，大部分请求无需访问存储。
+//!
+//! Revoking a token through the same manager drops it from the cache immediately.
+//! Other instances drop it when they receive the
+//! [`SaTokenEventType::OAuth2TokenRevoked`] event: register the cache as a
+//! listener on the event bus that carries those events.
+//! 通过同一个管理器撤销令牌会立即清除缓存；其他实例将缓存注册为事件总线的监听器，
+//! 收到 `OAuth2TokenRevoked` 事件时清除。
+//!
+//! ```rust,ignore
+//! let oauth2 = OAuth2Manager::new(storage)
+//!     .with_introspection_cache(30, 10_000)
+//!     .with_event_bus(event_bus.clone());
+//!
+//! if let Some(cache) = oauth2.introspection_cache() {
+//!     event_bus.register(cache);
+//! }
+//!
+//! let stats = oauth2.introspection_cache().unwrap().stats();
+//! tracing::info!("introspection hit rate: {:.2}", stats.hit_rate());
+//! ```
+//!
+//! [`OAuth2Manager::verify_access_token`]: super::OAuth2Manager::verify_access_token
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use crate::event::{SaTokenEvent, SaTokenEventType, SaTokenListener};
+use super::OAuth2TokenInfo;
+
+struct CachedToken {
+    info: OAuth2TokenInfo,
+    cached_at: DateTime<Utc>,
+}
+
+/// Hit/miss counters of the introspection cache | 校验缓存命中统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IntrospectionCacheStats {
+    /// Lookups served from the cache | 缓存命中次数
+    pub hits: u64,
+
+    /// Lookups that went to storage | 未命中次数
+    pub misses: u64,
+
+    /// Tokens currently cached | 当前缓存的令牌数
+    pub entries: usize,
+}
+
+impl IntrospectionCacheStats {
+    /// Fraction of lookups served from the cache | 命中率
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+/// Bounded in-process cache of verified access tokens | 有界的进程内访问令牌缓存
+pub struct IntrospectionCache {
+    ttl: Duration,
+    capacity: usize,
+    entries: Mutex<HashMap<String, CachedToken>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl IntrospectionCache {
+    /// Create a cache | 创建缓存
+    ///
+    /// # Arguments | 参数
+    /// * `ttl_secs` - How long a verified token is trusted without storage | 缓存有效期（秒）
+    /// * `capacity` - Maximum number of cached tokens | 最大缓存令牌数
+    pub fn new(ttl_secs: i64, capacity: usize) -> Self {
+        Self {
+            ttl: Duration::seconds(ttl_secs),
+            capacity,
+            entries: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Look up a token, counting the hit or miss | 查询令牌并记录命中情况
+    pub(crate) fn get(&self, access_token: &str, now: DateTime<Utc>) -> Option<OAuth2TokenInfo> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let fresh = entries
+            .get(access_token)
+            .map(|cached| self.is_fresh(cached, now));
+
+        match fresh {
+            Some(true) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                entries.get(access_token).map(|cached| cached.info.clone())
+            }
+            Some(false) => {
+                entries.remove(access_token);
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    /// Cache a verified token, evicting the oldest entry when full
+    /// 缓存校验通过的令牌，满时淘汰最早的条目
+    pub(crate) fn insert(&self, access_token: &str, info: OAuth2TokenInfo, now: DateTime<Utc>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() >= self.capacity && !entries.contains_key(access_token) {
+            entries.retain(|_, cached| self.is_fresh(cached, now));
+        }
+        if entries.len() >= self.capacity && !entries.contains_key(access_token) {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, cached)| cached.cached_at)
+                .map(|(token, _)| token.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(access_token.to_string(), CachedToken { info, cached_at: now });
+    }
+
+    /// Drop a token from the cache | 清除令牌缓存
+    pub fn invalidate(&self, access_token: &str) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(access_token);
+    }
+
+    /// Drop every cached token | 清空缓存
+    pub fn clear(&self) {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    /// Current hit/miss counters | 当前命中统计
+    pub fn stats(&self) -> IntrospectionCacheStats {
+        IntrospectionCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.entries.lock().unwrap_or_else(|e| e.into_inner()).len(),
+        }
+    }
+
+    fn is_fresh(&self, cached: &CachedToken, now: DateTime<Utc>) -> bool {
+        now < cached.cached_at + self.ttl && now <= cached.info.expires_at
+    }
+}
+
+#[async_trait]
+impl SaTokenListener for IntrospectionCache {
+    async fn on_event(&self, event: &SaTokenEvent) {
+        if event.event_type == SaTokenEventType::OAuth2TokenRevoked {
+            self.invalidate(&event.token);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use crate::event::SaTokenEventBus;
+    use crate::oauth2::{Clock, OAuth2Manager};
+    use crate::oauth2::testkit::OAuth2TestKit;
+
+    #[tokio::test]
+    async fn test_cache_hits_and_ttl() {
+        let kit = OAuth2TestKit::with_manager(|storage| {
+            OAuth2Manager::new(storage).with_introspection_cache(30, 100)
+        });
+        let client = kit.client("app").register().await;
+        let token = kit.token(&client).issue().await;
+        let cache = kit.manager().introspection_cache().unwrap();
+
+        for _ in 0..3 {
+            kit.manager().verify_access_token(&token.access_token).await.unwrap();
+        }
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (2, 1, 1));
+
+        kit.clock().advance(chrono::Duration::seconds(31));
+        kit.manager().verify_access_token(&token.access_token).await.unwrap();
+        assert_eq!(cache.stats().misses, 2);
+    }
+
+    #[tokio::test]
+    async fn test_revoke_invalidates_other_instances_via_event() {
+        let bus = SaTokenEventBus::new();
+        let kit = OAuth2TestKit::with_manager(|storage| {
+            OAuth2Manager::new(storage)
+                .with_introspection_cache(30, 100)
+                .with_event_bus(bus.clone())
+        });
+        let client = kit.client("app").register().await;
+        let token = kit.token(&client).issue().await;
+
+        // 另一个实例的缓存 | Cache of another instance
+        let remote = Arc::new(super::IntrospectionCache::new(30, 100));
+        bus.register(remote.clone());
+        let info = kit.manager().verify_access_token(&token.access_token).await.unwrap();
+        remote.insert(&token.access_token, info, kit.clock().now());
+
+        kit.manager().revoke_token(&token.access_token).await.unwrap();
+        assert!(kit.manager().verify_access_token(&token.access_token).await.is_err());
+        assert_eq!(remote.stats().entries, 0);
+    }
+}
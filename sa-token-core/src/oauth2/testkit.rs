@@ -0,0 +1,362 @@
+// Author: 金书记
+//
+//! OAuth2 Test Fixtures | OAuth2 测试夹具
+//!
+//! Declarative fixtures for unit-testing authorize/token endpoints without a real
+//! storage backend or knowledge of the internal key formats.
+//! 用于测试授权/令牌接口的声明式夹具，无需真实存储，也无需了解内部 key 格式。
+//!
+//! ## Usage Example | 使用示例
+//!
+//! ```rust,ignore
+//! use sa_token_core::oauth2::testkit::OAuth2TestKit;
+//!
+//! let kit = OAuth2TestKit::new();
+//! let client = kit.client("app_001").scope(["read"]).register().await;
+//! let code = kit.code(&client).user("user_123").issue().await;
+//!
+//! // Call your token endpoint with `code.code` against `kit.manager()` ...
+//!
+//! kit.clock().advance(chrono::Duration::minutes(11));
+//! assert!(kit.manager().get_authorization_code(&code.code).await.is_err());
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration as StdDuration;
+use async_trait::async_trait;
+use sa_token_adapter::storage::{KeyTtl, SaStorage, StorageResult};
+use super::{AccessToken, AuthorizationCode, OAuth2Client, OAuth2Manager, PkceMethod};
+
+/// Default redirect URI used by fixtures | 夹具默认使用的回调 URI
+pub const TEST_REDIRECT_URI: &str = "http://localhost/callback";
+
+/// Default client secret used by fixtures | 夹具默认使用的客户端密钥
+pub const TEST_CLIENT_SECRET: &str = "test_secret";
+
+/// Manually controlled clock, now [`MockClock`](sa_token_adapter::clock::MockClock) | 手动控制的时钟
+///
+/// Cloned handles share the same time. | 克隆出的句柄共享同一时间。
+pub use sa_token_adapter::clock::MockClock as FakeClock;
+
+/// Minimal in-process storage for fixtures | 夹具使用的最简进程内存储
+///
+/// TTLs are ignored; expiration is decided by the manager against [`FakeClock`].
+/// 忽略 TTL，过期由管理器根据 [`FakeClock`] 判断。
+#[derive(Debug, Default)]
+struct FixtureStorage {
+    data: Mutex<HashMap<String, String>>,
+}
+
+impl FixtureStorage {
+    fn data(&self) -> std::sync::MutexGuard<'_, HashMap<String, String>> {
+        self.data.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl SaStorage for FixtureStorage {
+    async fn get(&self, key: &str) -> StorageResult<Option<String>> {
+        Ok(self.data().get(key).cloned())
+    }
+
+    async fn set(&self, key: &str, value: &str, _ttl: Option<StdDuration>) -> StorageResult<()> {
+        self.data().insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> StorageResult<()> {
+        self.data().remove(key);
+        Ok(())
+    }
+
+    async fn exists(&self, key: &str) -> StorageResult<bool> {
+        Ok(self.data().contains_key(key))
+    }
+
+    async fn expire(&self, _key: &str, _ttl: StdDuration) -> StorageResult<()> {
+        Ok(())
+    }
+
+    async fn ttl(&self, key: &str) -> StorageResult<KeyTtl> {
+        Ok(if self.data().contains_key(key) { KeyTtl::Persistent } else { KeyTtl::Missing })
+    }
+
+    async fn clear(&self) -> StorageResult<()> {
+        self.data().clear();
+        Ok(())
+    }
+}
+
+/// OAuth2 test kit: manager, storage and fake clock wired together
+/// OAuth2 测试工具：组装好的管理器、存储和假时钟
+pub struct OAuth2TestKit {
+    manager: OAuth2Manager,
+    clock: FakeClock,
+}
+
+impl OAuth2TestKit {
+    /// Create a kit with default TTLs and the clock frozen at the current time
+    /// 使用默认 TTL 创建，时钟停在当前时间
+    pub fn new() -> Self {
+        Self::with_manager(OAuth2Manager::new)
+    }
+
+    /// Create a kit with a customized manager (e.g. custom TTLs)
+    /// 使用自定义的管理器创建（例如自定义 TTL）
+    ///
+    /// ```rust,ignore
+    /// let kit = OAuth2TestKit::with_manager(|storage| OAuth2Manager::new(storage).with_ttl(60, 300, 3600));
+    /// ```
+    pub fn with_manager(build: impl FnOnce(Arc<dyn SaStorage>) -> OAuth2Manager) -> Self {
+        let clock = FakeClock::new(crate::time::now());
+        // 测试时钟可用时让 `time::now()` 也跟随该时钟 | Let `time::now()` follow it when the test clock is available
+        #[cfg(any(test, feature = "test-clock"))]
+        let clock = crate::time::install(clock);
+        let manager = build(Arc::new(FixtureStorage::default())).with_clock(Arc::new(clock.clone()));
+        Self { manager, clock }
+    }
+
+    /// Manager under test | 被测管理器
+    pub fn manager(&self) -> &OAuth2Manager {
+        &self.manager
+    }
+
+    /// Clock shared with the manager | 与管理器共享的时钟
+    pub fn clock(&self) -> &FakeClock {
+        &self.clock
+    }
+
+    /// Start a registered-client fixture | 创建已注册客户端夹具
+    pub fn client(&self, client_id: impl Into<String>) -> ClientFixture<'_> {
+        ClientFixture {
+            kit: self,
+            client: OAuth2Client {
+                client_id: client_id.into(),
+                client_secret: TEST_CLIENT_SECRET.to_string(),
+                redirect_uris: vec![TEST_REDIRECT_URI.to_string()],
+                grant_types: vec!["authorization_code".to_string(), "refresh_token".to_string()],
+                scope: Vec::new(),
+            },
+        }
+    }
+
+    /// Start an issued-code fixture for `client` | 为 `client` 创建已签发授权码夹具
+    pub fn code(&self, client: &OAuth2Client) -> CodeFixture<'_> {
+        CodeFixture {
+            kit: self,
+            client_id: client.client_id.clone(),
+            user_id: "test_user".to_string(),
+            redirect_uri: client.redirect_uris.first().cloned().unwrap_or_default(),
+            scope: client.scope.clone(),
+            pkce: None,
+        }
+    }
+
+    /// Start an issued-token fixture for `client` | 为 `client` 创建已签发令牌夹具
+    pub fn token(&self, client: &OAuth2Client) -> TokenFixture<'_> {
+        TokenFixture {
+            kit: self,
+            client_id: client.client_id.clone(),
+            user_id: "test_user".to_string(),
+            scope: client.scope.clone(),
+        }
+    }
+}
+
+impl Default for OAuth2TestKit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Registered client fixture | 已注册客户端夹具
+pub struct ClientFixture<'a> {
+    kit: &'a OAuth2TestKit,
+    client: OAuth2Client,
+}
+
+impl ClientFixture<'_> {
+    /// Client secret (default [`TEST_CLIENT_SECRET`]) | 客户端密钥
+    pub fn secret(mut self, secret: impl Into<String>) -> Self {
+        self.client.client_secret = secret.into();
+        self
+    }
+
+    /// Replace the redirect URI whitelist | 替换回调 URI 白名单
+    pub fn redirect_uris<I, S>(mut self, uris: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.client.redirect_uris = uris.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Replace the supported grant types | 替换支持的授权类型
+    pub fn grant_types<I, S>(mut self, grant_types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.client.grant_types = grant_types.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Permitted scopes | 允许的权限范围
+    pub fn scope<I, S>(mut self, scope: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.client.scope = scope.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Register the client and return it | 注册客户端并返回
+    ///
+    /// # Panics
+    /// If registration fails | 注册失败时 panic
+    pub async fn register(self) -> OAuth2Client {
+        self.kit.manager.register_client(&self.client).await
+            .expect("failed to register fixture client");
+        self.client
+    }
+}
+
+/// Issued authorization code fixture | 已签发授权码夹具
+pub struct CodeFixture<'a> {
+    kit: &'a OAuth2TestKit,
+    client_id: String,
+    user_id: String,
+    redirect_uri: String,
+    scope: Vec<String>,
+    pkce: Option<(String, PkceMethod)>,
+}
+
+impl CodeFixture<'_> {
+    /// Authorizing user (default `test_user`) | 授权用户
+    pub fn user(mut self, user_id: impl Into<String>) -> Self {
+        self.user_id = user_id.into();
+        self
+    }
+
+    /// Redirect URI bound to the code | 授权码绑定的回调 URI
+    pub fn redirect_uri(mut self, uri: impl Into<String>) -> Self {
+        self.redirect_uri = uri.into();
+        self
+    }
+
+    /// Granted scopes (default: all client scopes) | 授予的权限范围（默认为客户端全部范围）
+    pub fn scope<I, S>(mut self, scope: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.scope = scope.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Bind the code to a PKCE challenge | 绑定 PKCE 质询
+    pub fn pkce(mut self, code_challenge: impl Into<String>, method: PkceMethod) -> Self {
+        self.pkce = Some((code_challenge.into(), method));
+        self
+    }
+
+    /// Issue and store the code | 签发并存储授权码
+    ///
+    /// # Panics
+    /// If storing fails | 存储失败时 panic
+    pub async fn issue(self) -> AuthorizationCode {
+        let code = match self.pkce {
+            Some((challenge, method)) => self.kit.manager.generate_authorization_code_with_pkce(
+                self.client_id,
+                self.user_id,
+                self.redirect_uri,
+                self.scope,
+                challenge,
+                method,
+            ),
+            None => self.kit.manager.generate_authorization_code(
+                self.client_id,
+                self.user_id,
+                self.redirect_uri,
+                self.scope,
+            ),
+        };
+        self.kit.manager.store_authorization_code(&code).await
+            .expect("failed to store fixture authorization code");
+        code
+    }
+}
+
+/// Issued access token fixture | 已签发访问令牌夹具
+pub struct TokenFixture<'a> {
+    kit: &'a OAuth2TestKit,
+    client_id: String,
+    user_id: String,
+    scope: Vec<String>,
+}
+
+impl TokenFixture<'_> {
+    /// Token owner (default `test_user`) | 令牌所属用户
+    pub fn user(mut self, user_id: impl Into<String>) -> Self {
+        self.user_id = user_id.into();
+        self
+    }
+
+    /// Granted scopes (default: all client scopes) | 授予的权限范围（默认为客户端全部范围）
+    pub fn scope<I, S>(mut self, scope: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.scope = scope.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Issue the access and refresh token | 签发访问令牌和刷新令牌
+    ///
+    /// # Panics
+    /// If storing fails | 存储失败时 panic
+    pub async fn issue(self) -> AccessToken {
+        self.kit.manager.generate_access_token(&self.client_id, &self.user_id, self.scope).await
+            .expect("failed to issue fixture access token")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[tokio::test]
+    async fn test_code_fixture_expires_with_fake_clock() {
+        let kit = OAuth2TestKit::new();
+        let client = kit.client("app_001").scope(["read", "write"]).register().await;
+        let code = kit.code(&client).user("user_123").scope(["read"]).issue().await;
+
+        let token = kit.manager()
+            .exchange_code_for_token(&code.code, "app_001", TEST_CLIENT_SECRET, TEST_REDIRECT_URI)
+            .await
+            .unwrap();
+        assert_eq!(token.scope, vec!["read".to_string()]);
+
+        let expired = kit.code(&client).issue().await;
+        kit.clock().advance(Duration::minutes(11));
+        assert!(kit.manager().get_authorization_code(&expired.code).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_token_fixture() {
+        let kit = OAuth2TestKit::new();
+        let client = kit.client("app_002").scope(["read"]).register().await;
+        let token = kit.token(&client).user("user_456").issue().await;
+
+        let info = kit.manager().verify_access_token(&token.access_token).await.unwrap();
+        assert_eq!(info.user_id, "user_456");
+
+        kit.clock().advance(Duration::hours(2));
+        assert!(kit.manager().verify_access_token(&token.access_token).await.is_err());
+    }
+}
@@ -64,6 +64,17 @@
 //!    └─→ mark_offline_all(login_id)
 //! ```
 //! 
+//! ### Storage-backed Registry
+//! With `OnlineManager::with_storage`, every connection is also written to
+//! `sa:online:{login_id}:{token}` in the shared storage. `list_online` pages
+//! through all instances' connections with device and login ID filters, and
+//! `get_online_count_by_device` counts them per device. Without storage both
+//! read this instance's connections.
+//! ```text
+//! let page = online.list_online(1, 50, Some("web"), Some("emp_")).await?;
+//! println!("{} of {}", page.users.len(), page.total);
+//! ```
+//!
 //! ### Message Types
 //! - Text: Plain text messages
 //! - Binary: Binary data
@@ -132,6 +143,11 @@
 //!    └─→ mark_offline_all(login_id)
 //! ```
 //! 
+//! ### 存储支持的在线注册表
+//! 通过 `OnlineManager::with_storage` 配置存储后，每个连接同时写入共享存储的
+//! `sa:online:{login_id}:{token}`。`list_online` 按设备、登录 ID 前缀筛选并分页浏览所有实例的
+//! 在线连接，`get_online_count_by_device` 按设备统计；未配置存储时两者读取本实例的连接。
+//!
 //! ### 消息类型
 //! - Text: 纯文本消息
 //! - Binary: 二进制数据
@@ -139,19 +155,23 @@
 //! - Notification: 系统通知
 //! - Custom: 用户自定义类型
 
-use crate::error::SaTokenError;
+use crate::context::SaTokenContext;
+use crate::error::{SaTokenError, SaTokenResult};
 use async_trait::async_trait;
+use sa_token_adapter::storage::SaStorage;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
 /// Online user information
 /// 在线用户信息
 ///
 /// Represents an active user connection with device and activity tracking
 /// 表示具有设备和活动跟踪的活跃用户连接
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OnlineUser {
     /// User login ID | 用户登录 ID
     pub login_id: String,
@@ -170,6 +190,58 @@ pub struct OnlineUser {
     
     /// Custom metadata for this connection | 该连接的自定义元数据
     pub metadata: HashMap<String, String>,
+    
+    /// Client IP of the connection | 连接的来源 IP
+    #[serde(default)]
+    pub login_ip: Option<String>,
+    
+    /// User-Agent of the connection | 连接的 User-Agent
+    #[serde(default)]
+    pub user_agent: Option<String>,
+}
+
+/// Storage key prefix of the online registry | 在线注册表的存储键前缀
+pub const ONLINE_REGISTRY_PREFIX: &str = "sa:online:";
+
+/// Lifetime of a registry entry, refreshed on activity; bounds leftovers of crashed instances
+/// 注册表条目的有效期，活跃时刷新；用于清理崩溃实例遗留的条目
+pub const ONLINE_REGISTRY_TTL: Duration = Duration::from_secs(86400);
+
+/// One page of online connections
+/// 一页在线连接
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnlinePage {
+    /// Connections on this page, ordered by login ID then connect time | 本页的连接，按登录 ID、连接时间排序
+    pub users: Vec<OnlineUser>,
+
+    /// Connections matching the filters across all pages | 所有页中符合筛选条件的连接数
+    pub total: usize,
+
+    /// Page number, starting at 1 | 页码，从 1 开始
+    pub page: usize,
+
+    /// Page size | 每页条数
+    pub page_size: usize,
+}
+
+/// Login history record
+/// 登录历史记录
+///
+/// Recorded when a new sign-in happens while the account already has active sessions
+/// 账号已有在线会话时发生新登录，会记录一条登录历史
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginRecord {
+    /// User login ID | 用户登录 ID
+    pub login_id: String,
+    
+    /// Login type | 登录类型
+    pub login_type: String,
+    
+    /// Device identifier | 设备标识
+    pub device: Option<String>,
+    
+    /// Login time | 登录时间
+    pub login_time: DateTime<Utc>,
 }
 
 /// Push message structure
@@ -177,7 +249,7 @@ pub struct OnlineUser {
 ///
 /// Represents a message to be delivered to online users
 /// 表示要传递给在线用户的消息
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PushMessage {
     /// Unique message identifier | 唯一消息标识符
     pub message_id: String,
@@ -195,12 +267,33 @@ pub struct PushMessage {
     pub metadata: HashMap<String, String>,
 }
 
+impl PushMessage {
+    /// Create a message with a fresh ID and the current timestamp
+    /// 创建消息，自动生成 ID 与时间戳
+    pub fn new(message_type: MessageType, content: impl Into<String>) -> Self {
+        Self {
+            message_id: uuid::Uuid::new_v4().to_string(),
+            content: content.into(),
+            message_type,
+            timestamp: crate::time::now(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Attach a metadata entry | 添加元数据
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+}
+
 /// Message type enumeration
 /// 消息类型枚举
 ///
 /// Defines different types of messages that can be sent
 /// 定义可以发送的不同类型的消息
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MessageType {
     /// Plain text message | 纯文本消息
     Text,
@@ -248,6 +341,9 @@ pub struct OnlineManager {
     
     /// Registered message pushers | 已注册的消息推送器
     pushers: Arc<RwLock<Vec<Arc<dyn MessagePusher>>>>,
+
+    /// Shared storage for the cross-instance registry | 跨实例在线注册表使用的共享存储
+    registry: Option<Arc<dyn SaStorage>>,
 }
 
 impl OnlineManager {
@@ -262,9 +358,22 @@ impl OnlineManager {
         Self {
             online_users: Arc::new(RwLock::new(HashMap::new())),
             pushers: Arc::new(RwLock::new(Vec::new())),
+            registry: None,
         }
     }
 
+    /// Also record connections in shared storage, so `list_online` covers all instances
+    /// 同时把连接记录到共享存储，使 `list_online` 覆盖所有实例
+    ///
+    /// # Example | 示例
+    /// ```rust,ignore
+    /// let online = OnlineManager::new().with_storage(storage.clone());
+    /// ```
+    pub fn with_storage(mut self, storage: Arc<dyn SaStorage>) -> Self {
+        self.registry = Some(storage);
+        self
+    }
+
     /// Register a message pusher
     /// 注册消息推送器
     ///
@@ -287,6 +396,9 @@ impl OnlineManager {
     /// # Arguments | 参数
     /// * `user` - Online user information | 在线用户信息
     ///
+    /// `login_ip` and `user_agent` left empty are taken from the current request context.
+    /// `login_ip` 与 `user_agent` 为空时取自当前请求上下文。
+    ///
     /// # Example | 示例
     /// ```rust,ignore
     /// let user = OnlineUser {
@@ -296,10 +408,18 @@ impl OnlineManager {
     ///     connect_time: Utc::now(),
     ///     last_activity: Utc::now(),
     ///     metadata: HashMap::new(),
+    ///     login_ip: None,
+    ///     user_agent: None,
     /// };
     /// manager.mark_online(user).await;
     /// ```
-    pub async fn mark_online(&self, user: OnlineUser) {
+    pub async fn mark_online(&self, mut user: OnlineUser) {
+        // Fill in the client of the request that opened the connection | 补上建立连接的请求的来源
+        if let Some(ctx) = SaTokenContext::get_current() {
+            user.login_ip = user.login_ip.take().or(ctx.client_ip);
+            user.user_agent = user.user_agent.take().or(ctx.user_agent);
+        }
+        self.write_registry(&user).await;
         let mut users = self.online_users.write().await;
         users.entry(user.login_id.clone())
             .or_insert_with(Vec::new)
@@ -318,6 +438,11 @@ impl OnlineManager {
     /// manager.mark_offline("user123", "token123").await;
     /// ```
     pub async fn mark_offline(&self, login_id: &str, token: &str) {
+        if let Some(storage) = &self.registry
+            && let Err(e) = storage.delete(&registry_key(login_id, token)).await
+        {
+            tracing::warn!("Sa-Token: 删除在线记录失败，login_id: {}, error: {}", login_id, e);
+        }
         let mut users = self.online_users.write().await;
         if let Some(user_sessions) = users.get_mut(login_id) {
             user_sessions.retain(|u| u.token != token);
@@ -338,6 +463,16 @@ impl OnlineManager {
     /// manager.mark_offline_all("user123").await;
     /// ```
     pub async fn mark_offline_all(&self, login_id: &str) {
+        if let Some(storage) = &self.registry {
+            match self.registry_entries(&format!("{}{}:", ONLINE_REGISTRY_PREFIX, login_id)).await {
+                Ok(entries) => {
+                    for user in entries.iter().filter(|user| user.login_id == login_id) {
+                        let _ = storage.delete(&registry_key(login_id, &user.token)).await;
+                    }
+                }
+                Err(e) => tracing::warn!("Sa-Token: 读取在线记录失败，login_id: {}, error: {}", login_id, e),
+            }
+        }
         let mut users = self.online_users.write().await;
         users.remove(login_id);
     }
@@ -428,14 +563,126 @@ impl OnlineManager {
     /// manager.update_activity("user123", "token123").await;
     /// ```
     pub async fn update_activity(&self, login_id: &str, token: &str) {
-        let mut users = self.online_users.write().await;
-        if let Some(user_sessions) = users.get_mut(login_id) {
-            for user in user_sessions.iter_mut() {
-                if user.token == token {
-                    user.last_activity = Utc::now();
-                    break;
-                }
+        let updated = {
+            let mut users = self.online_users.write().await;
+            users.get_mut(login_id)
+                .and_then(|user_sessions| user_sessions.iter_mut().find(|user| user.token == token))
+                .map(|user| {
+                    user.last_activity = crate::time::now();
+                    user.clone()
+                })
+        };
+        if let Some(user) = updated {
+            self.write_registry(&user).await;
+        }
+    }
+
+    /// Page through online connections, optionally filtered by device and login ID prefix
+    /// 分页列出在线连接，可按设备与登录 ID 前缀筛选
+    ///
+    /// Reads the shared registry when `with_storage` is configured, otherwise
+    /// this instance's connections. `page` starts at 1.
+    /// 配置了 `with_storage` 时读取共享注册表，否则读取本实例的连接；`page` 从 1 开始。
+    ///
+    /// # Example | 示例
+    /// ```rust,ignore
+    /// let page = manager.list_online(2, 20, Some("web"), None).await?;
+    /// let pages = page.total.div_ceil(page.page_size);
+    /// ```
+    pub async fn list_online(
+        &self,
+        page: usize,
+        page_size: usize,
+        device_filter: Option<&str>,
+        login_id_prefix: Option<&str>,
+    ) -> SaTokenResult<OnlinePage> {
+        let page = page.max(1);
+        let mut users: Vec<OnlineUser> = self.all_connections(login_id_prefix).await?
+            .into_iter()
+            .filter(|user| device_filter.is_none_or(|device| user.device == device))
+            .filter(|user| login_id_prefix.is_none_or(|prefix| user.login_id.starts_with(prefix)))
+            .collect();
+        users.sort_by(|a, b| {
+            a.login_id.cmp(&b.login_id)
+                .then_with(|| a.connect_time.cmp(&b.connect_time))
+                .then_with(|| a.token.cmp(&b.token))
+        });
+
+        let total = users.len();
+        let users = users.into_iter()
+            .skip((page - 1).saturating_mul(page_size))
+            .take(page_size)
+            .collect();
+        Ok(OnlinePage { users, total, page, page_size })
+    }
+
+    /// Count online connections per device
+    /// 按设备统计在线连接数
+    ///
+    /// # Example | 示例
+    /// ```rust,ignore
+    /// let counts = manager.get_online_count_by_device().await?;
+    /// println!("web: {}", counts.get("web").copied().unwrap_or(0));
+    /// ```
+    pub async fn get_online_count_by_device(&self) -> SaTokenResult<HashMap<String, usize>> {
+        let mut counts = HashMap::new();
+        for user in self.all_connections(None).await? {
+            *counts.entry(user.device).or_insert(0) += 1;
+        }
+        Ok(counts)
+    }
+
+    /// All connections from the registry, or this instance's without one
+    async fn all_connections(&self, login_id_prefix: Option<&str>) -> SaTokenResult<Vec<OnlineUser>> {
+        if self.registry.is_some() {
+            let prefix = format!("{}{}", ONLINE_REGISTRY_PREFIX, login_id_prefix.unwrap_or_default());
+            return self.registry_entries(&prefix).await;
+        }
+        let users = self.online_users.read().await;
+        Ok(users.values().flatten().cloned().collect())
+    }
+
+    /// Registry entries whose key starts with `key_prefix`
+    async fn registry_entries(&self, key_prefix: &str) -> SaTokenResult<Vec<OnlineUser>> {
+        let Some(storage) = &self.registry else {
+            return Ok(Vec::new());
+        };
+        let pattern = format!("{}*", key_prefix);
+        let mut seen = std::collections::HashSet::new();
+        let mut entries = Vec::new();
+        let mut cursor = 0;
+        loop {
+            let page = storage.scan_keys(&pattern, cursor).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+            // A key may show up on several scan pages | 同一个键可能出现在多页中
+            let keys: Vec<&str> = page.keys.iter()
+                .filter(|key| seen.insert(key.to_string()))
+                .map(String::as_str)
+                .collect();
+            let values = storage.mget(&keys).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+            entries.extend(values.into_iter()
+                .flatten()
+                .filter_map(|value| serde_json::from_str::<OnlineUser>(&value).ok()));
+            if page.is_last() {
+                break;
             }
+            cursor = page.cursor;
+        }
+        Ok(entries)
+    }
+
+    async fn write_registry(&self, user: &OnlineUser) {
+        let Some(storage) = &self.registry else {
+            return;
+        };
+        let result = match serde_json::to_string(user) {
+            Ok(value) => storage.set(&registry_key(&user.login_id, &user.token), &value, Some(ONLINE_REGISTRY_TTL)).await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = result {
+            tracing::warn!("Sa-Token: 写入在线记录失败，login_id: {}, error: {}", user.login_id, e);
         }
     }
 
@@ -455,7 +702,7 @@ impl OnlineManager {
             message_id: uuid::Uuid::new_v4().to_string(),
             content,
             message_type: MessageType::Text,
-            timestamp: Utc::now(),
+            timestamp: crate::time::now(),
             metadata: HashMap::new(),
         };
 
@@ -544,7 +791,7 @@ impl OnlineManager {
             message_id: uuid::Uuid::new_v4().to_string(),
             content: reason,
             message_type: MessageType::KickOut,
-            timestamp: Utc::now(),
+            timestamp: crate::time::now(),
             metadata: HashMap::new(),
         };
 
@@ -555,6 +802,39 @@ impl OnlineManager {
         self.mark_offline_all(login_id).await;
         Ok(())
     }
+
+    /// Notify existing devices of a new sign-in
+    /// 通知已在线设备有新的登录
+    ///
+    /// # Arguments | 参数
+    /// * `record` - The new sign-in | 新的登录记录
+    ///
+    /// # Example | 示例
+    /// ```rust,ignore
+    /// manager.new_sign_in_notify(&record).await?;
+    /// ```
+    pub async fn new_sign_in_notify(&self, record: &LoginRecord) -> Result<(), SaTokenError> {
+        let device = record.device.as_deref().unwrap_or("unknown device");
+        let mut metadata = HashMap::new();
+        metadata.insert("event".to_string(), "new_sign_in".to_string());
+        metadata.insert("device".to_string(), device.to_string());
+        metadata.insert("login_type".to_string(), record.login_type.clone());
+        metadata.insert("login_time".to_string(), record.login_time.to_rfc3339());
+        
+        let message = PushMessage {
+            message_id: uuid::Uuid::new_v4().to_string(),
+            content: format!("New sign-in from {}", device),
+            message_type: MessageType::Notification,
+            timestamp: crate::time::now(),
+            metadata,
+        };
+        
+        self.push_message_to_user(&record.login_id, message).await
+    }
+}
+
+fn registry_key(login_id: &str, token: &str) -> String {
+    format!("{}{}:{}", ONLINE_REGISTRY_PREFIX, login_id, token)
 }
 
 impl Default for OnlineManager {
@@ -636,9 +916,11 @@ mod tests {
             login_id: "user1".to_string(),
             token: "token1".to_string(),
             device: "web".to_string(),
-            connect_time: Utc::now(),
-            last_activity: Utc::now(),
+            connect_time: crate::time::now(),
+            last_activity: crate::time::now(),
             metadata: HashMap::new(),
+            login_ip: None,
+            user_agent: None,
         };
         
         manager.mark_online(user).await;
@@ -655,9 +937,11 @@ mod tests {
             login_id: "user2".to_string(),
             token: "token2".to_string(),
             device: "mobile".to_string(),
-            connect_time: Utc::now(),
-            last_activity: Utc::now(),
+            connect_time: crate::time::now(),
+            last_activity: crate::time::now(),
             metadata: HashMap::new(),
+            login_ip: None,
+            user_agent: None,
         };
         
         manager.mark_online(user).await;
@@ -678,9 +962,11 @@ mod tests {
             login_id: "user3".to_string(),
             token: "token3".to_string(),
             device: "web".to_string(),
-            connect_time: Utc::now(),
-            last_activity: Utc::now(),
+            connect_time: crate::time::now(),
+            last_activity: crate::time::now(),
             metadata: HashMap::new(),
+            login_ip: None,
+            user_agent: None,
         };
         
         manager.mark_online(user).await;
@@ -703,9 +989,11 @@ mod tests {
                 login_id: format!("user{}", i),
                 token: format!("token{}", i),
                 device: "web".to_string(),
-                connect_time: Utc::now(),
-                last_activity: Utc::now(),
+                connect_time: crate::time::now(),
+                last_activity: crate::time::now(),
                 metadata: HashMap::new(),
+                login_ip: None,
+                user_agent: None,
             };
             manager.mark_online(user).await;
         }
@@ -729,9 +1017,11 @@ mod tests {
             login_id: "user4".to_string(),
             token: "token4".to_string(),
             device: "web".to_string(),
-            connect_time: Utc::now(),
-            last_activity: Utc::now(),
+            connect_time: crate::time::now(),
+            last_activity: crate::time::now(),
             metadata: HashMap::new(),
+            login_ip: None,
+            user_agent: None,
         };
         
         manager.mark_online(user).await;
@@ -745,4 +1035,76 @@ mod tests {
         assert_eq!(messages.len(), 1);
         assert_eq!(messages[0].message_type, MessageType::KickOut);
     }
+
+    fn connection(login_id: &str, token: &str, device: &str) -> OnlineUser {
+        OnlineUser {
+            login_id: login_id.to_string(),
+            token: token.to_string(),
+            device: device.to_string(),
+            connect_time: crate::time::now(),
+            last_activity: crate::time::now(),
+            metadata: HashMap::new(),
+            login_ip: None,
+            user_agent: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_online_pages_shared_registry() {
+        let storage: Arc<dyn SaStorage> = Arc::new(sa_token_storage_memory::MemoryStorage::new());
+        let instance_a = OnlineManager::new().with_storage(storage.clone());
+        let instance_b = OnlineManager::new().with_storage(storage.clone());
+
+        for i in 0..5 {
+            instance_a.mark_online(connection(&format!("emp_{}", i), &format!("a{}", i), "web")).await;
+        }
+        instance_b.mark_online(connection("emp_9", "b1", "mobile")).await;
+        instance_b.mark_online(connection("guest_1", "b2", "web")).await;
+
+        let page = instance_a.list_online(2, 2, None, Some("emp_")).await.unwrap();
+        assert_eq!(page.total, 6);
+        let ids: Vec<&str> = page.users.iter().map(|u| u.login_id.as_str()).collect();
+        assert_eq!(ids, vec!["emp_2", "emp_3"]);
+
+        let web = instance_b.list_online(1, 10, Some("web"), None).await.unwrap();
+        assert_eq!(web.total, 6);
+
+        let counts = instance_a.get_online_count_by_device().await.unwrap();
+        assert_eq!((counts["web"], counts["mobile"]), (6, 1));
+
+        instance_b.mark_offline("emp_9", "b1").await;
+        instance_a.mark_offline_all("emp_0").await;
+        assert_eq!(instance_a.list_online(1, 10, None, Some("emp_")).await.unwrap().total, 4);
+    }
+
+    #[tokio::test]
+    async fn test_list_online_without_storage() {
+        let manager = OnlineManager::new();
+        manager.mark_online(connection("u1", "t1", "web")).await;
+        manager.mark_online(connection("u1", "t2", "ios")).await;
+
+        let page = manager.list_online(1, 1, Some("ios"), None).await.unwrap();
+        assert_eq!((page.total, page.users[0].token.as_str()), (1, "t2"));
+        assert_eq!(manager.get_online_count_by_device().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_new_sign_in_notify() {
+        let manager = OnlineManager::new();
+        let pusher = Arc::new(InMemoryPusher::new());
+        manager.register_pusher(pusher.clone()).await;
+        
+        let record = LoginRecord {
+            login_id: "user5".to_string(),
+            login_type: "default".to_string(),
+            device: Some("Chrome on Windows".to_string()),
+            login_time: crate::time::now(),
+        };
+        manager.new_sign_in_notify(&record).await.unwrap();
+        
+        let messages = pusher.get_messages("user5").await;
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].content, "New sign-in from Chrome on Windows");
+        assert_eq!(messages[0].metadata.get("event").map(String::as_str), Some("new_sign_in"));
+    }
 }
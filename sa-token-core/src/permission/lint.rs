@@ -0,0 +1,282 @@
+// Author: 金书记
+//
+//! 权限/角色命名校验与检查 | Permission and role naming rules and lint
+//!
+//! 权限按段匹配（外加整段的 `*` 通配符），`user:` 或 `user*:read` 这类拼写不会报错，
+//! 只会永远匹配不上。配置 [`AuthzNamingRules`] 后，设置权限/角色时
+//! 拒绝不合规的名称；[`lint_names`] 则检查已有数据中的可疑条目。
+//!
+//! Permissions match segment by segment plus whole-segment `*` wildcards, so
+//! typos such as `user:` or `user*:read` never fail, they just never match. With
+//! [`AuthzNamingRules`] configured, setting a permission or role rejects names
+//! that break the rules; [`lint_names`] flags suspicious existing entries.
+//!
+//! ```rust,ignore
+//! let config = SaTokenConfig::builder()
+//!     .authz_naming_rules(AuthzNamingRules::default().max_depth(3).reserve_prefix("sys:"))
+//!     .build();
+//!
+//! for issue in StpUtil::lint_permission_model().await {
+//!     tracing::warn!("{}", issue);
+//! }
+//! ```
+
+use std::collections::HashSet;
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::error::{SaTokenError, SaTokenResult};
+use super::permission_matches;
+
+/// 名称类别 | Kind of name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthzKind {
+    /// 权限 | Permission
+    Permission,
+    /// 角色 | Role
+    Role,
+}
+
+impl fmt::Display for AuthzKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Permission => f.write_str("permission"),
+            Self::Role => f.write_str("role"),
+        }
+    }
+}
+
+/// 权限/角色命名规则 | Naming rules for permissions and roles
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuthzNamingRules {
+    /// 字母、数字之外允许的字符 | Characters allowed besides ASCII letters and digits
+    pub allowed_chars: String,
+
+    /// 名称还需匹配的正则，None 表示不检查 | Regex names must also match, None to skip
+    pub pattern: Option<String>,
+
+    /// 最大层级（`:` 分隔的段数），0 表示不限制 | Maximum number of `:` segments, 0 for unlimited
+    pub max_depth: usize,
+
+    /// 禁止使用的前缀 | Prefixes names may not start with
+    pub reserved_prefixes: Vec<String>,
+}
+
+impl Default for AuthzNamingRules {
+    fn default() -> Self {
+        Self {
+            allowed_chars: ":_-.*".to_string(),
+            pattern: None,
+            max_depth: 0,
+            reserved_prefixes: Vec::new(),
+        }
+    }
+}
+
+impl AuthzNamingRules {
+    /// 设置字母、数字之外允许的字符 | Set the characters allowed besides letters and digits
+    pub fn allowed_chars(mut self, chars: impl Into<String>) -> Self {
+        self.allowed_chars = chars.into();
+        self
+    }
+
+    /// 设置名称需匹配的正则 | Set a regex names must match
+    pub fn pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.pattern = Some(pattern.into());
+        self
+    }
+
+    /// 设置最大层级 | Set the maximum depth
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// 添加保留前缀 | Add a reserved prefix
+    pub fn reserve_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.reserved_prefixes.push(prefix.into());
+        self
+    }
+
+    /// 校验一组名称，返回第一个不合规的名称 | Validate names, failing on the first offender
+    pub fn validate(&self, kind: AuthzKind, names: &[String]) -> SaTokenResult<()> {
+        let pattern = match &self.pattern {
+            Some(pattern) => Some(regex::Regex::new(pattern).map_err(|e| {
+                SaTokenError::ConfigError(format!("Invalid authz naming pattern: {}", e))
+            })?),
+            None => None,
+        };
+        let invalid = |name: &str, reason: String| {
+            SaTokenError::InvalidAuthzName(format!("{} '{}' {}", kind, name, reason))
+        };
+
+        for name in names {
+            if name.is_empty() {
+                return Err(invalid(name, "is empty".to_string()));
+            }
+            if let Some(c) = name.chars().find(|c| !c.is_ascii_alphanumeric() && !self.allowed_chars.contains(*c)) {
+                return Err(invalid(name, format!("contains disallowed character {:?}", c)));
+            }
+            if let Some(ref pattern) = pattern
+                && !pattern.is_match(name)
+            {
+                return Err(invalid(name, format!("does not match {}", pattern.as_str())));
+            }
+            let depth = name.split(':').count();
+            if self.max_depth > 0 && depth > self.max_depth {
+                return Err(invalid(name, format!("has {} levels, at most {} allowed", depth, self.max_depth)));
+            }
+            if let Some(prefix) = self.reserved_prefixes.iter().find(|p| name.starts_with(p.as_str())) {
+                return Err(invalid(name, format!("uses reserved prefix '{}'", prefix)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 检查发现的问题类别 | Kind of lint finding
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum LintKind {
+    /// 首尾或中间含空白 | Contains whitespace
+    Whitespace,
+    /// 以 `:` 结尾 | Ends with `:`
+    TrailingColon,
+    /// 含空段（如 `user::read`）| Has an empty segment, e.g. `user::read`
+    EmptySegment,
+    /// 相邻的通配符段（如 `*:*`），可简化为一个 | Adjacent wildcard segments, e.g. `*:*`, which can be collapsed
+    DuplicateWildcard,
+    /// 通配符只占段的一部分（如 `user*`），按字面匹配 | Wildcard inside a segment, e.g. `user*`, so it matches literally
+    MisplacedWildcard,
+    /// 与列表中另一条目完全相同 | Listed more than once
+    Duplicate,
+    /// 已被通配符授权覆盖，属于冗余 | Already covered by a wildcard grant
+    Shadowed {
+        /// 覆盖它的授权 | The covering grant
+        by: String,
+    },
+}
+
+/// 一条检查结果 | A lint finding
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LintIssue {
+    /// 条目所属的登录 ID | Login ID the entry belongs to
+    pub login_id: Option<String>,
+    /// 权限或角色 | Permission or role
+    pub target: AuthzKind,
+    /// 有问题的条目 | Offending entry
+    pub entry: String,
+    /// 问题类别 | What is wrong
+    #[serde(flatten)]
+    pub kind: LintKind,
+}
+
+impl fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(ref login_id) = self.login_id {
+            write!(f, "[{}] ", login_id)?;
+        }
+        write!(f, "{} '{}' ", self.target, self.entry)?;
+        match &self.kind {
+            LintKind::Whitespace => f.write_str("contains whitespace"),
+            LintKind::TrailingColon => f.write_str("ends with ':' and only matches literally"),
+            LintKind::EmptySegment => f.write_str("has an empty segment"),
+            LintKind::DuplicateWildcard => f.write_str("has adjacent wildcard segments"),
+            LintKind::MisplacedWildcard => f.write_str("has a wildcard inside a segment, which never expands"),
+            LintKind::Duplicate => f.write_str("is listed more than once"),
+            LintKind::Shadowed { by } => write!(f, "is already granted by '{}'", by),
+        }
+    }
+}
+
+/// 检查一组权限或角色 | Lint a list of permissions or roles
+///
+/// 角色按精确匹配，不检查通配符相关问题。
+/// Roles match exactly, so wildcard findings only apply to permissions.
+pub fn lint_names(kind: AuthzKind, names: &[String]) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    let mut seen = HashSet::new();
+    let mut report = |entry: &str, kind_found: LintKind| issues.push(LintIssue {
+        login_id: None,
+        target: kind,
+        entry: entry.to_string(),
+        kind: kind_found,
+    });
+
+    for name in names {
+        if !seen.insert(name.as_str()) {
+            report(name, LintKind::Duplicate);
+            continue;
+        }
+        if name.chars().any(char::is_whitespace) {
+            report(name, LintKind::Whitespace);
+        }
+        if name.ends_with(':') {
+            report(name, LintKind::TrailingColon);
+        } else if name.split(':').any(str::is_empty) {
+            report(name, LintKind::EmptySegment);
+        }
+        if kind == AuthzKind::Role {
+            continue;
+        }
+
+        let segments: Vec<&str> = name.split(':').collect();
+        if segments.iter().any(|segment| segment.contains('*') && *segment != "*") {
+            report(name, LintKind::MisplacedWildcard);
+        } else if segments.windows(2).any(|pair| pair == ["*", "*"]) {
+            report(name, LintKind::DuplicateWildcard);
+        }
+        if let Some(by) = names.iter().find(|other| *other != name && permission_matches(other, name)) {
+            report(name, LintKind::Shadowed { by: by.clone() });
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_naming_rules() {
+        let rules = AuthzNamingRules::default()
+            .max_depth(3)
+            .reserve_prefix("sys:")
+            .pattern("^[a-z]");
+
+        assert!(rules.validate(AuthzKind::Permission, &names(&["user:read", "admin:*", "order:item:edit"])).is_ok());
+        for bad in ["user:list:all:now", "sys:shutdown", "User:read", "user read", ""] {
+            let err = rules.validate(AuthzKind::Permission, &names(&[bad])).unwrap_err();
+            assert!(matches!(err, SaTokenError::InvalidAuthzName(_)), "{}", bad);
+        }
+        assert!(AuthzNamingRules::default().pattern("(").validate(AuthzKind::Role, &names(&["admin"])).is_err());
+    }
+
+    #[test]
+    fn test_lint_flags_suspicious_entries() {
+        let issues = lint_names(AuthzKind::Permission, &names(&[
+            "user:", "user::read", "user*:read", "user:*:read", "admin:*", "admin:delete", "order:read", "order:read",
+        ]));
+        let kinds: Vec<(&str, &LintKind)> = issues.iter().map(|i| (i.entry.as_str(), &i.kind)).collect();
+
+        assert!(kinds.contains(&("user:", &LintKind::TrailingColon)));
+        assert!(kinds.contains(&("user::read", &LintKind::EmptySegment)));
+        assert!(kinds.contains(&("user*:read", &LintKind::MisplacedWildcard)));
+        assert!(!kinds.iter().any(|(entry, _)| *entry == "user:*:read"));
+        assert!(kinds.contains(&("admin:delete", &LintKind::Shadowed { by: "admin:*".to_string() })));
+        assert!(kinds.contains(&("order:read", &LintKind::Duplicate)));
+        assert!(!kinds.iter().any(|(entry, _)| *entry == "admin:*"));
+        assert_eq!(lint_names(AuthzKind::Permission, &names(&["*:*"]))[0].kind, LintKind::DuplicateWildcard);
+
+        // 角色不做通配符检查
+        let role_issues = lint_names(AuthzKind::Role, &names(&["admin*", "ops "]));
+        assert_eq!(role_issues.len(), 1);
+        assert_eq!(role_issues[0].kind, LintKind::Whitespace);
+        assert!(role_issues[0].to_string().contains("role 'ops '"));
+    }
+}
@@ -0,0 +1,153 @@
+// Author: 金书记
+//
+//! 通配符权限匹配 | Wildcard permission matching
+//!
+//! 权限按分隔符（默认 `:`）切分为段，授权中的 `*` 段匹配任意一段，末尾的 `*`
+//! 匹配剩余的零段或多段：
+//!
+//! Permissions are split into segments on the separator (`:` by default). A
+//! `*` segment in a grant matches any single segment; a trailing `*` matches
+//! zero or more remaining segments:
+//!
+//! | 授权 Grant      | 匹配 Matches                          | 不匹配 Does not match     |
+//! |-----------------|---------------------------------------|---------------------------|
+//! | `*`             | 任意权限 anything                      |                           |
+//! | `admin:*`       | `admin`、`admin:ban`、`admin:user:ban` | `administrator`           |
+//! | `user:*:read`   | `user:1:read`、`user:profile:read`    | `user:read`、`user:1:write` |
+//! | `a.b.*`（`.` 分隔 separator `.`） | `a.b.c`、`a.b.c.d`     | `a.bc`                    |
+//!
+//! 只有整段的 `*` 是通配符，`user*` 按字面匹配。编译后的模式按（分隔符, 授权）缓存。
+//! Only a whole `*` segment is a wildcard; `user*` matches literally. Compiled
+//! patterns are cached per (separator, grant).
+
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// 默认的权限分隔符 | Default permission separator
+pub const DEFAULT_PERMISSION_SEPARATOR: &str = ":";
+
+/// 通配符段 | Wildcard segment
+const WILDCARD: &str = "*";
+
+/// 缓存的模式数上限，超出后清空重建 | Cached patterns kept at most, cleared when exceeded
+const MAX_CACHED_PATTERNS: usize = 4096;
+
+#[derive(Debug, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Any,
+}
+
+type PatternCache = RwLock<HashMap<(String, String), Arc<[Segment]>>>;
+
+fn pattern_cache() -> &'static PatternCache {
+    static CACHE: OnceLock<PatternCache> = OnceLock::new();
+    CACHE.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// 权限匹配器 | Permission matcher
+///
+/// ```rust,ignore
+/// let matcher = PermissionMatcher::new(".");
+/// assert!(matcher.matches("a.b.*", "a.b.c.d"));
+/// assert!(manager.permission_matcher().matches("user:*:read", "user:42:read"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionMatcher {
+    separator: String,
+}
+
+impl Default for PermissionMatcher {
+    fn default() -> Self {
+        Self::new(DEFAULT_PERMISSION_SEPARATOR)
+    }
+}
+
+impl PermissionMatcher {
+    /// 使用指定分隔符创建，空分隔符回退到 `:` | Create with a separator; an empty one falls back to `:`
+    pub fn new(separator: impl Into<String>) -> Self {
+        let separator = separator.into();
+        Self {
+            separator: if separator.is_empty() { DEFAULT_PERMISSION_SEPARATOR.to_string() } else { separator },
+        }
+    }
+
+    /// 分隔符 | Separator
+    pub fn separator(&self) -> &str {
+        &self.separator
+    }
+
+    /// 已授予的权限是否满足所需权限 | Whether a granted permission covers the required one
+    pub fn matches(&self, granted: &str, required: &str) -> bool {
+        if granted == required || granted == WILDCARD {
+            return true;
+        }
+        if !granted.contains(WILDCARD) {
+            return false;
+        }
+
+        let pattern = self.compile(granted);
+        let mut required = required.split(self.separator.as_str());
+        for (i, segment) in pattern.iter().enumerate() {
+            let is_last = i + 1 == pattern.len();
+            match (segment, required.next()) {
+                (Segment::Any, _) if is_last => return true,
+                (Segment::Any, Some(_)) => {}
+                (Segment::Literal(literal), Some(part)) if literal == part => {}
+                _ => return false,
+            }
+        }
+        required.next().is_none()
+    }
+
+    /// 权限列表中是否有任一权限满足所需权限 | Whether any granted permission covers the required one
+    pub fn matches_any(&self, granted: &[String], required: &str) -> bool {
+        granted.iter().any(|p| self.matches(p, required))
+    }
+
+    fn compile(&self, granted: &str) -> Arc<[Segment]> {
+        let key = (self.separator.clone(), granted.to_string());
+        if let Some(pattern) = pattern_cache().read().unwrap_or_else(|e| e.into_inner()).get(&key) {
+            return pattern.clone();
+        }
+
+        let pattern: Arc<[Segment]> = granted.split(self.separator.as_str())
+            .map(|part| match part {
+                WILDCARD => Segment::Any,
+                literal => Segment::Literal(literal.to_string()),
+            })
+            .collect();
+        let mut cache = pattern_cache().write().unwrap_or_else(|e| e.into_inner());
+        if cache.len() >= MAX_CACHED_PATTERNS {
+            cache.clear();
+        }
+        cache.insert(key, pattern.clone());
+        pattern
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multi_segment_wildcards() {
+        let matcher = PermissionMatcher::default();
+        assert!(matcher.matches("*", "order:item:edit"));
+        assert!(matcher.matches("admin:*", "admin"));
+        assert!(matcher.matches("admin:*", "admin:user:ban"));
+        assert!(!matcher.matches("admin:*", "administrator"));
+        assert!(matcher.matches("user:*:read", "user:42:read"));
+        assert!(!matcher.matches("user:*:read", "user:read"));
+        assert!(!matcher.matches("user:*:read", "user:42:read:all"));
+        assert!(!matcher.matches("user:*:read", "user:42:write"));
+        assert!(matcher.matches("*:*:read", "order:7:read"));
+        assert!(!matcher.matches("user*", "user:read"));
+
+        let dotted = PermissionMatcher::new(".");
+        assert!(dotted.matches("a.b.*", "a.b.c.d"));
+        assert!(!dotted.matches("a.b.*", "a.bc"));
+        assert!(!dotted.matches("a:b:*", "a:b:c"));
+        assert_eq!(PermissionMatcher::new("").separator(), ":");
+    }
+}
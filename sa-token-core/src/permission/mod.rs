@@ -3,8 +3,50 @@
 //! 权限验证模块
 
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use crate::error::SaTokenResult;
 
+pub mod lint;
+pub mod matcher;
+#[cfg(feature = "runtime")]
+pub mod payload;
+
+pub use lint::{AuthzKind, AuthzNamingRules, LintIssue, LintKind, lint_names};
+pub use matcher::{PermissionMatcher, DEFAULT_PERMISSION_SEPARATOR};
+#[cfg(feature = "runtime")]
+pub use payload::{PayloadGuard, ThresholdGuard, parse_payload, DEFAULT_PAYLOAD_LIMIT};
+
+/// 登录时预取的权限与角色快照 | Permissions and roles prefetched at login
+/// 
+/// `version` 与账号当前的权限版本不一致时快照失效，鉴权回退到实时查询。
+/// The snapshot is ignored once `version` falls behind the account's permission version.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthzSnapshot {
+    /// 权限列表 | Permissions
+    pub permissions: Vec<String>,
+    
+    /// 角色列表 | Roles
+    pub roles: Vec<String>,
+    
+    /// 预取时的权限版本 | Permission version at prefetch time
+    pub version: i64,
+}
+
+/// 已授予的权限是否满足所需权限（`:` 分隔）| Whether a granted permission covers the required one (`:` separated)
+/// 
+/// 支持精确匹配与 `*`、`admin:*`、`user:*:read` 等多段通配符，规则见 [`matcher`]；
+/// 使用其他分隔符时请用 [`PermissionMatcher`]。
+/// Supports exact matches and multi-segment wildcards such as `*`, `admin:*`
+/// and `user:*:read`, see [`matcher`]; use [`PermissionMatcher`] for other separators.
+pub fn permission_matches(granted: &str, required: &str) -> bool {
+    PermissionMatcher::default().matches(granted, required)
+}
+
+/// 权限列表中是否有任一权限满足所需权限 | Whether any granted permission covers the required one
+pub fn has_permission_in(granted: &[String], required: &str) -> bool {
+    PermissionMatcher::default().matches_any(granted, required)
+}
+
 /// 权限检查器 | Permission Checker
 /// 
 /// 用于检查用户权限的 trait
@@ -148,3 +190,18 @@ pub trait RoleChecker: Send + Sync {
     /// 用户的角色列表 | User's role list
     async fn get_roles(&self, login_id: &str) -> SaTokenResult<Vec<String>>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permission_matches() {
+        assert!(permission_matches("user:read", "user:read"));
+        assert!(permission_matches("admin:*", "admin:delete"));
+        assert!(!permission_matches("admin:*", "user:read"));
+        assert!(permission_matches("user:*:read", "user:1:read"));
+        assert!(!permission_matches("user:read", "user:write"));
+        assert!(has_permission_in(&["user:read".to_string(), "admin:*".to_string()], "admin:ban"));
+    }
+}
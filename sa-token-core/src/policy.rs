@@ -0,0 +1,180 @@
+// Author: 金书记
+//
+//! 登录策略模块 | Login Policy
+//!
+//! 登录时调用应用提供的策略钩子，例如判断密码是否已过期。
+//! 密码过期的账号只会拿到受限 token（scoped token），只能访问修改密码接口。
+//!
+//! A hook consulted at login. When it reports `PasswordExpired`, the manager
+//! issues a restricted token whose only scope is [`PASSWORD_CHANGE_SCOPE`],
+//! so the user can call the password-change endpoint and nothing else.
+//!
+//! ## 使用示例 | Usage Example
+//!
+//! ```rust,ignore
+//! use async_trait::async_trait;
+//! use sa_token_core::policy::{LoginPolicy, LoginPolicyDecision};
+//!
+//! struct PasswordAgePolicy;
+//!
+//! #[async_trait]
+//! impl LoginPolicy for PasswordAgePolicy {
+//!     async fn check(&self, login_id: &str, _login_type: &str) -> LoginPolicyDecision {
+//!         if password_changed_days_ago(login_id).await > 90 {
+//!             LoginPolicyDecision::PasswordExpired
+//!         } else {
+//!             LoginPolicyDecision::Allow
+//!         }
+//!     }
+//! }
+//!
+//! SaTokenConfig::builder()
+//!     .storage(Arc::new(MemoryStorage::new()))
+//!     .login_policy(Arc::new(PasswordAgePolicy))
+//!     .build();
+//!
+//! // 修改密码接口 | Password-change endpoint
+//! let login_id = StpUtil::check_scope_current(PASSWORD_CHANGE_SCOPE)?;
+//! ```
+//!
+//! [`TtlPolicy`] 则按风险信号动态决定 token 有效期，例如新设备给更短的有效期：
+//!
+//! [`TtlPolicy`] picks the token lifetime per login from risk signals:
+//!
+//! ```rust,ignore
+//! struct RiskBasedTtl;
+//!
+//! #[async_trait]
+//! impl TtlPolicy for RiskBasedTtl {
+//!     async fn ttl_for(&self, ctx: &LoginContext) -> Duration {
+//!         if is_trusted_device(&ctx.login_id, ctx.device.as_deref()).await {
+//!             Duration::from_secs(30 * 24 * 3600)
+//!         } else {
+//!             Duration::from_secs(3600)
+//!         }
+//!     }
+//! }
+//!
+//! SaTokenConfig::builder()
+//!     .storage(Arc::new(MemoryStorage::new()))
+//!     .ttl_policy(Arc::new(RiskBasedTtl))
+//!     .build();
+//! ```
+//!
+//! [`SessionValidator`] 在 token 校验时按间隔复查业务规则（订阅过期、账号已删除等），
+//! 不通过时自动登出：
+//!
+//! [`SessionValidator`] re-checks business rules periodically during validation
+//! and logs the token out when they fail:
+//!
+//! ```rust,ignore
+//! struct SubscriptionCheck;
+//!
+//! #[async_trait]
+//! impl SessionValidator for SubscriptionCheck {
+//!     async fn validate(&self, token_info: &TokenInfo) -> SessionValidation {
+//!         if subscription_active(&token_info.login_id).await {
+//!             SessionValidation::Valid
+//!         } else {
+//!             SessionValidation::Invalid("subscription expired".into())
+//!         }
+//!     }
+//! }
+//!
+//! SaTokenConfig::builder()
+//!     .storage(Arc::new(MemoryStorage::new()))
+//!     .session_validator(Arc::new(SubscriptionCheck))
+//!     .session_validation_interval(600)
+//!     .build();
+//! ```
+
+use std::time::Duration;
+use async_trait::async_trait;
+use crate::token::TokenInfo;
+
+/// 修改密码接口所需的 scope | Scope granted to tokens issued for expired passwords
+pub const PASSWORD_CHANGE_SCOPE: &str = "password:change";
+
+/// 登录策略的判定结果 | Login Policy Decision
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginPolicyDecision {
+    /// 正常登录 | Normal login
+    Allow,
+
+    /// 密码已过期，只签发修改密码用的受限 token
+    /// Password expired, issue a token restricted to password change
+    PasswordExpired,
+}
+
+/// 登录策略钩子 | Login Policy Hook
+#[async_trait]
+pub trait LoginPolicy: Send + Sync {
+    /// 登录时调用 | Called on every login
+    ///
+    /// # 参数 | Parameters
+    /// - `login_id`: 登录 ID | Login ID
+    /// - `login_type`: 登录类型 | Login type
+    async fn check(&self, login_id: &str, login_type: &str) -> LoginPolicyDecision;
+}
+
+/// 登录上下文，供 [`TtlPolicy`] 判断风险 | Login context passed to [`TtlPolicy`]
+#[derive(Debug, Clone)]
+pub struct LoginContext {
+    /// 登录 ID | Login ID
+    pub login_id: String,
+
+    /// 登录类型 | Login type
+    pub login_type: String,
+
+    /// 设备标识 | Device identifier
+    pub device: Option<String>,
+
+    /// 登录时附带的额外数据（如 IP）| Extra data attached at login (e.g. IP)
+    pub extra_data: Option<serde_json::Value>,
+
+    /// 配置的默认有效期（None 表示永不过期）| Configured timeout, None means never expires
+    pub default_ttl: Option<Duration>,
+}
+
+impl LoginContext {
+    /// 从 extra_data 中读取 `ip` 字段 | Reads the `ip` field from extra_data
+    pub fn ip(&self) -> Option<&str> {
+        self.extra_data.as_ref()?.get("ip")?.as_str()
+    }
+}
+
+/// token 有效期策略 | Token TTL Policy
+///
+/// 设置后登录时不再固定使用 `config.timeout`，而是由策略决定本次登录的有效期。
+/// 登录时显式指定了过期时间的，以显式值为准。
+///
+/// When set, login asks the policy for the token lifetime instead of always
+/// using `config.timeout`. An explicit expiration passed to login still wins.
+#[async_trait]
+pub trait TtlPolicy: Send + Sync {
+    /// 返回本次登录签发的 token 有效期 | Lifetime of the token issued for this login
+    async fn ttl_for(&self, ctx: &LoginContext) -> Duration;
+}
+
+/// 会话复查结果 | Session Re-validation Result
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionValidation {
+    /// 会话仍然有效 | Session is still valid
+    Valid,
+
+    /// 会话不再有效，token 会被自动登出 | Session is no longer valid, the token is logged out
+    Invalid(String),
+}
+
+/// 会话复查钩子 | Session Re-validation Hook
+///
+/// 每个 token 每隔 `session_validation_interval` 秒最多调用一次（结果缓存在存储中），
+/// 避免每个请求都查询数据库。
+///
+/// Invoked at most once per `session_validation_interval` seconds per token
+/// (the result is cached in storage), keeping DB checks off the hot path.
+#[async_trait]
+pub trait SessionValidator: Send + Sync {
+    /// 复查会话是否仍满足业务规则 | Re-check business rules for the session
+    async fn validate(&self, token_info: &TokenInfo) -> SessionValidation;
+}
@@ -0,0 +1,108 @@
+// Author: 金书记
+//
+//! 权限预取 | Authz Prefetch
+//!
+//! 开启 `prefetch_authz` 后，登录时把账号的权限与角色连同权限版本写入 TokenInfo，
+//! 请求上下文中的 `StpUtil::has_permission` / `has_role` 直接使用这份快照，权限较多的
+//! 页面首次加载时不会引发一连串权限查询。
+//!
+//! With `prefetch_authz` enabled, login stores the account's permissions and
+//! roles together with its permission version in the TokenInfo. Checks made by
+//! `StpUtil::has_permission` / `has_role` within the request context use that
+//! snapshot, so permission-heavy pages don't trigger a burst of queries.
+//!
+//! 权限变更时发布 `PermissionChanged` 事件（`StpUtil` 的权限/角色修改方法会自动发布），
+//! [`AuthzInvalidationListener`] 递增账号的权限版本，旧快照随即失效。
+//!
+//! Publishing a `PermissionChanged` event (done by `StpUtil`'s permission and
+//! role setters) lets [`AuthzInvalidationListener`] bump the account's version,
+//! which invalidates older snapshots.
+//!
+//! ```rust,ignore
+//! let manager = SaTokenConfig::builder()
+//!     .storage(storage)
+//!     .prefetch_authz(true)
+//!     .build();
+//!
+//! // 权限在外部系统中变更时 | When permissions change elsewhere
+//! manager.notify_authz_changed("user_123").await;
+//! ```
+
+use std::sync::Arc;
+use async_trait::async_trait;
+use sa_token_adapter::storage::SaStorage;
+use crate::event::{SaTokenEvent, SaTokenEventType, SaTokenListener};
+
+/// 账号权限版本的存储 key | Storage key of an account's permission version
+pub(crate) fn authz_version_key(login_id: &str) -> String {
+    format!("sa:authz-version:{}", login_id)
+}
+
+/// 收到权限变更事件时递增权限版本 | Bumps the permission version on `PermissionChanged`
+///
+/// 管理器创建时已自动注册 | Registered by the manager on creation
+pub struct AuthzInvalidationListener {
+    storage: Arc<dyn SaStorage>,
+}
+
+impl AuthzInvalidationListener {
+    pub fn new(storage: Arc<dyn SaStorage>) -> Self {
+        Self { storage }
+    }
+}
+
+#[async_trait]
+impl SaTokenListener for AuthzInvalidationListener {
+    async fn on_event(&self, event: &SaTokenEvent) {
+        if event.event_type != SaTokenEventType::PermissionChanged {
+            return;
+        }
+        if let Err(e) = self.storage.incr(&authz_version_key(&event.login_id)).await {
+            tracing::warn!("Sa-Token: 递增权限版本失败，login_id={}: {}", event.login_id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use sa_token_storage_memory::MemoryStorage;
+    use crate::{SaTokenConfig, SaTokenManager};
+
+    fn new_manager(prefetch: bool) -> SaTokenManager {
+        let config = SaTokenConfig::builder().prefetch_authz(prefetch).build_config();
+        SaTokenManager::new(Arc::new(MemoryStorage::new()), config)
+    }
+
+    #[tokio::test]
+    async fn test_login_stores_snapshot() {
+        let manager = new_manager(true);
+        manager.user_permissions.write().await.insert("u1".into(), vec!["user:read".into()]);
+        manager.user_roles.write().await.insert("u1".into(), vec!["admin".into()]);
+
+        let token = manager.login("u1").await.unwrap();
+        let snapshot = manager.get_token_info(&token).await.unwrap().authz.unwrap();
+        assert_eq!(snapshot.permissions, vec!["user:read".to_string()]);
+        assert_eq!(snapshot.roles, vec!["admin".to_string()]);
+        assert_eq!(snapshot.version, 0);
+
+        let plain = new_manager(false);
+        let token = plain.login("u1").await.unwrap();
+        assert!(plain.get_token_info(&token).await.unwrap().authz.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_permission_change_invalidates_snapshot() {
+        let manager = new_manager(true);
+        let token = manager.login("u2").await.unwrap();
+        assert!(manager.get_token_info(&token).await.unwrap().authz.is_some());
+
+        manager.notify_authz_changed("u2").await;
+        assert_eq!(manager.authz_version("u2").await.unwrap(), 1);
+        assert!(manager.get_token_info(&token).await.unwrap().authz.is_none());
+
+        // 重新登录后得到新版本的快照
+        let token = manager.login("u2").await.unwrap();
+        assert_eq!(manager.get_token_info(&token).await.unwrap().authz.unwrap().version, 1);
+    }
+}
@@ -0,0 +1,131 @@
+// Author: 金书记
+//
+//! 身份透传 | Identity Propagation
+//!
+//! 处理请求时调用下游服务，需要把当前登录身份一并带过去。这里定义透传使用的请求头
+//! 以及签名上下文的格式：`<base64url(login_id)>.<签发时间>.<HMAC-SHA256>`，下游持有
+//! 同一个密钥即可验证，无需访问 token 存储，也不会把用户的 token 转发出去。
+//!
+//! When a handler calls a downstream service, the current identity should go
+//! along. This module defines the propagation headers and the signed context
+//! format `<base64url(login_id)>.<issued_at>.<HMAC-SHA256>`. Downstream
+//! services verify it with the shared secret, without access to the token
+//! storage and without the user's token leaving the caller.
+//!
+//! 框架插件（如 axum 的 `SaTokenPropagationLayer`）负责从 [`SaTokenContext`]
+//! 读取身份并写入请求头。
+//!
+//! Framework plugins (e.g. axum's `SaTokenPropagationLayer`) read the identity
+//! from [`SaTokenContext`] and write the headers.
+//!
+//! ```rust,ignore
+//! // 下游服务 | Downstream service
+//! let value = headers.get(SIGNED_CONTEXT_HEADER).and_then(|v| v.to_str().ok())?;
+//! let ctx = PropagatedContext::verify(value, secret, 60)?;
+//! println!("called on behalf of {}", ctx.login_id);
+//! ```
+//!
+//! [`SaTokenContext`]: crate::SaTokenContext
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use crate::error::{SaTokenError, SaTokenResult};
+
+/// 签名上下文请求头 | Header carrying the signed context
+pub const SIGNED_CONTEXT_HEADER: &str = "sa-context";
+
+/// 服务间共享凭证请求头 | Header carrying the service-to-service Same-Token
+pub const SAME_TOKEN_HEADER: &str = "sa-same-token";
+
+/// 透传的身份上下文 | Identity propagated to a downstream service
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropagatedContext {
+    /// 发起调用的登录 ID | Login ID the call is made on behalf of
+    pub login_id: String,
+
+    /// 签发时间（Unix 秒）| Issued at, Unix seconds
+    pub issued_at: i64,
+}
+
+impl PropagatedContext {
+    /// 以当前时间创建上下文 | Context issued now
+    pub fn new(login_id: impl Into<String>) -> Self {
+        Self {
+            login_id: login_id.into(),
+            issued_at: crate::time::now().timestamp(),
+        }
+    }
+
+    /// 签名并编码为请求头的值 | Sign and encode as a header value
+    pub fn sign(&self, secret: &str) -> String {
+        let payload = format!("{}.{}", URL_SAFE_NO_PAD.encode(&self.login_id), self.issued_at);
+        let signature = hex::encode(mac(secret, &payload).finalize().into_bytes());
+        format!("{}.{}", payload, signature)
+    }
+
+    /// 校验签名与时效并解析 | Verify signature and age, then parse
+    ///
+    /// # 参数 | Parameters
+    /// - `value`: 请求头的值 | Header value
+    /// - `secret`: 与调用方共享的密钥 | Secret shared with the caller
+    /// - `max_age`: 允许的最大时效（秒），-1 表示不限制 | Maximum age in seconds, -1 for unlimited
+    pub fn verify(value: &str, secret: &str, max_age: i64) -> SaTokenResult<Self> {
+        let invalid = || SaTokenError::InvalidToken("invalid propagated context".to_string());
+
+        let (payload, signature) = value.rsplit_once('.').ok_or_else(invalid)?;
+        let signature = hex::decode(signature).map_err(|_| invalid())?;
+        mac(secret, payload).verify_slice(&signature).map_err(|_| invalid())?;
+
+        let (login_id, issued_at) = payload.split_once('.').ok_or_else(invalid)?;
+        let login_id = URL_SAFE_NO_PAD.decode(login_id).ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .ok_or_else(invalid)?;
+        let issued_at: i64 = issued_at.parse().map_err(|_| invalid())?;
+
+        if max_age >= 0 && crate::time::now().timestamp() - issued_at > max_age {
+            return Err(SaTokenError::TokenExpired);
+        }
+
+        Ok(Self { login_id, issued_at })
+    }
+}
+
+fn mac(secret: &str, payload: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(payload.as_bytes());
+    mac
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let value = PropagatedContext::new("user:1001").sign("s2s-secret");
+        let ctx = PropagatedContext::verify(&value, "s2s-secret", 60).unwrap();
+        assert_eq!(ctx.login_id, "user:1001");
+
+        // 错误密钥或篡改 login_id 都会被拒绝
+        assert!(PropagatedContext::verify(&value, "other-secret", 60).is_err());
+        let forged = value.replacen(&URL_SAFE_NO_PAD.encode("user:1001"), &URL_SAFE_NO_PAD.encode("admin"), 1);
+        assert!(PropagatedContext::verify(&forged, "s2s-secret", 60).is_err());
+        assert!(PropagatedContext::verify("garbage", "s2s-secret", 60).is_err());
+    }
+
+    #[test]
+    fn test_verify_rejects_stale_context() {
+        let value = PropagatedContext::new("user_1").sign("s2s-secret");
+        crate::time::advance(chrono::Duration::seconds(120));
+
+        assert!(matches!(
+            PropagatedContext::verify(&value, "s2s-secret", 60),
+            Err(SaTokenError::TokenExpired)
+        ));
+        assert!(PropagatedContext::verify(&value, "s2s-secret", -1).is_ok());
+        crate::time::reset();
+    }
+}
@@ -0,0 +1,120 @@
+// Author: 金书记
+//
+//! 只读（维护）模式 | Read-only (Maintenance) Mode
+//!
+//! 开启后，目标用户仍可正常登录和读取数据，但所有写操作（POST/PUT/PATCH/DELETE）都会被拒绝，
+//! 拥有豁免权限的用户除外。模式保存在存储中，多实例部署时同时生效，可在运行时开启或关闭。
+//!
+//! While enabled, targeted users can still authenticate and read, but every
+//! mutating HTTP method is rejected unless the user holds the override permission.
+//! The mode lives in storage so it applies to every instance and can be toggled at runtime.
+//!
+//! ## 使用示例 | Usage Example
+//!
+//! ```rust,ignore
+//! use sa_token_core::{ReadOnlyMode, StpUtil};
+//!
+//! // 事故处理期间，只读限制 "operator" 角色
+//! StpUtil::enable_read_only_mode(
+//!     ReadOnlyMode::new()
+//!         .roles(["operator"])
+//!         .reason("incident #42"),
+//! ).await?;
+//!
+//! // 恢复 | Restore
+//! StpUtil::disable_read_only_mode().await?;
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// 默认的豁免权限 | Default override permission
+pub const READ_ONLY_OVERRIDE_PERMISSION: &str = "system:read-only:override";
+
+/// 只读模式设置 | Read-only Mode Settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadOnlyMode {
+    /// 受限的角色，为空表示所有用户（包括未登录请求）都受限
+    /// Restricted roles, empty means every request is restricted
+    #[serde(default)]
+    pub roles: Vec<String>,
+
+    /// 拥有该权限的用户不受限制 | Users with this permission are exempt
+    pub override_permission: String,
+
+    /// 开启原因，会出现在拒绝信息中 | Reason, included in the rejection message
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl Default for ReadOnlyMode {
+    fn default() -> Self {
+        Self {
+            roles: Vec::new(),
+            override_permission: READ_ONLY_OVERRIDE_PERMISSION.to_string(),
+            reason: None,
+        }
+    }
+}
+
+impl ReadOnlyMode {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 只限制这些角色的用户 | Restrict only users with these roles
+    pub fn roles<I, S>(mut self, roles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.roles = roles.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// 设置豁免权限 | Set the override permission
+    pub fn override_permission(mut self, permission: impl Into<String>) -> Self {
+        self.override_permission = permission.into();
+        self
+    }
+
+    /// 设置开启原因 | Set the reason
+    pub fn reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+
+    /// 拒绝时返回的信息 | Message used when rejecting a request
+    pub fn message(&self) -> String {
+        match &self.reason {
+            Some(reason) => format!("Read-only mode: {}", reason),
+            None => "Read-only mode".to_string(),
+        }
+    }
+}
+
+/// 是否为写操作的 HTTP 方法 | Whether the HTTP method mutates state
+pub fn is_mutating_method(method: &str) -> bool {
+    !matches!(
+        method.to_ascii_uppercase().as_str(),
+        "GET" | "HEAD" | "OPTIONS" | "TRACE"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_mutating_method() {
+        assert!(!is_mutating_method("GET"));
+        assert!(!is_mutating_method("options"));
+        assert!(is_mutating_method("POST"));
+        assert!(is_mutating_method("delete"));
+    }
+
+    #[test]
+    fn test_message() {
+        assert_eq!(ReadOnlyMode::new().message(), "Read-only mode");
+        assert_eq!(ReadOnlyMode::new().reason("incident").message(), "Read-only mode: incident");
+    }
+}
@@ -4,16 +4,40 @@
 //!
 //! Implements token refresh mechanism for long-term authentication
 //! 实现长期认证的 Token 刷新机制
+//!
+//! Two independent lifetimes apply to a refresh token:
+//! refresh token 受两个相互独立的有效期约束：
+//!
+//! - `refresh_token_timeout`: sliding window, restarted by every refresh | 滑动有效期，每次刷新重新计时
+//! - `refresh_token_max_lifetime`: absolute lifetime of the family started at login | 登录时开启的家族的绝对寿命
+//!
+//! Either one running out yields `RefreshTokenExpired` (re-login required). With
+//! `refresh_token_rotation` enabled, reusing a replaced token yields
+//! `RefreshTokenRotated` (retry with the new token).
+//! 任一到期返回 `RefreshTokenExpired`（需要重新登录）；开启 `refresh_token_rotation` 后，
+//! 使用已被替换的 token 返回 `RefreshTokenRotated`（用新 token 重试）。
 
 use std::sync::Arc;
 use chrono::{DateTime, Utc, Duration};
+use serde::{Deserialize, Serialize};
 use sa_token_adapter::storage::SaStorage;
 use crate::error::{SaTokenError, SaTokenResult};
 use crate::token::TokenValue;
-use crate::token::TokenGenerator;
+use crate::token::DefaultTokenGenerator;
 use crate::config::SaTokenConfig;
+use crate::idempotency::IdempotencyStore;
 use uuid::Uuid;
 
+/// 刷新结果 | Result of a refresh
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RefreshResult {
+    /// 新的访问令牌 | New access token
+    pub access_token: TokenValue,
+    /// 后续使用的 refresh token，未开启轮换时与传入的相同 | Refresh token to use next; unchanged without rotation
+    pub refresh_token: String,
+    pub login_id: String,
+}
+
 /// Refresh Token Manager | Refresh Token 管理器
 ///
 /// Manages refresh token generation, validation, and access token renewal
@@ -48,7 +72,7 @@ impl RefreshTokenManager {
         // Format: refresh_TIMESTAMP_LOGINID_UUID
         format!(
             "refresh_{}_{}_{}",
-            Utc::now().timestamp_millis(),
+            crate::time::now().timestamp_millis(),
             login_id,
             Uuid::new_v4().simple()
         )
@@ -56,6 +80,10 @@ impl RefreshTokenManager {
 
     /// Store refresh token with associated access token | 存储 refresh token 及其关联的访问令牌
     ///
+    /// Starts a new refresh-token family: its absolute lifetime
+    /// (`refresh_token_max_lifetime`) is counted from here.
+    /// 开启一个新的 refresh token 家族，绝对最长寿命从此刻开始计算。
+    ///
     /// # Arguments | 参数
     ///
     /// * `refresh_token` - Refresh token | Refresh token
@@ -67,9 +95,8 @@ impl RefreshTokenManager {
         access_token: &str,
         login_id: &str,
     ) -> SaTokenResult<()> {
-        let key = format!("sa:refresh:{}", refresh_token);
-        let expire_time = if self.config.refresh_token_timeout > 0 {
-            Some(Utc::now() + Duration::seconds(self.config.refresh_token_timeout))
+        let family_expire_time = if self.config.refresh_token_max_lifetime > 0 {
+            Some(crate::time::now() + Duration::seconds(self.config.refresh_token_max_lifetime))
         } else {
             None
         };
@@ -77,21 +104,13 @@ impl RefreshTokenManager {
         let value = serde_json::json!({
             "access_token": access_token,
             "login_id": login_id,
-            "created_at": Utc::now().to_rfc3339(),
-            "expire_time": expire_time.map(|t| t.to_rfc3339()),
-        }).to_string();
-
-        let ttl = if self.config.refresh_token_timeout > 0 {
-            Some(std::time::Duration::from_secs(self.config.refresh_token_timeout as u64))
-        } else {
-            None
-        };
-
-        self.storage.set(&key, &value, ttl)
-            .await
-            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+            "created_at": crate::time::now().to_rfc3339(),
+            "family_id": Uuid::new_v4().simple().to_string(),
+            "family_expire_time": family_expire_time.map(|t| t.to_rfc3339()),
+            "expire_time": self.sliding_expire_time(family_expire_time).map(|t| t.to_rfc3339()),
+        });
 
-        Ok(())
+        self.write(refresh_token, &value).await
     }
 
     /// Validate refresh token | 验证 refresh token
@@ -103,39 +122,21 @@ impl RefreshTokenManager {
     /// # Returns | 返回
     ///
     /// Associated login_id if valid | 如果有效则返回关联的 login_id
+    ///
+    /// # Errors | 错误
+    ///
+    /// * `RefreshTokenExpired` - Sliding or absolute lifetime exceeded, re-login required | 已过期，需要重新登录
+    /// * `RefreshTokenRotated` - Already rotated, retry with the new token | 已被轮换，使用新 token 重试
     pub async fn validate(&self, refresh_token: &str) -> SaTokenResult<String> {
-        let key = format!("sa:refresh:{}", refresh_token);
-        
-        let value_str = self.storage.get(&key)
-            .await
-            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
-            .ok_or_else(|| SaTokenError::RefreshTokenNotFound)?;
-
-        let value: serde_json::Value = serde_json::from_str(&value_str)
-            .map_err(|_| SaTokenError::RefreshTokenInvalidData)?;
-
-        let login_id = value["login_id"].as_str()
-            .ok_or_else(|| SaTokenError::RefreshTokenMissingLoginId)?
-            .to_string();
-
-        // Check expiration if set
-        if let Some(expire_str) = value["expire_time"].as_str() {
-            let expire_time = DateTime::parse_from_rfc3339(expire_str)
-                .map_err(|_| SaTokenError::RefreshTokenInvalidExpireTime)?
-                .with_timezone(&Utc);
-
-            if Utc::now() > expire_time {
-                // Delete expired refresh token
-                self.delete(refresh_token).await?;
-                return Err(SaTokenError::TokenExpired);
-            }
-        }
-
-        Ok(login_id)
+        self.load(refresh_token).await.map(|(_, login_id)| login_id)
     }
 
     /// Refresh access token using refresh token | 使用 refresh token 刷新访问令牌
     ///
+    /// With `refresh_token_rotation` enabled the caller also needs the new
+    /// refresh token, use `refresh` instead.
+    /// 开启 `refresh_token_rotation` 时需要拿到新的 refresh token，请改用 `refresh`。
+    ///
     /// # Arguments | 参数
     ///
     /// * `refresh_token` - Refresh token | Refresh token
@@ -147,36 +148,135 @@ impl RefreshTokenManager {
         &self,
         refresh_token: &str,
     ) -> SaTokenResult<(TokenValue, String)> {
-        // Validate refresh token
-        let login_id = self.validate(refresh_token).await?;
+        let result = self.refresh(refresh_token).await?;
+        Ok((result.access_token, result.login_id))
+    }
+
+    /// Refresh access token and slide the refresh token | 刷新访问令牌并延长 refresh token
+    ///
+    /// The sliding window restarts but never goes past the family's absolute
+    /// lifetime. With rotation enabled a new refresh token is returned and the
+    /// old one answers `RefreshTokenRotated` from now on.
+    /// 滑动有效期重新计时，但不会超过家族的绝对最长寿命。开启轮换时返回新的
+    /// refresh token，旧 token 之后返回 `RefreshTokenRotated`。
+    pub async fn refresh(&self, refresh_token: &str) -> SaTokenResult<RefreshResult> {
+        let (mut value, login_id) = self.load(refresh_token).await?;
+
+        let access_token = DefaultTokenGenerator::generate_with_login_id(&self.config, &login_id);
+        let family_expire_time = Self::parse_time(&value["family_expire_time"])?;
+        value["access_token"] = serde_json::json!(access_token.as_str());
+        value["refreshed_at"] = serde_json::json!(crate::time::now().to_rfc3339());
+        value["expire_time"] = serde_json::json!(
+            self.sliding_expire_time(family_expire_time).map(|t| t.to_rfc3339())
+        );
+
+        let refresh_token = if self.config.refresh_token_rotation {
+            let rotated = self.generate(&login_id);
+            self.write(&rotated, &value).await?;
+
+            // 旧 token 留下轮换标记，存活时间与新 token 一致，期间重复使用可被识别
+            let marker = serde_json::json!({
+                "login_id": login_id,
+                "family_id": value["family_id"],
+                "rotated_at": crate::time::now().to_rfc3339(),
+                "expire_time": value["expire_time"],
+                "rotated_to": rotated,
+            });
+            self.write(refresh_token, &marker).await?;
+            rotated
+        } else {
+            self.write(refresh_token, &value).await?;
+            refresh_token.to_string()
+        };
 
-        // Generate new access token
-        let new_access_token = TokenGenerator::generate_with_login_id(&self.config, &login_id);
+        Ok(RefreshResult { access_token, refresh_token, login_id })
+    }
 
-        // Update stored refresh token with new access token
+    /// 读取并校验 refresh token 记录 | Load and check a refresh token record
+    async fn load(&self, refresh_token: &str) -> SaTokenResult<(serde_json::Value, String)> {
         let key = format!("sa:refresh:{}", refresh_token);
+        
         let value_str = self.storage.get(&key)
             .await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?
             .ok_or_else(|| SaTokenError::RefreshTokenNotFound)?;
 
-        let mut value: serde_json::Value = serde_json::from_str(&value_str)
+        let value: serde_json::Value = serde_json::from_str(&value_str)
             .map_err(|_| SaTokenError::RefreshTokenInvalidData)?;
 
-        value["access_token"] = serde_json::json!(new_access_token.as_str());
-        value["refreshed_at"] = serde_json::json!(Utc::now().to_rfc3339());
+        let login_id = value["login_id"].as_str()
+            .ok_or(SaTokenError::RefreshTokenMissingLoginId)?
+            .to_string();
 
-        let ttl = if self.config.refresh_token_timeout > 0 {
-            Some(std::time::Duration::from_secs(self.config.refresh_token_timeout as u64))
-        } else {
-            None
-        };
+        if value["rotated_to"].is_string() {
+            return Err(SaTokenError::RefreshTokenRotated);
+        }
+
+        // 绝对寿命与滑动有效期任一到期都需要重新登录
+        let now = crate::time::now();
+        for field in ["family_expire_time", "expire_time"] {
+            if let Some(expire_time) = Self::parse_time(&value[field])?
+                && now > expire_time
+            {
+                self.delete(refresh_token).await?;
+                return Err(SaTokenError::RefreshTokenExpired);
+            }
+        }
+
+        Ok((value, login_id))
+    }
+
+    /// 写入记录，存储 TTL 与记录中的 `expire_time` 一致 | Write a record with a TTL matching its `expire_time`
+    async fn write(&self, refresh_token: &str, value: &serde_json::Value) -> SaTokenResult<()> {
+        let key = format!("sa:refresh:{}", refresh_token);
+        let ttl = Self::parse_time(&value["expire_time"])?
+            .map(|t| (t - crate::time::now()).to_std().unwrap_or_default().max(std::time::Duration::from_secs(1)));
 
         self.storage.set(&key, &value.to_string(), ttl)
             .await
-            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+
+    /// 新的滑动过期时间，不超过家族的绝对过期时间 | Next sliding expiry, capped by the family expiry
+    fn sliding_expire_time(&self, family_expire_time: Option<DateTime<Utc>>) -> Option<DateTime<Utc>> {
+        let sliding = (self.config.refresh_token_timeout > 0)
+            .then(|| crate::time::now() + Duration::seconds(self.config.refresh_token_timeout));
+        match (sliding, family_expire_time) {
+            (Some(sliding), Some(family)) => Some(sliding.min(family)),
+            (sliding, family) => sliding.or(family),
+        }
+    }
+
+    fn parse_time(value: &serde_json::Value) -> SaTokenResult<Option<DateTime<Utc>>> {
+        match value.as_str() {
+            Some(s) => DateTime::parse_from_rfc3339(s)
+                .map(|t| Some(t.with_timezone(&Utc)))
+                .map_err(|_| SaTokenError::RefreshTokenInvalidExpireTime),
+            None => Ok(None),
+        }
+    }
 
-        Ok((new_access_token, login_id))
+    /// Refresh access token with an idempotency key | 使用幂等键刷新访问令牌
+    ///
+    /// Retried requests carrying the same idempotency key get the access token
+    /// issued by the first call instead of rotating it again.
+    /// 相同幂等键的重复请求返回第一次刷新得到的访问令牌，不会再次刷新。
+    ///
+    /// # Arguments | 参数
+    ///
+    /// * `refresh_token` - Refresh token | Refresh token
+    /// * `idempotency_key` - Client supplied idempotency key | 客户端提供的幂等键
+    pub async fn refresh_access_token_idempotent(
+        &self,
+        refresh_token: &str,
+        idempotency_key: &str,
+    ) -> SaTokenResult<(TokenValue, String)> {
+        let store = IdempotencyStore::new(self.storage.clone(), self.config.idempotency_retention_duration());
+        store
+            .run(&format!("refresh:{}", refresh_token), idempotency_key, || {
+                self.refresh_access_token(refresh_token)
+            })
+            .await
     }
 
     /// Delete refresh token | 删除 refresh token
@@ -283,6 +383,64 @@ mod tests {
         assert_ne!(new_access_token.as_str(), old_access_token);
     }
 
+    #[tokio::test]
+    async fn test_refresh_access_token_idempotent() {
+        let storage = Arc::new(MemoryStorage::new());
+        let config = create_test_config();
+        let refresh_mgr = RefreshTokenManager::new(storage, config);
+
+        let refresh_token = refresh_mgr.generate("user_123");
+        refresh_mgr.store(&refresh_token, "access", "user_123").await.unwrap();
+
+        let (first, _) = refresh_mgr.refresh_access_token_idempotent(&refresh_token, "retry-1").await.unwrap();
+        let (retried, login_id) = refresh_mgr.refresh_access_token_idempotent(&refresh_token, "retry-1").await.unwrap();
+        assert_eq!(first, retried);
+        assert_eq!(login_id, "user_123");
+    }
+
+    #[tokio::test]
+    async fn test_rotation_distinguishes_rotated_tokens() {
+        let storage = Arc::new(MemoryStorage::new());
+        let config = Arc::new(SaTokenConfig { refresh_token_rotation: true, ..(*create_test_config()).clone() });
+        let refresh_mgr = RefreshTokenManager::new(storage, config);
+
+        let refresh_token = refresh_mgr.generate("user_123");
+        refresh_mgr.store(&refresh_token, "access", "user_123").await.unwrap();
+
+        let result = refresh_mgr.refresh(&refresh_token).await.unwrap();
+        assert_ne!(result.refresh_token, refresh_token);
+        assert!(matches!(
+            refresh_mgr.refresh(&refresh_token).await,
+            Err(SaTokenError::RefreshTokenRotated)
+        ));
+        assert_eq!(refresh_mgr.validate(&result.refresh_token).await.unwrap(), "user_123");
+    }
+
+    #[tokio::test]
+    async fn test_absolute_lifetime_caps_sliding_renewal() {
+        let storage = Arc::new(MemoryStorage::new());
+        let config = Arc::new(SaTokenConfig { refresh_token_max_lifetime: 1, ..(*create_test_config()).clone() });
+        let refresh_mgr = RefreshTokenManager::new(storage.clone(), config);
+
+        let refresh_token = refresh_mgr.generate("user_123");
+        refresh_mgr.store(&refresh_token, "access", "user_123").await.unwrap();
+        refresh_mgr.refresh(&refresh_token).await.unwrap();
+
+        // 刷新后 TTL 仍不超过绝对寿命
+        let key = format!("sa:refresh:{}", refresh_token);
+        let remaining = storage.ttl(&key).await.unwrap().remaining().unwrap();
+        assert!(remaining <= std::time::Duration::from_secs(1));
+
+        // 直接改写家族过期时间模拟到期，避免测试等待
+        let mut value: serde_json::Value = serde_json::from_str(&storage.get(&key).await.unwrap().unwrap()).unwrap();
+        value["family_expire_time"] = serde_json::json!((crate::time::now() - Duration::seconds(1)).to_rfc3339());
+        storage.set(&key, &value.to_string(), None).await.unwrap();
+        assert!(matches!(
+            refresh_mgr.refresh(&refresh_token).await,
+            Err(SaTokenError::RefreshTokenExpired)
+        ));
+    }
+
     #[tokio::test]
     async fn test_delete_refresh_token() {
         let storage = Arc::new(MemoryStorage::new());
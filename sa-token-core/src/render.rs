@@ -0,0 +1,209 @@
+// Author: 金书记
+//
+//! 拒绝响应渲染 | Rendering rejection responses
+//!
+//! 各框架插件拒绝请求（未登录、权限或角色不足等）时，通过管理器上的
+//! [`ErrorRenderer`] 生成状态码、响应头与响应体，插件只负责把 [`ErrorResponse`]
+//! 写成各自框架的响应。默认的 [`DefaultErrorRenderer`] 保持原有的 JSON 格式；
+//! [`ProblemJsonRenderer`] 输出 RFC 7807 `application/problem+json`，并在 401 时带上
+//! `WWW-Authenticate`。
+//!
+//! When a plugin rejects a request (not logged in, missing permission or
+//! role, ...), the [`ErrorRenderer`] on the manager produces the status,
+//! headers and body; plugins only turn the [`ErrorResponse`] into their
+//! framework's response. [`DefaultErrorRenderer`] keeps the existing JSON
+//! format, [`ProblemJsonRenderer`] emits RFC 7807 `application/problem+json`
+//! with `WWW-Authenticate` on 401.
+//!
+//! ```rust,ignore
+//! struct MyRenderer;
+//!
+//! impl ErrorRenderer for MyRenderer {
+//!     fn render(&self, err: &SaTokenError) -> ErrorResponse {
+//!         let (status, _) = SaCheck::rejection(err);
+//!         ErrorResponse::json(status, &json!({ "ok": false, "error": err.to_string() }))
+//!     }
+//! }
+//!
+//! let manager = SaTokenConfig::builder()
+//!     .error_renderer(Arc::new(MyRenderer))
+//!     .storage(storage)
+//!     .build();
+//! ```
+
+use serde_json::{json, Value};
+use crate::check::SaCheck;
+use crate::error::SaTokenError;
+
+/// JSON 响应体的 Content-Type | Content type of JSON bodies
+pub const JSON_CONTENT_TYPE: &str = "application/json";
+
+/// RFC 7807 响应体的 Content-Type | Content type of RFC 7807 bodies
+pub const PROBLEM_JSON_CONTENT_TYPE: &str = "application/problem+json";
+
+/// 与框架无关的拒绝响应 | Framework-independent rejection response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorResponse {
+    /// HTTP 状态码 | HTTP status code
+    pub status: u16,
+    /// 响应体的 Content-Type | Content type of the body
+    pub content_type: String,
+    /// 额外的响应头 | Extra response headers
+    pub headers: Vec<(String, String)>,
+    /// 响应体 | Response body
+    pub body: String,
+}
+
+impl ErrorResponse {
+    /// JSON 响应 | JSON response
+    pub fn json(status: u16, body: &Value) -> Self {
+        Self {
+            status,
+            content_type: JSON_CONTENT_TYPE.to_string(),
+            headers: Vec::new(),
+            body: body.to_string(),
+        }
+    }
+
+    /// 添加响应头 | Add a response header
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// 按名称（不区分大小写）取响应头 | Header value by name, case-insensitive
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// 拒绝响应渲染器 | Rejection response renderer
+pub trait ErrorRenderer: Send + Sync {
+    /// 把鉴权错误渲染为响应 | Render an auth error as a response
+    fn render(&self, err: &SaTokenError) -> ErrorResponse;
+}
+
+/// 默认渲染器：`{"code", "message", "missing"}`，状态码同 [`SaCheck::rejection`]
+///
+/// Default renderer: `{"code", "message", "missing"}` with the status of [`SaCheck::rejection`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultErrorRenderer;
+
+impl ErrorRenderer for DefaultErrorRenderer {
+    fn render(&self, err: &SaTokenError) -> ErrorResponse {
+        let (status, body) = SaCheck::rejection(err);
+        ErrorResponse::json(status, &body)
+    }
+}
+
+/// RFC 7807 渲染器 | RFC 7807 renderer
+///
+/// 响应体为 `{"type", "title", "status", "detail"}`，权限/角色不足时附带 `missing`；
+/// 401 响应带 `WWW-Authenticate: Bearer realm="..."`。
+///
+/// The body is `{"type", "title", "status", "detail"}` plus `missing` for
+/// denied permissions or roles; 401 responses carry
+/// `WWW-Authenticate: Bearer realm="..."`.
+#[derive(Debug, Clone)]
+pub struct ProblemJsonRenderer {
+    realm: String,
+    type_base: Option<String>,
+}
+
+impl Default for ProblemJsonRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProblemJsonRenderer {
+    pub fn new() -> Self {
+        Self {
+            realm: "sa-token".to_string(),
+            type_base: None,
+        }
+    }
+
+    /// `WWW-Authenticate` 中的 realm，默认 `sa-token` | Realm of `WWW-Authenticate`, `sa-token` by default
+    pub fn realm(mut self, realm: impl Into<String>) -> Self {
+        self.realm = realm.into();
+        self
+    }
+
+    /// `type` 字段的前缀，如 `https://example.com/problems/`，未设置时为 `about:blank`
+    ///
+    /// Prefix of the `type` member, e.g. `https://example.com/problems/`; `about:blank` when unset.
+    pub fn type_base(mut self, base: impl Into<String>) -> Self {
+        self.type_base = Some(base.into());
+        self
+    }
+
+    fn problem_type(status: u16) -> &'static str {
+        match status {
+            400 => "invalid-payload",
+            401 => "not-login",
+            413 => "payload-too-large",
+            _ => "access-denied",
+        }
+    }
+}
+
+impl ErrorRenderer for ProblemJsonRenderer {
+    fn render(&self, err: &SaTokenError) -> ErrorResponse {
+        let (status, legacy) = SaCheck::rejection(err);
+        let mut body = json!({
+            "type": self.type_base.as_ref()
+                .map(|base| format!("{}{}", base, Self::problem_type(status)))
+                .unwrap_or_else(|| "about:blank".to_string()),
+            "title": legacy["message"],
+            "status": status,
+            "detail": err.to_string(),
+        });
+        if let Some(missing) = legacy.get("missing") {
+            body["missing"] = missing.clone();
+        }
+
+        let mut response = ErrorResponse::json(status, &body);
+        response.content_type = PROBLEM_JSON_CONTENT_TYPE.to_string();
+        if status == 401 {
+            response = response.with_header("WWW-Authenticate", format!("Bearer realm=\"{}\"", self.realm));
+        }
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_renderer_keeps_legacy_body() {
+        let response = DefaultErrorRenderer.render(&SaTokenError::RoleDenied("admin".to_string()));
+        assert_eq!(response.status, 403);
+        assert_eq!(response.content_type, JSON_CONTENT_TYPE);
+        let body: Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(body["message"], "Role required");
+        assert_eq!(body["missing"], json!(["admin"]));
+        assert!(response.headers.is_empty());
+    }
+
+    #[test]
+    fn test_problem_json_renderer() {
+        let renderer = ProblemJsonRenderer::new().realm("api").type_base("https://example.com/problems/");
+
+        let response = renderer.render(&SaTokenError::NotLogin);
+        assert_eq!((response.status, response.content_type.as_str()), (401, PROBLEM_JSON_CONTENT_TYPE));
+        assert_eq!(response.header("www-authenticate"), Some("Bearer realm=\"api\""));
+        let body: Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(body["type"], "https://example.com/problems/not-login");
+        assert_eq!(body["status"], 401);
+
+        let response = renderer.render(&SaTokenError::PermissionsDenied(vec!["user:delete".to_string()]));
+        assert_eq!(response.status, 403);
+        assert!(response.header("WWW-Authenticate").is_none());
+        let body: Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(body["missing"], json!(["user:delete"]));
+    }
+}
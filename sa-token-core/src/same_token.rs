@@ -0,0 +1,150 @@
+// Author: 金书记
+//
+//! Same-Token 服务间凭证 | Same-Token internal service credential
+//!
+//! 对应 Java 版的 `SaSameUtil`：网关与内部服务共享存储中的同一个随机凭证，调用内部
+//! 服务时通过 `sa-same-token` 请求头携带，内部服务据此拒绝绕过网关的直接访问。
+//! 凭证按 `same_token_timeout` 轮换，刷新后上一个凭证在原有效期内仍然有效，
+//! 正在使用旧凭证的调用方不会因轮换失败。
+//!
+//! Equivalent of Java's `SaSameUtil`. The gateway and internal services share
+//! one random credential through the storage and send it in the
+//! `sa-same-token` header; internal services reject calls that bypass the
+//! gateway. The credential rotates every `same_token_timeout` seconds, and the
+//! previous one stays valid for its original lifetime so callers holding it
+//! do not fail during a rotation.
+//!
+//! ```rust,ignore
+//! // 网关 | Gateway
+//! let same = manager.same_token_manager();
+//! request.headers_mut().insert(SAME_TOKEN_HEADER, same.get_token().await?.parse()?);
+//!
+//! // 内部服务 | Internal service
+//! same.check_token(headers.get(SAME_TOKEN_HEADER).and_then(|v| v.to_str().ok())).await?;
+//! ```
+
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+use sa_token_adapter::storage::SaStorage;
+use crate::context::SaTokenContext;
+use crate::error::{SaTokenError, SaTokenResult};
+
+/// 当前凭证的存储 key | Storage key of the current credential
+const SAME_TOKEN_KEY: &str = "sa:same-token";
+
+/// 上一个凭证的存储 key | Storage key of the previous credential
+const PAST_SAME_TOKEN_KEY: &str = "sa:same-token:past";
+
+/// 默认轮换周期（秒），与 Java 版一致为一天 | Default rotation period in seconds, one day as in Java
+pub const DEFAULT_SAME_TOKEN_TIMEOUT: i64 = 86400;
+
+/// Same-Token 管理器 | Same-Token manager
+#[derive(Clone)]
+pub struct SameTokenManager {
+    storage: Arc<dyn SaStorage>,
+    timeout: i64,
+}
+
+impl SameTokenManager {
+    /// 创建管理器，`timeout` 为轮换周期（秒），<= 0 表示不自动过期
+    ///
+    /// Create a manager; `timeout` is the rotation period in seconds, `<= 0` never expires.
+    pub fn new(storage: Arc<dyn SaStorage>, timeout: i64) -> Self {
+        Self { storage, timeout }
+    }
+
+    /// 当前凭证，不存在（首次使用或已过期）时生成 | Current credential, issued when missing or expired
+    pub async fn get_token(&self) -> SaTokenResult<String> {
+        match self.storage.get(SAME_TOKEN_KEY).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+        {
+            Some(token) => Ok(token),
+            None => self.refresh().await,
+        }
+    }
+
+    /// 立即轮换：生成新凭证，旧凭证转为上一个凭证，在原有效期内仍然有效
+    ///
+    /// Rotate now: issue a new credential and keep the old one valid for its original lifetime.
+    pub async fn refresh(&self) -> SaTokenResult<String> {
+        let ttl = (self.timeout > 0).then(|| Duration::from_secs(self.timeout as u64));
+        if let Some(current) = self.storage.get(SAME_TOKEN_KEY).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+        {
+            self.storage.set(PAST_SAME_TOKEN_KEY, &current, ttl).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        }
+
+        let token = Uuid::new_v4().simple().to_string();
+        self.storage.set(SAME_TOKEN_KEY, &token, ttl).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        Ok(token)
+    }
+
+    /// 是否为当前或上一个凭证 | Whether the token is the current or the previous credential
+    pub async fn is_valid(&self, token: &str) -> SaTokenResult<bool> {
+        if token.is_empty() {
+            return Ok(false);
+        }
+        let keys = [SAME_TOKEN_KEY, PAST_SAME_TOKEN_KEY];
+        let values = self.storage.mget(&keys).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        Ok(values.iter().flatten().any(|value| constant_time_eq(value.as_bytes(), token.as_bytes())))
+    }
+
+    /// 校验凭证，缺失或无效时返回 `InvalidSameToken` | Check a credential; missing or invalid yields `InvalidSameToken`
+    pub async fn check_token(&self, token: Option<&str>) -> SaTokenResult<()> {
+        match token {
+            Some(token) if self.is_valid(token).await? => Ok(()),
+            _ => Err(SaTokenError::InvalidSameToken),
+        }
+    }
+
+    /// 校验当前请求携带的凭证（框架插件从 `sa-same-token` 请求头记录到上下文中）
+    ///
+    /// Checks the credential of the current request, recorded into the
+    /// context from the `sa-same-token` header by the framework plugins.
+    pub async fn check_current_request(&self) -> SaTokenResult<()> {
+        let token = SaTokenContext::get_current().and_then(|ctx| ctx.same_token);
+        self.check_token(token.as_deref()).await
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_storage_memory::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_refresh_keeps_previous_token_valid() {
+        let same = SameTokenManager::new(Arc::new(MemoryStorage::new()), DEFAULT_SAME_TOKEN_TIMEOUT);
+        let first = same.get_token().await.unwrap();
+        assert_eq!(same.get_token().await.unwrap(), first);
+
+        let second = same.refresh().await.unwrap();
+        assert_ne!(second, first);
+        assert!(same.is_valid(&first).await.unwrap());
+        assert!(same.is_valid(&second).await.unwrap());
+
+        // 再次轮换后最早的凭证失效
+        same.refresh().await.unwrap();
+        assert!(!same.is_valid(&first).await.unwrap());
+        assert!(matches!(same.check_token(None).await, Err(SaTokenError::InvalidSameToken)));
+    }
+
+    #[tokio::test]
+    async fn test_check_current_request() {
+        let same = SameTokenManager::new(Arc::new(MemoryStorage::new()), DEFAULT_SAME_TOKEN_TIMEOUT);
+        let token = same.get_token().await.unwrap();
+
+        let mut ctx = SaTokenContext::new();
+        ctx.capture_same_token(Some(&token));
+        assert!(SaTokenContext::scope(ctx, same.check_current_request()).await.is_ok());
+        assert!(SaTokenContext::scope(SaTokenContext::new(), same.check_current_request()).await.is_err());
+    }
+}
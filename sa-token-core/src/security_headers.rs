@@ -0,0 +1,130 @@
+// Author: 金书记
+//
+//! 安全响应头 | Security Headers
+//!
+//! 与框架无关的安全响应头配置，各 Web 插件的 `SaSecurityHeadersLayer` 基于它实现。
+//! Framework-agnostic set of standard security headers, applied by the
+//! `SaSecurityHeadersLayer` of each web plugin.
+//!
+//! ## 使用示例 | Usage Example
+//!
+//! ```rust,ignore
+//! use sa_token_core::SecurityHeaders;
+//!
+//! let headers = SecurityHeaders::default()
+//!     .frame_options("SAMEORIGIN")
+//!     .content_security_policy("default-src 'self'");
+//!
+//! for (name, value) in headers.headers() {
+//!     response.set_header(name, &value);
+//! }
+//! ```
+
+/// 安全响应头配置 | Security Headers Configuration
+///
+/// 默认值 | Defaults:
+/// - `X-Content-Type-Options: nosniff`
+/// - `X-Frame-Options: DENY`
+/// - `Referrer-Policy: strict-origin-when-cross-origin`
+/// - `Content-Security-Policy`: 不设置 | not set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecurityHeaders {
+    /// X-Content-Type-Options（None 表示不设置）| None to skip
+    pub content_type_options: Option<String>,
+
+    /// X-Frame-Options（None 表示不设置）| None to skip
+    pub frame_options: Option<String>,
+
+    /// Referrer-Policy（None 表示不设置）| None to skip
+    pub referrer_policy: Option<String>,
+
+    /// Content-Security-Policy 模板（None 表示不设置）| CSP template, None to skip
+    pub content_security_policy: Option<String>,
+
+    /// 是否对所有响应都设置（默认只对已登录请求的响应设置）
+    /// Apply to every response instead of only authenticated ones
+    pub apply_to_all: bool,
+}
+
+impl Default for SecurityHeaders {
+    fn default() -> Self {
+        Self {
+            content_type_options: Some("nosniff".to_string()),
+            frame_options: Some("DENY".to_string()),
+            referrer_policy: Some("strict-origin-when-cross-origin".to_string()),
+            content_security_policy: None,
+            apply_to_all: false,
+        }
+    }
+}
+
+impl SecurityHeaders {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置 X-Frame-Options（如 "DENY"、"SAMEORIGIN"）
+    pub fn frame_options(mut self, value: impl Into<String>) -> Self {
+        self.frame_options = Some(value.into());
+        self
+    }
+
+    /// 设置 Referrer-Policy
+    pub fn referrer_policy(mut self, value: impl Into<String>) -> Self {
+        self.referrer_policy = Some(value.into());
+        self
+    }
+
+    /// 设置 Content-Security-Policy
+    pub fn content_security_policy(mut self, value: impl Into<String>) -> Self {
+        self.content_security_policy = Some(value.into());
+        self
+    }
+
+    /// 对所有响应（包括未登录请求）都设置安全响应头
+    pub fn apply_to_all(mut self, enabled: bool) -> Self {
+        self.apply_to_all = enabled;
+        self
+    }
+
+    /// 需要写入响应的头部列表 | Headers to write into the response
+    pub fn headers(&self) -> Vec<(&'static str, String)> {
+        let candidates = [
+            ("X-Content-Type-Options", &self.content_type_options),
+            ("X-Frame-Options", &self.frame_options),
+            ("Referrer-Policy", &self.referrer_policy),
+            ("Content-Security-Policy", &self.content_security_policy),
+        ];
+        candidates.into_iter()
+            .filter_map(|(name, value)| value.as_ref().map(|v| (name, v.clone())))
+            .collect()
+    }
+
+    /// 是否应对该响应设置头部 | Whether headers apply to this response
+    pub fn should_apply(&self, authenticated: bool) -> bool {
+        self.apply_to_all || authenticated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_headers() {
+        let headers = SecurityHeaders::default().headers();
+        assert_eq!(headers, vec![
+            ("X-Content-Type-Options", "nosniff".to_string()),
+            ("X-Frame-Options", "DENY".to_string()),
+            ("Referrer-Policy", "strict-origin-when-cross-origin".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_csp_and_scope() {
+        let config = SecurityHeaders::new().content_security_policy("default-src 'self'");
+        assert!(config.headers().contains(&("Content-Security-Policy", "default-src 'self'".to_string())));
+        assert!(!config.should_apply(false));
+        assert!(config.apply_to_all(true).should_apply(false));
+    }
+}
@@ -15,6 +15,7 @@ use chrono::{DateTime, Utc};
 /// - `id`: Session 唯一标识 | Session unique identifier
 /// - `create_time`: 创建时间 | Creation time
 /// - `data`: 存储的键值对数据 | Stored key-value data
+/// - `key_expires`: 临时数据的过期时间 | Expiry of temporary keys
 /// 
 /// # 使用示例 | Usage Example
 /// 
@@ -25,6 +26,9 @@ use chrono::{DateTime, Utc};
 /// 
 /// let username: Option<String> = session.get("username");
 /// println!("Username: {:?}", username);
+/// 
+/// // 5 分钟后自动失效的临时数据 | Temporary data, gone after 5 minutes
+/// session.set_with_ttl("sms_code", "123456", 300)?;
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SaSession {
@@ -37,14 +41,19 @@ pub struct SaSession {
     /// 数据存储 | Data storage
     #[serde(flatten)]
     pub data: HashMap<String, serde_json::Value>,
+    
+    /// 通过 `set_with_ttl` 写入的 key 的过期时间 | Expiry of keys written with `set_with_ttl`
+    #[serde(rename = "__key_expires", default, skip_serializing_if = "HashMap::is_empty")]
+    pub key_expires: HashMap<String, DateTime<Utc>>,
 }
 
 impl SaSession {
     pub fn new(id: impl Into<String>) -> Self {
         Self {
             id: id.into(),
-            create_time: Utc::now(),
+            create_time: crate::time::now(),
             data: HashMap::new(),
+            key_expires: HashMap::new(),
         }
     }
     
@@ -58,11 +67,64 @@ impl SaSession {
     /// - `Ok(())`: 设置成功 | Set successfully
     /// - `Err`: 序列化失败 | Serialization failed
     pub fn set<T: Serialize>(&mut self, key: impl Into<String>, value: T) -> Result<(), serde_json::Error> {
+        let key = key.into();
         let json_value = serde_json::to_value(value)?;
-        self.data.insert(key.into(), json_value);
+        self.key_expires.remove(&key);
+        self.data.insert(key, json_value);
         Ok(())
     }
     
+    /// 设置带有效期的值 | Set Value with TTL
+    /// 
+    /// 到期后 `get` / `has` 视为不存在，下次保存 session 时清除。
+    /// Once expired the key reads as absent and is dropped on the next save.
+    /// 
+    /// # 参数 | Parameters
+    /// - `key`: 键名 | Key name
+    /// - `value`: 要存储的值 | Value to store
+    /// - `ttl`: 有效期（秒），小于等于 0 时与 `set` 相同 | Seconds to live, `set` semantics when <= 0
+    pub fn set_with_ttl<T: Serialize>(&mut self, key: impl Into<String>, value: T, ttl: i64) -> Result<(), serde_json::Error> {
+        let key = key.into();
+        self.set(key.clone(), value)?;
+        if ttl > 0 {
+            self.key_expires.insert(key, crate::time::now() + chrono::Duration::seconds(ttl));
+        }
+        Ok(())
+    }
+    
+    /// 获取 key 的剩余有效期 | Get Key Timeout
+    /// 
+    /// # 返回 | Returns
+    /// - `Some(-1)`: 永久有效 | No TTL
+    /// - `Some(seconds)`: 剩余秒数 | Seconds left
+    /// - `None`: 键不存在或已过期 | Key missing or expired
+    pub fn get_key_timeout(&self, key: &str) -> Option<i64> {
+        if !self.has(key) {
+            return None;
+        }
+        Some(match self.key_expires.get(key) {
+            Some(at) => (*at - crate::time::now()).num_seconds(),
+            None => -1,
+        })
+    }
+    
+    /// 清除已过期的 key，返回被清除的键名 | Drop expired keys, returning their names
+    pub fn remove_expired(&mut self) -> Vec<String> {
+        let now = crate::time::now();
+        let expired: Vec<String> = self.key_expires.iter()
+            .filter(|(_, at)| **at <= now)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &expired {
+            self.remove(key);
+        }
+        expired
+    }
+    
+    fn is_expired(&self, key: &str) -> bool {
+        self.key_expires.get(key).is_some_and(|at| *at <= crate::time::now())
+    }
+    
     /// 获取值 | Get Value
     /// 
     /// # 参数 | Parameters
@@ -72,6 +134,9 @@ impl SaSession {
     /// - `Some(value)`: 找到值并成功反序列化 | Found value and deserialized successfully
     /// - `None`: 键不存在或反序列化失败 | Key not found or deserialization failed
     pub fn get<T: for<'de> Deserialize<'de>>(&self, key: &str) -> Option<T> {
+        if self.is_expired(key) {
+            return None;
+        }
         self.data.get(key)
             .and_then(|v| serde_json::from_value(v.clone()).ok())
     }
@@ -85,6 +150,7 @@ impl SaSession {
     /// 被删除的值，如果键不存在则返回 None
     /// Removed value, or None if key doesn't exist
     pub fn remove(&mut self, key: &str) -> Option<serde_json::Value> {
+        self.key_expires.remove(key);
         self.data.remove(key)
     }
     
@@ -93,6 +159,7 @@ impl SaSession {
     /// 删除所有存储的数据 | Remove all stored data
     pub fn clear(&mut self) {
         self.data.clear();
+        self.key_expires.clear();
     }
     
     /// 检查 key 是否存在 | Check if Key Exists
@@ -104,6 +171,58 @@ impl SaSession {
     /// - `true`: 键存在 | Key exists
     /// - `false`: 键不存在 | Key doesn't exist
     pub fn has(&self, key: &str) -> bool {
-        self.data.contains_key(key)
+        self.data.contains_key(key) && !self.is_expired(key)
+    }
+    
+    /// 统计 Session 占用空间 | Session Size Report
+    /// 
+    /// 按序列化后的字节数统计每个 key 的大小，结果按从大到小排序
+    /// Measures each key by its serialized size, largest first
+    pub fn usage(&self) -> SessionUsage {
+        let mut keys: Vec<(String, usize)> = self.data.iter()
+            .map(|(k, v)| (k.clone(), v.to_string().len()))
+            .collect();
+        keys.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        
+        SessionUsage {
+            total_bytes: serde_json::to_string(self).map(|s| s.len()).unwrap_or(0),
+            keys,
+        }
+    }
+}
+
+/// Session 占用空间报告 | Session Size Report
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionUsage {
+    /// 整个 Session 序列化后的字节数 | Serialized size of the whole session
+    pub total_bytes: usize,
+    
+    /// 每个 key 序列化后的字节数（从大到小）| Serialized size per key, largest first
+    pub keys: Vec<(String, usize)>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_with_ttl_expires_key() {
+        let mut session = SaSession::new("user_1");
+        session.set("name", "alice").unwrap();
+        session.set_with_ttl("sms_code", "123456", 300).unwrap();
+        assert_eq!(session.get::<String>("sms_code").as_deref(), Some("123456"));
+        assert_eq!(session.get_key_timeout("name"), Some(-1));
+        assert!(session.get_key_timeout("sms_code").unwrap() > 290);
+
+        // 往返序列化保留过期时间
+        let mut session: SaSession = serde_json::from_str(&serde_json::to_string(&session).unwrap()).unwrap();
+        crate::time::advance(chrono::Duration::seconds(301));
+        assert!(session.get::<String>("sms_code").is_none());
+        assert!(!session.has("sms_code"));
+        assert_eq!(session.get_key_timeout("sms_code"), None);
+        assert_eq!(session.remove_expired(), vec!["sms_code"]);
+        assert!(session.key_expires.is_empty());
+        assert!(session.has("name"));
+        crate::time::reset();
     }
 }
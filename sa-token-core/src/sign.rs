@@ -0,0 +1,212 @@
+// Author: 金书记
+//
+//! 参数签名 | Request parameter signing
+//!
+//! 对应 Java 版的 sa-sign（`SaSignUtil`），用于服务间调用：调用方用共享密钥给请求参数
+//! 签名，附带 `timestamp`（毫秒）、`nonce` 与 `sign` 三个参数；接收方校验签名、时间戳
+//! 窗口，并通过存储保证 nonce 只能使用一次。
+//!
+//! Equivalent of Java's sa-sign (`SaSignUtil`) for service-to-service calls.
+//! The caller signs the request parameters with a shared secret, adding
+//! `timestamp` (milliseconds), `nonce` and `sign`. The receiver checks the
+//! signature and the timestamp window, and the storage makes each nonce
+//! single-use.
+//!
+//! 签名为 HMAC-SHA256（十六进制），签名内容是除 `sign` 外的全部参数按名称排序后
+//! URL 编码拼接的 `k1=v1&k2=v2`。
+//! The signature is hex HMAC-SHA256 over all parameters except `sign`,
+//! sorted by name and URL-encoded as `k1=v1&k2=v2`.
+//!
+//! ```rust,ignore
+//! // 调用方 | Caller
+//! let params = SignManager::sign([("user_id", "1001"), ("amount", "20")], "s2s-secret");
+//! client.get(format!("{}/internal/pay?{}", base, SignManager::to_query(&params))).send().await?;
+//!
+//! // 接收方 | Receiver
+//! let signer = SignManager::new(storage.clone(), "s2s-secret").timestamp_window(300);
+//! signer.verify_query(request.uri().query().unwrap_or_default()).await?;
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use uuid::Uuid;
+use sa_token_adapter::storage::SaStorage;
+use crate::error::{SaTokenError, SaTokenResult};
+
+/// 签名参数名 | Name of the signature parameter
+pub const SIGN_PARAM: &str = "sign";
+
+/// 时间戳参数名（Unix 毫秒）| Name of the timestamp parameter, Unix milliseconds
+pub const SIGN_TIMESTAMP_PARAM: &str = "timestamp";
+
+/// nonce 参数名 | Name of the nonce parameter
+pub const SIGN_NONCE_PARAM: &str = "nonce";
+
+/// 默认允许的时间戳偏差（秒），与 Java 版一致为 15 分钟
+///
+/// Default allowed timestamp skew in seconds, 15 minutes as in the Java version.
+pub const DEFAULT_SIGN_TIMESTAMP_WINDOW: i64 = 900;
+
+fn sign_nonce_key(nonce: &str) -> String {
+    format!("sa:sign-nonce:{}", nonce)
+}
+
+/// 参数签名管理器 | Parameter signing manager
+#[derive(Clone)]
+pub struct SignManager {
+    storage: Arc<dyn SaStorage>,
+    secret: String,
+    window: i64,
+}
+
+impl SignManager {
+    /// 使用共享密钥创建接收方的校验器 | Create a verifier with the shared secret
+    pub fn new(storage: Arc<dyn SaStorage>, secret: impl Into<String>) -> Self {
+        Self {
+            storage,
+            secret: secret.into(),
+            window: DEFAULT_SIGN_TIMESTAMP_WINDOW,
+        }
+    }
+
+    /// 允许的时间戳偏差（秒），默认 900 | Allowed timestamp skew in seconds, 900 by default
+    pub fn timestamp_window(mut self, seconds: i64) -> Self {
+        self.window = seconds;
+        self
+    }
+
+    /// 为参数签名，返回附带 `timestamp`、`nonce` 与 `sign` 的完整参数
+    ///
+    /// Signs the parameters and returns them with `timestamp`, `nonce` and `sign` added.
+    pub fn sign<K, V>(params: impl IntoIterator<Item = (K, V)>, secret: &str) -> BTreeMap<String, String>
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let mut params: BTreeMap<String, String> = params.into_iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect();
+        params.insert(SIGN_TIMESTAMP_PARAM.to_string(), crate::time::now().timestamp_millis().to_string());
+        params.insert(SIGN_NONCE_PARAM.to_string(), Uuid::new_v4().simple().to_string());
+        params.remove(SIGN_PARAM);
+        let signature = hex::encode(mac(&params, secret).finalize().into_bytes());
+        params.insert(SIGN_PARAM.to_string(), signature);
+        params
+    }
+
+    /// 把参数编码为查询字符串 | Encode parameters as a query string
+    pub fn to_query(params: &BTreeMap<String, String>) -> String {
+        params.iter()
+            .map(|(key, value)| format!("{}={}", urlencoding::encode(key), urlencoding::encode(value)))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    /// 校验签名参数：签名、时间戳窗口，并消费 nonce
+    ///
+    /// 缺少参数或签名不符返回 `InvalidSign`，时间戳超出窗口返回 `InvalidNonceTimestamp`，
+    /// nonce 已使用过返回 `NonceAlreadyUsed`。
+    ///
+    /// Missing parameters or a wrong signature yield `InvalidSign`, a timestamp
+    /// outside the window `InvalidNonceTimestamp`, a reused nonce `NonceAlreadyUsed`.
+    pub async fn verify(&self, params: &BTreeMap<String, String>) -> SaTokenResult<()> {
+        let param = |name: &str| params.get(name)
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| SaTokenError::InvalidSign(format!("missing parameter '{}'", name)));
+        let signature = param(SIGN_PARAM)?;
+        let timestamp: i64 = param(SIGN_TIMESTAMP_PARAM)?.parse()
+            .map_err(|_| SaTokenError::InvalidSign("timestamp is not a number".to_string()))?;
+        let nonce = param(SIGN_NONCE_PARAM)?;
+
+        if (crate::time::now().timestamp_millis() - timestamp).abs() > self.window * 1000 {
+            return Err(SaTokenError::InvalidNonceTimestamp);
+        }
+
+        let mut signed = params.clone();
+        signed.remove(SIGN_PARAM);
+        let signature = hex::decode(signature)
+            .map_err(|_| SaTokenError::InvalidSign("signature mismatch".to_string()))?;
+        mac(&signed, &self.secret).verify_slice(&signature)
+            .map_err(|_| SaTokenError::InvalidSign("signature mismatch".to_string()))?;
+
+        // 签名通过后才消费 nonce，set_nx 保证并发请求中只有一个成功，且 nonce 与过期时间一次写入
+        // 时间戳在窗口两侧都可能被接受，nonce 至少保留两个窗口
+        let ttl = Duration::from_secs((self.window.max(1) * 2) as u64);
+        let fresh = self.storage.set_nx(&sign_nonce_key(nonce), "1", Some(ttl)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        if !fresh {
+            return Err(SaTokenError::NonceAlreadyUsed);
+        }
+        Ok(())
+    }
+
+    /// 解析查询字符串后校验 | Parse a query string, then verify
+    pub async fn verify_query(&self, query: &str) -> SaTokenResult<()> {
+        self.verify(&parse_query(query)).await
+    }
+}
+
+/// 解析 `application/x-www-form-urlencoded` 格式的参数，重复的参数名取最后一个
+///
+/// Parses form-urlencoded parameters; the last value wins for repeated names.
+pub fn parse_query(query: &str) -> BTreeMap<String, String> {
+    let decode = |part: &str| urlencoding::decode(&part.replace('+', " "))
+        .map(|decoded| decoded.into_owned())
+        .unwrap_or_else(|_| part.to_string());
+    query.split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(key), decode(value))
+        })
+        .collect()
+}
+
+fn mac(params: &BTreeMap<String, String>, secret: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(SignManager::to_query(params).as_bytes());
+    mac
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_storage_memory::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_sign_and_verify_once() {
+        let storage = Arc::new(MemoryStorage::new());
+        let signer = SignManager::new(storage.clone(), "s2s-secret");
+        let params = SignManager::sign([("user_id", "1001"), ("note", "a&b=c d")], "s2s-secret");
+        let query = SignManager::to_query(&params);
+
+        signer.verify_query(&query).await.unwrap();
+        assert!(matches!(signer.verify_query(&query).await, Err(SaTokenError::NonceAlreadyUsed)));
+        // 已使用的 nonce 带有过期时间 | A consumed nonce is stored with a TTL
+        let nonce_key = sign_nonce_key(&params[SIGN_NONCE_PARAM]);
+        assert!(storage.ttl(&nonce_key).await.unwrap().remaining().is_some());
+
+        // 篡改参数或使用其他密钥签名都会被拒绝
+        let mut tampered = SignManager::sign([("user_id", "1001")], "s2s-secret");
+        tampered.insert("user_id".to_string(), "1".to_string());
+        assert!(matches!(signer.verify(&tampered).await, Err(SaTokenError::InvalidSign(_))));
+        let foreign = SignManager::sign([("user_id", "1001")], "other-secret");
+        assert!(matches!(signer.verify(&foreign).await, Err(SaTokenError::InvalidSign(_))));
+        assert!(matches!(signer.verify_query("user_id=1").await, Err(SaTokenError::InvalidSign(_))));
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_stale_timestamp() {
+        let signer = SignManager::new(Arc::new(MemoryStorage::new()), "s2s-secret").timestamp_window(60);
+        let params = SignManager::sign([("user_id", "1001")], "s2s-secret");
+
+        crate::time::advance(chrono::Duration::seconds(120));
+        let result = signer.verify(&params).await;
+        crate::time::reset();
+        assert!(matches!(result, Err(SaTokenError::InvalidNonceTimestamp)));
+    }
+}
@@ -0,0 +1,288 @@
+// Author: 金书记
+//
+//! Server-Sent Events 推送 | Server-Sent Events push
+//!
+//! ## English
+//!
+//! [`SsePusher`] is a [`MessagePusher`] for browsers that use `EventSource`
+//! instead of WebSocket. Each authenticated request subscribes and gets an
+//! [`SseSubscription`]; the framework handler turns its frames into the
+//! response stream.
+//!
+//! - Messages become `event: message` frames with the JSON `PushMessage` as data.
+//! - Every heartbeat interval a comment ping is sent and
+//!   `OnlineManager::update_activity` is called.
+//! - A kick-out notice is delivered, then the stream ends.
+//! - Dropping the subscription (client gone) marks the connection offline.
+//!
+//! ## 中文
+//!
+//! [`SsePusher`] 为使用 `EventSource` 而非 WebSocket 的浏览器提供 [`MessagePusher`]。
+//! 每个已认证的请求订阅后得到 [`SseSubscription`]，框架侧把其中的帧写入响应流。
+//!
+//! - 消息以 `event: message` 帧发送，data 为 `PushMessage` 的 JSON
+//! - 每个心跳间隔发送注释帧，并调用 `OnlineManager::update_activity`
+//! - 踢人通知送达后结束流
+//! - 订阅被丢弃（客户端断开）时自动标记下线
+//!
+//! ```rust,ignore
+//! let sse = Arc::new(SsePusher::new().with_online_manager(online.clone()));
+//! online.register_pusher(sse.clone()).await;
+//!
+//! let mut subscription = sse.subscribe(&login_id, &token, "web").await;
+//! while let Some(frame) = subscription.next().await {
+//!     body.write(frame.to_sse_string());
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+use tokio::time::{Instant, Interval};
+use crate::error::SaTokenError;
+use crate::online::{MessagePusher, MessageType, OnlineManager, OnlineUser, PushMessage};
+
+/// 默认心跳间隔 | Default heartbeat interval
+pub const DEFAULT_SSE_HEARTBEAT: Duration = Duration::from_secs(15);
+
+/// 推送消息使用的 SSE 事件名 | SSE event name of pushed messages
+pub const SSE_MESSAGE_EVENT: &str = "message";
+
+/// 写入响应流的帧 | Frame written to the response stream
+#[derive(Debug, Clone)]
+pub enum SseFrame {
+    /// 推送消息 | Push message
+    Message(PushMessage),
+    /// 心跳，已刷新在线活跃时间 | Heartbeat, online activity already refreshed
+    Heartbeat,
+}
+
+impl SseFrame {
+    /// 编码为 `text/event-stream` 文本 | Encode as `text/event-stream` text
+    pub fn to_sse_string(&self) -> String {
+        match self {
+            SseFrame::Message(message) => format!(
+                "event: {}\ndata: {}\n\n",
+                SSE_MESSAGE_EVENT,
+                serde_json::to_string(message).unwrap_or_default(),
+            ),
+            SseFrame::Heartbeat => ": ping\n\n".to_string(),
+        }
+    }
+}
+
+struct Subscriber {
+    id: u64,
+    sender: mpsc::UnboundedSender<PushMessage>,
+}
+
+type SubscriberMap = Arc<Mutex<HashMap<String, Vec<Subscriber>>>>;
+
+/// SSE 消息推送器 | SSE message pusher
+pub struct SsePusher {
+    /// login_id -> 订阅列表
+    subscribers: SubscriberMap,
+    online_manager: Option<Arc<OnlineManager>>,
+    heartbeat: Duration,
+    next_id: AtomicU64,
+}
+
+impl Default for SsePusher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SsePusher {
+    pub fn new() -> Self {
+        Self {
+            subscribers: Arc::new(Mutex::new(HashMap::new())),
+            online_manager: None,
+            heartbeat: DEFAULT_SSE_HEARTBEAT,
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// 关联在线用户管理器，订阅/断开时自动标记上线/下线，心跳时刷新活跃时间
+    pub fn with_online_manager(mut self, online_manager: Arc<OnlineManager>) -> Self {
+        self.online_manager = Some(online_manager);
+        self
+    }
+
+    /// 心跳间隔，默认 15 秒 | Heartbeat interval, 15 seconds by default
+    pub fn heartbeat_interval(mut self, interval: Duration) -> Self {
+        self.heartbeat = interval;
+        self
+    }
+
+    /// 为已认证的用户订阅推送 | Subscribe an authenticated user
+    pub async fn subscribe(
+        &self,
+        login_id: impl Into<String>,
+        token: impl Into<String>,
+        device: impl Into<String>,
+    ) -> SseSubscription {
+        let login_id = login_id.into();
+        let token = token.into();
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = mpsc::unbounded_channel();
+        lock(&self.subscribers).entry(login_id.clone()).or_default().push(Subscriber { id, sender });
+
+        if let Some(online) = &self.online_manager {
+            let now = crate::time::now();
+            online.mark_online(OnlineUser {
+                login_id: login_id.clone(),
+                token: token.clone(),
+                device: device.into(),
+                connect_time: now,
+                last_activity: now,
+                metadata: HashMap::new(),
+                login_ip: None,
+                user_agent: None,
+            }).await;
+        }
+
+        SseSubscription {
+            id,
+            login_id,
+            token,
+            receiver,
+            heartbeat: tokio::time::interval_at(Instant::now() + self.heartbeat, self.heartbeat),
+            subscribers: self.subscribers.clone(),
+            online_manager: self.online_manager.clone(),
+        }
+    }
+
+    /// 用户当前的订阅数 | Number of open subscriptions of a user
+    pub fn subscriber_count(&self, login_id: &str) -> usize {
+        lock(&self.subscribers).get(login_id).map(Vec::len).unwrap_or(0)
+    }
+}
+
+#[async_trait]
+impl MessagePusher for SsePusher {
+    async fn push(&self, login_id: &str, message: PushMessage) -> Result<(), SaTokenError> {
+        let mut subscribers = lock(&self.subscribers);
+        let kick_out = message.message_type == MessageType::KickOut;
+        if let Some(entries) = subscribers.get(login_id) {
+            for entry in entries {
+                let _ = entry.sender.send(message.clone());
+            }
+        }
+        // 丢弃发送端：订阅读完踢人通知后结束
+        if kick_out {
+            subscribers.remove(login_id);
+        }
+        Ok(())
+    }
+}
+
+/// 单个 SSE 连接的订阅 | Subscription of one SSE connection
+///
+/// 丢弃时注销订阅并标记下线 | Unsubscribes and marks offline when dropped
+pub struct SseSubscription {
+    id: u64,
+    login_id: String,
+    token: String,
+    receiver: mpsc::UnboundedReceiver<PushMessage>,
+    heartbeat: Interval,
+    subscribers: SubscriberMap,
+    online_manager: Option<Arc<OnlineManager>>,
+}
+
+impl SseSubscription {
+    pub fn login_id(&self) -> &str {
+        &self.login_id
+    }
+
+    /// 下一帧；推送消息或心跳，流结束（被踢下线）时返回 `None`
+    ///
+    /// Next frame: a message or a heartbeat; `None` once the stream ended (kicked out).
+    pub async fn next(&mut self) -> Option<SseFrame> {
+        tokio::select! {
+            message = self.receiver.recv() => message.map(SseFrame::Message),
+            _ = self.heartbeat.tick() => {
+                if let Some(online) = &self.online_manager {
+                    online.update_activity(&self.login_id, &self.token).await;
+                }
+                Some(SseFrame::Heartbeat)
+            }
+        }
+    }
+}
+
+impl Drop for SseSubscription {
+    fn drop(&mut self) {
+        {
+            let mut subscribers = lock(&self.subscribers);
+            if let Some(entries) = subscribers.get_mut(&self.login_id) {
+                entries.retain(|entry| entry.id != self.id);
+                if entries.is_empty() {
+                    subscribers.remove(&self.login_id);
+                }
+            }
+        }
+
+        let Some(online) = self.online_manager.clone() else {
+            return;
+        };
+        let (login_id, token) = (self.login_id.clone(), self.token.clone());
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(async move { online.mark_offline(&login_id, &token).await });
+        }
+    }
+}
+
+fn lock(subscribers: &SubscriberMap) -> std::sync::MutexGuard<'_, HashMap<String, Vec<Subscriber>>> {
+    subscribers.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_push_then_kick_out_ends_stream() {
+        let online = Arc::new(OnlineManager::new());
+        let sse = Arc::new(SsePusher::new().with_online_manager(online.clone()));
+        online.register_pusher(sse.clone()).await;
+
+        let mut subscription = sse.subscribe("u1", "t1", "web").await;
+        assert!(online.is_online("u1").await);
+
+        online.push_to_user("u1", "hello".to_string()).await.unwrap();
+        online.kick_out_notify("u1", "kicked".to_string()).await.unwrap();
+
+        match subscription.next().await {
+            Some(SseFrame::Message(message)) => assert_eq!(message.content, "hello"),
+            other => panic!("unexpected {:?}", other),
+        }
+        match subscription.next().await {
+            Some(frame @ SseFrame::Message(_)) => assert!(frame.to_sse_string().contains("\"kick_out\"")),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(subscription.next().await.is_none());
+        assert_eq!(sse.subscriber_count("u1"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_and_offline_on_drop() {
+        let online = Arc::new(OnlineManager::new());
+        let sse = SsePusher::new()
+            .with_online_manager(online.clone())
+            .heartbeat_interval(Duration::from_millis(10));
+
+        let mut subscription = sse.subscribe("u2", "t2", "web").await;
+        let connected = online.get_user_sessions("u2").await[0].last_activity;
+        assert!(matches!(subscription.next().await, Some(SseFrame::Heartbeat)));
+        assert!(online.get_user_sessions("u2").await[0].last_activity >= connected);
+
+        drop(subscription);
+        assert_eq!(sse.subscriber_count("u2"), 0);
+        tokio::task::yield_now().await;
+        assert!(!online.is_online("u2").await);
+    }
+}
@@ -139,8 +139,9 @@ use std::sync::Arc;
 use std::collections::HashMap;
 use chrono::{DateTime, Utc, Duration as ChronoDuration};
 use serde::{Serialize, Deserialize};
+use async_trait::async_trait;
 use tokio::sync::RwLock;
-use crate::{SaTokenError, SaTokenResult, SaTokenManager};
+use crate::{SaTokenError, SaTokenResult, SaTokenManager, TokenValue};
 
 /// SSO 票据结构 | SSO Ticket Structure
 ///
@@ -170,7 +171,7 @@ impl SsoTicket {
     /// * `service` - 目标服务 URL | Target service URL
     /// * `timeout_seconds` - 票据有效期（秒）| Ticket validity period (seconds)
     pub fn new(login_id: String, service: String, timeout_seconds: i64) -> Self {
-        let now = Utc::now();
+        let now = crate::time::now();
         Self {
             ticket_id: uuid::Uuid::new_v4().to_string(),
             service,
@@ -183,7 +184,7 @@ impl SsoTicket {
 
     /// 检查票据是否过期 | Check if ticket is expired
     pub fn is_expired(&self) -> bool {
-        Utc::now() > self.expire_time
+        crate::time::now() > self.expire_time
     }
 
     /// 检查票据是否有效（未使用且未过期）| Check if ticket is valid (not used and not expired)
@@ -211,7 +212,7 @@ pub struct SsoSession {
 impl SsoSession {
     /// 创建新会话 | Create a new session
     pub fn new(login_id: String) -> Self {
-        let now = Utc::now();
+        let now = crate::time::now();
         Self {
             login_id,
             clients: Vec::new(),
@@ -228,16 +229,67 @@ impl SsoSession {
         if !self.clients.contains(&service) {
             self.clients.push(service);
         }
-        self.last_active_time = Utc::now();
+        self.last_active_time = crate::time::now();
     }
 
     /// 从会话中移除客户端 | Remove client from session
     pub fn remove_client(&mut self, service: &str) {
         self.clients.retain(|c| c != service);
-        self.last_active_time = Utc::now();
+        self.last_active_time = crate::time::now();
     }
 }
 
+/// 发现文档路径 | Well-known path of the discovery document
+pub const SSO_DISCOVERY_PATH: &str = "/.well-known/sso-configuration";
+
+/// 票据模式 | Ticket mode
+pub const SSO_MODE_TICKET: &str = "ticket";
+
+/// 认证接口路径：已登录时签发票据并重定向回 `service` | Auth endpoint: issues a ticket and redirects back to `service` when logged in
+pub const SSO_AUTH_PATH: &str = "/sso/auth";
+
+/// 登录接口路径：校验账号密码并登录 SSO 服务端 | Login endpoint: checks credentials and signs in to the SSO Server
+pub const SSO_DO_LOGIN_PATH: &str = "/sso/doLogin";
+
+/// 票据校验接口路径，供客户端应用调用 | Ticket check endpoint, called by client applications
+pub const SSO_CHECK_TICKET_PATH: &str = "/sso/checkTicket";
+
+/// 统一登出接口路径 | Unified logout endpoint
+pub const SSO_SIGNOUT_PATH: &str = "/sso/signout";
+
+/// SSO 公钥 | SSO Public Key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SsoPublicKey {
+    /// 密钥 ID | Key ID
+    pub kid: String,
+    /// 签名算法（如 RS256）| Signing algorithm (e.g. RS256)
+    pub alg: String,
+    /// PEM 格式公钥 | Public key in PEM format
+    pub pem: String,
+}
+
+/// SSO 发现文档 | SSO Discovery Document
+///
+/// 由 [`SsoServer::discovery_document`] 生成，客户端据此自动配置，避免两端手工复制配置
+/// Generated by [`SsoServer::discovery_document`]; clients configure themselves from it
+/// instead of copying settings by hand
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SsoDiscoveryDocument {
+    /// 服务端标识（对外地址）| Server identifier (public base URL)
+    pub issuer: String,
+    /// 登录地址 | Login endpoint
+    pub login_endpoint: String,
+    /// 登出地址 | Logout endpoint
+    pub logout_endpoint: String,
+    /// 支持的模式 | Supported modes
+    pub supported_modes: Vec<String>,
+    /// 票据有效期（秒）| Ticket timeout in seconds
+    pub ticket_timeout: i64,
+    /// 公钥列表 | Public keys
+    #[serde(default)]
+    pub public_keys: Vec<SsoPublicKey>,
+}
+
 /// SSO 服务端 | SSO Server
 ///
 /// 中央认证服务，负责票据生成、验证和会话管理
@@ -247,6 +299,7 @@ pub struct SsoServer {
     tickets: Arc<RwLock<HashMap<String, SsoTicket>>>,
     sessions: Arc<RwLock<HashMap<String, SsoSession>>>,
     ticket_timeout: i64,
+    public_keys: Vec<SsoPublicKey>,
 }
 
 impl SsoServer {
@@ -260,6 +313,7 @@ impl SsoServer {
             tickets: Arc::new(RwLock::new(HashMap::new())),
             sessions: Arc::new(RwLock::new(HashMap::new())),
             ticket_timeout: 300, // 默认 5 分钟 | Default 5 minutes
+            public_keys: Vec::new(),
         }
     }
 
@@ -272,6 +326,36 @@ impl SsoServer {
         self
     }
 
+    /// 发布公钥（出现在发现文档中）| Publish a public key in the discovery document
+    ///
+    /// # 参数 | Parameters
+    /// * `key` - 公钥信息 | Public key
+    pub fn with_public_key(mut self, key: SsoPublicKey) -> Self {
+        self.public_keys.push(key);
+        self
+    }
+
+    /// 生成发现文档 | Generate the discovery document
+    ///
+    /// 通常挂载在 `{base_url}/.well-known/sso-configuration`，客户端通过
+    /// [`SsoClient::from_discovery`] 自动配置。
+    /// Usually served at `{base_url}/.well-known/sso-configuration` so clients can
+    /// configure themselves with [`SsoClient::from_discovery`].
+    ///
+    /// # 参数 | Parameters
+    /// * `base_url` - SSO 服务端对外地址 | Public base URL of the SSO Server
+    pub fn discovery_document(&self, base_url: &str) -> SsoDiscoveryDocument {
+        let base_url = base_url.trim_end_matches('/');
+        SsoDiscoveryDocument {
+            issuer: base_url.to_string(),
+            login_endpoint: base_url.to_string(),
+            logout_endpoint: format!("{}/logout", base_url),
+            supported_modes: vec![SSO_MODE_TICKET.to_string()],
+            ticket_timeout: self.ticket_timeout,
+            public_keys: self.public_keys.clone(),
+        }
+    }
+
     /// 检查用户是否已登录 | Check if user is logged in
     ///
     /// 通过检查 SSO 会话是否存在来判断
@@ -358,6 +442,34 @@ impl SsoServer {
         Ok(login_id)
     }
 
+    /// 登录 SSO 服务端 | Sign in to the SSO Server
+    ///
+    /// 创建 SSO 类型的 Token 与会话，不签发票据；之后访问认证接口即可直接获得票据。
+    /// Creates an SSO token and session without a ticket; later visits to the
+    /// auth endpoint get a ticket straight away.
+    ///
+    /// # 参数 | Parameters
+    /// * `login_id` - 用户登录 ID | User login ID
+    ///
+    /// # 返回 | Returns
+    /// SSO 服务端的 Token | Token for the SSO Server
+    pub async fn sign_in(&self, login_id: &str) -> SaTokenResult<TokenValue> {
+        let token = self.manager.login_with_options(
+            login_id,
+            Some("sso".to_string()),
+            None,
+            Some(serde_json::json!({ "sso_mode": true })),
+            None,
+            None,
+        ).await?;
+
+        let mut sessions = self.sessions.write().await;
+        sessions.entry(login_id.to_string())
+            .or_insert_with(|| SsoSession::new(login_id.to_string()));
+
+        Ok(token)
+    }
+
     /// 用户登录 | User login
     ///
     /// 完整的登录流程：创建 Token、会话和票据
@@ -486,6 +598,10 @@ pub struct SsoClient {
     server_url: String,
     /// 当前服务 URL | Current service URL
     service_url: String,
+    /// SSO 服务端登出 URL | SSO Server logout URL
+    logout_url: String,
+    /// 用于配置客户端的发现文档 | Discovery document the client was configured from
+    discovery: Option<SsoDiscoveryDocument>,
     /// 登出回调函数 | Logout callback function
     logout_callback: Option<Arc<dyn Fn(&str) -> bool + Send + Sync>>,
 }
@@ -502,14 +618,74 @@ impl SsoClient {
         server_url: String,
         service_url: String,
     ) -> Self {
+        let logout_url = format!("{}/logout", server_url);
         Self {
             manager,
             server_url,
             service_url,
+            logout_url,
+            discovery: None,
             logout_callback: None,
         }
     }
 
+    /// 根据发现文档创建 SSO 客户端 | Create an SSO Client from a discovery document
+    ///
+    /// # 参数 | Parameters
+    /// * `manager` - SaTokenManager 实例 | SaTokenManager instance
+    /// * `document` - SSO 服务端的发现文档 | Discovery document of the SSO Server
+    /// * `service_url` - 当前服务 URL | Current service URL
+    ///
+    /// # 错误 | Errors
+    /// * `SsoDiscoveryFailed` - 服务端不支持票据模式 | Server does not support ticket mode
+    pub fn from_discovery_document(
+        manager: Arc<SaTokenManager>,
+        document: SsoDiscoveryDocument,
+        service_url: String,
+    ) -> SaTokenResult<Self> {
+        if !document.supported_modes.iter().any(|mode| mode == SSO_MODE_TICKET) {
+            return Err(SaTokenError::SsoDiscoveryFailed(format!(
+                "server {} does not support ticket mode", document.issuer
+            )));
+        }
+
+        Ok(Self {
+            manager,
+            server_url: document.login_endpoint.clone(),
+            service_url,
+            logout_url: document.logout_endpoint.clone(),
+            discovery: Some(document),
+            logout_callback: None,
+        })
+    }
+
+    /// 从发现地址拉取文档并创建 SSO 客户端 | Fetch the discovery document and create an SSO Client
+    ///
+    /// 需要开启 `sso-discovery` feature | Requires the `sso-discovery` feature
+    ///
+    /// # 参数 | Parameters
+    /// * `manager` - SaTokenManager 实例 | SaTokenManager instance
+    /// * `discovery_url` - 发现文档地址，如 `https://sso.example.com/.well-known/sso-configuration`
+    ///   | Discovery URL, e.g. `https://sso.example.com/.well-known/sso-configuration`
+    /// * `service_url` - 当前服务 URL | Current service URL
+    #[cfg(feature = "sso-discovery")]
+    pub async fn from_discovery(
+        manager: Arc<SaTokenManager>,
+        discovery_url: &str,
+        service_url: String,
+    ) -> SaTokenResult<Self> {
+        let fetch_error = |e: reqwest::Error| SaTokenError::SsoDiscoveryFailed(e.to_string());
+        let body = reqwest::get(discovery_url).await
+            .map_err(fetch_error)?
+            .error_for_status()
+            .map_err(fetch_error)?
+            .text().await
+            .map_err(fetch_error)?;
+        let document: SsoDiscoveryDocument = serde_json::from_str(&body)?;
+
+        Self::from_discovery_document(manager, document, service_url)
+    }
+
     /// 设置登出回调函数 | Set logout callback
     ///
     /// # 参数 | Parameters
@@ -537,7 +713,7 @@ impl SsoClient {
     /// SSO 服务端登出 URL，包含当前服务的回调地址
     /// SSO Server logout URL with current service callback
     pub fn get_logout_url(&self) -> String {
-        format!("{}/logout?service={}", self.server_url, urlencoding::encode(&self.service_url))
+        format!("{}?service={}", self.logout_url, urlencoding::encode(&self.service_url))
     }
 
     /// 检查本地是否已登录 | Check if locally logged in
@@ -632,6 +808,11 @@ impl SsoClient {
     pub fn service_url(&self) -> &str {
         &self.service_url
     }
+
+    /// 获取发现文档（通过发现文档创建时）| Get the discovery document, if configured from one
+    pub fn discovery(&self) -> Option<&SsoDiscoveryDocument> {
+        self.discovery.as_ref()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -699,10 +880,48 @@ impl SsoConfigBuilder {
     }
 }
 
+/// SSO 登录凭证校验 | Credential check for the SSO login endpoint
+///
+/// 由应用实现，`/sso/doLogin` 接口用它校验账号密码。
+/// Implemented by the application; `/sso/doLogin` uses it to check the
+/// submitted name and password.
+///
+/// ```rust,ignore
+/// struct UserTable;
+///
+/// #[async_trait]
+/// impl SsoCredentialValidator for UserTable {
+///     async fn validate(&self, name: &str, password: &str) -> SaTokenResult<Option<String>> {
+///         Ok((name == "admin" && password == "123456").then(|| "10001".to_string()))
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait SsoCredentialValidator: Send + Sync {
+    /// 校验账号密码 | Check a name and password
+    ///
+    /// # 返回 | Returns
+    /// - `Ok(Some(login_id))`: 校验通过 | Credentials accepted
+    /// - `Ok(None)`: 账号或密码错误 | Wrong name or password
+    async fn validate(&self, name: &str, password: &str) -> SaTokenResult<Option<String>>;
+}
+
+/// `/sso/doLogin` 的结果 | Result of `/sso/doLogin`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SsoLoginResult {
+    /// SSO 服务端的 Token | Token for the SSO Server
+    pub token: TokenValue,
+    /// 登录 ID | Login ID
+    pub login_id: String,
+    /// 带票据的回跳地址（请求中带了 `service` 时）| Redirect back to `service` with a ticket, when one was given
+    pub redirect: Option<String>,
+}
+
 pub struct SsoManager {
     server: Option<Arc<SsoServer>>,
     client: Option<Arc<SsoClient>>,
     config: SsoConfig,
+    credential_validator: Option<Arc<dyn SsoCredentialValidator>>,
 }
 
 impl SsoManager {
@@ -711,9 +930,16 @@ impl SsoManager {
             server: None,
             client: None,
             config,
+            credential_validator: None,
         }
     }
 
+    /// 设置登录凭证校验 | Set the credential check used by `/sso/doLogin`
+    pub fn with_credential_validator(mut self, validator: Arc<dyn SsoCredentialValidator>) -> Self {
+        self.credential_validator = Some(validator);
+        self
+    }
+
     pub fn with_server(mut self, server: Arc<SsoServer>) -> Self {
         self.server = Some(server);
         self
@@ -744,5 +970,171 @@ impl SsoManager {
         self.config.allowed_origins.contains(&"*".to_string()) ||
         self.config.allowed_origins.contains(&origin.to_string())
     }
+
+    /// 校验回跳地址是否在允许的来源内 | Check that a redirect target is an allowed origin
+    ///
+    /// 只比较 `scheme://host[:port]` 部分，防止认证接口被用作开放重定向。
+    /// Compares the `scheme://host[:port]` part only, so the auth endpoint
+    /// cannot be used as an open redirect.
+    ///
+    /// # 错误 | Errors
+    /// * `ServiceMismatch` - 地址不合法或来源不在白名单中 | Malformed URL or origin not allowed
+    pub fn check_service(&self, service: &str) -> SaTokenResult<()> {
+        match service_origin(service) {
+            Some(origin) if self.is_allowed_origin(origin) => Ok(()),
+            _ => Err(SaTokenError::ServiceMismatch),
+        }
+    }
+
+    /// 认证：为已登录用户签发票据 | Auth: issue a ticket for a logged-in user
+    ///
+    /// # 返回 | Returns
+    /// 带 `ticket` 参数的回跳地址 | The `service` URL with a `ticket` parameter
+    pub async fn auth(&self, login_id: &str, service: &str) -> SaTokenResult<String> {
+        self.check_service(service)?;
+        let ticket = self.require_server()?.create_ticket(login_id.to_string(), service.to_string()).await?;
+        Ok(redirect_with_ticket(service, &ticket.ticket_id))
+    }
+
+    /// 账号密码登录 | Log in with a name and password
+    ///
+    /// 校验通过后登录 SSO 服务端；带 `service` 时同时签发票据并返回回跳地址。
+    /// Signs in to the SSO Server once the credentials pass; with a `service`,
+    /// also issues a ticket and returns the redirect.
+    ///
+    /// # 错误 | Errors
+    /// * `NotLogin` - 账号或密码错误 | Wrong name or password
+    /// * `ConfigError` - 未配置服务端或凭证校验 | No server or credential validator configured
+    pub async fn do_login(&self, name: &str, password: &str, service: Option<&str>) -> SaTokenResult<SsoLoginResult> {
+        if let Some(service) = service {
+            self.check_service(service)?;
+        }
+        let server = self.require_server()?;
+        let validator = self.credential_validator.as_ref()
+            .ok_or_else(|| SaTokenError::ConfigError("SSO credential validator is not configured".to_string()))?;
+        let login_id = validator.validate(name, password).await?.ok_or(SaTokenError::NotLogin)?;
+
+        let token = server.sign_in(&login_id).await?;
+        let redirect = match service {
+            Some(service) => Some(self.auth(&login_id, service).await?),
+            None => None,
+        };
+        Ok(SsoLoginResult { token, login_id, redirect })
+    }
+
+    /// 校验票据，返回登录 ID | Check a ticket and return the login ID
+    pub async fn check_ticket(&self, ticket: &str, service: &str) -> SaTokenResult<String> {
+        self.require_server()?.validate_ticket(ticket, service).await
+    }
+
+    /// 统一登出，返回需要通知的客户端 | Unified logout; returns the clients to notify
+    pub async fn signout(&self, login_id: &str) -> SaTokenResult<Vec<String>> {
+        self.require_server()?.logout(login_id).await
+    }
+
+    fn require_server(&self) -> SaTokenResult<&Arc<SsoServer>> {
+        self.server.as_ref()
+            .ok_or_else(|| SaTokenError::ConfigError("SSO server is not configured".to_string()))
+    }
+}
+
+/// 取 URL 的 `scheme://host[:port]` 部分 | The `scheme://host[:port]` part of a URL
+fn service_origin(url: &str) -> Option<&str> {
+    let (scheme, rest) = url.split_once("://")?;
+    if scheme.is_empty() || !scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c)) {
+        return None;
+    }
+    let authority_len = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    if authority_len == 0 || rest[..authority_len].contains('@') {
+        return None;
+    }
+    Some(&url[..scheme.len() + 3 + authority_len])
+}
+
+/// 在回跳地址上追加 `ticket` 参数 | Append the `ticket` parameter to a redirect URL
+fn redirect_with_ticket(service: &str, ticket: &str) -> String {
+    let (url, fragment) = service.split_once('#').map_or((service, None), |(url, fragment)| (url, Some(fragment)));
+    let separator = if url.contains('?') { '&' } else { '?' };
+    let mut redirect = format!("{}{}ticket={}", url, separator, urlencoding::encode(ticket));
+    if let Some(fragment) = fragment {
+        redirect.push('#');
+        redirect.push_str(fragment);
+    }
+    redirect
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_storage_memory::MemoryStorage;
+    use crate::SaTokenConfig;
+
+    fn manager() -> Arc<SaTokenManager> {
+        Arc::new(SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default()))
+    }
+
+    #[test]
+    fn test_client_from_discovery_document() {
+        let server = SsoServer::new(manager())
+            .with_ticket_timeout(120)
+            .with_public_key(SsoPublicKey {
+                kid: "k1".to_string(),
+                alg: "RS256".to_string(),
+                pem: "-----BEGIN PUBLIC KEY-----".to_string(),
+            });
+        let json = serde_json::to_string(&server.discovery_document("https://sso.example.com/")).unwrap();
+        let document: SsoDiscoveryDocument = serde_json::from_str(&json).unwrap();
+
+        let client = SsoClient::from_discovery_document(manager(), document, "http://app1".to_string()).unwrap();
+        assert_eq!(client.get_login_url(), "https://sso.example.com?service=http%3A%2F%2Fapp1");
+        assert_eq!(client.get_logout_url(), "https://sso.example.com/logout?service=http%3A%2F%2Fapp1");
+        assert_eq!(client.discovery().unwrap().public_keys[0].kid, "k1");
+        assert_eq!(client.discovery().unwrap().ticket_timeout, 120);
+    }
+
+    struct FixedUser;
+
+    #[async_trait]
+    impl SsoCredentialValidator for FixedUser {
+        async fn validate(&self, name: &str, password: &str) -> SaTokenResult<Option<String>> {
+            Ok((name == "admin" && password == "123456").then(|| "10001".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_manager_login_ticket_signout() {
+        let config = SsoConfig::builder().add_allowed_origin("http://app1.com".to_string()).build();
+        let sso = SsoManager::new(config)
+            .with_server(Arc::new(SsoServer::new(manager())))
+            .with_credential_validator(Arc::new(FixedUser));
+
+        assert!(matches!(sso.do_login("admin", "wrong", None).await, Err(SaTokenError::NotLogin)));
+        assert!(matches!(
+            sso.do_login("admin", "123456", Some("http://evil.com/cb")).await,
+            Err(SaTokenError::ServiceMismatch)
+        ));
+        assert!(matches!(sso.check_service("http://app1.com@evil.com/"), Err(SaTokenError::ServiceMismatch)));
+
+        let result = sso.do_login("admin", "123456", Some("http://app1.com/cb?from=home")).await.unwrap();
+        assert_eq!(result.login_id, "10001");
+        let redirect = result.redirect.unwrap();
+        let ticket = redirect.split("ticket=").nth(1).unwrap();
+        assert!(redirect.starts_with("http://app1.com/cb?from=home&ticket="));
+
+        assert!(matches!(sso.check_ticket(ticket, "http://app1.com/other").await, Err(SaTokenError::ServiceMismatch)));
+        assert_eq!(sso.check_ticket(ticket, "http://app1.com/cb?from=home").await.unwrap(), "10001");
+        assert!(sso.check_ticket(ticket, "http://app1.com/cb?from=home").await.is_err());
+
+        let redirect = sso.auth("10001", "http://app1.com/").await.unwrap();
+        assert!(redirect.starts_with("http://app1.com/?ticket="));
+        assert_eq!(sso.signout("10001").await.unwrap(), vec!["http://app1.com/cb?from=home".to_string(), "http://app1.com/".to_string()]);
+    }
+
+    #[test]
+    fn test_discovery_requires_ticket_mode() {
+        let mut document = SsoServer::new(manager()).discovery_document("https://sso.example.com");
+        document.supported_modes.clear();
+        let result = SsoClient::from_discovery_document(manager(), document, "http://app1".to_string());
+        assert!(matches!(result, Err(SaTokenError::SsoDiscoveryFailed(_))));
+    }
+}
@@ -0,0 +1,340 @@
+// Author: 金书记
+//
+//! 框架插件共用的状态 | Application state shared by the framework plugins
+//!
+//! 各插件曾各自实现 `SaTokenState` / `SaTokenStateBuilder`，可用选项与 `StpUtil`
+//! 的初始化方式并不一致。现在所有插件都重新导出这里的实现：构建器覆盖全部常用选项，
+//! 其余选项可通过 [`SaTokenStateBuilder::configure`] 直接设置 `SaTokenConfigBuilder`。
+//!
+//! Each plugin used to carry its own `SaTokenState` / `SaTokenStateBuilder`
+//! with diverging options and `StpUtil` initialization. All plugins now
+//! re-export this one. The builder covers the common options; anything else
+//! is reachable through [`SaTokenStateBuilder::configure`].
+//!
+//! ```rust,ignore
+//! let state = SaTokenState::builder()
+//!     .storage(Arc::new(MemoryStorage::new()))
+//!     .timeout(7200)
+//!     .configure(|config| config.token_bind_ip(true))
+//!     .build();
+//! ```
+
+use std::sync::Arc;
+use sa_token_adapter::storage::SaStorage;
+use crate::config::{LoginEviction, SaTokenConfig, SaTokenConfigBuilder, TokenStyle};
+use crate::event::SaTokenListener;
+use crate::online::OnlineManager;
+use crate::permission::{AuthzNamingRules, PermissionChecker, RoleChecker};
+use crate::render::ErrorRenderer;
+use crate::{JsonFieldCase, SaTokenManager, StpUtil};
+
+/// 框架插件的应用状态 | Application state of the framework plugins
+#[derive(Clone)]
+pub struct SaTokenState {
+    pub manager: Arc<SaTokenManager>,
+}
+
+impl SaTokenState {
+    /// 从存储和配置创建状态（不初始化 `StpUtil`）| Create state from storage and config, without initializing `StpUtil`
+    #[deprecated(since = "0.1.12", note = "use `SaTokenState::builder()` or `SaTokenState::from_manager()`, see `sa_token_core::compat::v01`")]
+    pub fn new(storage: Arc<dyn SaStorage>, config: SaTokenConfig) -> Self {
+        Self {
+            manager: Arc::new(SaTokenManager::new(storage, config)),
+        }
+    }
+
+    /// 从存储和配置创建状态（不初始化 `StpUtil`）| Create state from storage and config, without initializing `StpUtil`
+    #[deprecated(since = "0.1.12", note = "use `SaTokenState::builder()` or `SaTokenState::from_manager()`, see `sa_token_core::compat::v01`")]
+    pub fn from_storage_and_config(storage: Arc<dyn SaStorage>, config: SaTokenConfig) -> Self {
+        Self {
+            manager: Arc::new(SaTokenManager::new(storage, config)),
+        }
+    }
+
+    /// 从 SaTokenManager 创建状态，并初始化全局 `StpUtil` | Create state from a manager and initialize the global `StpUtil`
+    pub fn from_manager(manager: SaTokenManager) -> Self {
+        StpUtil::init_manager(manager.clone());
+
+        Self {
+            manager: Arc::new(manager),
+        }
+    }
+
+    /// 使用构建器创建状态 | Create state with the builder
+    pub fn builder() -> SaTokenStateBuilder {
+        SaTokenStateBuilder::new()
+    }
+}
+
+/// SaTokenState 构建器 | SaTokenState builder
+#[derive(Default)]
+pub struct SaTokenStateBuilder {
+    config_builder: SaTokenConfigBuilder,
+}
+
+impl From<SaTokenConfigBuilder> for SaTokenStateBuilder {
+    fn from(config_builder: SaTokenConfigBuilder) -> Self {
+        Self { config_builder }
+    }
+}
+
+impl SaTokenStateBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 直接设置底层的 `SaTokenConfigBuilder`，用于构建器未列出的选项
+    ///
+    /// Adjusts the underlying `SaTokenConfigBuilder`, for options the state builder does not list.
+    pub fn configure(mut self, f: impl FnOnce(SaTokenConfigBuilder) -> SaTokenConfigBuilder) -> Self {
+        self.config_builder = f(self.config_builder);
+        self
+    }
+
+    pub fn storage(mut self, storage: Arc<dyn SaStorage>) -> Self {
+        self.config_builder = self.config_builder.storage(storage);
+        self
+    }
+
+    pub fn token_name(mut self, name: impl Into<String>) -> Self {
+        self.config_builder = self.config_builder.token_name(name);
+        self
+    }
+
+    pub fn timeout(mut self, timeout: i64) -> Self {
+        self.config_builder = self.config_builder.timeout(timeout);
+        self
+    }
+
+    /// 设置"记住我"登录的 token 有效期（秒）
+    pub fn remember_timeout(mut self, timeout: i64) -> Self {
+        self.config_builder = self.config_builder.remember_timeout(timeout);
+        self
+    }
+
+    pub fn active_timeout(mut self, timeout: i64) -> Self {
+        self.config_builder = self.config_builder.active_timeout(timeout);
+        self
+    }
+
+    /// 设置是否开启自动续签
+    pub fn auto_renew(mut self, enabled: bool) -> Self {
+        self.config_builder = self.config_builder.auto_renew(enabled);
+        self
+    }
+
+    pub fn is_concurrent(mut self, concurrent: bool) -> Self {
+        self.config_builder = self.config_builder.is_concurrent(concurrent);
+        self
+    }
+
+    pub fn is_share(mut self, share: bool) -> Self {
+        self.config_builder = self.config_builder.is_share(share);
+        self
+    }
+
+    /// 设置同一账号最多同时在线的 token 数，-1 表示不限制
+    pub fn max_login_count(mut self, count: i64) -> Self {
+        self.config_builder = self.config_builder.max_login_count(count);
+        self
+    }
+
+    /// 设置超出 `max_login_count` 时的淘汰策略
+    pub fn login_eviction(mut self, eviction: LoginEviction) -> Self {
+        self.config_builder = self.config_builder.login_eviction(eviction);
+        self
+    }
+
+    pub fn token_style(mut self, style: TokenStyle) -> Self {
+        self.config_builder = self.config_builder.token_style(style);
+        self
+    }
+
+    /// 设置是否输出操作日志
+    pub fn is_log(mut self, is_log: bool) -> Self {
+        self.config_builder = self.config_builder.is_log(is_log);
+        self
+    }
+
+    /// 设置认证失败日志限流
+    pub fn auth_failure_log_limit(mut self, limit: u32, window_secs: i64) -> Self {
+        self.config_builder = self.config_builder.auth_failure_log_limit(limit, window_secs);
+        self
+    }
+
+    pub fn token_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.config_builder = self.config_builder.token_prefix(prefix);
+        self
+    }
+
+    /// 设置请求头中接受的认证方案，如 `["Bearer", "Token"]`
+    pub fn token_schemes<I, S>(mut self, schemes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.config_builder = self.config_builder.token_schemes(schemes);
+        self
+    }
+
+    pub fn jwt_secret_key(mut self, key: impl Into<String>) -> Self {
+        self.config_builder = self.config_builder.jwt_secret_key(key);
+        self
+    }
+
+    /// 添加轮换用的 JWT 密钥，自 `active_from`（Unix 秒）起用于签名
+    pub fn jwt_rotation_secret(mut self, secret: impl Into<String>, active_from: i64) -> Self {
+        self.config_builder = self.config_builder.jwt_rotation_secret(secret, active_from);
+        self
+    }
+
+    /// 设置 JWT 算法（HS256 / RS256 / ES256 / EdDSA 等）
+    pub fn jwt_algorithm(mut self, algorithm: impl Into<String>) -> Self {
+        self.config_builder = self.config_builder.jwt_algorithm(algorithm);
+        self
+    }
+
+    /// 设置 JWT 私钥（PEM 文本）
+    pub fn jwt_private_key_pem(mut self, pem: impl Into<String>) -> Self {
+        self.config_builder = self.config_builder.jwt_private_key_pem(pem);
+        self
+    }
+
+    /// 设置 JWT 公钥（PEM 文本）
+    pub fn jwt_public_key_pem(mut self, pem: impl Into<String>) -> Self {
+        self.config_builder = self.config_builder.jwt_public_key_pem(pem);
+        self
+    }
+
+    /// 设置 JWT 私钥文件路径
+    pub fn jwt_private_key_path(mut self, path: impl Into<String>) -> Self {
+        self.config_builder = self.config_builder.jwt_private_key_path(path);
+        self
+    }
+
+    /// 设置 JWT 公钥文件路径
+    pub fn jwt_public_key_path(mut self, path: impl Into<String>) -> Self {
+        self.config_builder = self.config_builder.jwt_public_key_path(path);
+        self
+    }
+
+    /// 开启开发模式免登录（仅限本地开发）
+    pub fn dev_bypass(mut self, enabled: bool) -> Self {
+        self.config_builder = self.config_builder.dev_bypass(enabled);
+        self
+    }
+
+    /// 设置开发模式免登录的 login_id
+    pub fn dev_bypass_login_id(mut self, login_id: impl Into<String>) -> Self {
+        self.config_builder = self.config_builder.dev_bypass_login_id(login_id);
+        self
+    }
+
+    /// 设置是否在登录后写入 token Cookie
+    pub fn write_cookie(mut self, enabled: bool) -> Self {
+        self.config_builder = self.config_builder.write_cookie(enabled);
+        self
+    }
+
+    /// 设置响应 JSON 的字段命名风格（snake_case / camelCase）
+    pub fn json_field_case(mut self, case: JsonFieldCase) -> Self {
+        self.config_builder = self.config_builder.json_field_case(case);
+        self
+    }
+
+    /// 登录时预取权限与角色
+    pub fn prefetch_authz(mut self, enabled: bool) -> Self {
+        self.config_builder = self.config_builder.prefetch_authz(enabled);
+        self
+    }
+
+    /// 设置踢人下线后的宽限期（秒）
+    pub fn kick_out_grace_period(mut self, seconds: i64) -> Self {
+        self.config_builder = self.config_builder.kick_out_grace_period(seconds);
+        self
+    }
+
+    /// 设置当前实例所在可用区，配合 `RoutingStorage` 使用
+    pub fn locality_hint(mut self, zone: impl Into<String>) -> Self {
+        self.config_builder = self.config_builder.locality_hint(zone);
+        self
+    }
+
+    /// 设置权限/角色命名规则
+    pub fn authz_naming_rules(mut self, rules: AuthzNamingRules) -> Self {
+        self.config_builder = self.config_builder.authz_naming_rules(rules);
+        self
+    }
+
+    /// 设置在线用户管理器
+    pub fn online_manager(mut self, manager: Arc<OnlineManager>) -> Self {
+        self.config_builder = self.config_builder.online_manager(manager);
+        self
+    }
+
+    /// 设置权限检查器，优先于内存中的权限映射
+    pub fn permission_checker(mut self, checker: Arc<dyn PermissionChecker>) -> Self {
+        self.config_builder = self.config_builder.permission_checker(checker);
+        self
+    }
+
+    /// 设置角色检查器，优先于内存中的角色映射
+    pub fn role_checker(mut self, checker: Arc<dyn RoleChecker>) -> Self {
+        self.config_builder = self.config_builder.role_checker(checker);
+        self
+    }
+
+    /// 设置拒绝响应渲染器（状态码、响应头、响应体）
+    pub fn error_renderer(mut self, renderer: Arc<dyn ErrorRenderer>) -> Self {
+        self.config_builder = self.config_builder.error_renderer(renderer);
+        self
+    }
+
+    /// 注册事件监听器
+    pub fn register_listener(mut self, listener: Arc<dyn SaTokenListener>) -> Self {
+        self.config_builder = self.config_builder.register_listener(listener);
+        self
+    }
+
+    /// 注册事件监听器，同 `register_listener`
+    pub fn listener(self, listener: Arc<dyn SaTokenListener>) -> Self {
+        self.register_listener(listener)
+    }
+
+    /// 注册多个事件监听器
+    pub fn listeners(self, listeners: Vec<Arc<dyn SaTokenListener>>) -> Self {
+        listeners.into_iter().fold(self, Self::register_listener)
+    }
+
+    /// 构建状态，并初始化全局 `StpUtil`；未设置存储时 panic
+    ///
+    /// Builds the state and initializes the global `StpUtil`; panics without a storage.
+    pub fn build(self) -> SaTokenState {
+        // config_builder.build() 已经初始化了 StpUtil
+        SaTokenState {
+            manager: Arc::new(self.config_builder.build()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_applies_options_and_configure() {
+        // build() 会初始化全局 StpUtil，这里只检查生成的配置
+        let config = SaTokenState::builder()
+            .token_name("x-token")
+            .timeout(600)
+            .token_schemes(["Bearer", "Token"])
+            .configure(|config| config.token_bind_ip(true))
+            .config_builder
+            .build_config();
+
+        assert_eq!(config.token_name, "x-token");
+        assert_eq!(config.timeout, 600);
+        assert_eq!(config.token_schemes, ["Bearer", "Token"]);
+        assert!(config.token_bind_ip);
+    }
+}
@@ -0,0 +1,285 @@
+// Author: 金书记
+//
+//! Token 状态监听 | Token Status Watching
+//!
+//! 前端单页应用可以长轮询当前 token 的状态，在被踢下线、被封禁、被顶替或即将过期时
+//! 立即收到通知，而不必等到下一次 API 调用失败才发现。
+//!
+//! SPAs can long-poll the status of their token and react as soon as it is
+//! kicked, banned, replaced or about to expire, instead of discovering it on
+//! the next failed API call.
+//!
+//! ```rust,ignore
+//! let report = manager.watch_token_status(&token, StatusWatchOptions::default()).await?;
+//! match report.status {
+//!     TokenStatus::Active => { /* 超时返回，重新发起轮询 | timed out, poll again */ }
+//!     TokenStatus::ExpiringSoon => { /* 刷新 token | refresh the token */ }
+//!     _ => { /* 跳转登录页 | back to the login page */ }
+//! }
+//! ```
+//!
+//! 管理器创建时会自动把 [`TokenStatusWatcher`] 注册到事件总线，状态变化来自
+//! `Logout`、`KickOut`、`Replaced`、`Banned` 事件；读取状态不会触发自动续签。
+//!
+//! The manager registers a [`TokenStatusWatcher`] on its event bus, fed by
+//! `Logout`, `KickOut`, `Replaced` and `Banned` events. Reading the status
+//! never triggers auto-renew.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Duration;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use crate::event::{SaTokenEvent, SaTokenEventType, SaTokenListener};
+
+/// 终止状态在内存中保留的时长（秒），供稍后的轮询读取
+const RECENT_STATUS_TTL: i64 = 600;
+
+/// 状态变化通知通道容量
+const CHANNEL_CAPACITY: usize = 256;
+
+/// Token 状态 | Token status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenStatus {
+    /// 正常 | Active
+    Active,
+    /// 即将过期 | Expires within the configured window
+    ExpiringSoon,
+    /// 已过期 | Expired
+    Expired,
+    /// 已登出 | Logged out
+    LoggedOut,
+    /// 被踢下线 | Kicked out
+    Kicked,
+    /// 被新登录顶替 | Replaced by a newer login
+    Replaced,
+    /// 账号被封禁 | Account banned
+    Banned,
+    /// token 不存在 | Unknown token
+    Invalid,
+}
+
+impl TokenStatus {
+    /// 是否为终止状态（token 已不可用）| Whether the token is no longer usable
+    pub fn is_terminal(&self) -> bool {
+        !matches!(self, TokenStatus::Active | TokenStatus::ExpiringSoon)
+    }
+
+    /// 登出之外的终止原因优先，避免踢人后紧随的登出事件覆盖原因
+    fn overrides(&self, existing: &TokenStatus) -> bool {
+        !(*self == TokenStatus::LoggedOut && existing.is_terminal())
+    }
+}
+
+/// 状态查询结果 | Status report
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenStatusReport {
+    pub status: TokenStatus,
+
+    /// 登录 ID，token 已不存在时为 None | Login ID, `None` once the token is gone
+    pub login_id: Option<String>,
+
+    /// 剩余有效期（秒），永不过期时为 None | Seconds left, `None` if it never expires
+    pub expires_in: Option<i64>,
+}
+
+impl TokenStatusReport {
+    pub(crate) fn terminal(status: TokenStatus) -> Self {
+        Self { status, login_id: None, expires_in: None }
+    }
+}
+
+/// 长轮询参数 | Long-poll options
+#[derive(Debug, Clone, Copy)]
+pub struct StatusWatchOptions {
+    /// 无变化时最长等待时间 | Maximum wait without a change
+    pub timeout: Duration,
+
+    /// 剩余有效期低于该值（秒）时视为即将过期 | Seconds left that count as expiring soon
+    pub expiring_within: i64,
+}
+
+impl Default for StatusWatchOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+            expiring_within: 300,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct StatusChange {
+    login_id: String,
+    token: String,
+    status: TokenStatus,
+}
+
+impl StatusChange {
+    /// 踢人、封禁作用于账号下所有 token，其余只作用于事件中的 token
+    fn affects(&self, token: &str, login_id: &str) -> bool {
+        match self.status {
+            TokenStatus::Kicked | TokenStatus::Banned => self.login_id == login_id || self.token == token,
+            _ => self.token == token,
+        }
+    }
+}
+
+/// Token 状态监听器 | Token status watcher
+pub struct TokenStatusWatcher {
+    sender: broadcast::Sender<StatusChange>,
+    /// 最近进入终止状态的 token | Tokens that recently became unusable
+    recent: RwLock<HashMap<String, (TokenStatus, DateTime<Utc>)>>,
+}
+
+impl Default for TokenStatusWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TokenStatusWatcher {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self {
+            sender,
+            recent: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// 记录状态变化并唤醒等待者 | Record a change and wake up waiters
+    fn record(&self, login_id: &str, token: &str, status: TokenStatus) {
+        if !token.is_empty() {
+            let now = crate::time::now();
+            let mut recent = self.recent.write().unwrap();
+            recent.retain(|_, (_, at)| (now - *at).num_seconds() < RECENT_STATUS_TTL);
+            if let Some((existing, _)) = recent.get(token)
+                && !status.overrides(existing)
+            {
+                return;
+            }
+            recent.insert(token.to_string(), (status, now));
+        }
+
+        let _ = self.sender.send(StatusChange {
+            login_id: login_id.to_string(),
+            token: token.to_string(),
+            status,
+        });
+    }
+
+    /// 最近记录的终止状态 | Recently recorded terminal status
+    pub fn recent_status(&self, token: &str) -> Option<TokenStatus> {
+        let recent = self.recent.read().unwrap();
+        recent.get(token)
+            .filter(|(_, at)| (crate::time::now() - *at).num_seconds() < RECENT_STATUS_TTL)
+            .map(|(status, _)| *status)
+    }
+
+    /// 订阅状态变化，需在读取当前状态之前订阅以免错过通知
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<StatusChange> {
+        self.sender.subscribe()
+    }
+
+    /// 等待影响该 token 的状态变化，超时返回 None
+    ///
+    /// Waits for a change affecting the token, `None` on timeout.
+    pub(crate) async fn wait_for_change(
+        &self,
+        mut receiver: broadcast::Receiver<StatusChange>,
+        token: &str,
+        login_id: &str,
+        timeout: Duration,
+    ) -> Option<TokenStatus> {
+        let deadline = tokio::time::sleep(timeout);
+        tokio::pin!(deadline);
+
+        loop {
+            tokio::select! {
+                _ = &mut deadline => return None,
+                change = receiver.recv() => match change {
+                    Ok(change) if change.affects(token, login_id) => return Some(change.status),
+                    Ok(_) => continue,
+                    // 丢失了部分通知，交给调用方重新读取状态
+                    Err(broadcast::error::RecvError::Lagged(_)) => return None,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                },
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl SaTokenListener for TokenStatusWatcher {
+    async fn on_event(&self, event: &SaTokenEvent) {
+        let status = match event.event_type {
+            SaTokenEventType::Logout => TokenStatus::LoggedOut,
+            SaTokenEventType::KickOut => TokenStatus::Kicked,
+            SaTokenEventType::Replaced => TokenStatus::Replaced,
+            SaTokenEventType::Banned => TokenStatus::Banned,
+            _ => return,
+        };
+        // 设备级踢人只影响事件中的 token，不按 login_id 匹配
+        let login_id = if event.device.is_some() { "" } else { event.login_id.as_str() };
+        self.record(login_id, &event.token, status);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use sa_token_storage_memory::MemoryStorage;
+    use crate::{SaTokenConfig, SaTokenManager};
+    use super::*;
+
+    fn new_manager() -> Arc<SaTokenManager> {
+        Arc::new(SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default()))
+    }
+
+    #[tokio::test]
+    async fn test_watch_wakes_up_on_kick_out() {
+        let manager = new_manager();
+        let token = manager.login("user_1").await.unwrap();
+
+        let watcher = {
+            let manager = manager.clone();
+            let token = token.clone();
+            tokio::spawn(async move {
+                let options = StatusWatchOptions { timeout: Duration::from_secs(5), ..Default::default() };
+                manager.watch_token_status(&token, options).await.unwrap()
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        manager.kick_out("user_1").await.unwrap();
+
+        let report = watcher.await.unwrap();
+        assert_eq!(report.status, TokenStatus::Kicked);
+        assert_eq!(report.login_id.as_deref(), Some("user_1"));
+
+        // 稍后的轮询仍能读到踢出原因，而不是被随后的登出事件覆盖
+        let report = manager.token_status(&token, 300).await.unwrap();
+        assert_eq!(report.status, TokenStatus::Kicked);
+    }
+
+    #[tokio::test]
+    async fn test_status_without_change() {
+        let manager = new_manager();
+        let token = manager.login("user_2").await.unwrap();
+
+        let options = StatusWatchOptions { timeout: Duration::from_millis(20), expiring_within: 60 };
+        let report = manager.watch_token_status(&token, options).await.unwrap();
+        assert_eq!(report.status, TokenStatus::Active);
+
+        // 默认有效期 30 天，窗口更大时立即返回即将过期
+        let report = manager.token_status(&token, 31 * 24 * 3600).await.unwrap();
+        assert_eq!(report.status, TokenStatus::ExpiringSoon);
+
+        manager.logout(&token).await.unwrap();
+        assert_eq!(manager.token_status(&token, 60).await.unwrap().status, TokenStatus::LoggedOut);
+        let unknown = crate::TokenValue::new("unknown");
+        assert_eq!(manager.token_status(&unknown, 60).await.unwrap().status, TokenStatus::Invalid);
+    }
+}
@@ -0,0 +1,385 @@
+// Author: 金书记
+//
+//! 多账号体系 | Multiple account systems
+//!
+//! 一个应用里常有多套互不相干的账号，例如前台用户与后台管理员。`StpLogic` 绑定一个
+//! 登录类型（login_type），在共享的 `SaTokenManager` 上维护独立的 token、Session、
+//! 权限与角色空间：
+//!
+//! - token 记录所属的登录类型，其他体系的 token 在本体系中视为未登录
+//! - Session、权限/角色映射与封禁以 `account_key`（`{login_type}:{login_id}`）为键
+//! - 不允许并发登录时只顶替同一体系中的旧 token，踢人与登出也只作用于本体系
+//!
+//! `StpUtil` 委托给登录类型为 `default` 的实例，默认体系的存储键与之前完全一致。
+//!
+//! An app often has unrelated account systems, e.g. customers and admins.
+//! A `StpLogic` binds one login type and keeps its own token, session,
+//! permission and role space on a shared `SaTokenManager`: tokens of another
+//! system are treated as not logged in, sessions, permission/role maps and
+//! bans are keyed by `account_key` (`{login_type}:{login_id}`), and
+//! replacement, kick-out and logout stay within the system. `StpUtil`
+//! delegates to the `default` instance, whose storage keys are unchanged.
+//!
+//! 自定义的 `PermissionChecker` / `RoleChecker` 在非默认体系中收到的是 `account_key`。
+//! Custom `PermissionChecker` / `RoleChecker` implementations receive the
+//! `account_key` for non-default systems.
+//!
+//! ```rust,ignore
+//! static ADMIN: Lazy<StpLogic> = Lazy::new(|| StpLogic::new("admin", manager.clone()));
+//!
+//! let token = ADMIN.login(10001).await?;
+//! ADMIN.set_permissions(10001, vec!["system:config".into()]).await?;
+//! assert!(ADMIN.is_login(&token).await);
+//! assert!(!StpUtil::is_login_by_login_id(10001).await);   // 用户体系中的 10001 未登录
+//! ```
+
+use crate::error::{SaTokenError, SaTokenResult};
+use crate::login::{LoginOptions, LoginResult};
+use crate::manager::{account_key, device_token_key, login_token_key, SaTokenManager};
+use crate::permission::AuthzKind;
+use crate::session::SaSession;
+use crate::context::SaTokenContext;
+use crate::token::{TokenInfo, TokenValue};
+use crate::util::LoginId;
+
+/// 绑定登录类型的认证逻辑 | Authentication logic bound to one login type
+#[derive(Clone)]
+pub struct StpLogic {
+    login_type: String,
+    manager: SaTokenManager,
+}
+
+impl StpLogic {
+    /// 创建账号体系，`manager` 可与其他体系共享
+    ///
+    /// Creates the account system; the manager may be shared with others.
+    pub fn new(login_type: impl Into<String>, manager: SaTokenManager) -> Self {
+        Self {
+            login_type: login_type.into(),
+            manager,
+        }
+    }
+
+    /// 登录类型 | Login type
+    pub fn login_type(&self) -> &str {
+        &self.login_type
+    }
+
+    /// 底层管理器 | The underlying manager
+    pub fn manager(&self) -> &SaTokenManager {
+        &self.manager
+    }
+
+    /// 账号在本体系中的键，直接调用管理器的 Session/权限接口时使用
+    ///
+    /// The account's key in this system, for calling the manager's session
+    /// and permission APIs directly.
+    pub fn account_key(&self, login_id: impl LoginId) -> String {
+        account_key(&login_id.to_login_id(), &self.login_type)
+    }
+
+    // ==================== 登录与登出 ====================
+
+    /// 登录 | Login
+    pub async fn login(&self, login_id: impl LoginId) -> SaTokenResult<TokenValue> {
+        self.login_with_options(login_id, LoginOptions::new()).await
+    }
+
+    /// "记住我"登录，见 `StpUtil::login_with_remember` | Login with remember me, see `StpUtil::login_with_remember`
+    pub async fn login_with_remember(&self, login_id: impl LoginId, remember: bool) -> SaTokenResult<TokenValue> {
+        self.login_with_options(login_id, LoginOptions::new().remember(remember)).await
+    }
+
+    /// 按登录参数登录，参数中的登录类型被本体系的类型覆盖
+    ///
+    /// Login with options; the options' login type is replaced by this system's.
+    pub async fn login_with_options(&self, login_id: impl LoginId, options: LoginOptions) -> SaTokenResult<TokenValue> {
+        self.manager.login_with(login_id.to_login_id(), self.options(options)).await
+    }
+
+    /// 登录并返回完整的登录结果 | Login and return the full login result
+    pub async fn login_ex(&self, login_id: impl LoginId, options: LoginOptions) -> SaTokenResult<LoginResult> {
+        self.manager.login_ex(login_id.to_login_id(), self.options(options)).await
+    }
+
+    /// 登出 token，不属于本体系的 token 返回 `NotLogin`
+    pub async fn logout(&self, token: &TokenValue) -> SaTokenResult<()> {
+        self.get_token_info(token).await?;
+        self.manager.logout(token).await
+    }
+
+    /// 登出账号在本体系中的所有 token | Log out all of the account's tokens in this system
+    pub async fn logout_by_login_id(&self, login_id: impl LoginId) -> SaTokenResult<()> {
+        self.manager.logout_by_login_type(&login_id.to_login_id(), &self.login_type).await
+    }
+
+    /// 踢人下线并删除账号在本体系中的 Session | Kick out and delete the account's session in this system
+    pub async fn kick_out(&self, login_id: impl LoginId) -> SaTokenResult<()> {
+        self.manager.kick_out_by_login_type(&login_id.to_login_id(), &self.login_type).await
+    }
+
+    /// 登出账号在本体系中指定设备上的 token | Log out the account's tokens on one device in this system
+    pub async fn logout_by_device(&self, login_id: impl LoginId, device: &str) -> SaTokenResult<()> {
+        for (token, _) in self.manager.device_tokens(&login_id.to_login_id(), &self.login_type, device).await {
+            self.manager.logout(&token).await?;
+        }
+        Ok(())
+    }
+
+    // ==================== Token 验证 ====================
+
+    /// 获取 token 信息，其他体系的 token 返回 `NotLogin`
+    ///
+    /// Token info; tokens of other systems yield `NotLogin`.
+    pub async fn get_token_info(&self, token: &TokenValue) -> SaTokenResult<TokenInfo> {
+        let token_info = self.manager.get_token_info(token).await?;
+        if token_info.login_type != self.login_type {
+            return Err(SaTokenError::NotLogin);
+        }
+        Ok(token_info)
+    }
+
+    /// token 是否在本体系中已登录 | Whether the token is logged in to this system
+    pub async fn is_login(&self, token: &TokenValue) -> bool {
+        self.get_token_info(token).await.is_ok()
+    }
+
+    /// 未登录本体系时返回 `NotLogin` | `NotLogin` unless logged in to this system
+    pub async fn check_login(&self, token: &TokenValue) -> SaTokenResult<()> {
+        self.get_token_info(token).await.map(|_| ())
+    }
+
+    /// token 对应的 login_id | The token's login_id
+    pub async fn get_login_id(&self, token: &TokenValue) -> SaTokenResult<String> {
+        self.get_token_info(token).await.map(|info| info.login_id)
+    }
+
+    /// 账号在本体系中最近一次登录的 token | The account's latest token in this system
+    pub async fn get_token_by_login_id(&self, login_id: impl LoginId) -> SaTokenResult<TokenValue> {
+        self.manager.storage.get(&login_token_key(&login_id.to_login_id(), &self.login_type)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+            .map(TokenValue::new)
+            .ok_or(SaTokenError::NotLogin)
+    }
+
+    /// 账号在本体系中指定设备上最近一次登录的 token | The account's latest token on a device in this system
+    pub async fn get_token_by_device(&self, login_id: impl LoginId, device: &str) -> SaTokenResult<TokenValue> {
+        self.manager.storage.get(&device_token_key(&login_id.to_login_id(), &self.login_type, device)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+            .map(TokenValue::new)
+            .ok_or(SaTokenError::TokenNotFound)
+    }
+
+    /// 账号是否在本体系中已登录 | Whether the account is logged in to this system
+    pub async fn is_login_by_login_id(&self, login_id: impl LoginId) -> bool {
+        match self.get_token_by_login_id(login_id).await {
+            Ok(token) => self.is_login(&token).await,
+            Err(_) => false,
+        }
+    }
+
+    // ==================== 当前请求 ====================
+
+    /// 当前请求的 token 是否属于本体系（不访问存储）
+    ///
+    /// Whether the current request's token belongs to this system (no storage access).
+    pub fn is_login_current(&self) -> bool {
+        self.get_token_info_current().is_ok()
+    }
+
+    /// 当前请求的 login_id，未登录本体系时返回 `NotLogin`
+    pub fn get_login_id_current(&self) -> SaTokenResult<String> {
+        self.get_token_info_current().map(|info| info.login_id)
+    }
+
+    fn get_token_info_current(&self) -> SaTokenResult<TokenInfo> {
+        SaTokenContext::get_current()
+            .and_then(|ctx| ctx.token_info)
+            .filter(|info| info.login_type == self.login_type && !info.is_restricted())
+            .map(|info| (*info).clone())
+            .ok_or(SaTokenError::NotLogin)
+    }
+
+    // ==================== Session ====================
+
+    /// 账号在本体系中的 Session | The account's session in this system
+    pub async fn get_session(&self, login_id: impl LoginId) -> SaTokenResult<SaSession> {
+        self.manager.get_session(&self.account_key(login_id)).await
+    }
+
+    /// 删除账号在本体系中的 Session | Delete the account's session in this system
+    pub async fn delete_session(&self, login_id: impl LoginId) -> SaTokenResult<()> {
+        self.manager.delete_session(&self.account_key(login_id)).await
+    }
+
+    // ==================== 权限与角色 ====================
+
+    /// 设置账号在本体系中的权限 | Set the account's permissions in this system
+    pub async fn set_permissions(&self, login_id: impl LoginId, permissions: Vec<String>) -> SaTokenResult<()> {
+        self.manager.validate_authz_names(AuthzKind::Permission, &permissions)?;
+        let account = self.account_key(login_id);
+        self.manager.user_permissions.write().await.insert(account.clone(), permissions);
+        self.manager.notify_authz_changed(&account).await;
+        Ok(())
+    }
+
+    /// 账号在本体系中的所有权限 | The account's permissions in this system
+    pub async fn get_permissions(&self, login_id: impl LoginId) -> Vec<String> {
+        self.manager.get_permissions(&self.account_key(login_id)).await
+    }
+
+    /// 账号在本体系中是否拥有指定权限 | Whether the account has the permission in this system
+    pub async fn has_permission(&self, login_id: impl LoginId, permission: &str) -> bool {
+        self.manager.dev_bypass_login_id().is_some()
+            || self.manager.has_permission(&self.account_key(login_id), permission).await
+    }
+
+    /// 缺少权限时返回 `PermissionDeniedDetail` | `PermissionDeniedDetail` when the permission is missing
+    pub async fn check_permission(&self, login_id: impl LoginId, permission: &str) -> SaTokenResult<()> {
+        if !self.has_permission(login_id, permission).await {
+            self.record_denial(AuthzKind::Permission, permission);
+            return Err(SaTokenError::PermissionDeniedDetail(permission.to_string()));
+        }
+        Ok(())
+    }
+
+    /// 设置账号在本体系中的角色 | Set the account's roles in this system
+    pub async fn set_roles(&self, login_id: impl LoginId, roles: Vec<String>) -> SaTokenResult<()> {
+        self.manager.validate_authz_names(AuthzKind::Role, &roles)?;
+        let account = self.account_key(login_id);
+        self.manager.user_roles.write().await.insert(account.clone(), roles);
+        self.manager.notify_authz_changed(&account).await;
+        Ok(())
+    }
+
+    /// 账号在本体系中的所有角色 | The account's roles in this system
+    pub async fn get_roles(&self, login_id: impl LoginId) -> Vec<String> {
+        self.manager.get_roles(&self.account_key(login_id)).await
+    }
+
+    /// 账号在本体系中是否拥有指定角色 | Whether the account has the role in this system
+    pub async fn has_role(&self, login_id: impl LoginId, role: &str) -> bool {
+        self.manager.dev_bypass_login_id().is_some()
+            || self.manager.has_role(&self.account_key(login_id), role).await
+    }
+
+    /// 缺少角色时返回 `RoleDenied` | `RoleDenied` when the role is missing
+    pub async fn check_role(&self, login_id: impl LoginId, role: &str) -> SaTokenResult<()> {
+        if !self.has_role(login_id, role).await {
+            self.record_denial(AuthzKind::Role, role);
+            return Err(SaTokenError::RoleDenied(role.to_string()));
+        }
+        Ok(())
+    }
+
+    // ==================== 账号封禁 ====================
+
+    /// 在本体系中封禁账号（`seconds` 为 -1 时永久封禁）| Ban the account in this system (-1 for permanent)
+    pub async fn disable(&self, login_id: impl LoginId, seconds: i64) -> SaTokenResult<()> {
+        self.manager.disable_by_login_type(&login_id.to_login_id(), &self.login_type, seconds).await
+    }
+
+    /// 账号在本体系中是否被封禁 | Whether the account is banned in this system
+    pub async fn is_disabled(&self, login_id: impl LoginId) -> bool {
+        self.manager.is_disabled(&self.account_key(login_id)).await
+    }
+
+    /// 解除账号在本体系中的封禁 | Lift the account's ban in this system
+    pub async fn untie_disable(&self, login_id: impl LoginId) -> SaTokenResult<()> {
+        self.manager.untie_disable(&self.account_key(login_id)).await
+    }
+
+    fn record_denial(&self, kind: AuthzKind, name: &str) {
+        let route = SaTokenContext::get_current().and_then(|ctx| ctx.route);
+        self.manager.record_denial(route.as_deref(), kind, name);
+    }
+
+    fn options(&self, mut options: LoginOptions) -> LoginOptions {
+        options.login_type = Some(self.login_type.clone());
+        options
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use super::*;
+    use crate::config::SaTokenConfig;
+    use crate::manager::DEFAULT_LOGIN_TYPE;
+    use sa_token_storage_memory::MemoryStorage;
+
+    fn logics() -> (StpLogic, StpLogic) {
+        let config = SaTokenConfig::builder().is_concurrent(false).build_config();
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        (StpLogic::new(DEFAULT_LOGIN_TYPE, manager.clone()), StpLogic::new("admin", manager))
+    }
+
+    #[tokio::test]
+    async fn test_account_systems_are_isolated() {
+        let (user, admin) = logics();
+        let user_token = user.login(1).await.unwrap();
+        let admin_token = admin.login(1).await.unwrap();
+
+        // 不允许并发登录时，管理员登录不会顶掉同 ID 的用户
+        assert!(user.is_login(&user_token).await);
+        assert!(admin.is_login(&admin_token).await);
+        assert!(!admin.is_login(&user_token).await);
+        assert!(matches!(user.get_login_id(&admin_token).await, Err(SaTokenError::NotLogin)));
+        assert_eq!(admin.get_token_by_login_id(1).await.unwrap(), admin_token);
+
+        admin.set_permissions(1, vec!["system:config".to_string()]).await.unwrap();
+        assert!(admin.has_permission(1, "system:config").await);
+        assert!(!user.has_permission(1, "system:config").await);
+
+        let mut session = admin.get_session(1).await.unwrap();
+        session.set("theme", "dark").unwrap();
+        admin.manager().save_session(&session).await.unwrap();
+        assert!(user.get_session(1).await.unwrap().get::<String>("theme").is_none());
+
+        admin.kick_out(1).await.unwrap();
+        assert!(!admin.is_login(&admin_token).await);
+        assert!(user.is_login(&user_token).await);
+    }
+
+    #[tokio::test]
+    async fn test_device_logins_are_isolated_between_systems() {
+        let (user, admin) = logics();
+        let device = || LoginOptions::new().device("pc");
+        let user_token = user.login_with_options(7, device()).await.unwrap();
+        let admin_token = admin.login_with_options(7, device()).await.unwrap();
+
+        // 不允许并发登录时，管理员在同一设备登录不会顶掉同 ID 的用户
+        assert!(user.is_login(&user_token).await);
+        assert!(admin.is_login(&admin_token).await);
+        assert_eq!(user.get_token_by_device(7, "pc").await.unwrap(), user_token);
+        assert_eq!(admin.get_token_by_device(7, "pc").await.unwrap(), admin_token);
+
+        let renewed = admin.manager().renew_token(&admin_token).await.unwrap();
+        assert_eq!(admin.get_token_by_device(7, "pc").await.unwrap(), renewed);
+        assert_eq!(user.get_token_by_device(7, "pc").await.unwrap(), user_token);
+
+        admin.logout_by_device(7, "pc").await.unwrap();
+        assert!(!admin.is_login(&renewed).await);
+        assert!(user.is_login(&user_token).await);
+    }
+
+    #[tokio::test]
+    async fn test_disable_is_per_account_system() {
+        let (user, admin) = logics();
+        admin.disable(1, 60).await.unwrap();
+        assert!(matches!(admin.login(1).await, Err(SaTokenError::AccountBanned(_))));
+        assert!(user.login(1).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_disable_logs_out_the_account_in_its_system() {
+        let (user, admin) = logics();
+        let admin_token = admin.login("u1").await.unwrap();
+        let user_token = user.login("u1").await.unwrap();
+
+        admin.disable("u1", 600).await.unwrap();
+        assert!(admin.is_disabled("u1").await);
+        assert!(!admin.manager().is_valid(&admin_token).await);
+        assert!(user.manager().is_valid(&user_token).await);
+    }
+}
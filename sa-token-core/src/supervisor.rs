@@ -0,0 +1,362 @@
+// Author: 金书记
+//
+//! 存储健康监控 | Storage Supervisor
+//!
+//! `StorageSupervisor` 包装实际的存储，定期调用 `SaStorage::health_check`：存储失联时
+//! 发布 `StorageDown` 事件，恢复后发布 `StorageRecovered`。配置了回退缓存时，健康期间
+//! 读到的值会同步写入缓存，存储不可用期间的读取由缓存应答，已登录的用户仍能通过校验。
+//! 写入始终只交给实际存储，不可用期间的登录等写操作照常失败；删除会同时作用于缓存，
+//! 故障期间的登出在本实例上立即生效。
+//!
+//! `StorageSupervisor` wraps the real storage and calls
+//! `SaStorage::health_check` periodically, publishing `StorageDown` when the
+//! backend is lost and `StorageRecovered` when it is back. With a fallback
+//! cache, values read while healthy are copied into it and reads during an
+//! outage are answered from it, so logged-in users keep passing validation.
+//! Writes only go to the real storage and keep failing during an outage;
+//! deletes also apply to the cache, so logouts take effect on this instance.
+//!
+//! ```rust,ignore
+//! let supervisor = Arc::new(
+//!     StorageSupervisor::new(Arc::new(RedisStorage::new(url, "sa").await?))
+//!         .interval(Duration::from_secs(5))
+//!         .fallback_cache(Arc::new(MemoryStorage::new())),
+//! );
+//!
+//! let manager = SaTokenConfig::builder()
+//!     .storage(supervisor.clone())
+//!     .build();
+//! supervisor.attach_event_bus(manager.event_bus().clone());
+//! supervisor.spawn();
+//! ```
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use async_trait::async_trait;
+use sa_token_adapter::storage::{KeyTtl, SaStorage, ScanPage, StorageResult};
+use crate::event::{SaTokenEvent, SaTokenEventBus};
+
+/// 默认的健康检查间隔 | Default health check interval
+pub const DEFAULT_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// 回退缓存中条目的默认保留时长 | Default lifetime of fallback cache entries
+pub const DEFAULT_FALLBACK_CACHE_TTL: Duration = Duration::from_secs(600);
+
+/// 带健康检查与回退缓存的存储 | Storage with health checks and a fallback cache
+pub struct StorageSupervisor {
+    inner: Arc<dyn SaStorage>,
+    fallback: Option<Arc<dyn SaStorage>>,
+    interval: Duration,
+    cache_ttl: Duration,
+    healthy: AtomicBool,
+    event_bus: OnceLock<SaTokenEventBus>,
+}
+
+impl StorageSupervisor {
+    /// 包装实际存储，初始视为可用 | Wrap the real storage, assumed healthy at first
+    pub fn new(inner: Arc<dyn SaStorage>) -> Self {
+        Self {
+            inner,
+            fallback: None,
+            interval: DEFAULT_HEALTH_CHECK_INTERVAL,
+            cache_ttl: DEFAULT_FALLBACK_CACHE_TTL,
+            healthy: AtomicBool::new(true),
+            event_bus: OnceLock::new(),
+        }
+    }
+
+    /// 设置健康检查间隔 | Set the health check interval
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// 设置回退缓存，通常为 `MemoryStorage` | Set the fallback cache, usually a `MemoryStorage`
+    pub fn fallback_cache(mut self, cache: Arc<dyn SaStorage>) -> Self {
+        self.fallback = Some(cache);
+        self
+    }
+
+    /// 设置缓存条目的最长保留时长 | Cap on how long cached entries live
+    pub fn cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// 设置发布 `StorageDown` / `StorageRecovered` 的事件总线，只有第一次调用生效
+    ///
+    /// Sets the bus for `StorageDown` / `StorageRecovered`; only the first call takes effect.
+    pub fn attach_event_bus(&self, event_bus: SaTokenEventBus) {
+        let _ = self.event_bus.set(event_bus);
+    }
+
+    /// 最近一次检查时存储是否可用 | Whether the storage was reachable at the last check
+    pub fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    /// 被包装的实际存储 | The wrapped storage
+    pub fn inner(&self) -> &Arc<dyn SaStorage> {
+        &self.inner
+    }
+
+    /// 立即执行一次健康检查，状态变化时发布事件，返回检查后是否可用
+    ///
+    /// Runs one health check now, publishing an event when the state changes.
+    /// Returns whether the storage is reachable.
+    pub async fn check(&self) -> bool {
+        let result = self.inner.health_check().await;
+        let healthy = result.is_ok();
+        if self.healthy.swap(healthy, Ordering::Relaxed) != healthy {
+            let event = match result {
+                Ok(()) => {
+                    tracing::info!("StorageSupervisor: 存储已恢复");
+                    SaTokenEvent::storage_recovered()
+                }
+                Err(e) => {
+                    tracing::warn!("StorageSupervisor: 存储不可用: {}", e);
+                    SaTokenEvent::storage_down(e.to_string())
+                }
+            };
+            if let Some(event_bus) = self.event_bus.get() {
+                event_bus.publish(event).await;
+            }
+        }
+        healthy
+    }
+
+    /// 启动后台检查任务，最后一个 `Arc` 释放后自动停止
+    ///
+    /// Starts the background check loop; it stops once the last `Arc` is dropped.
+    pub fn spawn(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let supervisor = Arc::downgrade(self);
+        let interval = self.interval;
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let Some(supervisor) = supervisor.upgrade() else { break };
+                supervisor.check().await;
+            }
+        })
+    }
+
+    /// 不可用期间可以应答读取的回退缓存 | The fallback cache, when reads should go to it
+    fn fallback_if_down(&self) -> Option<&Arc<dyn SaStorage>> {
+        self.fallback.as_ref().filter(|_| !self.is_healthy())
+    }
+
+    /// 把读到的值同步到缓存，保留 `cache_ttl`（不额外查询 TTL）
+    ///
+    /// Copies a value read from the storage into the cache for `cache_ttl`,
+    /// without an extra TTL round trip; token expiry is still checked against
+    /// the `TokenInfo` itself.
+    async fn remember(&self, key: &str, value: Option<&str>) {
+        let Some(cache) = &self.fallback else { return };
+        let _ = match value {
+            Some(value) => cache.set(key, value, Some(self.cache_ttl)).await,
+            None => cache.delete(key).await,
+        };
+    }
+}
+
+#[async_trait]
+impl SaStorage for StorageSupervisor {
+    async fn get(&self, key: &str) -> StorageResult<Option<String>> {
+        if let Some(cache) = self.fallback_if_down() {
+            return cache.get(key).await;
+        }
+        match self.inner.get(key).await {
+            Ok(value) => {
+                self.remember(key, value.as_deref()).await;
+                Ok(value)
+            }
+            // 检查周期之间出现的故障也由缓存应答 | Failures between checks are answered by the cache too
+            Err(e) => match &self.fallback {
+                Some(cache) => cache.get(key).await,
+                None => Err(e),
+            },
+        }
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Option<Duration>) -> StorageResult<()> {
+        self.inner.set(key, value, ttl).await?;
+        if let Some(cache) = &self.fallback {
+            let ttl = ttl.map_or(self.cache_ttl, |ttl| ttl.min(self.cache_ttl));
+            let _ = cache.set(key, value, Some(ttl)).await;
+        }
+        Ok(())
+    }
+
+    async fn set_nx(&self, key: &str, value: &str, ttl: Option<Duration>) -> StorageResult<bool> {
+        self.inner.set_nx(key, value, ttl).await
+    }
+
+    async fn delete(&self, key: &str) -> StorageResult<()> {
+        if let Some(cache) = &self.fallback {
+            let _ = cache.delete(key).await;
+        }
+        self.inner.delete(key).await
+    }
+
+    async fn exists(&self, key: &str) -> StorageResult<bool> {
+        match self.fallback_if_down() {
+            Some(cache) => cache.exists(key).await,
+            None => self.inner.exists(key).await,
+        }
+    }
+
+    async fn expire(&self, key: &str, ttl: Duration) -> StorageResult<()> {
+        self.inner.expire(key, ttl).await?;
+        if let Some(cache) = &self.fallback {
+            let _ = cache.expire(key, ttl.min(self.cache_ttl)).await;
+        }
+        Ok(())
+    }
+
+    async fn ttl(&self, key: &str) -> StorageResult<KeyTtl> {
+        match self.fallback_if_down() {
+            Some(cache) => cache.ttl(key).await,
+            None => self.inner.ttl(key).await,
+        }
+    }
+
+    async fn mget(&self, keys: &[&str]) -> StorageResult<Vec<Option<String>>> {
+        match self.fallback_if_down() {
+            Some(cache) => cache.mget(keys).await,
+            None => self.inner.mget(keys).await,
+        }
+    }
+
+    async fn mdel(&self, keys: &[&str]) -> StorageResult<()> {
+        if let Some(cache) = &self.fallback {
+            let _ = cache.mdel(keys).await;
+        }
+        self.inner.mdel(keys).await
+    }
+
+    async fn incr(&self, key: &str) -> StorageResult<i64> {
+        self.inner.incr(key).await
+    }
+
+    async fn decr(&self, key: &str) -> StorageResult<i64> {
+        self.inner.decr(key).await
+    }
+
+    async fn clear(&self) -> StorageResult<()> {
+        if let Some(cache) = &self.fallback {
+            let _ = cache.clear().await;
+        }
+        self.inner.clear().await
+    }
+
+    async fn keys(&self, pattern: &str) -> StorageResult<Vec<String>> {
+        self.inner.keys(pattern).await
+    }
+
+    async fn scan_keys(&self, pattern: &str, cursor: u64) -> StorageResult<ScanPage> {
+        self.inner.scan_keys(pattern, cursor).await
+    }
+
+    async fn health_check(&self) -> StorageResult<()> {
+        self.inner.health_check().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use sa_token_adapter::storage::StorageError;
+    use sa_token_storage_memory::MemoryStorage;
+    use crate::event::{SaTokenEventType, SaTokenListener};
+
+    /// 可以切换为不可用的存储 | Storage that can be switched off
+    #[derive(Default)]
+    struct FlakyStorage {
+        inner: MemoryStorage,
+        down: AtomicBool,
+    }
+
+    impl FlakyStorage {
+        fn up(&self) -> StorageResult<()> {
+            if self.down.load(Ordering::Relaxed) {
+                return Err(StorageError::ConnectionError("connection refused".to_string()));
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl SaStorage for FlakyStorage {
+        async fn get(&self, key: &str) -> StorageResult<Option<String>> {
+            self.up()?;
+            self.inner.get(key).await
+        }
+        async fn set(&self, key: &str, value: &str, ttl: Option<Duration>) -> StorageResult<()> {
+            self.up()?;
+            self.inner.set(key, value, ttl).await
+        }
+        async fn delete(&self, key: &str) -> StorageResult<()> {
+            self.up()?;
+            self.inner.delete(key).await
+        }
+        async fn exists(&self, key: &str) -> StorageResult<bool> {
+            self.up()?;
+            self.inner.exists(key).await
+        }
+        async fn expire(&self, key: &str, ttl: Duration) -> StorageResult<()> {
+            self.up()?;
+            self.inner.expire(key, ttl).await
+        }
+        async fn ttl(&self, key: &str) -> StorageResult<KeyTtl> {
+            self.up()?;
+            self.inner.ttl(key).await
+        }
+        async fn clear(&self) -> StorageResult<()> {
+            self.up()?;
+            self.inner.clear().await
+        }
+    }
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<SaTokenEventType>>);
+
+    #[async_trait]
+    impl SaTokenListener for Recorder {
+        async fn on_event(&self, event: &SaTokenEvent) {
+            self.0.lock().unwrap().push(event.event_type.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_outage_publishes_events_and_serves_cached_reads() {
+        let backend = Arc::new(FlakyStorage::default());
+        let supervisor = StorageSupervisor::new(backend.clone())
+            .fallback_cache(Arc::new(MemoryStorage::new()));
+        let event_bus = SaTokenEventBus::new();
+        let recorder = Arc::new(Recorder::default());
+        event_bus.register(recorder.clone());
+        supervisor.attach_event_bus(event_bus);
+
+        supervisor.set("sa:token:a", "user_1", Some(Duration::from_secs(60))).await.unwrap();
+        supervisor.set("sa:token:b", "user_2", None).await.unwrap();
+
+        backend.down.store(true, Ordering::Relaxed);
+        assert!(!supervisor.check().await);
+        assert!(!supervisor.check().await);
+        assert_eq!(supervisor.get("sa:token:a").await.unwrap().as_deref(), Some("user_1"));
+        assert!(supervisor.set("sa:token:c", "user_3", None).await.is_err());
+
+        // 故障期间登出，本实例上立即生效 | A logout during the outage applies locally at once
+        assert!(supervisor.delete("sa:token:b").await.is_err());
+        assert_eq!(supervisor.get("sa:token:b").await.unwrap(), None);
+
+        backend.down.store(false, Ordering::Relaxed);
+        assert!(supervisor.check().await);
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            [SaTokenEventType::StorageDown, SaTokenEventType::StorageRecovered]
+        );
+    }
+}
@@ -0,0 +1,136 @@
+// Author: 金书记
+//
+//! 临时 Token 模块 | Temporary Tokens
+//!
+//! 对应 Java 版的 `SaTempUtil`：签发一个绑定任意值、短时间有效的随机 token，
+//! 常用于重置密码、邮箱验证等链接。临时 token 与登录态无关，不会出现在
+//! `sa:token:*` 中，到期后由存储自动清理。
+//!
+//! Equivalent of Java's `SaTempUtil`: issues a short-lived random token bound
+//! to a value, for password-reset or email-verification links. Temp tokens are
+//! unrelated to login state and are cleaned up by the storage TTL.
+//!
+//! ## 使用示例 | Usage Example
+//!
+//! ```rust,ignore
+//! let temp = TempTokenManager::new(storage.clone());
+//!
+//! // 发送重置密码邮件，链接 30 分钟内有效
+//! let token = temp.create_temp_token(&"user_123", 1800).await?;
+//! send_mail(format!("https://example.com/reset?token={}", token));
+//!
+//! // 用户点击链接：取出值并立即作废，链接只能使用一次
+//! let login_id: String = temp.consume_temp_token(&token).await?
+//!     .ok_or(SaTokenError::TokenNotFound)?;
+//! ```
+
+use std::sync::Arc;
+use std::time::Duration;
+use serde::{de::DeserializeOwned, Serialize};
+use uuid::Uuid;
+use sa_token_adapter::storage::{KeyTtl, SaStorage};
+use crate::error::{SaTokenError, SaTokenResult};
+
+/// 临时 token 的存储 key | Storage key of a temp token
+fn temp_token_key(token: &str) -> String {
+    format!("sa:temp-token:{}", token)
+}
+
+/// 临时 Token 管理器 | Temporary token manager
+#[derive(Clone)]
+pub struct TempTokenManager {
+    storage: Arc<dyn SaStorage>,
+}
+
+impl TempTokenManager {
+    /// 创建临时 token 管理器 | Create a temp token manager
+    pub fn new(storage: Arc<dyn SaStorage>) -> Self {
+        Self { storage }
+    }
+
+    /// 签发临时 token | Issue a temp token
+    ///
+    /// # 参数 | Parameters
+    /// - `value`: 绑定的值，如用户 ID | Bound value, e.g. a user ID
+    /// - `ttl`: 有效期（秒），必须大于 0 | Validity in seconds, must be positive
+    pub async fn create_temp_token<T: Serialize + ?Sized>(&self, value: &T, ttl: i64) -> SaTokenResult<String> {
+        if ttl <= 0 {
+            return Err(SaTokenError::ConfigError(format!("temp token ttl must be positive, got {}", ttl)));
+        }
+
+        let token = Uuid::new_v4().simple().to_string();
+        let json = serde_json::to_string(value)?;
+        self.storage.set(&temp_token_key(&token), &json, Some(Duration::from_secs(ttl as u64))).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+
+        Ok(token)
+    }
+
+    /// 解析临时 token，已过期或不存在时返回 None | Parse a temp token, None when expired or unknown
+    pub async fn parse_temp_token<T: DeserializeOwned>(&self, token: &str) -> SaTokenResult<Option<T>> {
+        let value = self.storage.get(&temp_token_key(token)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        match value {
+            Some(value) => Ok(Some(serde_json::from_str(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// 解析并删除临时 token，保证链接只能使用一次 | Parse and delete, making the token single-use
+    pub async fn consume_temp_token<T: DeserializeOwned>(&self, token: &str) -> SaTokenResult<Option<T>> {
+        let value = self.parse_temp_token(token).await?;
+        if value.is_some() {
+            self.delete_temp_token(token).await?;
+        }
+        Ok(value)
+    }
+
+    /// 剩余有效期（秒），不存在时返回 None | Seconds left, None when the token is unknown
+    pub async fn get_temp_token_timeout(&self, token: &str) -> SaTokenResult<Option<i64>> {
+        let ttl = self.storage.ttl(&temp_token_key(token)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        Ok(match ttl {
+            KeyTtl::Expires(remaining) => Some(remaining.as_secs() as i64),
+            KeyTtl::Persistent => Some(-1),
+            KeyTtl::Missing => None,
+        })
+    }
+
+    /// 作废临时 token | Revoke a temp token
+    pub async fn delete_temp_token(&self, token: &str) -> SaTokenResult<()> {
+        self.storage.delete(&temp_token_key(token)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_storage_memory::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_create_parse_and_delete() {
+        let temp = TempTokenManager::new(Arc::new(MemoryStorage::new()));
+        let token = temp.create_temp_token("user_1", 600).await.unwrap();
+
+        let value: Option<String> = temp.parse_temp_token(&token).await.unwrap();
+        assert_eq!(value.as_deref(), Some("user_1"));
+        let remaining = temp.get_temp_token_timeout(&token).await.unwrap().unwrap();
+        assert!(remaining > 590 && remaining <= 600);
+
+        temp.delete_temp_token(&token).await.unwrap();
+        assert!(temp.parse_temp_token::<String>(&token).await.unwrap().is_none());
+        assert!(temp.get_temp_token_timeout(&token).await.unwrap().is_none());
+        assert!(temp.create_temp_token("user_1", 0).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_consume_is_single_use() {
+        let temp = TempTokenManager::new(Arc::new(MemoryStorage::new()));
+        let token = temp.create_temp_token(&serde_json::json!({"email": "a@example.com"}), 60).await.unwrap();
+
+        let value: Option<serde_json::Value> = temp.consume_temp_token(&token).await.unwrap();
+        assert_eq!(value.unwrap()["email"], "a@example.com");
+        assert!(temp.consume_temp_token::<serde_json::Value>(&token).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,163 @@
+// Author: 金书记
+//
+//! 时间源 | Time Source
+//!
+//! 过期判断（token、nonce 时间窗口、refresh token、OAuth2 授权码、封禁等）统一通过
+//! [`now`] 取当前时间。开启 `test-clock` feature 后，测试可以冻结或拨快当前线程的时间，
+//! 让过期相关的测试立即、确定地完成；未开启时 [`now`] 就是 `Utc::now()`。
+//!
+//! Every expiry check (tokens, nonce windows, refresh tokens, OAuth2 codes,
+//! bans, ...) reads the time through [`now`]. With the `test-clock` feature,
+//! tests can freeze or advance the time of the current thread so expiry tests
+//! run instantly and deterministically. Without it, [`now`] is `Utc::now()`.
+//!
+//! 测试时钟按线程生效，适用于 `#[tokio::test]` 默认的单线程运行时；存储自身的 TTL
+//! （如 Redis 过期）不受影响。
+//!
+//! The test clock is per thread, which fits the current-thread runtime used by
+//! `#[tokio::test]`. Storage-side TTLs (e.g. Redis expiry) are not affected.
+//!
+//! 需要按实例控制时间时，可以给 `SaTokenManager` / `OAuth2Manager` / 存储注入
+//! `Clock`（如 `MockClock`）；未注入时它们使用 [`DefaultClock`]，即 [`now`]。测试时钟本身就是
+//! 一个 `MockClock`：把 `freeze()` 返回的时钟（或用 `install` 安装的时钟）注入实例，
+//! 实例与 [`now`] 读到的始终是同一个时间。
+//!
+//! To control time per instance, inject a `Clock` (e.g. `MockClock`) into
+//! `SaTokenManager`, `OAuth2Manager` or a storage. Without one they use
+//! [`DefaultClock`], which is [`now`]. The test clock is itself a `MockClock`:
+//! inject the clock returned by `freeze()` (or one passed to `install`) and the
+//! instance always agrees with [`now`].
+
+use chrono::{DateTime, Utc};
+
+/// 读取 [`now`] 的时钟，`SaTokenManager` 与 `OAuth2Manager` 的默认时钟
+///
+/// The clock reading [`now`]; the default of `SaTokenManager` and `OAuth2Manager`.
+#[cfg(feature = "runtime")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultClock;
+
+#[cfg(feature = "runtime")]
+impl sa_token_adapter::clock::Clock for DefaultClock {
+    fn now(&self) -> DateTime<Utc> {
+        now()
+    }
+}
+
+/// 当前时间 | Current time
+#[cfg(not(any(test, feature = "test-clock")))]
+#[inline]
+pub fn now() -> DateTime<Utc> {
+    Utc::now()
+}
+
+/// 当前时间，测试时钟生效时返回冻结或拨快后的时间 | Current time, honouring the test clock
+#[cfg(any(test, feature = "test-clock"))]
+pub fn now() -> DateTime<Utc> {
+    test_clock::now()
+}
+
+#[cfg(any(test, feature = "test-clock"))]
+pub use test_clock::{advance, clock, freeze, freeze_at, install, reset};
+
+#[cfg(any(test, feature = "test-clock"))]
+mod test_clock {
+    use std::cell::RefCell;
+    use chrono::{DateTime, Duration, Utc};
+    use sa_token_adapter::clock::{Clock, MockClock};
+
+    thread_local! {
+        static CLOCK: RefCell<Option<MockClock>> = const { RefCell::new(None) };
+    }
+
+    pub(super) fn now() -> DateTime<Utc> {
+        clock().map_or_else(Utc::now, |clock| clock.now())
+    }
+
+    /// 当前线程的测试时钟 | The current thread's test clock
+    pub fn clock() -> Option<MockClock> {
+        CLOCK.with(|clock| clock.borrow().clone())
+    }
+
+    /// 让 `clock` 驱动当前线程的 [`now`](super::now) | Make `clock` drive the current thread's `now`
+    ///
+    /// 同一个 `MockClock` 注入 manager、存储后，它们与 `now()` 读到同一个时间。
+    /// Inject the same `MockClock` into managers and storages and they read the
+    /// same time as `now()`.
+    pub fn install(clock: MockClock) -> MockClock {
+        CLOCK.with(|slot| *slot.borrow_mut() = Some(clock.clone()));
+        clock
+    }
+
+    /// 把当前线程的时间冻结在此刻，返回该线程的时钟 | Freeze the current thread's time at this instant and return its clock
+    pub fn freeze() -> MockClock {
+        freeze_at(now())
+    }
+
+    /// 把当前线程的时间冻结在指定时刻 | Freeze the current thread's time at `at`
+    pub fn freeze_at(at: DateTime<Utc>) -> MockClock {
+        match clock() {
+            Some(clock) => {
+                clock.set(at);
+                clock
+            }
+            None => install(MockClock::new(at)),
+        }
+    }
+
+    /// 拨快当前线程的时间；已冻结时移动冻结时刻 | Move the current thread's time forward
+    pub fn advance(by: Duration) {
+        match clock() {
+            Some(clock) => clock.advance(by),
+            None => {
+                install(MockClock::offset(by));
+            }
+        }
+    }
+
+    /// 恢复真实时间 | Back to the wall clock
+    pub fn reset() {
+        CLOCK.with(|clock| *clock.borrow_mut() = None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_freeze_and_advance() {
+        freeze();
+        let frozen = now();
+        assert_eq!(now(), frozen);
+
+        advance(Duration::hours(2));
+        assert_eq!(now(), frozen + Duration::hours(2));
+
+        reset();
+        assert!(now() < frozen + Duration::hours(1));
+    }
+
+    #[test]
+    fn test_advance_without_freeze_keeps_time_flowing() {
+        advance(Duration::days(1));
+        let shifted = now();
+        assert!(shifted > Utc::now() + Duration::hours(23));
+        reset();
+    }
+
+    #[test]
+    fn test_frozen_clock_is_shared_with_injected_handles() {
+        use sa_token_adapter::clock::{Clock, MockClock};
+
+        let injected: std::sync::Arc<dyn Clock> = std::sync::Arc::new(freeze());
+        advance(Duration::minutes(5));
+        assert_eq!(injected.now(), now());
+
+        let other = install(MockClock::default());
+        other.advance(Duration::hours(1));
+        assert_eq!(clock().unwrap().now(), now());
+        reset();
+    }
+}
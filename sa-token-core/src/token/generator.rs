@@ -4,17 +4,33 @@
 //!
 //! Supports multiple token styles including UUID, Random, and JWT
 //! 支持多种 Token 风格，包括 UUID、随机字符串和 JWT
+//!
+//! [`DefaultTokenGenerator`] 按配置的 `token_style` 生成 token（原名 `TokenGenerator`，
+//! 该名称现在是自定义生成器的 trait）。`SaTokenManager` 登录时还会从存储分配 `Sequence`
+//! 风格的序号，并检查与已有 token 的冲突；直接调用这里的函数时，`Sequence` 使用进程内计数器。
+//!
+//! [`DefaultTokenGenerator`] generates tokens for the configured `token_style`
+//! (it used to be called `TokenGenerator`, which is now the trait for custom
+//! generators). At login, `SaTokenManager` additionally allocates `Sequence`
+//! numbers from the storage and checks for collisions with existing tokens;
+//! called directly, `Sequence` uses an in-process counter.
 
+use std::sync::atomic::{AtomicU64, Ordering};
 use uuid::Uuid;
 use crate::config::{TokenStyle, SaTokenConfig};
+use crate::error::SaTokenResult;
 use crate::token::TokenValue;
-use crate::token::jwt::{JwtManager, JwtClaims, JwtAlgorithm};
-use chrono::Utc;
+use crate::login::LoginOptions;
+use crate::token::jwt::{JwtManager, JwtClaims};
 use sha2::{Sha256, Digest};
 
-pub struct TokenGenerator;
+/// 进程内的 `Sequence` 计数器，仅在不经过 `SaTokenManager` 时使用
+static LOCAL_SEQUENCE: AtomicU64 = AtomicU64::new(0);
 
-impl TokenGenerator {
+/// 内置 token 风格的生成器 | Generator of the built-in token styles
+pub struct DefaultTokenGenerator;
+
+impl DefaultTokenGenerator {
     /// Generate token based on configuration | 根据配置生成 token
     ///
     /// # Arguments | 参数
@@ -22,7 +38,7 @@ impl TokenGenerator {
     /// * `config` - Sa-token configuration | Sa-token 配置
     /// * `login_id` - User login ID (required for JWT) | 用户登录ID（JWT 必需）
     pub fn generate_with_login_id(config: &SaTokenConfig, login_id: &str) -> TokenValue {
-        match config.token_style {
+        let token = match &config.token_style {
             TokenStyle::Uuid => Self::generate_uuid(),
             TokenStyle::SimpleUuid => Self::generate_simple_uuid(),
             TokenStyle::Random32 => Self::generate_random(32),
@@ -32,6 +48,58 @@ impl TokenGenerator {
             TokenStyle::Hash => Self::generate_hash(login_id),
             TokenStyle::Timestamp => Self::generate_timestamp(),
             TokenStyle::Tik => Self::generate_tik(),
+            TokenStyle::Sequence { prefix } => {
+                Self::sequence_token(prefix, LOCAL_SEQUENCE.fetch_add(1, Ordering::Relaxed) + 1)
+            }
+            TokenStyle::Custom(generator) => TokenValue::new(generator.generate(login_id)),
+        };
+        Self::apply_locality_hint(config, token)
+    }
+    
+    /// 由配置生成 `Sequence` 风格的 token，`seq` 为已分配的序号
+    /// Builds a `Sequence` token from an allocated number
+    pub(crate) fn generate_sequence(config: &SaTokenConfig, prefix: &str, seq: i64) -> TokenValue {
+        Self::apply_locality_hint(config, Self::sequence_token(prefix, seq))
+    }
+    
+    fn sequence_token(prefix: &str, seq: impl std::fmt::Display) -> TokenValue {
+        TokenValue::new(format!("{}{}", prefix, seq))
+    }
+    
+    /// 配置了 `locality_hint` 时给 token 加上可用区前缀，JWT 风格除外
+    /// Prefix the token with the configured zone hint, except for JWT style
+    fn apply_locality_hint(config: &SaTokenConfig, token: TokenValue) -> TokenValue {
+        match &config.locality_hint {
+            Some(zone) if !matches!(config.token_style, TokenStyle::Jwt) => {
+                TokenValue::new(crate::routing::with_locality_hint(zone, token.as_str()))
+            }
+            _ => token,
+        }
+    }
+    
+    /// Generate token with login options | 根据配置和登录参数生成 token
+    ///
+    /// JWT 风格会把登录参数写入声明，其他风格与 `generate_with_login_id` 相同
+    /// JWT style carries the options as claims, other styles ignore them
+    ///
+    /// # Panics
+    ///
+    /// JWT 配置无法签名时 panic，需要处理错误时使用 `try_generate_with_options`
+    /// Panics when the JWT configuration cannot sign; use `try_generate_with_options` to handle the error
+    pub fn generate_with_options(config: &SaTokenConfig, login_id: &str, options: &LoginOptions) -> TokenValue {
+        Self::try_generate_with_options(config, login_id, options)
+            .unwrap_or_else(|e| panic!("Failed to generate token: {}", e))
+    }
+    
+    /// Generate token with login options, reporting JWT errors | 根据配置和登录参数生成 token，返回 JWT 错误
+    ///
+    /// JWT 配置缺少签名密钥（例如只配置了公钥）或无效时返回 `ConfigError`
+    /// Returns `ConfigError` when the JWT configuration is invalid or has no
+    /// signing key, e.g. only a public key is configured
+    pub fn try_generate_with_options(config: &SaTokenConfig, login_id: &str, options: &LoginOptions) -> SaTokenResult<TokenValue> {
+        match config.token_style {
+            TokenStyle::Jwt => Self::try_generate_jwt_with_options(config, login_id, options),
+            _ => Ok(Self::generate_with_login_id(config, login_id)),
         }
     }
     
@@ -50,14 +118,15 @@ impl TokenGenerator {
         TokenValue::new(Uuid::new_v4().simple().to_string())
     }
     
-    /// 生成随机字符串
+    /// 生成指定长度的随机十六进制字符串
     pub fn generate_random(length: usize) -> TokenValue {
-        use sha2::{Sha256, Digest};
-        let uuid = Uuid::new_v4();
-        let random_bytes = uuid.as_bytes();
-        let hash = Sha256::digest(random_bytes);
-        let hex_string = hex::encode(hash);
-        TokenValue::new(hex_string[..length.min(hex_string.len())].to_string())
+        // 每个 SHA256 摘要提供 64 个字符，Random128 需要两段
+        let mut hex_string = String::with_capacity(length + 64);
+        while hex_string.len() < length {
+            hex_string.push_str(&hex::encode(Sha256::digest(Uuid::new_v4().as_bytes())));
+        }
+        hex_string.truncate(length);
+        TokenValue::new(hex_string)
     }
     
     /// Generate JWT token | 生成 JWT token
@@ -67,65 +136,59 @@ impl TokenGenerator {
     /// * `config` - Sa-token configuration | Sa-token 配置
     /// * `login_id` - User login ID | 用户登录ID
     pub fn generate_jwt(config: &SaTokenConfig, login_id: &str) -> TokenValue {
+        Self::generate_jwt_with_options(config, login_id, &LoginOptions::default())
+    }
+    
+    /// Generate JWT token carrying login options | 生成携带登录参数的 JWT token
+    ///
+    /// # Arguments | 参数
+    ///
+    /// * `config` - Sa-token configuration | Sa-token 配置
+    /// * `login_id` - User login ID | 用户登录ID
+    /// * `options` - Login type, device, timeout and custom claims | 登录类型、设备、有效期与自定义声明
+    ///
+    /// # Panics
+    ///
+    /// JWT 配置无法签名时 panic | Panics when the JWT configuration cannot sign
+    pub fn generate_jwt_with_options(config: &SaTokenConfig, login_id: &str, options: &LoginOptions) -> TokenValue {
+        Self::try_generate_jwt_with_options(config, login_id, options)
+            .unwrap_or_else(|e| panic!("Invalid JWT configuration: {}", e))
+    }
+    
+    /// Generate JWT token, reporting configuration errors | 生成 JWT token，返回配置错误
+    pub fn try_generate_jwt_with_options(config: &SaTokenConfig, login_id: &str, options: &LoginOptions) -> SaTokenResult<TokenValue> {
         // 如果 login_id 为空，则使用时间戳作为 login_id
         let effective_login_id = if login_id.is_empty() {
-            Utc::now().timestamp_millis().to_string()
+            crate::time::now().timestamp_millis().to_string()
         } else {
             login_id.to_string()
         };
         
-        // Get JWT secret key | 获取 JWT 密钥
-        let secret = config.jwt_secret_key.as_ref()
-            .expect("JWT secret key is required when using JWT token style");
-        
-        // Parse algorithm | 解析算法
-        let algorithm = config.jwt_algorithm.as_ref()
-            .and_then(|alg| Self::parse_jwt_algorithm(alg))
-            .unwrap_or(JwtAlgorithm::HS256);
-        
-        // Create JWT manager | 创建 JWT 管理器
-        let mut jwt_manager = JwtManager::with_algorithm(secret, algorithm);
-        
-        if let Some(ref issuer) = config.jwt_issuer {
-            jwt_manager = jwt_manager.set_issuer(issuer);
-        }
-        
-        if let Some(ref audience) = config.jwt_audience {
-            jwt_manager = jwt_manager.set_audience(audience);
-        }
+        // Create JWT manager (secret or PEM keys) | 创建 JWT 管理器（密钥或 PEM 密钥对）
+        let jwt_manager = JwtManager::from_config(config)?;
         
         // Create claims | 创建声明
         let mut claims = JwtClaims::new(effective_login_id);
         
         // Set expiration | 设置过期时间
-        if config.timeout > 0 {
-            claims.set_expiration(config.timeout);
+        let timeout = options.timeout.unwrap_or(config.timeout);
+        if timeout > 0 {
+            claims.set_expiration(timeout);
         }
         
-        // Generate JWT token | 生成 JWT token
-        match jwt_manager.generate(&claims) {
-            Ok(token) => TokenValue::new(token),
-            Err(e) => {
-                eprintln!("Failed to generate JWT token: {:?}", e);
-                // Fallback to UUID | 回退到 UUID
-                Self::generate_uuid()
-            }
+        // Login options | 登录参数
+        if let Some(ref login_type) = options.login_type {
+            claims.set_login_type(login_type);
         }
-    }
-    
-    /// Parse JWT algorithm from string | 从字符串解析 JWT 算法
-    fn parse_jwt_algorithm(alg: &str) -> Option<JwtAlgorithm> {
-        match alg.to_uppercase().as_str() {
-            "HS256" => Some(JwtAlgorithm::HS256),
-            "HS384" => Some(JwtAlgorithm::HS384),
-            "HS512" => Some(JwtAlgorithm::HS512),
-            "RS256" => Some(JwtAlgorithm::RS256),
-            "RS384" => Some(JwtAlgorithm::RS384),
-            "RS512" => Some(JwtAlgorithm::RS512),
-            "ES256" => Some(JwtAlgorithm::ES256),
-            "ES384" => Some(JwtAlgorithm::ES384),
-            _ => None,
+        if let Some(ref device) = options.device {
+            claims.set_device(device);
         }
+        if !options.claims.is_empty() {
+            claims.set_claims(options.claims.clone());
+        }
+        
+        // Generate JWT token | 生成 JWT token
+        jwt_manager.generate(&claims).map(TokenValue::new)
     }
     
     /// Generate Hash style token | 生成 Hash 风格 token
@@ -139,12 +202,12 @@ impl TokenGenerator {
     pub fn generate_hash(login_id: &str) -> TokenValue {
         // 如果 login_id 为空，使用时间戳代替
         let login_id_value = if login_id.is_empty() {
-            Utc::now().timestamp_millis().to_string()
+            crate::time::now().timestamp_millis().to_string()
         } else {
             login_id.to_string()
         };
         
-        let timestamp = Utc::now().timestamp_millis();
+        let timestamp = crate::time::now().timestamp_millis();
         let uuid = Uuid::new_v4();
         let data = format!("{}{}{}", login_id_value, timestamp, uuid);
         
@@ -164,10 +227,9 @@ impl TokenGenerator {
     /// Example: 1760403556789_a3b2c1d4e5f6g7h8
     /// 示例：1760403556789_a3b2c1d4e5f6g7h8
     pub fn generate_timestamp() -> TokenValue {
-        use chrono::Utc;
         use sha2::{Sha256, Digest};
         
-        let timestamp = Utc::now().timestamp_millis();
+        let timestamp = crate::time::now().timestamp_millis();
         let uuid = Uuid::new_v4();
         
         // Generate random suffix | 生成随机后缀
@@ -7,8 +7,10 @@
 //!
 //! ## Features | 功能特性
 //!
-//! - Multiple algorithms support (HS256, HS384, HS512, RS256, etc.)
-//!   支持多种算法（HS256, HS384, HS512, RS256 等）
+//! - Multiple algorithms support (HS256, HS384, HS512, RS256, ES256, EdDSA, etc.)
+//!   支持多种算法（HS256, HS384, HS512, RS256, ES256, EdDSA 等）
+//! - Asymmetric signing with PEM keys and JWK export | 基于 PEM 密钥的非对称签名及 JWK 导出
+//! - HMAC secret rotation without mass logout | HMAC 密钥轮换，无需全员重新登录
 //! - Custom claims support | 支持自定义声明
 //! - Expiration time validation | 过期时间验证
 //! - Token refresh | Token 刷新
@@ -30,14 +32,58 @@
 //! let decoded_claims = jwt_manager.validate(&token)?;
 //! println!("User ID: {}", decoded_claims.login_id);
 //! ```
+//!
+//! ## Asymmetric Keys | 非对称密钥
+//!
+//! With RS*/ES*/EdDSA the issuer signs with its private key, and resource
+//! servers verify with the public key only, so the signing secret never
+//! leaves the issuer. The public key can be published as a JWK.
+//! 使用 RS*/ES*/EdDSA 时，签发方用私钥签名，资源服务只需公钥即可验证，
+//! 签名密钥不必分发；公钥可以以 JWK 形式发布。
+//!
+//! ```rust,ignore
+//! // Issuer | 签发方
+//! let issuer = JwtManager::asymmetric(JwtAlgorithm::ES256)
+//!     .set_private_key_pem(std::fs::read_to_string("jwt_private.pem")?)
+//!     .set_public_key_pem(std::fs::read_to_string("jwt_public.pem")?)
+//!     .set_key_id("2024-01");
+//! let token = issuer.generate(&claims)?;
+//! let jwks = issuer.jwks()?; // {"keys":[{"kty":"EC","crv":"P-256",...}]}
+//!
+//! // Resource server | 资源服务
+//! let verifier = JwtManager::verifier(JwtAlgorithm::ES256, public_pem);
+//! let claims = verifier.validate(&token)?;
+//! ```
+//!
+//! ## Secret Rotation | 密钥轮换
+//!
+//! Replacing `jwt_secret_key` outright breaks every token already issued.
+//! Instead, roll the new secret out with an activation time: before it, tokens
+//! are still signed with the old secret; after it, with the new one. Both keep
+//! verifying, and [`JwtManager::legacy_verifications`] counts tokens that only
+//! the old secret accepted, so the old one can be dropped once it reaches zero.
+//! 直接替换 `jwt_secret_key` 会让已签发的 token 全部失效。改为带生效时间下发新密钥：
+//! 生效前仍用旧密钥签名，生效后用新密钥签名，两者都能验证；
+//! [`JwtManager::legacy_verifications`] 统计仅能被旧密钥验证的 token 数量，
+//! 降为零后即可移除旧密钥。
+//!
+//! ```rust,ignore
+//! let jwt = JwtManager::new("old-secret")
+//!     .add_secret("new-secret", activate_at);
+//! ```
 
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
 use chrono::{DateTime, Duration, Utc};
 use jsonwebtoken::{
     decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation,
 };
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
+use simple_asn1::ASN1Block;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 
 use crate::error::{SaTokenError, SaTokenResult};
 
@@ -60,6 +106,46 @@ pub enum JwtAlgorithm {
     ES256,
     /// ECDSA using SHA-384 | 使用 SHA-384 的 ECDSA
     ES384,
+    /// EdDSA using Ed25519 | 使用 Ed25519 的 EdDSA
+    EdDSA,
+}
+
+impl JwtAlgorithm {
+    /// Parse algorithm name, case-insensitive | 从名称解析算法（不区分大小写）
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_uppercase().as_str() {
+            "HS256" => Some(Self::HS256),
+            "HS384" => Some(Self::HS384),
+            "HS512" => Some(Self::HS512),
+            "RS256" => Some(Self::RS256),
+            "RS384" => Some(Self::RS384),
+            "RS512" => Some(Self::RS512),
+            "ES256" => Some(Self::ES256),
+            "ES384" => Some(Self::ES384),
+            "EDDSA" => Some(Self::EdDSA),
+            _ => None,
+        }
+    }
+
+    /// Whether this is an HMAC (shared secret) algorithm | 是否为 HMAC（共享密钥）算法
+    pub fn is_symmetric(&self) -> bool {
+        matches!(self, Self::HS256 | Self::HS384 | Self::HS512)
+    }
+
+    /// Algorithm name as used in the JWT header | JWT 头中的算法名
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::HS256 => "HS256",
+            Self::HS384 => "HS384",
+            Self::HS512 => "HS512",
+            Self::RS256 => "RS256",
+            Self::RS384 => "RS384",
+            Self::RS512 => "RS512",
+            Self::ES256 => "ES256",
+            Self::ES384 => "ES384",
+            Self::EdDSA => "EdDSA",
+        }
+    }
 }
 
 impl Default for JwtAlgorithm {
@@ -79,6 +165,7 @@ impl From<JwtAlgorithm> for Algorithm {
             JwtAlgorithm::RS512 => Algorithm::RS512,
             JwtAlgorithm::ES256 => Algorithm::ES256,
             JwtAlgorithm::ES384 => Algorithm::ES384,
+            JwtAlgorithm::EdDSA => Algorithm::EdDSA,
         }
     }
 }
@@ -128,7 +215,7 @@ pub struct JwtClaims {
     pub device: Option<String>,
 
     /// Custom data | 自定义数据
-    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub extra: HashMap<String, Value>,
 }
 
@@ -139,7 +226,7 @@ impl JwtClaims {
     ///
     /// * `login_id` - User identifier | 用户标识符
     pub fn new(login_id: impl Into<String>) -> Self {
-        let now = Utc::now().timestamp();
+        let now = crate::time::now().timestamp();
         Self {
             login_id: login_id.into(),
             iss: None,
@@ -160,7 +247,7 @@ impl JwtClaims {
     ///
     /// * `seconds` - Seconds until expiration | 到期秒数
     pub fn set_expiration(&mut self, seconds: i64) -> &mut Self {
-        let exp_time = Utc::now() + Duration::seconds(seconds);
+        let exp_time = crate::time::now() + Duration::seconds(seconds);
         self.exp = Some(exp_time.timestamp());
         self
     }
@@ -226,7 +313,7 @@ impl JwtClaims {
     /// Check if token is expired | 检查 token 是否过期
     pub fn is_expired(&self) -> bool {
         if let Some(exp) = self.exp {
-            let now = Utc::now().timestamp();
+            let now = crate::time::now().timestamp();
             now >= exp
         } else {
             false
@@ -236,12 +323,35 @@ impl JwtClaims {
     /// Get remaining time in seconds | 获取剩余时间（秒）
     pub fn remaining_time(&self) -> Option<i64> {
         self.exp.map(|exp| {
-            let now = Utc::now().timestamp();
+            let now = crate::time::now().timestamp();
             (exp - now).max(0)
         })
     }
 }
 
+/// HMAC secret with an activation time | 带生效时间的 HMAC 密钥
+///
+/// Signs new tokens from `active_from` on and verifies tokens at any time.
+/// 自 `active_from` 起用于签名，任何时候都可用于验证。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JwtSecret {
+    /// Secret key | 密钥
+    pub secret: String,
+
+    /// Activation time, Unix seconds | 生效时间（Unix 秒）
+    pub active_from: i64,
+}
+
+impl JwtSecret {
+    /// Create a secret active from `active_from` | 创建自 `active_from` 起生效的密钥
+    pub fn new(secret: impl Into<String>, active_from: DateTime<Utc>) -> Self {
+        Self {
+            secret: secret.into(),
+            active_from: active_from.timestamp(),
+        }
+    }
+}
+
 /// JWT Manager | JWT 管理器
 ///
 /// Manages JWT token generation, validation, and parsing
@@ -259,6 +369,21 @@ pub struct JwtManager {
 
     /// Audience | 受众
     audience: Option<String>,
+
+    /// PEM private key for RS*/ES*/EdDSA signing | RS*/ES*/EdDSA 签名用的 PEM 私钥
+    private_key_pem: Option<String>,
+
+    /// PEM public key for RS*/ES*/EdDSA verification | RS*/ES*/EdDSA 验证用的 PEM 公钥
+    public_key_pem: Option<String>,
+
+    /// Key ID written to the `kid` header | 写入 `kid` 头的密钥 ID
+    key_id: Option<String>,
+
+    /// Additional HMAC secrets, oldest activation first | 额外的 HMAC 密钥，按生效时间排序
+    rotation_secrets: Vec<JwtSecret>,
+
+    /// Tokens verified by a secret that no longer signs | 由已不再签名的密钥验证通过的 token 数
+    legacy_verifications: Arc<AtomicU64>,
 }
 
 impl JwtManager {
@@ -268,12 +393,7 @@ impl JwtManager {
     ///
     /// * `secret` - Secret key | 密钥
     pub fn new(secret: impl Into<String>) -> Self {
-        Self {
-            secret: secret.into(),
-            algorithm: JwtAlgorithm::HS256,
-            issuer: None,
-            audience: None,
-        }
+        Self::with_algorithm(secret, JwtAlgorithm::HS256)
     }
 
     /// Create JWT manager with custom algorithm | 创建使用自定义算法的 JWT 管理器
@@ -283,9 +403,138 @@ impl JwtManager {
             algorithm,
             issuer: None,
             audience: None,
+            private_key_pem: None,
+            public_key_pem: None,
+            key_id: None,
+            rotation_secrets: Vec::new(),
+            legacy_verifications: Arc::new(AtomicU64::new(0)),
         }
     }
 
+    /// Create JWT manager for an asymmetric algorithm | 创建使用非对称算法的 JWT 管理器
+    ///
+    /// Set the keys with `set_private_key_pem` / `set_public_key_pem`.
+    /// 通过 `set_private_key_pem` / `set_public_key_pem` 设置密钥。
+    pub fn asymmetric(algorithm: JwtAlgorithm) -> Self {
+        Self::with_algorithm(String::new(), algorithm)
+    }
+
+    /// Create a verify-only JWT manager from a public key | 创建只持有公钥、仅用于验证的 JWT 管理器
+    pub fn verifier(algorithm: JwtAlgorithm, public_key_pem: impl Into<String>) -> Self {
+        Self::asymmetric(algorithm).set_public_key_pem(public_key_pem)
+    }
+
+    /// Create JWT manager from configuration | 根据配置创建 JWT 管理器
+    ///
+    /// HMAC algorithms require `jwt_secret_key`; RS*/ES*/EdDSA read the PEM keys
+    /// from `jwt_private_key_pem` / `jwt_public_key_pem`, or from the
+    /// `*_path` fields when the PEM text is not set.
+    /// HMAC 算法需要 `jwt_secret_key`；RS*/ES*/EdDSA 从 `jwt_private_key_pem` /
+    /// `jwt_public_key_pem` 读取 PEM 密钥，未设置时读取对应的 `*_path` 文件。
+    #[cfg(feature = "runtime")]
+    pub fn from_config(config: &crate::config::SaTokenConfig) -> SaTokenResult<Self> {
+        let algorithm = match config.jwt_algorithm.as_deref() {
+            Some(name) => JwtAlgorithm::parse(name).ok_or_else(|| {
+                SaTokenError::ConfigError(format!("Unsupported JWT algorithm: {}", name))
+            })?,
+            None => JwtAlgorithm::HS256,
+        };
+
+        let mut manager = if algorithm.is_symmetric() {
+            let secret = config.jwt_secret_key.as_ref().ok_or_else(|| {
+                SaTokenError::ConfigError("JWT secret key is required for HMAC algorithms".to_string())
+            })?;
+            let mut manager = Self::with_algorithm(secret, algorithm);
+            for secret in &config.jwt_rotation_secrets {
+                manager = manager.add_jwt_secret(secret.clone());
+            }
+            manager
+        } else {
+            let mut manager = Self::asymmetric(algorithm);
+            manager.private_key_pem = config.jwt_private_key()?;
+            manager.public_key_pem = config.jwt_public_key()?;
+            if manager.private_key_pem.is_none() && manager.public_key_pem.is_none() {
+                return Err(SaTokenError::ConfigError(format!(
+                    "{} requires jwt_private_key_pem or jwt_public_key_pem", algorithm.name()
+                )));
+            }
+            manager
+        };
+
+        manager.key_id = config.jwt_key_id.clone();
+        if let Some(ref issuer) = config.jwt_issuer {
+            manager = manager.set_issuer(issuer);
+        }
+        if let Some(ref audience) = config.jwt_audience {
+            manager = manager.set_audience(audience);
+        }
+        Ok(manager)
+    }
+
+    /// Algorithm in use | 使用的算法
+    pub fn algorithm(&self) -> JwtAlgorithm {
+        self.algorithm
+    }
+
+    /// Key ID, if set | 密钥 ID（如有）
+    pub fn key_id(&self) -> Option<&str> {
+        self.key_id.as_deref()
+    }
+
+    /// Set PEM private key (PKCS#8, or PKCS#1 for RSA) | 设置 PEM 私钥（PKCS#8，RSA 也可用 PKCS#1）
+    pub fn set_private_key_pem(mut self, pem: impl Into<String>) -> Self {
+        self.private_key_pem = Some(pem.into());
+        self
+    }
+
+    /// Set PEM public key (SubjectPublicKeyInfo) | 设置 PEM 公钥（SubjectPublicKeyInfo）
+    pub fn set_public_key_pem(mut self, pem: impl Into<String>) -> Self {
+        self.public_key_pem = Some(pem.into());
+        self
+    }
+
+    /// Set key ID (`kid` header and JWK) | 设置密钥 ID（`kid` 头及 JWK）
+    pub fn set_key_id(mut self, kid: impl Into<String>) -> Self {
+        self.key_id = Some(kid.into());
+        self
+    }
+
+    /// Add an HMAC secret that signs from `active_from` on | 添加自 `active_from` 起用于签名的 HMAC 密钥
+    ///
+    /// Until then the current secret keeps signing. Every secret added here,
+    /// and the one passed to `new`, stays valid for verification, so tokens
+    /// signed before the switch survive it.
+    /// 在此之前仍由当前密钥签名。这里添加的密钥及 `new` 传入的密钥都可用于验证，
+    /// 切换前签发的 token 在切换后依然有效。
+    pub fn add_secret(self, secret: impl Into<String>, active_from: DateTime<Utc>) -> Self {
+        self.add_jwt_secret(JwtSecret::new(secret, active_from))
+    }
+
+    /// Add an HMAC secret | 添加 HMAC 密钥
+    pub fn add_jwt_secret(mut self, secret: JwtSecret) -> Self {
+        self.rotation_secrets.push(secret);
+        self.rotation_secrets.sort_by_key(|s| s.active_from);
+        self
+    }
+
+    /// Number of tokens accepted only by a secret that no longer signs | 仅被已停止签名的密钥验证通过的 token 数
+    ///
+    /// Shared by clones. Once it stops growing for a full token lifetime, the
+    /// old secret can be removed without logging anyone out.
+    /// 克隆体共享该计数。在一个完整 token 有效期内不再增长时，即可移除旧密钥而不影响任何用户。
+    pub fn legacy_verifications(&self) -> u64 {
+        self.legacy_verifications.load(Ordering::Relaxed)
+    }
+
+    /// HMAC secret that signs now | 当前用于签名的 HMAC 密钥
+    fn signing_secret(&self) -> &str {
+        let now = crate::time::now().timestamp();
+        self.rotation_secrets.iter()
+            .rev()
+            .find(|s| s.active_from <= now)
+            .map_or(self.secret.as_str(), |s| s.secret.as_str())
+    }
+
     /// Set issuer | 设置签发者
     pub fn set_issuer(mut self, issuer: impl Into<String>) -> Self {
         self.issuer = Some(issuer.into());
@@ -319,8 +568,9 @@ impl JwtManager {
             final_claims.aud = self.audience.clone();
         }
 
-        let header = Header::new(self.algorithm.into());
-        let encoding_key = EncodingKey::from_secret(self.secret.as_bytes());
+        let mut header = Header::new(self.algorithm.into());
+        header.kid = self.key_id.clone();
+        let encoding_key = self.encoding_key()?;
 
         encode(&header, &final_claims, &encoding_key).map_err(|e| {
             SaTokenError::InvalidToken(format!("Failed to generate JWT: {}", e))
@@ -339,8 +589,10 @@ impl JwtManager {
     pub fn validate(&self, token: &str) -> SaTokenResult<JwtClaims> {
         let mut validation = Validation::new(self.algorithm.into());
 
-        // Explicitly enable expiration validation | 明确启用过期验证
-        validation.validate_exp = true;
+        // Expiration is checked below against `crate::time::now()` so the test
+        // clock applies; `exp` is still required.
+        // 过期时间在下方按 `crate::time::now()` 检查，以便测试时钟生效；`exp` 仍为必填
+        validation.validate_exp = false;
         
         // Set leeway to 0 for strict validation | 设置时间偏差为0以进行严格验证
         validation.leeway = 0;
@@ -353,9 +605,30 @@ impl JwtManager {
             validation.set_audience(&[aud]);
         }
 
-        let decoding_key = DecodingKey::from_secret(self.secret.as_bytes());
+        let decoding_key = self.decoding_key()?;
+
+        let mut result = decode::<JwtClaims>(token, &decoding_key, &validation);
+
+        // Fall back to the other rotation secrets on a signature mismatch
+        // 签名不匹配时依次尝试其余轮换密钥
+        if self.algorithm.is_symmetric()
+            && !self.rotation_secrets.is_empty()
+            && let Err(ref e) = result
+            && matches!(e.kind(), jsonwebtoken::errors::ErrorKind::InvalidSignature)
+        {
+            let signing = self.signing_secret();
+            let fallback = std::iter::once(self.secret.as_str())
+                .chain(self.rotation_secrets.iter().rev().map(|s| s.secret.as_str()))
+                .filter(|secret| *secret != signing)
+                .find_map(|secret| decode::<JwtClaims>(token, &DecodingKey::from_secret(secret.as_bytes()), &validation).ok());
+            if let Some(token_data) = fallback {
+                let count = self.legacy_verifications.fetch_add(1, Ordering::Relaxed) + 1;
+                tracing::debug!("Sa-Token: JWT 由非当前签名密钥验证通过，login_id: {}, 累计: {}", token_data.claims.login_id, count);
+                result = Ok(token_data);
+            }
+        }
 
-        let token_data = decode::<JwtClaims>(token, &decoding_key, &validation).map_err(|e| {
+        let token_data = result.map_err(|e| {
             match e.kind() {
                 jsonwebtoken::errors::ErrorKind::ExpiredSignature => {
                     SaTokenError::TokenExpired
@@ -364,6 +637,10 @@ impl JwtManager {
             }
         })?;
 
+        if token_data.claims.is_expired() {
+            return Err(SaTokenError::TokenExpired);
+        }
+
         Ok(token_data.claims)
     }
 
@@ -401,7 +678,7 @@ impl JwtManager {
         claims.set_expiration(extend_seconds);
 
         // Update issued at time | 更新签发时间
-        claims.iat = Some(Utc::now().timestamp());
+        claims.iat = Some(crate::time::now().timestamp());
 
         self.generate(&claims)
     }
@@ -414,6 +691,125 @@ impl JwtManager {
         let claims = self.decode_without_validation(token)?;
         Ok(claims.login_id)
     }
+
+    /// Public key as a JWK | 以 JWK 形式导出公钥
+    ///
+    /// Only available for RS*/ES*/EdDSA; HMAC secrets are never exported.
+    /// 仅适用于 RS*/ES*/EdDSA；HMAC 密钥不会被导出。
+    pub fn public_jwk(&self) -> SaTokenResult<Value> {
+        if self.algorithm.is_symmetric() {
+            return Err(SaTokenError::ConfigError("HMAC secrets cannot be exported as a JWK".to_string()));
+        }
+        let pem = self.public_key_pem.as_deref().ok_or_else(|| {
+            SaTokenError::ConfigError(format!("{} requires a public key", self.algorithm.name()))
+        })?;
+        let key = public_key_bits(pem)?;
+
+        let mut jwk = match self.algorithm {
+            JwtAlgorithm::RS256 | JwtAlgorithm::RS384 | JwtAlgorithm::RS512 => {
+                let (n, e) = rsa_components(&key)?;
+                json!({ "kty": "RSA", "n": n, "e": e })
+            }
+            JwtAlgorithm::ES256 | JwtAlgorithm::ES384 => {
+                let (crv, size) = if self.algorithm == JwtAlgorithm::ES256 { ("P-256", 32) } else { ("P-384", 48) };
+                // Uncompressed point: 0x04 || x || y | 未压缩点格式
+                if key.len() != 1 + 2 * size || key[0] != 0x04 {
+                    return Err(SaTokenError::ConfigError(format!("Public key is not an uncompressed {} point", crv)));
+                }
+                json!({
+                    "kty": "EC",
+                    "crv": crv,
+                    "x": URL_SAFE_NO_PAD.encode(&key[1..=size]),
+                    "y": URL_SAFE_NO_PAD.encode(&key[size + 1..]),
+                })
+            }
+            JwtAlgorithm::EdDSA => json!({ "kty": "OKP", "crv": "Ed25519", "x": URL_SAFE_NO_PAD.encode(&key) }),
+            _ => unreachable!("symmetric algorithms are rejected above"),
+        };
+
+        jwk["use"] = json!("sig");
+        jwk["alg"] = json!(self.algorithm.name());
+        if let Some(ref kid) = self.key_id {
+            jwk["kid"] = json!(kid);
+        }
+        Ok(jwk)
+    }
+
+    /// Public key as a JWK Set (`{"keys": [...]}`) | 以 JWK Set 形式导出公钥
+    pub fn jwks(&self) -> SaTokenResult<Value> {
+        Ok(json!({ "keys": [self.public_jwk()?] }))
+    }
+
+    /// Key used for signing | 签名使用的密钥
+    fn encoding_key(&self) -> SaTokenResult<EncodingKey> {
+        if self.algorithm.is_symmetric() {
+            return Ok(EncodingKey::from_secret(self.signing_secret().as_bytes()));
+        }
+        let pem = self.private_key_pem.as_deref().ok_or_else(|| {
+            SaTokenError::ConfigError(format!("{} requires a private key to sign", self.algorithm.name()))
+        })?;
+        let key = match self.algorithm {
+            JwtAlgorithm::ES256 | JwtAlgorithm::ES384 => EncodingKey::from_ec_pem(pem.as_bytes()),
+            JwtAlgorithm::EdDSA => EncodingKey::from_ed_pem(pem.as_bytes()),
+            _ => EncodingKey::from_rsa_pem(pem.as_bytes()),
+        };
+        key.map_err(|e| SaTokenError::ConfigError(format!("Invalid JWT private key: {}", e)))
+    }
+
+    /// Key used for verification | 验证使用的密钥
+    fn decoding_key(&self) -> SaTokenResult<DecodingKey> {
+        if self.algorithm.is_symmetric() {
+            return Ok(DecodingKey::from_secret(self.signing_secret().as_bytes()));
+        }
+        let pem = self.public_key_pem.as_deref().ok_or_else(|| {
+            SaTokenError::ConfigError(format!("{} requires a public key to verify", self.algorithm.name()))
+        })?;
+        let key = match self.algorithm {
+            JwtAlgorithm::ES256 | JwtAlgorithm::ES384 => DecodingKey::from_ec_pem(pem.as_bytes()),
+            JwtAlgorithm::EdDSA => DecodingKey::from_ed_pem(pem.as_bytes()),
+            _ => DecodingKey::from_rsa_pem(pem.as_bytes()),
+        };
+        key.map_err(|e| SaTokenError::ConfigError(format!("Invalid JWT public key: {}", e)))
+    }
+}
+
+/// Raw key bytes of a PEM public key | PEM 公钥中的原始密钥
+///
+/// `PUBLIC KEY` (SubjectPublicKeyInfo) yields the subjectPublicKey bit string;
+/// `RSA PUBLIC KEY` (PKCS#1) is returned as is.
+fn public_key_bits(pem_text: &str) -> SaTokenResult<Vec<u8>> {
+    let invalid = |reason: String| SaTokenError::ConfigError(format!("Invalid JWT public key: {}", reason));
+    let pem = pem::parse(pem_text).map_err(|e| invalid(e.to_string()))?;
+    match pem.tag() {
+        "RSA PUBLIC KEY" => Ok(pem.into_contents()),
+        "PUBLIC KEY" => {
+            let blocks = simple_asn1::from_der(pem.contents()).map_err(|e| invalid(e.to_string()))?;
+            match blocks.first() {
+                Some(ASN1Block::Sequence(_, items)) => match items.get(1) {
+                    Some(ASN1Block::BitString(_, _, bits)) => Ok(bits.clone()),
+                    _ => Err(invalid("missing subjectPublicKey".to_string())),
+                },
+                _ => Err(invalid("malformed SubjectPublicKeyInfo".to_string())),
+            }
+        }
+        tag => Err(invalid(format!("unexpected PEM tag {}", tag))),
+    }
+}
+
+/// Base64url modulus and exponent of a PKCS#1 RSA public key | PKCS#1 RSA 公钥的模数与指数
+fn rsa_components(der: &[u8]) -> SaTokenResult<(String, String)> {
+    let invalid = || SaTokenError::ConfigError("Invalid RSA public key".to_string());
+    let blocks = simple_asn1::from_der(der).map_err(|_| invalid())?;
+    match blocks.first() {
+        Some(ASN1Block::Sequence(_, items)) => match items.as_slice() {
+            [ASN1Block::Integer(_, n), ASN1Block::Integer(_, e)] => Ok((
+                URL_SAFE_NO_PAD.encode(n.to_bytes_be().1),
+                URL_SAFE_NO_PAD.encode(e.to_bytes_be().1),
+            )),
+            _ => Err(invalid()),
+        },
+        _ => Err(invalid()),
+    }
 }
 
 #[cfg(test)]
@@ -460,7 +856,7 @@ mod tests {
         let mut claims = JwtClaims::new("user_123");
         // Set expiration to 10 seconds in the past to account for leeway
         // 设置过期时间为10秒前以考虑时间偏差
-        let exp_time = Utc::now() - Duration::seconds(10);
+        let exp_time = crate::time::now() - Duration::seconds(10);
         claims.set_expiration_at(exp_time);
 
         let token = jwt_manager.generate(&claims).unwrap();
@@ -513,6 +909,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_claims_without_extra_round_trip() {
+        // 空的 extra 不会被序列化，解码时必须能缺省 | An empty `extra` is skipped on encode and must default on decode
+        let jwt_manager = JwtManager::new("test-secret-key");
+
+        let mut claims = JwtClaims::new("user_123");
+        claims.set_expiration(3600);
+
+        let json = serde_json::to_value(&claims).unwrap();
+        assert!(json.get("extra").is_none());
+
+        let decoded: JwtClaims = serde_json::from_value(json).unwrap();
+        assert!(decoded.extra.is_empty());
+
+        let token = jwt_manager.generate(&claims).unwrap();
+        assert!(jwt_manager.validate(&token).unwrap().extra.is_empty());
+    }
+
     #[test]
     fn test_extract_login_id() {
         let jwt_manager = JwtManager::new("test-secret-key");
@@ -525,5 +939,157 @@ mod tests {
 
         assert_eq!(login_id, "user_123");
     }
+
+    #[test]
+    fn test_asymmetric_sign_and_verify_with_public_key() {
+        let cases = [
+            (JwtAlgorithm::RS256, include_str!("testdata/rsa_private.pem"), include_str!("testdata/rsa_public.pem")),
+            (JwtAlgorithm::ES256, include_str!("testdata/ec_private.pem"), include_str!("testdata/ec_public.pem")),
+            (JwtAlgorithm::EdDSA, include_str!("testdata/ed_private.pem"), include_str!("testdata/ed_public.pem")),
+        ];
+
+        for (algorithm, private_pem, public_pem) in cases {
+            let issuer = JwtManager::asymmetric(algorithm)
+                .set_private_key_pem(private_pem)
+                .set_key_id("k1");
+            let mut claims = JwtClaims::new("user_123");
+            claims.set_expiration(3600);
+            let token = issuer.generate(&claims).unwrap();
+            assert_eq!(jsonwebtoken::decode_header(&token).unwrap().kid.as_deref(), Some("k1"));
+
+            // Resource server holds only the public key | 资源服务只持有公钥
+            let verifier = JwtManager::verifier(algorithm, public_pem);
+            assert_eq!(verifier.validate(&token).unwrap().login_id, "user_123");
+            assert!(verifier.generate(&claims).is_err());
+
+            // A token signed with a shared secret is rejected | 共享密钥签名的 token 被拒绝
+            let forged = JwtManager::new("secret").generate(&claims).unwrap();
+            assert!(verifier.validate(&forged).is_err());
+        }
+    }
+
+    #[test]
+    fn test_public_jwk_export() {
+        let rsa = JwtManager::verifier(JwtAlgorithm::RS256, include_str!("testdata/rsa_public.pem"))
+            .set_key_id("rsa-1");
+        let jwk = rsa.public_jwk().unwrap();
+        assert_eq!(jwk["kty"], "RSA");
+        assert_eq!(jwk["e"], "AQAB");
+        assert_eq!(jwk["alg"], "RS256");
+        assert_eq!(jwk["kid"], "rsa-1");
+        // 2048-bit modulus, base64url without padding | 2048 位模数
+        assert_eq!(jwk["n"].as_str().unwrap().len(), 342);
+
+        let ec = JwtManager::verifier(JwtAlgorithm::ES256, include_str!("testdata/ec_public.pem"));
+        let jwk = ec.public_jwk().unwrap();
+        assert_eq!(jwk["crv"], "P-256");
+        assert_eq!(jwk["x"].as_str().unwrap().len(), 43);
+        assert_eq!(jwk["y"].as_str().unwrap().len(), 43);
+
+        let ed = JwtManager::verifier(JwtAlgorithm::EdDSA, include_str!("testdata/ed_public.pem"));
+        assert_eq!(ed.jwks().unwrap()["keys"][0]["kty"], "OKP");
+
+        assert!(JwtManager::new("secret").public_jwk().is_err());
+    }
+
+    #[test]
+    fn test_secret_rotation_keeps_old_tokens_valid() {
+        let activate_at = crate::time::now() + Duration::hours(1);
+        let jwt = JwtManager::new("old-secret").add_secret("new-secret", activate_at);
+        let mut claims = JwtClaims::new("user_123");
+        claims.set_expiration(7200);
+
+        // Before activation the old secret signs | 生效前仍由旧密钥签名
+        let old_token = jwt.generate(&claims).unwrap();
+        assert!(JwtManager::new("old-secret").validate(&old_token).is_ok());
+        assert!(jwt.validate(&old_token).is_ok());
+        assert_eq!(jwt.legacy_verifications(), 0);
+
+        crate::time::advance(Duration::hours(1));
+        let new_token = jwt.generate(&claims).unwrap();
+        assert!(JwtManager::new("new-secret").validate(&new_token).is_ok());
+
+        // Old token still verifies, counted as legacy | 旧 token 仍可验证，计入旧密钥统计
+        assert!(jwt.validate(&new_token).is_ok());
+        assert_eq!(jwt.clone().validate(&old_token).unwrap().login_id, "user_123");
+        assert_eq!(jwt.legacy_verifications(), 1);
+        assert!(JwtManager::new("new-secret").validate(&old_token).is_err());
+        crate::time::reset();
+    }
+
+    #[cfg(feature = "runtime")]
+    #[test]
+    fn test_rotation_secrets_from_config() {
+        use crate::config::SaTokenConfig;
+
+        let now = crate::time::now().timestamp();
+        let config = SaTokenConfig::builder()
+            .jwt_secret_key("old-secret")
+            .jwt_rotation_secret("new-secret", now - 1)
+            .jwt_rotation_secret("next-secret", now + 3600)
+            .build_config();
+        let jwt = JwtManager::from_config(&config).unwrap();
+
+        let mut claims = JwtClaims::new("user_123");
+        claims.set_expiration(3600);
+        let token = jwt.generate(&claims).unwrap();
+        assert!(JwtManager::new("new-secret").validate(&token).is_ok());
+
+        let legacy = JwtManager::new("old-secret").generate(&claims).unwrap();
+        let forged = JwtManager::new("unknown-secret").generate(&claims).unwrap();
+        assert!(jwt.validate(&legacy).is_ok());
+        assert!(jwt.validate(&forged).is_err());
+        assert_eq!(jwt.legacy_verifications(), 1);
+    }
+
+    #[cfg(feature = "runtime")]
+    #[tokio::test]
+    async fn test_login_signs_with_key_file_from_config() {
+        use crate::config::{SaTokenConfig, TokenStyle};
+        use crate::SaTokenManager;
+
+        let dir = std::env::temp_dir().join(format!("sa-token-jwt-{}", uuid::Uuid::new_v4().simple()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let private_path = dir.join("private.pem");
+        std::fs::write(&private_path, include_str!("testdata/ec_private.pem")).unwrap();
+
+        let config = SaTokenConfig::builder()
+            .token_style(TokenStyle::Jwt)
+            .jwt_algorithm("ES256")
+            .jwt_private_key_path(private_path.to_string_lossy())
+            .jwt_key_id("ec-1")
+            .build_config();
+        let manager = SaTokenManager::new(
+            std::sync::Arc::new(sa_token_storage_memory::MemoryStorage::new()),
+            config,
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // Key file was loaded once at startup | 密钥文件在启动时读取一次
+        let token = manager.login("user_123").await.unwrap();
+        let verifier = JwtManager::verifier(JwtAlgorithm::ES256, include_str!("testdata/ec_public.pem"));
+        assert_eq!(verifier.validate(token.as_str()).unwrap().login_id, "user_123");
+        assert_eq!(jsonwebtoken::decode_header(token.as_str()).unwrap().kid.as_deref(), Some("ec-1"));
+    }
+
+    #[cfg(feature = "runtime")]
+    #[tokio::test]
+    async fn test_login_without_signing_key_is_a_config_error() {
+        use crate::config::{SaTokenConfig, TokenStyle};
+        use crate::SaTokenManager;
+
+        // 资源服务只配置公钥，不能签发 token | A verifier-only config cannot issue tokens
+        let config = SaTokenConfig::builder()
+            .token_style(TokenStyle::Jwt)
+            .jwt_algorithm("ES256")
+            .jwt_public_key_pem(include_str!("testdata/ec_public.pem"))
+            .build_config();
+        let manager = SaTokenManager::new(
+            std::sync::Arc::new(sa_token_storage_memory::MemoryStorage::new()),
+            config,
+        );
+
+        assert!(matches!(manager.login("user_123").await, Err(SaTokenError::ConfigError(_))));
+    }
 }
 
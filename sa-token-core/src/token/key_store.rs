@@ -0,0 +1,230 @@
+// Author: 金书记
+//
+//! JWT Key Store | JWT 密钥库
+//!
+//! Holds several signing keys identified by `kid`, so keys can be rotated
+//! without invalidating tokens signed by the previous key. New tokens are
+//! signed with the current key; validation picks the key named by the token's
+//! `kid` header. The public keys are published as a JWK Set.
+//! 按 `kid` 管理多把签名密钥，轮换密钥时旧密钥签发的 token 仍然有效：新 token
+//! 使用当前密钥签名，验证时按 token 头中的 `kid` 选择密钥。公钥以 JWK Set 形式发布。
+//!
+//! ## Usage Example | 使用示例
+//!
+//! ```rust,ignore
+//! let store = JwtKeyStore::new(
+//!     JwtManager::asymmetric(JwtAlgorithm::ES256)
+//!         .set_private_key_pem(key_2024_01)
+//!         .set_public_key_pem(pub_2024_01)
+//!         .set_key_id("2024-01"),
+//! )?;
+//!
+//! let token = store.generate(&claims)?;
+//!
+//! // Rotate: "2024-02" signs from now on, "2024-01" still verifies
+//! // 轮换：之后由 "2024-02" 签名，"2024-01" 仍可验证
+//! store.rotate_key(next_manager.set_key_id("2024-02"))?;
+//! assert!(store.validate(&token).is_ok());
+//!
+//! // Serve at /.well-known/jwks.json | 挂在 /.well-known/jwks.json
+//! let body = store.to_jwks_json()?;
+//! ```
+
+use std::sync::{Arc, RwLock};
+use serde_json::{json, Value};
+
+use crate::error::{SaTokenError, SaTokenResult};
+use super::jwt::{JwtClaims, JwtManager};
+
+/// Default number of keys kept after rotation | 轮换后默认保留的密钥数量
+pub const DEFAULT_MAX_KEYS: usize = 2;
+
+/// JWT key store with rotation | 支持轮换的 JWT 密钥库
+///
+/// Cheap to clone; clones share the same keys.
+/// 克隆开销很小，克隆体共享同一组密钥。
+#[derive(Clone)]
+pub struct JwtKeyStore {
+    /// Oldest first; the last key signs | 按加入顺序排列，最后一把用于签名
+    keys: Arc<RwLock<Vec<JwtManager>>>,
+    max_keys: usize,
+}
+
+impl JwtKeyStore {
+    /// Create a key store with an initial signing key | 以初始签名密钥创建密钥库
+    ///
+    /// The key must have a key ID (`set_key_id`).
+    /// 密钥必须设置了密钥 ID（`set_key_id`）。
+    pub fn new(initial: JwtManager) -> SaTokenResult<Self> {
+        require_kid(&initial)?;
+        Ok(Self {
+            keys: Arc::new(RwLock::new(vec![initial])),
+            max_keys: DEFAULT_MAX_KEYS,
+        })
+    }
+
+    /// Set how many keys are kept, including the current one | 设置保留的密钥数量（含当前密钥）
+    pub fn with_max_keys(mut self, max_keys: usize) -> Self {
+        self.max_keys = max_keys.max(1);
+        self
+    }
+
+    /// Make `next` the signing key | 把 `next` 设为签名密钥
+    ///
+    /// Previous keys stay available for verification until more than
+    /// `max_keys` keys are held, then the oldest are dropped. Rotating to a
+    /// `kid` already in the store replaces that key.
+    /// 旧密钥继续用于验证，超过 `max_keys` 时丢弃最旧的密钥。轮换到已存在的
+    /// `kid` 时替换该密钥。
+    pub fn rotate_key(&self, next: JwtManager) -> SaTokenResult<()> {
+        let kid = require_kid(&next)?.to_string();
+        let mut keys = self.keys.write().unwrap();
+        keys.retain(|key| key.key_id() != Some(kid.as_str()));
+        keys.push(next);
+        let excess = keys.len().saturating_sub(self.max_keys);
+        keys.drain(..excess);
+        Ok(())
+    }
+
+    /// Remove a verification key | 移除一把验证密钥
+    ///
+    /// The current signing key cannot be removed; rotate first.
+    /// 当前签名密钥不能移除，需先轮换。
+    pub fn retire_key(&self, kid: &str) -> SaTokenResult<()> {
+        let mut keys = self.keys.write().unwrap();
+        if keys.last().and_then(JwtManager::key_id) == Some(kid) {
+            return Err(SaTokenError::ConfigError(format!("Key {} is the current signing key", kid)));
+        }
+        keys.retain(|key| key.key_id() != Some(kid));
+        Ok(())
+    }
+
+    /// Key ID of the current signing key | 当前签名密钥的 ID
+    pub fn current_kid(&self) -> String {
+        let keys = self.keys.read().unwrap();
+        keys.last().and_then(JwtManager::key_id).unwrap_or_default().to_string()
+    }
+
+    /// Key IDs held, oldest first | 持有的密钥 ID，按加入顺序
+    pub fn kids(&self) -> Vec<String> {
+        let keys = self.keys.read().unwrap();
+        keys.iter().filter_map(JwtManager::key_id).map(str::to_string).collect()
+    }
+
+    /// Sign with the current key | 使用当前密钥签发
+    pub fn generate(&self, claims: &JwtClaims) -> SaTokenResult<String> {
+        let current = self.keys.read().unwrap().last().cloned();
+        match current {
+            Some(manager) => manager.generate(claims),
+            None => Err(SaTokenError::ConfigError("JWT key store is empty".to_string())),
+        }
+    }
+
+    /// Validate with the key named by the `kid` header | 按 `kid` 头选择密钥验证
+    ///
+    /// Tokens without `kid` are checked against the current key.
+    /// 不带 `kid` 的 token 使用当前密钥验证。
+    pub fn validate(&self, token: &str) -> SaTokenResult<JwtClaims> {
+        let header = jsonwebtoken::decode_header(token)
+            .map_err(|e| SaTokenError::InvalidToken(format!("Invalid JWT header: {}", e)))?;
+
+        let manager = {
+            let keys = self.keys.read().unwrap();
+            match header.kid.as_deref() {
+                Some(kid) => keys.iter().find(|key| key.key_id() == Some(kid)).cloned(),
+                None => keys.last().cloned(),
+            }
+        };
+        match manager {
+            Some(manager) => manager.validate(token),
+            None => Err(SaTokenError::InvalidToken(format!(
+                "Unknown JWT key id: {}", header.kid.unwrap_or_default()
+            ))),
+        }
+    }
+
+    /// Public keys as a JWK Set | 以 JWK Set 形式导出公钥
+    ///
+    /// HMAC keys are never exported and are skipped.
+    /// HMAC 密钥不会导出，直接跳过。
+    pub fn jwks(&self) -> SaTokenResult<Value> {
+        let keys = self.keys.read().unwrap();
+        let jwks = keys.iter()
+            .filter(|key| !key.algorithm().is_symmetric())
+            .map(JwtManager::public_jwk)
+            .collect::<SaTokenResult<Vec<_>>>()?;
+        Ok(json!({ "keys": jwks }))
+    }
+
+    /// JWK Set as a JSON string, for `/.well-known/jwks.json` | JWK Set 的 JSON 字符串
+    pub fn to_jwks_json(&self) -> SaTokenResult<String> {
+        Ok(serde_json::to_string(&self.jwks()?)?)
+    }
+}
+
+fn require_kid(manager: &JwtManager) -> SaTokenResult<&str> {
+    manager.key_id()
+        .ok_or_else(|| SaTokenError::ConfigError("Keys in a JwtKeyStore need a key id".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::token::jwt::JwtAlgorithm;
+
+    fn es256(kid: &str) -> JwtManager {
+        JwtManager::asymmetric(JwtAlgorithm::ES256)
+            .set_private_key_pem(include_str!("testdata/ec_private.pem"))
+            .set_public_key_pem(include_str!("testdata/ec_public.pem"))
+            .set_key_id(kid)
+    }
+
+    fn claims() -> JwtClaims {
+        let mut claims = JwtClaims::new("user_123");
+        claims.set_expiration(3600);
+        claims
+    }
+
+    #[test]
+    fn test_rotation_keeps_previous_key_for_verification() {
+        let store = JwtKeyStore::new(es256("k1")).unwrap();
+        let old_token = store.generate(&claims()).unwrap();
+
+        let rsa = JwtManager::asymmetric(JwtAlgorithm::RS256)
+            .set_private_key_pem(include_str!("testdata/rsa_private.pem"))
+            .set_public_key_pem(include_str!("testdata/rsa_public.pem"))
+            .set_key_id("k2");
+        store.rotate_key(rsa).unwrap();
+        assert_eq!(store.current_kid(), "k2");
+
+        let new_token = store.generate(&claims()).unwrap();
+        assert_eq!(jsonwebtoken::decode_header(&new_token).unwrap().kid.as_deref(), Some("k2"));
+        assert!(store.validate(&old_token).is_ok());
+        assert!(store.validate(&new_token).is_ok());
+
+        // Third key pushes k1 out | 第三把密钥挤掉 k1
+        store.rotate_key(es256("k3")).unwrap();
+        assert_eq!(store.kids(), vec!["k2", "k3"]);
+        assert!(store.validate(&old_token).is_err());
+        assert!(store.validate(&new_token).is_ok());
+
+        assert!(store.retire_key("k3").is_err());
+        store.retire_key("k2").unwrap();
+        assert!(store.validate(&new_token).is_err());
+        assert!(store.rotate_key(JwtManager::new("no-kid")).is_err());
+    }
+
+    #[test]
+    fn test_jwks_lists_public_keys() {
+        let store = JwtKeyStore::new(es256("k1")).unwrap().with_max_keys(3);
+        store.rotate_key(JwtManager::new("secret").set_key_id("hmac")).unwrap();
+        store.rotate_key(es256("k2")).unwrap();
+
+        let jwks: Value = serde_json::from_str(&store.to_jwks_json().unwrap()).unwrap();
+        let kids: Vec<&str> = jwks["keys"].as_array().unwrap().iter()
+            .map(|key| key["kid"].as_str().unwrap())
+            .collect();
+        assert_eq!(kids, vec!["k1", "k2"]);
+        assert_eq!(jwks["keys"][0]["kty"], "EC");
+    }
+}
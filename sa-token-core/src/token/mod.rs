@@ -4,14 +4,54 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use crate::permission::AuthzSnapshot;
 
+#[cfg(feature = "runtime")]
 pub mod generator;
 pub mod validator;
 pub mod jwt;
+pub mod key_store;
 
-pub use generator::TokenGenerator;
+#[cfg(feature = "runtime")]
+pub use generator::DefaultTokenGenerator;
 pub use validator::TokenValidator;
-pub use jwt::{JwtManager, JwtClaims, JwtAlgorithm};
+pub use jwt::{JwtManager, JwtClaims, JwtAlgorithm, JwtSecret};
+pub use key_store::JwtKeyStore;
+
+/// 自定义 token 生成器，通过 `TokenStyle::Custom` 注册 | Custom token generator, registered with `TokenStyle::Custom`
+/// 
+/// 闭包 `Fn(&str) -> String` 也实现了该 trait，参数为 login_id。
+/// Closures `Fn(&str) -> String` taking the login ID implement it too.
+/// 
+/// ```rust,ignore
+/// let config = SaTokenConfig::builder()
+///     .token_style(TokenStyle::custom(|login_id: &str| format!("{}-{}", login_id, Uuid::new_v4().simple())))
+///     .build_config();
+/// ```
+pub trait TokenGenerator: Send + Sync {
+    /// 为登录 ID 生成 token | Generate a token for the login ID
+    fn generate(&self, login_id: &str) -> String;
+    
+    /// 生成器名称，用于序列化配置与日志 | Name used when the config is serialized and in logs
+    fn name(&self) -> &str {
+        "custom"
+    }
+}
+
+impl<F> TokenGenerator for F
+where
+    F: Fn(&str) -> String + Send + Sync,
+{
+    fn generate(&self, login_id: &str) -> String {
+        self(login_id)
+    }
+}
+
+impl std::fmt::Debug for dyn TokenGenerator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TokenGenerator").field(&self.name()).finish()
+    }
+}
 
 /// Token 值
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -62,6 +102,7 @@ impl std::fmt::Display for TokenValue {
 /// - `nonce`: 防重放攻击的一次性令牌 | One-time token for replay attack prevention
 /// - `refresh_token`: 用于刷新的长期令牌 | Long-term token for refresh
 /// - `refresh_token_expire_time`: Refresh Token 过期时间 | Refresh token expiration time
+/// - `scope`: 受限 token 允许的 scope（None 表示不受限）| Scopes of a restricted token (None means unrestricted)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenInfo {
     /// Token 值 | Token value
@@ -96,11 +137,31 @@ pub struct TokenInfo {
     
     /// Refresh Token 过期时间 | Refresh Token expiration time
     pub refresh_token_expire_time: Option<DateTime<Utc>>,
+    
+    /// 受限 token 允许的 scope（None 表示不受限）| Scopes of a restricted token (None means unrestricted)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<Vec<String>>,
+    
+    /// 登录时预取的权限与角色（开启 `prefetch_authz` 时）| Authz prefetched at login (with `prefetch_authz`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authz: Option<AuthzSnapshot>,
+    
+    /// 登录请求的来源 IP | Client IP of the login request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub login_ip: Option<String>,
+    
+    /// 登录请求的 User-Agent | User-Agent of the login request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    
+    /// 是否"记住我"登录，决定写回的 Cookie 是否持久 | Whether the login was remembered, which decides if the cookie persists
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remember: Option<bool>,
 }
 
 impl TokenInfo {
     pub fn new(token: TokenValue, login_id: impl Into<String>) -> Self {
-        let now = Utc::now();
+        let now = crate::time::now();
         Self {
             token,
             login_id: login_id.into(),
@@ -113,19 +174,39 @@ impl TokenInfo {
             nonce: None,
             refresh_token: None,
             refresh_token_expire_time: None,
+            scope: None,
+            authz: None,
+            login_ip: None,
+            user_agent: None,
+            remember: None,
         }
     }
     
     pub fn is_expired(&self) -> bool {
-        if let Some(expire_time) = self.expire_time {
-            Utc::now() > expire_time
-        } else {
-            false
-        }
+        self.is_expired_at(crate::time::now())
+    }
+    
+    /// 在给定时刻是否已过期 | Whether the token is expired at `now`
+    pub fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
+        self.expire_time.is_some_and(|expire_time| now > expire_time)
     }
     
     pub fn update_active_time(&mut self) {
-        self.last_active_time = Utc::now();
+        self.last_active_time = crate::time::now();
+    }
+    
+    /// 是否为受限 token | Whether this is a restricted (scoped) token
+    pub fn is_restricted(&self) -> bool {
+        self.scope.is_some()
+    }
+    
+    /// 是否允许访问指定 scope（不受限的 token 允许所有 scope）
+    /// Whether the token may access `scope` (unrestricted tokens allow everything)
+    pub fn allows_scope(&self, scope: &str) -> bool {
+        match &self.scope {
+            Some(scopes) => scopes.iter().any(|s| s == scope),
+            None => true,
+        }
     }
 }
 
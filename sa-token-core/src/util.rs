@@ -20,17 +20,44 @@
 //! StpUtil::set_permissions(10001, vec!["user:list".to_string()]).await?;
 //! ```
 
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use std::fmt::Display;
-use once_cell::sync::OnceCell;
-use crate::{SaTokenManager, SaTokenResult, SaTokenError};
+use crate::{SaTokenManager, SaTokenConfig, SaTokenResult, SaTokenError};
 use crate::token::{TokenValue, TokenInfo};
-use crate::session::SaSession;
+use crate::login::{LoginOptions, LoginResult};
+use crate::session::{SaSession, SessionUsage};
 use crate::context::SaTokenContext;
 use crate::event::{SaTokenEventBus, SaTokenListener};
+use crate::read_only::ReadOnlyMode;
+use crate::permission::{AuthzKind, AuthzSnapshot, LintIssue};
+use crate::metrics::DenialCount;
+use crate::manager::DEFAULT_LOGIN_TYPE;
+use crate::stp_logic::StpLogic;
+use crate::render::{DefaultErrorRenderer, ErrorRenderer, ErrorResponse};
+use crate::same_token::SameTokenManager;
+use crate::audit::AuditLogger;
 
-/// 全局 SaTokenManager 实例
-static GLOBAL_MANAGER: OnceCell<Arc<SaTokenManager>> = OnceCell::new();
+/// 全局 SaTokenManager 实例与默认账号体系
+static GLOBAL: RwLock<Option<Arc<GlobalState>>> = RwLock::new(None);
+
+struct GlobalState {
+    manager: Arc<SaTokenManager>,
+    /// 默认账号体系，StpUtil 的登录与 Session 操作委托给它
+    default_logic: Arc<StpLogic>,
+}
+
+impl GlobalState {
+    fn new(manager: SaTokenManager) -> Arc<Self> {
+        Arc::new(Self {
+            default_logic: Arc::new(StpLogic::new(DEFAULT_LOGIN_TYPE, manager.clone())),
+            manager: Arc::new(manager),
+        })
+    }
+}
+
+fn global() -> Option<Arc<GlobalState>> {
+    GLOBAL.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
 
 /// LoginId trait - 支持任何可以转换为字符串的类型作为登录 ID
 /// 
@@ -56,22 +83,94 @@ impl StpUtil {
     
     /// 初始化全局 SaTokenManager（应用启动时调用一次）
     /// 
+    /// 幂等：已经初始化过时保留原有 Manager 并记录警告，不会 panic。
+    /// `SaTokenConfigBuilder::build()` 与 `SaTokenState::from_manager()` 已自动调用。
+    /// 
+    /// Idempotent: once initialized, the existing manager is kept and a warning
+    /// is logged instead of panicking.
+    /// 
     /// # 示例
     /// ```rust,ignore
-    /// let manager = SaTokenConfig::builder()
-    ///     .storage(Arc::new(MemoryStorage::new()))
-    ///     .build();
+    /// let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
     /// StpUtil::init_manager(manager);
     /// ```
     pub fn init_manager(manager: SaTokenManager) {
-        GLOBAL_MANAGER.set(Arc::new(manager))
-            .unwrap_or_else(|_| panic!("StpUtil manager already initialized"));
+        if !Self::try_init_manager(manager) {
+            tracing::warn!("Sa-Token: StpUtil 已初始化，忽略重复的 init_manager 调用 | StpUtil already initialized, ignoring init_manager");
+        }
+    }
+    
+    /// 尚未初始化时设置全局 Manager，返回是否设置成功
+    /// 
+    /// Sets the global manager unless one is already set; returns whether it was set.
+    pub fn try_init_manager(manager: SaTokenManager) -> bool {
+        let mut global = GLOBAL.write().unwrap_or_else(|e| e.into_inner());
+        if global.is_some() {
+            return false;
+        }
+        *global = Some(GlobalState::new(manager));
+        true
+    }
+    
+    /// 是否已初始化全局 Manager | Whether the global manager is initialized
+    pub fn is_initialized() -> bool {
+        global().is_some()
+    }
+    
+    /// 强制替换全局 Manager，供需要多个 Manager 的集成测试使用（需开启 `test-reinit` feature）
+    /// 
+    /// 已通过 `default_logic()` 取得的账号体系仍指向旧的 Manager。
+    /// 
+    /// Replaces the global manager, for integration tests that need several
+    /// managers in one process (requires the `test-reinit` feature). Account
+    /// systems already obtained from `default_logic()` keep the old manager.
+    #[cfg(any(test, feature = "test-reinit"))]
+    pub fn force_reinit_manager(manager: SaTokenManager) {
+        *GLOBAL.write().unwrap_or_else(|e| e.into_inner()) = Some(GlobalState::new(manager));
     }
     
     /// 获取全局 Manager
-    fn get_manager() -> &'static Arc<SaTokenManager> {
-        GLOBAL_MANAGER.get()
+    fn get_manager() -> Arc<SaTokenManager> {
+        global()
+            .expect("StpUtil not initialized. Call StpUtil::init_manager() first.")
+            .manager
+            .clone()
+    }
+    
+    /// 默认账号体系（登录类型 `default`），StpUtil 的登录与 Session 操作委托给它
+    pub fn default_logic() -> Arc<StpLogic> {
+        global()
             .expect("StpUtil not initialized. Call StpUtil::init_manager() first.")
+            .default_logic
+            .clone()
+    }
+    
+    /// 全局管理器上的另一个账号体系
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// let admin = StpUtil::logic("admin");
+    /// let token = admin.login(10001).await?;
+    /// ```
+    pub fn logic(login_type: impl Into<String>) -> StpLogic {
+        StpLogic::new(login_type, (*Self::get_manager()).clone())
+    }
+    
+    /// 开发模式免登录的假 login_id（未初始化或未开启时返回 None）
+    /// 
+    /// Fake login_id when dev bypass is active, `None` otherwise (or if not initialized).
+    pub fn dev_bypass_login_id() -> Option<String> {
+        global().and_then(|global| global.manager.dev_bypass_login_id())
+    }
+    
+    /// 用全局管理器配置的渲染器渲染鉴权错误，未初始化时使用默认渲染器
+    /// 
+    /// Renders an auth error with the global manager's renderer, or the default one if not initialized.
+    pub fn render_error(err: &SaTokenError) -> ErrorResponse {
+        match global() {
+            Some(global) => global.manager.render_error(err),
+            None => DefaultErrorRenderer.render(err),
+        }
     }
     
     /// 获取事件总线，用于注册监听器
@@ -93,8 +192,27 @@ impl StpUtil {
     /// // 注册监听器
     /// StpUtil::event_bus().register(Arc::new(MyListener)).await;
     /// ```
-    pub fn event_bus() -> &'static SaTokenEventBus {
-        &Self::get_manager().event_bus
+    /// 
+    /// 返回的总线与全局管理器共享监听器 | The returned bus shares listeners with the global manager
+    pub fn event_bus() -> SaTokenEventBus {
+        Self::get_manager().event_bus.clone()
+    }
+    
+    /// 运行时替换全局管理器的配置（见 `SaTokenManager::update_config`）
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// let mut config = (*StpUtil::config()).clone();
+    /// config.is_concurrent = false;
+    /// StpUtil::update_config(config).await;
+    /// ```
+    pub async fn update_config(config: SaTokenConfig) {
+        Self::get_manager().update_config(config).await;
+    }
+    
+    /// 全局管理器当前配置的快照
+    pub fn config() -> Arc<SaTokenConfig> {
+        Self::get_manager().config()
     }
     
     /// 注册事件监听器（便捷方法）
@@ -121,11 +239,73 @@ impl StpUtil {
     /// let token = StpUtil::login(10001_i64).await?;
     /// ```
     pub async fn login(login_id: impl LoginId) -> SaTokenResult<TokenValue> {
-        Self::get_manager().login(login_id.to_login_id()).await
+        Self::default_logic().login(login_id).await
     }
 
-    pub async fn login_with_type(login_id: impl LoginId, _login_type: impl Into<String>) -> SaTokenResult<TokenValue> {
-        Self::get_manager().login(login_id.to_login_id()).await
+    /// 以指定登录类型（账号体系）登录，见 `StpLogic`
+    pub async fn login_with_type(login_id: impl LoginId, login_type: impl Into<String>) -> SaTokenResult<TokenValue> {
+        Self::logic(login_type).login(login_id).await
+    }
+    
+    /// 按登录参数登录 | Login with options
+    /// 
+    /// 使用 `TokenStyle::Jwt` 时，自定义声明、登录类型、设备与有效期会写入生成的 JWT
+    /// 
+    /// # 示例 | Example
+    /// ```rust,ignore
+    /// let token = StpUtil::login_with_options(10001, LoginOptions::new()
+    ///     .login_type("admin")
+    ///     .timeout(3600)
+    ///     .claim("role", json!("admin"))
+    /// ).await?;
+    /// ```
+    pub async fn login_with_options(login_id: impl LoginId, options: LoginOptions) -> SaTokenResult<TokenValue> {
+        Self::get_manager().login_with(login_id.to_login_id(), options).await
+    }
+    
+    /// 登录并返回完整的登录结果 | Login and return the full login result
+    /// 
+    /// 包含 TokenInfo、会话以及不允许并发登录时被顶替下线的 token
+    /// 
+    /// # 示例 | Example
+    /// ```rust,ignore
+    /// let result = StpUtil::login_ex(10001, LoginOptions::new().device("pc")).await?;
+    /// if !result.replaced_tokens.is_empty() {
+    ///     tracing::info!("{} 个设备被顶下线", result.replaced_tokens.len());
+    /// }
+    /// ```
+    pub async fn login_ex(login_id: impl LoginId, options: LoginOptions) -> SaTokenResult<LoginResult> {
+        Self::get_manager().login_ex(login_id.to_login_id(), options).await
+    }
+    
+    /// "记住我"登录 | Login with remember me
+    /// 
+    /// `remember` 为 true 时 token 使用 `remember_timeout`，写回的 Cookie 带相同的 `Max-Age`；
+    /// 为 false 时写回会话 Cookie，浏览器关闭即失效（token 仍按 `timeout` 过期）。
+    /// 
+    /// With `remember` the token lives for `remember_timeout` and the written
+    /// cookie gets the same `Max-Age`. Without it the cookie is a session
+    /// cookie; the token itself still expires after `timeout`.
+    /// 
+    /// # 示例 | Example
+    /// ```rust,ignore
+    /// let token = StpUtil::login_with_remember(10001, form.remember_me).await?;
+    /// ```
+    pub async fn login_with_remember(login_id: impl LoginId, remember: bool) -> SaTokenResult<TokenValue> {
+        Self::default_logic().login_with_options(login_id, LoginOptions::new().remember(remember)).await
+    }
+    
+    /// 按设备登录 | Login on a device
+    /// 
+    /// 同一账号在不同设备上的会话互不影响，不允许并发登录时只顶替同一设备上的旧 token
+    /// 
+    /// # 示例 | Example
+    /// ```rust,ignore
+    /// let web = StpUtil::login_with_device(10001, "web").await?;
+    /// let app = StpUtil::login_with_device(10001, "mobile").await?;
+    /// ```
+    pub async fn login_with_device(login_id: impl LoginId, device: impl Into<String>) -> SaTokenResult<TokenValue> {
+        Self::get_manager().login_with_device(login_id.to_login_id(), device).await
     }
     
     /// 登录并设置额外数据 | Login with extra data
@@ -173,6 +353,16 @@ impl StpUtil {
         Self::get_manager().kick_out(&login_id.to_login_id()).await
     }
     
+    /// 带原因踢人下线，旧 token 再次访问时返回 `KickedOut(reason)`
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// StpUtil::kick_out_with_reason(10001, "Logged in on another device").await?;
+    /// ```
+    pub async fn kick_out_with_reason(login_id: impl LoginId, reason: &str) -> SaTokenResult<()> {
+        Self::get_manager().kick_out_with_reason(&login_id.to_login_id(), reason).await
+    }
+    
     pub async fn kick_out_with_manager(
         manager: &SaTokenManager,
         login_id: &str,
@@ -180,11 +370,73 @@ impl StpUtil {
         manager.kick_out(login_id).await
     }
     
+    /// 踢人下线（仅指定设备）
+    pub async fn kick_out_by_device(login_id: impl LoginId, device: &str) -> SaTokenResult<()> {
+        Self::get_manager().kick_out_by_device(&login_id.to_login_id(), device).await
+    }
+    
+    // ==================== 账号封禁 ====================
+    
+    /// 封禁账号（`seconds` 为 -1 时永久封禁），已登录的 token 立即失效
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// StpUtil::disable(10001, 86400).await?;
+    /// ```
+    pub async fn disable(login_id: impl LoginId, seconds: i64) -> SaTokenResult<()> {
+        Self::get_manager().disable(&login_id.to_login_id(), seconds).await
+    }
+    
+    /// 封禁账号的指定服务（如禁言 `comment`），不影响登录
+    pub async fn disable_by_service(login_id: impl LoginId, service: &str, seconds: i64) -> SaTokenResult<()> {
+        Self::get_manager().disable_by_service(&login_id.to_login_id(), service, seconds).await
+    }
+    
+    /// 账号是否被封禁
+    pub async fn is_disabled(login_id: impl LoginId) -> bool {
+        Self::get_manager().is_disabled(&login_id.to_login_id()).await
+    }
+    
+    /// 账号的指定服务是否被封禁
+    pub async fn is_disabled_by_service(login_id: impl LoginId, service: &str) -> bool {
+        Self::get_manager().is_disabled_by_service(&login_id.to_login_id(), service).await
+    }
+    
+    /// 校验账号未被封禁，封禁时返回 `AccountBanned`
+    pub async fn check_disable(login_id: impl LoginId) -> SaTokenResult<()> {
+        Self::get_manager().check_disable(&login_id.to_login_id()).await
+    }
+    
+    /// 校验账号的指定服务未被封禁
+    pub async fn check_disable_by_service(login_id: impl LoginId, service: &str) -> SaTokenResult<()> {
+        Self::get_manager().check_disable_by_service(&login_id.to_login_id(), service).await
+    }
+    
+    /// 剩余封禁时间（秒），永久封禁返回 -1，未封禁返回 None
+    pub async fn until_disabled_time(login_id: impl LoginId) -> SaTokenResult<Option<i64>> {
+        Self::get_manager().until_disabled_time(&login_id.to_login_id()).await
+    }
+    
+    /// 解除封禁
+    pub async fn untie_disable(login_id: impl LoginId) -> SaTokenResult<()> {
+        Self::get_manager().untie_disable(&login_id.to_login_id()).await
+    }
+    
+    /// 解除指定服务的封禁
+    pub async fn untie_disable_by_service(login_id: impl LoginId, service: &str) -> SaTokenResult<()> {
+        Self::get_manager().untie_disable_by_service(&login_id.to_login_id(), service).await
+    }
+    
     /// 强制登出（根据登录ID）
     pub async fn logout_by_login_id(login_id: impl LoginId) -> SaTokenResult<()> {
         Self::get_manager().logout_by_login_id(&login_id.to_login_id()).await
     }
     
+    /// 登出指定设备上的会话，其他设备不受影响
+    pub async fn logout_by_device(login_id: impl LoginId, device: &str) -> SaTokenResult<()> {
+        Self::get_manager().logout_by_device(&login_id.to_login_id(), device).await
+    }
+    
     /// 根据 token 登出（别名方法，更直观）
     pub async fn logout_by_token(token: &TokenValue) -> SaTokenResult<()> {
         Self::logout(token).await
@@ -234,7 +486,9 @@ impl StpUtil {
     /// }
     /// ```
     pub fn is_login_current() -> bool {
-        if let Ok(_token) = Self::get_token_value() {
+        if (Self::get_token_value().is_ok() && !Self::is_restricted_current())
+            || Self::dev_bypass_login_id().is_some()
+        {
             // 注意：这里使用同步检查，只检查上下文中是否有 token
             // 如果需要异步验证，需要使用 is_login(&token).await
             true
@@ -251,10 +505,41 @@ impl StpUtil {
     /// StpUtil::check_login_current()?;
     /// ```
     pub fn check_login_current() -> SaTokenResult<()> {
+        if Self::dev_bypass_login_id().is_some() {
+            return Ok(());
+        }
         Self::get_token_value()?;
+        if Self::is_restricted_current() {
+            return Err(SaTokenError::PasswordExpired);
+        }
         Ok(())
     }
     
+    /// 当前 token 是否为受限 token（例如密码过期后签发的 token）
+    pub fn is_restricted_current() -> bool {
+        SaTokenContext::get_current()
+            .and_then(|ctx| ctx.token_info)
+            .map(|info| info.is_restricted())
+            .unwrap_or(false)
+    }
+    
+    /// 检查当前 token 是否允许访问指定 scope，返回 login_id
+    /// 
+    /// 受限 token 只能访问其 scope 内的接口（例如修改密码接口），普通 token 不受影响
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// // 修改密码接口
+    /// let login_id = StpUtil::check_scope_current(PASSWORD_CHANGE_SCOPE)?;
+    /// ```
+    pub fn check_scope_current(scope: &str) -> SaTokenResult<String> {
+        let token_info = Self::get_token_info_current()?;
+        if !token_info.allows_scope(scope) {
+            return Err(SaTokenError::PasswordExpired);
+        }
+        Ok(token_info.login_id.clone())
+    }
+    
     /// 获取当前会话的 login_id（String 类型，无参数）
     /// 
     /// # 示例
@@ -263,7 +548,13 @@ impl StpUtil {
     /// let login_id = StpUtil::get_login_id_as_string().await?;
     /// ```
     pub async fn get_login_id_as_string() -> SaTokenResult<String> {
-        let token = Self::get_token_value()?;
+        let token = match Self::get_token_value() {
+            Ok(token) => token,
+            Err(e) => return Self::dev_bypass_login_id().ok_or(e),
+        };
+        if Self::is_restricted_current() {
+            return Err(SaTokenError::PasswordExpired);
+        }
         Self::get_login_id(&token).await
     }
     
@@ -323,11 +614,21 @@ impl StpUtil {
     }
     
     /// 检查当前 token 是否已登录，如果未登录则抛出异常
+    /// 
+    /// 被 `kick_out_with_reason` 踢下线的 token 返回 `KickedOut(reason)`，被新登录顶替的 token
+    /// 返回 `Replaced`，开启 `token_bind_ip` 后从其他 IP 访问返回 `IpMismatch`，其余情况返回 `NotLogin`
     pub async fn check_login(token: &TokenValue) -> SaTokenResult<()> {
-        if !Self::is_login(token).await {
-            return Err(SaTokenError::NotLogin);
+        // 请求之外的调用没有请求 IP，不校验 IP 绑定
+        let manager = Self::get_manager();
+        let result = match SaTokenContext::get_current() {
+            Some(ctx) => manager.get_token_info_from(token, ctx.client_ip.as_deref()).await,
+            None => manager.get_token_info(token).await,
+        };
+        match result {
+            Ok(_) => Ok(()),
+            Err(e @ (SaTokenError::KickedOut(_) | SaTokenError::Replaced | SaTokenError::IpMismatch)) => Err(e),
+            Err(_) => Err(SaTokenError::NotLogin),
         }
-        Ok(())
     }
     
     /// 获取 token 信息
@@ -335,6 +636,11 @@ impl StpUtil {
         Self::get_manager().get_token_info(token).await
     }
     
+    /// 批量获取 token 信息（只读，一次存储往返）
+    pub async fn get_token_infos(tokens: &[TokenValue]) -> SaTokenResult<Vec<Option<TokenInfo>>> {
+        Self::get_manager().get_token_infos(tokens).await
+    }
+    
     /// 获取当前 token 的登录ID
     pub async fn get_login_id(token: &TokenValue) -> SaTokenResult<String> {
         let token_info = Self::get_manager().get_token_info(token).await?;
@@ -359,16 +665,17 @@ impl StpUtil {
     /// let token = StpUtil::get_token_by_login_id(10001).await?;
     /// ```
     pub async fn get_token_by_login_id(login_id: impl LoginId) -> SaTokenResult<TokenValue> {
-        let manager = Self::get_manager();
-        let login_id_str = login_id.to_login_id();
-        
-        // 从存储中获取该用户的 token
-        let key = format!("sa:login:token:{}", login_id_str);
-        match manager.storage.get(&key).await {
-            Ok(Some(token_str)) => Ok(TokenValue::new(token_str)),
-            Ok(None) => Err(SaTokenError::NotLogin),
-            Err(e) => Err(SaTokenError::StorageError(e.to_string())),
-        }
+        Self::default_logic().get_token_by_login_id(login_id).await
+    }
+    
+    /// 获取账号在指定设备上的 token
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// let token = StpUtil::get_token_by_device("user_123", "mobile").await?;
+    /// ```
+    pub async fn get_token_by_device(login_id: impl LoginId, device: &str) -> SaTokenResult<TokenValue> {
+        Self::get_manager().get_token_by_device(&login_id.to_login_id(), device).await
     }
     
     /// 根据登录 ID 获取所有在线的 token 列表（支持多设备登录）
@@ -398,7 +705,7 @@ impl StpUtil {
     
     /// 获取当前登录账号的 Session
     pub async fn get_session(login_id: impl LoginId) -> SaTokenResult<SaSession> {
-        Self::get_manager().get_session(&login_id.to_login_id()).await
+        Self::default_logic().get_session(login_id).await
     }
     
     /// 保存 Session
@@ -408,7 +715,25 @@ impl StpUtil {
     
     /// 删除 Session
     pub async fn delete_session(login_id: impl LoginId) -> SaTokenResult<()> {
-        Self::get_manager().delete_session(&login_id.to_login_id()).await
+        Self::default_logic().delete_session(login_id).await
+    }
+    
+    /// 统计 Session 占用空间，用于排查体积过大的 key
+    pub async fn session_usage(login_id: impl LoginId) -> SaTokenResult<SessionUsage> {
+        Self::get_manager().session_usage(&login_id.to_login_id()).await
+    }
+    
+    /// 获取 Session 版本号（每次写入递增），可用作 ETag
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// let etag = format!("\"{}\"", StpUtil::session_revision("user_123").await?);
+    /// if if_none_match == Some(etag.as_str()) {
+    ///     // 返回 304 Not Modified
+    /// }
+    /// ```
+    pub async fn session_revision(login_id: impl LoginId) -> SaTokenResult<u64> {
+        Self::get_manager().session_revision(&login_id.to_login_id()).await
     }
     
     /// 在 Session 中设置值
@@ -433,6 +758,48 @@ impl StpUtil {
         Ok(session.get::<T>(key))
     }
     
+    /// 获取账号保存的语言（登录时从 `Accept-Language` 记录）
+    pub async fn get_locale(login_id: impl LoginId) -> SaTokenResult<Option<String>> {
+        Self::get_manager().get_locale(&login_id.to_login_id()).await
+    }
+    
+    /// 获取账号保存的时区（登录时从 `X-Timezone` 记录）
+    pub async fn get_timezone(login_id: impl LoginId) -> SaTokenResult<Option<String>> {
+        Self::get_manager().get_timezone(&login_id.to_login_id()).await
+    }
+    
+    /// 修改账号保存的语言与时区，None 表示保留原值
+    pub async fn set_client_locale(login_id: impl LoginId, locale: Option<&str>, timezone: Option<&str>) -> SaTokenResult<()> {
+        Self::get_manager().set_client_locale(&login_id.to_login_id(), locale, timezone).await
+    }
+    
+    /// 获取当前请求的语言
+    /// 
+    /// 已登录时取账号 Session 中保存的语言，没有保存时（或未登录）取本次请求的
+    /// `Accept-Language`。
+    /// Uses the locale saved in the session when logged in, falling back to
+    /// this request's `Accept-Language`.
+    pub async fn get_locale_current() -> SaTokenResult<Option<String>> {
+        let ctx = SaTokenContext::get_current().unwrap_or_default();
+        if let Some(login_id) = &ctx.login_id
+            && let Some(locale) = Self::get_locale(login_id).await?
+        {
+            return Ok(Some(locale));
+        }
+        Ok(ctx.locale)
+    }
+    
+    /// 获取当前请求的时区，规则同 [`StpUtil::get_locale_current`]
+    pub async fn get_timezone_current() -> SaTokenResult<Option<String>> {
+        let ctx = SaTokenContext::get_current().unwrap_or_default();
+        if let Some(login_id) = &ctx.login_id
+            && let Some(timezone) = Self::get_timezone(login_id).await?
+        {
+            return Ok(Some(timezone));
+        }
+        Ok(ctx.timezone)
+    }
+    
     // ==================== Token 相关 ====================
     
     /// 创建一个新的 token（但不登录）
@@ -450,13 +817,17 @@ impl StpUtil {
 
 impl StpUtil {
     /// 为用户添加权限
+    /// 
+    /// 配置了 `authz_naming_rules` 时，名称不合规返回 `InvalidAuthzName`
     pub async fn set_permissions(
         login_id: impl LoginId,
         permissions: Vec<String>,
     ) -> SaTokenResult<()> {
         let manager = Self::get_manager();
-        let mut map = manager.user_permissions.write().await;
-        map.insert(login_id.to_login_id(), permissions);
+        manager.validate_authz_names(AuthzKind::Permission, &permissions)?;
+        let login_id = login_id.to_login_id();
+        manager.user_permissions.write().await.insert(login_id.clone(), permissions);
+        manager.notify_authz_changed(&login_id).await;
         Ok(())
     }
     
@@ -466,13 +837,18 @@ impl StpUtil {
         permission: impl Into<String>,
     ) -> SaTokenResult<()> {
         let manager = Self::get_manager();
-        let mut map = manager.user_permissions.write().await;
         let login_id_str = login_id.to_login_id();
-        let permissions = map.entry(login_id_str).or_insert_with(Vec::new);
         let perm = permission.into();
-        if !permissions.contains(&perm) {
+        manager.validate_authz_names(AuthzKind::Permission, std::slice::from_ref(&perm))?;
+        {
+            let mut map = manager.user_permissions.write().await;
+            let permissions = map.entry(login_id_str.clone()).or_insert_with(Vec::new);
+            if permissions.contains(&perm) {
+                return Ok(());
+            }
             permissions.push(perm);
         }
+        manager.notify_authz_changed(&login_id_str).await;
         Ok(())
     }
     
@@ -482,52 +858,46 @@ impl StpUtil {
         permission: &str,
     ) -> SaTokenResult<()> {
         let manager = Self::get_manager();
-        let mut map = manager.user_permissions.write().await;
-        if let Some(permissions) = map.get_mut(&login_id.to_login_id()) {
+        let login_id = login_id.to_login_id();
+        if let Some(permissions) = manager.user_permissions.write().await.get_mut(&login_id) {
             permissions.retain(|p| p != permission);
         }
+        manager.notify_authz_changed(&login_id).await;
         Ok(())
     }
     
     /// 清除用户的所有权限
     pub async fn clear_permissions(login_id: impl LoginId) -> SaTokenResult<()> {
         let manager = Self::get_manager();
-        let mut map = manager.user_permissions.write().await;
-        map.remove(&login_id.to_login_id());
+        let login_id = login_id.to_login_id();
+        manager.user_permissions.write().await.remove(&login_id);
+        manager.notify_authz_changed(&login_id).await;
         Ok(())
     }
     
     /// 获取用户的所有权限
+    /// 
+    /// 包含已注册权限检查器返回的权限
     pub async fn get_permissions(login_id: impl LoginId) -> Vec<String> {
-        let manager = Self::get_manager();
-        let map = manager.user_permissions.read().await;
-        map.get(&login_id.to_login_id()).cloned().unwrap_or_default()
+        Self::get_manager().get_permissions(&login_id.to_login_id()).await
     }
     
     /// 检查用户是否拥有指定权限
+    /// 
+    /// 依次使用登录时预取的快照、已注册的权限检查器和内存中的权限映射
     pub async fn has_permission(
         login_id: impl LoginId,
         permission: &str,
     ) -> bool {
         let manager = Self::get_manager();
-        let map = manager.user_permissions.read().await;
-        if let Some(permissions) = map.get(&login_id.to_login_id()) {
-            // 精确匹配
-            if permissions.contains(&permission.to_string()) {
-                return true;
-            }
-            
-            // 通配符匹配（例如 admin:* 匹配 admin:read）
-            for perm in permissions {
-                if perm.ends_with(":*") {
-                    let prefix = &perm[..perm.len() - 2];
-                    if permission.starts_with(prefix) {
-                        return true;
-                    }
-                }
-            }
+        if manager.dev_bypass_login_id().is_some() {
+            return true;
         }
-        false
+        let login_id = login_id.to_login_id();
+        if let Some(snapshot) = Self::prefetched_authz(&login_id) {
+            return manager.permission_matcher().matches_any(&snapshot.permissions, permission);
+        }
+        manager.has_permission(&login_id, permission).await
     }
     
     /// 检查用户是否拥有所有指定权限（AND 逻辑）
@@ -580,23 +950,96 @@ impl StpUtil {
         permission: &str,
     ) -> SaTokenResult<()> {
         if !Self::has_permission(login_id, permission).await {
+            Self::record_denial(AuthzKind::Permission, permission);
             return Err(SaTokenError::PermissionDeniedDetail(permission.to_string()));
         }
         Ok(())
     }
+    
+    /// 获取用户缺少的权限（保持传入顺序）
+    pub async fn get_missing_permissions(
+        login_id: impl LoginId,
+        permissions: &[&str],
+    ) -> Vec<String> {
+        let login_id_str = login_id.to_login_id();
+        let mut missing = Vec::new();
+        for permission in permissions {
+            if !Self::has_permission(&login_id_str, permission).await {
+                missing.push(permission.to_string());
+            }
+        }
+        missing
+    }
+    
+    /// 检查多个权限（AND 逻辑），一次性返回所有缺少的权限
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// match StpUtil::check_permissions_and("user_123", &["user:read", "user:write"]).await {
+    ///     Err(e) => println!("缺少权限: {:?}", e.missing()),
+    ///     Ok(()) => {}
+    /// }
+    /// ```
+    pub async fn check_permissions_and(
+        login_id: impl LoginId,
+        permissions: &[&str],
+    ) -> SaTokenResult<()> {
+        let missing = Self::get_missing_permissions(login_id, permissions).await;
+        if !missing.is_empty() {
+            missing.iter().for_each(|p| Self::record_denial(AuthzKind::Permission, p));
+            return Err(SaTokenError::PermissionsDenied(missing));
+        }
+        Ok(())
+    }
+}
+
+impl StpUtil {
+    /// 记录一次拒绝，路由取自当前请求上下文
+    /// 
+    /// `check_*` 方法拒绝时会自动记录；自行用 `has_permission` 判断后返回 403 的中间件需手动调用。
+    /// OR 组合记为 `a | b`。
+    pub fn record_denial(kind: AuthzKind, name: &str) {
+        let route = SaTokenContext::get_current().and_then(|ctx| ctx.route);
+        Self::get_manager().record_denial(route.as_deref(), kind, name);
+    }
+    
+    /// 拒绝次数最多的（路由、权限/角色）组合
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// async fn denial_report() -> Json<Vec<DenialCount>> {
+    ///     Json(StpUtil::top_denials(20))
+    /// }
+    /// ```
+    pub fn top_denials(limit: usize) -> Vec<DenialCount> {
+        Self::get_manager().top_denials(limit)
+    }
+    
+    /// 当前请求上下文中该账号登录时预取的权限快照（已按权限版本校验）
+    fn prefetched_authz(login_id: &str) -> Option<AuthzSnapshot> {
+        let token_info = SaTokenContext::get_current()?.token_info?;
+        if token_info.login_id != login_id {
+            return None;
+        }
+        token_info.authz.clone()
+    }
 }
 
 // ==================== 角色管理 ====================
 
 impl StpUtil {
     /// 为用户设置角色
+    /// 
+    /// 配置了 `authz_naming_rules` 时，名称不合规返回 `InvalidAuthzName`
     pub async fn set_roles(
         login_id: impl LoginId,
         roles: Vec<String>,
     ) -> SaTokenResult<()> {
         let manager = Self::get_manager();
-        let mut map = manager.user_roles.write().await;
-        map.insert(login_id.to_login_id(), roles);
+        manager.validate_authz_names(AuthzKind::Role, &roles)?;
+        let login_id = login_id.to_login_id();
+        manager.user_roles.write().await.insert(login_id.clone(), roles);
+        manager.notify_authz_changed(&login_id).await;
         Ok(())
     }
     
@@ -606,13 +1049,18 @@ impl StpUtil {
         role: impl Into<String>,
     ) -> SaTokenResult<()> {
         let manager = Self::get_manager();
-        let mut map = manager.user_roles.write().await;
         let login_id_str = login_id.to_login_id();
-        let roles = map.entry(login_id_str).or_insert_with(Vec::new);
         let r = role.into();
-        if !roles.contains(&r) {
+        manager.validate_authz_names(AuthzKind::Role, std::slice::from_ref(&r))?;
+        {
+            let mut map = manager.user_roles.write().await;
+            let roles = map.entry(login_id_str.clone()).or_insert_with(Vec::new);
+            if roles.contains(&r) {
+                return Ok(());
+            }
             roles.push(r);
         }
+        manager.notify_authz_changed(&login_id_str).await;
         Ok(())
     }
     
@@ -622,40 +1070,51 @@ impl StpUtil {
         role: &str,
     ) -> SaTokenResult<()> {
         let manager = Self::get_manager();
-        let mut map = manager.user_roles.write().await;
-        if let Some(roles) = map.get_mut(&login_id.to_login_id()) {
+        let login_id = login_id.to_login_id();
+        if let Some(roles) = manager.user_roles.write().await.get_mut(&login_id) {
             roles.retain(|r| r != role);
         }
+        manager.notify_authz_changed(&login_id).await;
         Ok(())
     }
     
     /// 清除用户的所有角色
     pub async fn clear_roles(login_id: impl LoginId) -> SaTokenResult<()> {
         let manager = Self::get_manager();
-        let mut map = manager.user_roles.write().await;
-        map.remove(&login_id.to_login_id());
+        let login_id = login_id.to_login_id();
+        manager.user_roles.write().await.remove(&login_id);
+        manager.notify_authz_changed(&login_id).await;
         Ok(())
     }
     
+    /// 检查所有账号的权限与角色，列出可疑条目（末尾冒号、重复通配符、被通配符覆盖等）
+    pub async fn lint_permission_model() -> Vec<LintIssue> {
+        Self::get_manager().lint_permission_model().await
+    }
+    
     /// 获取用户的所有角色
+    /// 
+    /// 包含已注册角色检查器返回的角色
     pub async fn get_roles(login_id: impl LoginId) -> Vec<String> {
-        let manager = Self::get_manager();
-        let map = manager.user_roles.read().await;
-        map.get(&login_id.to_login_id()).cloned().unwrap_or_default()
+        Self::get_manager().get_roles(&login_id.to_login_id()).await
     }
     
     /// 检查用户是否拥有指定角色
+    /// 
+    /// 依次使用登录时预取的快照、已注册的角色检查器和内存中的角色映射
     pub async fn has_role(
         login_id: impl LoginId,
         role: &str,
     ) -> bool {
         let manager = Self::get_manager();
-        let map = manager.user_roles.read().await;
-        if let Some(roles) = map.get(&login_id.to_login_id()) {
-            roles.contains(&role.to_string())
-        } else {
-            false
+        if manager.dev_bypass_login_id().is_some() {
+            return true;
         }
+        let login_id = login_id.to_login_id();
+        if let Some(snapshot) = Self::prefetched_authz(&login_id) {
+            return snapshot.roles.iter().any(|r| r == role);
+        }
+        manager.has_role(&login_id, role).await
     }
     
     /// 检查用户是否拥有所有指定角色（AND 逻辑）
@@ -708,10 +1167,69 @@ impl StpUtil {
         role: &str,
     ) -> SaTokenResult<()> {
         if !Self::has_role(login_id, role).await {
+            Self::record_denial(AuthzKind::Role, role);
             return Err(SaTokenError::RoleDenied(role.to_string()));
         }
         Ok(())
     }
+    
+    /// 获取用户缺少的角色（保持传入顺序）
+    pub async fn get_missing_roles(
+        login_id: impl LoginId,
+        roles: &[&str],
+    ) -> Vec<String> {
+        let login_id_str = login_id.to_login_id();
+        let mut missing = Vec::new();
+        for role in roles {
+            if !Self::has_role(&login_id_str, role).await {
+                missing.push(role.to_string());
+            }
+        }
+        missing
+    }
+    
+    /// 检查多个角色（AND 逻辑），一次性返回所有缺少的角色
+    pub async fn check_roles_and(
+        login_id: impl LoginId,
+        roles: &[&str],
+    ) -> SaTokenResult<()> {
+        let missing = Self::get_missing_roles(login_id, roles).await;
+        if !missing.is_empty() {
+            missing.iter().for_each(|r| Self::record_denial(AuthzKind::Role, r));
+            return Err(SaTokenError::RolesDenied(missing));
+        }
+        Ok(())
+    }
+}
+
+// ==================== 只读模式 ====================
+
+impl StpUtil {
+    /// 开启只读模式（运行时生效，多实例共享）
+    /// 
+    /// 目标用户仍可登录和读取，但写操作会被 `ReadOnlyModeLayer` 等中间件拒绝
+    pub async fn enable_read_only_mode(mode: ReadOnlyMode) -> SaTokenResult<()> {
+        Self::get_manager().enable_read_only_mode(mode).await
+    }
+    
+    /// 关闭只读模式
+    pub async fn disable_read_only_mode() -> SaTokenResult<()> {
+        Self::get_manager().disable_read_only_mode().await
+    }
+    
+    /// 当前的只读模式设置，未开启时返回 None
+    pub async fn read_only_mode() -> SaTokenResult<Option<ReadOnlyMode>> {
+        Self::get_manager().read_only_mode().await
+    }
+    
+    /// 只读模式检查：当前请求的写操作被拒绝时返回 `ReadOnlyMode` 错误
+    /// 
+    /// # 参数
+    /// - `login_id`: 当前登录 ID（未登录时为 None）
+    /// - `method`: HTTP 方法
+    pub async fn check_read_only(login_id: Option<&str>, method: &str) -> SaTokenResult<()> {
+        Self::get_manager().check_read_only(login_id, method).await
+    }
 }
 
 // ==================== 扩展工具方法 ====================
@@ -735,7 +1253,7 @@ impl StpUtil {
         let token_info = manager.get_token_info(token).await?;
         
         if let Some(expire_time) = token_info.expire_time {
-            let now = chrono::Utc::now();
+            let now = manager.now();
             let duration = expire_time.signed_duration_since(now);
             Ok(Some(duration.num_seconds()))
         } else {
@@ -743,6 +1261,38 @@ impl StpUtil {
         }
     }
     
+    /// 全局管理器配置的审计日志，未配置时为 None | Audit logger of the global manager, None when not configured
+    pub fn audit_logger() -> Option<Arc<dyn AuditLogger>> {
+        Self::get_manager().audit_logger().cloned()
+    }
+    
+    /// Same-Token 管理器（使用全局管理器的存储）| Same-Token manager on the global manager's storage
+    pub fn same_token_manager() -> SameTokenManager {
+        Self::get_manager().same_token_manager()
+    }
+    
+    /// 当前 Same-Token，网关调用内部服务时放入 `sa-same-token` 请求头
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// request.headers_mut().insert(SAME_TOKEN_HEADER, StpUtil::get_same_token().await?.parse()?);
+    /// ```
+    pub async fn get_same_token() -> SaTokenResult<String> {
+        Self::same_token_manager().get_token().await
+    }
+    
+    /// 换发 token：签发绑定同一账号与 Session 的新 token，旧 token 立即失效
+    /// 
+    /// 修改密码或提升权限后调用，防止会话固定攻击
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// let token = StpUtil::renew_token(&old_token).await?;
+    /// ```
+    pub async fn renew_token(old_token: &TokenValue) -> SaTokenResult<TokenValue> {
+        Self::get_manager().renew_token(old_token).await
+    }
+    
     /// 续期 token（重置过期时间）
     pub async fn renew_timeout(
         token: &TokenValue,
@@ -752,7 +1302,7 @@ impl StpUtil {
         let mut token_info = manager.get_token_info(token).await?;
         
         // 设置新的过期时间
-        let new_expire_time = chrono::Utc::now() + chrono::Duration::seconds(timeout_seconds);
+        let new_expire_time = manager.now() + chrono::Duration::seconds(timeout_seconds);
         token_info.expire_time = Some(new_expire_time);
         
         // 保存更新后的 token 信息
@@ -786,7 +1336,7 @@ impl StpUtil {
         let value = serde_json::to_string(&token_info)
             .map_err(|e| SaTokenError::SerializationError(e))?;
         
-        manager.storage.set(&key, &value, manager.config.timeout_duration()).await
+        manager.storage.set(&key, &value, manager.config().timeout_duration()).await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
         
         Ok(())
@@ -894,7 +1444,7 @@ impl TokenBuilder {
         let value = serde_json::to_string(&token_info)
             .map_err(|e| SaTokenError::SerializationError(e))?;
         
-        manager.storage.set(&key, &value, manager.config.timeout_duration()).await
+        manager.storage.set(&key, &value, manager.config().timeout_duration()).await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
         
         Ok(token)
@@ -904,6 +1454,29 @@ impl TokenBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use sa_token_storage_memory::MemoryStorage;
+    use crate::SaTokenConfig;
+    
+    fn init_test_manager() {
+        StpUtil::try_init_manager(SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default()));
+    }
+    
+    #[test]
+    fn test_init_manager_is_idempotent() {
+        init_test_manager();
+        let current = (*StpUtil::get_manager()).clone();
+        let other_config = SaTokenConfig { token_name: "other-token".to_string(), ..SaTokenConfig::default() };
+        let other = SaTokenManager::new(Arc::new(MemoryStorage::new()), other_config);
+
+        assert!(!StpUtil::try_init_manager(other.clone()));
+        StpUtil::init_manager(other);
+        assert!(StpUtil::is_initialized());
+        assert_ne!(StpUtil::config().token_name, "other-token");
+
+        // 换成共享同一状态的克隆，避免影响并行运行的其他测试
+        StpUtil::force_reinit_manager(current);
+        assert_ne!(StpUtil::config().token_name, "other-token");
+    }
     
     #[test]
     fn test_token_format_validation() {
@@ -917,4 +1490,65 @@ mod tests {
         let token = StpUtil::create_token("test-token-123");
         assert_eq!(token.as_str(), "test-token-123");
     }
+    
+    #[tokio::test]
+    async fn test_check_permissions_and_reports_all_missing() {
+        init_test_manager();
+        StpUtil::set_permissions("perm_user", vec!["user:read".to_string()]).await.unwrap();
+        
+        let err = StpUtil::check_permissions_and("perm_user", &["user:read", "user:write", "user:delete"])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SaTokenError::PermissionsDenied(_)));
+        assert_eq!(err.missing(), vec!["user:write", "user:delete"]);
+        
+        assert!(StpUtil::check_permissions_and("perm_user", &["user:read"]).await.is_ok());
+    }
+    
+    #[tokio::test]
+    async fn test_check_roles_and_reports_all_missing() {
+        init_test_manager();
+        StpUtil::set_roles("role_user", vec!["admin".to_string()]).await.unwrap();
+        
+        let err = StpUtil::check_roles_and("role_user", &["admin", "super", "auditor"])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SaTokenError::RolesDenied(_)));
+        assert_eq!(err.missing(), vec!["super", "auditor"]);
+    }
+    
+    #[tokio::test]
+    async fn test_session_revision_increments_on_write() {
+        init_test_manager();
+        assert_eq!(StpUtil::session_revision("rev_user").await.unwrap(), 0);
+        
+        StpUtil::set_session_value("rev_user", "nickname", "alice").await.unwrap();
+        assert_eq!(StpUtil::session_revision("rev_user").await.unwrap(), 1);
+        
+        // 读取不会改变版本号
+        let _: Option<String> = StpUtil::get_session_value("rev_user", "nickname").await.unwrap();
+        assert_eq!(StpUtil::session_revision("rev_user").await.unwrap(), 1);
+        
+        StpUtil::delete_session("rev_user").await.unwrap();
+        assert_eq!(StpUtil::session_revision("rev_user").await.unwrap(), 2);
+    }
+    
+    #[tokio::test]
+    async fn test_restricted_token_only_allows_its_scope() {
+        init_test_manager();
+        let mut info = TokenInfo::new(TokenValue::new("restricted-token"), "expired_user");
+        info.scope = Some(vec![crate::PASSWORD_CHANGE_SCOPE.to_string()]);
+        
+        let mut ctx = SaTokenContext::new();
+        ctx.token = Some(info.token.clone());
+        ctx.token_info = Some(Arc::new(info));
+        SaTokenContext::set_current(ctx);
+        
+        assert!(!StpUtil::is_login_current());
+        assert!(matches!(StpUtil::check_login_current(), Err(SaTokenError::PasswordExpired)));
+        assert_eq!(StpUtil::check_scope_current(crate::PASSWORD_CHANGE_SCOPE).unwrap(), "expired_user");
+        assert!(StpUtil::check_scope_current("user:profile").is_err());
+        
+        SaTokenContext::clear();
+    }
 }
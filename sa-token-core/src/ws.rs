@@ -167,15 +167,19 @@ pub trait WsTokenExtractor: Send + Sync {
 /// 1. Authorization header (Bearer token)
 /// 2. Sec-WebSocket-Protocol header
 /// 3. Query parameter "token"
+///
+/// The Authorization value is returned as sent; `WsAuthManager` strips the
+/// configured `token_prefix` / `token_schemes` from it.
+/// Authorization 请求头的值原样返回，由 `WsAuthManager` 按配置的 `token_prefix` / `token_schemes` 去掉前缀。
 pub struct DefaultWsTokenExtractor;
 
 #[async_trait]
 impl WsTokenExtractor for DefaultWsTokenExtractor {
     async fn extract_token(&self, headers: &HashMap<String, String>, query: &HashMap<String, String>) -> Option<String> {
-        // Priority 1: Authorization header with Bearer scheme
-        // 优先级 1: Authorization 请求头（Bearer 方式）
+        // Priority 1: Authorization header, scheme stripped by WsAuthManager
+        // 优先级 1: Authorization 请求头，认证方案由 WsAuthManager 去掉
         if let Some(token) = headers.get("Authorization") {
-            return Some(token.trim_start_matches("Bearer ").to_string());
+            return Some(token.to_string());
         }
         
         // Priority 2: WebSocket Protocol header
@@ -287,6 +291,7 @@ impl WsAuthManager {
         // 步骤 1: 从请求中提取 Token
         let token_str = self.extractor.extract_token(headers, query).await
             .ok_or(SaTokenError::NotLogin)?;
+        let token_str = self.manager.config().strip_token_prefix(&token_str).to_string();
 
         // Step 2: Convert to TokenValue and get token info
         // 步骤 2: 转换为 TokenValue 并获取 Token 信息
@@ -296,7 +301,7 @@ impl WsAuthManager {
         // Step 3: Validate token expiration
         // 步骤 3: 验证 Token 过期时间
         if let Some(expire_time) = token_info.expire_time {
-            if chrono::Utc::now() > expire_time {
+            if crate::time::now() > expire_time {
                 return Err(SaTokenError::TokenExpired);
             }
         }
@@ -312,7 +317,7 @@ impl WsAuthManager {
             login_id: login_id.clone(),
             token: token_str.clone(),
             session_id,
-            connect_time: chrono::Utc::now(),
+            connect_time: crate::time::now(),
             metadata: HashMap::new(),
         };
 
@@ -348,7 +353,7 @@ impl WsAuthManager {
         
         // Validate expiration | 验证过期时间
         if let Some(expire_time) = token_info.expire_time {
-            if chrono::Utc::now() > expire_time {
+            if crate::time::now() > expire_time {
                 return Err(SaTokenError::TokenExpired);
             }
         }
@@ -416,6 +421,25 @@ mod tests {
         assert_eq!(auth_info.login_id, "user456");
     }
 
+    #[tokio::test]
+    async fn test_authorization_header_uses_configured_schemes() {
+        let config = SaTokenConfig {
+            token_prefix: Some("ak.".to_string()),
+            token_schemes: vec!["Token".to_string()],
+            ..Default::default()
+        };
+        let manager = Arc::new(SaTokenManager::new(Arc::new(MemoryStorage::new()), config));
+        let ws_manager = WsAuthManager::new(manager.clone());
+        let token = manager.login("user123").await.unwrap();
+
+        for value in [format!("ak.{}", token.as_str()), format!("token {}", token.as_str())] {
+            let headers = HashMap::from([("Authorization".to_string(), value)]);
+            let auth_info = ws_manager.authenticate(&headers, &HashMap::new()).await.unwrap();
+            assert_eq!(auth_info.login_id, "user123");
+            assert_eq!(auth_info.token, token.as_str());
+        }
+    }
+
     #[tokio::test]
     async fn test_verify_token() {
         let config = SaTokenConfig::default();
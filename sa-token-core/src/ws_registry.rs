@@ -0,0 +1,450 @@
+// Author: 金书记
+//
+//! WebSocket Connection Registry | WebSocket 连接注册表
+//!
+//! ## English
+//!
+//! [`WsConnectionRegistry`] keeps every live WebSocket connection together with
+//! an outbound channel. The framework handler forwards [`WsOutbound`] items to
+//! the socket: messages as text frames, [`WsCloseReason`] as a close frame.
+//!
+//! ```text
+//! WsAuthManager.authenticate() → registry.register(auth, device) → (connection, receiver)
+//!                                      │
+//!   WebSocketPusher (MessagePusher) ───┤ send_to_user / close_user
+//!   Registry as SaTokenListener ───────┘ Logout → 4002, KickOut → 4001, Replaced → 4003, Banned → 4004
+//! ```
+//!
+//! - Multi-device: a user may hold several connections; with
+//!   `replace_same_device(true)` (the default) a new connection from the same
+//!   device closes the previous one with `REPLACED`.
+//! - With an [`OnlineManager`] attached, connections are marked online and
+//!   offline automatically.
+//!
+//! ## 中文
+//!
+//! [`WsConnectionRegistry`] 保存所有 WebSocket 连接及其发送通道，框架侧的处理函数把
+//! [`WsOutbound`] 转发到 socket：消息作为文本帧，[`WsCloseReason`] 作为关闭帧。
+//!
+//! - 多设备：同一用户可以有多个连接；开启 `replace_same_device`（默认）时，同一设备的新连接
+//!   会以 `REPLACED` 关闭旧连接
+//! - 关联 [`OnlineManager`] 后自动标记上线与下线
+//! - 注册为事件监听器后，登出、踢人、顶号、封禁会以对应的关闭码关闭连接
+//!
+//! ```rust,ignore
+//! let registry = Arc::new(WsConnectionRegistry::new().with_online_manager(online.clone()));
+//! online.register_pusher(Arc::new(WebSocketPusher::new(registry.clone()))).await;
+//! manager.event_bus().register(registry.clone());
+//!
+//! let auth = ws_auth.authenticate(&headers, &query).await?;
+//! let (connection, mut outbound) = registry.register(&auth, "web").await;
+//! while let Some(item) = outbound.recv().await {
+//!     match item {
+//!         WsOutbound::Message(message) => { /* 发送文本帧 | send a text frame */ }
+//!         WsOutbound::Close(reason) => { /* 发送关闭帧 | send a close frame */ break; }
+//!     }
+//! }
+//! registry.unregister(&connection.session_id).await;
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tokio::sync::{mpsc, RwLock};
+use crate::error::SaTokenError;
+use crate::event::{SaTokenEvent, SaTokenEventType, SaTokenListener};
+use crate::online::{MessagePusher, MessageType, OnlineManager, OnlineUser, PushMessage};
+use crate::ws::WsAuthInfo;
+
+/// 关闭原因，对应 WebSocket 关闭帧的状态码与原因 | Close code and reason of the close frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WsCloseReason {
+    /// 关闭码（4000-4999 为应用自定义）| Close code (4000-4999 are application codes)
+    pub code: u16,
+
+    /// 关闭原因 | Reason text
+    pub reason: String,
+}
+
+impl WsCloseReason {
+    /// 被踢下线 | Kicked out
+    pub const KICKED_OUT: u16 = 4001;
+    /// 已登出 | Logged out
+    pub const LOGGED_OUT: u16 = 4002;
+    /// 被新连接或新登录顶替 | Replaced by a newer connection or login
+    pub const REPLACED: u16 = 4003;
+    /// 账号被封禁 | Account banned
+    pub const BANNED: u16 = 4004;
+
+    pub fn new(code: u16, reason: impl Into<String>) -> Self {
+        Self { code, reason: reason.into() }
+    }
+
+    pub fn kicked_out(reason: impl Into<String>) -> Self {
+        Self::new(Self::KICKED_OUT, reason)
+    }
+
+    pub fn logged_out() -> Self {
+        Self::new(Self::LOGGED_OUT, "logged out")
+    }
+
+    pub fn replaced() -> Self {
+        Self::new(Self::REPLACED, "replaced by another connection")
+    }
+
+    pub fn banned() -> Self {
+        Self::new(Self::BANNED, "account banned")
+    }
+}
+
+/// 发往连接的内容 | Item sent to a connection
+#[derive(Debug, Clone)]
+pub enum WsOutbound {
+    /// 推送消息 | Push message
+    Message(PushMessage),
+    /// 关闭连接 | Close the connection
+    Close(WsCloseReason),
+}
+
+/// 已注册的连接 | Registered connection
+#[derive(Debug, Clone)]
+pub struct WsConnection {
+    pub session_id: String,
+    pub login_id: String,
+    pub token: String,
+    pub device: String,
+    pub connect_time: DateTime<Utc>,
+}
+
+struct ConnectionEntry {
+    connection: WsConnection,
+    sender: mpsc::UnboundedSender<WsOutbound>,
+}
+
+/// WebSocket 连接注册表 | WebSocket connection registry
+pub struct WsConnectionRegistry {
+    /// login_id -> 连接列表
+    connections: RwLock<HashMap<String, Vec<ConnectionEntry>>>,
+    online_manager: Option<Arc<OnlineManager>>,
+    replace_same_device: bool,
+}
+
+impl Default for WsConnectionRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WsConnectionRegistry {
+    pub fn new() -> Self {
+        Self {
+            connections: RwLock::new(HashMap::new()),
+            online_manager: None,
+            replace_same_device: true,
+        }
+    }
+
+    /// 关联在线用户管理器，连接注册/关闭时自动标记上线/下线
+    pub fn with_online_manager(mut self, online_manager: Arc<OnlineManager>) -> Self {
+        self.online_manager = Some(online_manager);
+        self
+    }
+
+    /// 同一设备的新连接是否顶替旧连接，默认开启
+    pub fn replace_same_device(mut self, enabled: bool) -> Self {
+        self.replace_same_device = enabled;
+        self
+    }
+
+    /// 注册连接，返回连接信息和待发送内容的接收端
+    ///
+    /// Registers a connection; forward the returned receiver to the socket.
+    pub async fn register(
+        &self,
+        auth: &WsAuthInfo,
+        device: impl Into<String>,
+    ) -> (WsConnection, mpsc::UnboundedReceiver<WsOutbound>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let connection = WsConnection {
+            session_id: auth.session_id.clone(),
+            login_id: auth.login_id.clone(),
+            token: auth.token.clone(),
+            device: device.into(),
+            connect_time: crate::time::now(),
+        };
+
+        let replaced = {
+            let mut connections = self.connections.write().await;
+            let entries = connections.entry(connection.login_id.clone()).or_default();
+            let mut replaced = Vec::new();
+            if self.replace_same_device {
+                entries.retain(|entry| {
+                    if entry.connection.device != connection.device {
+                        return true;
+                    }
+                    let _ = entry.sender.send(WsOutbound::Close(WsCloseReason::replaced()));
+                    replaced.push(entry.connection.clone());
+                    false
+                });
+            }
+            entries.push(ConnectionEntry { connection: connection.clone(), sender });
+            replaced
+        };
+
+        if let Some(online) = &self.online_manager {
+            self.mark_offline(&replaced).await;
+            online.mark_online(OnlineUser {
+                login_id: connection.login_id.clone(),
+                token: connection.token.clone(),
+                device: connection.device.clone(),
+                connect_time: connection.connect_time,
+                last_activity: connection.connect_time,
+                metadata: HashMap::new(),
+                login_ip: None,
+                user_agent: None,
+            }).await;
+        }
+
+        (connection, receiver)
+    }
+
+    /// 连接断开后移除 | Remove a connection after the socket closed
+    pub async fn unregister(&self, session_id: &str) {
+        let removed = self.remove_where(|c| c.session_id == session_id, None).await;
+        self.mark_offline(&removed).await;
+    }
+
+    /// 收到客户端消息时刷新在线活跃时间 | Refresh the online activity when the client sends something
+    pub async fn record_activity(&self, connection: &WsConnection) {
+        if let Some(online) = &self.online_manager {
+            online.update_activity(&connection.login_id, &connection.token).await;
+        }
+    }
+
+    /// 用户当前的所有连接 | All connections of a user
+    pub async fn connections(&self, login_id: &str) -> Vec<WsConnection> {
+        let connections = self.connections.read().await;
+        connections.get(login_id)
+            .map(|entries| entries.iter().map(|e| e.connection.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    /// 当前连接总数 | Total number of connections
+    pub async fn connection_count(&self) -> usize {
+        let connections = self.connections.read().await;
+        connections.values().map(Vec::len).sum()
+    }
+
+    /// 有连接的用户 | Users holding at least one connection
+    pub async fn connected_users(&self) -> Vec<String> {
+        let connections = self.connections.read().await;
+        connections.keys().cloned().collect()
+    }
+
+    /// 推送给用户的所有连接，返回送达的连接数 | Push to every connection of a user
+    pub async fn send_to_user(&self, login_id: &str, message: PushMessage) -> usize {
+        let connections = self.connections.read().await;
+        connections.get(login_id)
+            .map(|entries| entries.iter()
+                .filter(|e| e.sender.send(WsOutbound::Message(message.clone())).is_ok())
+                .count())
+            .unwrap_or(0)
+    }
+
+    /// 推送给所有连接，返回送达的连接数 | Push to every connection
+    pub async fn broadcast(&self, message: PushMessage) -> usize {
+        let connections = self.connections.read().await;
+        connections.values()
+            .flatten()
+            .filter(|e| e.sender.send(WsOutbound::Message(message.clone())).is_ok())
+            .count()
+    }
+
+    /// 推送给指定连接 | Push to a single connection
+    pub async fn send_to_session(&self, session_id: &str, message: PushMessage) -> bool {
+        let connections = self.connections.read().await;
+        connections.values()
+            .flatten()
+            .find(|e| e.connection.session_id == session_id)
+            .is_some_and(|e| e.sender.send(WsOutbound::Message(message)).is_ok())
+    }
+
+    /// 关闭用户的所有连接，返回关闭的连接数 | Close every connection of a user
+    pub async fn close_user(&self, login_id: &str, reason: WsCloseReason) -> usize {
+        let closed = self.remove_where(|c| c.login_id == login_id, Some(reason)).await;
+        self.mark_offline(&closed).await;
+        closed.len()
+    }
+
+    /// 关闭使用该 token 的连接 | Close the connections opened with a token
+    pub async fn close_token(&self, token: &str, reason: WsCloseReason) -> usize {
+        let closed = self.remove_where(|c| c.token == token, Some(reason)).await;
+        self.mark_offline(&closed).await;
+        closed.len()
+    }
+
+    /// 关闭指定连接 | Close a single connection
+    pub async fn close_session(&self, session_id: &str, reason: WsCloseReason) -> bool {
+        let closed = self.remove_where(|c| c.session_id == session_id, Some(reason)).await;
+        self.mark_offline(&closed).await;
+        !closed.is_empty()
+    }
+
+    /// 移除满足条件的连接，指定原因时先发送关闭帧
+    async fn remove_where(
+        &self,
+        matches: impl Fn(&WsConnection) -> bool,
+        reason: Option<WsCloseReason>,
+    ) -> Vec<WsConnection> {
+        let mut removed = Vec::new();
+        let mut connections = self.connections.write().await;
+        connections.retain(|_, entries| {
+            entries.retain(|entry| {
+                if !matches(&entry.connection) {
+                    return true;
+                }
+                if let Some(reason) = &reason {
+                    let _ = entry.sender.send(WsOutbound::Close(reason.clone()));
+                }
+                removed.push(entry.connection.clone());
+                false
+            });
+            !entries.is_empty()
+        });
+        removed
+    }
+
+    async fn mark_offline(&self, connections: &[WsConnection]) {
+        let Some(online) = &self.online_manager else {
+            return;
+        };
+        for connection in connections {
+            online.mark_offline(&connection.login_id, &connection.token).await;
+        }
+    }
+}
+
+#[async_trait]
+impl SaTokenListener for WsConnectionRegistry {
+    async fn on_event(&self, event: &SaTokenEvent) {
+        match event.event_type {
+            SaTokenEventType::Logout => {
+                self.close_token(&event.token, WsCloseReason::logged_out()).await;
+            }
+            // 设备级踢人只关闭该 token 的连接
+            SaTokenEventType::KickOut if event.device.is_some() => {
+                self.close_token(&event.token, WsCloseReason::kicked_out("kicked out")).await;
+            }
+            SaTokenEventType::KickOut => {
+                self.close_user(&event.login_id, WsCloseReason::kicked_out("kicked out")).await;
+            }
+            SaTokenEventType::Replaced => {
+                self.close_token(&event.token, WsCloseReason::replaced()).await;
+            }
+            SaTokenEventType::Banned => {
+                self.close_user(&event.login_id, WsCloseReason::banned()).await;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 通过 WebSocket 连接推送消息 | Pushes messages over registered WebSocket connections
+///
+/// 踢人通知（`MessageType::KickOut`）送达后会以 `KICKED_OUT` 关闭用户的所有连接。
+/// Kick-out notifications are delivered, then every connection of the user is
+/// closed with `KICKED_OUT`.
+pub struct WebSocketPusher {
+    registry: Arc<WsConnectionRegistry>,
+}
+
+impl WebSocketPusher {
+    pub fn new(registry: Arc<WsConnectionRegistry>) -> Self {
+        Self { registry }
+    }
+}
+
+#[async_trait]
+impl MessagePusher for WebSocketPusher {
+    async fn push(&self, login_id: &str, message: PushMessage) -> Result<(), SaTokenError> {
+        let kick_out = message.message_type == MessageType::KickOut;
+        let reason = message.content.clone();
+        self.registry.send_to_user(login_id, message).await;
+        if kick_out {
+            self.registry.close_user(login_id, WsCloseReason::kicked_out(reason)).await;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sa_token_storage_memory::MemoryStorage;
+    use crate::{SaTokenConfig, SaTokenManager, WsAuthManager};
+    use super::*;
+
+    fn auth_info(login_id: &str, token: &str) -> WsAuthInfo {
+        WsAuthInfo {
+            login_id: login_id.to_string(),
+            token: token.to_string(),
+            session_id: format!("ws:{}:{}", login_id, uuid::Uuid::new_v4()),
+            connect_time: crate::time::now(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_multi_device_and_replacement() {
+        let online = Arc::new(OnlineManager::new());
+        let registry = WsConnectionRegistry::new().with_online_manager(online.clone());
+
+        let (_, mut web) = registry.register(&auth_info("u1", "t1"), "web").await;
+        let (mobile, _mobile_rx) = registry.register(&auth_info("u1", "t2"), "mobile").await;
+        assert_eq!(registry.connections("u1").await.len(), 2);
+        assert_eq!(online.get_user_sessions("u1").await.len(), 2);
+
+        // 同一设备的新连接顶替旧连接
+        let (_, _web2) = registry.register(&auth_info("u1", "t3"), "web").await;
+        match web.recv().await {
+            Some(WsOutbound::Close(reason)) => assert_eq!(reason.code, WsCloseReason::REPLACED),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(registry.connections("u1").await.len(), 2);
+
+        registry.unregister(&mobile.session_id).await;
+        assert_eq!(registry.connection_count().await, 1);
+        assert_eq!(online.get_user_sessions("u1").await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_kick_out_sends_notice_then_close_frame() {
+        let online = Arc::new(OnlineManager::new());
+        let registry = Arc::new(WsConnectionRegistry::new().with_online_manager(online.clone()));
+        online.register_pusher(Arc::new(WebSocketPusher::new(registry.clone()))).await;
+
+        let manager = Arc::new(
+            SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default())
+                .with_online_manager(online.clone()),
+        );
+        manager.event_bus().register(registry.clone());
+
+        let token = manager.login("u2").await.unwrap();
+        let mut query = HashMap::new();
+        query.insert("token".to_string(), token.as_str().to_string());
+        let auth = WsAuthManager::new(manager.clone()).authenticate(&HashMap::new(), &query).await.unwrap();
+        let (_, mut outbound) = registry.register(&auth, "web").await;
+
+        manager.kick_out("u2").await.unwrap();
+        match outbound.recv().await {
+            Some(WsOutbound::Message(message)) => assert_eq!(message.message_type, MessageType::KickOut),
+            other => panic!("unexpected {:?}", other),
+        }
+        match outbound.recv().await {
+            Some(WsOutbound::Close(reason)) => assert_eq!(reason.code, WsCloseReason::KICKED_OUT),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(registry.connection_count().await, 0);
+        assert!(!online.is_online("u2").await);
+    }
+}
@@ -0,0 +1,52 @@
+/*
+ * Author: 金书记
+ *
+ * sa-token-rust C ABI
+ *
+ * Return codes:
+ *    1  yes / success
+ *    0  no
+ *   -1  invalid argument (NULL or non UTF-8)
+ *   -2  not initialized or initialization failed
+ */
+
+#ifndef SA_TOKEN_H
+#define SA_TOKEN_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define SA_TOKEN_OK 1
+#define SA_TOKEN_NO 0
+#define SA_TOKEN_INVALID_ARGUMENT -1
+#define SA_TOKEN_NOT_INITIALIZED -2
+
+/*
+ * Initialize once per process.
+ * config_json: SaTokenConfig as JSON (missing fields use defaults), NULL for defaults.
+ * redis_url:   Redis URL, NULL for in-memory storage.
+ * key_prefix:  Redis key prefix shared with the main service, NULL for none.
+ */
+int sa_token_init(const char *config_json, const char *redis_url, const char *key_prefix);
+
+/* 1 if the token is valid, 0 if unknown or expired. */
+int sa_token_validate_token(const char *token);
+
+/* Login ID bound to the token, NULL if invalid. Free with sa_token_free_string. */
+char *sa_token_get_login_id(const char *token);
+
+/* 1 if the account behind the token has the permission (supports "admin:*"). */
+int sa_token_check_permission(const char *token, const char *permission);
+
+/* Set the permissions of login_id from a JSON string array, e.g. ["user:read"]. */
+int sa_token_set_permissions(const char *login_id, const char *permissions_json);
+
+/* Free a string returned by this library. */
+void sa_token_free_string(char *ptr);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SA_TOKEN_H */
@@ -0,0 +1,320 @@
+// Author: 金书记
+//
+//! # sa-token-ffi
+//!
+//! sa-token-rust 的 C ABI，供 sidecar 或其他语言的服务直接复用 token 校验逻辑，
+//! 无需重新实现存储 key 格式。
+//!
+//! C ABI over the core validation logic so sidecars and services written in other
+//! languages can validate tokens against the same storage without reimplementing
+//! the key formats. The matching C header lives in `include/sa_token.h`.
+//!
+//! ## 返回值约定 | Return Codes
+//!
+//! - `1`: 是 / 成功 | yes / success
+//! - `0`: 否 | no
+//! - `-1`: 参数无效（空指针或非 UTF-8）| invalid argument (null or non UTF-8)
+//! - `-2`: 未初始化或初始化失败 | not initialized or initialization failed
+//!
+//! ## 使用示例 | Usage Example
+//!
+//! ```c
+//! #include "sa_token.h"
+//!
+//! sa_token_init("{\"token_name\":\"Authorization\",\"timeout\":7200}",
+//!               "redis://127.0.0.1:6379/0", "sa-token:");
+//!
+//! if (sa_token_validate_token(token) == 1) {
+//!     char *login_id = sa_token_get_login_id(token);
+//!     int allowed = sa_token_check_permission(token, "user:read");
+//!     sa_token_free_string(login_id);
+//! }
+//! ```
+
+use std::ffi::{c_char, CStr, CString};
+use std::sync::{Arc, Mutex, OnceLock};
+use tokio::runtime::Runtime;
+use sa_token_adapter::storage::SaStorage;
+use sa_token_core::{SaTokenConfig, SaTokenManager, StpUtil, TokenValue};
+use sa_token_storage_memory::MemoryStorage;
+
+/// 成功 / 是 | Success / yes
+pub const SA_TOKEN_OK: i32 = 1;
+/// 否 | No
+pub const SA_TOKEN_NO: i32 = 0;
+/// 参数无效 | Invalid argument
+pub const SA_TOKEN_INVALID_ARGUMENT: i32 = -1;
+/// 未初始化或初始化失败 | Not initialized or initialization failed
+pub const SA_TOKEN_NOT_INITIALIZED: i32 = -2;
+
+struct FfiState {
+    runtime: Runtime,
+    manager: SaTokenManager,
+}
+
+static STATE: OnceLock<FfiState> = OnceLock::new();
+/// 防止并发初始化时重复创建全局 Manager
+static INIT_LOCK: Mutex<()> = Mutex::new(());
+
+/// 读取 C 字符串：空指针返回 `Ok(None)`，非 UTF-8 返回 `Err`
+///
+/// # Safety
+/// `ptr` 为空或指向以 NUL 结尾的有效字符串
+unsafe fn read_str<'a>(ptr: *const c_char) -> Result<Option<&'a str>, i32> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map(Some)
+        .map_err(|_| SA_TOKEN_INVALID_ARGUMENT)
+}
+
+/// 读取必填的 C 字符串 | Reads a required C string
+///
+/// # Safety
+/// 同 [`read_str`]
+unsafe fn read_required<'a>(ptr: *const c_char) -> Result<&'a str, i32> {
+    unsafe { read_str(ptr) }?.ok_or(SA_TOKEN_INVALID_ARGUMENT)
+}
+
+fn state() -> Result<&'static FfiState, i32> {
+    STATE.get().ok_or(SA_TOKEN_NOT_INITIALIZED)
+}
+
+/// 将内部结果转换为返回码 | Maps an internal result to a return code
+fn to_code(result: Result<bool, i32>) -> i32 {
+    match result {
+        Ok(true) => SA_TOKEN_OK,
+        Ok(false) => SA_TOKEN_NO,
+        Err(code) => code,
+    }
+}
+
+/// 解析配置 JSON，未给出的字段使用默认值 | Parses config JSON, missing fields use defaults
+fn parse_config(json: &str) -> Result<SaTokenConfig, String> {
+    let overrides: serde_json::Value = serde_json::from_str(json)
+        .map_err(|e| format!("invalid config: {}", e))?;
+    let serde_json::Value::Object(overrides) = overrides else {
+        return Err("invalid config: expected a JSON object".to_string());
+    };
+    let mut config = serde_json::to_value(SaTokenConfig::default())
+        .map_err(|e| format!("invalid config: {}", e))?;
+    if let Some(fields) = config.as_object_mut() {
+        fields.extend(overrides);
+    }
+    serde_json::from_value(config).map_err(|e| format!("invalid config: {}", e))
+}
+
+fn init_state(config_json: Option<&str>, redis_url: Option<&str>, key_prefix: &str) -> Result<FfiState, String> {
+    let config = match config_json {
+        Some(json) => parse_config(json)?,
+        None => SaTokenConfig::default(),
+    };
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| format!("failed to start runtime: {}", e))?;
+
+    let storage: Arc<dyn SaStorage> = match redis_url {
+        Some(url) => connect_redis(&runtime, url, key_prefix)?,
+        None => Arc::new(MemoryStorage::new()),
+    };
+
+    let manager = SaTokenManager::new(storage, config);
+    StpUtil::init_manager(manager.clone());
+    Ok(FfiState { runtime, manager })
+}
+
+#[cfg(feature = "redis")]
+fn connect_redis(runtime: &Runtime, url: &str, key_prefix: &str) -> Result<Arc<dyn SaStorage>, String> {
+    let storage = runtime
+        .block_on(sa_token_storage_redis::RedisStorage::new(url, key_prefix))
+        .map_err(|e| format!("failed to connect redis: {}", e))?;
+    Ok(Arc::new(storage))
+}
+
+#[cfg(not(feature = "redis"))]
+fn connect_redis(_runtime: &Runtime, _url: &str, _key_prefix: &str) -> Result<Arc<dyn SaStorage>, String> {
+    Err("sa-token-ffi was built without the `redis` feature".to_string())
+}
+
+/// 初始化（整个进程只需调用一次）| Initialize once per process
+///
+/// - `config_json`: `SaTokenConfig` 的 JSON，NULL 使用默认配置 | config as JSON, NULL for defaults
+/// - `redis_url`: Redis 地址，NULL 使用内存存储 | Redis URL, NULL for in-memory storage
+/// - `key_prefix`: Redis key 前缀，需与主服务一致，NULL 表示无前缀 | key prefix shared with the main service
+///
+/// 重复调用直接返回 `1`。| Repeated calls return `1` without re-initializing.
+///
+/// # Safety
+/// 非空参数必须指向以 NUL 结尾的有效字符串。
+/// Non-null arguments must point to valid NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sa_token_init(
+    config_json: *const c_char,
+    redis_url: *const c_char,
+    key_prefix: *const c_char,
+) -> i32 {
+    let _guard = INIT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if STATE.get().is_some() {
+        return SA_TOKEN_OK;
+    }
+    let args = unsafe { (read_str(config_json), read_str(redis_url), read_str(key_prefix)) };
+    let (Ok(config_json), Ok(redis_url), Ok(key_prefix)) = args else {
+        return SA_TOKEN_INVALID_ARGUMENT;
+    };
+
+    match init_state(config_json, redis_url, key_prefix.unwrap_or("")) {
+        Ok(state) => {
+            let _ = STATE.set(state);
+            SA_TOKEN_OK
+        }
+        Err(e) => {
+            tracing::error!("sa-token-ffi: 初始化失败: {}", e);
+            SA_TOKEN_NOT_INITIALIZED
+        }
+    }
+}
+
+/// token 是否有效 | Whether the token is valid
+///
+/// 返回 `1` 有效，`0` 无效或已过期。| Returns `1` if valid, `0` if unknown or expired.
+///
+/// # Safety
+/// `token` 必须指向以 NUL 结尾的有效字符串。| `token` must be a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sa_token_validate_token(token: *const c_char) -> i32 {
+    let result = (|| {
+        let token = unsafe { read_required(token) }?;
+        let state = state()?;
+        Ok(state.runtime.block_on(state.manager.is_valid(&TokenValue::new(token))))
+    })();
+    to_code(result)
+}
+
+/// 获取 token 对应的 login_id | Login ID bound to the token
+///
+/// token 无效或参数错误时返回 NULL。返回的字符串需用 [`sa_token_free_string`] 释放。
+/// Returns NULL if the token is invalid. Free the result with [`sa_token_free_string`].
+///
+/// # Safety
+/// `token` 必须指向以 NUL 结尾的有效字符串。| `token` must be a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sa_token_get_login_id(token: *const c_char) -> *mut c_char {
+    let Ok(token) = (unsafe { read_required(token) }) else {
+        return std::ptr::null_mut();
+    };
+    let Ok(state) = state() else {
+        return std::ptr::null_mut();
+    };
+
+    match state.runtime.block_on(state.manager.get_token_info(&TokenValue::new(token))) {
+        Ok(info) => CString::new(info.login_id)
+            .map(CString::into_raw)
+            .unwrap_or(std::ptr::null_mut()),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// token 对应的账号是否拥有指定权限（支持 `admin:*` 通配符）
+///
+/// Whether the account behind `token` has `permission` (supports `admin:*` wildcards).
+/// token 无效时返回 `0`。| Returns `0` when the token is invalid.
+///
+/// # Safety
+/// 参数必须指向以 NUL 结尾的有效字符串。| Arguments must be valid NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sa_token_check_permission(token: *const c_char, permission: *const c_char) -> i32 {
+    let result = (|| {
+        let token = unsafe { read_required(token) }?;
+        let permission = unsafe { read_required(permission) }?;
+        let state = state()?;
+        state.runtime.block_on(async {
+            match state.manager.get_token_info(&TokenValue::new(token)).await {
+                Ok(info) => Ok(StpUtil::has_permission(info.login_id, permission).await),
+                Err(_) => Ok(false),
+            }
+        })
+    })();
+    to_code(result)
+}
+
+/// 设置账号的权限列表（JSON 字符串数组），用于在本进程内加载权限数据
+///
+/// Sets the permissions of `login_id` from a JSON string array, so the host
+/// process can load permission data for [`sa_token_check_permission`].
+///
+/// # Safety
+/// 参数必须指向以 NUL 结尾的有效字符串。| Arguments must be valid NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sa_token_set_permissions(login_id: *const c_char, permissions_json: *const c_char) -> i32 {
+    let result = (|| {
+        let login_id = unsafe { read_required(login_id) }?;
+        let permissions_json = unsafe { read_required(permissions_json) }?;
+        let permissions: Vec<String> = serde_json::from_str(permissions_json)
+            .map_err(|_| SA_TOKEN_INVALID_ARGUMENT)?;
+        let state = state()?;
+        state.runtime
+            .block_on(StpUtil::set_permissions(login_id, permissions))
+            .map(|_| true)
+            .map_err(|_| SA_TOKEN_INVALID_ARGUMENT)
+    })();
+    to_code(result)
+}
+
+/// 释放本库返回的字符串 | Frees a string returned by this library
+///
+/// # Safety
+/// `ptr` 必须为 NULL 或由本库返回且尚未释放。
+/// `ptr` must be NULL or a string returned by this library that was not freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sa_token_free_string(ptr: *mut c_char) {
+    if !ptr.is_null() {
+        drop(unsafe { CString::from_raw(ptr) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    fn login(login_id: &str) -> CString {
+        let config = c(r#"{"token_name":"Authorization","timeout":3600}"#);
+        assert_eq!(unsafe { sa_token_init(config.as_ptr(), std::ptr::null(), std::ptr::null()) }, SA_TOKEN_OK);
+        let state = state().unwrap();
+        let token = state.runtime.block_on(state.manager.login(login_id)).unwrap();
+        c(token.as_str())
+    }
+
+    #[test]
+    fn test_validate_and_get_login_id() {
+        let token = login("user_ffi");
+        unsafe {
+            assert_eq!(sa_token_validate_token(token.as_ptr()), SA_TOKEN_OK);
+            assert_eq!(sa_token_validate_token(c("unknown").as_ptr()), SA_TOKEN_NO);
+            assert_eq!(sa_token_validate_token(std::ptr::null()), SA_TOKEN_INVALID_ARGUMENT);
+
+            let login_id = sa_token_get_login_id(token.as_ptr());
+            assert_eq!(CStr::from_ptr(login_id).to_str().unwrap(), "user_ffi");
+            sa_token_free_string(login_id);
+            assert!(sa_token_get_login_id(c("unknown").as_ptr()).is_null());
+        }
+    }
+
+    #[test]
+    fn test_check_permission() {
+        let token = login("admin_ffi");
+        unsafe {
+            let perms = c(r#"["admin:*"]"#);
+            assert_eq!(sa_token_set_permissions(c("admin_ffi").as_ptr(), perms.as_ptr()), SA_TOKEN_OK);
+            assert_eq!(sa_token_check_permission(token.as_ptr(), c("admin:delete").as_ptr()), SA_TOKEN_OK);
+            assert_eq!(sa_token_check_permission(token.as_ptr(), c("user:read").as_ptr()), SA_TOKEN_NO);
+            assert_eq!(sa_token_check_permission(c("unknown").as_ptr(), c("admin:delete").as_ptr()), SA_TOKEN_NO);
+        }
+    }
+}
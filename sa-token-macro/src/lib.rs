@@ -42,6 +42,16 @@
 //! // 也可以用在结构体上，表示整个控制器都忽略认证
 //! #[sa_ignore]
 //! struct PublicController;
+//!
+//! // 检查宏也可以用在 impl 块上：块内每个 async 方法都会插入检查，
+//! // 同步方法与标注了 #[sa_ignore] 的方法除外
+//! #[sa_check_role("admin")]
+//! impl AdminController {
+//!     async fn list_users(&self) -> Result<String, SaTokenError> { Ok("users".into()) }
+//!
+//!     #[sa_ignore]
+//!     async fn health(&self) -> Result<String, SaTokenError> { Ok("ok".into()) }
+//! }
 //! ```
 
 use proc_macro::TokenStream;
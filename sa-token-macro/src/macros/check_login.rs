@@ -5,9 +5,8 @@
 //! Provides compile-time login check that automatically inserts authentication verification
 
 use proc_macro::TokenStream;
-use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{parse_macro_input, ItemFn};
+use crate::utils::expand_check;
 
 /// Login check macro
 /// 
@@ -48,27 +47,6 @@ use syn::{parse_macro_input, ItemFn};
 /// - Function must return Result type for `?` operator to work
 /// - Supports generic parameters and lifetime annotations
 pub fn sa_check_login_impl(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(item as ItemFn);
-    
-    // Extract function signature components
-    let fn_name = &input.sig.ident;
-    let fn_inputs = &input.sig.inputs;
-    let fn_output = &input.sig.output;
-    let fn_body = &input.block;
-    let fn_attrs = &input.attrs;
-    let fn_vis = &input.vis;
-    let fn_asyncness = &input.sig.asyncness;
-    let fn_generics = &input.sig.generics;
-    let fn_where_clause = &input.sig.generics.where_clause;
-    
-    // Check if function is async
-    if fn_asyncness.is_none() {
-        return syn::Error::new_spanned(
-            fn_name,
-            "sa_check_login macro requires function to be async (async fn)"
-        ).to_compile_error().into();
-    }
-    
     // Generate authentication check code
     // Insert login check at the beginning of function body
     let auth_check = quote! {
@@ -79,22 +57,7 @@ pub fn sa_check_login_impl(_attr: TokenStream, item: TokenStream) -> TokenStream
         }
     };
     
-    // Generate expanded function
-    let expanded: TokenStream2 = quote! {
-        // Preserve original attributes
-        #(#fn_attrs)*
-        // Add metadata marker (for middleware recognition)
-        #[doc(hidden)]
-        #fn_vis #fn_asyncness fn #fn_name #fn_generics(#fn_inputs) #fn_output #fn_where_clause {
-            // Insert authentication check at beginning of function body
-            #auth_check
-            
-            // Original function body
-            #fn_body
-        }
-    };
-    
-    expanded.into()
+    expand_check(item, auth_check, "sa_check_login macro requires function to be async (async fn)")
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
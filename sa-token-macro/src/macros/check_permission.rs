@@ -5,9 +5,9 @@
 //! 提供细粒度的权限控制，支持通配符和精确匹配
 
 use proc_macro::TokenStream;
-use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{parse_macro_input, ItemFn, LitStr, Error};
+use syn::{parse_macro_input, LitStr, Error};
+use crate::utils::expand_check;
 
 /// 检查权限的宏
 /// 
@@ -15,10 +15,12 @@ use syn::{parse_macro_input, ItemFn, LitStr, Error};
 /// 
 /// # 参数
 /// 
-/// - `permission` - 权限标识符，支持以下格式：
-///   - 精确匹配: `"user:delete"`
-///   - 通配符: `"admin:*"` (表示 admin 模块的所有权限)
-///   - 全局通配符: `"*"` (表示所有权限)
+/// - `permission` - 所需的权限标识符，如 `"user:delete"`
+/// 
+/// 与 `StpUtil::has_permission` 使用同一个匹配器（`PermissionMatcher`，分隔符见
+/// `permission_separator`）：授予的权限可以含通配符，授予 `"user:*"` 或 `"*"` 满足
+/// `"user:delete"`，授予 `"user:*:read"` 满足 `"user:42:read"`；所需权限中的 `*`
+/// 按字面匹配，只有授予了同名权限或 `"*"` 的用户才能通过。
 /// 
 /// # 工作原理
 /// 
@@ -69,38 +71,12 @@ pub fn sa_check_permission_impl(attr: TokenStream, item: TokenStream) -> TokenSt
         return TokenStream::from(err.to_compile_error());
     }
     
-    let input = parse_macro_input!(item as ItemFn);
-    
-    let fn_name = &input.sig.ident;
-    let fn_inputs = &input.sig.inputs;
-    let fn_output = &input.sig.output;
-    let fn_body = &input.block;
-    let fn_attrs = &input.attrs;
-    let fn_vis = &input.vis;
-    let fn_asyncness = &input.sig.asyncness;
-    let fn_generics = &input.sig.generics;
-    let fn_where_clause = &input.sig.generics.where_clause;
-    
-    if fn_asyncness.is_none() {
-        return syn::Error::new_spanned(fn_name, "Macro requires async function")
-            .to_compile_error().into();
-    }
-    
     let check_code = quote! {
         let __login_id = sa_token_core::StpUtil::get_login_id_as_string().await?;
         sa_token_core::StpUtil::check_permission(&__login_id, #perm_value).await?;
     };
     
-    let expanded: TokenStream2 = quote! {
-        #(#fn_attrs)*
-        #[doc(hidden)]
-        #fn_vis #fn_asyncness fn #fn_name #fn_generics(#fn_inputs) #fn_output #fn_where_clause {
-            #check_code
-            #fn_body
-        }
-    };
-    
-    expanded.into()
+    expand_check(item, check_code, "Macro requires async function")
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
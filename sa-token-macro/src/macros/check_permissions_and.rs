@@ -3,14 +3,18 @@
 //! 多权限检查宏（AND逻辑）
 
 use proc_macro::TokenStream;
-use proc_macro2::{Span, TokenStream as TokenStream2};
+use proc_macro2::Span;
 use quote::quote;
-use syn::{parse_macro_input, ItemFn, LitStr, Token, parse::Parser};
+use syn::{LitStr, Token, parse::Parser};
+use crate::utils::expand_check;
 
 /// 同时检查多个权限（AND逻辑）
 /// 
 /// 用户必须拥有所有指定的权限才能访问
 /// 
+/// 检查失败时返回 `SaTokenError::PermissionsDenied`，其中包含所有缺少的权限，
+/// 可通过 `err.missing()` 获取
+/// 
 /// # 示例
 /// 
 /// ```rust,ignore
@@ -21,8 +25,6 @@ use syn::{parse_macro_input, ItemFn, LitStr, Token, parse::Parser};
 /// }
 /// ```
 pub fn sa_check_permissions_and_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(item as ItemFn);
-    
     let parser = syn::punctuated::Punctuated::<LitStr, Token![,]>::parse_terminated;
     let permissions = parser.parse(attr).unwrap_or_default();
     let perm_lits: Vec<LitStr> = permissions.iter().cloned().collect();
@@ -31,40 +33,11 @@ pub fn sa_check_permissions_and_impl(attr: TokenStream, item: TokenStream) -> To
             .to_compile_error()
             .into();
     }
-    let perm_values: Vec<String> = perm_lits.iter().map(|p| p.value()).collect();
-    let perm_desc = LitStr::new(&perm_values.join(" & "), Span::call_site());
-    
-    let fn_name = &input.sig.ident;
-    let fn_inputs = &input.sig.inputs;
-    let fn_output = &input.sig.output;
-    let fn_body = &input.block;
-    let fn_attrs = &input.attrs;
-    let fn_vis = &input.vis;
-    let fn_asyncness = &input.sig.asyncness;
-    let fn_generics = &input.sig.generics;
-    let fn_where_clause = &input.sig.generics.where_clause;
-    
-    if fn_asyncness.is_none() {
-        return syn::Error::new_spanned(fn_name, "Macro requires async function")
-            .to_compile_error()
-            .into();
-    }
     
     let check_code = quote! {
         let __login_id = sa_token_core::StpUtil::get_login_id_as_string().await?;
-        if !sa_token_core::StpUtil::has_permissions_and(&__login_id, &[#(#perm_lits),*]).await {
-            return Err(sa_token_core::SaTokenError::PermissionDeniedDetail(String::from(#perm_desc)).into());
-        }
-    };
-    
-    let expanded: TokenStream2 = quote! {
-        #(#fn_attrs)*
-        #[doc(hidden)]
-        #fn_vis #fn_asyncness fn #fn_name #fn_generics(#fn_inputs) #fn_output #fn_where_clause {
-            #check_code
-            #fn_body
-        }
+        sa_token_core::StpUtil::check_permissions_and(&__login_id, &[#(#perm_lits),*]).await?;
     };
     
-    expanded.into()
+    expand_check(item, check_code, "Macro requires async function")
 }
@@ -2,7 +2,7 @@
 //
 //! 宏工具函数
 
-use syn::{ItemFn, punctuated::Punctuated, Token, LitStr};
+use syn::{parse_quote, punctuated::Punctuated, Attribute, ImplItem, Item, ItemFn, LitStr, Token};
 use quote::quote;
 use proc_macro2::TokenStream;
 
@@ -75,3 +75,57 @@ pub fn generate_auth_metadata(check_type: &str, value: Option<&str>) -> TokenStr
         #[cfg_attr(feature = "sa-token-metadata", sa_token_auth_check = #metadata)]
     }
 }
+
+/// 检查宏的展开：函数体开头插入检查代码
+///
+/// 用在函数上时要求是 async fn；用在 impl 块上时插入块内每个 async 方法，
+/// 同步方法与标注了 `#[sa_ignore]` 的方法保持不变。
+pub fn expand_check(item: proc_macro::TokenStream, check_code: TokenStream, not_async: &str) -> proc_macro::TokenStream {
+    let expanded = match syn::parse::<Item>(item) {
+        Ok(Item::Fn(input)) => {
+            if input.sig.asyncness.is_none() {
+                return syn::Error::new_spanned(&input.sig.ident, not_async).to_compile_error().into();
+            }
+            let fn_name = &input.sig.ident;
+            let fn_inputs = &input.sig.inputs;
+            let fn_output = &input.sig.output;
+            let fn_body = &input.block;
+            let fn_attrs = &input.attrs;
+            let fn_vis = &input.vis;
+            let fn_asyncness = &input.sig.asyncness;
+            let fn_generics = &input.sig.generics;
+            let fn_where_clause = &input.sig.generics.where_clause;
+            quote! {
+                #(#fn_attrs)*
+                #[doc(hidden)]
+                #fn_vis #fn_asyncness fn #fn_name #fn_generics(#fn_inputs) #fn_output #fn_where_clause {
+                    #check_code
+                    #fn_body
+                }
+            }
+        }
+        Ok(Item::Impl(mut item_impl)) => {
+            for impl_item in &mut item_impl.items {
+                if let ImplItem::Fn(method) = impl_item
+                    && method.sig.asyncness.is_some()
+                    && !is_ignored(&method.attrs)
+                {
+                    let stmts = &method.block.stmts;
+                    method.block = parse_quote!({
+                        #check_code
+                        #(#stmts)*
+                    });
+                }
+            }
+            quote! { #item_impl }
+        }
+        Ok(other) => syn::Error::new_spanned(other, "expected an async fn or an impl block").to_compile_error(),
+        Err(e) => e.to_compile_error(),
+    };
+    expanded.into()
+}
+
+/// 方法是否标注了 `#[sa_ignore]`
+fn is_ignored(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| attr.path().segments.last().is_some_and(|segment| segment.ident == "sa_ignore"))
+}
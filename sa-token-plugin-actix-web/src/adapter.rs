@@ -51,7 +51,7 @@ impl<'a> SaRequest for ActixRequestAdapter<'a> {
         self.request.method().to_string()
     }
     
-    fn get_client_ip(&self) -> Option<String> {
+    fn remote_addr(&self) -> Option<String> {
         self.request.peer_addr()
             .map(|addr| addr.ip().to_string())
     }
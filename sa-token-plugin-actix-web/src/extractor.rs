@@ -2,9 +2,10 @@
 //
 //! Actix-web提取器
 
-use actix_web::{FromRequest, HttpRequest, HttpMessage, dev::Payload, error::ErrorUnauthorized};
+use actix_web::{FromRequest, HttpRequest, HttpMessage, dev::Payload};
 use std::future::{ready, Ready};
-use sa_token_core::{token::TokenValue, error::messages};
+use sa_token_core::{token::TokenValue, SaTokenContext, SaTokenError, StpUtil};
+use crate::middleware::rejection_error;
 
 /// Token 提取器 - 必须存在，否则返回错误
 pub struct SaTokenExtractor(pub TokenValue);
@@ -16,10 +17,7 @@ impl FromRequest for SaTokenExtractor {
     fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
         match req.extensions().get::<TokenValue>() {
             Some(token) => ready(Ok(SaTokenExtractor(token.clone()))),
-            None => ready(Err(ErrorUnauthorized(serde_json::json!({
-                "code": 401,
-                "message": messages::AUTH_ERROR
-            })))),
+            None => ready(Err(not_login(req))),
         }
     }
 }
@@ -47,10 +45,15 @@ impl FromRequest for LoginIdExtractor {
     fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
         match req.extensions().get::<String>() {
             Some(login_id) => ready(Ok(LoginIdExtractor(login_id.clone()))),
-            None => ready(Err(ErrorUnauthorized(serde_json::json!({
-                "code": 401,
-                "message": messages::AUTH_ERROR
-            })))),
+            None => ready(Err(not_login(req))),
         }
     }
 }
+
+/// 未登录时的拒绝响应，由配置的 `ErrorRenderer` 生成；受限 token 返回 `PasswordExpired`
+fn not_login(req: &HttpRequest) -> actix_web::Error {
+    let err = req.extensions().get::<SaTokenContext>()
+        .map(|ctx| ctx.login_error())
+        .unwrap_or(SaTokenError::NotLogin);
+    rejection_error(StpUtil::render_error(&err), err)
+}
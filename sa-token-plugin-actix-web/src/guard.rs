@@ -0,0 +1,287 @@
+// Author: 金书记
+//
+//! 路由守卫 | Route guards
+//!
+//! [`SaGuard`] 让路由直接声明访问要求，无需为每个权限编写中间件，有两种用法：
+//!
+//! - `.guard(SaGuard::role("admin"))`：实现 `actix_web::guard::Guard`，不满足时路由不匹配，
+//!   请求继续尝试后续路由（最终为 404）。Guard 是同步的，只能使用登录时预取的权限快照，
+//!   需开启 `prefetch_authz`，未开启时权限/角色守卫一律不匹配。
+//! - `.wrap(SaGuard::role("admin"))`：异步检查，实时查询权限检查器，不满足时按
+//!   `ErrorRenderer` 返回 401/403。
+//!
+//! 两种用法都依赖外层的 `SaTokenMiddleware`（或 `SaTokenLayer`）解析 token。
+//!
+//! [`SaGuard`] lets routes declare their access requirement without a
+//! middleware per permission. It works two ways:
+//!
+//! - `.guard(SaGuard::role("admin"))` implements `actix_web::guard::Guard`.
+//!   A failed check makes the route not match, so routing moves on (and
+//!   ends in 404). Guards are synchronous and can only use the permissions
+//!   prefetched at login; enable `prefetch_authz`, otherwise permission and
+//!   role guards never match.
+//! - `.wrap(SaGuard::role("admin"))` checks asynchronously against the live
+//!   permission checkers and rejects with 401/403 through the `ErrorRenderer`.
+//!
+//! Both rely on an outer `SaTokenMiddleware` (or `SaTokenLayer`) resolving the token.
+//!
+//! ```rust,ignore
+//! App::new()
+//!     .wrap(SaTokenMiddleware::new(state.clone()))
+//!     .service(
+//!         web::scope("/admin")
+//!             .guard(SaGuard::role("admin"))
+//!             .route("/users/{id}", web::delete().to(delete_user).wrap(SaGuard::permission("user:delete"))),
+//!     )
+//! ```
+
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
+use std::rc::Rc;
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    guard::{Guard, GuardContext},
+    Error, HttpMessage,
+};
+use sa_token_core::{permission::PermissionMatcher, CheckMode, SaCheck, SaTokenContext, StpUtil};
+use crate::middleware::rejection_error;
+
+/// 路由守卫，既可作为 `Guard` 也可作为中间件使用 | Route guard usable as a `Guard` or as middleware
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaGuard {
+    check: SaCheck,
+}
+
+impl SaGuard {
+    /// 按任意 `SaCheck` 创建 | Create from any `SaCheck`
+    pub fn new(check: SaCheck) -> Self {
+        Self { check }
+    }
+
+    /// 必须登录 | Login required
+    pub fn login() -> Self {
+        Self::new(SaCheck::login())
+    }
+
+    /// 必须拥有指定权限 | A single permission required
+    pub fn permission(permission: impl Into<String>) -> Self {
+        Self::new(SaCheck::permission(permission))
+    }
+
+    /// 必须拥有全部权限 | All permissions required
+    pub fn permissions_and<I, S>(permissions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::new(SaCheck::permissions_and(permissions))
+    }
+
+    /// 拥有任一权限即可 | Any of the permissions
+    pub fn permissions_or<I, S>(permissions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::new(SaCheck::permissions_or(permissions))
+    }
+
+    /// 必须拥有指定角色 | A single role required
+    pub fn role(role: impl Into<String>) -> Self {
+        Self::new(SaCheck::role(role))
+    }
+
+    /// 必须拥有全部角色 | All roles required
+    pub fn roles_and<I, S>(roles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::new(SaCheck::roles_and(roles))
+    }
+
+    /// 拥有任一角色即可 | Any of the roles
+    pub fn roles_or<I, S>(roles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::new(SaCheck::roles_or(roles))
+    }
+
+    /// 按上下文中的登录 ID 与预取的权限快照同步判定 | Decide synchronously from the context's login ID and prefetched snapshot
+    fn allows(&self, ctx: &SaTokenContext) -> bool {
+        let (items, mode, is_permission) = match &self.check {
+            SaCheck::Optional => return true,
+            SaCheck::Login => return ctx.login_id.is_some(),
+            SaCheck::Permissions(items, mode) => (items, mode, true),
+            SaCheck::Roles(items, mode) => (items, mode, false),
+        };
+        if ctx.login_id.is_none() {
+            return false;
+        }
+        if StpUtil::dev_bypass_login_id().is_some() {
+            return true;
+        }
+
+        let Some(snapshot) = ctx.token_info.as_ref().and_then(|info| info.authz.as_ref()) else {
+            tracing::debug!("Sa-Token: 守卫 {} 缺少预取的权限快照，请开启 prefetch_authz", self.check.stage());
+            return false;
+        };
+        let matcher = PermissionMatcher::new(StpUtil::config().permission_separator.as_str());
+        let granted = |item: &String| match is_permission {
+            true => matcher.matches_any(&snapshot.permissions, item),
+            false => snapshot.roles.contains(item),
+        };
+        match mode {
+            CheckMode::And => items.iter().all(granted),
+            CheckMode::Or => items.iter().any(granted),
+        }
+    }
+}
+
+impl Guard for SaGuard {
+    fn check(&self, ctx: &GuardContext<'_>) -> bool {
+        match ctx.req_data().get::<SaTokenContext>() {
+            Some(sa_ctx) => self.allows(sa_ctx),
+            None => matches!(self.check, SaCheck::Optional),
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for SaGuard
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = SaGuardService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(SaGuardService {
+            service: Rc::new(service),
+            check: Rc::new(self.check.clone()),
+        }))
+    }
+}
+
+pub struct SaGuardService<S> {
+    service: Rc<S>,
+    check: Rc<SaCheck>,
+}
+
+impl<S, B> Service<ServiceRequest> for SaGuardService<S>
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output=Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let check = Rc::clone(&self.check);
+
+        Box::pin(async move {
+            let login_id = req.extensions().get::<SaTokenContext>().and_then(|ctx| ctx.login_id.clone());
+            match check.check(login_id.as_deref()).await {
+                Ok(()) => service.call(req).await,
+                Err(err) => Err(rejection_error(StpUtil::render_error(&err), err)),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, OnceLock};
+    use actix_web::{test, web, App, HttpResponse};
+    use actix_web::body::BoxBody;
+    use actix_web::http::StatusCode;
+    use sa_token_core::{SaTokenConfig, SaTokenManager, TokenValue};
+    use sa_token_storage_memory::MemoryStorage;
+    use crate::{SaTokenMiddleware, SaTokenState};
+
+    /// 守卫读取全局 `StpUtil` 的配置与权限，测试共用同一个状态 | Guards read the global `StpUtil` config and permissions, so the tests share one state
+    fn state() -> SaTokenState {
+        static STATE: OnceLock<SaTokenState> = OnceLock::new();
+        STATE.get_or_init(|| {
+            let config = SaTokenConfig { prefetch_authz: true, ..Default::default() };
+            SaTokenState::from_manager(SaTokenManager::new(Arc::new(MemoryStorage::new()), config))
+        }).clone()
+    }
+
+    async fn ok() -> &'static str {
+        "ok"
+    }
+
+    /// 登录前授予角色与权限，使其进入预取的快照 | Grant roles and permissions before login so they land in the prefetched snapshot
+    async fn login(state: &SaTokenState, login_id: &str, roles: &[&str], permissions: &[&str]) -> TokenValue {
+        StpUtil::set_roles(login_id, roles.iter().map(|r| r.to_string()).collect()).await.unwrap();
+        StpUtil::set_permissions(login_id, permissions.iter().map(|p| p.to_string()).collect()).await.unwrap();
+        state.manager.login(login_id).await.unwrap()
+    }
+
+    fn get(path: &str, token: Option<&TokenValue>) -> test::TestRequest {
+        let request = test::TestRequest::get().uri(path);
+        match token {
+            Some(token) => request.insert_header(("sa-token", token.as_str())),
+            None => request,
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_guard_only_matches_routes_the_user_may_access() {
+        let state = state();
+        let admin = login(&state, "guard_admin", &["admin"], &[]).await;
+        let user = login(&state, "guard_user", &[], &[]).await;
+        let app = test::init_service(App::new()
+            .wrap(SaTokenMiddleware::new(state.clone()))
+            .service(web::scope("/admin").guard(SaGuard::role("admin")).route("/users", web::get().to(ok)))
+            .default_service(web::to(|| async { HttpResponse::NotFound().finish() }))).await;
+
+        let response = test::call_service(&app, get("/admin/users", Some(&admin)).to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        for token in [Some(&user), None] {
+            let response = test::call_service(&app, get("/admin/users", token).to_request()).await;
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_wrapped_guard_rejects_with_status_and_reason() {
+        let state = state();
+        let admin = login(&state, "wrap_admin", &[], &["user:delete"]).await;
+        let user = login(&state, "wrap_user", &[], &[]).await;
+        let app = test::init_service(App::new()
+            .wrap(SaTokenMiddleware::new(state.clone()))
+            .service(web::resource("/users/1").wrap(SaGuard::permission("user:delete")).route(web::delete().to(ok)))).await;
+        let call = |token: Option<&TokenValue>| {
+            let request = get("/users/1", token).method(actix_web::http::Method::DELETE).to_request();
+            let app = &app;
+            async move {
+                let response = match test::try_call_service(app, request).await {
+                    Ok(response) => response.map_into_boxed_body(),
+                    Err(e) => ServiceResponse::<BoxBody>::new(test::TestRequest::default().to_http_request(), e.error_response()),
+                };
+                let status = response.status();
+                (status, String::from_utf8(test::read_body(response).await.to_vec()).unwrap())
+            }
+        };
+
+        assert_eq!(call(Some(&admin)).await, (StatusCode::OK, "ok".to_string()));
+        let (status, body) = call(Some(&user)).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert!(body.contains("user:delete"));
+        assert_eq!(call(None).await.0, StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,47 @@
+// Author: 金书记
+//
+//! JWKS 公钥发布接口
+//!
+//! 把 `JwtKeyStore` 中的公钥以 JWK Set 形式挂在 `/.well-known/jwks.json`，
+//! 资源服务拉取后即可按 token 头中的 `kid` 验证签名。密钥轮换后接口立即返回新的公钥集合。
+
+use actix_web::{http::header, web, HttpResponse};
+use serde_json::json;
+use sa_token_core::JwtKeyStore;
+
+/// JWKS 接口路径
+pub const JWKS_PATH: &str = "/.well-known/jwks.json";
+
+/// 注册 JWKS 接口
+///
+/// # 示例
+/// ```rust,ignore
+/// let keys = JwtKeyStore::new(signing_key)?;
+/// HttpServer::new(move || {
+///     App::new()
+///         .configure(jwks_config(keys.clone()))
+///         .service(user_info)
+/// })
+/// ```
+pub fn jwks_config(keys: JwtKeyStore) -> impl FnOnce(&mut web::ServiceConfig) {
+    move |cfg| {
+        cfg.service(
+            web::resource(JWKS_PATH)
+                .app_data(web::Data::new(keys))
+                .route(web::get().to(serve_jwks)),
+        );
+    }
+}
+
+async fn serve_jwks(keys: web::Data<JwtKeyStore>) -> HttpResponse {
+    match keys.jwks() {
+        // 允许资源服务短时间缓存，轮换时保留旧密钥即可平滑过渡
+        Ok(jwks) => HttpResponse::Ok()
+            .insert_header((header::CACHE_CONTROL, "public, max-age=300"))
+            .json(jwks),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "code": 500,
+            "message": e.to_string()
+        })),
+    }
+}
@@ -3,13 +3,12 @@ use std::pin::Pin;
 use std::rc::Rc;
 use actix_web::{
     dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{HeaderMap, HeaderValue, SET_COOKIE},
     Error, HttpMessage,
 };
 use crate::SaTokenState;
 use crate::adapter::ActixRequestAdapter;
-use sa_token_adapter::context::SaRequest;
-use sa_token_core::{token::TokenValue, SaTokenContext};
-use std::sync::Arc;
+use sa_token_core::{token::TokenValue, SaTokenContext, ACCEPT_LANGUAGE_HEADER, TIMEZONE_HEADER, SAME_TOKEN_HEADER};
 
 #[derive(Clone)]
 pub struct SaTokenLayer {
@@ -67,74 +66,50 @@ where
         
         Box::pin(async move {
             let mut ctx = SaTokenContext::new();
+            let header = |name: &str| req.headers().get(name).and_then(|v| v.to_str().ok());
+            ctx.capture_locale(header(ACCEPT_LANGUAGE_HEADER), header(TIMEZONE_HEADER));
+            ctx.capture_same_token(header(SAME_TOKEN_HEADER));
+            ctx.capture_client(state.manager.client_ip(&ActixRequestAdapter::new(req.request())).as_deref(), header("user-agent"));
             
             if let Some(token_str) = extract_token_from_request(&req, &state) {
                 tracing::debug!("Sa-Token: extracted token from request: {}", token_str);
                 let token = TokenValue::new(token_str);
                 
-                if state.manager.is_valid(&token).await {
-                    req.extensions_mut().insert(token.clone());
-                    
-                    if let Ok(token_info) = state.manager.get_token_info(&token).await {
-                        let login_id = token_info.login_id.clone();
-                        req.extensions_mut().insert(login_id.clone());
-                        
-                        ctx.token = Some(token.clone());
-                        ctx.token_info = Some(Arc::new(token_info));
-                        ctx.login_id = Some(login_id);
-                    }
+                if let Some(login_id) = state.manager.authenticate_context(&token, &mut ctx).await {
+                    req.extensions_mut().insert(token);
+                    req.extensions_mut().insert(login_id);
                 }
             }
             
-            SaTokenContext::set_current(ctx);
-            let result = service.call(req).await;
-            SaTokenContext::clear();
-            result
+            // 开发模式免登录 | Dev bypass
+            if let Some(login_id) = state.manager.dev_bypass_login_id().filter(|_| ctx.login_id.is_none()) {
+                req.extensions_mut().insert(login_id.to_string());
+                ctx.login_id = Some(login_id.to_string());
+            }
+            
+            // 供 `SaGuard` 读取 | Read by `SaGuard`
+            req.extensions_mut().insert(ctx.clone());
+            let response_cookie = ctx.clone();
+            let mut res = SaTokenContext::scope(ctx, service.call(req)).await?;
+            
+            // 处理期间登录/登出产生的 token Cookie（`is_write_cookie`）
+            append_response_cookie(&response_cookie, res.headers_mut());
+            Ok(res)
         })
     }
 }
 
+/// 先调用自定义提取器，再按 `SaTokenConfig::token_sources` 的顺序查找 |
+/// Custom extractors first, then `SaTokenConfig::token_sources` in order
 fn extract_token_from_request(req: &ServiceRequest, state: &SaTokenState) -> Option<String> {
-    let adapter = ActixRequestAdapter::new(req.request());
-    let token_name = &state.manager.config.token_name;
-    
-    // 1. 优先从 Header 中获取（检查 token_name 配置的头）
-    if let Some(token) = adapter.get_header(token_name) {
-        return Some(extract_bearer_token(&token));
-    }
-    
-    // 2. 如果 token_name 不是 "Authorization"，也尝试从 "Authorization" 头获取
-    if token_name != "Authorization" {
-        if let Some(token) = adapter.get_header("Authorization") {
-            return Some(extract_bearer_token(&token));
-        }
-    }
-    
-    // 3. 从 Cookie 中获取
-    if let Some(token) = adapter.get_cookie(token_name) {
-        return Some(token);
-    }
-    
-    // 4. 从 Query 参数中获取
-    if let Some(query) = req.query_string().split('&').find_map(|pair| {
-        let mut parts = pair.split('=');
-        if let (Some(key), Some(value)) = (parts.next(), parts.next()) {
-            if key == token_name {
-                return urlencoding::decode(value).ok().map(|s| s.to_string());
-            }
-        }
-        None
-    }) {
-        return Some(query);
-    }
-    
-    None
+    state.manager.extract_token(&ActixRequestAdapter::new(req.request())).map(|(token, _)| token)
 }
 
-fn extract_bearer_token(token: &str) -> String {
-    if token.starts_with("Bearer ") {
-        token[7..].to_string()
-    } else {
-        token.to_string()
+/// 把请求处理期间记录的 token Cookie 追加到响应头，不覆盖已有的 `Set-Cookie`
+pub(crate) fn append_response_cookie(ctx: &SaTokenContext, headers: &mut HeaderMap) {
+    if let Some(cookie) = ctx.take_response_cookie()
+        && let Ok(value) = HeaderValue::from_str(&cookie.header_value())
+    {
+        headers.append(SET_COOKIE, value);
     }
 }
@@ -27,7 +27,7 @@
 //!     .build();
 //! 
 //! // 2. 登录
-//! let token = state.manager().login("user123").await?;
+//! let token = state.manager.login("user123").await?;
 //! 
 //! // 3. 使用宏检查权限
 //! #[sa_check_login]
@@ -45,17 +45,28 @@ pub mod middleware;
 pub mod extractor;
 pub mod adapter;
 pub mod layer;
+pub mod jwks;
+pub mod sso;
+pub mod oauth2;
+pub mod sse;
+pub mod guard;
 
 // ============================================================================
 // Actix-web 框架集成（本插件特有）
 // ============================================================================
 pub use middleware::SaCheckLoginMiddleware;
+pub use middleware::SaSameTokenMiddleware;
+pub use guard::SaGuard;
 pub use layer::SaTokenLayer;
 
 // 为保持向后兼容，SaTokenMiddleware 从 layer 模块重新导出
 pub use middleware::SaTokenMiddleware;
 pub use extractor::{SaTokenExtractor, OptionalSaTokenExtractor, LoginIdExtractor};
 pub use adapter::{ActixRequestAdapter, ActixResponseAdapter};
+pub use jwks::{jwks_config, JWKS_PATH};
+pub use sse::{sse_config, SSE_PATH};
+pub use sso::sso_scope;
+pub use oauth2::oauth2_scope;
 
 // ============================================================================
 // 重新导出核心功能（sa-token-core）
@@ -80,23 +91,30 @@ pub use sa_token_core::{self,
     // 错误处理
     SaTokenError,
     
+    // 路由检查
+    SaCheck, CheckMode,
+    
     // 事件系统
     SaTokenEvent, SaTokenListener, SaTokenEventBus, LoggingListener,
     
     // JWT 支持
-    JwtManager, JwtClaims, JwtAlgorithm,
+    JwtManager, JwtClaims, JwtAlgorithm, JwtKeyStore, JwtSecret,
     
     // OAuth2 支持
-    OAuth2Manager, OAuth2Client, AuthorizationCode, AccessToken, OAuth2TokenInfo,
+    OAuth2Manager, OAuth2Client, AuthorizationCode, AccessToken, OAuth2TokenInfo, IntrospectionResponse,
+    
+    // SSO 单点登录
+    SsoManager, SsoServer, SsoClient, SsoConfig, SsoCredentialValidator, SsoLoginResult,
     
     // 安全特性
-    NonceManager, RefreshTokenManager,
+    NonceManager, RefreshTokenManager, TempTokenManager, SameTokenManager,
     
     // WebSocket 认证
     WsAuthManager, WsAuthInfo, WsTokenExtractor, DefaultWsTokenExtractor,
     
     // 在线用户管理
     OnlineManager, OnlineUser, PushMessage, MessageType, MessagePusher, InMemoryPusher,
+    SsePusher, SseSubscription, SseFrame,
     
     // 分布式会话
     DistributedSessionManager, DistributedSession, DistributedSessionStorage, 
@@ -144,94 +162,12 @@ pub use sa_token_storage_redis::RedisStorage;
 #[cfg(feature = "database")]
 pub use sa_token_storage_database::DatabaseStorage;
 
-use std::sync::Arc;
 use actix_web::web::Data;
 
-/// Actix-web应用数据
-pub type SaTokenData = Data<SaTokenState>;
-
-/// 应用状态
-#[derive(Clone)]
-pub struct SaTokenState {
-    pub manager: Arc<SaTokenManager>,
-}
-
-impl SaTokenState {
-    /// 创建状态构建器
-    pub fn builder() -> SaTokenStateBuilder {
-        SaTokenStateBuilder::new()
-    }
-}
-
-/// 状态构建器
-#[derive(Default)]
-pub struct SaTokenStateBuilder {
-    config_builder: sa_token_core::config::SaTokenConfigBuilder,
-}
-
-impl SaTokenStateBuilder {
-    pub fn new() -> Self {
-        Self::default()
-    }
-    
-    pub fn storage(mut self, storage: Arc<dyn SaStorage>) -> Self {
-        self.config_builder = self.config_builder.storage(storage);
-        self
-    }
-    
-    pub fn token_name(mut self, name: impl Into<String>) -> Self {
-        self.config_builder = self.config_builder.token_name(name);
-        self
-    }
-    
-    pub fn timeout(mut self, timeout: i64) -> Self {
-        self.config_builder = self.config_builder.timeout(timeout);
-        self
-    }
-    
-    pub fn active_timeout(mut self, timeout: i64) -> Self {
-        self.config_builder = self.config_builder.active_timeout(timeout);
-        self
-    }
-    
-    /// 设置是否开启自动续签
-    pub fn auto_renew(mut self, enabled: bool) -> Self {
-        self.config_builder = self.config_builder.auto_renew(enabled);
-        self
-    }
-    
-    pub fn is_concurrent(mut self, concurrent: bool) -> Self {
-        self.config_builder = self.config_builder.is_concurrent(concurrent);
-        self
-    }
-    
-    pub fn is_share(mut self, share: bool) -> Self {
-        self.config_builder = self.config_builder.is_share(share);
-        self
-    }
-    
-    /// 设置 Token 风格
-    pub fn token_style(mut self, style: sa_token_core::config::TokenStyle) -> Self {
-        self.config_builder = self.config_builder.token_style(style);
-        self
-    }
-    
-    pub fn token_prefix(mut self, prefix: impl Into<String>) -> Self {
-        self.config_builder = self.config_builder.token_prefix(prefix);
-        self
-    }
-    
-    pub fn jwt_secret_key(mut self, key: impl Into<String>) -> Self {
-        self.config_builder = self.config_builder.jwt_secret_key(key);
-        self
-    }
-    
-    pub fn build(self) -> Data<SaTokenState> {
-        let manager = self.config_builder.build();
-                
-        Data::new(SaTokenState {
-            manager: Arc::new(manager),
-        })
-    }
-}
+// ============================================================================
+// 应用状态（所有插件共用 sa-token-core 中的实现）
+// ============================================================================
+pub use sa_token_core::state::{SaTokenState, SaTokenStateBuilder};
 
+/// Actix-web应用数据，`App::app_data(Data::new(state))` 注册后可在处理器中提取
+pub type SaTokenData = Data<SaTokenState>;
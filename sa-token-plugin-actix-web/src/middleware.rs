@@ -7,13 +7,12 @@ use std::pin::Pin;
 use std::rc::Rc;
 use actix_web::{
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
-    Error, HttpMessage, error::ErrorUnauthorized,
+    error::InternalError, http::StatusCode, Error, HttpMessage, HttpResponse,
 };
 use crate::SaTokenState;
 use crate::adapter::ActixRequestAdapter;
-use sa_token_adapter::context::SaRequest;
-use sa_token_core::{token::TokenValue, SaTokenContext, error::messages};
-use std::sync::Arc;
+use crate::layer::append_response_cookie;
+use sa_token_core::{token::TokenValue, SaTokenContext, ACCEPT_LANGUAGE_HEADER, TIMEZONE_HEADER, SAME_TOKEN_HEADER, ErrorResponse, SaTokenError, SameTokenManager, StpUtil};
 
 /// sa-token 基础中间件 - 提取并验证 token
 pub struct SaTokenMiddleware {
@@ -69,6 +68,10 @@ where
         
         Box::pin(async move {
             let mut ctx = SaTokenContext::new();
+            let header = |name: &str| req.headers().get(name).and_then(|v| v.to_str().ok());
+            ctx.capture_locale(header(ACCEPT_LANGUAGE_HEADER), header(TIMEZONE_HEADER));
+            ctx.capture_same_token(header(SAME_TOKEN_HEADER));
+            ctx.capture_client(state.manager.client_ip(&ActixRequestAdapter::new(req.request())).as_deref(), header("user-agent"));
             
             tracing::debug!("Sa-Token: 开始处理请求 {} {}", req.method(), req.path());
             
@@ -77,21 +80,11 @@ where
                 tracing::debug!("Sa-Token: extracted token from request: {}", token_str);
                 let token = TokenValue::new(token_str);
                 
-                // 验证 token
-                if state.manager.is_valid(&token).await {
-                    tracing::debug!("Sa-Token: token 验证成功");
-                    // 存储 token
-                    req.extensions_mut().insert(token.clone());
-                    
-                    // 获取并存储 login_id
-                    if let Ok(token_info) = state.manager.get_token_info(&token).await {
-                        let login_id = token_info.login_id.clone();
-                        tracing::debug!("Sa-Token: login_id = {}", login_id);
-                        req.extensions_mut().insert(login_id.clone());
-                        ctx.token = Some(token.clone());
-                        ctx.token_info = Some(Arc::new(token_info));
-                        ctx.login_id = Some(login_id);
-                    }
+                // 验证 token，存储 token 与 login_id
+                if let Some(login_id) = state.manager.authenticate_context(&token, &mut ctx).await {
+                    tracing::debug!("Sa-Token: login_id = {}", login_id);
+                    req.extensions_mut().insert(token);
+                    req.extensions_mut().insert(login_id);
                 } else {
                     tracing::debug!("Sa-Token: token 验证失败");
                 }
@@ -99,10 +92,20 @@ where
                 tracing::debug!("Sa-Token: 未提取到 token");
             }
             
-            SaTokenContext::set_current(ctx);
-            let result = service.call(req).await;
-            SaTokenContext::clear();
-            result
+            // 开发模式免登录 | Dev bypass
+            if let Some(login_id) = state.manager.dev_bypass_login_id().filter(|_| ctx.login_id.is_none()) {
+                req.extensions_mut().insert(login_id.to_string());
+                ctx.login_id = Some(login_id.to_string());
+            }
+            
+            // 供 `SaGuard` 读取 | Read by `SaGuard`
+            req.extensions_mut().insert(ctx.clone());
+            let response_cookie = ctx.clone();
+            let mut res = SaTokenContext::scope(ctx, service.call(req)).await?;
+            
+            // 处理期间登录/登出产生的 token Cookie（`is_write_cookie`）
+            append_response_cookie(&response_cookie, res.headers_mut());
+            Ok(res)
         })
     }
 }
@@ -161,91 +164,252 @@ where
 
         Box::pin(async move {
             let mut ctx = SaTokenContext::new();
+            let header = |name: &str| req.headers().get(name).and_then(|v| v.to_str().ok());
+            ctx.capture_locale(header(ACCEPT_LANGUAGE_HEADER), header(TIMEZONE_HEADER));
+            ctx.capture_same_token(header(SAME_TOKEN_HEADER));
+            ctx.capture_client(state.manager.client_ip(&ActixRequestAdapter::new(req.request())).as_deref(), header("user-agent"));
             // 提取 token
             if let Some(token_str) = extract_token_from_request(&req, &state) {
                 tracing::debug!("Sa-Token(login-check): extracted token from request: {}", token_str);
                 let token = TokenValue::new(token_str);
 
-                // 验证 token
-                if state.manager.is_valid(&token).await {
-                    // 存储 token 和 login_id
-                    req.extensions_mut().insert(token.clone());
-
-                    if let Ok(token_info) = state.manager.get_token_info(&token).await {
-                        let login_id = token_info.login_id.clone();
-                        req.extensions_mut().insert(login_id.clone());
-                        ctx.token = Some(token.clone());
-                        ctx.token_info = Some(Arc::new(token_info));
-                        ctx.login_id = Some(login_id);
-
-                        // 设置上下文
-                        SaTokenContext::set_current(ctx);
-                        let result = service.call(req).await;
-                        SaTokenContext::clear();
-                        return result;
-                    }
+                // 验证 token，存储 token 和 login_id
+                if let Some(login_id) = state.manager.authenticate_context(&token, &mut ctx).await {
+                    req.extensions_mut().insert(token);
+                    req.extensions_mut().insert(login_id);
+
+                    // 设置上下文
+                    req.extensions_mut().insert(ctx.clone());
+                    return SaTokenContext::scope(ctx, service.call(req)).await;
                 }
             }
 
-            // 未登录，返回 401
-            Err(ErrorUnauthorized(serde_json::json!({
-                "code": 401,
-                "message": messages::AUTH_ERROR
-            }).to_string()))
+            // 开发模式免登录 | Dev bypass
+            if let Some(login_id) = state.manager.dev_bypass_login_id() {
+                req.extensions_mut().insert(login_id.to_string());
+                ctx.login_id = Some(login_id.to_string());
+                req.extensions_mut().insert(ctx.clone());
+                return SaTokenContext::scope(ctx, service.call(req)).await;
+            }
+            
+            // 未登录（或为受限 token），返回 401
+            let err = ctx.login_error();
+            Err(rejection_error(state.manager.render_error(&err), err))
+        })
+    }
+}
+
+/// Same-Token 校验中间件 - 要求请求携带有效的 `sa-same-token` 请求头，用于只允许内部服务访问的路由
+///
+/// ```rust,ignore
+/// web::scope("/internal")
+///     .wrap(SaSameTokenMiddleware::new(state.manager.same_token_manager()))
+///     .route("/users/{id}", web::get().to(load_user))
+/// ```
+pub struct SaSameTokenMiddleware {
+    same: Rc<SameTokenManager>,
+}
+
+impl SaSameTokenMiddleware {
+    pub fn new(same: SameTokenManager) -> Self {
+        Self { same: Rc::new(same) }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for SaSameTokenMiddleware
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = SaSameTokenMiddlewareService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(SaSameTokenMiddlewareService {
+            service: Rc::new(service),
+            same: Rc::clone(&self.same),
+        }))
+    }
+}
+
+pub struct SaSameTokenMiddlewareService<S> {
+    service: Rc<S>,
+    same: Rc<SameTokenManager>,
+}
+
+impl<S, B> Service<ServiceRequest> for SaSameTokenMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output=Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let same = Rc::clone(&self.same);
+
+        Box::pin(async move {
+            let token = req.headers().get(SAME_TOKEN_HEADER).and_then(|v| v.to_str().ok());
+            match same.check_token(token).await {
+                Ok(()) => service.call(req).await,
+                Err(err) => Err(rejection_error(StpUtil::render_error(&err), err)),
+            }
         })
     }
 }
 
+/// 把 `ErrorRenderer` 渲染的拒绝响应转换为 Actix 错误
+pub(crate) fn rejection_error(rendered: ErrorResponse, err: SaTokenError) -> Error {
+    let mut response = HttpResponse::build(
+        StatusCode::from_u16(rendered.status).unwrap_or(StatusCode::FORBIDDEN)
+    );
+    response.content_type(rendered.content_type);
+    for header in rendered.headers {
+        response.append_header(header);
+    }
+    InternalError::from_response(err, response.body(rendered.body)).into()
+}
+
 /// 从请求中提取 token
+///
+/// 先调用自定义提取器，再按 `SaTokenConfig::token_sources` 的顺序查找
 fn extract_token_from_request(req: &ServiceRequest, state: &SaTokenState) -> Option<String> {
-    let adapter = ActixRequestAdapter::new(req.request());
-    let token_name = &state.manager.config.token_name;
-    
-    tracing::debug!("Sa-Token: 尝试从请求提取 token，token_name: {}", token_name);
-    
-    // 1. 优先从 Header 中获取（检查 token_name 配置的头）
-    if let Some(token) = adapter.get_header(token_name) {
-        tracing::debug!("Sa-Token: 从 Header[{}] 获取到 token", token_name);
-        return Some(extract_bearer_token(&token));
-    }
-    
-    // 2. 如果 token_name 不是 "Authorization"，也尝试从 "Authorization" 头获取
-    if token_name != "Authorization" {
-        if let Some(token) = adapter.get_header("Authorization") {
-            tracing::debug!("Sa-Token: 从 Header[Authorization] 获取到 token");
-            return Some(extract_bearer_token(&token));
+    let extracted = state.manager.extract_token(&ActixRequestAdapter::new(req.request()));
+    match &extracted {
+        Some((_, source)) => tracing::debug!("Sa-Token: 从 {} 获取到 token", source),
+        None => tracing::debug!("Sa-Token: 所有位置都未找到 token"),
+    }
+    extracted.map(|(token, _)| token)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, web, App};
+    use actix_web::http::StatusCode;
+    use sa_token_core::{SaTokenConfig, TokenInfo, PASSWORD_CHANGE_SCOPE};
+    use sa_token_test_utils::state::{state, state_with};
+    use crate::LoginIdExtractor;
+
+    async fn profile(LoginIdExtractor(login_id): LoginIdExtractor) -> String {
+        login_id
+    }
+
+    #[actix_web::test]
+    async fn test_restricted_token_is_rejected_on_login_routes() {
+        let state = state();
+        let mut token_info = TokenInfo::new(TokenValue::new(""), "user_1");
+        token_info.scope = Some(vec![PASSWORD_CHANGE_SCOPE.to_string()]);
+        let restricted = state.manager.login_with_token_info(token_info).await.unwrap();
+        let token = state.manager.login("user_2").await.unwrap();
+
+        let app = test::init_service(App::new()
+            .service(web::scope("/checked").wrap(SaCheckLoginMiddleware::new(state.clone())).route("/profile", web::get().to(profile)))
+            .service(web::scope("/extracted").wrap(SaTokenMiddleware::new(state.clone())).route("/profile", web::get().to(profile)))).await;
+
+        for path in ["/checked/profile", "/extracted/profile"] {
+            let request = test::TestRequest::get().uri(path).insert_header(("sa-token", token.as_str())).to_request();
+            let response = test::call_service(&app, request).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(test::read_body(response).await, "user_2");
+
+            // 中间件以错误拒绝，提取器的错误已转换为响应
+            let request = test::TestRequest::get().uri(path).insert_header(("sa-token", restricted.as_str())).to_request();
+            let response = match test::try_call_service(&app, request).await {
+                Ok(response) => response.map_into_boxed_body(),
+                Err(e) => ServiceResponse::new(test::TestRequest::default().to_http_request(), e.error_response()),
+            };
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+            let body = test::read_body(response).await;
+            assert!(String::from_utf8_lossy(&body).contains(&SaTokenError::PasswordExpired.to_string()));
         }
     }
-    
-    // 3. 从 Cookie 中获取
-    if let Some(token) = adapter.get_cookie(token_name) {
-        tracing::debug!("Sa-Token: 从 Cookie[{}] 获取到 token", token_name);
-        return Some(token);
-    }
-    
-    // 4. 从 Query 参数中获取
-    if let Some(query) = req.query_string().split('&').find_map(|pair| {
-        let mut parts = pair.split('=');
-        if let (Some(key), Some(value)) = (parts.next(), parts.next()) {
-            if key == token_name {
-                return urlencoding::decode(value).ok().map(|s| s.to_string());
-            }
+
+    #[actix_web::test]
+    async fn test_replaced_and_kicked_out_tokens_are_rejected_with_reason() {
+        let config = SaTokenConfig { is_concurrent: false, ..Default::default() };
+        let state = state_with(config);
+        let replaced = state.manager.login("user_1").await.unwrap();
+        let current = state.manager.login("user_1").await.unwrap();
+        state.manager.kick_out_with_reason("user_1", "Signed in elsewhere").await.unwrap();
+
+        let app = test::init_service(App::new()
+            .service(web::scope("/checked").wrap(SaCheckLoginMiddleware::new(state.clone())).route("/profile", web::get().to(profile)))
+            .service(web::scope("/extracted").wrap(SaTokenMiddleware::new(state.clone())).route("/profile", web::get().to(profile)))).await;
+
+        for path in ["/checked/profile", "/extracted/profile"] {
+            let request = test::TestRequest::get().uri(path).insert_header(("sa-token", replaced.as_str())).to_request();
+            let response = match test::try_call_service(&app, request).await {
+                Ok(response) => response.map_into_boxed_body(),
+                Err(e) => ServiceResponse::new(test::TestRequest::default().to_http_request(), e.error_response()),
+            };
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+            let body = test::read_body(response).await;
+            assert!(String::from_utf8_lossy(&body).contains(&SaTokenError::Replaced.to_string()));
+
+            let request = test::TestRequest::get().uri(path).insert_header(("sa-token", current.as_str())).to_request();
+            let response = match test::try_call_service(&app, request).await {
+                Ok(response) => response.map_into_boxed_body(),
+                Err(e) => ServiceResponse::new(test::TestRequest::default().to_http_request(), e.error_response()),
+            };
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+            let body = test::read_body(response).await;
+            assert!(String::from_utf8_lossy(&body).contains(&SaTokenError::KickedOut("Signed in elsewhere".to_string()).to_string()));
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_token_used_from_another_ip_is_rejected_with_reason() {
+        let config = SaTokenConfig { token_bind_ip: true, ..Default::default() };
+        let state = state_with(config);
+        let mut login_ctx = SaTokenContext::new();
+        login_ctx.capture_client(Some("203.0.113.9"), None);
+        let token = SaTokenContext::scope(login_ctx, state.manager.login("user_1")).await.unwrap();
+
+        let app = test::init_service(App::new()
+            .service(web::scope("/checked").wrap(SaCheckLoginMiddleware::new(state.clone())).route("/profile", web::get().to(profile)))
+            .service(web::scope("/extracted").wrap(SaTokenMiddleware::new(state.clone())).route("/profile", web::get().to(profile)))).await;
+
+        for path in ["/checked/profile", "/extracted/profile"] {
+            let request = test::TestRequest::get().uri(path).peer_addr("203.0.113.9:4000".parse().unwrap())
+                .insert_header(("sa-token", token.as_str())).to_request();
+            assert_eq!(test::call_service(&app, request).await.status(), StatusCode::OK);
+
+            let request = test::TestRequest::get().uri(path).peer_addr("198.51.100.4:4000".parse().unwrap())
+                .insert_header(("sa-token", token.as_str())).to_request();
+            let response = match test::try_call_service(&app, request).await {
+                Ok(response) => response.map_into_boxed_body(),
+                Err(e) => ServiceResponse::new(test::TestRequest::default().to_http_request(), e.error_response()),
+            };
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+            let body = test::read_body(response).await;
+            assert!(String::from_utf8_lossy(&body).contains(&SaTokenError::IpMismatch.to_string()));
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_layer_accepts_configured_schemes() {
+        let config = SaTokenConfig { token_schemes: vec!["Bearer".into(), "Token".into()], ..Default::default() };
+        let state = state_with(config);
+        let token = state.manager.login("user_1").await.unwrap();
+        let app = test::init_service(App::new()
+            .service(web::scope("/extracted").wrap(SaTokenMiddleware::new(state.clone())).route("/profile", web::get().to(profile)))).await;
+
+        for value in [format!("bearer {}", token), format!("TOKEN  {}", token), format!(" Bearer\t{} ", token)] {
+            let request = test::TestRequest::get().uri("/extracted/profile").insert_header(("Authorization", value)).to_request();
+            assert_eq!(test::call_and_read_body(&app, request).await, "user_1");
         }
-        None
-    }) {
-        tracing::debug!("Sa-Token: 从 Query[{}] 获取到 token", token_name);
-        return Some(query);
-    }
-    
-    tracing::debug!("Sa-Token: 所有位置都未找到 token");
-    None
-}
-
-/// 提取 Bearer token
-fn extract_bearer_token(token: &str) -> String {
-    if token.starts_with("Bearer ") {
-        token[7..].to_string()
-    } else {
-        token.to_string()
+        let request = test::TestRequest::get().uri("/extracted/profile").insert_header(("Authorization", format!("Basic {}", token))).to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), StatusCode::UNAUTHORIZED);
     }
 }
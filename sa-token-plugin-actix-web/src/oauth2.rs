@@ -0,0 +1,68 @@
+// Author: 金书记
+//
+//! OAuth2 令牌内省与撤销接口
+//!
+//! - `POST /oauth2/introspect`（RFC 7662）：表单 `token`、`client_id`、`client_secret`，
+//!   返回 `{"active": true, "scope", "client_id", "exp", "sub"}`；无效令牌返回 `{"active": false}`
+//! - `POST /oauth2/revoke`（RFC 7009）：表单同上，撤销令牌（访问令牌连同其刷新令牌），成功返回 200 空响应
+//!
+//! 客户端凭据错误返回 401 `{"error": "invalid_client"}`，撤销其他客户端的令牌返回 400 `{"error": "unauthorized_client"}`。
+
+use std::sync::Arc;
+use actix_web::{http::StatusCode, web, HttpResponse, Scope};
+use serde::Deserialize;
+use serde_json::json;
+use sa_token_core::{OAuth2Manager, SaTokenError, OAUTH2_INTROSPECT_PATH, OAUTH2_REVOKE_PATH};
+
+/// OAuth2 接口的公共路径前缀
+const OAUTH2_SCOPE: &str = "/oauth2";
+
+/// 创建 OAuth2 内省与撤销 Scope
+///
+/// # 示例
+/// ```rust,ignore
+/// let oauth2 = Arc::new(OAuth2Manager::new(storage));
+/// HttpServer::new(move || App::new().service(oauth2_scope(oauth2.clone())))
+/// ```
+pub fn oauth2_scope(oauth2: Arc<OAuth2Manager>) -> Scope {
+    let path = |full: &'static str| full.trim_start_matches(OAUTH2_SCOPE);
+    web::scope(OAUTH2_SCOPE)
+        .app_data(web::Data::from(oauth2))
+        .route(path(OAUTH2_INTROSPECT_PATH), web::post().to(introspect))
+        .route(path(OAUTH2_REVOKE_PATH), web::post().to(revoke))
+}
+
+#[derive(Deserialize)]
+struct TokenForm {
+    token: String,
+    client_id: String,
+    client_secret: String,
+}
+
+async fn introspect(oauth2: web::Data<OAuth2Manager>, form: web::Form<TokenForm>) -> HttpResponse {
+    let result = match oauth2.verify_client(&form.client_id, &form.client_secret).await {
+        Ok(true) => oauth2.introspect(&form.token).await,
+        Ok(false) => Err(SaTokenError::OAuth2InvalidCredentials),
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => error_response(e),
+    }
+}
+
+async fn revoke(oauth2: web::Data<OAuth2Manager>, form: web::Form<TokenForm>) -> HttpResponse {
+    match oauth2.revoke_client_token(&form.client_id, &form.client_secret, &form.token).await {
+        Ok(()) => HttpResponse::Ok().finish(),
+        Err(e) => error_response(e),
+    }
+}
+
+fn error_response(error: SaTokenError) -> HttpResponse {
+    let (status, code) = match error {
+        SaTokenError::OAuth2InvalidCredentials | SaTokenError::OAuth2ClientNotFound => (StatusCode::UNAUTHORIZED, "invalid_client"),
+        SaTokenError::OAuth2ClientIdMismatch => (StatusCode::BAD_REQUEST, "unauthorized_client"),
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, "server_error"),
+    };
+    HttpResponse::build(status).json(json!({ "error": code }))
+}
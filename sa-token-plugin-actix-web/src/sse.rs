@@ -0,0 +1,71 @@
+// Author: 金书记
+//
+//! Server-Sent Events 推送接口
+//!
+//! 不使用 WebSocket 的浏览器通过 `EventSource` 连接 `/auth/events`，接收 `SsePusher` 推送的消息。
+//! 心跳注释帧会刷新在线活跃时间，客户端断开后自动标记下线，踢人通知送达后服务端结束流。
+//!
+//! 查询参数：`device`（默认 `web`）。需要 `SaTokenLayer` 完成认证，未登录返回 401。
+
+use std::convert::Infallible;
+use std::sync::Arc;
+use actix_web::{http::header, web, HttpResponse};
+use futures_util::stream;
+use serde::Deserialize;
+use sa_token_core::SsePusher;
+use crate::extractor::{LoginIdExtractor, SaTokenExtractor};
+
+/// SSE 接口路径
+pub const SSE_PATH: &str = "/auth/events";
+
+#[derive(Debug, Default, Deserialize)]
+struct EventsQuery {
+    device: Option<String>,
+}
+
+/// 注册 SSE 接口
+///
+/// # 示例
+/// ```rust,ignore
+/// let sse = Arc::new(SsePusher::new().with_online_manager(online.clone()));
+/// online.register_pusher(sse.clone()).await;
+///
+/// HttpServer::new(move || {
+///     App::new()
+///         .wrap(SaTokenLayer::new(state.get_ref().clone()))
+///         .configure(sse_config(sse.clone()))
+///         .service(user_info)
+/// })
+/// ```
+///
+/// 前端：`new EventSource("/auth/events?device=web&token=...")`，监听 `message` 事件
+pub fn sse_config(pusher: Arc<SsePusher>) -> impl FnOnce(&mut web::ServiceConfig) {
+    move |cfg| {
+        cfg.service(
+            web::resource(SSE_PATH)
+                .app_data(web::Data::from(pusher))
+                .route(web::get().to(sse_events)),
+        );
+    }
+}
+
+async fn sse_events(
+    pusher: web::Data<SsePusher>,
+    SaTokenExtractor(token): SaTokenExtractor,
+    LoginIdExtractor(login_id): LoginIdExtractor,
+    query: web::Query<EventsQuery>,
+) -> HttpResponse {
+    let device = query.into_inner().device.unwrap_or_else(|| "web".to_string());
+    let subscription = pusher.subscribe(login_id, token.as_str(), device).await;
+
+    // 响应流被丢弃时订阅随之丢弃，自动注销并标记下线
+    let body = stream::unfold(subscription, |mut subscription| async move {
+        let frame = subscription.next().await?;
+        Some((Ok::<_, Infallible>(web::Bytes::from(frame.to_sse_string())), subscription))
+    });
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(body)
+}
@@ -0,0 +1,123 @@
+// Author: 金书记
+//
+//! SSO 服务端接口
+//!
+//! 把 `SsoManager` 挂成现成的 HTTP 接口，几行代码即可搭起一个 SSO 服务端：
+//!
+//! - `GET /sso/auth?service=<url>`：已登录时签发票据并 302 重定向回 `service`，未登录返回 401
+//! - `POST /sso/doLogin`：请求体 `{"name", "pwd", "service"?}`，校验通过后返回 SSO token 与回跳地址
+//! - `GET /sso/checkTicket?ticket=<ticket>&service=<url>`：客户端应用用票据换取登录 ID
+//! - `GET|POST /sso/signout`：统一登出当前用户，返回需要通知的客户端列表
+//!
+//! `/sso/auth` 与 `/sso/signout` 依赖 `SaTokenLayer` 识别当前用户，需在 App 上注册该中间件。
+
+use std::sync::Arc;
+use actix_web::{http::{header, StatusCode}, web, HttpResponse, Scope};
+use serde::Deserialize;
+use serde_json::json;
+use sa_token_core::{
+    SaTokenError, SsoManager, SSO_AUTH_PATH, SSO_CHECK_TICKET_PATH, SSO_DO_LOGIN_PATH, SSO_SIGNOUT_PATH,
+};
+use crate::extractor::LoginIdExtractor;
+
+/// SSO 接口的公共路径前缀
+const SSO_SCOPE: &str = "/sso";
+
+/// 创建 SSO 服务端 Scope
+///
+/// # 示例
+/// ```rust,ignore
+/// let sso = Arc::new(
+///     SsoManager::new(SsoConfig::builder().add_allowed_origin("http://app1.com".into()).build())
+///         .with_server(Arc::new(SsoServer::new(state.manager.clone())))
+///         .with_credential_validator(Arc::new(UserTable)),
+/// );
+///
+/// HttpServer::new(move || {
+///     App::new()
+///         .wrap(SaTokenLayer::new(state.clone()))
+///         .service(sso_scope(sso.clone()))
+/// })
+/// ```
+pub fn sso_scope(sso: Arc<SsoManager>) -> Scope {
+    let path = |full: &'static str| full.trim_start_matches(SSO_SCOPE);
+    web::scope(SSO_SCOPE)
+        .app_data(web::Data::from(sso))
+        .route(path(SSO_AUTH_PATH), web::get().to(auth))
+        .route(path(SSO_DO_LOGIN_PATH), web::post().to(do_login))
+        .route(path(SSO_CHECK_TICKET_PATH), web::get().to(check_ticket))
+        .route(path(SSO_SIGNOUT_PATH), web::get().to(signout))
+        .route(path(SSO_SIGNOUT_PATH), web::post().to(signout))
+}
+
+#[derive(Deserialize)]
+struct AuthQuery {
+    service: String,
+}
+
+#[derive(Deserialize)]
+struct LoginBody {
+    name: String,
+    pwd: String,
+    service: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TicketQuery {
+    ticket: String,
+    service: String,
+}
+
+async fn auth(
+    sso: web::Data<SsoManager>,
+    LoginIdExtractor(login_id): LoginIdExtractor,
+    query: web::Query<AuthQuery>,
+) -> HttpResponse {
+    match sso.auth(&login_id, &query.service).await {
+        Ok(redirect) => HttpResponse::Found()
+            .insert_header((header::LOCATION, redirect))
+            .finish(),
+        Err(e) => error_response(e),
+    }
+}
+
+async fn do_login(sso: web::Data<SsoManager>, body: web::Json<LoginBody>) -> HttpResponse {
+    match sso.do_login(&body.name, &body.pwd, body.service.as_deref()).await {
+        Ok(result) => success(json!(result)),
+        Err(e) => error_response(e),
+    }
+}
+
+async fn check_ticket(sso: web::Data<SsoManager>, query: web::Query<TicketQuery>) -> HttpResponse {
+    match sso.check_ticket(&query.ticket, &query.service).await {
+        Ok(login_id) => success(json!({ "login_id": login_id })),
+        Err(e) => error_response(e),
+    }
+}
+
+async fn signout(sso: web::Data<SsoManager>, LoginIdExtractor(login_id): LoginIdExtractor) -> HttpResponse {
+    match sso.signout(&login_id).await {
+        Ok(clients) => success(json!({ "clients": clients })),
+        Err(e) => error_response(e),
+    }
+}
+
+fn success(data: serde_json::Value) -> HttpResponse {
+    HttpResponse::Ok().json(json!({
+        "code": 200,
+        "message": "success",
+        "data": data
+    }))
+}
+
+fn error_response(error: SaTokenError) -> HttpResponse {
+    let status = match &error {
+        e if e.is_auth_error() => StatusCode::UNAUTHORIZED,
+        SaTokenError::InvalidTicket | SaTokenError::TicketExpired | SaTokenError::ServiceMismatch => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    HttpResponse::build(status).json(json!({
+        "code": status.as_u16(),
+        "message": error.to_string()
+    }))
+}
@@ -3,6 +3,8 @@
 //! Axum请求/响应适配器
 
 use std::collections::HashMap;
+use std::net::SocketAddr;
+use axum::extract::ConnectInfo;
 use http::{Request, Response};
 use sa_token_adapter::context::{SaRequest, SaResponse, CookieOptions};
 use serde::Serialize;
@@ -45,6 +47,11 @@ impl<'a, T> SaRequest for AxumRequestAdapter<'a, T> {
     fn get_method(&self) -> String {
         self.request.method().to_string()
     }
+    
+    fn remote_addr(&self) -> Option<String> {
+        self.request.extensions().get::<ConnectInfo<SocketAddr>>()
+            .map(|info| info.0.ip().to_string())
+    }
 }
 
 /// Axum响应适配器
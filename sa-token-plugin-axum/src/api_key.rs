@@ -0,0 +1,123 @@
+// Author: 金书记
+//
+//! API Key 认证层 | API key authentication layer
+//!
+//! 作为 token 之外的另一种登录来源，放在 `SaTokenLayer` 内层、检查层外层：请求已通过
+//! token 登录时直接放行；否则读取 `X-API-Key` 请求头，Key 有效时把绑定的账号写入请求扩展
+//! 与上下文，之后的权限检查使用 Key 自身的权限；Key 无效时返回 401；未携带 Key 时放行，
+//! 交给后面的检查层决定。
+//!
+//! An alternative login source, placed inside `SaTokenLayer` and outside the
+//! check layers. Requests already logged in by token pass through. Otherwise
+//! the `X-API-Key` header is read: a valid key puts its account into the
+//! request extensions and context, and later permission checks use the key's
+//! own permissions; an invalid key gets 401; no key at all passes through for
+//! the check layers to decide.
+//!
+//! ```rust,ignore
+//! let app = Router::new()
+//!     .route("/ops/deploy", post(deploy))
+//!     .route_layer(SaCheckPermissionLayer::new("deploy:run"))
+//!     .layer(ApiKeyAuthLayer::new(ApiKeyManager::new(storage.clone())))
+//!     .layer(SaTokenLayer::new(state));
+//! ```
+
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use http::{Request, Response};
+use tower::{Layer, Service};
+use sa_token_core::{ApiKeyManager, DecisionTrace, SaTokenContext, API_KEY_HEADER};
+use crate::layer::record_decision;
+use crate::middleware::rejection_response;
+
+/// API Key 认证层 | API key authentication layer
+#[derive(Clone)]
+pub struct ApiKeyAuthLayer {
+    api_keys: Arc<ApiKeyManager>,
+    header: &'static str,
+}
+
+impl ApiKeyAuthLayer {
+    pub fn new(api_keys: ApiKeyManager) -> Self {
+        Self {
+            api_keys: Arc::new(api_keys),
+            header: API_KEY_HEADER,
+        }
+    }
+
+    /// 读取 Key 的请求头，默认 `X-API-Key` | Header carrying the key, `X-API-Key` by default
+    pub fn header(mut self, header: &'static str) -> Self {
+        self.header = header;
+        self
+    }
+}
+
+impl<S> Layer<S> for ApiKeyAuthLayer {
+    type Service = ApiKeyAuthService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ApiKeyAuthService {
+            inner,
+            api_keys: self.api_keys.clone(),
+            header: self.header,
+        }
+    }
+}
+
+/// API Key 认证服务 | API key authentication service
+#[derive(Clone)]
+pub struct ApiKeyAuthService<S> {
+    inner: S,
+    api_keys: Arc<ApiKeyManager>,
+    header: &'static str,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for ApiKeyAuthService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+    ResBody: http_body::Body + From<String> + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<ReqBody>) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let api_keys = self.api_keys.clone();
+        let header = self.header;
+
+        Box::pin(async move {
+            let key = request.headers().get(header)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            // 已通过 token 登录或未携带 Key，交给后面的检查层
+            if key.is_none() || request.extensions().get::<String>().is_some() {
+                return inner.call(request).await;
+            }
+
+            let trace = request.extensions().get::<DecisionTrace>().cloned();
+            let info = match api_keys.authenticate(key.as_deref()).await {
+                Ok(info) => info,
+                Err(e) => {
+                    record_decision(trace.as_ref(), "api_key", "denied");
+                    return Ok(rejection_response(&e));
+                }
+            };
+            record_decision(trace.as_ref(), "api_key", info.login_id.as_str());
+
+            let mut ctx = request.extensions().get::<SaTokenContext>().cloned().unwrap_or_default();
+            ctx.login_id = Some(info.login_id.clone());
+            ctx.token_info = Some(Arc::new(info.to_token_info()));
+            request.extensions_mut().insert(info.login_id.clone());
+            request.extensions_mut().insert(info);
+            request.extensions_mut().insert(ctx.clone());
+            SaTokenContext::scope(ctx, inner.call(request)).await
+        })
+    }
+}
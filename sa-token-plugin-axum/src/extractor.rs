@@ -4,12 +4,11 @@
 
 use axum::{
     extract::FromRequestParts,
-    http::{request::Parts, StatusCode},
-    response::{IntoResponse, Response},
-    Json,
+    http::request::Parts,
+    response::Response,
 };
-use sa_token_core::{token::TokenValue, error::messages};
-use serde_json::json;
+use sa_token_core::{token::TokenValue, SaTokenError, IDEMPOTENCY_KEY_HEADER};
+use crate::middleware::rejection_response;
 
 pub struct SaTokenExtractor(pub TokenValue);
 
@@ -22,13 +21,7 @@ where
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
         match parts.extensions.get::<TokenValue>() {
             Some(token) => Ok(SaTokenExtractor(token.clone())),
-            None => Err((
-                StatusCode::UNAUTHORIZED,
-                Json(json!({
-                    "code": 401,
-                    "message": messages::AUTH_ERROR
-                }))
-            ).into_response()),
+            None => Err(rejection_response(&SaTokenError::NotLogin)),
         }
     }
 }
@@ -58,13 +51,26 @@ where
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
         match parts.extensions.get::<String>() {
             Some(login_id) => Ok(LoginIdExtractor(login_id.clone())),
-            None => Err((
-                StatusCode::UNAUTHORIZED,
-                Json(json!({
-                    "code": 401,
-                    "message": messages::AUTH_ERROR
-                }))
-            ).into_response()),
+            None => Err(rejection_response(&SaTokenError::NotLogin)),
         }
     }
 }
+
+/// 请求头中的幂等键（`Idempotency-Key`），用于登录/刷新接口的重试去重
+pub struct IdempotencyKeyExtractor(pub Option<String>);
+
+impl<S> FromRequestParts<S> for IdempotencyKeyExtractor
+where
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+    
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let key = parts.headers
+            .get(IDEMPOTENCY_KEY_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .filter(|v| !v.is_empty())
+            .map(|v| v.to_string());
+        Ok(IdempotencyKeyExtractor(key))
+    }
+}
@@ -0,0 +1,106 @@
+// Author: 金书记
+//
+//! HTTP Basic 认证层 | HTTP Basic authentication layer
+//!
+//! 用于运维接口等只需一个固定账号的路由，可与 `SaTokenLayer` 组合：请求已通过 token
+//! 登录时直接放行，否则校验 `Authorization: Basic ...`，通过后以用户名作为 `login_id`，
+//! 失败时返回 401 并带上 `WWW-Authenticate: Basic realm="..."`。
+//!
+//! For ops routes guarded by a single fixed account; combines with
+//! `SaTokenLayer`. Requests already logged in by token pass through, others
+//! must send `Authorization: Basic ...`; the username becomes the `login_id`,
+//! and failures get 401 with `WWW-Authenticate: Basic realm="..."`.
+//!
+//! ```rust,ignore
+//! let ops = Router::new()
+//!     .route("/ops/metrics", get(metrics))
+//!     .layer(HttpBasicLayer::new(HttpBasicAuth::new("ops", "s3cret").realm("ops")))
+//!     .layer(SaTokenLayer::new(state));
+//! ```
+
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use http::{header, HeaderValue, Request, Response};
+use tower::{Layer, Service};
+use sa_token_core::{DecisionTrace, HttpBasicAuth, SaTokenContext};
+use crate::layer::record_decision;
+use crate::middleware::rejection_response;
+
+/// HTTP Basic 认证层 | HTTP Basic authentication layer
+#[derive(Clone)]
+pub struct HttpBasicLayer {
+    basic: Arc<HttpBasicAuth>,
+}
+
+impl HttpBasicLayer {
+    pub fn new(basic: HttpBasicAuth) -> Self {
+        Self { basic: Arc::new(basic) }
+    }
+}
+
+impl<S> Layer<S> for HttpBasicLayer {
+    type Service = HttpBasicService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        HttpBasicService {
+            inner,
+            basic: self.basic.clone(),
+        }
+    }
+}
+
+/// HTTP Basic 认证服务 | HTTP Basic authentication service
+#[derive(Clone)]
+pub struct HttpBasicService<S> {
+    inner: S,
+    basic: Arc<HttpBasicAuth>,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for HttpBasicService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+    ResBody: http_body::Body + From<String> + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<ReqBody>) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let basic = self.basic.clone();
+
+        Box::pin(async move {
+            if request.extensions().get::<String>().is_some() {
+                return inner.call(request).await;
+            }
+
+            let trace = request.extensions().get::<DecisionTrace>().cloned();
+            let authorization = request.headers().get(header::AUTHORIZATION)
+                .and_then(|value| value.to_str().ok());
+            let username = match basic.check(authorization) {
+                Ok(username) => username,
+                Err(e) => {
+                    record_decision(trace.as_ref(), "http_basic", "denied");
+                    let mut response: Response<ResBody> = rejection_response(&e);
+                    if let Ok(challenge) = HeaderValue::from_str(&basic.challenge()) {
+                        response.headers_mut().insert(header::WWW_AUTHENTICATE, challenge);
+                    }
+                    return Ok(response);
+                }
+            };
+            record_decision(trace.as_ref(), "http_basic", username.as_str());
+
+            let mut ctx = request.extensions().get::<SaTokenContext>().cloned().unwrap_or_default();
+            ctx.login_id = Some(username.clone());
+            request.extensions_mut().insert(username);
+            request.extensions_mut().insert(ctx.clone());
+            SaTokenContext::scope(ctx, inner.call(request)).await
+        })
+    }
+}
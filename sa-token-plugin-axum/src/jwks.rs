@@ -0,0 +1,51 @@
+// Author: 金书记
+//
+//! JWKS 公钥发布接口
+//!
+//! 把 `JwtKeyStore` 中的公钥以 JWK Set 形式挂在 `/.well-known/jwks.json`，
+//! 资源服务拉取后即可按 token 头中的 `kid` 验证签名。密钥轮换后接口立即返回新的公钥集合。
+
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde_json::json;
+use sa_token_core::JwtKeyStore;
+
+/// JWKS 接口路径
+pub const JWKS_PATH: &str = "/.well-known/jwks.json";
+
+/// 创建 JWKS 路由，合并到应用路由中即可
+///
+/// # 示例
+/// ```rust,ignore
+/// let keys = JwtKeyStore::new(signing_key)?;
+/// let app = Router::new()
+///     .route("/api/user", get(user_info))
+///     .merge(jwks_router(keys.clone()));
+/// ```
+pub fn jwks_router<S>(keys: JwtKeyStore) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route(JWKS_PATH, get(serve_jwks))
+        .with_state(keys)
+}
+
+async fn serve_jwks(State(keys): State<JwtKeyStore>) -> Response {
+    match keys.jwks() {
+        // 允许资源服务短时间缓存，轮换时保留旧密钥即可平滑过渡
+        Ok(jwks) => ([(header::CACHE_CONTROL, "public, max-age=300")], Json(jwks)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({
+                "code": 500,
+                "message": e.to_string()
+            }))
+        ).into_response(),
+    }
+}
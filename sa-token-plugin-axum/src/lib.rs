@@ -47,23 +47,58 @@ pub mod layer;
 pub mod extractor;
 pub mod middleware;
 pub mod adapter;
+pub mod security_headers;
+pub mod read_only;
+pub mod status;
+pub mod session_admin;
+pub mod propagation;
+pub mod jwks;
+pub mod payload_guard;
+pub mod sso;
+pub mod oauth2;
+pub mod sse;
+pub mod router;
+pub mod sign;
+pub mod same_token;
+pub mod api_key;
+pub mod http_basic;
+#[cfg(feature = "ws")]
+pub mod ws;
 
 // ============================================================================
 // Axum 框架集成（本插件特有）
 // ============================================================================
 pub use layer::SaTokenLayer;
-pub use extractor::{SaTokenExtractor, OptionalSaTokenExtractor, LoginIdExtractor};
-pub use middleware::{SaTokenMiddleware, SaCheckLoginLayer, SaCheckLoginMiddleware, SaCheckPermissionLayer, SaCheckPermissionMiddleware};
+pub use extractor::{SaTokenExtractor, OptionalSaTokenExtractor, LoginIdExtractor, IdempotencyKeyExtractor};
+pub use security_headers::{SaSecurityHeadersLayer, SaSecurityHeadersMiddleware};
+pub use read_only::{ReadOnlyModeLayer, ReadOnlyModeMiddleware};
+pub use status::{status_watch_router, STATUS_WATCH_PATH};
+pub use sse::{sse_router, SSE_PATH};
+pub use router::RouterExt;
+pub use session_admin::{session_admin_router, SESSION_ADMIN_PATH};
+pub use propagation::{SaTokenPropagationLayer, SaTokenPropagation};
+pub use jwks::{jwks_router, JWKS_PATH};
+pub use payload_guard::{SaPayloadGuardLayer, SaPayloadGuard};
+pub use sign::{SaSignLayer, SaSignService};
+pub use same_token::{SaSameTokenLayer, SaSameTokenService};
+pub use api_key::{ApiKeyAuthLayer, ApiKeyAuthService};
+pub use http_basic::{HttpBasicLayer, HttpBasicService};
+pub use sso::sso_router;
+pub use oauth2::oauth2_router;
+pub use middleware::{
+    SaTokenMiddleware, SaCheckLoginLayer, SaCheckLoginMiddleware, SaCheckPermissionLayer, SaCheckPermissionMiddleware,
+    SaCheckLayer, SaCheckService,
+};
 
 // ============================================================================
 // 重新导出核心功能（sa-token-core）
 // ============================================================================
 pub use sa_token_core::{self,
     // 核心管理器
-    SaTokenManager, StpUtil,
+    SaTokenManager, StpUtil, LoginOptions, LoginResult,
     
     // 配置
-    SaTokenConfig,
+    SaTokenConfig, JsonFieldCase,
     config::TokenStyle,
     
     // Token 相关
@@ -73,28 +108,46 @@ pub use sa_token_core::{self,
     SaSession,
     
     // 权限
-    PermissionChecker,
+    PermissionChecker, RoleChecker, PermissionMatcher, SaCheck, CheckMode, PayloadGuard, ThresholdGuard,
     
     // 错误处理
-    SaTokenError,
+    SaTokenError, ErrorRenderer, ErrorResponse, DefaultErrorRenderer, ProblemJsonRenderer,
     
     // 事件系统
     SaTokenEvent, SaTokenListener, SaTokenEventBus, LoggingListener,
     
     // JWT 支持
-    JwtManager, JwtClaims, JwtAlgorithm,
+    JwtManager, JwtClaims, JwtAlgorithm, JwtKeyStore, JwtSecret,
     
     // OAuth2 支持
-    OAuth2Manager, OAuth2Client, AuthorizationCode, AccessToken, OAuth2TokenInfo,
+    OAuth2Manager, OAuth2Client, AuthorizationCode, AccessToken, OAuth2TokenInfo, IntrospectionResponse,
+    
+    // SSO 单点登录
+    SsoManager, SsoServer, SsoClient, SsoConfig, SsoCredentialValidator, SsoLoginResult,
     
     // 安全特性
-    NonceManager, RefreshTokenManager,
+    NonceManager, RefreshTokenManager, TempTokenManager, SignManager, SameTokenManager, ApiKeyManager, ApiKeyInfo, API_KEY_HEADER, HttpBasicAuth, AuditLogger, AuditRecord, AuditPage, StorageAuditLogger, RoutingStorage, RoutingStats, SecurityHeaders, IdempotencyStore, IDEMPOTENCY_KEY_HEADER,
+    ReadOnlyMode,
+    
+    // 自定义 Token 提取
+    TokenExtractor, TokenRequest,
+    
+    // 身份透传
+    PropagatedContext, SIGNED_CONTEXT_HEADER, SAME_TOKEN_HEADER,
+    
+    // 调试
+    DecisionLog, DecisionTrace, DECISION_TRACE_HEADER, REQUEST_ID_HEADER, DenialCount,
     
     // WebSocket 认证
     WsAuthManager, WsAuthInfo, WsTokenExtractor, DefaultWsTokenExtractor,
+    WsConnectionRegistry, WsConnection, WsOutbound, WsCloseReason, WebSocketPusher,
+    
+    // Token 状态监听
+    TokenStatus, TokenStatusReport, StatusWatchOptions,
     
     // 在线用户管理
     OnlineManager, OnlineUser, PushMessage, MessageType, MessagePusher, InMemoryPusher,
+    SsePusher, SseSubscription, SseFrame,
     
     // 分布式会话
     DistributedSessionManager, DistributedSession, DistributedSessionStorage, 
@@ -142,114 +195,7 @@ pub use sa_token_storage_redis::RedisStorage;
 #[cfg(feature = "database")]
 pub use sa_token_storage_database::DatabaseStorage;
 
-use std::sync::Arc;
-
-/// Axum应用状态
-#[derive(Clone)]
-pub struct SaTokenState {
-    pub manager: Arc<SaTokenManager>,
-}
-
-impl SaTokenState {
-    /// 从存储和配置创建状态
-    pub fn new(storage: Arc<dyn SaStorage>, config: SaTokenConfig) -> Self {
-        Self {
-            manager: Arc::new(SaTokenManager::new(storage, config)),
-        }
-    }
-    
-    /// 从 SaTokenManager 创建状态
-    pub fn from_manager(manager: SaTokenManager) -> Self {
-        Self {
-            manager: Arc::new(manager),
-        }
-    }
-    
-    /// 使用构建器模式创建状态
-    /// 
-    /// # 示例
-    /// ```rust,ignore
-    /// use std::sync::Arc;
-    /// use sa_token_plugin_axum::SaTokenState;
-    /// use sa_token_storage_memory::MemoryStorage;
-    /// use sa_token_core::SaTokenConfig;
-    /// 
-    /// let state = SaTokenState::builder()
-    ///     .storage(Arc::new(MemoryStorage::new()))
-    ///     .timeout(7200)
-    ///     .build();
-    /// ```
-    pub fn builder() -> SaTokenStateBuilder {
-        SaTokenStateBuilder::default()
-    }
-}
-
-/// SaTokenState 构建器
-#[derive(Default)]
-pub struct SaTokenStateBuilder {
-    config_builder: sa_token_core::config::SaTokenConfigBuilder,
-}
-
-impl SaTokenStateBuilder {
-    pub fn token_name(mut self, name: impl Into<String>) -> Self {
-        self.config_builder = self.config_builder.token_name(name);
-        self
-    }
-    
-    pub fn timeout(mut self, timeout: i64) -> Self {
-        self.config_builder = self.config_builder.timeout(timeout);
-        self
-    }
-    
-    pub fn active_timeout(mut self, timeout: i64) -> Self {
-        self.config_builder = self.config_builder.active_timeout(timeout);
-        self
-    }
-    
-    /// 设置是否开启自动续签
-    pub fn auto_renew(mut self, enabled: bool) -> Self {
-        self.config_builder = self.config_builder.auto_renew(enabled);
-        self
-    }
-    
-    pub fn is_concurrent(mut self, concurrent: bool) -> Self {
-        self.config_builder = self.config_builder.is_concurrent(concurrent);
-        self
-    }
-    
-    pub fn is_share(mut self, share: bool) -> Self {
-        self.config_builder = self.config_builder.is_share(share);
-        self
-    }
-    
-    pub fn token_style(mut self, style: sa_token_core::config::TokenStyle) -> Self {
-        self.config_builder = self.config_builder.token_style(style);
-        self
-    }
-    
-    pub fn token_prefix(mut self, prefix: impl Into<String>) -> Self {
-        self.config_builder = self.config_builder.token_prefix(prefix);
-        self
-    }
-    
-    pub fn jwt_secret_key(mut self, key: impl Into<String>) -> Self {
-        self.config_builder = self.config_builder.jwt_secret_key(key);
-        self
-    }
-    
-    pub fn storage(mut self, storage: Arc<dyn SaStorage>) -> Self {
-        self.config_builder = self.config_builder.storage(storage);
-        self
-    }
-    
-    pub fn build(self) -> SaTokenState {
-        // config_builder.build() 已经自动初始化了 StpUtil
-        // config_builder.build() already auto-initializes StpUtil
-        let manager = self.config_builder.build();
-        // 直接创建 SaTokenState，不再调用 from_manager 避免重复初始化
-        // Create SaTokenState directly, don't call from_manager to avoid duplicate initialization
-        SaTokenState {
-            manager: Arc::new(manager),
-        }
-    }
-}
+// ============================================================================
+// 应用状态（所有插件共用 sa-token-core 中的实现）
+// ============================================================================
+pub use sa_token_core::state::{SaTokenState, SaTokenStateBuilder};
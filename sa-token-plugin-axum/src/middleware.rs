@@ -5,13 +5,15 @@
 //! 提供两种中间件：
 //! - `SaTokenMiddleware`：基础中间件，从请求中提取token并设置上下文
 //! - `SaCheckLoginMiddleware`：检查登录中间件，未登录时返回401错误
+//! - `SaCheckLayer`：按 `SaCheck` 检查角色、多权限/多角色 AND/OR，需放在 `SaTokenLayer` 内层
 
+use std::sync::Arc;
 use std::task::{Context, Poll};
 use tower::{Layer, Service};
-use http::{Request, Response, StatusCode};
+use http::{header, HeaderName, HeaderValue, Request, Response, StatusCode};
 use http_body;
-use serde_json::json;
-use sa_token_core::error::messages;
+use sa_token_core::{AuthzKind, DecisionTrace, SaCheck, SaTokenError, StpUtil};
+use crate::layer::record_decision;
 
 pub use crate::layer::SaTokenMiddleware;
 
@@ -80,7 +82,7 @@ where
     S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
     S::Future: Send + 'static,
     ReqBody: Send + 'static,
-    ResBody: http_body::Body + Default + Send + 'static,
+    ResBody: http_body::Body + From<String> + Send + 'static,
 {
     type Response = S::Response;
     type Error = S::Error;
@@ -94,31 +96,17 @@ where
         let mut inner = self.inner.clone();
         
         Box::pin(async move {
-            // 检查是否有登录ID
-            if request.extensions().get::<String>().is_none() {
-                // 未登录，返回401错误
-                // 由于我们无法直接返回AxumResponse，这里使用一个hack方法
-                // 创建一个错误响应
-                let mut response = Response::builder()
-                    .status(StatusCode::UNAUTHORIZED)
-                    .body(ResBody::default())
-                    .expect("Unable to create response");
-                
-                // 添加错误信息
-                let error_json = serde_json::to_string(&json!({
-                    "code": 401,
-                    "message": messages::AUTH_ERROR
-                })).unwrap_or_default();
-                
-                // 添加到响应头中，这样上层可以读取
-                if let Ok(header_value) = http::header::HeaderValue::from_str(&error_json) {
-                    response.headers_mut().insert("X-Sa-Token-Error", header_value);
-                }
-                
-                return Ok(response);
+            let trace = request.extensions().get::<DecisionTrace>().cloned();
+            let login_id = request.extensions().get::<String>().cloned();
+            
+            // 检查是否有登录ID，未登录时返回401错误（受限 token 等带具体原因）
+            if let Err(e) = SaCheck::login().check(login_id.as_deref()).await {
+                record_decision(trace.as_ref(), "check_login", "denied");
+                return Ok(rejection_response(&e));
             }
             
             // 已登录，继续处理
+            record_decision(trace.as_ref(), "check_login", "ok");
             inner.call(request).await
         })
     }
@@ -129,7 +117,7 @@ where
     S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
     S::Future: Send + 'static,
     ReqBody: Send + 'static,
-    ResBody: http_body::Body + Default + Send + 'static,
+    ResBody: http_body::Body + From<String> + Send + 'static,
 {
     type Response = S::Response;
     type Error = S::Error;
@@ -144,33 +132,208 @@ where
         let permission = self.permission.clone();
         
         Box::pin(async move {
+            let trace = request.extensions().get::<DecisionTrace>().cloned();
+            let stage = format!("check_permission({})", permission);
+            
             // 检查是否有登录ID
             if let Some(login_id) = request.extensions().get::<String>() {
                 // 检查权限
                 if sa_token_core::StpUtil::has_permission(login_id, &permission).await {
                     // 有权限，继续处理
+                    record_decision(trace.as_ref(), &stage, "granted");
                     return inner.call(request).await;
                 }
+                sa_token_core::StpUtil::record_denial(AuthzKind::Permission, &permission);
+                record_decision(trace.as_ref(), &stage, "denied");
+            } else {
+                record_decision(trace.as_ref(), &stage, "not_logged_in");
             }
             
             // 无权限或未登录，返回403错误
-            let mut response = Response::builder()
-                .status(StatusCode::FORBIDDEN)
-                .body(ResBody::default())
-                .expect("Unable to create response");
-            
-            // 添加错误信息
-            let error_json = serde_json::to_string(&json!({
-                "code": 403,
-                "message": messages::PERMISSION_REQUIRED
-            })).unwrap_or_default();
-            
-            // 添加到响应头中，这样上层可以读取
-            if let Ok(header_value) = http::header::HeaderValue::from_str(&error_json) {
-                response.headers_mut().insert("X-Sa-Token-Error", header_value);
-            }
+            Ok(rejection_response(&SaTokenError::PermissionDeniedDetail(permission)))
+        })
+    }
+}
+
+/// 访问检查中间件层
+/// 
+/// 按 [`SaCheck`] 检查，拒绝时的状态码与其他框架插件一致，响应由配置的
+/// `ErrorRenderer` 生成
+/// 
+/// # 示例
+/// ```rust,ignore
+/// let app = Router::new()
+///     .route("/admin", get(admin))
+///     .layer(SaCheckLayer::new(SaCheck::roles_or(["admin", "ops"])))
+///     .layer(SaTokenLayer::new(state));
+/// ```
+#[derive(Clone)]
+pub struct SaCheckLayer {
+    check: Arc<SaCheck>,
+}
+
+impl SaCheckLayer {
+    pub fn new(check: SaCheck) -> Self {
+        Self { check: Arc::new(check) }
+    }
+}
+
+impl<S> Layer<S> for SaCheckLayer {
+    type Service = SaCheckService<S>;
+    
+    fn layer(&self, inner: S) -> Self::Service {
+        SaCheckService {
+            inner,
+            check: self.check.clone(),
+        }
+    }
+}
+
+/// 访问检查中间件
+#[derive(Clone)]
+pub struct SaCheckService<S> {
+    inner: S,
+    check: Arc<SaCheck>,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for SaCheckService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+    ResBody: http_body::Body + From<String> + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+    
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+    
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let check = self.check.clone();
+        
+        Box::pin(async move {
+            let trace = request.extensions().get::<DecisionTrace>().cloned();
+            let login_id = request.extensions().get::<String>().cloned();
             
-            Ok(response)
+            let Err(e) = check.check(login_id.as_deref()).await else {
+                record_decision(trace.as_ref(), &check.stage(), "granted");
+                return inner.call(request).await;
+            };
+            record_decision(trace.as_ref(), &check.stage(), "denied");
+            Ok(rejection_response(&e))
         })
     }
 }
+
+/// 用配置的 `ErrorRenderer` 生成拒绝响应
+/// 
+/// 响应体同时写入 `X-Sa-Token-Error` 响应头（能作为头部值时），兼容读取该头部的旧客户端。
+pub(crate) fn rejection_response<B: From<String>>(err: &SaTokenError) -> Response<B> {
+    let rendered = StpUtil::render_error(err);
+    let mut response = Response::new(B::from(rendered.body.clone()));
+    *response.status_mut() = StatusCode::from_u16(rendered.status).unwrap_or(StatusCode::FORBIDDEN);
+    
+    let headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(&rendered.content_type) {
+        headers.insert(header::CONTENT_TYPE, value);
+    }
+    for (name, value) in &rendered.headers {
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(name.as_str()), HeaderValue::from_str(value)) {
+            headers.append(name, value);
+        }
+    }
+    if let Ok(value) = HeaderValue::from_str(&rendered.body) {
+        headers.insert("X-Sa-Token-Error", value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Router};
+    use std::net::SocketAddr;
+    use axum::extract::ConnectInfo;
+    use sa_token_core::{SaTokenConfig, SaTokenContext, SaTokenState, TokenInfo, TokenValue, PASSWORD_CHANGE_SCOPE};
+    use sa_token_test_utils::state::{state, state_with};
+    use tower::ServiceExt;
+    use crate::SaTokenLayer;
+
+    fn app(state: &SaTokenState) -> Router {
+        Router::new()
+            .route("/profile", get(|| async { "ok" }).layer(SaCheckLayer::new(SaCheck::login())))
+            .route("/orders", get(|| async { "ok" }).layer(SaCheckLoginLayer::new()))
+            .layer(SaTokenLayer::new(state.clone()))
+    }
+
+    async fn call(app: Router, path: &str, token: &TokenValue) -> (StatusCode, String) {
+        let request = Request::builder().uri(path).header("sa-token", token.as_str()).body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_restricted_token_is_rejected_on_login_routes() {
+        let state = state();
+        let mut token_info = TokenInfo::new(TokenValue::new(""), "user_1");
+        token_info.scope = Some(vec![PASSWORD_CHANGE_SCOPE.to_string()]);
+        let restricted = state.manager.login_with_token_info(token_info).await.unwrap();
+        let token = state.manager.login("user_2").await.unwrap();
+
+        for path in ["/profile", "/orders"] {
+            assert_eq!(call(app(&state), path, &token).await, (StatusCode::OK, "ok".to_string()));
+
+            let (status, body) = call(app(&state), path, &restricted).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+            assert!(body.contains(&SaTokenError::PasswordExpired.to_string()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replaced_and_kicked_out_tokens_are_rejected_with_reason() {
+        let config = SaTokenConfig { is_concurrent: false, ..Default::default() };
+        let state = state_with(config);
+        let replaced = state.manager.login("user_1").await.unwrap();
+        let current = state.manager.login("user_1").await.unwrap();
+        state.manager.kick_out_with_reason("user_1", "Signed in elsewhere").await.unwrap();
+
+        for path in ["/profile", "/orders"] {
+            let (status, body) = call(app(&state), path, &replaced).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+            assert!(body.contains(&SaTokenError::Replaced.to_string()));
+
+            let (status, body) = call(app(&state), path, &current).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+            assert!(body.contains(&SaTokenError::KickedOut("Signed in elsewhere".to_string()).to_string()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_token_used_from_another_ip_is_rejected_with_reason() {
+        let config = SaTokenConfig { token_bind_ip: true, ..Default::default() };
+        let state = state_with(config);
+        let mut login_ctx = SaTokenContext::new();
+        login_ctx.capture_client(Some("203.0.113.9"), None);
+        let token = SaTokenContext::scope(login_ctx, state.manager.login("user_1")).await.unwrap();
+
+        for path in ["/profile", "/orders"] {
+            let call_from = |peer: &str| {
+                let mut request = Request::builder().uri(path).header("sa-token", token.as_str()).body(Body::empty()).unwrap();
+                request.extensions_mut().insert(ConnectInfo(peer.parse::<SocketAddr>().unwrap()));
+                app(&state).oneshot(request)
+            };
+            assert_eq!(call_from("203.0.113.9:4000").await.unwrap().status(), StatusCode::OK);
+
+            let response = call_from("198.51.100.4:4000").await.unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            assert!(String::from_utf8_lossy(&body).contains(&SaTokenError::IpMismatch.to_string()));
+        }
+    }
+}
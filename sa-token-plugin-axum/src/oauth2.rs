@@ -0,0 +1,73 @@
+// Author: 金书记
+//
+//! OAuth2 令牌内省与撤销接口
+//!
+//! - `POST /oauth2/introspect`（RFC 7662）：表单 `token`、`client_id`、`client_secret`，
+//!   返回 `{"active": true, "scope", "client_id", "exp", "sub"}`；无效令牌返回 `{"active": false}`
+//! - `POST /oauth2/revoke`（RFC 7009）：表单同上，撤销令牌（访问令牌连同其刷新令牌），成功返回 200 空响应
+//!
+//! 客户端凭据错误返回 401 `{"error": "invalid_client"}`，撤销其他客户端的令牌返回 400 `{"error": "unauthorized_client"}`。
+
+use std::sync::Arc;
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::post,
+    Form, Json, Router,
+};
+use serde::Deserialize;
+use serde_json::json;
+use sa_token_core::{OAuth2Manager, SaTokenError, OAUTH2_INTROSPECT_PATH, OAUTH2_REVOKE_PATH};
+
+/// 创建 OAuth2 内省与撤销路由
+///
+/// # 示例
+/// ```rust,ignore
+/// let oauth2 = Arc::new(OAuth2Manager::new(storage));
+/// let app = Router::new().merge(oauth2_router(oauth2));
+/// ```
+pub fn oauth2_router<S>(oauth2: Arc<OAuth2Manager>) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route(OAUTH2_INTROSPECT_PATH, post(introspect))
+        .route(OAUTH2_REVOKE_PATH, post(revoke))
+        .with_state(oauth2)
+}
+
+#[derive(Deserialize)]
+struct TokenForm {
+    token: String,
+    client_id: String,
+    client_secret: String,
+}
+
+async fn introspect(State(oauth2): State<Arc<OAuth2Manager>>, Form(form): Form<TokenForm>) -> Response {
+    let result = match oauth2.verify_client(&form.client_id, &form.client_secret).await {
+        Ok(true) => oauth2.introspect(&form.token).await,
+        Ok(false) => Err(SaTokenError::OAuth2InvalidCredentials),
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(response) => Json(response).into_response(),
+        Err(e) => error_response(e),
+    }
+}
+
+async fn revoke(State(oauth2): State<Arc<OAuth2Manager>>, Form(form): Form<TokenForm>) -> Response {
+    match oauth2.revoke_client_token(&form.client_id, &form.client_secret, &form.token).await {
+        Ok(()) => StatusCode::OK.into_response(),
+        Err(e) => error_response(e),
+    }
+}
+
+fn error_response(error: SaTokenError) -> Response {
+    let (status, code) = match error {
+        SaTokenError::OAuth2InvalidCredentials | SaTokenError::OAuth2ClientNotFound => (StatusCode::UNAUTHORIZED, "invalid_client"),
+        SaTokenError::OAuth2ClientIdMismatch => (StatusCode::BAD_REQUEST, "unauthorized_client"),
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, "server_error"),
+    };
+    (status, Json(json!({ "error": code }))).into_response()
+}
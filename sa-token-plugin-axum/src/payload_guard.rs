@@ -0,0 +1,136 @@
+// Author: 金书记
+//
+//! 请求体守卫层 | Payload guard layer
+//!
+//! 在大小限制内缓冲 `application/json` 请求体，交给 [`PayloadGuard`] 检查后再原样
+//! 传给处理函数。需放在 `SaTokenLayer` 内层，以便拿到当前登录 ID；其他类型的请求体
+//! 不缓冲、不检查。
+//!
+//! Buffers `application/json` bodies up to a size limit, runs the
+//! [`PayloadGuard`]s, then hands the same bytes to the handler. Place it inside
+//! `SaTokenLayer` so the login ID is known; other content types are neither
+//! buffered nor checked.
+//!
+//! ```rust,ignore
+//! let app = Router::new()
+//!     .route("/transfers", post(create_transfer))
+//!     .layer(SaPayloadGuardLayer::new(ThresholdGuard::new("/amount", 10_000.0, "finance:approve")))
+//!     .layer(SaTokenLayer::new(state));
+//! ```
+
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use axum::body::Body;
+use axum::response::Response;
+use http::{header, Request};
+use tower::{Layer, Service};
+use sa_token_core::{DecisionTrace, PayloadGuard, SaTokenError, DEFAULT_PAYLOAD_LIMIT};
+use sa_token_core::permission::parse_payload;
+use crate::layer::record_decision;
+use crate::middleware::rejection_response;
+
+/// 请求体守卫层 | Payload guard layer
+#[derive(Clone)]
+pub struct SaPayloadGuardLayer {
+    guards: Vec<Arc<dyn PayloadGuard>>,
+    limit: usize,
+}
+
+impl SaPayloadGuardLayer {
+    /// 以一个守卫创建 | Create with one guard
+    pub fn new(guard: impl PayloadGuard + 'static) -> Self {
+        Self {
+            guards: vec![Arc::new(guard)],
+            limit: DEFAULT_PAYLOAD_LIMIT,
+        }
+    }
+
+    /// 追加守卫，按顺序检查 | Add a guard, checked in order
+    pub fn guard(mut self, guard: impl PayloadGuard + 'static) -> Self {
+        self.guards.push(Arc::new(guard));
+        self
+    }
+
+    /// 设置缓冲上限（字节），超过返回 413 | Set the buffering limit in bytes; larger bodies get 413
+    pub fn limit(mut self, bytes: usize) -> Self {
+        self.limit = bytes;
+        self
+    }
+}
+
+impl<S> Layer<S> for SaPayloadGuardLayer {
+    type Service = SaPayloadGuard<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        SaPayloadGuard {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// 请求体守卫服务 | Payload guard service
+#[derive(Clone)]
+pub struct SaPayloadGuard<S> {
+    inner: S,
+    layer: SaPayloadGuardLayer,
+}
+
+impl<S> Service<Request<Body>> for SaPayloadGuard<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let layer = self.layer.clone();
+
+        Box::pin(async move {
+            if !is_json(&request) {
+                return inner.call(request).await;
+            }
+            let trace = request.extensions().get::<DecisionTrace>().cloned();
+            let login_id = request.extensions().get::<String>().cloned();
+
+            let (parts, body) = request.into_parts();
+            let bytes = match axum::body::to_bytes(body, layer.limit).await {
+                Ok(bytes) => bytes,
+                Err(_) => return Ok(rejection_response(&SaTokenError::PayloadTooLarge(layer.limit))),
+            };
+
+            let checked = async {
+                let login_id = login_id.ok_or(SaTokenError::NotLogin)?;
+                let payload = parse_payload(&bytes, layer.limit)?;
+                for guard in &layer.guards {
+                    guard.check(&login_id, &payload).await?;
+                }
+                Ok::<_, SaTokenError>(())
+            };
+            if let Err(e) = checked.await {
+                record_decision(trace.as_ref(), "check_payload", "denied");
+                return Ok(rejection_response(&e));
+            }
+
+            record_decision(trace.as_ref(), "check_payload", "granted");
+            inner.call(Request::from_parts(parts, Body::from(bytes))).await
+        })
+    }
+}
+
+fn is_json(request: &Request<Body>) -> bool {
+    request.headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            let mime = value.split(';').next().unwrap_or_default().trim();
+            mime.eq_ignore_ascii_case("application/json") || mime.ends_with("+json")
+        })
+}
@@ -0,0 +1,155 @@
+// Author: 金书记
+//
+//! 出站请求身份透传层 | Outbound identity propagation layer
+//!
+//! 套在处理函数内使用的 HTTP 客户端（任何 `tower::Service<http::Request<B>>`，如
+//! hyper-util 的 `Client`）外面，调用时读取当前请求的 [`SaTokenContext`]，自动写入
+//! 服务间请求头，身份沿调用链传递而无需手动拼接请求头。
+//!
+//! Wraps the HTTP client used inside handlers (any
+//! `tower::Service<http::Request<B>>`, such as hyper-util's `Client`). On each
+//! call it reads the current [`SaTokenContext`] and writes the
+//! service-to-service headers, so identity follows the call chain without
+//! manual header plumbing.
+//!
+//! ```rust,ignore
+//! let client = ServiceBuilder::new()
+//!     .layer(SaTokenPropagationLayer::signed("s2s-secret").same_token("shared-same-token"))
+//!     .service(hyper_util::client::legacy::Client::builder(TokioExecutor::new()).build_http());
+//!
+//! // reqwest 等非 tower 客户端直接取请求头
+//! // For clients that are not tower services, such as reqwest
+//! let response = reqwest_client.get(url)
+//!     .headers(SaTokenPropagationLayer::signed("s2s-secret").headers())
+//!     .send().await?;
+//! ```
+
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use http::{HeaderMap, HeaderName, HeaderValue, Request};
+use tower::{Layer, Service};
+use sa_token_core::{PropagatedContext, SaTokenContext, SAME_TOKEN_HEADER, SIGNED_CONTEXT_HEADER};
+
+/// 透传方式 | How the identity is propagated
+#[derive(Clone)]
+enum Propagation {
+    /// 签名上下文，下游用共享密钥校验 | Signed context, verified downstream with a shared secret
+    Signed { secret: Arc<str> },
+    /// 原样转发当前 token | Forward the current token as is
+    ForwardToken { header: HeaderName },
+}
+
+/// 出站身份透传层 | Outbound identity propagation layer
+#[derive(Clone)]
+pub struct SaTokenPropagationLayer {
+    propagation: Propagation,
+    same_token: Option<HeaderValue>,
+}
+
+impl SaTokenPropagationLayer {
+    /// 写入签名上下文（`sa-context` 头）| Send a signed context in the `sa-context` header
+    pub fn signed(secret: impl AsRef<str>) -> Self {
+        Self {
+            propagation: Propagation::Signed { secret: Arc::from(secret.as_ref()) },
+            same_token: None,
+        }
+    }
+
+    /// 把当前 token 写入指定请求头，下游需共享 token 存储 | Forward the current token; downstream shares the token storage
+    ///
+    /// # Panics
+    /// 请求头名称不合法时 panic | Panics on an invalid header name
+    pub fn forward_token(header: &str) -> Self {
+        Self {
+            propagation: Propagation::ForwardToken {
+                header: HeaderName::try_from(header).expect("invalid header name"),
+            },
+            same_token: None,
+        }
+    }
+
+    /// 同时写入服务间 Same-Token（`sa-same-token` 头）| Also send a service-to-service Same-Token
+    ///
+    /// # Panics
+    /// 值不是合法请求头值时 panic | Panics when the value is not a valid header value
+    pub fn same_token(mut self, same_token: impl AsRef<str>) -> Self {
+        self.same_token = Some(HeaderValue::from_str(same_token.as_ref()).expect("invalid Same-Token"));
+        self
+    }
+
+    /// 根据当前上下文生成要写入的请求头 | Headers to send for the current context
+    ///
+    /// 没有登录身份时只包含 Same-Token。
+    /// Only the Same-Token is included when no identity is present.
+    pub fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(ref same_token) = self.same_token {
+            headers.insert(SAME_TOKEN_HEADER, same_token.clone());
+        }
+
+        let Some(ctx) = SaTokenContext::get_current() else {
+            return headers;
+        };
+        match &self.propagation {
+            Propagation::Signed { secret } => {
+                if let Some(login_id) = ctx.login_id
+                    && let Ok(value) = HeaderValue::from_str(&PropagatedContext::new(login_id).sign(secret))
+                {
+                    headers.insert(SIGNED_CONTEXT_HEADER, value);
+                }
+            }
+            Propagation::ForwardToken { header } => {
+                if let Some(token) = ctx.token
+                    && let Ok(mut value) = HeaderValue::from_str(token.as_str())
+                {
+                    value.set_sensitive(true);
+                    headers.insert(header.clone(), value);
+                }
+            }
+        }
+        headers
+    }
+}
+
+impl<S> Layer<S> for SaTokenPropagationLayer {
+    type Service = SaTokenPropagation<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        SaTokenPropagation {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// 出站身份透传服务 | Outbound identity propagation service
+#[derive(Clone)]
+pub struct SaTokenPropagation<S> {
+    inner: S,
+    layer: SaTokenPropagationLayer,
+}
+
+impl<S, B> Service<Request<B>> for SaTokenPropagation<S>
+where
+    S: Service<Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<B>) -> Self::Future {
+        // 调用方显式设置的请求头优先 | Headers set explicitly by the caller win
+        for (name, value) in self.layer.headers() {
+            if let Some(name) = name
+                && !request.headers().contains_key(&name)
+            {
+                request.headers_mut().insert(name, value);
+            }
+        }
+        self.inner.call(request)
+    }
+}
@@ -0,0 +1,97 @@
+// Author: 金书记
+//
+//! 只读模式中间件
+//!
+//! 只读模式开启后（`StpUtil::enable_read_only_mode`），拒绝受限用户的写操作
+//! （POST/PUT/PATCH/DELETE），返回 403。拥有豁免权限的用户不受影响。
+//! 需要放在 `SaTokenLayer` 内层（即先经过 `SaTokenLayer`）才能识别登录用户。
+
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+use http::{Request, Response, StatusCode};
+use serde_json::json;
+use sa_token_core::{SaTokenError, StpUtil};
+
+/// 只读模式中间件层
+///
+/// # 示例
+/// ```rust,ignore
+/// let app = Router::new()
+///     .route("/api/orders", get(list_orders).post(create_order))
+///     .layer(ReadOnlyModeLayer::new())
+///     .layer(SaTokenLayer::new(state.clone()));
+///
+/// // 运行时开启 | Toggle at runtime
+/// StpUtil::enable_read_only_mode(ReadOnlyMode::new().roles(["operator"])).await?;
+/// ```
+#[derive(Clone, Default)]
+pub struct ReadOnlyModeLayer;
+
+impl ReadOnlyModeLayer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Layer<S> for ReadOnlyModeLayer {
+    type Service = ReadOnlyModeMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ReadOnlyModeMiddleware { inner }
+    }
+}
+
+/// 只读模式中间件
+#[derive(Clone)]
+pub struct ReadOnlyModeMiddleware<S> {
+    inner: S,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for ReadOnlyModeMiddleware<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+    ResBody: http_body::Body + Default + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        let mut inner = self.inner.clone();
+
+        Box::pin(async move {
+            let login_id = request.extensions().get::<String>().cloned();
+            let message = match StpUtil::check_read_only(login_id.as_deref(), request.method().as_str()).await {
+                Ok(()) => return inner.call(request).await,
+                Err(SaTokenError::ReadOnlyMode(message)) => message,
+                Err(e) => {
+                    // 读取只读模式设置失败时不阻断请求
+                    tracing::warn!("Sa-Token: 只读模式检查失败: {}", e);
+                    return inner.call(request).await;
+                }
+            };
+
+            let mut response = Response::builder()
+                .status(StatusCode::FORBIDDEN)
+                .body(ResBody::default())
+                .expect("Unable to create response");
+
+            let error_json = serde_json::to_string(&json!({
+                "code": 403,
+                "message": message
+            })).unwrap_or_default();
+
+            if let Ok(header_value) = http::header::HeaderValue::from_str(&error_json) {
+                response.headers_mut().insert("X-Sa-Token-Error", header_value);
+            }
+
+            Ok(response)
+        })
+    }
+}
@@ -0,0 +1,176 @@
+// Author: 金书记
+//
+//! 路由级访问检查 | Router-level access checks
+//!
+//! 一次性保护整组路由，无需在每个处理函数上重复标注宏。检查以 `route_layer`
+//! 挂载，只作用于调用前已添加的路由，未匹配的请求照常返回 404；外层仍需
+//! [`SaTokenLayer`](crate::SaTokenLayer) 解析 token。
+//!
+//! Protects a whole route group without repeating macros on every handler.
+//! The check is mounted with `route_layer`, so it only covers routes added
+//! before the call and unmatched requests still get a 404; an outer
+//! [`SaTokenLayer`](crate::SaTokenLayer) is still required to resolve the token.
+//!
+//! ```rust,ignore
+//! let admin = Router::new()
+//!     .route("/admin/users", get(list_users))
+//!     .route("/admin/users/:id", delete(delete_user))
+//!     .require_permission("admin:*");
+//!
+//! let app = Router::new()
+//!     .merge(admin)
+//!     .route("/public", get(public))
+//!     .layer(SaTokenLayer::new(state));
+//! ```
+
+use axum::Router;
+use sa_token_core::SaCheck;
+use crate::middleware::SaCheckLayer;
+
+/// 为 [`Router`] 添加访问检查 | Access checks on a [`Router`]
+pub trait RouterExt {
+    /// 已添加的路由必须登录 | Routes added so far require login
+    fn require_login(self) -> Self;
+
+    /// 已添加的路由必须拥有权限，支持通配符 | Routes added so far require a permission, wildcards allowed
+    fn require_permission(self, permission: impl Into<String>) -> Self;
+
+    /// 已添加的路由必须拥有角色 | Routes added so far require a role
+    fn require_role(self, role: impl Into<String>) -> Self;
+
+    /// 已添加的路由按 [`SaCheck`] 检查 | Routes added so far are checked with a [`SaCheck`]
+    fn require(self, check: SaCheck) -> Self;
+}
+
+impl<S> RouterExt for Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    fn require_login(self) -> Self {
+        self.require(SaCheck::login())
+    }
+
+    fn require_permission(self, permission: impl Into<String>) -> Self {
+        self.require(SaCheck::permission(permission))
+    }
+
+    fn require_role(self, role: impl Into<String>) -> Self {
+        self.require(SaCheck::role(role))
+    }
+
+    fn require(self, check: SaCheck) -> Self {
+        self.route_layer(SaCheckLayer::new(check))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, OnceLock};
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use axum::response::{IntoResponse, Response};
+    use axum::routing::get;
+    use sa_token_core::{SaTokenError, SaTokenManager, SaTokenState, StpUtil, TokenValue};
+    use sa_token_macro::{sa_check_login, sa_check_role, sa_ignore};
+    use sa_token_storage_memory::MemoryStorage;
+    use tower::ServiceExt;
+    use crate::SaTokenLayer;
+
+    /// 权限与角色检查走全局 `StpUtil`，测试共用同一个状态 | Permission and role checks go through the global `StpUtil`, so the tests share one state
+    fn state() -> SaTokenState {
+        static STATE: OnceLock<SaTokenState> = OnceLock::new();
+        STATE.get_or_init(|| SaTokenState::from_manager(SaTokenManager::new(Arc::new(MemoryStorage::new()), Default::default()))).clone()
+    }
+
+    /// 按中间件的方式渲染检查失败 | Render a failed check the way the middlewares do
+    struct ApiError(SaTokenError);
+
+    impl From<SaTokenError> for ApiError {
+        fn from(err: SaTokenError) -> Self {
+            Self(err)
+        }
+    }
+
+    impl IntoResponse for ApiError {
+        fn into_response(self) -> Response {
+            let (status, body) = SaCheck::rejection(&self.0);
+            (StatusCode::from_u16(status).unwrap(), body.to_string()).into_response()
+        }
+    }
+
+    struct AdminApi;
+
+    #[sa_check_role("admin")]
+    impl AdminApi {
+        async fn dashboard() -> Result<&'static str, ApiError> {
+            Ok("dashboard")
+        }
+
+        #[sa_ignore]
+        async fn health() -> Result<&'static str, ApiError> {
+            Ok("healthy")
+        }
+    }
+
+    struct UserApi;
+
+    #[sa_check_login]
+    impl UserApi {
+        async fn profile() -> Result<String, ApiError> {
+            Ok(StpUtil::get_login_id_as_string().await?)
+        }
+    }
+
+    /// `token` 为 `None` 时不带请求头 | Sends no token header when `token` is `None`
+    async fn call(app: Router, path: &str, token: Option<&TokenValue>) -> (StatusCode, String) {
+        let mut request = Request::builder().uri(path);
+        if let Some(token) = token {
+            request = request.header("sa-token", token.as_str());
+        }
+        let response = app.oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_require_permission_guards_the_route_group() {
+        let state = state();
+        let app = || Router::new()
+            .merge(Router::new().route("/admin/users", get(|| async { "users" })).require_permission("admin:*"))
+            .route("/public", get(|| async { "public" }))
+            .layer(SaTokenLayer::new(state.clone()));
+        let admin = state.manager.login("router_admin").await.unwrap();
+        StpUtil::set_permissions("router_admin", vec!["admin:*".to_string()]).await.unwrap();
+        let user = state.manager.login("router_user").await.unwrap();
+
+        assert_eq!(call(app(), "/admin/users", None).await.0, StatusCode::UNAUTHORIZED);
+        assert_eq!(call(app(), "/admin/users", Some(&user)).await.0, StatusCode::FORBIDDEN);
+        assert_eq!(call(app(), "/admin/users", Some(&admin)).await, (StatusCode::OK, "users".to_string()));
+        assert_eq!(call(app(), "/public", None).await, (StatusCode::OK, "public".to_string()));
+        assert_eq!(call(app(), "/missing", None).await.0, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_check_macros_on_impl_blocks() {
+        let state = state();
+        let app = || Router::new()
+            .route("/dashboard", get(AdminApi::dashboard))
+            .route("/health", get(AdminApi::health))
+            .route("/profile", get(UserApi::profile))
+            .layer(SaTokenLayer::new(state.clone()));
+        let admin = state.manager.login("macro_admin").await.unwrap();
+        StpUtil::set_roles("macro_admin", vec!["admin".to_string()]).await.unwrap();
+        let user = state.manager.login("macro_user").await.unwrap();
+
+        assert_eq!(call(app(), "/dashboard", None).await.0, StatusCode::UNAUTHORIZED);
+        assert_eq!(call(app(), "/profile", None).await.0, StatusCode::UNAUTHORIZED);
+        assert_eq!(call(app(), "/dashboard", Some(&user)).await.0, StatusCode::FORBIDDEN);
+        assert_eq!(call(app(), "/dashboard", Some(&admin)).await, (StatusCode::OK, "dashboard".to_string()));
+        assert_eq!(call(app(), "/profile", Some(&user)).await, (StatusCode::OK, "macro_user".to_string()));
+
+        // `#[sa_ignore]` 的方法不做检查 | Methods marked `#[sa_ignore]` are not checked
+        assert_eq!(call(app(), "/health", None).await, (StatusCode::OK, "healthy".to_string()));
+    }
+}
@@ -0,0 +1,89 @@
+// Author: 金书记
+//
+//! Same-Token 校验层 | Same-Token layer
+//!
+//! 要求请求携带有效的 `sa-same-token` 请求头，用于只允许网关或其他内部服务访问的路由；
+//! 缺失或无效时按配置的 `ErrorRenderer` 返回 401。
+//!
+//! Requires a valid `sa-same-token` header, for routes that only the gateway
+//! or other internal services may call; a missing or invalid token gets 401
+//! through the configured `ErrorRenderer`.
+//!
+//! ```rust,ignore
+//! let internal = Router::new()
+//!     .route("/internal/users/{id}", get(load_user))
+//!     .route_layer(SaSameTokenLayer::new(state.manager.same_token_manager()));
+//! ```
+
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use http::{Request, Response};
+use tower::{Layer, Service};
+use sa_token_core::{DecisionTrace, SameTokenManager, SAME_TOKEN_HEADER};
+use crate::layer::record_decision;
+use crate::middleware::rejection_response;
+
+/// Same-Token 校验层 | Same-Token layer
+#[derive(Clone)]
+pub struct SaSameTokenLayer {
+    same: Arc<SameTokenManager>,
+}
+
+impl SaSameTokenLayer {
+    pub fn new(same: SameTokenManager) -> Self {
+        Self { same: Arc::new(same) }
+    }
+}
+
+impl<S> Layer<S> for SaSameTokenLayer {
+    type Service = SaSameTokenService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        SaSameTokenService {
+            inner,
+            same: self.same.clone(),
+        }
+    }
+}
+
+/// Same-Token 校验服务 | Same-Token service
+#[derive(Clone)]
+pub struct SaSameTokenService<S> {
+    inner: S,
+    same: Arc<SameTokenManager>,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for SaSameTokenService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+    ResBody: http_body::Body + From<String> + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let same = self.same.clone();
+
+        Box::pin(async move {
+            let trace = request.extensions().get::<DecisionTrace>().cloned();
+            let token = request.headers().get(SAME_TOKEN_HEADER)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+
+            let Err(e) = same.check_token(token.as_deref()).await else {
+                record_decision(trace.as_ref(), "check_same_token", "granted");
+                return inner.call(request).await;
+            };
+            record_decision(trace.as_ref(), "check_same_token", "denied");
+            Ok(rejection_response(&e))
+        })
+    }
+}
@@ -0,0 +1,95 @@
+// Author: 金书记
+//
+//! 安全响应头中间件
+//!
+//! 为已登录请求的响应统一设置 X-Content-Type-Options、X-Frame-Options、
+//! Referrer-Policy 以及可选的 Content-Security-Policy。
+//! 需要放在 `SaTokenLayer` 内层（即先经过 `SaTokenLayer`）才能识别登录状态。
+
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+use http::{HeaderName, HeaderValue, Request, Response};
+use sa_token_core::SecurityHeaders;
+
+/// 安全响应头中间件层
+///
+/// # 示例
+/// ```rust,ignore
+/// let app = Router::new()
+///     .route("/api/user", get(user_info))
+///     .layer(SaSecurityHeadersLayer::new())
+///     .layer(SaTokenLayer::new(state.clone()));
+/// ```
+#[derive(Clone, Default)]
+pub struct SaSecurityHeadersLayer {
+    config: SecurityHeaders,
+}
+
+impl SaSecurityHeadersLayer {
+    /// 使用默认安全响应头
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 使用自定义安全响应头配置
+    pub fn with_config(config: SecurityHeaders) -> Self {
+        Self { config }
+    }
+}
+
+impl<S> Layer<S> for SaSecurityHeadersLayer {
+    type Service = SaSecurityHeadersMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        SaSecurityHeadersMiddleware {
+            inner,
+            config: self.config.clone(),
+        }
+    }
+}
+
+/// 安全响应头中间件
+#[derive(Clone)]
+pub struct SaSecurityHeadersMiddleware<S> {
+    inner: S,
+    config: SecurityHeaders,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for SaSecurityHeadersMiddleware<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let config = self.config.clone();
+        // SaTokenLayer 会为已登录请求写入 login_id
+        let authenticated = request.extensions().get::<String>().is_some();
+
+        Box::pin(async move {
+            let mut response = inner.call(request).await?;
+
+            if config.should_apply(authenticated) {
+                let headers = response.headers_mut();
+                for (name, value) in config.headers() {
+                    let (Ok(name), Ok(value)) = (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(&value)) else {
+                        continue;
+                    };
+                    // 业务代码已设置的头部保持不变
+                    headers.entry(name).or_insert(value);
+                }
+            }
+
+            Ok(response)
+        })
+    }
+}
@@ -0,0 +1,80 @@
+// Author: 金书记
+//
+//! Session 管理接口
+//!
+//! 供运维/客服在管理后台查看并修复用户 session，无需直接访问 Redis：
+//!
+//! - `GET /admin/sessions/{login_id}`：返回存储中的原始 JSON
+//! - `PATCH /admin/sessions/{login_id}`：请求体为 RFC 6902 JSON Patch，返回修改后的 session
+//!
+//! 读取与修改都会以 `sa_token::audit` 为 target 记录审计日志，操作人为当前登录 ID。
+//! 路由本身不做权限校验，务必挂在管理员权限检查之后。
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde_json::{json, Value};
+use sa_token_core::SaTokenError;
+use crate::SaTokenState;
+
+/// Session 管理接口路径
+pub const SESSION_ADMIN_PATH: &str = "/admin/sessions/{login_id}";
+
+/// 创建 session 管理路由
+///
+/// # 示例
+/// ```rust,ignore
+/// let admin = session_admin_router(state.clone())
+///     .layer(SaCheckPermissionLayer::new("admin:session"));
+///
+/// let app = Router::new()
+///     .merge(admin)
+///     .layer(SaTokenLayer::new(state.clone()));
+/// ```
+pub fn session_admin_router<S>(state: SaTokenState) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route(SESSION_ADMIN_PATH, get(get_session_raw).patch(patch_session))
+        .with_state(state)
+}
+
+async fn get_session_raw(
+    State(state): State<SaTokenState>,
+    Path(login_id): Path<String>,
+) -> Response {
+    match state.manager.get_session_raw(&login_id).await {
+        Ok(Some(session)) => Json(session).into_response(),
+        Ok(None) => error_response(StatusCode::NOT_FOUND, SaTokenError::SessionNotFound.to_string()),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+async fn patch_session(
+    State(state): State<SaTokenState>,
+    Path(login_id): Path<String>,
+    Json(patch): Json<Value>,
+) -> Response {
+    match state.manager.patch_session(&login_id, &patch).await {
+        Ok(session) => Json(session).into_response(),
+        Err(e @ (SaTokenError::InvalidSessionPatch(_) | SaTokenError::SessionQuotaExceeded(_))) => {
+            error_response(StatusCode::BAD_REQUEST, e.to_string())
+        }
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+fn error_response(status: StatusCode, message: String) -> Response {
+    (
+        status,
+        Json(json!({
+            "code": status.as_u16(),
+            "message": message
+        }))
+    ).into_response()
+}
@@ -0,0 +1,75 @@
+// Author: 金书记
+//
+//! Server-Sent Events 推送接口
+//!
+//! 不使用 WebSocket 的浏览器通过 `EventSource` 连接 `/auth/events`，接收 `SsePusher` 推送的消息。
+//! 心跳注释帧会刷新在线活跃时间，客户端断开后自动标记下线，踢人通知送达后服务端结束流。
+//!
+//! 查询参数：`device`（默认 `web`）。需要 `SaTokenLayer` 完成认证，未登录返回 401。
+
+use std::convert::Infallible;
+use std::sync::Arc;
+use axum::{
+    extract::{Query, State},
+    response::sse::{Event, Sse},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use futures_util::stream;
+use serde::Deserialize;
+use sa_token_core::{SseFrame, SsePusher, SSE_MESSAGE_EVENT};
+use crate::extractor::{LoginIdExtractor, SaTokenExtractor};
+
+/// SSE 接口路径
+pub const SSE_PATH: &str = "/auth/events";
+
+#[derive(Debug, Default, Deserialize)]
+struct EventsQuery {
+    device: Option<String>,
+}
+
+/// 创建 SSE 路由，合并到应用路由后再套 `SaTokenLayer`
+///
+/// # 示例
+/// ```rust,ignore
+/// let sse = Arc::new(SsePusher::new().with_online_manager(online.clone()));
+/// online.register_pusher(sse.clone()).await;
+///
+/// let app = Router::new()
+///     .route("/api/user", get(user_info))
+///     .merge(sse_router(sse))
+///     .layer(SaTokenLayer::new(state.clone()));
+/// ```
+///
+/// 前端：`new EventSource("/auth/events?device=web&token=...")`，监听 `message` 事件
+pub fn sse_router<S>(pusher: Arc<SsePusher>) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route(SSE_PATH, get(sse_events))
+        .with_state(pusher)
+}
+
+async fn sse_events(
+    State(pusher): State<Arc<SsePusher>>,
+    SaTokenExtractor(token): SaTokenExtractor,
+    LoginIdExtractor(login_id): LoginIdExtractor,
+    Query(query): Query<EventsQuery>,
+) -> Response {
+    let device = query.device.unwrap_or_else(|| "web".to_string());
+    let subscription = pusher.subscribe(login_id, token.as_str(), device).await;
+
+    // 响应流被丢弃时订阅随之丢弃，自动注销并标记下线
+    let events = stream::unfold(subscription, |mut subscription| async move {
+        let event = match subscription.next().await? {
+            SseFrame::Message(message) => Event::default()
+                .event(SSE_MESSAGE_EVENT)
+                .data(serde_json::to_string(&message).unwrap_or_default()),
+            SseFrame::Heartbeat => Event::default().comment("ping"),
+        };
+        Some((Ok::<_, Infallible>(event), subscription))
+    });
+    Sse::new(events).into_response()
+}
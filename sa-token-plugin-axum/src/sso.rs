@@ -0,0 +1,126 @@
+// Author: 金书记
+//
+//! SSO 服务端接口
+//!
+//! 把 `SsoManager` 挂成现成的 HTTP 接口，几行代码即可搭起一个 SSO 服务端：
+//!
+//! - `GET /sso/auth?service=<url>`：已登录时签发票据并 302 重定向回 `service`，未登录返回 401
+//! - `POST /sso/doLogin`：请求体 `{"name", "pwd", "service"?}`，校验通过后返回 SSO token 与回跳地址
+//! - `GET /sso/checkTicket?ticket=<ticket>&service=<url>`：客户端应用用票据换取登录 ID
+//! - `GET|POST /sso/signout`：统一登出当前用户，返回需要通知的客户端列表
+//!
+//! `/sso/auth` 与 `/sso/signout` 依赖 `SaTokenLayer` 识别当前用户，路由需放在该层之内。
+
+use std::sync::Arc;
+use axum::{
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::json;
+use sa_token_core::{
+    SaTokenError, SsoManager, SSO_AUTH_PATH, SSO_CHECK_TICKET_PATH, SSO_DO_LOGIN_PATH, SSO_SIGNOUT_PATH,
+};
+use crate::extractor::LoginIdExtractor;
+
+/// 创建 SSO 服务端路由
+///
+/// # 示例
+/// ```rust,ignore
+/// let sso = Arc::new(
+///     SsoManager::new(SsoConfig::builder().add_allowed_origin("http://app1.com".into()).build())
+///         .with_server(Arc::new(SsoServer::new(state.manager.clone())))
+///         .with_credential_validator(Arc::new(UserTable)),
+/// );
+///
+/// let app = Router::new()
+///     .merge(sso_router(sso))
+///     .layer(SaTokenLayer::new(state.clone()));
+/// ```
+pub fn sso_router<S>(sso: Arc<SsoManager>) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route(SSO_AUTH_PATH, get(auth))
+        .route(SSO_DO_LOGIN_PATH, post(do_login))
+        .route(SSO_CHECK_TICKET_PATH, get(check_ticket))
+        .route(SSO_SIGNOUT_PATH, get(signout).post(signout))
+        .with_state(sso)
+}
+
+#[derive(Deserialize)]
+struct AuthQuery {
+    service: String,
+}
+
+#[derive(Deserialize)]
+struct LoginBody {
+    name: String,
+    pwd: String,
+    service: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TicketQuery {
+    ticket: String,
+    service: String,
+}
+
+async fn auth(
+    State(sso): State<Arc<SsoManager>>,
+    LoginIdExtractor(login_id): LoginIdExtractor,
+    Query(query): Query<AuthQuery>,
+) -> Response {
+    match sso.auth(&login_id, &query.service).await {
+        Ok(redirect) => (StatusCode::FOUND, [(header::LOCATION, redirect)]).into_response(),
+        Err(e) => error_response(e),
+    }
+}
+
+async fn do_login(State(sso): State<Arc<SsoManager>>, Json(body): Json<LoginBody>) -> Response {
+    match sso.do_login(&body.name, &body.pwd, body.service.as_deref()).await {
+        Ok(result) => success(json!(result)),
+        Err(e) => error_response(e),
+    }
+}
+
+async fn check_ticket(State(sso): State<Arc<SsoManager>>, Query(query): Query<TicketQuery>) -> Response {
+    match sso.check_ticket(&query.ticket, &query.service).await {
+        Ok(login_id) => success(json!({ "login_id": login_id })),
+        Err(e) => error_response(e),
+    }
+}
+
+async fn signout(State(sso): State<Arc<SsoManager>>, LoginIdExtractor(login_id): LoginIdExtractor) -> Response {
+    match sso.signout(&login_id).await {
+        Ok(clients) => success(json!({ "clients": clients })),
+        Err(e) => error_response(e),
+    }
+}
+
+fn success(data: serde_json::Value) -> Response {
+    Json(json!({
+        "code": 200,
+        "message": "success",
+        "data": data
+    })).into_response()
+}
+
+fn error_response(error: SaTokenError) -> Response {
+    let status = match &error {
+        e if e.is_auth_error() => StatusCode::UNAUTHORIZED,
+        SaTokenError::InvalidTicket | SaTokenError::TicketExpired | SaTokenError::ServiceMismatch => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (
+        status,
+        Json(json!({
+            "code": status.as_u16(),
+            "message": error.to_string()
+        }))
+    ).into_response()
+}
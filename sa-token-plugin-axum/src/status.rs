@@ -0,0 +1,85 @@
+// Author: 金书记
+//
+//! Token 状态长轮询接口
+//!
+//! 前端长轮询 `/auth/status/watch`，在 token 被踢下线、封禁、顶替或即将过期时立即得到响应，
+//! 超时无变化时返回 `active`，前端随即发起下一次轮询。
+//!
+//! 查询参数：`timeout`（秒，默认 30，最大 60）、`expiring_within`（秒，默认 300）。
+//! token 按 `SaTokenLayer` 相同的规则提取，不要求 token 仍然有效。
+
+use std::time::Duration;
+use axum::{
+    extract::{Query, Request, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::json;
+use sa_token_core::{SaTokenError, StatusWatchOptions, TokenValue};
+use crate::{layer::extract_token_from_request, SaTokenState};
+use crate::middleware::rejection_response;
+
+/// 状态监听接口路径
+pub const STATUS_WATCH_PATH: &str = "/auth/status/watch";
+
+/// 单次长轮询的最长等待时间（秒）
+const MAX_WATCH_TIMEOUT: u64 = 60;
+
+#[derive(Debug, Default, Deserialize)]
+struct WatchQuery {
+    timeout: Option<u64>,
+    expiring_within: Option<i64>,
+}
+
+/// 创建状态监听路由，合并到应用路由中即可
+///
+/// # 示例
+/// ```rust,ignore
+/// let app = Router::new()
+///     .route("/api/user", get(user_info))
+///     .merge(status_watch_router(state.clone()))
+///     .layer(SaTokenLayer::new(state.clone()));
+/// ```
+///
+/// 响应示例：`{"status": "kicked", "login_id": "10001", "expires_in": null}`
+pub fn status_watch_router<S>(state: SaTokenState) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route(STATUS_WATCH_PATH, get(watch_status))
+        .with_state(state)
+}
+
+async fn watch_status(
+    State(state): State<SaTokenState>,
+    Query(query): Query<WatchQuery>,
+    request: Request,
+) -> Response {
+    let Some((token, _)) = extract_token_from_request(&request, &state) else {
+        return rejection_response(&SaTokenError::NotLogin);
+    };
+
+    let defaults = StatusWatchOptions::default();
+    let options = StatusWatchOptions {
+        timeout: query.timeout
+            .map(|seconds| Duration::from_secs(seconds.min(MAX_WATCH_TIMEOUT)))
+            .unwrap_or(defaults.timeout),
+        expiring_within: query.expiring_within.unwrap_or(defaults.expiring_within),
+    };
+
+    let report = state.manager.watch_token_status(&TokenValue::new(token), options).await;
+    match report.and_then(|report| state.manager.response_json(&report).map_err(Into::into)) {
+        Ok(body) => Json(body).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({
+                "code": 500,
+                "message": e.to_string()
+            }))
+        ).into_response(),
+    }
+}
@@ -0,0 +1,216 @@
+// Author: 金书记
+//
+//! WebSocket 连接驱动（需要 `ws` feature）
+//!
+//! 把 axum 原生 `WebSocket` 接入 [`WsConnectionRegistry`]：注册连接后，
+//! `WebSocketPusher` 推送的消息以 JSON 文本帧发出，踢人、登出、顶号、封禁以对应关闭码的
+//! 关闭帧结束连接（踢人通知先送达，再发送 4001 关闭帧）；客户端的文本消息交给回调处理，
+//! socket 断开后自动注销连接并标记下线。
+//!
+//! ```rust,ignore
+//! let registry = Arc::new(WsConnectionRegistry::new().with_online_manager(online.clone()));
+//! online.register_pusher(Arc::new(WebSocketPusher::new(registry.clone()))).await;
+//!
+//! async fn ws_handler(ws: WebSocketUpgrade, State(state): State<AppState>,
+//!                     headers: HeaderMap, Query(query): Query<HashMap<String, String>>) -> Response {
+//!     let auth = match state.ws_auth.authenticate(&header_map(&headers), &query).await {
+//!         Ok(auth) => auth,
+//!         Err(e) => return (StatusCode::UNAUTHORIZED, e.to_string()).into_response(),
+//!     };
+//!     ws.on_upgrade(move |socket| serve_socket(socket, state.registry, auth, "web", |_, _| async {}))
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use axum::extract::ws::{CloseFrame, Message, WebSocket};
+use axum::http::HeaderMap;
+use sa_token_core::{WsAuthInfo, WsConnection, WsConnectionRegistry, WsOutbound};
+
+/// 把请求头转换为 `WsAuthManager::authenticate` 需要的形式
+pub fn header_map(headers: &HeaderMap) -> HashMap<String, String> {
+    headers.iter()
+        .filter_map(|(name, value)| value.to_str().ok().map(|v| (name.as_str().to_string(), v.to_string())))
+        .collect()
+}
+
+/// 注册连接并驱动 socket，直到任一方关闭
+///
+/// - 注册表 → socket：`WsOutbound::Message` 序列化为 JSON 文本帧，`WsOutbound::Close` 发送关闭帧后结束
+/// - socket → 应用：文本消息刷新在线活跃时间后交给 `on_text`
+pub async fn serve_socket<F, Fut>(
+    mut socket: WebSocket,
+    registry: Arc<WsConnectionRegistry>,
+    auth: WsAuthInfo,
+    device: impl Into<String>,
+    mut on_text: F,
+) where
+    F: FnMut(WsConnection, String) -> Fut + Send,
+    Fut: Future<Output = ()> + Send,
+{
+    let (connection, mut outbound) = registry.register(&auth, device).await;
+
+    loop {
+        tokio::select! {
+            item = outbound.recv() => match item {
+                Some(WsOutbound::Message(message)) => {
+                    let text = serde_json::to_string(&message).unwrap_or_default();
+                    if socket.send(Message::Text(text.into())).await.is_err() {
+                        break;
+                    }
+                }
+                Some(WsOutbound::Close(reason)) => {
+                    let frame = CloseFrame { code: reason.code, reason: reason.reason.into() };
+                    let _ = socket.send(Message::Close(Some(frame))).await;
+                    break;
+                }
+                None => break,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Text(text))) => {
+                    registry.record_activity(&connection).await;
+                    on_text(connection.clone(), text.to_string()).await;
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+
+    registry.unregister(&connection.session_id).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use axum::extract::{Query, State, WebSocketUpgrade};
+    use axum::http::StatusCode;
+    use axum::response::{IntoResponse, Response};
+    use axum::{routing::get, Router};
+    use futures_util::StreamExt;
+    use sa_token_core::{OnlineManager, SaTokenManager, TokenValue, WebSocketPusher, WsAuthManager, WsCloseReason};
+    use sa_token_test_utils::state::state;
+    use tokio::net::TcpStream;
+    use tokio_tungstenite::{tungstenite, MaybeTlsStream, WebSocketStream};
+
+    type Client = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+    #[derive(Clone)]
+    struct AppState {
+        ws_auth: Arc<WsAuthManager>,
+        registry: Arc<WsConnectionRegistry>,
+    }
+
+    async fn ws_handler(ws: WebSocketUpgrade, State(state): State<AppState>,
+                        headers: HeaderMap, Query(query): Query<HashMap<String, String>>) -> Response {
+        let auth = match state.ws_auth.authenticate(&header_map(&headers), &query).await {
+            Ok(auth) => auth,
+            Err(e) => return (StatusCode::UNAUTHORIZED, e.to_string()).into_response(),
+        };
+        ws.on_upgrade(move |socket| serve_socket(socket, state.registry, auth, "web", |_, _| async {}))
+    }
+
+    struct Server {
+        manager: Arc<SaTokenManager>,
+        online: Arc<OnlineManager>,
+        registry: Arc<WsConnectionRegistry>,
+        url: String,
+    }
+
+    impl Server {
+        /// 启动挂载了 `WebSocketPusher` 的 WebSocket 服务 | Start a WebSocket server with a `WebSocketPusher` attached
+        async fn start() -> Self {
+            let manager = state().manager;
+            let online = Arc::new(OnlineManager::new());
+            let registry = Arc::new(WsConnectionRegistry::new().with_online_manager(online.clone()));
+            online.register_pusher(Arc::new(WebSocketPusher::new(registry.clone()))).await;
+            manager.event_bus().register(registry.clone());
+
+            let state = AppState { ws_auth: Arc::new(WsAuthManager::new(manager.clone())), registry: registry.clone() };
+            let app = Router::new().route("/ws", get(ws_handler)).with_state(state);
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("ws://{}/ws", listener.local_addr().unwrap());
+            tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+            Self { manager, online, registry, url }
+        }
+
+        /// 连接并等待服务端完成注册 | Connect and wait until the server has registered the socket
+        async fn connect(&self, token: &TokenValue) -> Client {
+            let expected = self.registry.connection_count().await + 1;
+            let (client, _) = tokio_tungstenite::connect_async(format!("{}?token={}", self.url, token)).await.unwrap();
+            self.wait_for_connections(expected).await;
+            client
+        }
+
+        async fn wait_for_connections(&self, expected: usize) {
+            tokio::time::timeout(Duration::from_secs(5), async {
+                while self.registry.connection_count().await != expected {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+            }).await.unwrap();
+        }
+    }
+
+    async fn next_frame(client: &mut Client) -> tungstenite::Message {
+        tokio::time::timeout(Duration::from_secs(5), client.next()).await.unwrap().unwrap().unwrap()
+    }
+
+    /// 读取一条推送消息，返回 (类型, 内容) | Read a push message as (type, content)
+    async fn next_push(client: &mut Client) -> (String, String) {
+        let tungstenite::Message::Text(text) = next_frame(client).await else {
+            panic!("expected a text frame");
+        };
+        let message: serde_json::Value = serde_json::from_str(&text).unwrap();
+        (message["message_type"].as_str().unwrap().to_string(), message["content"].as_str().unwrap().to_string())
+    }
+
+    /// 读取关闭帧的关闭码与原因 | Read the close frame's code and reason
+    async fn next_close(client: &mut Client) -> (u16, String) {
+        let tungstenite::Message::Close(Some(frame)) = next_frame(client).await else {
+            panic!("expected a close frame");
+        };
+        (frame.code.into(), frame.reason.to_string())
+    }
+
+    #[tokio::test]
+    async fn test_pusher_delivers_user_messages_and_broadcasts() {
+        let server = Server::start().await;
+        let token_1 = server.manager.login("user_1").await.unwrap();
+        let token_2 = server.manager.login("user_2").await.unwrap();
+        let mut client_1 = server.connect(&token_1).await;
+        let mut client_2 = server.connect(&token_2).await;
+
+        server.online.push_to_user("user_1", "hello".to_string()).await.unwrap();
+        assert_eq!(next_push(&mut client_1).await, ("text".to_string(), "hello".to_string()));
+
+        server.online.broadcast("maintenance".to_string()).await.unwrap();
+        assert_eq!(next_push(&mut client_1).await, ("text".to_string(), "maintenance".to_string()));
+        assert_eq!(next_push(&mut client_2).await, ("text".to_string(), "maintenance".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_kick_out_notification_is_sent_before_the_socket_closes() {
+        let server = Server::start().await;
+        let token = server.manager.login("user_1").await.unwrap();
+        let mut client = server.connect(&token).await;
+
+        server.online.kick_out_notify("user_1", "Signed in elsewhere".to_string()).await.unwrap();
+        assert_eq!(next_push(&mut client).await, ("kick_out".to_string(), "Signed in elsewhere".to_string()));
+        assert_eq!(next_close(&mut client).await, (WsCloseReason::KICKED_OUT, "Signed in elsewhere".to_string()));
+        server.wait_for_connections(0).await;
+    }
+
+    #[tokio::test]
+    async fn test_logout_closes_the_socket() {
+        let server = Server::start().await;
+        let token = server.manager.login("user_1").await.unwrap();
+        let mut client = server.connect(&token).await;
+
+        server.manager.logout(&token).await.unwrap();
+        assert_eq!(next_close(&mut client).await.0, WsCloseReason::LOGGED_OUT);
+        server.wait_for_connections(0).await;
+        assert!(!server.online.is_online("user_1").await);
+    }
+}
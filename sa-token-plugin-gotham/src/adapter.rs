@@ -1,3 +1,4 @@
+use std::net::SocketAddr;
 use gotham::hyper::{HeaderMap, Uri};
 use sa_token_adapter::context::{SaRequest, SaResponse, CookieOptions};
 use serde::Serialize;
@@ -7,13 +8,21 @@ use serde::Serialize;
 pub struct GothamRequestAdapter<'a> {
     headers: &'a HeaderMap,
     uri: &'a Uri,
+    remote_addr: Option<SocketAddr>,
 }
 
 impl<'a> GothamRequestAdapter<'a> {
     /// 中文: 通过 HeaderMap 和 Uri 构造适配器
     /// English: Constructs adapter from HeaderMap and Uri
     pub fn new(headers: &'a HeaderMap, uri: &'a Uri) -> Self {
-        Self { headers, uri }
+        Self { headers, uri, remote_addr: None }
+    }
+
+    /// 中文: 设置连接对端地址（`gotham::state::client_addr`）
+    /// English: Sets the peer address (`gotham::state::client_addr`)
+    pub fn with_remote_addr(mut self, remote_addr: Option<SocketAddr>) -> Self {
+        self.remote_addr = remote_addr;
+        self
     }
 }
 
@@ -72,10 +81,10 @@ impl<'a> SaRequest for GothamRequestAdapter<'a> {
         "GET".to_string()
     }
 
-    /// 中文: Gotham 状态中默认无法获取客户端 IP
-    /// English: Client IP not available by default in Gotham state
-    fn get_client_ip(&self) -> Option<String> {
-        None
+    /// 中文: 连接对端 IP，需通过 `with_remote_addr` 传入
+    /// English: Peer IP, passed in through `with_remote_addr`
+    fn remote_addr(&self) -> Option<String> {
+        self.remote_addr.map(|addr| addr.ip().to_string())
     }
 }
 
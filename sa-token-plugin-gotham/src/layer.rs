@@ -1,10 +1,11 @@
 use gotham::state::State;
 use gotham::middleware::Middleware;
 use gotham::handler::HandlerFuture;
+use gotham::hyper::header::{HeaderMap, HeaderValue, SET_COOKIE};
 use std::pin::Pin;
-use sa_token_core::{token::TokenValue, SaTokenContext};
+use sa_token_core::{token::TokenValue, SaTokenContext, SaTokenManager, ACCEPT_LANGUAGE_HEADER, TIMEZONE_HEADER, SAME_TOKEN_HEADER};
 use crate::state::SaTokenState;
-use std::sync::Arc;
+use crate::adapter::GothamRequestAdapter;
 
 #[derive(Clone)]
 pub struct SaTokenLayer {
@@ -24,100 +25,69 @@ impl Middleware for SaTokenLayer {
     {
         Box::pin(async move {
             let mut ctx = SaTokenContext::new();
+            let headers = state.try_borrow::<gotham::hyper::HeaderMap>();
+            let header = |name: &str| headers.and_then(|h| h.get(name)).and_then(|v| v.to_str().ok());
+            ctx.capture_locale(header(ACCEPT_LANGUAGE_HEADER), header(TIMEZONE_HEADER));
+            ctx.capture_same_token(header(SAME_TOKEN_HEADER));
+            ctx.capture_client(client_ip_from_state(&state, &self.state.manager).as_deref(), header("user-agent"));
             
             if let Some(token_str) = extract_token_from_state(&state, &self.state) {
                 tracing::debug!("Sa-Token: extracted token from request: {}", token_str);
                 let token = TokenValue::new(token_str);
                 
-                if self.state.manager.is_valid(&token).await {
-                    if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                        let login_id = token_info.login_id.clone();
-                        
-                        ctx.token = Some(token.clone());
-                        ctx.token_info = Some(Arc::new(token_info));
-                        ctx.login_id = Some(login_id.clone());
-                        
-                        state.put(crate::wrapper::TokenValueWrapper(token));
-                        state.put(crate::wrapper::LoginIdWrapper(login_id));
-                    }
+                if let Some(login_id) = self.state.manager.authenticate_context(&token, &mut ctx).await {
+                    state.put(crate::wrapper::TokenValueWrapper(token));
+                    state.put(crate::wrapper::LoginIdWrapper(login_id));
                 }
             }
             
-            SaTokenContext::set_current(ctx);
-            let result = chain(state).await;
-            SaTokenContext::clear();
-            result
+            // 开发模式免登录 | Dev bypass
+            if let Some(login_id) = self.state.manager.dev_bypass_login_id().filter(|_| ctx.login_id.is_none()) {
+                state.put(crate::wrapper::LoginIdWrapper(login_id.to_string()));
+                ctx.login_id = Some(login_id.to_string());
+            }
+            
+            let response_cookie = ctx.clone();
+            let result = SaTokenContext::scope(ctx, chain(state)).await;
+            
+            // 处理期间登录/登出产生的 token Cookie（`is_write_cookie`）
+            result.map(|(state, mut response)| {
+                append_response_cookie(&response_cookie, response.headers_mut());
+                (state, response)
+            })
         })
     }
 }
 
 /// 从 Gotham State 中提取 Token
 /// 
-/// 按优先级顺序查找 Token：
-/// 1. HTTP Header - `<token_name>: <token>` 或 `<token_name>: Bearer <token>`
-/// 2. Authorization Header - `Authorization: Bearer <token>`
-/// 3. Cookie - `<token_name>=<token>`
-/// 4. Query Parameter - `?<token_name>=<token>`
+/// 先调用自定义提取器（`SaTokenConfig::builder().token_extractor()`），再按
+/// `SaTokenConfig::token_sources` 的顺序查找，默认 Header → Authorization → Cookie → Query
 fn extract_token_from_state(state: &State, token_state: &SaTokenState) -> Option<String> {
     use gotham::hyper::{HeaderMap, Uri};
-    use sa_token_adapter::utils::{parse_cookies, parse_query_string};
-    
-    // 从配置中获取 token_name
-    let token_name = &token_state.manager.config.token_name;
-    
-    // 1. 从 Header 中获取
-    if let Some(headers) = state.try_borrow::<HeaderMap>() {
-        // 1.1 尝试从指定名称的 header 获取
-        if let Some(header_value) = headers.get(token_name) {
-            if let Ok(value_str) = header_value.to_str() {
-                return Some(extract_bearer_token(value_str));
-            }
-        }
-        
-        // 1.2 尝试从 Authorization header 获取
-        if let Some(auth_header) = headers.get("authorization") {
-            if let Ok(auth_str) = auth_header.to_str() {
-                return Some(extract_bearer_token(auth_str));
-            }
-        }
-        
-        // 2. 从 Cookie 中获取
-        if let Some(cookie_header) = headers.get("cookie") {
-            if let Ok(cookie_str) = cookie_header.to_str() {
-                let cookies = parse_cookies(cookie_str);
-                if let Some(token) = cookies.get(token_name) {
-                    return Some(token.clone());
-                }
-            }
-        }
-    }
-    
-    // 3. 从 Query 参数中获取
-    if let Some(uri) = state.try_borrow::<Uri>() {
-        if let Some(query) = uri.query() {
-            let params = parse_query_string(query);
-            if let Some(token) = params.get(token_name) {
-                return Some(token.clone());
-            }
-        }
-    }
-    
-    None
+
+    state.try_borrow::<HeaderMap>()
+        .zip(state.try_borrow::<Uri>())
+        .and_then(|(headers, uri)| token_state.manager.extract_token(&GothamRequestAdapter::new(headers, uri)))
+        .map(|(token, _)| token)
 }
 
-/// 提取 Bearer Token
-/// 
-/// 支持两种格式：
-/// - `Bearer <token>` - 标准 Bearer Token 格式
-/// - `<token>` - 直接的 Token 字符串
-fn extract_bearer_token(header_value: &str) -> String {
-    const BEARER_PREFIX: &str = "Bearer ";
-    
-    if header_value.starts_with(BEARER_PREFIX) {
-        // 去除 "Bearer " 前缀
-        header_value[BEARER_PREFIX.len()..].trim().to_string()
-    } else {
-        // 直接返回 token
-        header_value.trim().to_string()
+/// 请求来源 IP，按管理器配置的受信任代理解析代理头
+pub(crate) fn client_ip_from_state(state: &State, manager: &SaTokenManager) -> Option<String> {
+    use gotham::hyper::Uri;
+
+    state.try_borrow::<HeaderMap>()
+        .zip(state.try_borrow::<Uri>())
+        .and_then(|(headers, uri)| manager.client_ip(
+            &GothamRequestAdapter::new(headers, uri).with_remote_addr(gotham::state::client_addr(state)),
+        ))
+}
+
+/// 把请求处理期间记录的 token Cookie 追加到响应头，不覆盖已有的 `Set-Cookie`
+pub(crate) fn append_response_cookie(ctx: &SaTokenContext, headers: &mut HeaderMap) {
+    if let Some(cookie) = ctx.take_response_cookie()
+        && let Ok(value) = HeaderValue::from_str(&cookie.header_value())
+    {
+        headers.append(SET_COOKIE, value);
     }
 }
@@ -7,6 +7,7 @@
 //! - `SaCheckLoginMiddleware`：检查登录中间件，未登录时返回401错误
 //! - `SaCheckPermissionMiddleware`：检查权限中间件，无权限时返回403错误
 //! - `SaCheckRoleMiddleware`：检查角色中间件，无角色时返回403错误
+//! - `SaSameTokenMiddleware`：检查 Same-Token 中间件，缺失或无效时返回401错误
 //! - `AuthMiddleware`：已废弃，建议使用上述中间件
 
 use gotham::state::{State, StateData};
@@ -15,15 +16,21 @@ use gotham::handler::HandlerFuture;
 use gotham::hyper::{Response, StatusCode};
 use gotham::hyper::body::Body;
 use std::pin::Pin;
-use std::sync::Arc;
-use serde_json::json;
 use sa_token_core::{
-    error::messages, 
+    AuthzKind,
     token::TokenValue, 
-    SaTokenContext
+    SaTokenContext,
+    ErrorResponse,
+    SaTokenError,
+    SameTokenManager,
+    StpUtil,
+    ACCEPT_LANGUAGE_HEADER,
+    TIMEZONE_HEADER,
+    SAME_TOKEN_HEADER
 };
-use sa_token_adapter::utils::{parse_cookies, parse_query_string, extract_bearer_token};
 use crate::{SaTokenState, wrapper::{TokenValueWrapper, LoginIdWrapper}};
+use crate::adapter::GothamRequestAdapter;
+use crate::layer::{append_response_cookie, client_ip_from_state};
 
 /// 中文 | English
 /// 登录 ID 状态数据 | Login ID state data
@@ -53,40 +60,39 @@ impl Middleware for SaTokenMiddleware {
         
         Box::pin(async move {
             let mut ctx = SaTokenContext::new();
+            let headers = state.try_borrow::<gotham::hyper::HeaderMap>();
+            let header = |name: &str| headers.and_then(|h| h.get(name)).and_then(|v| v.to_str().ok());
+            ctx.capture_locale(header(ACCEPT_LANGUAGE_HEADER), header(TIMEZONE_HEADER));
+            ctx.capture_same_token(header(SAME_TOKEN_HEADER));
+            ctx.capture_client(client_ip_from_state(&state, &token_state.manager).as_deref(), header("user-agent"));
             
             // 提取 token
             if let Some(token_str) = extract_token_from_state(&state, &token_state) {
                 tracing::debug!("Sa-Token: extracted token from request: {}", token_str);
                 let token = TokenValue::new(token_str);
                 
-                // 验证 token
-                if token_state.manager.is_valid(&token).await {
-                    // 存储 token 到 State
-                    state.put(TokenValueWrapper(token.clone()));
-                    
-                    // 获取并存储 login_id
-                    if let Ok(token_info) = token_state.manager.get_token_info(&token).await {
-                        let login_id = token_info.login_id.clone();
-                        state.put(LoginIdWrapper(login_id.clone()));
-                        
-                        // 设置上下文
-                        ctx.token = Some(token.clone());
-                        ctx.token_info = Some(Arc::new(token_info));
-                        ctx.login_id = Some(login_id);
-                    }
+                // 验证 token，存储 token 与 login_id 到 State
+                if let Some(login_id) = token_state.manager.authenticate_context(&token, &mut ctx).await {
+                    state.put(TokenValueWrapper(token));
+                    state.put(LoginIdWrapper(login_id));
                 }
             }
             
-            // 设置当前上下文
-            SaTokenContext::set_current(ctx);
-            
-            // 继续处理请求
-            let result = chain(state).await;
+            // 开发模式免登录 | Dev bypass
+            if let Some(login_id) = token_state.manager.dev_bypass_login_id().filter(|_| ctx.login_id.is_none()) {
+                state.put(LoginIdWrapper(login_id.to_string()));
+                ctx.login_id = Some(login_id.to_string());
+            }
             
-            // 清除上下文
-            SaTokenContext::clear();
+            // 在当前请求的上下文中继续处理
+            let response_cookie = ctx.clone();
+            let result = SaTokenContext::scope(ctx, chain(state)).await;
             
-            result
+            // 处理期间登录/登出产生的 token Cookie（`is_write_cookie`）
+            result.map(|(state, mut response)| {
+                append_response_cookie(&response_cookie, response.headers_mut());
+                (state, response)
+            })
         })
     }
 }
@@ -149,46 +155,35 @@ impl Middleware for SaCheckLoginMiddleware {
         
         Box::pin(async move {
             let mut ctx = SaTokenContext::new();
+            let headers = state.try_borrow::<gotham::hyper::HeaderMap>();
+            let header = |name: &str| headers.and_then(|h| h.get(name)).and_then(|v| v.to_str().ok());
+            ctx.capture_locale(header(ACCEPT_LANGUAGE_HEADER), header(TIMEZONE_HEADER));
+            ctx.capture_same_token(header(SAME_TOKEN_HEADER));
+            ctx.capture_client(client_ip_from_state(&state, &token_state.manager).as_deref(), header("user-agent"));
             
             // 提取 token
             if let Some(token_str) = extract_token_from_state(&state, &token_state) {
                 tracing::debug!("Sa-Token(login-check): extracted token from request: {}", token_str);
                 let token = TokenValue::new(token_str);
                 
-                // 验证 token
-                if token_state.manager.is_valid(&token).await {
-                    // 存储 token 和 login_id
-                    state.put(TokenValueWrapper(token.clone()));
+                // 验证 token，存储 token 和 login_id
+                if let Some(login_id) = token_state.manager.authenticate_context(&token, &mut ctx).await {
+                    state.put(TokenValueWrapper(token));
+                    state.put(LoginIdWrapper(login_id));
                     
-                    if let Ok(token_info) = token_state.manager.get_token_info(&token).await {
-                        let login_id = token_info.login_id.clone();
-                        state.put(LoginIdWrapper(login_id.clone()));
-                        
-                        // 设置上下文
-                        ctx.token = Some(token.clone());
-                        ctx.token_info = Some(Arc::new(token_info));
-                        ctx.login_id = Some(login_id);
-                        
-                        SaTokenContext::set_current(ctx);
-                        let result = chain(state).await;
-                        SaTokenContext::clear();
-                        return result;
-                    }
+                    return SaTokenContext::scope(ctx, chain(state)).await;
                 }
             }
             
-            // 未登录，返回401错误
-            let error_json = json!({
-                "code": 401,
-                "message": messages::AUTH_ERROR
-            });
-            
-            let response = Response::builder()
-                .status(StatusCode::UNAUTHORIZED)
-                .header("Content-Type", "application/json")
-                .body(Body::from(error_json.to_string()))
-                .expect("Unable to create response");
+            // 开发模式免登录 | Dev bypass
+            if let Some(login_id) = token_state.manager.dev_bypass_login_id() {
+                state.put(LoginIdWrapper(login_id.to_string()));
+                ctx.login_id = Some(login_id.to_string());
+                return SaTokenContext::scope(ctx, chain(state)).await;
+            }
             
+            // 未登录（或为受限 token），返回401错误
+            let response = rejection_response(token_state.manager.render_error(&ctx.login_error()));
             Ok((state, response))
         })
     }
@@ -222,6 +217,11 @@ impl Middleware for SaCheckPermissionMiddleware {
         
         Box::pin(async move {
             let mut ctx = SaTokenContext::new();
+            let headers = state.try_borrow::<gotham::hyper::HeaderMap>();
+            let header = |name: &str| headers.and_then(|h| h.get(name)).and_then(|v| v.to_str().ok());
+            ctx.capture_locale(header(ACCEPT_LANGUAGE_HEADER), header(TIMEZONE_HEADER));
+            ctx.capture_same_token(header(SAME_TOKEN_HEADER));
+            ctx.capture_client(client_ip_from_state(&state, &token_state.manager).as_deref(), header("user-agent"));
             
             // 提取 token
             if let Some(token_str) = extract_token_from_state(&state, &token_state) {
@@ -229,42 +229,29 @@ impl Middleware for SaCheckPermissionMiddleware {
                 let token = TokenValue::new(token_str);
                 
                 // 验证 token
-                if token_state.manager.is_valid(&token).await {
-                    if let Ok(token_info) = token_state.manager.get_token_info(&token).await {
-                        let login_id = token_info.login_id.clone();
+                if let Some(login_id) = token_state.manager.authenticate_context(&token, &mut ctx).await {
+                    // 检查权限
+                    if sa_token_core::StpUtil::has_permission(&login_id, &permission).await {
+                        // 存储信息到 State
+                        state.put(TokenValueWrapper(token));
+                        state.put(LoginIdWrapper(login_id));
                         
-                        // 检查权限
-                        if sa_token_core::StpUtil::has_permission(&login_id, &permission).await {
-                            // 存储信息到 State
-                            state.put(TokenValueWrapper(token.clone()));
-                            state.put(LoginIdWrapper(login_id.clone()));
-                            
-                            // 设置上下文
-                            ctx.token = Some(token.clone());
-                            ctx.token_info = Some(Arc::new(token_info));
-                            ctx.login_id = Some(login_id);
-                            
-                            SaTokenContext::set_current(ctx);
-                            let result = chain(state).await;
-                            SaTokenContext::clear();
-                            return result;
-                        }
+                        return SaTokenContext::scope(ctx, chain(state)).await;
                     }
+                    sa_token_core::StpUtil::record_denial(AuthzKind::Permission, &permission);
                 }
             }
             
-            // 无权限或未登录，返回403错误
-            let error_json = json!({
-                "code": 403,
-                "message": messages::PERMISSION_REQUIRED
-            });
-            
-            let response = Response::builder()
-                .status(StatusCode::FORBIDDEN)
-                .header("Content-Type", "application/json")
-                .body(Body::from(error_json.to_string()))
-                .expect("Unable to create response");
+            // 开发模式免登录 | Dev bypass
+            if let Some(login_id) = token_state.manager.dev_bypass_login_id() {
+                state.put(LoginIdWrapper(login_id.to_string()));
+                ctx.login_id = Some(login_id.to_string());
+                return SaTokenContext::scope(ctx, chain(state)).await;
+            }
             
+            // 无权限或未登录，返回403错误
+            let err = SaTokenError::PermissionDeniedDetail(permission);
+            let response = rejection_response(token_state.manager.render_error(&err));
             Ok((state, response))
         })
     }
@@ -298,6 +285,11 @@ impl Middleware for SaCheckRoleMiddleware {
         
         Box::pin(async move {
             let mut ctx = SaTokenContext::new();
+            let headers = state.try_borrow::<gotham::hyper::HeaderMap>();
+            let header = |name: &str| headers.and_then(|h| h.get(name)).and_then(|v| v.to_str().ok());
+            ctx.capture_locale(header(ACCEPT_LANGUAGE_HEADER), header(TIMEZONE_HEADER));
+            ctx.capture_same_token(header(SAME_TOKEN_HEADER));
+            ctx.capture_client(client_ip_from_state(&state, &token_state.manager).as_deref(), header("user-agent"));
             
             // 提取 token
             if let Some(token_str) = extract_token_from_state(&state, &token_state) {
@@ -305,95 +297,178 @@ impl Middleware for SaCheckRoleMiddleware {
                 let token = TokenValue::new(token_str);
                 
                 // 验证 token
-                if token_state.manager.is_valid(&token).await {
-                    if let Ok(token_info) = token_state.manager.get_token_info(&token).await {
-                        let login_id = token_info.login_id.clone();
+                if let Some(login_id) = token_state.manager.authenticate_context(&token, &mut ctx).await {
+                    // 检查角色
+                    if sa_token_core::StpUtil::has_role(&login_id, &role).await {
+                        // 存储信息到 State
+                        state.put(TokenValueWrapper(token));
+                        state.put(LoginIdWrapper(login_id));
                         
-                        // 检查角色
-                        if sa_token_core::StpUtil::has_role(&login_id, &role).await {
-                            // 存储信息到 State
-                            state.put(TokenValueWrapper(token.clone()));
-                            state.put(LoginIdWrapper(login_id.clone()));
-                            
-                            // 设置上下文
-                            ctx.token = Some(token.clone());
-                            ctx.token_info = Some(Arc::new(token_info));
-                            ctx.login_id = Some(login_id);
-                            
-                            SaTokenContext::set_current(ctx);
-                            let result = chain(state).await;
-                            SaTokenContext::clear();
-                            return result;
-                        }
+                        return SaTokenContext::scope(ctx, chain(state)).await;
                     }
+                    sa_token_core::StpUtil::record_denial(AuthzKind::Role, &role);
                 }
             }
             
-            // 无角色或未登录，返回403错误
-            let error_json = json!({
-                "code": 403,
-                "message": messages::ROLE_REQUIRED
-            });
-            
-            let response = Response::builder()
-                .status(StatusCode::FORBIDDEN)
-                .header("Content-Type", "application/json")
-                .body(Body::from(error_json.to_string()))
-                .expect("Unable to create response");
+            // 开发模式免登录 | Dev bypass
+            if let Some(login_id) = token_state.manager.dev_bypass_login_id() {
+                state.put(LoginIdWrapper(login_id.to_string()));
+                ctx.login_id = Some(login_id.to_string());
+                return SaTokenContext::scope(ctx, chain(state)).await;
+            }
             
+            // 无角色或未登录，返回403错误
+            let err = SaTokenError::RoleDenied(role);
+            let response = rejection_response(token_state.manager.render_error(&err));
             Ok((state, response))
         })
     }
 }
 
+/// sa-token Same-Token 检查中间件 - 只允许内部服务访问
+/// 
+/// 此中间件会检查请求是否携带有效的 `sa-same-token` 请求头，缺失或无效时返回401错误
+/// 
+/// # 示例
+/// ```rust,ignore
+/// let (chain, pipelines) = single_pipeline(
+///     new_pipeline().add(SaSameTokenMiddleware::new(manager.same_token_manager())).build()
+/// );
+/// ```
+#[derive(Clone)]
+pub struct SaSameTokenMiddleware {
+    same: SameTokenManager,
+}
+
+impl SaSameTokenMiddleware {
+    pub fn new(same: SameTokenManager) -> Self {
+        Self { same }
+    }
+}
+
+impl Middleware for SaSameTokenMiddleware {
+    fn call<Chain>(self, state: State, chain: Chain) -> Pin<Box<HandlerFuture>>
+    where
+        Chain: FnOnce(State) -> Pin<Box<HandlerFuture>> + Send + 'static,
+    {
+        Box::pin(async move {
+            let token = state.try_borrow::<gotham::hyper::HeaderMap>()
+                .and_then(|h| h.get(SAME_TOKEN_HEADER))
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string);
+            
+            if let Err(err) = self.same.check_token(token.as_deref()).await {
+                let response = rejection_response(StpUtil::render_error(&err));
+                return Ok((state, response));
+            }
+            chain(state).await
+        })
+    }
+}
+
+/// 把 `ErrorRenderer` 渲染的拒绝响应转换为 Gotham 响应
+fn rejection_response(rendered: ErrorResponse) -> Response<Body> {
+    let mut builder = Response::builder()
+        .status(StatusCode::from_u16(rendered.status).unwrap_or(StatusCode::FORBIDDEN))
+        .header("Content-Type", rendered.content_type);
+    for (name, value) in rendered.headers {
+        builder = builder.header(name, value);
+    }
+    builder.body(Body::from(rendered.body))
+        .expect("Unable to create response")
+}
+
 /// 从 State 中提取 token
 /// 
-/// 参考 Actix-web 实现，支持从 Header、Cookie、Query 参数中提取
+/// 先调用自定义提取器，再按 `SaTokenConfig::token_sources` 的顺序查找
 fn extract_token_from_state(state: &State, token_state: &SaTokenState) -> Option<String> {
     use gotham::hyper::{HeaderMap, Uri};
-    
-    let token_name = &token_state.manager.config.token_name;
-    
-    // 1. 优先从 Header 中获取
-    if let Some(headers) = state.try_borrow::<HeaderMap>() {
-        if let Some(header_value) = headers.get(token_name) {
-            if let Ok(value_str) = header_value.to_str() {
-                if let Some(token) = extract_bearer_token(value_str) {
-                    return Some(token);
-                }
-            }
-        }
-        
-        // 检查 Authorization header
-        if let Some(auth_header) = headers.get("authorization") {
-            if let Ok(auth_str) = auth_header.to_str() {
-                if let Some(token) = extract_bearer_token(auth_str) {
-                    return Some(token);
-                }
-            }
-        }
-        
-        // 2. 从 Cookie 中获取
-        if let Some(cookie_header) = headers.get("cookie") {
-            if let Ok(cookie_str) = cookie_header.to_str() {
-                let cookies = parse_cookies(cookie_str);
-                if let Some(token) = cookies.get(token_name) {
-                    return Some(token.clone());
-                }
-            }
-        }
+
+    state.try_borrow::<HeaderMap>()
+        .zip(state.try_borrow::<Uri>())
+        .and_then(|(headers, uri)| token_state.manager.extract_token(&GothamRequestAdapter::new(headers, uri)))
+        .map(|(token, _)| token)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gotham::hyper::{body, Request};
+    use gotham::state::FromState;
+    use sa_token_core::{SaTokenConfig, TokenInfo, PASSWORD_CHANGE_SCOPE};
+    use sa_token_test_utils::state::{state, state_with};
+
+    /// 经中间件处理请求，处理函数返回 State 中的 login_id | Run a request through the middleware; the handler echoes the login ID in State
+    async fn call<M: Middleware>(middleware: M, token: &TokenValue) -> (StatusCode, String) {
+        call_with(middleware, "sa-token", token.as_str()).await
     }
-    
-    // 3. 从 Query 参数中获取
-    if let Some(uri) = state.try_borrow::<Uri>() {
-        if let Some(query) = uri.query() {
-            let params = parse_query_string(query);
-            if let Some(token) = params.get(token_name) {
-                return Some(token.clone());
-            }
+
+    /// 带指定请求头经中间件处理请求 | Run a request carrying the given header through the middleware
+    async fn call_with<M: Middleware>(middleware: M, header: &str, value: &str) -> (StatusCode, String) {
+        let request = Request::get("/profile").header(header, value).body(Body::empty()).unwrap();
+        let state = State::from_request(request, "127.0.0.1:9000".parse().unwrap());
+        let result = middleware.call(state, |state| Box::pin(async move {
+            let login_id = LoginIdWrapper::try_borrow_from(&state).map(|id| id.0.clone()).unwrap_or_else(|| "anonymous".to_string());
+            Ok((state, Response::new(Body::from(login_id))))
+        })).await;
+        let response = match result {
+            Ok((_, response)) => response,
+            Err(_) => panic!("handler failed"),
+        };
+        let status = response.status();
+        let body = body::to_bytes(response.into_body()).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_restricted_token_is_rejected_on_login_routes() {
+        let state = state();
+        let mut token_info = TokenInfo::new(TokenValue::new(""), "user_1");
+        token_info.scope = Some(vec![PASSWORD_CHANGE_SCOPE.to_string()]);
+        let restricted = state.manager.login_with_token_info(token_info).await.unwrap();
+        let token = state.manager.login("user_2").await.unwrap();
+
+        let checked = SaCheckLoginMiddleware::new(state.clone());
+        assert_eq!(call(checked.clone(), &token).await, (StatusCode::OK, "user_2".to_string()));
+        let (status, body) = call(checked, &restricted).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(body.contains(&SaTokenError::PasswordExpired.to_string()));
+
+        // 基础中间件不为受限 token 写入 login_id
+        let layer = SaTokenMiddleware::new(state.clone());
+        assert_eq!(call(layer.clone(), &token).await, (StatusCode::OK, "user_2".to_string()));
+        assert_eq!(call(layer, &restricted).await, (StatusCode::OK, "anonymous".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_replaced_and_kicked_out_tokens_are_rejected_with_reason() {
+        let config = SaTokenConfig { is_concurrent: false, ..Default::default() };
+        let state = state_with(config);
+        let replaced = state.manager.login("user_1").await.unwrap();
+        let current = state.manager.login("user_1").await.unwrap();
+        state.manager.kick_out_with_reason("user_1", "Signed in elsewhere").await.unwrap();
+
+        let (status, body) = call(SaCheckLoginMiddleware::new(state.clone()), &replaced).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(body.contains(&SaTokenError::Replaced.to_string()));
+
+        let (status, body) = call(SaCheckLoginMiddleware::new(state.clone()), &current).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(body.contains(&SaTokenError::KickedOut("Signed in elsewhere".to_string()).to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_layer_accepts_configured_schemes() {
+        let config = SaTokenConfig { token_schemes: vec!["Bearer".into(), "Token".into()], ..Default::default() };
+        let state = state_with(config);
+        let token = state.manager.login("user_1").await.unwrap();
+        let layer = SaTokenMiddleware::new(state.clone());
+
+        for value in [format!("bearer {}", token), format!("TOKEN  {}", token), format!(" Bearer\t{} ", token)] {
+            assert_eq!(call_with(layer.clone(), "Authorization", &value).await, (StatusCode::OK, "user_1".to_string()));
         }
+        let (_, body) = call_with(layer, "Authorization", &format!("Basic {}", token)).await;
+        assert_eq!(body, "anonymous");
     }
-    
-    None
 }
-
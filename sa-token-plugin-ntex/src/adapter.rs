@@ -1,5 +1,6 @@
-use ntex::web::HttpRequest;
+use ntex::web::{HttpRequest, WebRequest};
 use sa_token_adapter::context::{SaRequest, SaResponse, CookieOptions};
+use sa_token_adapter::utils;
 use serde::Serialize;
 
 /// 中文: 将 Ntex HttpRequest 封装为 SaRequest 适配器
@@ -73,9 +74,53 @@ impl<'a> SaRequest for NtexRequestAdapter<'a> {
         self.request.method().to_string()
     }
 
-    /// 中文: 提取客户端 IP
-    /// English: Extracts client IP address
-    fn get_client_ip(&self) -> Option<String> {
+    /// 中文: 提取连接对端 IP
+    /// English: Extracts the peer IP address
+    fn remote_addr(&self) -> Option<String> {
+        self.request.peer_addr()
+            .map(|addr| addr.ip().to_string())
+    }
+}
+
+/// 中文: 将 Ntex WebRequest（中间件中的请求）封装为 SaRequest 适配器
+/// English: Adapter wrapping Ntex WebRequest (the request seen by middlewares)
+pub struct NtexWebRequestAdapter<'a, Err> {
+    request: &'a WebRequest<Err>,
+}
+
+impl<'a, Err> NtexWebRequestAdapter<'a, Err> {
+    /// 中文: 创建适配器实例
+    /// English: Creates a new adapter instance
+    pub fn new(request: &'a WebRequest<Err>) -> Self {
+        Self { request }
+    }
+}
+
+impl<'a, Err> SaRequest for NtexWebRequestAdapter<'a, Err> {
+    fn get_header(&self, name: &str) -> Option<String> {
+        self.request.headers().get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string())
+    }
+
+    fn get_cookie(&self, name: &str) -> Option<String> {
+        self.get_header("cookie")
+            .and_then(|cookies| utils::parse_cookies(&cookies).get(name).cloned())
+    }
+
+    fn get_param(&self, name: &str) -> Option<String> {
+        utils::parse_query_string(self.request.query_string()).get(name).cloned()
+    }
+
+    fn get_path(&self) -> String {
+        self.request.path().to_string()
+    }
+
+    fn get_method(&self) -> String {
+        self.request.method().to_string()
+    }
+
+    fn remote_addr(&self) -> Option<String> {
         self.request.peer_addr()
             .map(|addr| addr.ip().to_string())
     }
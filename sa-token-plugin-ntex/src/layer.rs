@@ -1,8 +1,10 @@
 use ntex::service::{Service, ServiceCtx, Middleware};
 use ntex::web::{Error, ErrorRenderer, WebRequest, WebResponse};
+use ntex::http::header::{HeaderValue, SET_COOKIE};
+use ntex::http::HeaderMap;
 use crate::state::SaTokenState;
-use sa_token_core::{token::TokenValue, SaTokenContext};
-use std::sync::Arc;
+use crate::adapter::NtexWebRequestAdapter;
+use sa_token_core::{token::TokenValue, SaTokenContext, ACCEPT_LANGUAGE_HEADER, TIMEZONE_HEADER, SAME_TOKEN_HEADER};
 
 #[derive(Clone)]
 pub struct SaTokenLayer {
@@ -41,101 +43,50 @@ where
 
     async fn call(&self, req: WebRequest<Err>, ctx: ServiceCtx<'_, Self>) -> Result<Self::Response, Self::Error> {
         let mut sa_ctx = SaTokenContext::new();
+        let header = |name: &str| req.headers().get(name).and_then(|v| v.to_str().ok());
+        sa_ctx.capture_locale(header(ACCEPT_LANGUAGE_HEADER), header(TIMEZONE_HEADER));
+        sa_ctx.capture_same_token(header(SAME_TOKEN_HEADER));
+        sa_ctx.capture_client(self.state.manager.client_ip(&NtexWebRequestAdapter::new(&req)).as_deref(), header("user-agent"));
         
         if let Some(token_str) = extract_token_from_request(&req, &self.state) {
             tracing::debug!("Sa-Token: extracted token from request: {}", token_str);
             let token = TokenValue::new(token_str);
             
-            if self.state.manager.is_valid(&token).await {
-                req.extensions_mut().insert(token.clone());
-                
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                    let login_id = token_info.login_id.clone();
-                    req.extensions_mut().insert(login_id.clone());
-                    
-                    sa_ctx.token = Some(token.clone());
-                    sa_ctx.token_info = Some(Arc::new(token_info));
-                    sa_ctx.login_id = Some(login_id);
-                }
+            if let Some(login_id) = self.state.manager.authenticate_context(&token, &mut sa_ctx).await {
+                req.extensions_mut().insert(token);
+                req.extensions_mut().insert(login_id);
             }
         }
         
-        SaTokenContext::set_current(sa_ctx);
-        let result = ctx.call(&self.service, req).await;
-        SaTokenContext::clear();
-        result
+        // 开发模式免登录 | Dev bypass
+        if let Some(login_id) = self.state.manager.dev_bypass_login_id().filter(|_| sa_ctx.login_id.is_none()) {
+            req.extensions_mut().insert(login_id.to_string());
+            sa_ctx.login_id = Some(login_id.to_string());
+        }
+        
+        let response_cookie = sa_ctx.clone();
+        let mut res = SaTokenContext::scope(sa_ctx, ctx.call(&self.service, req)).await?;
+        
+        // 处理期间登录/登出产生的 token Cookie（`is_write_cookie`）
+        append_response_cookie(&response_cookie, res.headers_mut());
+        Ok(res)
     }
 }
 
+/// 先调用自定义提取器，再按 `SaTokenConfig::token_sources` 的顺序查找 |
+/// Custom extractors first, then `SaTokenConfig::token_sources` in order
 fn extract_token_from_request<Err>(req: &WebRequest<Err>, state: &SaTokenState) -> Option<String> 
 where
     Err: ErrorRenderer,
 {
-    let token_name = &state.manager.config.token_name;
-    let headers = req.headers();
-    
-    // 1. 从 token_name 指定的 header 获取
-    if let Some(header_value) = headers.get(token_name) {
-        if let Ok(value_str) = header_value.to_str() {
-            return Some(extract_bearer_token(value_str));
-        }
-    }
-    
-    // 2. 从标准 Authorization 头获取
-    if let Some(auth_header) = headers.get("Authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            return Some(extract_bearer_token(auth_str));
-        }
-    }
-    
-    // 3. 从 Cookie 获取
-    if let Some(cookie_header) = headers.get("cookie") {
-        if let Ok(cookie_str) = cookie_header.to_str() {
-            if let Some(token) = parse_cookie(cookie_str, token_name) {
-                return Some(token);
-            }
-        }
-    }
-    
-    // 4. 从查询参数获取
-    if let Some(query) = req.uri().query() {
-        if let Some(token) = parse_query_param(query, token_name) {
-            return Some(token);
-        }
-    }
-    
-    None
-}
-
-fn extract_bearer_token(header_value: &str) -> String {
-    if header_value.starts_with("Bearer ") {
-        header_value[7..].trim().to_string()
-    } else {
-        header_value.trim().to_string()
-    }
+    state.manager.extract_token(&NtexWebRequestAdapter::new(req)).map(|(token, _)| token)
 }
 
-fn parse_cookie(cookie_str: &str, token_name: &str) -> Option<String> {
-    for part in cookie_str.split(';') {
-        let part = part.trim();
-        if let Some(eq_pos) = part.find('=') {
-            let (name, value) = part.split_at(eq_pos);
-            if name.trim() == token_name {
-                return Some(value[1..].trim().to_string());
-            }
-        }
+/// 把请求处理期间记录的 token Cookie 追加到响应头，不覆盖已有的 `Set-Cookie`
+pub(crate) fn append_response_cookie(ctx: &SaTokenContext, headers: &mut HeaderMap) {
+    if let Some(cookie) = ctx.take_response_cookie()
+        && let Ok(value) = HeaderValue::from_str(&cookie.header_value())
+    {
+        headers.append(SET_COOKIE, value);
     }
-    None
 }
-
-fn parse_query_param(query: &str, param_name: &str) -> Option<String> {
-    for pair in query.split('&') {
-        let parts: Vec<&str> = pair.splitn(2, '=').collect();
-        if parts.len() == 2 && parts[0] == param_name {
-            return urlencoding::decode(parts[1])
-                .ok()
-                .map(|s| s.into_owned());
-        }
-    }
-    None
-}
\ No newline at end of file
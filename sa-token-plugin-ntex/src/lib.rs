@@ -66,9 +66,9 @@ pub mod state;
 
 // 重新导出核心功能 | Re-export core functionalities
 pub use sa_token_core::{self, SaTokenManager, StpUtil, SaTokenConfig, TokenValue, TokenInfo, 
-    SaSession, PermissionChecker, SaTokenError, SaTokenEvent, SaTokenListener, SaTokenEventBus, LoggingListener,
-    JwtManager, JwtClaims, JwtAlgorithm, OAuth2Manager, OAuth2Client, AuthorizationCode, AccessToken, OAuth2TokenInfo,
-    NonceManager, RefreshTokenManager, WsAuthManager, WsAuthInfo, WsTokenExtractor, DefaultWsTokenExtractor,
+    SaSession, PermissionChecker, SaCheck, CheckMode, SaTokenError, SaTokenEvent, SaTokenListener, SaTokenEventBus, LoggingListener,
+    JwtManager, JwtClaims, JwtAlgorithm, JwtKeyStore, JwtSecret, OAuth2Manager, OAuth2Client, AuthorizationCode, AccessToken, OAuth2TokenInfo,
+    NonceManager, RefreshTokenManager, TempTokenManager, SameTokenManager, WsAuthManager, WsAuthInfo, WsTokenExtractor, DefaultWsTokenExtractor,
     OnlineManager, OnlineUser, PushMessage, MessageType, MessagePusher, InMemoryPusher,
     DistributedSessionManager, DistributedSession, DistributedSessionStorage, ServiceCredential, InMemoryDistributedStorage,
     config::TokenStyle, token, error};
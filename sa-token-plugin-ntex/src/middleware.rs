@@ -4,23 +4,32 @@
 //!
 //! 提供多种中间件：
 //! - `SaTokenMiddleware`：基础 token 提取和验证中间件
+//! - `SaCheckMiddleware`：按 `SaCheck` 检查（可选登录、多权限/多角色 AND/OR），下列检查中间件均基于它
 //! - `SaCheckLoginMiddleware`：检查登录中间件，未登录时返回401错误
 //! - `SaCheckPermissionMiddleware`：检查权限中间件，无权限时返回403错误
 //! - `SaCheckRoleMiddleware`：检查角色中间件，无角色时返回403错误
+//! - `SaSameTokenMiddleware`：检查 Same-Token 中间件，缺失或无效时返回401错误
 //! - `AuthMiddleware`、`PermissionMiddleware`：已废弃，建议使用上述中间件
 
 use ntex::service::{Middleware, Service, ServiceCtx};
-use ntex::web::{Error, ErrorRenderer, WebRequest, WebResponse};
+use ntex::web::{Error, ErrorRenderer, HttpResponse, WebRequest, WebResponse};
 use std::sync::Arc;
-use serde_json::json;
 use sa_token_core::{
-    error::messages, 
+    AuthzKind,
+    ErrorResponse,
+    SaTokenError,
     token::TokenValue, 
     SaTokenContext,
+    ACCEPT_LANGUAGE_HEADER,
+    TIMEZONE_HEADER,
+    SAME_TOKEN_HEADER,
+    SaCheck,
+    SameTokenManager,
     StpUtil
 };
-use sa_token_adapter::utils::{parse_cookies, parse_query_string, extract_bearer_token};
 use crate::SaTokenState;
+use crate::adapter::NtexWebRequestAdapter;
+use crate::layer::append_response_cookie;
 use ntex::web::error::InternalError;
 use ntex::web::Error as WebError;
 
@@ -63,41 +72,15 @@ where
     type Error = Error;
 
     async fn call(&self, req: WebRequest<Err>, ctx: ServiceCtx<'_, Self>) -> Result<Self::Response, Self::Error> {
-        let mut sa_ctx = SaTokenContext::new();
+        let sa_ctx = resolve_context(&req, &self.state).await;
         
-        // 提取 token
-        if let Some(token_str) = extract_token_from_request(&req, &self.state) {
-            tracing::debug!("Sa-Token: extracted token from request: {}", token_str);
-            let token = TokenValue::new(token_str);
-            
-            // 验证 token
-            if self.state.manager.is_valid(&token).await {
-                // 存储 token 到请求扩展
-                req.extensions_mut().insert(token.clone());
-                
-                // 获取并存储 login_id
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                    let login_id = token_info.login_id.clone();
-                    req.extensions_mut().insert(login_id.clone());
-                    
-                    // 设置上下文
-                    sa_ctx.token = Some(token.clone());
-                    sa_ctx.token_info = Some(Arc::new(token_info));
-                    sa_ctx.login_id = Some(login_id);
-                }
-            }
-        }
-        
-        // 设置当前上下文
-        SaTokenContext::set_current(sa_ctx);
-        
-        // 继续处理请求
-        let result = ctx.call(&self.service, req).await;
+        // 在当前请求的上下文中继续处理
+        let response_cookie = sa_ctx.clone();
+        let mut res = SaTokenContext::scope(sa_ctx, ctx.call(&self.service, req)).await?;
         
-        // 清除上下文
-        SaTokenContext::clear();
-        
-        result
+        // 处理期间登录/登出产生的 token Cookie（`is_write_cookie`）
+        append_response_cookie(&response_cookie, res.headers_mut());
+        Ok(res)
     }
 }
 
@@ -141,67 +124,123 @@ where
     async fn call(&self, req: WebRequest<Err>, ctx: ServiceCtx<'_, Self>) -> Result<Self::Response, Self::Error> {
         // 中文 | English
         // 从请求头中获取 token | Get token from request headers
-        let token = req
-            .headers()
-            .get("Authorization")
-            .and_then(|v| v.to_str().ok())
-            .and_then(|s| s.strip_prefix("Bearer "))
-            .map(|s| s.to_string());
+        let token = extract_token_from_request_simple(&req);
         
         if let Some(token_str) = token {
             // 中文 | English
             // 验证 token 是否有效 | Verify if token is valid
             use sa_token_core::TokenValue;
             let token_value = TokenValue::from(token_str.clone());
-            if StpUtil::is_login(&token_value).await {
+            match StpUtil::get_token_info(&token_value).await {
                 // 中文 | English
                 // Token 有效，继续处理请求 | Token valid, continue processing
-                if let Ok(login_id) = StpUtil::get_login_id(&token_value).await {
-                    req.extensions_mut().insert(login_id);
+                Ok(token_info) if !token_info.is_restricted() => {
+                    req.extensions_mut().insert(token_info.login_id);
                     return ctx.call(&self.service, req).await;
                 }
+                // 中文 | English
+                // 受限 token（如密码过期）只能访问对应接口 | Restricted tokens (e.g. password expired) only reach their own routes
+                Ok(_) => {
+                    let err = SaTokenError::PasswordExpired;
+                    return Err(rejection_error(StpUtil::render_error(&err), err));
+                }
+                // 中文 | English
+                // 校验失败时返回具体原因（被踢、被顶替等）| Report why validation failed (kicked out, replaced, ...)
+                Err(e) => {
+                    let err = e.clone_auth_error().unwrap_or(SaTokenError::NotLogin);
+                    return Err(rejection_error(StpUtil::render_error(&err), err));
+                }
             }
         }
         
         // 中文 | English
         // Token 无效，返回 401 | Token invalid, return 401
-        Err(WebError::from(InternalError::new(
-            "Unauthorized",
-            ntex::http::StatusCode::UNAUTHORIZED,
-        )))
+        let err = SaTokenError::NotLogin;
+        Err(rejection_error(StpUtil::render_error(&err), err))
     }
 }
 
-/// sa-token 登录检查中间件 - 强制要求登录
+/// sa-token 访问检查中间件 - 按 [`SaCheck`] 检查登录、权限或角色
 /// 
-/// 此中间件会检查用户是否已登录，如果未登录则返回401错误
-pub struct SaCheckLoginMiddleware {
+/// 其余检查中间件都基于它实现，拒绝时的状态码和响应体与其他框架插件一致
+/// 
+/// # 示例
+/// ```rust,ignore
+/// let app = web::App::new()
+///     .wrap(SaCheckMiddleware::roles_or(state.clone(), ["admin", "ops"]))
+///     .route("/admin", web::get().to(admin_handler));
+/// ```
+pub struct SaCheckMiddleware {
     pub state: SaTokenState,
+    check: Arc<SaCheck>,
 }
 
-impl SaCheckLoginMiddleware {
-    pub fn new(state: SaTokenState) -> Self {
-        Self { state }
+impl SaCheckMiddleware {
+    pub fn new(state: SaTokenState, check: SaCheck) -> Self {
+        Self { state, check: Arc::new(check) }
+    }
+    
+    /// 可选登录：有合法 token 时写入上下文，否则照常放行
+    pub fn optional(state: SaTokenState) -> Self {
+        Self::new(state, SaCheck::optional())
+    }
+    
+    /// 必须拥有全部权限（AND）
+    pub fn permissions_and<I, T>(state: SaTokenState, permissions: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        Self::new(state, SaCheck::permissions_and(permissions))
+    }
+    
+    /// 拥有任一权限即可（OR）
+    pub fn permissions_or<I, T>(state: SaTokenState, permissions: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        Self::new(state, SaCheck::permissions_or(permissions))
+    }
+    
+    /// 必须拥有全部角色（AND）
+    pub fn roles_and<I, T>(state: SaTokenState, roles: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        Self::new(state, SaCheck::roles_and(roles))
+    }
+    
+    /// 拥有任一角色即可（OR）
+    pub fn roles_or<I, T>(state: SaTokenState, roles: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        Self::new(state, SaCheck::roles_or(roles))
     }
 }
 
-impl<S> Middleware<S> for SaCheckLoginMiddleware {
-    type Service = SaCheckLoginMiddlewareService<S>;
+impl<S> Middleware<S> for SaCheckMiddleware {
+    type Service = SaCheckMiddlewareService<S>;
 
     fn create(&self, service: S) -> Self::Service {
-        SaCheckLoginMiddlewareService {
+        SaCheckMiddlewareService {
             service,
             state: self.state.clone(),
+            check: self.check.clone(),
         }
     }
 }
 
-pub struct SaCheckLoginMiddlewareService<S> {
+pub struct SaCheckMiddlewareService<S> {
     service: S,
     state: SaTokenState,
+    check: Arc<SaCheck>,
 }
 
-impl<S, Err> Service<WebRequest<Err>> for SaCheckLoginMiddlewareService<S>
+impl<S, Err> Service<WebRequest<Err>> for SaCheckMiddlewareService<S>
 where
     S: Service<WebRequest<Err>, Response = WebResponse, Error = Error>,
     Err: ErrorRenderer,
@@ -210,219 +249,143 @@ where
     type Error = Error;
 
     async fn call(&self, req: WebRequest<Err>, ctx: ServiceCtx<'_, Self>) -> Result<Self::Response, Self::Error> {
-        let mut sa_ctx = SaTokenContext::new();
+        let sa_ctx = resolve_context(&req, &self.state).await;
+        let login_id = sa_ctx.login_id.clone();
         
-        // 提取 token
-        if let Some(token_str) = extract_token_from_request(&req, &self.state) {
-            tracing::debug!("Sa-Token(login-check): extracted token from request: {}", token_str);
-            let token = TokenValue::new(token_str);
-            
-            // 验证 token
-            if self.state.manager.is_valid(&token).await {
-                // 存储 token 和 login_id
-                req.extensions_mut().insert(token.clone());
-                
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                    let login_id = token_info.login_id.clone();
-                    req.extensions_mut().insert(login_id.clone());
-                    
-                    // 设置上下文
-                    sa_ctx.token = Some(token.clone());
-                    sa_ctx.token_info = Some(Arc::new(token_info));
-                    sa_ctx.login_id = Some(login_id);
-                    
-                    SaTokenContext::set_current(sa_ctx);
-                    let result = ctx.call(&self.service, req).await;
-                    SaTokenContext::clear();
-                    return result;
-                }
+        // 检查在上下文中执行，受限 token 等拒绝原因由上下文给出
+        SaTokenContext::scope(sa_ctx, async move {
+            if let Err(e) = self.check.check(login_id.as_deref()).await {
+                tracing::debug!("Sa-Token: {} rejected: {}", self.check.stage(), e);
+                return Err(rejection_error(self.state.manager.render_error(&e), e));
             }
-        }
-        
-        // 未登录，返回401错误
-        Err(WebError::from(InternalError::new(
-            json!({
-                "code": 401,
-                "message": messages::AUTH_ERROR
-            }).to_string(),
-            ntex::http::StatusCode::UNAUTHORIZED,
-        )))
+            ctx.call(&self.service, req).await
+        }).await
     }
 }
 
-/// sa-token 权限检查中间件 - 强制要求特定权限
-/// 
-/// 此中间件会检查用户是否拥有指定权限，如果没有则返回403错误
-pub struct SaCheckPermissionMiddleware {
-    pub state: SaTokenState,
-    permission: String,
-}
+/// 为固定检查的中间件生成类型，创建的服务都是 [`SaCheckMiddlewareService`]
+macro_rules! check_middleware {
+    ($(#[$doc:meta])* $name:ident, $service:ident) => {
+        $(#[$doc])*
+        pub struct $name(SaCheckMiddleware);
+        
+        impl<S> Middleware<S> for $name {
+            type Service = SaCheckMiddlewareService<S>;
 
-impl SaCheckPermissionMiddleware {
-    pub fn new(state: SaTokenState, permission: impl Into<String>) -> Self {
-        Self {
-            state,
-            permission: permission.into(),
+            fn create(&self, service: S) -> Self::Service {
+                self.0.create(service)
+            }
         }
-    }
+        
+        pub type $service<S> = SaCheckMiddlewareService<S>;
+    };
 }
 
-impl<S> Middleware<S> for SaCheckPermissionMiddleware {
-    type Service = SaCheckPermissionMiddlewareService<S>;
+check_middleware!(
+    /// sa-token 登录检查中间件 - 强制要求登录
+    /// 
+    /// 此中间件会检查用户是否已登录，如果未登录则返回401错误
+    SaCheckLoginMiddleware, SaCheckLoginMiddlewareService
+);
+
+check_middleware!(
+    /// sa-token 权限检查中间件 - 强制要求特定权限
+    /// 
+    /// 此中间件会检查用户是否拥有指定权限，如果没有则返回403错误
+    SaCheckPermissionMiddleware, SaCheckPermissionMiddlewareService
+);
+
+check_middleware!(
+    /// sa-token 角色检查中间件 - 强制要求特定角色
+    /// 
+    /// 此中间件会检查用户是否拥有指定角色，如果没有则返回403错误
+    SaCheckRoleMiddleware, SaCheckRoleMiddlewareService
+);
 
-    fn create(&self, service: S) -> Self::Service {
-        SaCheckPermissionMiddlewareService {
-            service,
-            state: self.state.clone(),
-            permission: self.permission.clone(),
-        }
+impl SaCheckLoginMiddleware {
+    pub fn new(state: SaTokenState) -> Self {
+        Self(SaCheckMiddleware::new(state, SaCheck::login()))
     }
 }
 
-pub struct SaCheckPermissionMiddlewareService<S> {
-    service: S,
-    state: SaTokenState,
-    permission: String,
-}
-
-impl<S, Err> Service<WebRequest<Err>> for SaCheckPermissionMiddlewareService<S>
-where
-    S: Service<WebRequest<Err>, Response = WebResponse, Error = Error>,
-    Err: ErrorRenderer,
-{
-    type Response = WebResponse;
-    type Error = Error;
-
-    async fn call(&self, req: WebRequest<Err>, ctx: ServiceCtx<'_, Self>) -> Result<Self::Response, Self::Error> {
-        let mut sa_ctx = SaTokenContext::new();
-        
-        // 提取 token
-        if let Some(token_str) = extract_token_from_request(&req, &self.state) {
-            tracing::debug!("Sa-Token(permission-check): extracted token from request: {}", token_str);
-            let token = TokenValue::new(token_str);
-            
-            // 验证 token
-            if self.state.manager.is_valid(&token).await {
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                    let login_id = token_info.login_id.clone();
-                    
-                    // 检查权限
-                    if StpUtil::has_permission(&login_id, &self.permission).await {
-                        // 存储信息到请求扩展
-                        req.extensions_mut().insert(token.clone());
-                        req.extensions_mut().insert(login_id.clone());
-                        
-                        // 设置上下文
-                        sa_ctx.token = Some(token.clone());
-                        sa_ctx.token_info = Some(Arc::new(token_info));
-                        sa_ctx.login_id = Some(login_id);
-                        
-                        SaTokenContext::set_current(sa_ctx);
-                        let result = ctx.call(&self.service, req).await;
-                        SaTokenContext::clear();
-                        return result;
-                    }
-                }
-            }
-        }
-        
-        // 无权限或未登录，返回403错误
-        Err(WebError::from(InternalError::new(
-            json!({
-                "code": 403,
-                "message": messages::PERMISSION_REQUIRED
-            }).to_string(),
-            ntex::http::StatusCode::FORBIDDEN,
-        )))
+impl SaCheckPermissionMiddleware {
+    pub fn new(state: SaTokenState, permission: impl Into<String>) -> Self {
+        Self(SaCheckMiddleware::new(state, SaCheck::permission(permission)))
+    }
+    
+    /// 必须拥有全部权限（AND）
+    pub fn and<I, T>(state: SaTokenState, permissions: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        Self(SaCheckMiddleware::permissions_and(state, permissions))
+    }
+    
+    /// 拥有任一权限即可（OR）
+    pub fn or<I, T>(state: SaTokenState, permissions: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        Self(SaCheckMiddleware::permissions_or(state, permissions))
     }
-}
-
-/// sa-token 角色检查中间件 - 强制要求特定角色
-/// 
-/// 此中间件会检查用户是否拥有指定角色，如果没有则返回403错误
-pub struct SaCheckRoleMiddleware {
-    pub state: SaTokenState,
-    role: String,
 }
 
 impl SaCheckRoleMiddleware {
     pub fn new(state: SaTokenState, role: impl Into<String>) -> Self {
-        Self {
-            state,
-            role: role.into(),
-        }
+        Self(SaCheckMiddleware::new(state, SaCheck::role(role)))
     }
-}
-
-impl<S> Middleware<S> for SaCheckRoleMiddleware {
-    type Service = SaCheckRoleMiddlewareService<S>;
-
-    fn create(&self, service: S) -> Self::Service {
-        SaCheckRoleMiddlewareService {
-            service,
-            state: self.state.clone(),
-            role: self.role.clone(),
-        }
+    
+    /// 必须拥有全部角色（AND）
+    pub fn and<I, T>(state: SaTokenState, roles: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        Self(SaCheckMiddleware::roles_and(state, roles))
+    }
+    
+    /// 拥有任一角色即可（OR）
+    pub fn or<I, T>(state: SaTokenState, roles: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        Self(SaCheckMiddleware::roles_or(state, roles))
     }
 }
 
-pub struct SaCheckRoleMiddlewareService<S> {
-    service: S,
-    state: SaTokenState,
-    role: String,
-}
-
-impl<S, Err> Service<WebRequest<Err>> for SaCheckRoleMiddlewareService<S>
+/// 提取并验证 token，写入请求扩展并构建请求上下文（开发模式免登录时补上 login_id）
+async fn resolve_context<Err>(req: &WebRequest<Err>, state: &SaTokenState) -> SaTokenContext
 where
-    S: Service<WebRequest<Err>, Response = WebResponse, Error = Error>,
     Err: ErrorRenderer,
 {
-    type Response = WebResponse;
-    type Error = Error;
-
-    async fn call(&self, req: WebRequest<Err>, ctx: ServiceCtx<'_, Self>) -> Result<Self::Response, Self::Error> {
-        let mut sa_ctx = SaTokenContext::new();
+    let mut sa_ctx = SaTokenContext::new();
+    let header = |name: &str| req.headers().get(name).and_then(|v| v.to_str().ok());
+    sa_ctx.capture_locale(header(ACCEPT_LANGUAGE_HEADER), header(TIMEZONE_HEADER));
+    sa_ctx.capture_same_token(header(SAME_TOKEN_HEADER));
+    sa_ctx.capture_client(state.manager.client_ip(&NtexWebRequestAdapter::new(req)).as_deref(), header("user-agent"));
+    
+    // 提取 token
+    if let Some(token_str) = extract_token_from_request(req, state) {
+        tracing::debug!("Sa-Token: extracted token from request: {}", token_str);
+        let token = TokenValue::new(token_str);
         
-        // 提取 token
-        if let Some(token_str) = extract_token_from_request(&req, &self.state) {
-            tracing::debug!("Sa-Token(role-check): extracted token from request: {}", token_str);
-            let token = TokenValue::new(token_str);
-            
-            // 验证 token
-            if self.state.manager.is_valid(&token).await {
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                    let login_id = token_info.login_id.clone();
-                    
-                    // 检查角色
-                    if StpUtil::has_role(&login_id, &self.role).await {
-                        // 存储信息到请求扩展
-                        req.extensions_mut().insert(token.clone());
-                        req.extensions_mut().insert(login_id.clone());
-                        
-                        // 设置上下文
-                        sa_ctx.token = Some(token.clone());
-                        sa_ctx.token_info = Some(Arc::new(token_info));
-                        sa_ctx.login_id = Some(login_id);
-                        
-                        SaTokenContext::set_current(sa_ctx);
-                        let result = ctx.call(&self.service, req).await;
-                        SaTokenContext::clear();
-                        return result;
-                    }
-                }
-            }
+        // 验证 token，存储 token 与 login_id 到请求扩展
+        if let Some(login_id) = state.manager.authenticate_context(&token, &mut sa_ctx).await {
+            req.extensions_mut().insert(token);
+            req.extensions_mut().insert(login_id);
         }
-        
-        // 无角色或未登录，返回403错误
-
-        Err(WebError::from(InternalError::new(
-            json!({
-                "code": 403,
-                "message": messages::ROLE_REQUIRED
-            }).to_string(),
-            ntex::http::StatusCode::FORBIDDEN,
-        )))
     }
+    
+    // 开发模式免登录 | Dev bypass
+    if let Some(login_id) = state.manager.dev_bypass_login_id().filter(|_| sa_ctx.login_id.is_none()) {
+        req.extensions_mut().insert(login_id.to_string());
+        sa_ctx.login_id = Some(login_id.to_string());
+    }
+    
+    sa_ctx
 }
 
 /// 中文 | English
@@ -483,6 +446,7 @@ where
             if StpUtil::has_permission(&login_id, &self.permission).await {
                 return ctx.call(&self.service, req).await;
             }
+            StpUtil::record_denial(AuthzKind::Permission, &self.permission);
         } else {
             // 如果扩展中没有 login_id，尝试从请求中提取 token 并验证
             // If no login_id in extensions, try to extract token from request and verify
@@ -500,86 +464,185 @@ where
                             req.extensions_mut().insert(login_id);
                             return ctx.call(&self.service, req).await;
                         }
+                        StpUtil::record_denial(AuthzKind::Permission, &self.permission);
                     }
                 }
             }
         }
         
         // 无权限或未登录，返回 403 | No permission or not logged in, return 403
-        Err(WebError::from(InternalError::new(
-            json!({
-                "code": 403,
-                "message": messages::PERMISSION_REQUIRED
-            }).to_string(),
-            ntex::http::StatusCode::FORBIDDEN,
-        )))
+        let err = SaTokenError::PermissionDeniedDetail(self.permission.clone());
+        Err(rejection_error(StpUtil::render_error(&err), err))
     }
 }
 
-/// 从请求中提取 token
+/// sa-token Same-Token 检查中间件 - 只允许内部服务访问
 /// 
-/// 参考 Actix-web 实现，支持从 Header、Cookie、Query 参数中提取
-fn extract_token_from_request<Err>(req: &WebRequest<Err>, state: &SaTokenState) -> Option<String>
-where
-    Err: ErrorRenderer,
-{
-    let token_name = &state.manager.config.token_name;
-    
-    // 1. 优先从 Header 中获取
-    if let Some(header_value) = req.headers().get(token_name) {
-        if let Ok(value_str) = header_value.to_str() {
-            if let Some(token) = extract_bearer_token(value_str) {
-                return Some(token);
-            }
-        }
+/// 此中间件会检查请求是否携带有效的 `sa-same-token` 请求头，缺失或无效时返回401错误
+/// 
+/// # 示例
+/// ```rust,ignore
+/// let internal = web::scope("/internal")
+///     .wrap(SaSameTokenMiddleware::new(state.manager.same_token_manager()))
+///     .route("/users/{id}", web::get().to(load_user));
+/// ```
+pub struct SaSameTokenMiddleware {
+    same: Arc<SameTokenManager>,
+}
+
+impl SaSameTokenMiddleware {
+    pub fn new(same: SameTokenManager) -> Self {
+        Self { same: Arc::new(same) }
     }
-    
-    // 检查 Authorization header
-    if let Some(auth_header) = req.headers().get("authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if let Some(token) = extract_bearer_token(auth_str) {
-                return Some(token);
-            }
+}
+
+impl<S> Middleware<S> for SaSameTokenMiddleware {
+    type Service = SaSameTokenMiddlewareService<S>;
+
+    fn create(&self, service: S) -> Self::Service {
+        SaSameTokenMiddlewareService {
+            service,
+            same: self.same.clone(),
         }
     }
-    
-    // 2. 从 Cookie 中获取
-    if let Some(cookie_header) = req.headers().get("cookie") {
-        if let Ok(cookie_str) = cookie_header.to_str() {
-            let cookies = parse_cookies(cookie_str);
-            if let Some(token) = cookies.get(token_name) {
-                return Some(token.clone());
-            }
+}
+
+pub struct SaSameTokenMiddlewareService<S> {
+    service: S,
+    same: Arc<SameTokenManager>,
+}
+
+impl<S, Err> Service<WebRequest<Err>> for SaSameTokenMiddlewareService<S>
+where
+    S: Service<WebRequest<Err>, Response = WebResponse, Error = Error>,
+    Err: ErrorRenderer,
+{
+    type Response = WebResponse;
+    type Error = Error;
+
+    async fn call(&self, req: WebRequest<Err>, ctx: ServiceCtx<'_, Self>) -> Result<Self::Response, Self::Error> {
+        let token = req.headers().get(SAME_TOKEN_HEADER).and_then(|v| v.to_str().ok());
+        if let Err(e) = self.same.check_token(token).await {
+            return Err(rejection_error(StpUtil::render_error(&e), e));
         }
+        ctx.call(&self.service, req).await
     }
-    
-    // 3. 从 Query 参数中获取
-    let query = req.query_string();
-    if !query.is_empty() {
-        let params = parse_query_string(query);
-        if let Some(token) = params.get(token_name) {
-            return Some(token.clone());
-        }
+}
+
+/// 把 `ErrorRenderer` 渲染的拒绝响应转换为 ntex 错误
+fn rejection_error(rendered: ErrorResponse, err: SaTokenError) -> WebError {
+    let mut response = HttpResponse::build(
+        ntex::http::StatusCode::from_u16(rendered.status).unwrap_or(ntex::http::StatusCode::FORBIDDEN)
+    );
+    response.content_type(rendered.content_type.as_str());
+    for (name, value) in &rendered.headers {
+        response.header(name.as_str(), value.as_str());
     }
-    
-    None
+    WebError::from(InternalError::from_response(err, response.body(rendered.body)))
+}
+
+/// 从请求中提取 token
+/// 
+/// 先调用自定义提取器，再按 `SaTokenConfig::token_sources` 的顺序查找
+fn extract_token_from_request<Err>(req: &WebRequest<Err>, state: &SaTokenState) -> Option<String>
+where
+    Err: ErrorRenderer,
+{
+    state.manager.extract_token(&NtexWebRequestAdapter::new(req)).map(|(token, _)| token)
 }
 
 /// 简化的 token 提取函数（用于废弃的中间件）
 /// 
-/// 仅从 Authorization header 中提取 Bearer token
+/// 仅从 Authorization header 中提取 token，按配置的 `token_prefix` / `token_schemes` 去掉前缀
 fn extract_token_from_request_simple<Err>(req: &WebRequest<Err>) -> Option<String>
 where
     Err: ErrorRenderer,
 {
-    // 只从 Authorization header 中获取 Bearer token
-    if let Some(auth_header) = req.headers().get("authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if let Some(token) = extract_bearer_token(auth_str) {
-                return Some(token);
-            }
+    req.headers()
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| StpUtil::config().strip_token_prefix(v).to_string())
+        .filter(|token| !token.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ntex::http::StatusCode;
+    use ntex::web::{self, test, App, ErrorContainer, HttpRequest};
+    use sa_token_core::{SaTokenConfig, TokenInfo, PASSWORD_CHANGE_SCOPE};
+    use sa_token_test_utils::state::{state, state_with};
+    use crate::LoginIdExtractor;
+
+    /// 状态码与响应体，中间件的拒绝错误渲染为响应 | Status and body; a middleware's rejection error is rendered as a response
+    async fn into_parts(result: Result<WebResponse, WebError>) -> (StatusCode, String) {
+        let response = result.unwrap_or_else(|e| {
+            let res = e.error_response(&test::TestRequest::default().to_http_request());
+            test::TestRequest::default().to_srv_request().into_response(res)
+        });
+        let status = response.status();
+        (status, String::from_utf8(test::read_body(response).await.to_vec()).unwrap())
+    }
+
+    async fn profile(req: HttpRequest) -> String {
+        LoginIdExtractor::from_request(&req).0.unwrap_or_else(|| "anonymous".to_string())
+    }
+
+    #[tokio::test]
+    async fn test_restricted_token_is_rejected_on_login_routes() {
+        let state = state();
+        let mut token_info = TokenInfo::new(TokenValue::new(""), "user_1");
+        token_info.scope = Some(vec![PASSWORD_CHANGE_SCOPE.to_string()]);
+        let restricted = state.manager.login_with_token_info(token_info).await.unwrap();
+        let token = state.manager.login("user_2").await.unwrap();
+
+        let app = test::init_service(App::new()
+            .service(web::scope("/checked").wrap(SaCheckLoginMiddleware::new(state.clone())).route("/profile", web::get().to(profile)))
+            .service(web::scope("/resolved").wrap(SaTokenMiddleware::new(state.clone())).route("/profile", web::get().to(profile)))).await;
+        let call = |path: &str, token: &TokenValue| app.call(test::TestRequest::get().uri(path).header("sa-token", token.as_str()).to_request());
+
+        assert_eq!(into_parts(call("/checked/profile", &token).await).await, (StatusCode::OK, "user_2".to_string()));
+        let (status, body) = into_parts(call("/checked/profile", &restricted).await).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(body.contains(&SaTokenError::PasswordExpired.to_string()));
+
+        // 基础中间件不为受限 token 写入 login_id
+        assert_eq!(into_parts(call("/resolved/profile", &restricted).await).await, (StatusCode::OK, "anonymous".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_replaced_and_kicked_out_tokens_are_rejected_with_reason() {
+        let config = SaTokenConfig { is_concurrent: false, ..Default::default() };
+        let state = state_with(config);
+        let replaced = state.manager.login("user_1").await.unwrap();
+        let current = state.manager.login("user_1").await.unwrap();
+        state.manager.kick_out_with_reason("user_1", "Signed in elsewhere").await.unwrap();
+
+        let app = test::init_service(App::new()
+            .service(web::scope("/checked").wrap(SaCheckLoginMiddleware::new(state.clone())).route("/profile", web::get().to(profile)))).await;
+        let request = test::TestRequest::get().uri("/checked/profile").header("sa-token", replaced.as_str()).to_request();
+        let (status, body) = into_parts(app.call(request).await).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(body.contains(&SaTokenError::Replaced.to_string()));
+
+        let request = test::TestRequest::get().uri("/checked/profile").header("sa-token", current.as_str()).to_request();
+        let (status, body) = into_parts(app.call(request).await).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(body.contains(&SaTokenError::KickedOut("Signed in elsewhere".to_string()).to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_layer_accepts_configured_schemes() {
+        let config = SaTokenConfig { token_schemes: vec!["Bearer".into(), "Token".into()], ..Default::default() };
+        let state = state_with(config);
+        let token = state.manager.login("user_1").await.unwrap();
+        let app = test::init_service(App::new()
+            .service(web::scope("/resolved").wrap(SaTokenMiddleware::new(state.clone())).route("/profile", web::get().to(profile)))).await;
+        let call = |value: String| app.call(test::TestRequest::get().uri("/resolved/profile").header("Authorization", value).to_request());
+
+        for value in [format!("bearer {}", token), format!("TOKEN  {}", token), format!(" Bearer\t{} ", token)] {
+            assert_eq!(into_parts(call(value).await).await, (StatusCode::OK, "user_1".to_string()));
         }
+        assert_eq!(into_parts(call(format!("Basic {}", token)).await).await, (StatusCode::OK, "anonymous".to_string()));
     }
-    
-    None
 }
@@ -50,6 +50,11 @@ impl<'a> SaRequest for PoemRequestAdapter<'a> {
     fn get_method(&self) -> String {
         self.request.method().to_string()
     }
+    
+    fn remote_addr(&self) -> Option<String> {
+        self.request.remote_addr().as_socket_addr()
+            .map(|addr| addr.ip().to_string())
+    }
 }
 
 /// Poem 响应构建器适配器
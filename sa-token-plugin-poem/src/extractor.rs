@@ -3,9 +3,8 @@
 //! Poem Extractor 实现
 
 use poem::{Request, Result, FromRequest, RequestBody};
-use poem::http::StatusCode;
-use sa_token_core::{token::TokenValue, error::messages};
-use serde_json::json;
+use sa_token_core::{token::TokenValue, SaTokenError, StpUtil};
+use crate::middleware::rejection_response;
 
 /// Token 提取器
 /// 
@@ -46,30 +45,14 @@ impl<'a> FromRequest<'a> for SaTokenExtractor {
             .extensions()
             .get::<TokenValue>()
             .cloned()
-            .ok_or_else(|| {
-                poem::Error::from_string(
-                    json!({
-                        "code": 401,
-                        "message": messages::AUTH_ERROR
-                    }).to_string(),
-                    StatusCode::UNAUTHORIZED
-                )
-            })?;
+            .ok_or_else(not_login)?;
         
         // 从请求扩展中获取 login_id
         let login_id = req
             .extensions()
             .get::<String>()
             .cloned()
-            .ok_or_else(|| {
-                poem::Error::from_string(
-                    json!({
-                        "code": 401,
-                        "message": messages::AUTH_ERROR
-                    }).to_string(),
-                    StatusCode::UNAUTHORIZED
-                )
-            })?;
+            .ok_or_else(not_login)?;
         
         Ok(Self { token, login_id })
     }
@@ -131,16 +114,13 @@ impl<'a> FromRequest<'a> for LoginIdExtractor {
             .extensions()
             .get::<String>()
             .cloned()
-            .ok_or_else(|| {
-                poem::Error::from_string(
-                    json!({
-                        "code": 401,
-                        "message": messages::AUTH_ERROR
-                    }).to_string(),
-                    StatusCode::UNAUTHORIZED
-                )
-            })?;
+            .ok_or_else(not_login)?;
         
         Ok(Self(login_id))
     }
 }
+
+/// 未登录时的拒绝响应，由配置的 `ErrorRenderer` 生成
+fn not_login() -> poem::Error {
+    poem::Error::from_response(rejection_response(StpUtil::render_error(&SaTokenError::NotLogin)))
+}
@@ -3,11 +3,11 @@
 //! Poem middleware layer for Sa-Token
 //! Poem 中间件层，用于 Sa-Token
 
-use poem::{Endpoint, Middleware, Request, Result};
-use std::sync::Arc;
-use sa_token_core::{token::TokenValue, SaTokenContext};
-use sa_token_adapter::utils::{parse_cookies, parse_query_string, extract_bearer_token};
+use poem::{Endpoint, IntoResponse, Middleware, Request, Response, Result};
+use poem::http::{header::{HeaderValue, SET_COOKIE}, HeaderMap};
+use sa_token_core::{token::TokenValue, SaTokenContext, ACCEPT_LANGUAGE_HEADER, TIMEZONE_HEADER, SAME_TOKEN_HEADER};
 use crate::SaTokenState;
+use crate::adapter::PoemRequestAdapter;
 
 /// Sa-Token layer for Poem framework
 /// Poem 框架的 Sa-Token 层
@@ -46,86 +46,54 @@ impl<E> Endpoint for SaTokenMiddleware<E>
 where
     E: Endpoint,
 {
-    type Output = E::Output;
+    type Output = Response;
 
     async fn call(&self, mut req: Request) -> Result<Self::Output> {
         let mut ctx = SaTokenContext::new();
+        let header = |name: &str| req.headers().get(name).and_then(|v| v.to_str().ok());
+        ctx.capture_locale(header(ACCEPT_LANGUAGE_HEADER), header(TIMEZONE_HEADER));
+        ctx.capture_same_token(header(SAME_TOKEN_HEADER));
+        ctx.capture_client(self.state.manager.client_ip(&PoemRequestAdapter::new(&req)).as_deref(), header("user-agent"));
         
         // Extract token from request | 从请求中提取 token
         if let Some(token_str) = extract_token_from_request(&req, &self.state) {
             tracing::debug!("Sa-Token: extracted token from request: {}", token_str);
             let token = TokenValue::new(token_str);
             
-            // Validate token | 验证 token
-            if self.state.manager.is_valid(&token).await {
-                // Store token in request extensions | 将 token 存储到请求扩展中
-                req.extensions_mut().insert(token.clone());
-                
-                // Get and store login_id | 获取并存储 login_id
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                    let login_id = token_info.login_id.clone();
-                    req.extensions_mut().insert(login_id.clone());
-                    
-                    // Set context | 设置上下文
-                    ctx.token = Some(token.clone());
-                    ctx.token_info = Some(Arc::new(token_info));
-                    ctx.login_id = Some(login_id);
-                }
+            // Validate token and fill the context | 验证 token 并写入上下文
+            if let Some(login_id) = self.state.manager.authenticate_context(&token, &mut ctx).await {
+                // Store token and login_id in request extensions | 将 token 与 login_id 存储到请求扩展中
+                req.extensions_mut().insert(token);
+                req.extensions_mut().insert(login_id);
             }
         }
         
-        // Set current context | 设置当前上下文
-        SaTokenContext::set_current(ctx);
-        
-        // Continue processing | 继续处理请求
-        let result = self.inner.call(req).await;
+        // 开发模式免登录 | Dev bypass
+        if let Some(login_id) = self.state.manager.dev_bypass_login_id().filter(|_| ctx.login_id.is_none()) {
+            req.extensions_mut().insert(login_id.to_string());
+            ctx.login_id = Some(login_id.to_string());
+        }
         
-        // Clear context | 清除上下文
-        SaTokenContext::clear();
+        // Continue within the request context | 在请求上下文中继续处理
+        let response_cookie = ctx.clone();
+        let mut response = SaTokenContext::scope(ctx, self.inner.call(req)).await?.into_response();
         
-        result
+        // 处理期间登录/登出产生的 token Cookie（`is_write_cookie`）
+        append_response_cookie(&response_cookie, response.headers_mut());
+        Ok(response)
     }
 }
 
 /// Extract token from Poem request | 从 Poem 请求中提取 token
 fn extract_token_from_request(req: &Request, state: &SaTokenState) -> Option<String> {
-    let token_name = &state.manager.config.token_name;
-    
-    // 1. From header | 从 Header 中获取
-    if let Some(header_value) = req.headers().get(token_name) {
-        if let Ok(value_str) = header_value.to_str() {
-            if let Some(token) = extract_bearer_token(value_str) {
-                return Some(token);
-            }
-        }
-    }
-    
-    // Check Authorization header | 检查 Authorization header
-    if let Some(auth_header) = req.headers().get("authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if let Some(token) = extract_bearer_token(auth_str) {
-                return Some(token);
-            }
-        }
-    }
-    
-    // 2. From cookie | 从 Cookie 中获取
-    if let Some(cookie_header) = req.headers().get("cookie") {
-        if let Ok(cookie_str) = cookie_header.to_str() {
-            let cookies = parse_cookies(cookie_str);
-            if let Some(token) = cookies.get(token_name) {
-                return Some(token.clone());
-            }
-        }
-    }
-    
-    // 3. From query parameters | 从查询参数中获取
-    if let Some(query) = req.uri().query() {
-        let params = parse_query_string(query);
-        if let Some(token) = params.get(token_name) {
-            return Some(token.clone());
-        }
+    state.manager.extract_token(&PoemRequestAdapter::new(req)).map(|(token, _)| token)
+}
+
+/// 把请求处理期间记录的 token Cookie 追加到响应头，不覆盖已有的 `Set-Cookie`
+pub(crate) fn append_response_cookie(ctx: &SaTokenContext, headers: &mut HeaderMap) {
+    if let Some(cookie) = ctx.take_response_cookie()
+        && let Ok(value) = HeaderValue::from_str(&cookie.header_value())
+    {
+        headers.append(SET_COOKIE, value);
     }
-    
-    None
 }
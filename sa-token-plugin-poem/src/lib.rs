@@ -59,15 +59,26 @@ pub mod middleware;
 pub mod extractor;
 pub mod layer;
 pub mod state;
+pub mod security_headers;
+pub mod read_only;
+pub mod sign;
+pub mod same_token;
 
 // ============================================================================
 // Poem 框架集成（本插件特有）
 // ============================================================================
-pub use middleware::{SaTokenMiddleware, SaCheckLoginMiddleware};
+pub use middleware::{
+    SaTokenMiddleware, SaCheckMiddleware, SaCheckLoginMiddleware,
+    SaCheckPermissionMiddleware, SaCheckRoleMiddleware,
+};
 pub use extractor::{SaTokenExtractor, OptionalSaTokenExtractor, LoginIdExtractor};
 pub use adapter::{PoemRequestAdapter, PoemResponseAdapter};
 pub use layer::SaTokenLayer;
 pub use state::{SaTokenState, SaTokenStateBuilder};
+pub use security_headers::SaSecurityHeadersMiddleware;
+pub use read_only::ReadOnlyModeMiddleware;
+pub use sign::SaSignMiddleware;
+pub use same_token::SaSameTokenMiddleware;
 
 // ============================================================================
 // 重新导出核心功能（sa-token-core）
@@ -87,7 +98,7 @@ pub use sa_token_core::{
     SaSession,
     
     // 权限
-    PermissionChecker,
+    PermissionChecker, SaCheck, CheckMode,
     
     // 错误处理
     SaTokenError,
@@ -96,13 +107,13 @@ pub use sa_token_core::{
     SaTokenEvent, SaTokenListener, SaTokenEventBus, LoggingListener,
     
     // JWT 支持
-    JwtManager, JwtClaims, JwtAlgorithm,
+    JwtManager, JwtClaims, JwtAlgorithm, JwtKeyStore, JwtSecret,
     
     // OAuth2 支持
     OAuth2Manager, OAuth2Client, AuthorizationCode, AccessToken, OAuth2TokenInfo,
     
     // 安全特性
-    NonceManager, RefreshTokenManager,
+    NonceManager, RefreshTokenManager, TempTokenManager, SignManager, SameTokenManager, AuditLogger, AuditRecord, AuditPage, StorageAuditLogger, SecurityHeaders, ReadOnlyMode,
     
     // WebSocket 认证
     WsAuthManager, WsAuthInfo, WsTokenExtractor, DefaultWsTokenExtractor,
@@ -7,10 +7,10 @@ use poem::{
     Endpoint, IntoResponse, Middleware, Request, Response, Result as PoemResult,
     http::StatusCode,
 };
-use sa_token_core::{token::TokenValue, SaTokenContext, error::messages};
-use sa_token_adapter::utils::{parse_cookies, parse_query_string, extract_bearer_token};
-use serde_json::json;
+use sa_token_core::{token::TokenValue, SaTokenContext, ACCEPT_LANGUAGE_HEADER, TIMEZONE_HEADER, SAME_TOKEN_HEADER, SaCheck, ErrorResponse};
 use crate::SaTokenState;
+use crate::adapter::PoemRequestAdapter;
+use crate::layer::append_response_cookie;
 
 /// sa-token 基础中间件 - 提取并验证 token
 pub struct SaTokenMiddleware {
@@ -43,162 +43,325 @@ impl<E: Endpoint> Endpoint for SaTokenMiddlewareImpl<E> {
     type Output = Response;
     
     async fn call(&self, mut req: Request) -> PoemResult<Self::Output> {
-        let mut ctx = SaTokenContext::new();
+        let ctx = resolve_context(&mut req, &self.state).await;
         
-        // Extract token from request | 从请求中提取 token
-        if let Some(token_str) = extract_token_from_request(&req, &self.state) {
-            tracing::debug!("Sa-Token: extracted token from request: {}", token_str);
-            let token = TokenValue::new(token_str);
-            
-            // Validate token | 验证 token
-            if self.state.manager.is_valid(&token).await {
-                // Store token in request extensions | 将 token 存储到请求扩展中
-                req.extensions_mut().insert(token.clone());
-                
-                // Get and store login_id | 获取并存储 login_id
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                    let login_id = token_info.login_id.clone();
-                    req.extensions_mut().insert(login_id.clone());
-                    
-                    // Set context | 设置上下文
-                    ctx.token = Some(token.clone());
-                    ctx.token_info = Some(Arc::new(token_info));
-                    ctx.login_id = Some(login_id);
-                }
-            }
-        }
-        
-        // Set current context | 设置当前上下文
-        SaTokenContext::set_current(ctx);
-        
-        // Continue processing | 继续处理请求
-        let result = self.ep.call(req).await;
-        
-        // Clear context | 清除上下文
-        SaTokenContext::clear();
+        // Continue within the request context | 在请求上下文中继续处理
+        let response_cookie = ctx.clone();
+        let mut response = SaTokenContext::scope(ctx, self.ep.call(req)).await?.into_response();
         
-        // Convert response | 转换响应
-        match result {
-            Ok(resp) => Ok(resp.into_response()),
-            Err(e) => Err(e),
-        }
+        // 处理期间登录/登出产生的 token Cookie（`is_write_cookie`）
+        append_response_cookie(&response_cookie, response.headers_mut());
+        Ok(response)
     }
 }
 
-/// sa-token 登录检查中间件 - 强制要求登录
-pub struct SaCheckLoginMiddleware {
+/// sa-token 访问检查中间件 - 按 [`SaCheck`] 检查登录、权限或角色
+/// 
+/// 其余检查中间件都基于它实现，拒绝时的状态码和响应体与其他框架插件一致。
+/// 
+/// # 示例
+/// ```rust,ignore
+/// let app = Route::new()
+///     .at("/orders", get(orders).with(SaCheckMiddleware::permissions_or(state.clone(), ["order:read", "order:admin"])))
+///     .at("/feed", get(feed).with(SaCheckMiddleware::optional(state)));
+/// ```
+pub struct SaCheckMiddleware {
     state: SaTokenState,
+    check: Arc<SaCheck>,
 }
 
-impl SaCheckLoginMiddleware {
-    pub fn new(state: SaTokenState) -> Self {
-        Self { state }
+impl SaCheckMiddleware {
+    pub fn new(state: SaTokenState, check: SaCheck) -> Self {
+        Self { state, check: Arc::new(check) }
+    }
+    
+    /// 可选登录：有合法 token 时写入上下文，否则照常放行
+    pub fn optional(state: SaTokenState) -> Self {
+        Self::new(state, SaCheck::optional())
+    }
+    
+    /// 必须登录
+    pub fn login(state: SaTokenState) -> Self {
+        Self::new(state, SaCheck::login())
+    }
+    
+    /// 必须拥有全部权限（AND）
+    pub fn permissions_and<I, S>(state: SaTokenState, permissions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::new(state, SaCheck::permissions_and(permissions))
+    }
+    
+    /// 拥有任一权限即可（OR）
+    pub fn permissions_or<I, S>(state: SaTokenState, permissions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::new(state, SaCheck::permissions_or(permissions))
+    }
+    
+    /// 必须拥有全部角色（AND）
+    pub fn roles_and<I, S>(state: SaTokenState, roles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::new(state, SaCheck::roles_and(roles))
+    }
+    
+    /// 拥有任一角色即可（OR）
+    pub fn roles_or<I, S>(state: SaTokenState, roles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::new(state, SaCheck::roles_or(roles))
     }
 }
 
-impl<E: Endpoint> Middleware<E> for SaCheckLoginMiddleware {
-    type Output = SaCheckLoginMiddlewareImpl<E>;
+impl<E: Endpoint> Middleware<E> for SaCheckMiddleware {
+    type Output = SaCheckMiddlewareImpl<E>;
     
     fn transform(&self, ep: E) -> Self::Output {
-        SaCheckLoginMiddlewareImpl {
+        SaCheckMiddlewareImpl {
             ep,
             state: self.state.clone(),
+            check: self.check.clone(),
         }
     }
 }
 
-pub struct SaCheckLoginMiddlewareImpl<E> {
+pub struct SaCheckMiddlewareImpl<E> {
     ep: E,
     state: SaTokenState,
+    check: Arc<SaCheck>,
 }
 
-impl<E: Endpoint> Endpoint for SaCheckLoginMiddlewareImpl<E> {
+impl<E: Endpoint> Endpoint for SaCheckMiddlewareImpl<E> {
     type Output = Response;
     
     async fn call(&self, mut req: Request) -> PoemResult<Self::Output> {
-        let mut ctx = SaTokenContext::new();
+        let ctx = resolve_context(&mut req, &self.state).await;
+        let login_id = ctx.login_id.clone();
+        
+        // 在请求上下文中检查，拒绝原因可以取到 token 校验结果 | Check inside the request context so the rejection sees the token validation result
+        SaTokenContext::scope(ctx, async move {
+            if let Err(e) = self.check.check(login_id.as_deref()).await {
+                tracing::debug!("Sa-Token: {} rejected: {}", self.check.stage(), e);
+                return Ok(rejection_response(self.state.manager.render_error(&e)));
+            }
+            self.ep.call(req).await.map(IntoResponse::into_response)
+        }).await
+    }
+}
+
+/// 把 `ErrorRenderer` 渲染的拒绝响应转换为 Poem 响应
+pub(crate) fn rejection_response(rendered: ErrorResponse) -> Response {
+    let mut builder = Response::builder()
+        .status(StatusCode::from_u16(rendered.status).unwrap_or(StatusCode::FORBIDDEN))
+        .content_type(rendered.content_type);
+    for (name, value) in rendered.headers {
+        builder = builder.header(name, value);
+    }
+    builder.body(rendered.body)
+}
+
+/// 为固定检查的中间件生成类型，转换后都是 [`SaCheckMiddlewareImpl`]
+macro_rules! check_middleware {
+    ($(#[$doc:meta])* $name:ident) => {
+        $(#[$doc])*
+        pub struct $name(SaCheckMiddleware);
         
-        // Extract token from request | 从请求中提取 token
-        if let Some(token_str) = extract_token_from_request(&req, &self.state) {
-            tracing::debug!("Sa-Token(login-check): extracted token from request: {}", token_str);
-            let token = TokenValue::new(token_str);
+        impl<E: Endpoint> Middleware<E> for $name {
+            type Output = SaCheckMiddlewareImpl<E>;
             
-            // Validate token | 验证 token
-            if self.state.manager.is_valid(&token).await {
-                // Store token and login_id | 存储 token 和 login_id
-                req.extensions_mut().insert(token.clone());
-                
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                    let login_id = token_info.login_id.clone();
-                    req.extensions_mut().insert(login_id.clone());
-                    
-                    // Set context | 设置上下文
-                    ctx.token = Some(token.clone());
-                    ctx.token_info = Some(Arc::new(token_info));
-                    ctx.login_id = Some(login_id);
-                    
-                    SaTokenContext::set_current(ctx);
-                    let result = self.ep.call(req).await;
-                    SaTokenContext::clear();
-                    
-                    return match result {
-                        Ok(resp) => Ok(resp.into_response()),
-                        Err(e) => Err(e),
-                    };
-                }
+            fn transform(&self, ep: E) -> Self::Output {
+                self.0.transform(ep)
             }
         }
-        
-        // Not logged in, return 401 | 未登录，返回401错误
-        Ok(Response::builder()
-            .status(StatusCode::UNAUTHORIZED)
-            .header("Content-Type", "application/json")
-            .body(json!({
-                "code": 401,
-                "message": messages::AUTH_ERROR
-            }).to_string()))
+    };
+}
+
+check_middleware!(
+    /// sa-token 登录检查中间件 - 强制要求登录，未登录返回 401
+    SaCheckLoginMiddleware
+);
+
+check_middleware!(
+    /// sa-token 权限检查中间件 - 缺少权限返回 403
+    SaCheckPermissionMiddleware
+);
+
+check_middleware!(
+    /// sa-token 角色检查中间件 - 缺少角色返回 403
+    SaCheckRoleMiddleware
+);
+
+impl SaCheckLoginMiddleware {
+    pub fn new(state: SaTokenState) -> Self {
+        Self(SaCheckMiddleware::login(state))
     }
 }
 
-/// Extract token from Poem request | 从 Poem 请求中提取 token
-fn extract_token_from_request(req: &Request, state: &SaTokenState) -> Option<String> {
-    let token_name = &state.manager.config.token_name;
+impl SaCheckPermissionMiddleware {
+    pub fn new(state: SaTokenState, permission: impl Into<String>) -> Self {
+        Self(SaCheckMiddleware::new(state, SaCheck::permission(permission)))
+    }
     
-    // 1. From header | 从 Header 中获取
-    if let Some(header_value) = req.headers().get(token_name) {
-        if let Ok(value_str) = header_value.to_str() {
-            if let Some(token) = extract_bearer_token(value_str) {
-                return Some(token);
-            }
-        }
+    /// 必须拥有全部权限（AND）
+    pub fn and<I, S>(state: SaTokenState, permissions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self(SaCheckMiddleware::permissions_and(state, permissions))
     }
     
-    // Check Authorization header | 检查 Authorization header
-    if let Some(auth_header) = req.headers().get("authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if let Some(token) = extract_bearer_token(auth_str) {
-                return Some(token);
-            }
-        }
+    /// 拥有任一权限即可（OR）
+    pub fn or<I, S>(state: SaTokenState, permissions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self(SaCheckMiddleware::permissions_or(state, permissions))
+    }
+}
+
+impl SaCheckRoleMiddleware {
+    pub fn new(state: SaTokenState, role: impl Into<String>) -> Self {
+        Self(SaCheckMiddleware::new(state, SaCheck::role(role)))
     }
     
-    // 2. From cookie | 从 Cookie 中获取
-    if let Some(cookie_header) = req.headers().get("cookie") {
-        if let Ok(cookie_str) = cookie_header.to_str() {
-            let cookies = parse_cookies(cookie_str);
-            if let Some(token) = cookies.get(token_name) {
-                return Some(token.clone());
-            }
-        }
+    /// 必须拥有全部角色（AND）
+    pub fn and<I, S>(state: SaTokenState, roles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self(SaCheckMiddleware::roles_and(state, roles))
+    }
+    
+    /// 拥有任一角色即可（OR）
+    pub fn or<I, S>(state: SaTokenState, roles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self(SaCheckMiddleware::roles_or(state, roles))
     }
+}
+
+/// 提取并验证 token，写入请求扩展并构建请求上下文
+/// 
+/// Extract and validate the token, store it in the request extensions and
+/// build the request context. Dev bypass fills in the login ID when no token matched.
+async fn resolve_context(req: &mut Request, state: &SaTokenState) -> SaTokenContext {
+    let mut ctx = SaTokenContext::new();
+    let header = |name: &str| req.headers().get(name).and_then(|v| v.to_str().ok());
+    ctx.capture_locale(header(ACCEPT_LANGUAGE_HEADER), header(TIMEZONE_HEADER));
+    ctx.capture_same_token(header(SAME_TOKEN_HEADER));
+    ctx.capture_client(state.manager.client_ip(&PoemRequestAdapter::new(req)).as_deref(), header("user-agent"));
     
-    // 3. From query parameters | 从查询参数中获取
-    if let Some(query) = req.uri().query() {
-        let params = parse_query_string(query);
-        if let Some(token) = params.get(token_name) {
-            return Some(token.clone());
+    // Extract token from request | 从请求中提取 token
+    if let Some(token_str) = extract_token_from_request(req, state) {
+        tracing::debug!("Sa-Token: extracted token from request: {}", token_str);
+        let token = TokenValue::new(token_str);
+        
+        // Validate token and fill the context | 验证 token 并写入上下文
+        if let Some(login_id) = state.manager.authenticate_context(&token, &mut ctx).await {
+            // Store token and login_id in request extensions | 将 token 与 login_id 存储到请求扩展中
+            req.extensions_mut().insert(token);
+            req.extensions_mut().insert(login_id);
         }
     }
     
-    None
+    // 开发模式免登录 | Dev bypass
+    if let Some(login_id) = state.manager.dev_bypass_login_id().filter(|_| ctx.login_id.is_none()) {
+        req.extensions_mut().insert(login_id.to_string());
+        ctx.login_id = Some(login_id.to_string());
+    }
+    
+    ctx
+}
+
+/// Extract token from Poem request | 从 Poem 请求中提取 token
+fn extract_token_from_request(req: &Request, state: &SaTokenState) -> Option<String> {
+    state.manager.extract_token(&PoemRequestAdapter::new(req)).map(|(token, _)| token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poem::{get, handler, EndpointExt, Route};
+    use sa_token_core::{SaTokenConfig, SaTokenError, TokenInfo, PASSWORD_CHANGE_SCOPE};
+    use sa_token_test_utils::state::{state, state_with};
+
+    #[handler]
+    fn ok() -> &'static str {
+        "ok"
+    }
+
+    /// 每次调用都围绕 `state` 组装新的路由 | Builds a fresh route around `state` for every call
+    async fn call(state: &SaTokenState, path: &str, token: &TokenValue) -> (StatusCode, String) {
+        let app = Route::new()
+            .at("/profile", get(ok).with(SaCheckMiddleware::login(state.clone())))
+            .at("/orders", get(ok).with(SaCheckLoginMiddleware::new(state.clone())));
+        let request = Request::builder().uri(path.parse().unwrap()).header("sa-token", token.as_str()).finish();
+        let response = app.get_response(request).await;
+        let status = response.status();
+        (status, response.into_body().into_string().await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_restricted_token_is_rejected_on_login_routes() {
+        let state = state();
+        let mut token_info = TokenInfo::new(TokenValue::new(""), "user_1");
+        token_info.scope = Some(vec![PASSWORD_CHANGE_SCOPE.to_string()]);
+        let restricted = state.manager.login_with_token_info(token_info).await.unwrap();
+        let token = state.manager.login("user_2").await.unwrap();
+
+        for path in ["/profile", "/orders"] {
+            assert_eq!(call(&state, path, &token).await, (StatusCode::OK, "ok".to_string()));
+
+            let (status, body) = call(&state, path, &restricted).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+            assert!(body.contains(&SaTokenError::PasswordExpired.to_string()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replaced_and_kicked_out_tokens_are_rejected_with_reason() {
+        let config = SaTokenConfig { is_concurrent: false, ..Default::default() };
+        let state = state_with(config);
+        let replaced = state.manager.login("user_1").await.unwrap();
+        let current = state.manager.login("user_1").await.unwrap();
+        state.manager.kick_out_with_reason("user_1", "Signed in elsewhere").await.unwrap();
+
+        for path in ["/profile", "/orders"] {
+            let (status, body) = call(&state, path, &replaced).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+            assert!(body.contains(&SaTokenError::Replaced.to_string()));
+
+            let (status, body) = call(&state, path, &current).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+            assert!(body.contains(&SaTokenError::KickedOut("Signed in elsewhere".to_string()).to_string()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_layer_accepts_configured_schemes() {
+        let config = SaTokenConfig { token_schemes: vec!["Bearer".into(), "Token".into()], ..Default::default() };
+        let state = state_with(config);
+        let token = state.manager.login("user_1").await.unwrap();
+        let app = Route::new().at("/profile", get(ok).with(SaCheckLoginMiddleware::new(state.clone())));
+        let call = |value: String| app.get_response(Request::builder().uri("/profile".parse().unwrap()).header("Authorization", value).finish());
+
+        for value in [format!("bearer {}", token), format!("TOKEN  {}", token), format!(" Bearer\t{} ", token)] {
+            assert_eq!(call(value).await.status(), StatusCode::OK);
+        }
+        assert_eq!(call(format!("Basic {}", token)).await.status(), StatusCode::UNAUTHORIZED);
+    }
 }
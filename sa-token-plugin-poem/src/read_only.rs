@@ -0,0 +1,67 @@
+// Author: 金书记
+//
+//! Read-only mode middleware | 只读模式中间件
+//!
+//! 只读模式开启后（`StpUtil::enable_read_only_mode`），拒绝受限用户的写操作
+//! （POST/PUT/PATCH/DELETE），返回 403。拥有豁免权限的用户不受影响。
+//! Must run after `SaTokenMiddleware` so it can see the login state.
+
+use poem::{
+    Endpoint, IntoResponse, Middleware, Request, Response, Result as PoemResult,
+    http::StatusCode,
+};
+use serde_json::json;
+use sa_token_core::{SaTokenError, StpUtil};
+
+/// Read-only mode middleware | 只读模式中间件
+///
+/// # Example | 示例
+/// ```rust,ignore
+/// let app = Route::new()
+///     .at("/api/orders", get(list_orders).post(create_order))
+///     .with(ReadOnlyModeMiddleware::new())
+///     .with(SaTokenMiddleware::new(state));
+/// ```
+#[derive(Clone, Default)]
+pub struct ReadOnlyModeMiddleware;
+
+impl ReadOnlyModeMiddleware {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for ReadOnlyModeMiddleware {
+    type Output = ReadOnlyModeMiddlewareImpl<E>;
+    
+    fn transform(&self, ep: E) -> Self::Output {
+        ReadOnlyModeMiddlewareImpl { ep }
+    }
+}
+
+pub struct ReadOnlyModeMiddlewareImpl<E> {
+    ep: E,
+}
+
+impl<E: Endpoint> Endpoint for ReadOnlyModeMiddlewareImpl<E> {
+    type Output = Response;
+    
+    async fn call(&self, req: Request) -> PoemResult<Self::Output> {
+        let login_id = req.extensions().get::<String>().cloned();
+        match StpUtil::check_read_only(login_id.as_deref(), req.method().as_str()).await {
+            Err(SaTokenError::ReadOnlyMode(message)) => Ok(Response::builder()
+                .status(StatusCode::FORBIDDEN)
+                .header("Content-Type", "application/json")
+                .body(json!({
+                    "code": 403,
+                    "message": message
+                }).to_string())),
+            Err(e) => {
+                // Don't block requests if the mode can't be read | 读取只读模式设置失败时不阻断请求
+                tracing::warn!("Sa-Token: read-only mode check failed: {}", e);
+                self.ep.call(req).await.map(IntoResponse::into_response)
+            }
+            Ok(()) => self.ep.call(req).await.map(IntoResponse::into_response),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+// Author: 金书记
+//
+//! Same-Token middleware | Same-Token 校验中间件
+//!
+//! 要求请求携带有效的 `sa-same-token` 请求头，用于只允许内部服务访问的接口；
+//! 缺失或无效时按配置的 `ErrorRenderer` 返回 401。
+//! Requires a valid `sa-same-token` header on internal-only endpoints; failures
+//! get 401 through the configured `ErrorRenderer`.
+
+use std::sync::Arc;
+use poem::{Endpoint, IntoResponse, Middleware, Request, Response, Result as PoemResult};
+use sa_token_core::{SameTokenManager, StpUtil, SAME_TOKEN_HEADER};
+use crate::middleware::rejection_response;
+
+/// Same-Token middleware | Same-Token 校验中间件
+///
+/// # Example | 示例
+/// ```rust,ignore
+/// let app = Route::new()
+///     .at("/internal/users/:id", get(load_user).with(SaSameTokenMiddleware::new(state.manager.same_token_manager())));
+/// ```
+#[derive(Clone)]
+pub struct SaSameTokenMiddleware {
+    same: Arc<SameTokenManager>,
+}
+
+impl SaSameTokenMiddleware {
+    pub fn new(same: SameTokenManager) -> Self {
+        Self { same: Arc::new(same) }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for SaSameTokenMiddleware {
+    type Output = SaSameTokenMiddlewareImpl<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        SaSameTokenMiddlewareImpl {
+            ep,
+            same: self.same.clone(),
+        }
+    }
+}
+
+pub struct SaSameTokenMiddlewareImpl<E> {
+    ep: E,
+    same: Arc<SameTokenManager>,
+}
+
+impl<E: Endpoint> Endpoint for SaSameTokenMiddlewareImpl<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> PoemResult<Self::Output> {
+        if let Err(e) = self.same.check_token(req.header(SAME_TOKEN_HEADER)).await {
+            return Ok(rejection_response(StpUtil::render_error(&e)));
+        }
+        self.ep.call(req).await.map(IntoResponse::into_response)
+    }
+}
@@ -0,0 +1,78 @@
+// Author: 金书记
+//
+//! Security headers middleware | 安全响应头中间件
+//!
+//! 为已登录请求的响应统一设置 X-Content-Type-Options、X-Frame-Options、
+//! Referrer-Policy 以及可选的 Content-Security-Policy。
+//! Must run after `SaTokenMiddleware` so it can see the login state.
+
+use poem::{
+    Endpoint, IntoResponse, Middleware, Request, Response, Result as PoemResult,
+    http::{HeaderName, HeaderValue},
+};
+use sa_token_core::SecurityHeaders;
+
+/// Security headers middleware | 安全响应头中间件
+///
+/// # Example | 示例
+/// ```rust,ignore
+/// let app = Route::new()
+///     .at("/api/user", get(user_info))
+///     .with(SaSecurityHeadersMiddleware::new())
+///     .with(SaTokenMiddleware::new(state));
+/// ```
+#[derive(Clone, Default)]
+pub struct SaSecurityHeadersMiddleware {
+    config: SecurityHeaders,
+}
+
+impl SaSecurityHeadersMiddleware {
+    /// Use default headers | 使用默认安全响应头
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Use custom headers | 使用自定义安全响应头配置
+    pub fn with_config(config: SecurityHeaders) -> Self {
+        Self { config }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for SaSecurityHeadersMiddleware {
+    type Output = SaSecurityHeadersMiddlewareImpl<E>;
+    
+    fn transform(&self, ep: E) -> Self::Output {
+        SaSecurityHeadersMiddlewareImpl {
+            ep,
+            config: self.config.clone(),
+        }
+    }
+}
+
+pub struct SaSecurityHeadersMiddlewareImpl<E> {
+    ep: E,
+    config: SecurityHeaders,
+}
+
+impl<E: Endpoint> Endpoint for SaSecurityHeadersMiddlewareImpl<E> {
+    type Output = Response;
+    
+    async fn call(&self, req: Request) -> PoemResult<Self::Output> {
+        // SaTokenMiddleware stores login_id for authenticated requests | 已登录请求会带有 login_id
+        let authenticated = req.extensions().get::<String>().is_some();
+        let mut resp = self.ep.call(req).await?.into_response();
+        
+        if self.config.should_apply(authenticated) {
+            let headers = resp.headers_mut();
+            for (name, value) in self.config.headers() {
+                let (Ok(name), Ok(value)) = (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(&value)) else {
+                    continue;
+                };
+                // Keep headers set by handlers | 业务代码已设置的头部保持不变
+                headers.entry(name).or_insert(value);
+            }
+        }
+        
+        Ok(resp)
+    }
+}
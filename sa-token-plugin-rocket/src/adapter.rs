@@ -9,17 +9,17 @@ use serde::Serialize;
 use std::collections::HashMap;
 
 /// Rocket 请求适配器
-pub struct RocketRequestAdapter<'a> {
-    request: &'a Request<'a>,
+pub struct RocketRequestAdapter<'a, 'r> {
+    request: &'a Request<'r>,
 }
 
-impl<'a> RocketRequestAdapter<'a> {
-    pub fn new(request: &'a Request<'a>) -> Self {
+impl<'a, 'r> RocketRequestAdapter<'a, 'r> {
+    pub fn new(request: &'a Request<'r>) -> Self {
         Self { request }
     }
 }
 
-impl<'a> SaRequest for RocketRequestAdapter<'a> {
+impl<'a, 'r> SaRequest for RocketRequestAdapter<'a, 'r> {
     fn get_header(&self, name: &str) -> Option<String> {
         self.request.headers().get_one(name)
             .map(|s| s.to_string())
@@ -48,6 +48,12 @@ impl<'a> SaRequest for RocketRequestAdapter<'a> {
         self.request.method().to_string()
     }
     
+    fn remote_addr(&self) -> Option<String> {
+        self.request.remote()
+            .map(|addr| addr.ip().to_string())
+    }
+    
+    /// Rocket 的 `client_ip` 按配置的 `ip_header` 取真实 IP
     fn get_client_ip(&self) -> Option<String> {
         self.request.client_ip()
             .map(|ip| ip.to_string())
@@ -4,23 +4,32 @@
 
 use rocket::request::{FromRequest, Request, Outcome};
 use rocket::http::Status;
-use rocket::http::ContentType;
 use rocket::response::{self, Responder};
-use sa_token_core::{token::TokenValue, error::messages};
-use serde_json::json;
+use sa_token_core::{token::TokenValue, ErrorResponse, SaTokenContext, SaTokenError, StpUtil, SAME_TOKEN_HEADER};
+use crate::middleware::write_rejection;
 
-/// 认证错误响应
+/// 认证错误响应，由配置的 `ErrorRenderer` 生成
 #[derive(Debug)]
 pub struct AuthError {
-    json: String,
+    rendered: ErrorResponse,
+}
+
+impl AuthError {
+    /// 未登录：受限 token 返回 `PasswordExpired`，否则返回 `NotLogin`
+    fn not_login(request: &Request<'_>) -> (Status, Self) {
+        Self::from_error(&request.local_cache(SaTokenContext::new).login_error())
+    }
+    
+    fn from_error(err: &SaTokenError) -> (Status, Self) {
+        let rendered = StpUtil::render_error(err);
+        (Status::from_code(rendered.status).unwrap_or(Status::Unauthorized), Self { rendered })
+    }
 }
 
 impl<'r> Responder<'r, 'static> for AuthError {
     fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
         let mut response = rocket::Response::new();
-        response.set_header(ContentType::JSON);
-        response.set_status(Status::Unauthorized);
-        response.set_sized_body(self.json.len(), std::io::Cursor::new(self.json));
+        write_rejection(&mut response, self.rendered);
         Ok(response)
     }
 }
@@ -44,12 +53,7 @@ impl<'r> FromRequest<'r> for SaTokenGuard {
             return Outcome::Success(SaTokenGuard(token.clone()));
         }
         
-        let error = json!({
-            "code": 401,
-            "message": messages::AUTH_ERROR
-        }).to_string();
-        
-        Outcome::Error((Status::Unauthorized, AuthError { json: error }))
+        Outcome::Error(AuthError::not_login(request))
     }
 }
 
@@ -85,11 +89,122 @@ impl<'r> FromRequest<'r> for LoginIdGuard {
             return Outcome::Success(LoginIdGuard(login_id.clone()));
         }
         
-        let error = json!({
-            "code": 401,
-            "message": messages::AUTH_ERROR
-        }).to_string();
-        
-        Outcome::Error((Status::Unauthorized, AuthError { json: error }))
+        Outcome::Error(AuthError::not_login(request))
+    }
+}
+
+/// Same-Token 守卫 - 要求请求携带有效的 `sa-same-token` 请求头，用于只允许内部服务访问的路由
+/// 
+/// ```rust,ignore
+/// #[get("/internal/users/<id>")]
+/// async fn load_user(_same: SameTokenGuard, id: u64) -> Json<User> { ... }
+/// ```
+pub struct SameTokenGuard;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for SameTokenGuard {
+    type Error = AuthError;
+    
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let token = request.headers().get_one(SAME_TOKEN_HEADER);
+        match StpUtil::same_token_manager().check_token(token).await {
+            Ok(()) => Outcome::Success(SameTokenGuard),
+            Err(e) => Outcome::Error(AuthError::from_error(&e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::local::asynchronous::Client;
+    use sa_token_core::{SaTokenConfig, TokenInfo, PASSWORD_CHANGE_SCOPE};
+    use sa_token_test_utils::state::{state, state_with};
+    use crate::{SaCheckLoginFairing, SaTokenLayer};
+
+    #[rocket::get("/profile")]
+    fn profile(login_id: LoginIdGuard) -> String {
+        login_id.0
+    }
+
+    #[rocket::get("/orders")]
+    fn orders() -> &'static str {
+        "ok"
+    }
+
+    async fn client(rocket: rocket::Rocket<rocket::Build>) -> Client {
+        Client::tracked(rocket).await.unwrap()
+    }
+
+    async fn call(client: &Client, path: &str, token: &TokenValue) -> (Status, String) {
+        let response = client.get(path).header(rocket::http::Header::new("sa-token", token.as_str().to_string())).dispatch().await;
+        let status = response.status();
+        (status, response.into_string().await.unwrap_or_default())
+    }
+
+    #[rocket::async_test]
+    async fn test_restricted_token_is_rejected_by_login_guard() {
+        let state = state();
+        let mut token_info = TokenInfo::new(TokenValue::new(""), "user_1");
+        token_info.scope = Some(vec![PASSWORD_CHANGE_SCOPE.to_string()]);
+        let restricted = state.manager.login_with_token_info(token_info).await.unwrap();
+        let token = state.manager.login("user_2").await.unwrap();
+
+        // 请求守卫的错误交给 Rocket 的 catcher，只能检查状态码
+        let guarded = client(rocket::build()
+            .attach(SaTokenLayer::new(state.clone()))
+            .mount("/", rocket::routes![profile])).await;
+        assert_eq!(call(&guarded, "/profile", &token).await, (Status::Ok, "user_2".to_string()));
+        assert_eq!(call(&guarded, "/profile", &restricted).await.0, Status::Unauthorized);
+
+        let checked = client(rocket::build()
+            .attach(SaCheckLoginFairing::new(state.clone()))
+            .mount("/", rocket::routes![orders])).await;
+        assert_eq!(call(&checked, "/orders", &token).await, (Status::Ok, "ok".to_string()));
+        let (status, body) = call(&checked, "/orders", &restricted).await;
+        assert_eq!(status, Status::Unauthorized);
+        assert!(body.contains(&SaTokenError::PasswordExpired.to_string()));
+    }
+
+    #[rocket::async_test]
+    async fn test_replaced_and_kicked_out_tokens_are_rejected_with_reason() {
+        let config = SaTokenConfig { is_concurrent: false, ..Default::default() };
+        let state = state_with(config);
+        let replaced = state.manager.login("user_1").await.unwrap();
+        let current = state.manager.login("user_1").await.unwrap();
+        state.manager.kick_out_with_reason("user_1", "Signed in elsewhere").await.unwrap();
+
+        let guarded = client(rocket::build()
+            .attach(SaTokenLayer::new(state.clone()))
+            .mount("/", rocket::routes![profile])).await;
+        assert_eq!(call(&guarded, "/profile", &replaced).await.0, Status::Unauthorized);
+
+        let checked = client(rocket::build()
+            .attach(SaCheckLoginFairing::new(state.clone()))
+            .mount("/", rocket::routes![orders])).await;
+        let (status, body) = call(&checked, "/orders", &replaced).await;
+        assert_eq!(status, Status::Unauthorized);
+        assert!(body.contains(&SaTokenError::Replaced.to_string()));
+
+        assert_eq!(call(&guarded, "/profile", &current).await.0, Status::Unauthorized);
+        let (status, body) = call(&checked, "/orders", &current).await;
+        assert_eq!(status, Status::Unauthorized);
+        assert!(body.contains(&SaTokenError::KickedOut("Signed in elsewhere".to_string()).to_string()));
+    }
+
+    #[rocket::async_test]
+    async fn test_layer_accepts_configured_schemes() {
+        let config = SaTokenConfig { token_schemes: vec!["Bearer".into(), "Token".into()], ..Default::default() };
+        let state = state_with(config);
+        let token = state.manager.login("user_1").await.unwrap();
+        let client = client(rocket::build()
+            .attach(SaTokenLayer::new(state.clone()))
+            .mount("/", rocket::routes![profile])).await;
+        let call = |value: String| client.get("/profile").header(rocket::http::Header::new("Authorization", value)).dispatch();
+
+        for value in [format!("bearer {}", token), format!("TOKEN  {}", token), format!(" Bearer\t{} ", token)] {
+            assert_eq!(call(value).await.into_string().await.unwrap_or_default(), "user_1");
+        }
+        assert_eq!(call(format!("Basic {}", token)).await.status(), Status::Unauthorized);
     }
 }
@@ -63,13 +63,15 @@ pub mod extractor;
 pub mod adapter;
 pub mod layer;
 pub mod state;
+pub mod security_headers;
 
 // ============================================================================
 // Rocket 框架集成（本插件特有）
 // ============================================================================
 pub use middleware::{SaTokenFairing, SaCheckLoginFairing, SaCheckPermissionFairing, SaCheckRoleFairing};
 pub use layer::SaTokenLayer;
-pub use extractor::{SaTokenGuard, OptionalSaTokenGuard, LoginIdGuard};
+pub use security_headers::SaSecurityHeadersFairing;
+pub use extractor::{SaTokenGuard, OptionalSaTokenGuard, LoginIdGuard, SameTokenGuard};
 pub use adapter::{RocketRequestAdapter, RocketResponseAdapter};
 
 // ============================================================================
@@ -99,13 +101,13 @@ pub use sa_token_core::{self,
     SaTokenEvent, SaTokenListener, SaTokenEventBus, LoggingListener,
     
     // JWT 支持
-    JwtManager, JwtClaims, JwtAlgorithm,
+    JwtManager, JwtClaims, JwtAlgorithm, JwtKeyStore, JwtSecret,
     
     // OAuth2 支持
     OAuth2Manager, OAuth2Client, AuthorizationCode, AccessToken, OAuth2TokenInfo,
     
     // 安全特性
-    NonceManager, RefreshTokenManager,
+    NonceManager, RefreshTokenManager, TempTokenManager, SameTokenManager, SecurityHeaders,
     
     // WebSocket 认证
     WsAuthManager, WsAuthInfo, WsTokenExtractor, DefaultWsTokenExtractor,
@@ -4,10 +4,10 @@
 
 use rocket::{Request, Data, Response};
 use rocket::fairing::{Fairing, Info, Kind};
-use rocket::http::{Status, ContentType};
+use rocket::http::{Header, Status};
 use crate::SaTokenState;
-use sa_token_core::{token::TokenValue, error::messages};
-use serde_json::json;
+use crate::adapter::RocketRequestAdapter;
+use sa_token_core::{token::TokenValue, AuthzKind, ErrorResponse, SaTokenContext, SaTokenError};
 
 /// sa-token Fairing - 提取并验证 token
 pub struct SaTokenFairing {
@@ -30,40 +30,7 @@ impl Fairing for SaTokenFairing {
     }
     
     async fn on_request(&self, request: &mut Request<'_>, _data: &mut Data<'_>) {
-        // 提取 token
-        let token_str = {
-            let token_name = &self.state.manager.config.token_name;
-            
-            // 1. 从 Header 获取
-            if let Some(header_val) = request.headers().get_one(token_name) {
-                Some(extract_bearer_token(header_val))
-            }
-            // 2. 从 Cookie 获取
-            else if let Some(cookie) = request.cookies().get(token_name) {
-                Some(cookie.value().to_string())
-            }
-            // 3. 从 Query 参数获取
-            else if let Some(query) = request.uri().query() {
-                parse_query_param(query.as_str(), token_name)
-            } else {
-                None
-            }
-        };
-        
-        if let Some(token_str) = token_str {
-            let token = TokenValue::new(token_str);
-            
-            // 验证 token
-            if self.state.manager.is_valid(&token).await {
-                // 存储 token 到本地缓存
-                request.local_cache(|| Some(token.clone()));
-                
-                // 获取并存储 login_id
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                    request.local_cache(|| Some(token_info.login_id.clone()));
-                }
-            }
-        }
+        authenticate(&self.state, request).await;
     }
 }
 
@@ -88,40 +55,14 @@ impl Fairing for SaCheckLoginFairing {
     }
     
     async fn on_request(&self, request: &mut Request<'_>, _data: &mut Data<'_>) {
-        // 提取 token
-        let token_str = {
-            let token_name = &self.state.manager.config.token_name;
-            
-            // 1. 从 Header 获取
-            if let Some(header_val) = request.headers().get_one(token_name) {
-                Some(extract_bearer_token(header_val))
-            }
-            // 2. 从 Cookie 获取
-            else if let Some(cookie) = request.cookies().get(token_name) {
-                Some(cookie.value().to_string())
-            }
-            // 3. 从 Query 参数获取
-            else if let Some(query) = request.uri().query() {
-                parse_query_param(query.as_str(), token_name)
-            } else {
-                None
-            }
-        };
+        if authenticate(&self.state, request).await {
+            return;
+        }
         
-        if let Some(token_str) = token_str {
-            let token = TokenValue::new(token_str);
-            
-            // 验证 token
-            if self.state.manager.is_valid(&token).await {
-                // 存储 token
-                request.local_cache(|| Some(token.clone()));
-                
-                // 获取并存储 login_id
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                    request.local_cache(|| Some(token_info.login_id.clone()));
-                }
-                return;
-            }
+        // 开发模式免登录 | Dev bypass
+        if let Some(login_id) = self.state.manager.dev_bypass_login_id() {
+            request.local_cache(|| Some(login_id.to_string()));
+            return;
         }
         
         // 未登录，标记为未授权
@@ -132,13 +73,8 @@ impl Fairing for SaCheckLoginFairing {
         // 检查是否标记为未授权
         if let Some(_) = request.local_cache(|| None::<&str>) {
             if *request.local_cache(|| None::<&str>) == Some("unauthorized") {
-                response.set_status(Status::Unauthorized);
-                response.set_sized_body(None, std::io::Cursor::new(
-                    json!({
-                        "code": 401,
-                        "message": messages::AUTH_ERROR
-                    }).to_string()
-                ));
+                let err = request.local_cache(SaTokenContext::new).login_error();
+                write_rejection(response, self.state.manager.render_error(&err));
             }
         }
     }
@@ -146,7 +82,6 @@ impl Fairing for SaCheckLoginFairing {
 
 /// sa-token 权限检查 Fairing - 强制要求特定权限
 pub struct SaCheckPermissionFairing {
-    #[allow(dead_code)]
     state: SaTokenState,
     permission: String,
 }
@@ -176,6 +111,7 @@ impl Fairing for SaCheckPermissionFairing {
             if sa_token_core::StpUtil::has_permission(&login_id, &self.permission).await {
                 return;
             }
+            sa_token_core::StpUtil::record_denial(AuthzKind::Permission, &self.permission);
         }
         
         // 无权限，标记为禁止访问
@@ -186,14 +122,8 @@ impl Fairing for SaCheckPermissionFairing {
         // 检查是否标记为禁止访问
         if let Some(_) = request.local_cache(|| None::<&str>) {
             if *request.local_cache(|| None::<&str>) == Some("forbidden") {
-                response.set_status(Status::Forbidden);
-                response.set_header(ContentType::JSON);
-                response.set_sized_body(None, std::io::Cursor::new(
-                    json!({
-                        "code": 403,
-                        "message": messages::PERMISSION_REQUIRED
-                    }).to_string()
-                ));
+                let err = SaTokenError::PermissionDeniedDetail(self.permission.clone());
+                write_rejection(response, self.state.manager.render_error(&err));
             }
         }
     }
@@ -201,7 +131,6 @@ impl Fairing for SaCheckPermissionFairing {
 
 /// sa-token 角色检查 Fairing - 强制要求特定角色
 pub struct SaCheckRoleFairing {
-    #[allow(dead_code)]
     state: SaTokenState,
     role: String,
 }
@@ -231,6 +160,7 @@ impl Fairing for SaCheckRoleFairing {
             if sa_token_core::StpUtil::has_role(&login_id, &self.role).await {
                 return;
             }
+            sa_token_core::StpUtil::record_denial(AuthzKind::Role, &self.role);
         }
         
         // 无角色，标记为禁止访问
@@ -241,36 +171,42 @@ impl Fairing for SaCheckRoleFairing {
         // 检查是否标记为禁止访问
         if let Some(_) = request.local_cache(|| None::<&str>) {
             if *request.local_cache(|| None::<&str>) == Some("forbidden_role") {
-                response.set_status(Status::Forbidden);
-                response.set_header(ContentType::JSON);
-                response.set_sized_body(None, std::io::Cursor::new(
-                    json!({
-                        "code": 403,
-                        "message": messages::ROLE_REQUIRED
-                    }).to_string()
-                ));
+                let err = SaTokenError::RoleDenied(self.role.clone());
+                write_rejection(response, self.state.manager.render_error(&err));
             }
         }
     }
 }
 
-/// 提取 Bearer token
-fn extract_bearer_token(token: &str) -> String {
-    if token.starts_with("Bearer ") {
-        token[7..].to_string()
-    } else {
-        token.to_string()
-    }
+/// 校验请求携带的 token，把 token、login_id 与上下文写入本地缓存，返回是否已登录
+async fn authenticate(state: &SaTokenState, request: &Request<'_>) -> bool {
+    let mut ctx = SaTokenContext::new();
+    ctx.capture_client(state.manager.client_ip(&RocketRequestAdapter::new(request)).as_deref(), request.headers().get_one("user-agent"));
+    
+    let token = state.manager
+        .extract_token(&RocketRequestAdapter::new(request))
+        .map(|(token, _)| TokenValue::new(token));
+    let logged_in = match token {
+        Some(token) => match state.manager.authenticate_context(&token, &mut ctx).await {
+            Some(login_id) => {
+                request.local_cache(|| Some(token));
+                request.local_cache(|| Some(login_id));
+                true
+            }
+            None => false,
+        },
+        None => false,
+    };
+    request.local_cache(|| ctx);
+    logged_in
 }
 
-/// 解析查询参数
-fn parse_query_param(query: &str, name: &str) -> Option<String> {
-    for pair in query.split('&') {
-        if let Some((key, value)) = pair.split_once('=') {
-            if key == name {
-                return urlencoding::decode(value).ok().map(|s| s.to_string());
-            }
-        }
-    }
-    None
+/// 把 `ErrorRenderer` 渲染的拒绝响应写入 Rocket 响应
+pub(crate) fn write_rejection(response: &mut Response<'_>, rendered: ErrorResponse) {
+    response.set_status(Status::from_code(rendered.status).unwrap_or(Status::Forbidden));
+    response.set_header(Header::new("Content-Type", rendered.content_type));
+    for (name, value) in rendered.headers {
+        response.adjoin_header(Header::new(name, value));
+    }
+    response.set_sized_body(None, std::io::Cursor::new(rendered.body));
 }
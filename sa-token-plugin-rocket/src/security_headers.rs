@@ -0,0 +1,61 @@
+// Author: 金书记
+//
+//! 安全响应头 Fairing
+//!
+//! 为已登录请求的响应统一设置 X-Content-Type-Options、X-Frame-Options、
+//! Referrer-Policy 以及可选的 Content-Security-Policy。
+//! 需要在 `SaTokenLayer` / `SaTokenFairing` 之后 attach 才能识别登录状态。
+
+use rocket::{Request, Response};
+use rocket::fairing::{Fairing, Info, Kind};
+use sa_token_core::SecurityHeaders;
+
+/// 安全响应头 Fairing
+///
+/// # 示例
+/// ```rust,ignore
+/// rocket::build()
+///     .attach(SaTokenLayer::new(state.clone()))
+///     .attach(SaSecurityHeadersFairing::new())
+/// ```
+#[derive(Clone, Default)]
+pub struct SaSecurityHeadersFairing {
+    config: SecurityHeaders,
+}
+
+impl SaSecurityHeadersFairing {
+    /// 使用默认安全响应头
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// 使用自定义安全响应头配置
+    pub fn with_config(config: SecurityHeaders) -> Self {
+        Self { config }
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for SaSecurityHeadersFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Sa-Token Security Headers",
+            kind: Kind::Response,
+        }
+    }
+    
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        // SaTokenLayer 会为已登录请求缓存 login_id
+        let authenticated = req.local_cache(|| None::<String>).is_some();
+        if !self.config.should_apply(authenticated) {
+            return;
+        }
+        
+        for (name, value) in self.config.headers() {
+            // 业务代码已设置的头部保持不变
+            if !res.headers().contains(name) {
+                res.set_raw_header(name, value);
+            }
+        }
+    }
+}
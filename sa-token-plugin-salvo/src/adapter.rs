@@ -73,6 +73,11 @@ impl<'a> SaRequest for SalvoRequestAdapter<'a> {
     fn get_method(&self) -> String {
         self.request.method().to_string()
     }
+    
+    fn remote_addr(&self) -> Option<String> {
+        self.request.remote_addr().clone().into_std()
+            .map(|addr| addr.ip().to_string())
+    }
 }
 
 /// 中文 | English
@@ -99,8 +104,10 @@ impl<'a> SaResponse for SalvoResponseAdapter<'a> {
     }
     
     fn set_cookie(&mut self, name: &str, value: &str, options: CookieOptions) {
-        let cookie_string = build_cookie_string(name, value, options);
-        self.set_header("Set-Cookie", &cookie_string);
+        // 追加而不是覆盖，保留处理函数已设置的其他 Cookie
+        if let Ok(header_value) = http::header::HeaderValue::from_str(&build_cookie_string(name, value, options)) {
+            self.response.headers_mut().append(http::header::SET_COOKIE, header_value);
+        }
     }
     
     fn set_status(&mut self, status: u16) {
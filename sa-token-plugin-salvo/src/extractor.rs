@@ -1,6 +1,5 @@
 use salvo::prelude::*;
-use sa_token_core::{token::TokenValue, error::messages};
-use serde_json::json;
+use sa_token_core::{token::TokenValue, error::messages, SaTokenError, StpUtil};
 
 /// 中文: 认证错误 | English: Authentication error
 #[derive(Debug)]
@@ -17,12 +16,9 @@ impl AuthError {
         messages::AUTH_ERROR
     }
     
-    /// 中文: 转换为 JSON 字符串 | English: Convert to JSON string
+    /// 中文: 按配置的 `ErrorRenderer` 渲染的响应体 | English: Body rendered by the configured `ErrorRenderer`
     pub fn to_json(&self) -> String {
-        json!({
-            "code": 401,
-            "message": self.message()
-        }).to_string()
+        StpUtil::render_error(&SaTokenError::NotLogin).body
     }
 }
 
@@ -64,9 +64,9 @@ pub mod state;
 
 // 重新导出核心功能 | Re-export core functionalities
 pub use sa_token_core::{self, SaTokenManager, StpUtil, SaTokenConfig, TokenValue, TokenInfo, 
-    SaSession, PermissionChecker, SaTokenError, SaTokenEvent, SaTokenListener, SaTokenEventBus, LoggingListener,
-    JwtManager, JwtClaims, JwtAlgorithm, OAuth2Manager, OAuth2Client, AuthorizationCode, AccessToken, OAuth2TokenInfo,
-    NonceManager, RefreshTokenManager, WsAuthManager, WsAuthInfo, WsTokenExtractor, DefaultWsTokenExtractor,
+    SaSession, PermissionChecker, SaCheck, CheckMode, SaTokenError, SaTokenEvent, SaTokenListener, SaTokenEventBus, LoggingListener,
+    JwtManager, JwtClaims, JwtAlgorithm, JwtKeyStore, JwtSecret, OAuth2Manager, OAuth2Client, AuthorizationCode, AccessToken, OAuth2TokenInfo,
+    NonceManager, RefreshTokenManager, TempTokenManager, SameTokenManager, WsAuthManager, WsAuthInfo, WsTokenExtractor, DefaultWsTokenExtractor,
     OnlineManager, OnlineUser, PushMessage, MessageType, MessagePusher, InMemoryPusher,
     DistributedSessionManager, DistributedSession, DistributedSessionStorage, ServiceCredential, InMemoryDistributedStorage,
     config::TokenStyle, token, error};
@@ -89,8 +89,9 @@ pub use sa_token_storage_database::*;
 pub use adapter::*;
 pub use extractor::*;
 pub use middleware::{
-    auth_middleware, permission_middleware, 
-    SaCheckLoginMiddleware, SaCheckPermissionMiddleware, SaCheckRoleMiddleware
+    auth_middleware, permission_middleware, SaCheckMiddleware,
+    SaCheckLoginMiddleware, SaCheckPermissionMiddleware, SaCheckRoleMiddleware,
+    SaSameTokenMiddleware
 };
 pub use layer::{SaTokenLayer, extract_token_from_request};
 pub use state::{SaTokenState, SaTokenStateBuilder};
\ No newline at end of file
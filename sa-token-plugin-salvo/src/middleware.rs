@@ -4,11 +4,11 @@
 // Salvo 认证中间件 | Salvo authentication middleware
 
 use salvo::prelude::*;
-use sa_token_core::{StpUtil, error::messages, SaTokenContext, token::TokenValue};
-use serde_json::json;
+use sa_token_core::{StpUtil, SaTokenContext, SaCheck, AuthzKind, ErrorResponse, SaTokenError, SameTokenManager, SAME_TOKEN_HEADER};
+use salvo::http::header::{HeaderName, CONTENT_TYPE};
 use crate::state::SaTokenState;
 use std::sync::Arc;
-use crate::layer::extract_token_from_request;
+use crate::layer::resolve_context;
 
 /// 中文 | English
 /// 认证中间件 - 验证用户登录状态 | Authentication middleware - verify user login status
@@ -34,29 +34,43 @@ async fn auth_middleware_handler(req: &mut Request, res: &mut Response, depot: &
         .headers()
         .get("Authorization")
         .and_then(|v| v.to_str().ok())
-        .and_then(|s| s.strip_prefix("Bearer "))
-        .map(|s| s.to_string());
+        .map(|v| StpUtil::config().strip_token_prefix(v).to_string())
+        .filter(|token| !token.is_empty());
     
     if let Some(token_str) = token {
         // 中文 | English
         // 验证 token 是否有效 | Verify if token is valid
         use sa_token_core::TokenValue;
         let token_value = TokenValue::from(token_str.clone());
-        if StpUtil::is_login(&token_value).await {
+        match StpUtil::get_token_info(&token_value).await {
             // 中文 | English
             // Token 有效，将 login_id 存入 depot | Token valid, store login_id in depot
-            if let Ok(login_id) = StpUtil::get_login_id(&token_value).await {
-                depot.insert("login_id", login_id);
+            Ok(token_info) if !token_info.is_restricted() => {
+                depot.insert("login_id", token_info.login_id);
                 ctrl.call_next(req, depot, res).await;
                 return;
             }
+            // 中文 | English
+            // 受限 token（如密码过期）只能访问对应接口 | Restricted tokens (e.g. password expired) only reach their own routes
+            Ok(_) => {
+                write_rejection(res, StpUtil::render_error(&SaTokenError::PasswordExpired));
+                ctrl.skip_rest();
+                return;
+            }
+            // 中文 | English
+            // 校验失败时返回具体原因（被踢、被顶替等）| Report why validation failed (kicked out, replaced, ...)
+            Err(e) => {
+                let err = e.clone_auth_error().unwrap_or(SaTokenError::NotLogin);
+                write_rejection(res, StpUtil::render_error(&err));
+                ctrl.skip_rest();
+                return;
+            }
         }
     }
     
     // 中文 | English
     // Token 无效，返回 401 | Token invalid, return 401
-    res.status_code(StatusCode::UNAUTHORIZED);
-    res.render(Text::Json(r#"{"error":"Unauthorized"}"#));
+    write_rejection(res, StpUtil::render_error(&SaTokenError::NotLogin));
     ctrl.skip_rest();
 }
 
@@ -92,184 +106,338 @@ impl PermissionMiddleware {
                 ctrl.call_next(req, depot, res).await;
                 return;
             }
+            StpUtil::record_denial(AuthzKind::Permission, self.permission);
         }
         
         // 中文 | English
         // 无权限，返回 403 | No permission, return 403
-        res.status_code(StatusCode::FORBIDDEN);
-        res.render(Text::Json(r#"{"error":"Forbidden"}"#));
+        let err = SaTokenError::PermissionDeniedDetail(self.permission.to_string());
+        write_rejection(res, StpUtil::render_error(&err));
         ctrl.skip_rest();
     }
 }
 
 /// 中文 | English
-/// Sa-Token 登录检查中间件 | Sa-Token login check middleware
+/// Sa-Token 访问检查中间件 | Sa-Token access check middleware
+///
+/// 按 [`SaCheck`] 检查登录、权限或角色，其余检查中间件都基于它实现，拒绝时的状态码和
+/// 响应体与其他框架插件一致。 | Checks login, permissions or roles as described by a
+/// [`SaCheck`]; the other check middlewares are built on it, and rejections match
+/// the other framework plugins.
 ///
-/// 使用标准错误消息，检查当前请求是否已登录 | Uses standard error messages, checks if current request is logged in
+/// # 示例 | Example
+/// ```rust,ignore
+/// let router = Router::new()
+///     .hoop(SaCheckMiddleware::permissions_or(state.clone(), ["order:read", "order:admin"]))
+///     .push(Router::with_path("orders").get(orders));
+/// ```
 #[derive(Clone)]
-pub struct SaCheckLoginMiddleware {
+pub struct SaCheckMiddleware {
     pub state: SaTokenState,
+    check: Arc<SaCheck>,
 }
 
-impl SaCheckLoginMiddleware {
+impl SaCheckMiddleware {
     /// 中文 | English
-    /// 创建新的登录检查中间件 | Create new login check middleware
-    pub fn new(state: SaTokenState) -> Self {
-        Self { state }
+    /// 按指定检查创建中间件 | Create a middleware for the given check
+    pub fn new(state: SaTokenState, check: SaCheck) -> Self {
+        Self { state, check: Arc::new(check) }
+    }
+    
+    /// 中文 | English
+    /// 可选登录：有合法 token 时写入上下文，否则照常放行 | Optional login: fill the context when a valid token is present, never reject
+    pub fn optional(state: SaTokenState) -> Self {
+        Self::new(state, SaCheck::optional())
+    }
+    
+    /// 中文 | English
+    /// 必须拥有全部权限 | All permissions required
+    pub fn permissions_and<I, S>(state: SaTokenState, permissions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::new(state, SaCheck::permissions_and(permissions))
+    }
+    
+    /// 中文 | English
+    /// 拥有任一权限即可 | Any of the permissions
+    pub fn permissions_or<I, S>(state: SaTokenState, permissions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::new(state, SaCheck::permissions_or(permissions))
+    }
+    
+    /// 中文 | English
+    /// 必须拥有全部角色 | All roles required
+    pub fn roles_and<I, S>(state: SaTokenState, roles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::new(state, SaCheck::roles_and(roles))
+    }
+    
+    /// 中文 | English
+    /// 拥有任一角色即可 | Any of the roles
+    pub fn roles_or<I, S>(state: SaTokenState, roles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::new(state, SaCheck::roles_or(roles))
     }
 }
 
 #[salvo::async_trait]
-impl Handler for SaCheckLoginMiddleware {
+impl Handler for SaCheckMiddleware {
     async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
-        let mut ctx = SaTokenContext::new();
+        let ctx = resolve_context(req, depot, &self.state).await;
         
-        if let Some(token_str) = extract_token_from_request(req, &self.state) {
-            tracing::debug!("Sa-Token(login-check): extracted token from request: {}", token_str);
-            let token = TokenValue::new(token_str);
-            
-            if self.state.manager.is_valid(&token).await {
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                    let login_id = token_info.login_id.clone();
-                    depot.insert("sa_token", token.clone());
-                    depot.insert("sa_login_id", login_id.clone());
-                    
-                    ctx.token = Some(token.clone());
-                    ctx.token_info = Some(Arc::new(token_info));
-                    ctx.login_id = Some(login_id);
-                    
-                    SaTokenContext::set_current(ctx);
-                    ctrl.call_next(req, depot, res).await;
-                    SaTokenContext::clear();
-                    return;
-                }
-            }
+        // 检查在上下文中执行，受限 token 等拒绝原因由上下文给出
+        if let Err(e) = SaTokenContext::scope(ctx.clone(), self.check.check(ctx.login_id.as_deref())).await {
+            tracing::debug!("Sa-Token: {} rejected: {}", self.check.stage(), e);
+            write_rejection(res, self.state.manager.render_error(&e));
+            ctrl.skip_rest();
+            return;
         }
         
-        // 未登录，返回401错误
-        res.status_code(StatusCode::UNAUTHORIZED);
-        res.render(Text::Json(json!({
-            "code": 401,
-            "message": messages::AUTH_ERROR
-        }).to_string()));
-        ctrl.skip_rest();
+        SaTokenContext::scope(ctx, ctrl.call_next(req, depot, res)).await;
     }
 }
 
 /// 中文 | English
-/// Sa-Token 权限检查中间件 | Sa-Token permission check middleware
+/// Same-Token 校验中间件 | Same-Token middleware
 ///
-/// 检查当前请求用户是否拥有指定权限 | Checks if current request user has specified permission
+/// 要求请求携带有效的 `sa-same-token` 请求头，用于只允许内部服务访问的路由 |
+/// Requires a valid `sa-same-token` header on internal-only routes
+///
+/// # 示例 | Example
+/// ```rust,ignore
+/// let router = Router::with_path("internal")
+///     .hoop(SaSameTokenMiddleware::new(state.manager.same_token_manager()))
+///     .push(Router::with_path("users/<id>").get(load_user));
+/// ```
 #[derive(Clone)]
-pub struct SaCheckPermissionMiddleware {
-    pub state: SaTokenState,
-    permission: String,
+pub struct SaSameTokenMiddleware {
+    same: Arc<SameTokenManager>,
 }
 
-impl SaCheckPermissionMiddleware {
+impl SaSameTokenMiddleware {
     /// 中文 | English
-    /// 创建新的权限检查中间件 | Create new permission check middleware
-    pub fn new(state: SaTokenState, permission: impl Into<String>) -> Self {
-        Self { state, permission: permission.into() }
+    /// 创建 Same-Token 校验中间件 | Create Same-Token middleware
+    pub fn new(same: SameTokenManager) -> Self {
+        Self { same: Arc::new(same) }
     }
 }
 
 #[salvo::async_trait]
-impl Handler for SaCheckPermissionMiddleware {
+impl Handler for SaSameTokenMiddleware {
     async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
-        let mut ctx = SaTokenContext::new();
+        let token = req.headers().get(SAME_TOKEN_HEADER).and_then(|v| v.to_str().ok());
+        if let Err(e) = self.same.check_token(token).await {
+            write_rejection(res, StpUtil::render_error(&e));
+            ctrl.skip_rest();
+            return;
+        }
+        ctrl.call_next(req, depot, res).await;
+    }
+}
+
+/// 中文 | English
+/// 把 `ErrorRenderer` 渲染的拒绝响应写入 Salvo 响应 | Write a rejection rendered by the `ErrorRenderer` into the Salvo response
+pub(crate) fn write_rejection(res: &mut Response, rendered: ErrorResponse) {
+    res.status_code(StatusCode::from_u16(rendered.status).unwrap_or(StatusCode::FORBIDDEN));
+    let _ = res.add_header(CONTENT_TYPE, rendered.content_type, true);
+    for (name, value) in rendered.headers {
+        if let Ok(name) = HeaderName::try_from(name) {
+            let _ = res.add_header(name, value, false);
+        }
+    }
+    let _ = res.write_body(rendered.body);
+}
+
+/// 中文 | English
+/// 为固定检查的中间件生成类型，均委托给 [`SaCheckMiddleware`] | Generate fixed-check middlewares delegating to [`SaCheckMiddleware`]
+macro_rules! check_middleware {
+    ($(#[$doc:meta])* $name:ident) => {
+        $(#[$doc])*
+        #[derive(Clone)]
+        pub struct $name(SaCheckMiddleware);
         
-        if let Some(token_str) = extract_token_from_request(req, &self.state) {
-            tracing::debug!("Sa-Token(permission-check): extracted token from request: {}", token_str);
-            let token = TokenValue::new(token_str);
-            
-            if self.state.manager.is_valid(&token).await {
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                    let login_id = token_info.login_id.clone();
-                    
-                    // 检查权限
-                    if StpUtil::has_permission(&login_id, &self.permission).await {
-                        depot.insert("sa_token", token.clone());
-                        depot.insert("sa_login_id", login_id.clone());
-                        
-                        ctx.token = Some(token.clone());
-                        ctx.token_info = Some(Arc::new(token_info));
-                        ctx.login_id = Some(login_id);
-                        
-                        SaTokenContext::set_current(ctx);
-                        ctrl.call_next(req, depot, res).await;
-                        SaTokenContext::clear();
-                        return;
-                    }
-                }
+        #[salvo::async_trait]
+        impl Handler for $name {
+            async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+                self.0.handle(req, depot, res, ctrl).await
             }
         }
-        
-        // 无权限，返回403错误
-        res.status_code(StatusCode::FORBIDDEN);
-        res.render(Text::Json(json!({
-            "code": 403,
-            "message": messages::PERMISSION_REQUIRED
-        }).to_string()));
-        ctrl.skip_rest();
+    };
+}
+
+check_middleware!(
+    /// 中文 | English
+    /// Sa-Token 登录检查中间件 | Sa-Token login check middleware
+    ///
+    /// 未登录返回 401 | Responds 401 when not logged in
+    SaCheckLoginMiddleware
+);
+
+check_middleware!(
+    /// 中文 | English
+    /// Sa-Token 权限检查中间件 | Sa-Token permission check middleware
+    ///
+    /// 缺少权限返回 403 | Responds 403 when a permission is missing
+    SaCheckPermissionMiddleware
+);
+
+check_middleware!(
+    /// 中文 | English
+    /// Sa-Token 角色检查中间件 | Sa-Token role check middleware
+    ///
+    /// 缺少角色返回 403 | Responds 403 when a role is missing
+    SaCheckRoleMiddleware
+);
+
+impl SaCheckLoginMiddleware {
+    /// 中文 | English
+    /// 创建新的登录检查中间件 | Create new login check middleware
+    pub fn new(state: SaTokenState) -> Self {
+        Self(SaCheckMiddleware::new(state, SaCheck::login()))
     }
 }
 
-/// 中文 | English
-/// Sa-Token 角色检查中间件 | Sa-Token role check middleware
-///
-/// 检查当前请求用户是否拥有指定角色 | Checks if current request user has specified role
-#[derive(Clone)]
-pub struct SaCheckRoleMiddleware {
-    pub state: SaTokenState,
-    role: String,
+impl SaCheckPermissionMiddleware {
+    /// 中文 | English
+    /// 创建新的权限检查中间件 | Create new permission check middleware
+    pub fn new(state: SaTokenState, permission: impl Into<String>) -> Self {
+        Self(SaCheckMiddleware::new(state, SaCheck::permission(permission)))
+    }
+    
+    /// 中文 | English
+    /// 必须拥有全部权限 | All permissions required
+    pub fn and<I, S>(state: SaTokenState, permissions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self(SaCheckMiddleware::permissions_and(state, permissions))
+    }
+    
+    /// 中文 | English
+    /// 拥有任一权限即可 | Any of the permissions
+    pub fn or<I, S>(state: SaTokenState, permissions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self(SaCheckMiddleware::permissions_or(state, permissions))
+    }
 }
 
 impl SaCheckRoleMiddleware {
     /// 中文 | English
     /// 创建新的角色检查中间件 | Create new role check middleware
     pub fn new(state: SaTokenState, role: impl Into<String>) -> Self {
-        Self { state, role: role.into() }
+        Self(SaCheckMiddleware::new(state, SaCheck::role(role)))
+    }
+    
+    /// 中文 | English
+    /// 必须拥有全部角色 | All roles required
+    pub fn and<I, S>(state: SaTokenState, roles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self(SaCheckMiddleware::roles_and(state, roles))
+    }
+    
+    /// 中文 | English
+    /// 拥有任一角色即可 | Any of the roles
+    pub fn or<I, S>(state: SaTokenState, roles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self(SaCheckMiddleware::roles_or(state, roles))
     }
 }
 
-#[salvo::async_trait]
-impl Handler for SaCheckRoleMiddleware {
-    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
-        let mut ctx = SaTokenContext::new();
-        
-        if let Some(token_str) = extract_token_from_request(req, &self.state) {
-            tracing::debug!("Sa-Token(role-check): extracted token from request: {}", token_str);
-            let token = TokenValue::new(token_str);
-            
-            if self.state.manager.is_valid(&token).await {
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                    let login_id = token_info.login_id.clone();
-                    
-                    // 检查角色
-                    if StpUtil::has_role(&login_id, &self.role).await {
-                        depot.insert("sa_token", token.clone());
-                        depot.insert("sa_login_id", login_id.clone());
-                        
-                        ctx.token = Some(token.clone());
-                        ctx.token_info = Some(Arc::new(token_info));
-                        ctx.login_id = Some(login_id);
-                        
-                        SaTokenContext::set_current(ctx);
-                        ctrl.call_next(req, depot, res).await;
-                        SaTokenContext::clear();
-                        return;
-                    }
-                }
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use salvo::test::{ResponseExt, TestClient};
+    use sa_token_core::{token::TokenValue, SaTokenConfig, TokenInfo, PASSWORD_CHANGE_SCOPE};
+    use sa_token_test_utils::state::{state, state_with};
+    use crate::SaTokenLayer;
+
+    #[handler]
+    async fn profile(depot: &mut Depot) -> String {
+        depot.get::<String>("sa_login_id").cloned().unwrap_or_else(|_| "anonymous".to_string())
+    }
+
+    fn service(state: &SaTokenState) -> Service {
+        Service::new(Router::new()
+            .push(Router::with_path("checked").hoop(SaCheckLoginMiddleware::new(state.clone())).get(profile))
+            .push(Router::with_path("resolved").hoop(SaTokenLayer::new(state.clone())).get(profile)))
+    }
+
+    /// 通过 `TestClient` 访问服务，token 放在 `sa-token` 请求头 | Calls the service through `TestClient` with the token in the `sa-token` header
+    async fn call(service: &Service, path: &str, token: &TokenValue) -> (StatusCode, String) {
+        let mut response = TestClient::get(format!("http://127.0.0.1{}", path))
+            .add_header("sa-token", token.as_str(), true)
+            .send(service)
+            .await;
+        (response.status_code.unwrap_or(StatusCode::OK), response.take_string().await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_restricted_token_is_rejected_on_login_routes() {
+        let state = state();
+        let mut token_info = TokenInfo::new(TokenValue::new(""), "user_1");
+        token_info.scope = Some(vec![PASSWORD_CHANGE_SCOPE.to_string()]);
+        let restricted = state.manager.login_with_token_info(token_info).await.unwrap();
+        let token = state.manager.login("user_2").await.unwrap();
+        let service = service(&state);
+
+        assert_eq!(call(&service, "/checked", &token).await, (StatusCode::OK, "user_2".to_string()));
+        let (status, body) = call(&service, "/checked", &restricted).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(body.contains(&SaTokenError::PasswordExpired.to_string()));
+
+        // 认证层不为受限 token 写入 login_id
+        assert_eq!(call(&service, "/resolved", &restricted).await, (StatusCode::OK, "anonymous".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_replaced_and_kicked_out_tokens_are_rejected_with_reason() {
+        let config = SaTokenConfig { is_concurrent: false, ..Default::default() };
+        let state = state_with(config);
+        let replaced = state.manager.login("user_1").await.unwrap();
+        let current = state.manager.login("user_1").await.unwrap();
+        state.manager.kick_out_with_reason("user_1", "Signed in elsewhere").await.unwrap();
+
+        let (status, body) = call(&service(&state), "/checked", &replaced).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(body.contains(&SaTokenError::Replaced.to_string()));
+
+        let (status, body) = call(&service(&state), "/checked", &current).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(body.contains(&SaTokenError::KickedOut("Signed in elsewhere".to_string()).to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_layer_accepts_configured_schemes() {
+        let config = SaTokenConfig { token_schemes: vec!["Bearer".into(), "Token".into()], ..Default::default() };
+        let state = state_with(config);
+        let token = state.manager.login("user_1").await.unwrap();
+        let service = service(&state);
+        let call = |value: String| TestClient::get("http://127.0.0.1/resolved").add_header("Authorization", value, true).send(&service);
+
+        for value in [format!("bearer {}", token), format!("TOKEN  {}", token), format!(" Bearer\t{} ", token)] {
+            assert_eq!(call(value).await.take_string().await.unwrap(), "user_1");
         }
-        
-        // 无角色权限，返回403错误
-        res.status_code(StatusCode::FORBIDDEN);
-        res.render(Text::Json(json!({
-            "code": 403,
-            "message": messages::ROLE_REQUIRED
-        }).to_string()));
-        ctrl.skip_rest();
+        assert_eq!(call(format!("Basic {}", token)).await.take_string().await.unwrap(), "anonymous");
     }
-}
\ No newline at end of file
+}
@@ -72,6 +72,13 @@ impl<'a, State> SaRequest for TideRequestAdapter<'a, State> {
     fn get_method(&self) -> String {
         self.request.method().to_string()
     }
+    
+    fn remote_addr(&self) -> Option<String> {
+        // tide 以 `ip:port` 字符串给出对端地址 | tide reports the peer as an `ip:port` string
+        self.request.peer_addr()
+            .and_then(|addr| addr.parse::<std::net::SocketAddr>().ok())
+            .map(|addr| addr.ip().to_string())
+    }
 }
 
 /// 中文 | English
@@ -100,8 +107,9 @@ impl SaResponse for TideResponseAdapter {
     }
     
     fn set_cookie(&mut self, name: &str, value: &str, options: CookieOptions) {
+        // 追加而不是覆盖，保留处理函数已设置的其他 Cookie
         let cookie_string = build_cookie_string(name, value, options);
-        self.set_header("Set-Cookie", &cookie_string);
+        self.response.append_header("Set-Cookie", cookie_string);
     }
     
     fn set_status(&mut self, status: u16) {
@@ -1,6 +1,6 @@
-use tide::{Request, Response, StatusCode};
-use sa_token_core::{token::TokenValue, error::messages};
-use serde_json::json;
+use tide::{Request, Response};
+use sa_token_core::{token::TokenValue, error::messages, SaTokenError, StpUtil};
+use crate::middleware::rejection_response;
 
 /// 中文: 认证错误 | English: Authentication error
 #[derive(Debug)]
@@ -17,20 +17,14 @@ impl AuthError {
         messages::AUTH_ERROR
     }
     
-    /// 中文: 转换为 JSON 字符串 | English: Convert to JSON string
+    /// 中文: 按配置的 `ErrorRenderer` 渲染的响应体 | English: Body rendered by the configured `ErrorRenderer`
     pub fn to_json(&self) -> String {
-        json!({
-            "code": 401,
-            "message": self.message()
-        }).to_string()
+        StpUtil::render_error(&SaTokenError::NotLogin).body
     }
     
     /// 中文: 转换为 Response | English: Convert to Response
     pub fn to_response(&self) -> Response {
-        let mut res = Response::new(StatusCode::Unauthorized);
-        res.set_body(self.to_json());
-        res.set_content_type("application/json");
-        res
+        rejection_response(StpUtil::render_error(&SaTokenError::NotLogin))
     }
 }
 
@@ -4,12 +4,12 @@
 // Tide 认证中间件 | Tide authentication middleware
 
 use tide::{Middleware, Request, Response, Next, StatusCode};
-use sa_token_core::{StpUtil, error::messages, SaTokenContext, token::TokenValue};
+use sa_token_core::{StpUtil, AuthzKind, SaTokenContext, ACCEPT_LANGUAGE_HEADER, TIMEZONE_HEADER, SAME_TOKEN_HEADER, token::TokenValue, ErrorResponse, SaTokenError, SameTokenManager};
 use async_trait::async_trait;
 use crate::state::SaTokenState;
 use crate::layer::extract_token_from_request;
+use crate::adapter::TideRequestAdapter;
 use std::sync::Arc;
-use serde_json::json;
 
 /// 中文 | English
 /// 认证中间件 - 验证用户登录状态 | Authentication middleware - verify user login status
@@ -33,30 +33,36 @@ impl<State: Clone + Send + Sync + 'static> Middleware<State> for AuthMiddleware
         // 从请求头中获取 token | Get token from request headers
         let token = req
             .header("Authorization")
-            .and_then(|v| v.as_str().strip_prefix("Bearer "))
-            .map(|s| s.to_string());
+            .map(|v| StpUtil::config().strip_token_prefix(v.as_str()).to_string())
+            .filter(|token| !token.is_empty());
         
         if let Some(token_str) = token {
             // 中文 | English
             // 验证 token 是否有效 | Verify if token is valid
             use sa_token_core::TokenValue;
             let token_value = TokenValue::from(token_str.clone());
-            if StpUtil::is_login(&token_value).await {
+            match StpUtil::get_token_info(&token_value).await {
                 // 中文 | English
                 // Token 有效，将 login_id 存入扩展数据 | Token valid, store login_id in extensions
-                if let Ok(login_id) = StpUtil::get_login_id(&token_value).await {
-                    req.set_ext(login_id);
+                Ok(token_info) if !token_info.is_restricted() => {
+                    req.set_ext(token_info.login_id);
                     return Ok(next.run(req).await);
                 }
+                // 中文 | English
+                // 受限 token（如密码过期）只能访问对应接口 | Restricted tokens (e.g. password expired) only reach their own routes
+                Ok(_) => return Ok(rejection_response(StpUtil::render_error(&SaTokenError::PasswordExpired))),
+                // 中文 | English
+                // 校验失败时返回具体原因（被踢、被顶替等）| Report why validation failed (kicked out, replaced, ...)
+                Err(e) => {
+                    let err = e.clone_auth_error().unwrap_or(SaTokenError::NotLogin);
+                    return Ok(rejection_response(StpUtil::render_error(&err)));
+                }
             }
         }
         
         // 中文 | English
         // Token 无效，返回 401 | Token invalid, return 401
-        let mut res = Response::new(StatusCode::Unauthorized);
-        res.set_body(r#"{"error":"Unauthorized"}"#);
-        res.set_content_type("application/json");
-        Ok(res)
+        Ok(rejection_response(StpUtil::render_error(&SaTokenError::NotLogin)))
     }
 }
 
@@ -94,14 +100,13 @@ impl<State: Clone + Send + Sync + 'static> Middleware<State> for PermissionMiddl
             if StpUtil::has_permission(login_id, &self.permission).await {
                 return Ok(next.run(req).await);
             }
+            StpUtil::record_denial(AuthzKind::Permission, &self.permission);
         }
         
         // 中文 | English
         // 无权限，返回 403 | No permission, return 403
-        let mut res = Response::new(StatusCode::Forbidden);
-        res.set_body(r#"{"error":"Forbidden"}"#);
-        res.set_content_type("application/json");
-        Ok(res)
+        let err = SaTokenError::PermissionDeniedDetail(self.permission.clone());
+        Ok(rejection_response(StpUtil::render_error(&err)))
     }
 }
 
@@ -126,37 +131,34 @@ impl SaCheckLoginMiddleware {
 impl<State: Clone + Send + Sync + 'static> Middleware<State> for SaCheckLoginMiddleware {
     async fn handle(&self, mut req: Request<State>, next: Next<'_, State>) -> tide::Result {
         let mut ctx = SaTokenContext::new();
+        let header = |name: &str| req.header(name).map(|v| v.as_str());
+        ctx.capture_locale(header(ACCEPT_LANGUAGE_HEADER), header(TIMEZONE_HEADER));
+        ctx.capture_same_token(header(SAME_TOKEN_HEADER));
+        ctx.capture_client(self.state.manager.client_ip(&TideRequestAdapter::new(&req)).as_deref(), header("user-agent"));
         
         if let Some(token_str) = extract_token_from_request(&req, &self.state) {
             tracing::debug!("Sa-Token(login-check): extracted token from request: {}", token_str);
             let token = TokenValue::new(token_str);
             
-            if self.state.manager.is_valid(&token).await {
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                    let login_id = token_info.login_id.clone();
-                    req.set_ext(token.clone());
-                    req.set_ext(login_id.clone());
-                    
-                    ctx.token = Some(token.clone());
-                    ctx.token_info = Some(Arc::new(token_info));
-                    ctx.login_id = Some(login_id);
-                    
-                    SaTokenContext::set_current(ctx);
-                    let result = next.run(req).await;
-                    SaTokenContext::clear();
-                    return Ok(result);
-                }
+            if let Some(login_id) = self.state.manager.authenticate_context(&token, &mut ctx).await {
+                req.set_ext(token);
+                req.set_ext(login_id);
+                
+                let result = SaTokenContext::scope(ctx, next.run(req)).await;
+                return Ok(result);
             }
         }
         
-        // 未登录，返回401错误
-        let mut res = Response::new(StatusCode::Unauthorized);
-        res.set_body(json!({
-            "code": 401,
-            "message": messages::AUTH_ERROR
-        }).to_string());
-        res.set_content_type("application/json");
-        Ok(res)
+        // 开发模式免登录 | Dev bypass
+        if let Some(login_id) = self.state.manager.dev_bypass_login_id() {
+            req.set_ext(login_id.to_string());
+            ctx.login_id = Some(login_id.to_string());
+            let result = SaTokenContext::scope(ctx, next.run(req)).await;
+            return Ok(result);
+        }
+        
+        // 未登录（或为受限 token），返回401错误
+        Ok(rejection_response(self.state.manager.render_error(&ctx.login_error())))
     }
 }
 
@@ -182,41 +184,39 @@ impl SaCheckPermissionMiddleware {
 impl<State: Clone + Send + Sync + 'static> Middleware<State> for SaCheckPermissionMiddleware {
     async fn handle(&self, mut req: Request<State>, next: Next<'_, State>) -> tide::Result {
         let mut ctx = SaTokenContext::new();
+        let header = |name: &str| req.header(name).map(|v| v.as_str());
+        ctx.capture_locale(header(ACCEPT_LANGUAGE_HEADER), header(TIMEZONE_HEADER));
+        ctx.capture_same_token(header(SAME_TOKEN_HEADER));
+        ctx.capture_client(self.state.manager.client_ip(&TideRequestAdapter::new(&req)).as_deref(), header("user-agent"));
         
         if let Some(token_str) = extract_token_from_request(&req, &self.state) {
             tracing::debug!("Sa-Token(permission-check): extracted token from request: {}", token_str);
             let token = TokenValue::new(token_str);
             
-            if self.state.manager.is_valid(&token).await {
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                    let login_id = token_info.login_id.clone();
+            if let Some(login_id) = self.state.manager.authenticate_context(&token, &mut ctx).await {
+                // 检查权限
+                if StpUtil::has_permission(&login_id, &self.permission).await {
+                    req.set_ext(token);
+                    req.set_ext(login_id);
                     
-                    // 检查权限
-                    if StpUtil::has_permission(&login_id, &self.permission).await {
-                        req.set_ext(token.clone());
-                        req.set_ext(login_id.clone());
-                        
-                        ctx.token = Some(token.clone());
-                        ctx.token_info = Some(Arc::new(token_info));
-                        ctx.login_id = Some(login_id);
-                        
-                        SaTokenContext::set_current(ctx);
-                        let result = next.run(req).await;
-                        SaTokenContext::clear();
-                        return Ok(result);
-                    }
+                    let result = SaTokenContext::scope(ctx, next.run(req)).await;
+                    return Ok(result);
                 }
+                StpUtil::record_denial(AuthzKind::Permission, &self.permission);
             }
         }
         
+        // 开发模式免登录 | Dev bypass
+        if let Some(login_id) = self.state.manager.dev_bypass_login_id() {
+            req.set_ext(login_id.to_string());
+            ctx.login_id = Some(login_id.to_string());
+            let result = SaTokenContext::scope(ctx, next.run(req)).await;
+            return Ok(result);
+        }
+        
         // 无权限，返回403错误
-        let mut res = Response::new(StatusCode::Forbidden);
-        res.set_body(json!({
-            "code": 403,
-            "message": messages::PERMISSION_REQUIRED
-        }).to_string());
-        res.set_content_type("application/json");
-        Ok(res)
+        let err = SaTokenError::PermissionDeniedDetail(self.permission.clone());
+        Ok(rejection_response(self.state.manager.render_error(&err)))
     }
 }
 
@@ -242,40 +242,170 @@ impl SaCheckRoleMiddleware {
 impl<State: Clone + Send + Sync + 'static> Middleware<State> for SaCheckRoleMiddleware {
     async fn handle(&self, mut req: Request<State>, next: Next<'_, State>) -> tide::Result {
         let mut ctx = SaTokenContext::new();
+        let header = |name: &str| req.header(name).map(|v| v.as_str());
+        ctx.capture_locale(header(ACCEPT_LANGUAGE_HEADER), header(TIMEZONE_HEADER));
+        ctx.capture_same_token(header(SAME_TOKEN_HEADER));
+        ctx.capture_client(self.state.manager.client_ip(&TideRequestAdapter::new(&req)).as_deref(), header("user-agent"));
         
         if let Some(token_str) = extract_token_from_request(&req, &self.state) {
             tracing::debug!("Sa-Token(role-check): extracted token from request: {}", token_str);
             let token = TokenValue::new(token_str);
             
-            if self.state.manager.is_valid(&token).await {
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                    let login_id = token_info.login_id.clone();
+            if let Some(login_id) = self.state.manager.authenticate_context(&token, &mut ctx).await {
+                // 检查角色
+                if StpUtil::has_role(&login_id, &self.role).await {
+                    req.set_ext(token);
+                    req.set_ext(login_id);
                     
-                    // 检查角色
-                    if StpUtil::has_role(&login_id, &self.role).await {
-                        req.set_ext(token.clone());
-                        req.set_ext(login_id.clone());
-                        
-                        ctx.token = Some(token.clone());
-                        ctx.token_info = Some(Arc::new(token_info));
-                        ctx.login_id = Some(login_id);
-                        
-                        SaTokenContext::set_current(ctx);
-                        let result = next.run(req).await;
-                        SaTokenContext::clear();
-                        return Ok(result);
-                    }
+                    let result = SaTokenContext::scope(ctx, next.run(req)).await;
+                    return Ok(result);
                 }
+                StpUtil::record_denial(AuthzKind::Role, &self.role);
             }
         }
         
+        // 开发模式免登录 | Dev bypass
+        if let Some(login_id) = self.state.manager.dev_bypass_login_id() {
+            req.set_ext(login_id.to_string());
+            ctx.login_id = Some(login_id.to_string());
+            let result = SaTokenContext::scope(ctx, next.run(req)).await;
+            return Ok(result);
+        }
+        
         // 无角色权限，返回403错误
-        let mut res = Response::new(StatusCode::Forbidden);
-        res.set_body(json!({
-            "code": 403,
-            "message": messages::ROLE_REQUIRED
-        }).to_string());
-        res.set_content_type("application/json");
-        Ok(res)
+        let err = SaTokenError::RoleDenied(self.role.clone());
+        Ok(rejection_response(self.state.manager.render_error(&err)))
+    }
+}
+
+/// 中文 | English
+/// Same-Token 校验中间件 | Same-Token middleware
+///
+/// 要求请求携带有效的 `sa-same-token` 请求头，用于只允许内部服务访问的路由 |
+/// Requires a valid `sa-same-token` header on internal-only routes
+///
+/// # 示例 | Example
+/// ```rust,ignore
+/// app.at("/internal/users/:id")
+///     .with(SaSameTokenMiddleware::new(state.manager.same_token_manager()))
+///     .get(load_user);
+/// ```
+#[derive(Clone)]
+pub struct SaSameTokenMiddleware {
+    same: Arc<SameTokenManager>,
+}
+
+impl SaSameTokenMiddleware {
+    /// 中文 | English
+    /// 创建 Same-Token 校验中间件 | Create Same-Token middleware
+    pub fn new(same: SameTokenManager) -> Self {
+        Self { same: Arc::new(same) }
     }
-}
\ No newline at end of file
+}
+
+#[async_trait]
+impl<State: Clone + Send + Sync + 'static> Middleware<State> for SaSameTokenMiddleware {
+    async fn handle(&self, req: Request<State>, next: Next<'_, State>) -> tide::Result {
+        let token = req.header(SAME_TOKEN_HEADER).map(|v| v.as_str());
+        if let Err(err) = self.same.check_token(token).await {
+            return Ok(rejection_response(StpUtil::render_error(&err)));
+        }
+        Ok(next.run(req).await)
+    }
+}
+
+/// 中文 | English
+/// 把 `ErrorRenderer` 渲染的拒绝响应转换为 Tide 响应 | Convert a rejection rendered by the `ErrorRenderer` into a Tide response
+pub(crate) fn rejection_response(rendered: ErrorResponse) -> Response {
+    let status = StatusCode::try_from(rendered.status).unwrap_or(StatusCode::Forbidden);
+    let mut res = Response::new(status);
+    for (name, value) in &rendered.headers {
+        res.append_header(name.as_str(), value.as_str());
+    }
+    res.set_body(rendered.body);
+    if let Ok(mime) = rendered.content_type.parse::<tide::http::Mime>() {
+        res.set_content_type(mime);
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_core::{SaTokenConfig, TokenInfo, PASSWORD_CHANGE_SCOPE};
+    use sa_token_test_utils::state::{state, state_with};
+    use tide::http::{Method, Url};
+    use crate::SaTokenLayer;
+
+    fn app(state: &SaTokenState) -> tide::Server<()> {
+        let mut app = tide::new();
+        app.at("/profile")
+            .with(SaCheckLoginMiddleware::new(state.clone()))
+            .get(|req: Request<()>| async move { Ok(req.ext::<String>().cloned().unwrap_or_default()) });
+        app.at("/me")
+            .with(SaTokenLayer::new(state.clone()))
+            .get(|req: Request<()>| async move { Ok(req.ext::<String>().cloned().unwrap_or_else(|| "anonymous".to_string())) });
+        app
+    }
+
+    async fn call(app: &tide::Server<()>, path: &str, token: &TokenValue) -> (StatusCode, String) {
+        let mut request = tide::http::Request::new(Method::Get, Url::parse(&format!("http://localhost{}", path)).unwrap());
+        request.insert_header("sa-token", token.as_str());
+        let mut response: tide::http::Response = app.respond(request).await.unwrap();
+        (response.status(), response.body_string().await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_restricted_token_is_rejected_on_login_routes() {
+        let state = state();
+        let mut token_info = TokenInfo::new(TokenValue::new(""), "user_1");
+        token_info.scope = Some(vec![PASSWORD_CHANGE_SCOPE.to_string()]);
+        let restricted = state.manager.login_with_token_info(token_info).await.unwrap();
+        let token = state.manager.login("user_2").await.unwrap();
+        let app = app(&state);
+
+        assert_eq!(call(&app, "/profile", &token).await, (StatusCode::Ok, "user_2".to_string()));
+        let (status, body) = call(&app, "/profile", &restricted).await;
+        assert_eq!(status, StatusCode::Unauthorized);
+        assert!(body.contains(&SaTokenError::PasswordExpired.to_string()));
+
+        // 认证层不为受限 token 写入 login_id
+        assert_eq!(call(&app, "/me", &token).await, (StatusCode::Ok, "user_2".to_string()));
+        assert_eq!(call(&app, "/me", &restricted).await, (StatusCode::Ok, "anonymous".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_replaced_and_kicked_out_tokens_are_rejected_with_reason() {
+        let config = SaTokenConfig { is_concurrent: false, ..Default::default() };
+        let state = state_with(config);
+        let replaced = state.manager.login("user_1").await.unwrap();
+        let current = state.manager.login("user_1").await.unwrap();
+        state.manager.kick_out_with_reason("user_1", "Signed in elsewhere").await.unwrap();
+
+        let (status, body) = call(&app(&state), "/profile", &replaced).await;
+        assert_eq!(status, StatusCode::Unauthorized);
+        assert!(body.contains(&SaTokenError::Replaced.to_string()));
+
+        let (status, body) = call(&app(&state), "/profile", &current).await;
+        assert_eq!(status, StatusCode::Unauthorized);
+        assert!(body.contains(&SaTokenError::KickedOut("Signed in elsewhere".to_string()).to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_layer_accepts_configured_schemes() {
+        let config = SaTokenConfig { token_schemes: vec!["Bearer".into(), "Token".into()], ..Default::default() };
+        let state = state_with(config);
+        let token = state.manager.login("user_1").await.unwrap();
+        let app = app(&state);
+        let call = |value: String| {
+            let mut request = tide::http::Request::new(Method::Get, Url::parse("http://localhost/me").unwrap());
+            request.insert_header("Authorization", value);
+            app.respond::<_, tide::http::Response>(request)
+        };
+
+        for value in [format!("bearer {}", token), format!("TOKEN  {}", token), format!(" Bearer\t{} ", token)] {
+            assert_eq!(call(value).await.unwrap().body_string().await.unwrap(), "user_1");
+        }
+        assert_eq!(call(format!("Basic {}", token)).await.unwrap().body_string().await.unwrap(), "anonymous");
+    }
+}
@@ -3,6 +3,7 @@
 // 中文 | English
 // Warp 请求/响应适配器 | Warp request/response adapters
 
+use std::net::SocketAddr;
 use warp::http::{HeaderMap, Response, StatusCode};
 use warp::hyper::body::Bytes;
 use sa_token_adapter::{SaRequest, SaResponse, CookieOptions, build_cookie_string, utils};
@@ -13,13 +14,21 @@ use serde::Serialize;
 pub struct WarpRequestAdapter<'a> {
     headers: &'a HeaderMap,
     query: &'a str,
+    remote_addr: Option<SocketAddr>,
 }
 
 impl<'a> WarpRequestAdapter<'a> {
     /// 中文 | English
     /// 创建新的请求适配器 | Create a new request adapter
     pub fn new(headers: &'a HeaderMap, query: &'a str) -> Self {
-        Self { headers, query }
+        Self { headers, query, remote_addr: None }
+    }
+    
+    /// 中文 | English
+    /// 设置连接对端地址（`warp::addr::remote()`）| Set the peer address (`warp::addr::remote()`)
+    pub fn with_remote_addr(mut self, remote_addr: Option<SocketAddr>) -> Self {
+        self.remote_addr = remote_addr;
+        self
     }
 }
 
@@ -59,10 +68,8 @@ impl<'a> SaRequest for WarpRequestAdapter<'a> {
         String::new()
     }
     
-    fn get_client_ip(&self) -> Option<String> {
-        // 从常见的代理头中获取 IP | Get IP from common proxy headers
-        self.get_header("x-forwarded-for")
-            .or_else(|| self.get_header("x-real-ip"))
+    fn remote_addr(&self) -> Option<String> {
+        self.remote_addr.map(|addr| addr.ip().to_string())
     }
 }
 
@@ -3,9 +3,12 @@
 // 中文 | English
 // Warp 提取器 | Warp extractors
 
-use sa_token_core::{token::TokenValue, error::messages};
+use sa_token_core::{token::TokenValue, error::messages, ErrorResponse, SaTokenError, StpUtil};
+use warp::http::header::{HeaderName, HeaderValue, CONTENT_TYPE};
+use warp::http::StatusCode;
 use warp::reject::Reject;
 use serde_json::json;
+use crate::filter::{SameTokenError, UnauthorizedError};
 
 /// 中文 | English
 /// 认证错误 | Authentication error
@@ -26,12 +29,15 @@ impl AuthError {
     }
     
     /// 中文 | English
-    /// 转换为 JSON 字符串 | Convert to JSON string
+    /// 对应的 Sa-Token 错误 | Matching Sa-Token error
+    pub fn to_error(&self) -> SaTokenError {
+        SaTokenError::NotLogin
+    }
+    
+    /// 中文 | English
+    /// 按配置的 `ErrorRenderer` 渲染的响应体 | Body rendered by the configured `ErrorRenderer`
     pub fn to_json(&self) -> String {
-        json!({
-            "code": 401,
-            "message": self.message()
-        }).to_string()
+        StpUtil::render_error(&self.to_error()).body
     }
 }
 
@@ -56,12 +62,15 @@ impl PermissionError {
     }
     
     /// 中文 | English
-    /// 转换为 JSON 字符串 | Convert to JSON string
+    /// 对应的 Sa-Token 错误 | Matching Sa-Token error
+    pub fn to_error(&self) -> SaTokenError {
+        SaTokenError::PermissionsDenied(Vec::new())
+    }
+    
+    /// 中文 | English
+    /// 按配置的 `ErrorRenderer` 渲染的响应体 | Body rendered by the configured `ErrorRenderer`
     pub fn to_json(&self) -> String {
-        json!({
-            "code": 403,
-            "message": self.message()
-        }).to_string()
+        StpUtil::render_error(&self.to_error()).body
     }
 }
 
@@ -86,12 +95,15 @@ impl RoleError {
     }
     
     /// 中文 | English
-    /// 转换为 JSON 字符串 | Convert to JSON string
+    /// 对应的 Sa-Token 错误 | Matching Sa-Token error
+    pub fn to_error(&self) -> SaTokenError {
+        SaTokenError::RolesDenied(Vec::new())
+    }
+    
+    /// 中文 | English
+    /// 按配置的 `ErrorRenderer` 渲染的响应体 | Body rendered by the configured `ErrorRenderer`
     pub fn to_json(&self) -> String {
-        json!({
-            "code": 403,
-            "message": self.message()
-        }).to_string()
+        StpUtil::render_error(&self.to_error()).body
     }
 }
 
@@ -136,22 +148,45 @@ impl LoginIdExtractor {
 /// 中文 | English
 /// 处理 Warp 拒绝 | Handle Warp rejection
 ///
-/// 将 Sa-Token 错误转换为 HTTP 响应 | Convert Sa-Token errors to HTTP responses
+/// 将 Sa-Token 错误经配置的 `ErrorRenderer` 转换为 HTTP 响应 | Convert Sa-Token errors to HTTP responses with the configured `ErrorRenderer`
 pub async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply, std::convert::Infallible> {
-    let (code, message) = if err.is_not_found() {
-        (404, json!({"code": 404, "message": "Not Found"}).to_string())
+    let error = if err.is_not_found() {
+        return Ok(json_reply(404, json!({"code": 404, "message": "Not Found"})));
     } else if let Some(auth_error) = err.find::<AuthError>() {
-        (401, auth_error.to_json())
+        auth_error.to_error()
+    } else if let Some(UnauthorizedError(error)) = err.find::<UnauthorizedError>() {
+        return Ok(rejection_reply(StpUtil::render_error(error)));
+    } else if err.find::<SameTokenError>().is_some() {
+        SaTokenError::InvalidSameToken
     } else if let Some(perm_error) = err.find::<PermissionError>() {
-        (403, perm_error.to_json())
+        perm_error.to_error()
     } else if let Some(role_error) = err.find::<RoleError>() {
-        (403, role_error.to_json())
+        role_error.to_error()
     } else {
-        (500, json!({"code": 500, "message": "Internal Server Error"}).to_string())
+        return Ok(json_reply(500, json!({"code": 500, "message": "Internal Server Error"})));
     };
     
-    Ok(warp::reply::with_status(
-        warp::reply::json(&serde_json::from_str::<serde_json::Value>(&message).unwrap_or_default()),
-        warp::http::StatusCode::from_u16(code).unwrap_or(warp::http::StatusCode::INTERNAL_SERVER_ERROR)
-    ))
-}
\ No newline at end of file
+    Ok(rejection_reply(StpUtil::render_error(&error)))
+}
+
+fn json_reply(status: u16, body: serde_json::Value) -> warp::reply::Response {
+    rejection_reply(ErrorResponse::json(status, &body))
+}
+
+/// 中文 | English
+/// 把渲染后的拒绝响应转换为 Warp 响应 | Convert a rendered rejection into a Warp response
+fn rejection_reply(rendered: ErrorResponse) -> warp::reply::Response {
+    let mut response = warp::reply::Response::new(rendered.body.into());
+    *response.status_mut() = StatusCode::from_u16(rendered.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    
+    let headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(&rendered.content_type) {
+        headers.insert(CONTENT_TYPE, value);
+    }
+    for (name, value) in &rendered.headers {
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(name.as_str()), HeaderValue::from_str(value)) {
+            headers.append(name, value);
+        }
+    }
+    response
+}
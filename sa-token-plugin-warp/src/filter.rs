@@ -2,15 +2,43 @@
 //
 //! Warp Filter (中间件)
 
-use warp::{Filter, Rejection, http::HeaderMap};
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use warp::{Filter, Rejection, Reply, http::HeaderMap};
+use warp::http::header::{HeaderValue, SET_COOKIE};
 use crate::SaTokenState;
-use sa_token_core::token::TokenValue;
+use crate::adapter::WarpRequestAdapter;
+use sa_token_adapter::SaRequest;
+use sa_token_core::{token::{TokenInfo, TokenValue}, SaTokenContext, SaTokenError, SameTokenManager, SAME_TOKEN_HEADER};
 
 /// Token 数据，存储在请求中
 #[derive(Clone)]
 pub struct TokenData {
     pub token: Option<TokenValue>,
     pub login_id: Option<String>,
+    /// token 信息（受限 token 也会写入，但没有 `login_id`）| Token info, also set for restricted tokens, which get no `login_id`
+    pub token_info: Option<Arc<TokenInfo>>,
+    /// token 校验失败的原因（被踢、被顶替、IP 不一致等）| Why token validation failed (kicked out, replaced, IP mismatch, ...)
+    pub auth_error: Option<Arc<SaTokenError>>,
+    /// 请求来源 IP | Client IP of the request
+    pub client_ip: Option<String>,
+    /// 请求的 User-Agent | User-Agent of the request
+    pub user_agent: Option<String>,
+}
+
+impl TokenData {
+    /// 中文 | English
+    /// 由过滤结果构建请求上下文 | Build the request context from the filter result
+    pub fn context(&self) -> SaTokenContext {
+        let mut ctx = SaTokenContext::new();
+        ctx.capture_client(self.client_ip.as_deref(), self.user_agent.as_deref());
+        ctx.token = self.token.clone();
+        ctx.token_info = self.token_info.clone();
+        ctx.auth_error = self.auth_error.clone();
+        ctx.login_id = self.login_id.clone();
+        ctx
+    }
 }
 
 /// sa-token 基础过滤器 - 提取并验证 token
@@ -19,10 +47,10 @@ pub fn sa_token_filter(
 ) -> impl Filter<Extract = (TokenData,), Error = Rejection> + Clone {
     warp::any()
         .and(warp::header::headers_cloned())
-        .and(warp::cookie::optional::<String>("satoken"))
-        .and(warp::query::<std::collections::HashMap<String, String>>().or_else(|_| async {
-            Ok::<(std::collections::HashMap<String, String>,), Rejection>((std::collections::HashMap::new(),))
+        .and(warp::query::raw().or_else(|_| async {
+            Ok::<(String,), Rejection>((String::new(),))
         }))
+        .and(warp::addr::remote())
         .and(warp::any().map(move || state.clone()))
         .and_then(extract_and_validate_token)
 }
@@ -33,68 +61,174 @@ pub fn sa_check_login_filter(
 ) -> impl Filter<Extract = (TokenData,), Error = Rejection> + Clone {
     sa_token_filter(state)
         .and_then(|token_data: TokenData| async move {
-            if token_data.token.is_some() && token_data.login_id.is_some() {
+            if token_data.login_id.is_some() {
                 Ok(token_data)
             } else {
-                Err(warp::reject::custom(UnauthorizedError))
+                Err(warp::reject::custom(UnauthorizedError(token_data.context().login_error())))
             }
         })
 }
 
+/// Same-Token 过滤器 - 要求请求携带有效的 `sa-same-token` 请求头，用于只允许内部服务访问的路由
+/// 
+/// 校验失败时以 `SameTokenError` 拒绝，由 `handle_rejection` 渲染为 401。
+/// 
+/// # 示例
+/// ```rust,ignore
+/// let internal = warp::path!("internal" / "users" / u64)
+///     .and(sa_same_token_filter(state.manager.same_token_manager()))
+///     .and_then(load_user);
+/// ```
+pub fn sa_same_token_filter(
+    same: SameTokenManager,
+) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::header::optional::<String>(SAME_TOKEN_HEADER)
+        .and(warp::any().map(move || same.clone()))
+        .and_then(|token: Option<String>, same: SameTokenManager| async move {
+            same.check_token(token.as_deref()).await
+                .map_err(|_| warp::reject::custom(SameTokenError))
+        })
+        .untuple_one()
+}
+
+/// 在请求上下文中执行处理函数，并写回处理期间产生的 token Cookie（`is_write_cookie`）
+/// 
+/// warp 的过滤器无法包裹处理函数，需要写回 Cookie 的登录/登出接口显式使用。
+/// 
+/// # 示例
+/// ```rust,ignore
+/// let login = warp::path("login")
+///     .and(sa_token_filter(state.clone()))
+///     .then(|token_data: TokenData| with_token_cookie(token_data, async {
+///         let token = StpUtil::login("user_1").await.unwrap();
+///         warp::reply::json(&token.as_str())
+///     }));
+/// ```
+pub async fn with_token_cookie<F, R>(token_data: TokenData, handler: F) -> warp::reply::Response
+where
+    F: Future<Output = R>,
+    R: Reply,
+{
+    let ctx = token_data.context();
+    let response_cookie = ctx.clone();
+    
+    let mut response = SaTokenContext::scope(ctx, handler).await.into_response();
+    if let Some(cookie) = response_cookie.take_response_cookie()
+        && let Ok(value) = HeaderValue::from_str(&cookie.header_value())
+    {
+        response.headers_mut().append(SET_COOKIE, value);
+    }
+    response
+}
+
 /// 提取并验证 token
 async fn extract_and_validate_token(
     headers: HeaderMap,
-    cookie_token: Option<String>,
-    query: std::collections::HashMap<String, String>,
+    query: String,
+    remote: Option<SocketAddr>,
     state: SaTokenState,
 ) -> Result<TokenData, Rejection> {
-    let token_name = &state.manager.config.token_name;
-    
-    // 1. 从 Header 获取
-    let token_str = if let Some(header_val) = headers.get(token_name) {
-        header_val.to_str().ok().map(|s| extract_bearer_token(s))
-    }
-    // 2. 从 Cookie 获取
-    else if let Some(token) = cookie_token {
-        Some(token)
-    }
-    // 3. 从 Query 参数获取
-    else {
-        query.get(token_name).cloned()
-    };
+    let adapter = WarpRequestAdapter::new(&headers, &query).with_remote_addr(remote);
+    let mut ctx = SaTokenContext::new();
+    ctx.capture_client(state.manager.client_ip(&adapter).as_deref(), adapter.get_user_agent().as_deref());
+    let token_str = state.manager
+        .extract_token(&adapter)
+        .map(|(token, _)| token);
     
     if let Some(token_str) = token_str {
-        let token = TokenValue::new(token_str);
-        
-        // 验证 token
-        if state.manager.is_valid(&token).await {
-            // 获取 login_id
-            if let Ok(token_info) = state.manager.get_token_info(&token).await {
-                return Ok(TokenData {
-                    token: Some(token),
-                    login_id: Some(token_info.login_id),
-                });
-            }
-        }
+        state.manager.authenticate_context(&TokenValue::new(token_str), &mut ctx).await;
     }
     
+    // 开发模式免登录 | Dev bypass
+    let login_id = ctx.login_id
+        .or_else(|| state.manager.dev_bypass_login_id().map(|id| id.to_string()));
     Ok(TokenData {
-        token: None,
-        login_id: None,
+        token: ctx.token,
+        login_id,
+        token_info: ctx.token_info,
+        auth_error: ctx.auth_error,
+        client_ip: ctx.client_ip,
+        user_agent: ctx.user_agent,
     })
 }
 
-/// 提取 Bearer token
-fn extract_bearer_token(token: &str) -> String {
-    if token.starts_with("Bearer ") {
-        token[7..].to_string()
-    } else {
-        token.to_string()
-    }
-}
 
-/// 未授权错误
+/// 未授权错误，携带拒绝原因（`NotLogin`、`PasswordExpired` 等）
 #[derive(Debug)]
-pub struct UnauthorizedError;
+pub struct UnauthorizedError(pub SaTokenError);
 
 impl warp::reject::Reject for UnauthorizedError {}
+
+/// Same-Token 缺失或无效
+#[derive(Debug)]
+pub struct SameTokenError;
+
+impl warp::reject::Reject for SameTokenError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_core::{SaTokenConfig, PASSWORD_CHANGE_SCOPE};
+    use sa_token_test_utils::state::{state, state_with};
+    use warp::http::StatusCode;
+    use crate::handle_rejection;
+
+    /// 经过登录检查过滤器与 `handle_rejection` 访问 `/profile` | Hits `/profile` through the login filter and `handle_rejection`
+    async fn call(state: &SaTokenState, token: &TokenValue) -> (StatusCode, String) {
+        let route = warp::path("profile")
+            .and(sa_check_login_filter(state.clone()))
+            .map(|token_data: TokenData| token_data.login_id.unwrap_or_default())
+            .recover(handle_rejection);
+        let response = warp::test::request().path("/profile").header("sa-token", token.as_str()).reply(&route).await;
+        (response.status(), String::from_utf8(response.body().to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_restricted_token_is_rejected_by_login_filter() {
+        let state = state();
+        let mut token_info = TokenInfo::new(TokenValue::new(""), "user_1");
+        token_info.scope = Some(vec![PASSWORD_CHANGE_SCOPE.to_string()]);
+        let restricted = state.manager.login_with_token_info(token_info).await.unwrap();
+        let token = state.manager.login("user_2").await.unwrap();
+
+        assert_eq!(call(&state, &token).await, (StatusCode::OK, "user_2".to_string()));
+
+        let (status, body) = call(&state, &restricted).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(body.contains(&SaTokenError::PasswordExpired.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_replaced_and_kicked_out_tokens_are_rejected_with_reason() {
+        let config = SaTokenConfig { is_concurrent: false, ..Default::default() };
+        let state = state_with(config);
+        let replaced = state.manager.login("user_1").await.unwrap();
+        let current = state.manager.login("user_1").await.unwrap();
+        state.manager.kick_out_with_reason("user_1", "Signed in elsewhere").await.unwrap();
+
+        let (status, body) = call(&state, &replaced).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(body.contains(&SaTokenError::Replaced.to_string()));
+
+        let (status, body) = call(&state, &current).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(body.contains(&SaTokenError::KickedOut("Signed in elsewhere".to_string()).to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_filter_accepts_configured_schemes() {
+        let config = SaTokenConfig { token_schemes: vec!["Bearer".into(), "Token".into()], ..Default::default() };
+        let state = state_with(config);
+        let token = state.manager.login("user_1").await.unwrap();
+        let route = sa_check_login_filter(state.clone())
+            .map(|token_data: TokenData| token_data.login_id.unwrap_or_default())
+            .recover(handle_rejection);
+
+        for value in [format!("bearer {}", token), format!("TOKEN  {}", token), format!(" Bearer\t{} ", token)] {
+            let response = warp::test::request().header("Authorization", value).reply(&route).await;
+            assert_eq!(response.body().as_ref(), b"user_1");
+        }
+        let response = warp::test::request().header("Authorization", format!("Basic {}", token)).reply(&route).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
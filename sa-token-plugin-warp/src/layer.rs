@@ -1,7 +1,7 @@
 use warp::{Filter, Reply, reply};
 use sa_token_core::SaTokenContext;
 use crate::state::SaTokenState;
-use sa_token_adapter::utils::{parse_cookies, parse_query_string, extract_bearer_token as utils_extract_bearer_token};
+use crate::adapter::WarpRequestAdapter;
 
 /// 中文 | English
 /// 创建 Sa-Token 认证层 | Create Sa-Token authentication layer
@@ -28,63 +28,14 @@ pub fn sa_token_cleanup() -> impl Filter<Extract = impl Reply, Error = std::conv
 /// 中文 | English
 /// 从请求中提取 token | Extract token from request
 ///
-/// 按以下顺序尝试提取 token: | Try to extract token in the following order:
-/// 1. 从指定名称的请求头 | From specified header name
-/// 2. 从 Authorization 请求头 | From Authorization header
-/// 3. 从 Cookie | From cookie
-/// 4. 从查询参数 | From query parameter
+/// 先调用自定义提取器，再按 `SaTokenConfig::token_sources` 的顺序查找 |
+/// Custom extractors first, then `SaTokenConfig::token_sources` in order
 pub fn extract_token_from_request(
     headers: &warp::http::HeaderMap, 
     query: &str, 
     state: &SaTokenState
 ) -> Option<String> {
-    let token_name = &state.manager.config.token_name;
-    
-    // 1. 从指定名称的请求头提取 | Extract from specified header name
-    if let Some(header_value) = headers.get(token_name) {
-        if let Ok(value_str) = header_value.to_str() {
-            if !value_str.is_empty() {
-                if let Some(token) = utils_extract_bearer_token(value_str) {
-                    return Some(token);
-                }
-            }
-        }
-    }
-    
-    // 2. 从 Authorization 请求头提取 | Extract from Authorization header
-    if let Some(auth_header) = headers.get("authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if !auth_str.is_empty() {
-                if let Some(token) = utils_extract_bearer_token(auth_str) {
-                    return Some(token);
-                }
-            }
-        }
-    }
-    
-    // 3. 从 Cookie 提取 | Extract from cookie
-    if let Some(cookie_header) = headers.get("cookie") {
-        if let Ok(cookie_str) = cookie_header.to_str() {
-            let cookies = parse_cookies(cookie_str);
-            if let Some(token) = cookies.get(token_name) {
-                if !token.is_empty() {
-                    return Some(token.to_string());
-                }
-            }
-        }
-    }
-    
-    // 4. 从查询参数提取 | Extract from query parameter
-    if !query.is_empty() {
-        let params = parse_query_string(query);
-        if let Some(token) = params.get(token_name) {
-            if !token.is_empty() {
-                return Some(token.to_string());
-            }
-        }
-    }
-    
-    None
+    state.manager.extract_token(&WarpRequestAdapter::new(headers, query)).map(|(token, _)| token)
 }
 
 /// 中文 | English
@@ -76,7 +76,7 @@ pub mod filter;
 // ============================================================================
 // Warp 框架集成（本插件特有） | Warp framework integration (plugin specific)
 // ============================================================================
-pub use filter::{sa_token_filter, sa_check_login_filter};
+pub use filter::{sa_token_filter, sa_check_login_filter, sa_same_token_filter, with_token_cookie, TokenData, SameTokenError};
 pub use layer::{sa_token_layer, sa_token_cleanup, sa_check_login, sa_check_permission, sa_check_role, extract_token_from_request};
 pub use middleware::{with_auth, with_permission, with_role, require_auth, require_permission, require_role};
 pub use extractor::{SaTokenExtractor, OptionalSaTokenExtractor, LoginIdExtractor, AuthError, PermissionError, RoleError, handle_rejection};
@@ -110,13 +110,13 @@ pub use sa_token_core::{self,
     SaTokenEvent, SaTokenListener, SaTokenEventBus, LoggingListener,
     
     // JWT 支持 | JWT support
-    JwtManager, JwtClaims, JwtAlgorithm,
+    JwtManager, JwtClaims, JwtAlgorithm, JwtKeyStore, JwtSecret,
     
     // OAuth2 支持 | OAuth2 support
     OAuth2Manager, OAuth2Client, AuthorizationCode, AccessToken, OAuth2TokenInfo,
     
     // 安全特性 | Security features
-    NonceManager, RefreshTokenManager,
+    NonceManager, RefreshTokenManager, TempTokenManager, SameTokenManager,
     
     // WebSocket 认证 | WebSocket authentication
     WsAuthManager, WsAuthInfo, WsTokenExtractor, DefaultWsTokenExtractor,
@@ -15,7 +15,8 @@ use std::time::Duration;
 use async_trait::async_trait;
 use tokio::sync::RwLock;
 use chrono::{DateTime, Utc};
-use sa_token_adapter::storage::{SaStorage, StorageResult, StorageError};
+use sa_token_adapter::storage::{SaStorage, StorageResult, StorageError, KeyTtl};
+use sa_token_adapter::clock::{Clock, SystemClock};
 
 /// 内存存储项
 #[derive(Debug, Clone)]
@@ -25,14 +26,14 @@ struct StorageItem {
 }
 
 impl StorageItem {
-    fn new(value: String, ttl: Option<Duration>) -> Self {
-        let expire_at = ttl.map(|d| Utc::now() + chrono::Duration::from_std(d).unwrap());
+    fn new(value: String, ttl: Option<Duration>, now: DateTime<Utc>) -> Self {
+        let expire_at = ttl.map(|d| now + chrono::Duration::from_std(d).unwrap());
         Self { value, expire_at }
     }
     
-    fn is_expired(&self) -> bool {
+    fn is_expired(&self, now: DateTime<Utc>) -> bool {
         if let Some(expire_at) = self.expire_at {
-            Utc::now() > expire_at
+            now > expire_at
         } else {
             false
         }
@@ -40,23 +41,40 @@ impl StorageItem {
 }
 
 /// 内存存储实现
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct MemoryStorage {
     data: Arc<RwLock<HashMap<String, StorageItem>>>,
+    /// 计算过期时间的时钟
+    clock: Arc<dyn Clock>,
 }
 
 impl MemoryStorage {
     /// 创建新的内存存储
     pub fn new() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+    
+    /// 使用指定时钟计算过期时间，测试中可传入 `MockClock`
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
         Self {
             data: Arc::new(RwLock::new(HashMap::new())),
+            clock,
         }
     }
     
     /// 清理过期的数据
     pub async fn cleanup_expired(&self) {
+        let now = self.clock.now();
         let mut data = self.data.write().await;
-        data.retain(|_, item| !item.is_expired());
+        data.retain(|_, item| !item.is_expired(now));
+    }
+}
+
+impl std::fmt::Debug for MemoryStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemoryStorage")
+            .field("data", &self.data)
+            .finish_non_exhaustive()
     }
 }
 
@@ -68,11 +86,15 @@ impl Default for MemoryStorage {
 
 #[async_trait]
 impl SaStorage for MemoryStorage {
+    async fn health_check(&self) -> StorageResult<()> {
+        Ok(())
+    }
+    
     async fn get(&self, key: &str) -> StorageResult<Option<String>> {
         let data = self.data.read().await;
         
         if let Some(item) = data.get(key) {
-            if item.is_expired() {
+            if item.is_expired(self.clock.now()) {
                 // 数据已过期
                 drop(data);
                 self.delete(key).await?;
@@ -87,11 +109,21 @@ impl SaStorage for MemoryStorage {
     
     async fn set(&self, key: &str, value: &str, ttl: Option<Duration>) -> StorageResult<()> {
         let mut data = self.data.write().await;
-        let item = StorageItem::new(value.to_string(), ttl);
+        let item = StorageItem::new(value.to_string(), ttl, self.clock.now());
         data.insert(key.to_string(), item);
         Ok(())
     }
     
+    async fn set_nx(&self, key: &str, value: &str, ttl: Option<Duration>) -> StorageResult<bool> {
+        let mut data = self.data.write().await;
+        let now = self.clock.now();
+        if data.get(key).is_some_and(|item| !item.is_expired(now)) {
+            return Ok(false);
+        }
+        data.insert(key.to_string(), StorageItem::new(value.to_string(), ttl, now));
+        Ok(true)
+    }
+    
     async fn delete(&self, key: &str) -> StorageResult<()> {
         let mut data = self.data.write().await;
         data.remove(key);
@@ -101,7 +133,7 @@ impl SaStorage for MemoryStorage {
     async fn exists(&self, key: &str) -> StorageResult<bool> {
         let data = self.data.read().await;
         if let Some(item) = data.get(key) {
-            Ok(!item.is_expired())
+            Ok(!item.is_expired(self.clock.now()))
         } else {
             Ok(false)
         }
@@ -110,28 +142,28 @@ impl SaStorage for MemoryStorage {
     async fn expire(&self, key: &str, ttl: Duration) -> StorageResult<()> {
         let mut data = self.data.write().await;
         if let Some(item) = data.get_mut(key) {
-            item.expire_at = Some(Utc::now() + chrono::Duration::from_std(ttl).unwrap());
+            item.expire_at = Some(self.clock.now() + chrono::Duration::from_std(ttl).unwrap());
         }
         Ok(())
     }
     
-    async fn ttl(&self, key: &str) -> StorageResult<Option<Duration>> {
+    async fn ttl(&self, key: &str) -> StorageResult<KeyTtl> {
         let data = self.data.read().await;
         if let Some(item) = data.get(key) {
             if let Some(expire_at) = item.expire_at {
-                let now = Utc::now();
+                let now = self.clock.now();
                 if expire_at > now {
                     let duration = (expire_at - now).to_std()
                         .map_err(|e| StorageError::InternalError(e.to_string()))?;
-                    Ok(Some(duration))
+                    Ok(KeyTtl::Expires(duration))
                 } else {
-                    Ok(Some(Duration::from_secs(0)))
+                    Ok(KeyTtl::Missing) // 已过期，等同于不存在
                 }
             } else {
-                Ok(None) // 永不过期
+                Ok(KeyTtl::Persistent)
             }
         } else {
-            Ok(None) // 键不存在
+            Ok(KeyTtl::Missing)
         }
     }
     
@@ -153,8 +185,9 @@ impl SaStorage for MemoryStorage {
         };
         
         // 筛选匹配的键
+        let now = self.clock.now();
         for (key, item) in data.iter() {
-            if !item.is_expired() && regex.is_match(key) {
+            if !item.is_expired(now) && regex.is_match(key) {
                 result.push(key.clone());
             }
         }
@@ -204,5 +237,46 @@ mod tests {
         // 过期后应该返回 None
         let value = storage.get("key1").await.unwrap();
         assert_eq!(value, None);
+        assert_eq!(storage.ttl("key1").await.unwrap(), KeyTtl::Missing);
+    }
+    
+    #[tokio::test]
+    async fn test_ttl_distinguishes_persistent_and_missing() {
+        let storage = MemoryStorage::new();
+        storage.set("persistent", "v", None).await.unwrap();
+        storage.set("expiring", "v", Some(Duration::from_secs(60))).await.unwrap();
+        
+        assert_eq!(storage.ttl("persistent").await.unwrap(), KeyTtl::Persistent);
+        assert_eq!(storage.ttl("missing").await.unwrap(), KeyTtl::Missing);
+        let remaining = storage.ttl("expiring").await.unwrap().remaining().unwrap();
+        assert!(remaining <= Duration::from_secs(60) && remaining > Duration::from_secs(58));
+    }
+    
+    #[tokio::test]
+    async fn test_ttl_follows_injected_clock() {
+        let clock = sa_token_adapter::clock::MockClock::default();
+        let storage = MemoryStorage::with_clock(Arc::new(clock.clone()));
+        storage.set("key1", "value1", Some(Duration::from_secs(60))).await.unwrap();
+        
+        clock.advance(chrono::Duration::seconds(59));
+        assert_eq!(storage.ttl("key1").await.unwrap(), KeyTtl::Expires(Duration::from_secs(1)));
+        
+        clock.advance(chrono::Duration::seconds(2));
+        assert_eq!(storage.get("key1").await.unwrap(), None);
+    }
+    
+    #[tokio::test]
+    async fn test_set_nx_only_writes_missing_or_expired_keys() {
+        let clock = sa_token_adapter::clock::MockClock::default();
+        let storage = MemoryStorage::with_clock(Arc::new(clock.clone()));
+        
+        assert!(storage.set_nx("nonce", "1", Some(Duration::from_secs(60))).await.unwrap());
+        assert!(!storage.set_nx("nonce", "2", Some(Duration::from_secs(60))).await.unwrap());
+        assert_eq!(storage.get("nonce").await.unwrap().as_deref(), Some("1"));
+        assert!(storage.ttl("nonce").await.unwrap().remaining().is_some());
+        
+        clock.advance(chrono::Duration::seconds(61));
+        assert!(storage.set_nx("nonce", "3", None).await.unwrap());
+        assert_eq!(storage.ttl("nonce").await.unwrap(), KeyTtl::Persistent);
     }
 }
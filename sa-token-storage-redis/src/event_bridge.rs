@@ -0,0 +1,189 @@
+// Author: 金书记
+//
+//! 基于 Redis pub/sub 的跨实例事件桥 | Cross-instance event bridge over Redis pub/sub
+//!
+//! 踢人、封禁等事件默认只在执行操作的实例上触发。`RedisEventBridge` 作为监听器注册到本地
+//! `SaTokenEventBus`，把本地事件 `PUBLISH` 到频道；同时订阅该频道，把其他实例的事件重新
+//! 发布到本地事件总线，WebSocket 踢人推送等监听器因此在多实例部署下也能收到事件。
+//!
+//! 转发来的事件带有 `origin`（来源实例 ID），不会被再次转发，审计监听器也会跳过它们。
+//! 订阅连接断开后每秒重连，断开期间的事件会丢失。
+//!
+//! Kick-out, ban and similar events only fire on the instance that performed
+//! them. `RedisEventBridge` registers on the local `SaTokenEventBus`,
+//! `PUBLISH`es local events to a channel and subscribes to it, re-publishing
+//! other instances' events on the local bus so listeners such as WebSocket
+//! kick-out pushers work in multi-instance deployments.
+//!
+//! Forwarded events carry an `origin` (the sender's instance ID); they are
+//! never forwarded again and the audit listener skips them. A dropped
+//! subscription reconnects every second; events sent meanwhile are lost.
+//!
+//! ```rust,ignore
+//! let bridge = RedisEventBridge::new("redis://localhost:6379/0", "sa:events").await?
+//!     .event_types([SaTokenEventType::KickOut, SaTokenEventType::Banned]);
+//! bridge.attach(manager.event_bus()).await?;
+//! ```
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use redis::{Client, aio::{ConnectionManager, PubSub}};
+use sa_token_adapter::storage::{StorageError, StorageResult};
+use sa_token_core::event::{SaTokenEvent, SaTokenEventBus, SaTokenEventType, SaTokenListener};
+
+/// 订阅断开后的重连间隔 | Delay before resubscribing after the subscription drops
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(1);
+
+/// 默认转发的事件类型 | Event types forwarded by default
+///
+/// 登录、认证失败等高频事件默认不转发 | High-volume events such as login and auth failures stay local
+pub const DEFAULT_BRIDGED_EVENTS: &[SaTokenEventType] = &[
+    SaTokenEventType::Logout,
+    SaTokenEventType::KickOut,
+    SaTokenEventType::Replaced,
+    SaTokenEventType::Banned,
+    SaTokenEventType::Unbanned,
+    SaTokenEventType::PermissionChanged,
+    SaTokenEventType::OAuth2TokenRevoked,
+];
+
+/// 基于 Redis pub/sub 的跨实例事件桥 | Cross-instance event bridge over Redis pub/sub
+pub struct RedisEventBridge {
+    client: Client,
+    publisher: ConnectionManager,
+    channel: String,
+    instance_id: String,
+    event_types: Vec<SaTokenEventType>,
+}
+
+impl RedisEventBridge {
+    /// 连接 Redis 并使用指定的频道 | Connect to Redis and use the given channel
+    pub async fn new(redis_url: &str, channel: impl Into<String>) -> StorageResult<Self> {
+        let client = Client::open(redis_url)
+            .map_err(|e| StorageError::ConnectionError(e.to_string()))?;
+        let publisher = ConnectionManager::new(client.clone()).await
+            .map_err(|e| StorageError::ConnectionError(e.to_string()))?;
+
+        Ok(Self {
+            client,
+            publisher,
+            channel: channel.into(),
+            instance_id: new_instance_id(),
+            event_types: DEFAULT_BRIDGED_EVENTS.to_vec(),
+        })
+    }
+
+    /// 设置需要转发的事件类型 | Set the event types to forward
+    pub fn event_types(mut self, event_types: impl IntoIterator<Item = SaTokenEventType>) -> Self {
+        self.event_types = event_types.into_iter().collect();
+        self
+    }
+
+    /// 频道名 | Channel name
+    pub fn channel(&self) -> &str {
+        &self.channel
+    }
+
+    /// 本实例 ID，写入转发事件的 `origin` | This instance's ID, written to forwarded events' `origin`
+    pub fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    /// 订阅频道并注册到事件总线 | Subscribe to the channel and register on the event bus
+    ///
+    /// 第一次订阅失败直接返回错误，之后由后台任务保持订阅。
+    /// The first subscription fails loudly; a background task keeps it alive afterwards.
+    pub async fn attach(self, event_bus: &SaTokenEventBus) -> StorageResult<Arc<Self>> {
+        let first = Self::connect(&self.client, &self.channel).await
+            .map_err(|e| StorageError::ConnectionError(e.to_string()))?;
+
+        let client = self.client.clone();
+        let channel = self.channel.clone();
+        let instance_id = self.instance_id.clone();
+        let bus = event_bus.clone();
+        tokio::spawn(async move {
+            let mut pubsub = Some(first);
+            loop {
+                let subscription = match pubsub.take() {
+                    Some(pubsub) => Ok(pubsub),
+                    None => Self::connect(&client, &channel).await,
+                };
+                match subscription {
+                    Ok(subscription) => Self::listen(subscription, &bus, &instance_id).await,
+                    Err(e) => tracing::warn!("RedisEventBridge: 重新订阅失败: {}", e),
+                }
+                tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+            }
+        });
+
+        let bridge = Arc::new(self);
+        event_bus.register(bridge.clone());
+        Ok(bridge)
+    }
+
+    /// 建立订阅连接 | Open a subscription
+    async fn connect(client: &Client, channel: &str) -> redis::RedisResult<PubSub> {
+        let mut pubsub = client.get_async_pubsub().await?;
+        pubsub.subscribe(channel).await?;
+        Ok(pubsub)
+    }
+
+    /// 把其他实例的事件发布到本地总线，直到连接断开
+    /// Re-publish other instances' events on the local bus until the connection drops
+    async fn listen(pubsub: PubSub, bus: &SaTokenEventBus, instance_id: &str) {
+        let mut messages = pubsub.into_on_message();
+        while let Some(message) = messages.next().await {
+            let event = message.get_payload::<String>()
+                .map_err(|e| e.to_string())
+                .and_then(|payload| serde_json::from_str::<SaTokenEvent>(&payload).map_err(|e| e.to_string()));
+            match event {
+                // 自己发出的事件会被回送，忽略 | Our own events are echoed back; skip them
+                Ok(event) if event.origin.as_deref() == Some(instance_id) => {}
+                Ok(event) if event.is_remote() => bus.publish(event).await,
+                Ok(_) => tracing::warn!("RedisEventBridge: 收到缺少 origin 的事件，已忽略"),
+                Err(e) => tracing::warn!("RedisEventBridge: 无法解析事件: {}", e),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl SaTokenListener for RedisEventBridge {
+    async fn on_event(&self, event: &SaTokenEvent) {
+        // 转发来的事件不再转发，避免在实例间循环
+        // Forwarded events are not forwarded again so they never loop between instances
+        if event.is_remote() || !self.event_types.contains(&event.event_type) {
+            return;
+        }
+
+        let mut event = event.clone();
+        event.origin = Some(self.instance_id.clone());
+        let payload = match serde_json::to_string(&event) {
+            Ok(payload) => payload,
+            Err(e) => {
+                tracing::warn!("RedisEventBridge: 序列化事件失败: {}", e);
+                return;
+            }
+        };
+
+        let mut conn = self.publisher.clone();
+        if let Err(e) = redis::cmd("PUBLISH")
+            .arg(&self.channel)
+            .arg(payload)
+            .query_async::<i64>(&mut conn).await
+        {
+            tracing::warn!("RedisEventBridge: 发布事件失败: {}", e);
+        }
+    }
+}
+
+/// 生成实例 ID（进程号 + 纳秒时间戳）| Build an instance ID from the process ID and a nanosecond timestamp
+fn new_instance_id() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    format!("{:x}-{:x}", std::process::id(), nanos)
+}
@@ -0,0 +1,112 @@
+// Author: 金书记
+//
+//! 基于 Redis pub/sub 的缓存失效通道 | Cache invalidation over Redis pub/sub
+//!
+//! 配合 `sa_token_core::l1_cache::CachedStorage` 使用：某个实例写入、删除键后，
+//! 通过 `PUBLISH` 通知其他实例丢弃本地缓存中的同一个键。每条消息是以换行分隔的键列表。
+//! 订阅连接断开后每秒重连，断开期间的通知会丢失，由本地缓存的短 TTL 兜底。
+//!
+//! Used with `CachedStorage`: after an instance writes or deletes keys it
+//! `PUBLISH`es them so the other instances drop the same keys from their
+//! local cache. Each message is a newline-separated list of keys. A dropped
+//! subscription reconnects every second; notifications missed meanwhile are
+//! covered by the local cache's short TTL.
+//!
+//! ```rust,ignore
+//! let channel = RedisCacheInvalidation::new("redis://localhost:6379/0", "sa:cache-invalidate").await?;
+//! let cached = Arc::new(CachedStorage::new(redis).invalidation(Arc::new(channel)));
+//! cached.listen_invalidations().await?;
+//! ```
+
+use std::time::Duration;
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use redis::{Client, aio::{ConnectionManager, PubSub}};
+use sa_token_adapter::storage::{CacheInvalidation, InvalidationHandler, StorageError, StorageResult};
+
+/// 订阅断开后的重连间隔 | Delay before resubscribing after the subscription drops
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(1);
+
+/// 基于 Redis pub/sub 的缓存失效通道 | Cache invalidation channel over Redis pub/sub
+pub struct RedisCacheInvalidation {
+    client: Client,
+    publisher: ConnectionManager,
+    channel: String,
+}
+
+impl RedisCacheInvalidation {
+    /// 连接 Redis 并使用指定的频道 | Connect to Redis and use the given channel
+    pub async fn new(redis_url: &str, channel: impl Into<String>) -> StorageResult<Self> {
+        let client = Client::open(redis_url)
+            .map_err(|e| StorageError::ConnectionError(e.to_string()))?;
+        let publisher = ConnectionManager::new(client.clone()).await
+            .map_err(|e| StorageError::ConnectionError(e.to_string()))?;
+
+        Ok(Self {
+            client,
+            publisher,
+            channel: channel.into(),
+        })
+    }
+
+    /// 频道名 | Channel name
+    pub fn channel(&self) -> &str {
+        &self.channel
+    }
+
+    /// 建立订阅连接 | Open a subscription
+    async fn connect(client: &Client, channel: &str) -> redis::RedisResult<PubSub> {
+        let mut pubsub = client.get_async_pubsub().await?;
+        pubsub.subscribe(channel).await?;
+        Ok(pubsub)
+    }
+
+    /// 把消息交给处理函数，直到连接断开 | Hand messages to the handler until the connection drops
+    async fn listen(pubsub: PubSub, handler: &InvalidationHandler) {
+        let mut messages = pubsub.into_on_message();
+        while let Some(message) = messages.next().await {
+            match message.get_payload::<String>() {
+                Ok(payload) => handler(payload.lines().map(str::to_string).collect()),
+                Err(e) => tracing::warn!("RedisCacheInvalidation: 无法解析失效通知: {}", e),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl CacheInvalidation for RedisCacheInvalidation {
+    async fn publish(&self, keys: &[String]) -> StorageResult<()> {
+        let mut conn = self.publisher.clone();
+        redis::cmd("PUBLISH")
+            .arg(&self.channel)
+            .arg(keys.join("\n"))
+            .query_async::<i64>(&mut conn).await
+            .map(|_| ())
+            .map_err(|e| StorageError::OperationFailed(e.to_string()))
+    }
+
+    async fn subscribe(&self, handler: InvalidationHandler) -> StorageResult<()> {
+        // 第一次订阅失败直接返回错误，之后由后台任务保持订阅
+        // The first subscription fails loudly; the background task keeps it alive afterwards
+        let first = Self::connect(&self.client, &self.channel).await
+            .map_err(|e| StorageError::ConnectionError(e.to_string()))?;
+
+        let client = self.client.clone();
+        let channel = self.channel.clone();
+        tokio::spawn(async move {
+            let mut pubsub = Some(first);
+            loop {
+                let subscription = match pubsub.take() {
+                    Some(pubsub) => Ok(pubsub),
+                    None => Self::connect(&client, &channel).await,
+                };
+                match subscription {
+                    Ok(subscription) => Self::listen(subscription, &handler).await,
+                    Err(e) => tracing::warn!("RedisCacheInvalidation: 重新订阅失败: {}", e),
+                }
+                tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+            }
+        });
+        Ok(())
+    }
+}
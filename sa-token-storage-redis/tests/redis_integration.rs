@@ -0,0 +1,158 @@
+// Author: 金书记
+//
+//! Redis 集成测试 | Redis integration tests
+//!
+//! 需要真实的 Redis，默认跳过。使用 `docker-compose.yml` 启动 Redis 后运行：
+//! These need a real Redis and are skipped by default. Start Redis with
+//! `docker-compose.yml`, then run:
+//!
+//! ```text
+//! docker compose -f sa-token-storage-redis/docker-compose.yml up -d
+//! cargo test -p sa-token-storage-redis -- --ignored
+//! ```
+//!
+//! 通过 `SA_TOKEN_REDIS_URL` 指定其他实例（默认 `redis://127.0.0.1:6379/15`）。每个测试使用
+//! 独立的键前缀，可以并行运行，也不会影响库中的其他数据。
+//! Set `SA_TOKEN_REDIS_URL` to use another instance (default
+//! `redis://127.0.0.1:6379/15`). Each test uses its own key prefix, so tests
+//! run in parallel and leave other data alone.
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use sa_token_adapter::storage::{KeyTtl, SaStorage};
+use sa_token_core::{SaTokenConfig, SaTokenManager};
+use sa_token_storage_redis::RedisStorage;
+
+fn redis_url() -> String {
+    std::env::var("SA_TOKEN_REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379/15".to_string())
+}
+
+/// 连接 Redis，键前缀按测试名与时间区分 | Connect with a prefix unique to this test run
+async fn storage(test: &str) -> RedisStorage {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+    RedisStorage::new(&redis_url(), format!("sa-it:{}:{}:", test, nanos))
+        .await
+        .expect("Redis is not reachable, see docker-compose.yml")
+}
+
+#[tokio::test]
+#[ignore = "needs Redis, see docker-compose.yml"]
+async fn test_storage_round_trip_and_ttl() {
+    let storage = storage("round-trip").await;
+
+    storage.set("plain", "value", None).await.unwrap();
+    assert_eq!(storage.get("plain").await.unwrap().as_deref(), Some("value"));
+    assert!(storage.exists("plain").await.unwrap());
+    assert_eq!(storage.ttl("plain").await.unwrap(), KeyTtl::Persistent);
+
+    storage.expire("plain", Duration::from_secs(100)).await.unwrap();
+    let remaining = storage.ttl("plain").await.unwrap().remaining().unwrap();
+    assert!(remaining > Duration::from_secs(90) && remaining <= Duration::from_secs(100));
+
+    storage.delete("plain").await.unwrap();
+    assert_eq!(storage.get("plain").await.unwrap(), None);
+    assert_eq!(storage.ttl("plain").await.unwrap(), KeyTtl::Missing);
+
+    // Redis 自身的过期 | Expiry enforced by Redis itself
+    storage.set("short", "value", Some(Duration::from_secs(1))).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert!(!storage.exists("short").await.unwrap());
+
+    storage.clear().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs Redis, see docker-compose.yml"]
+async fn test_batch_operations_and_counters() {
+    let storage = storage("batch").await;
+
+    storage.mset(&[("a", "1"), ("b", "2")], Some(Duration::from_secs(60))).await.unwrap();
+    let values = storage.mget(&["a", "missing", "b"]).await.unwrap();
+    assert_eq!(values, vec![Some("1".to_string()), None, Some("2".to_string())]);
+    assert!(storage.ttl("a").await.unwrap().remaining().is_some());
+
+    storage.mdel(&["a", "b"]).await.unwrap();
+    assert_eq!(storage.mget(&["a", "b"]).await.unwrap(), vec![None, None]);
+
+    assert_eq!(storage.incr("counter").await.unwrap(), 1);
+    assert_eq!(storage.incr("counter").await.unwrap(), 2);
+    assert_eq!(storage.decr("counter").await.unwrap(), 1);
+
+    storage.clear().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs Redis, see docker-compose.yml"]
+async fn test_clear_only_touches_own_prefix() {
+    let ours = storage("clear-ours").await;
+    let theirs = storage("clear-theirs").await;
+
+    ours.set("key", "ours", None).await.unwrap();
+    theirs.set("key", "theirs", None).await.unwrap();
+
+    ours.clear().await.unwrap();
+    assert_eq!(ours.get("key").await.unwrap(), None);
+    assert_eq!(theirs.get("key").await.unwrap().as_deref(), Some("theirs"));
+
+    theirs.clear().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs Redis, see docker-compose.yml"]
+async fn test_scan_pages_through_many_keys() {
+    let storage = storage("scan").await.with_scan_count(7);
+    for i in 0..50 {
+        storage.set(&format!("token:{}", i), "v", None).await.unwrap();
+    }
+    storage.set("session:1", "v", None).await.unwrap();
+
+    let mut paged = Vec::new();
+    let mut cursor = 0;
+    loop {
+        let page = storage.scan_keys("token:*", cursor).await.unwrap();
+        let last = page.is_last();
+        paged.extend(page.keys);
+        if last {
+            break;
+        }
+        cursor = page.cursor;
+    }
+    paged.sort();
+    paged.dedup();
+    assert_eq!(paged.len(), 50);
+    assert_eq!(storage.keys("token:*").await.unwrap(), paged);
+
+    storage.clear().await.unwrap();
+    assert!(storage.keys("*").await.unwrap().is_empty());
+}
+
+#[tokio::test]
+#[ignore = "needs Redis, see docker-compose.yml"]
+async fn test_manager_login_session_and_logout() {
+    let storage = Arc::new(storage("manager").await);
+    let config = SaTokenConfig::builder().timeout(3600).build_config();
+    let manager = SaTokenManager::new(storage.clone(), config);
+
+    let token = manager.login("user_1").await.unwrap();
+    let token_info = manager.get_token_info(&token).await.unwrap();
+    assert_eq!(token_info.login_id, "user_1");
+
+    // token 在 Redis 中带有与 timeout 一致的 TTL | The token key carries the configured TTL
+    let ttl = storage.ttl(&format!("sa:token:{}", token.as_str())).await.unwrap();
+    assert!(ttl.remaining().is_some_and(|remaining| remaining <= Duration::from_secs(3600)));
+
+    let mut session = manager.get_session("user_1").await.unwrap();
+    session.set("theme", "dark").unwrap();
+    manager.save_session(&session).await.unwrap();
+    let session = manager.get_session("user_1").await.unwrap();
+    assert_eq!(session.get::<String>("theme").as_deref(), Some("dark"));
+
+    let infos = manager.get_token_infos(std::slice::from_ref(&token)).await.unwrap();
+    assert!(infos[0].is_some());
+
+    manager.logout(&token).await.unwrap();
+    assert!(!manager.is_valid(&token).await);
+
+    storage.clear().await.unwrap();
+}
@@ -0,0 +1,27 @@
+// Author: 金书记
+//
+//! # sa-token-test-utils
+//!
+//! sa-token-rust 的测试辅助工具，只应作为 `dev-dependencies` 引入。
+//!
+//! Test helpers for sa-token-rust. Add it to `dev-dependencies` only.
+//!
+//! ```toml
+//! [dev-dependencies]
+//! sa-token-test-utils = "0.1"
+//! ```
+//!
+//! - [`time`]：冻结、拨快时间，让过期相关的测试立即完成 | freeze and advance time
+//! - [`MockClock`]：按实例注入的时钟，见 `SaTokenConfigBuilder::clock` | a per-instance clock, see `SaTokenConfigBuilder::clock`
+//! - [`storage`]：记录调用、可注入失败与延迟的模拟存储 | mock storage recording calls, with injected failures and latency
+//! - [`login`]：一步完成测试用户的登录与授权，并构造请求上下文 | log in and authorize a test user, and build the request context
+//! - [`state`]：框架插件测试用的内存存储状态 | in-memory state for framework plugin tests
+
+pub mod time;
+pub mod storage;
+pub mod login;
+pub mod state;
+
+pub use storage::{InstrumentedMockStorage, StorageCall, StorageOp};
+pub use sa_token_core::MockClock;
+pub use login::{TestLogin, TestSession};
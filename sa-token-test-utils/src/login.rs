@@ -0,0 +1,174 @@
+// Author: 金书记
+//
+//! 测试登录 | Test Logins
+//!
+//! [`TestLogin`] 一步完成登录、授予权限与角色，返回的 [`TestSession`] 可以生成请求头，
+//! 或者构造与插件中间件相同的 `SaTokenContext`，让处理函数的单元测试无需经过中间件，
+//! 对所有框架插件都适用。
+//!
+//! [`TestLogin`] logs a user in and grants permissions and roles in one step.
+//! The returned [`TestSession`] builds the request header, or the same
+//! `SaTokenContext` the plugin middlewares set up, so handler unit tests can
+//! skip the middleware with any framework plugin.
+//!
+//! ```rust,ignore
+//! let session = TestLogin::as_user("admin")
+//!     .permissions(["user:delete"])
+//!     .roles(["admin"])
+//!     .login_with(&manager)
+//!     .await?;
+//!
+//! // 直接调用处理函数 | Call the handler directly
+//! let response = session.scope(delete_user(Path(42))).await;
+//!
+//! // 或经过完整的路由 | Or go through the router
+//! let request = Request::get("/admin").header("Authorization", session.authorization_header());
+//! ```
+
+use std::future::Future;
+use std::sync::Arc;
+use sa_token_core::{
+    SaTokenContext, SaTokenManager, SaTokenResult, StpLogic, StpUtil, TokenInfo, TokenValue,
+    DEFAULT_LOGIN_TYPE,
+};
+
+/// 测试用户登录的构建器 | Builder for logging in a test user
+#[derive(Debug, Clone)]
+pub struct TestLogin {
+    login_id: String,
+    login_type: String,
+    permissions: Vec<String>,
+    roles: Vec<String>,
+}
+
+impl TestLogin {
+    /// 以指定的登录 ID 登录 | Log in as `login_id`
+    pub fn as_user(login_id: impl Into<String>) -> Self {
+        Self {
+            login_id: login_id.into(),
+            login_type: DEFAULT_LOGIN_TYPE.to_string(),
+            permissions: Vec::new(),
+            roles: Vec::new(),
+        }
+    }
+
+    /// 登录到指定的账号体系，默认为 `default` | Log in to another account system, `default` by default
+    pub fn login_type(mut self, login_type: impl Into<String>) -> Self {
+        self.login_type = login_type.into();
+        self
+    }
+
+    /// 授予的权限，覆盖该用户已有的权限 | Permissions to grant, replacing the user's current ones
+    pub fn permissions<I, S>(mut self, permissions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.permissions = permissions.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// 授予的角色，覆盖该用户已有的角色 | Roles to grant, replacing the user's current ones
+    pub fn roles<I, S>(mut self, roles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.roles = roles.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// 在全局 `StpUtil` 的管理器上登录 | Log in on the global `StpUtil` manager
+    pub async fn login(self) -> SaTokenResult<TestSession> {
+        let manager = StpUtil::default_logic().manager().clone();
+        self.login_with(&manager).await
+    }
+
+    /// 在指定的管理器上登录 | Log in on the given manager
+    ///
+    /// 权限与角色在登录前写入，开启 `prefetch_authz` 时会进入 token 的权限快照。
+    /// Permissions and roles are set before the login, so they land in the
+    /// token's snapshot when `prefetch_authz` is on.
+    pub async fn login_with(self, manager: &SaTokenManager) -> SaTokenResult<TestSession> {
+        let logic = StpLogic::new(self.login_type, manager.clone());
+        if !self.permissions.is_empty() {
+            logic.set_permissions(&self.login_id, self.permissions).await?;
+        }
+        if !self.roles.is_empty() {
+            logic.set_roles(&self.login_id, self.roles).await?;
+        }
+
+        let token = logic.login(&self.login_id).await?;
+        let token_info = manager.get_token_info(&token).await?;
+        Ok(TestSession {
+            token,
+            login_id: self.login_id,
+            token_info: Arc::new(token_info),
+        })
+    }
+}
+
+/// 已登录的测试用户 | A logged-in test user
+#[derive(Debug, Clone)]
+pub struct TestSession {
+    pub token: TokenValue,
+    pub login_id: String,
+    pub token_info: Arc<TokenInfo>,
+}
+
+impl TestSession {
+    /// `Authorization` 请求头的值（`Bearer <token>`）| Value of the `Authorization` header, `Bearer <token>`
+    pub fn authorization_header(&self) -> String {
+        format!("Bearer {}", self.token)
+    }
+
+    /// 与插件中间件解析 token 后相同的上下文 | The context a plugin middleware builds after resolving the token
+    pub fn context(&self) -> SaTokenContext {
+        let mut ctx = SaTokenContext::new();
+        ctx.token = Some(self.token.clone());
+        ctx.token_info = Some(self.token_info.clone());
+        ctx.login_id = Some(self.login_id.clone());
+        ctx
+    }
+
+    /// 在该用户的上下文中执行 future，`StpUtil::get_login_id_as_string()` 等返回该用户
+    ///
+    /// Runs the future in this user's context, so `StpUtil::get_login_id_as_string()`
+    /// and friends return this user.
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        SaTokenContext::scope(self.context(), future).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_core::SaTokenConfig;
+    use sa_token_storage_memory::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_as_user_grants_and_builds_context() {
+        let config = SaTokenConfig::builder().prefetch_authz(true).build_config();
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+
+        let session = TestLogin::as_user("admin")
+            .permissions(["user:delete"])
+            .roles(["admin"])
+            .login_with(&manager)
+            .await
+            .unwrap();
+
+        assert_eq!(session.authorization_header(), format!("Bearer {}", session.token));
+        assert!(manager.is_valid(&session.token).await);
+        assert!(manager.has_permission("admin", "user:delete").await);
+        assert!(manager.has_role("admin", "admin").await);
+
+        let authz = session.token_info.authz.as_ref().expect("prefetched snapshot");
+        assert_eq!(authz.roles, ["admin"]);
+
+        let login_id = session.scope(async {
+            SaTokenContext::get_current().and_then(|ctx| ctx.login_id)
+        }).await;
+        assert_eq!(login_id.as_deref(), Some("admin"));
+    }
+}
@@ -0,0 +1,47 @@
+// Author: 金书记
+//
+//! 插件测试状态 | Plugin Test State
+//!
+//! 框架插件的测试都需要一个使用内存存储的 `SaTokenState`。[`state_with`] 不初始化全局
+//! `StpUtil`，每个测试拿到互不影响的管理器；处理函数依赖 `StpUtil` 时改用
+//! `SaTokenState::from_manager`。
+//!
+//! Framework plugin tests all need a `SaTokenState` on an in-memory storage.
+//! [`state_with`] leaves the global `StpUtil` alone, so every test gets its own
+//! manager; use `SaTokenState::from_manager` when handlers go through `StpUtil`.
+//!
+//! ```rust,ignore
+//! let state = state_with(SaTokenConfig::builder().token_bind_ip(true).build_config());
+//! let token = state.manager.login("user_1").await?;
+//! ```
+
+use std::sync::Arc;
+use sa_token_core::{SaTokenConfig, SaTokenManager, SaTokenState};
+use sa_token_storage_memory::MemoryStorage;
+
+/// 使用默认配置和内存存储的状态 | State with the default config on an in-memory storage
+pub fn state() -> SaTokenState {
+    state_with(SaTokenConfig::default())
+}
+
+/// 使用指定配置和内存存储的状态，不初始化 `StpUtil` | State with `config` on an in-memory storage, without initializing `StpUtil`
+pub fn state_with(config: SaTokenConfig) -> SaTokenState {
+    SaTokenState {
+        manager: Arc::new(SaTokenManager::new(Arc::new(MemoryStorage::new()), config)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_states_do_not_share_storage() {
+        let (a, b) = (state(), state_with(SaTokenConfig::builder().timeout(60).build_config()));
+        let token = a.manager.login("user_1").await.unwrap();
+
+        assert!(a.manager.is_valid(&token).await);
+        assert!(!b.manager.is_valid(&token).await);
+        assert_eq!(b.manager.config().timeout, 60);
+    }
+}
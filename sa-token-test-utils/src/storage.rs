@@ -0,0 +1,298 @@
+// Author: 金书记
+//
+//! 可观测的模拟存储 | Instrumented Mock Storage
+//!
+//! [`InstrumentedMockStorage`] 基于 `MemoryStorage`，额外记录每一次调用，并可以注入
+//! 失败与延迟，用来断言某个流程访问了哪些键、访问了几次，以及存储故障时的行为。
+//!
+//! [`InstrumentedMockStorage`] wraps `MemoryStorage`, records every call and
+//! can inject failures and latency, so tests can assert which keys a flow
+//! touched, how often, and how it behaves when the storage fails.
+//!
+//! ```rust,ignore
+//! let storage = Arc::new(InstrumentedMockStorage::new());
+//! let manager = SaTokenManager::new(storage.clone(), config);
+//!
+//! let token = manager.login("user_1").await?;
+//! assert!(storage.count(StorageOp::Set) > 0);
+//!
+//! storage.fail_on(StorageOp::Get);
+//! assert!(manager.get_token_info(&token).await.is_err());
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::Duration;
+use async_trait::async_trait;
+use sa_token_adapter::storage::{KeyTtl, SaStorage, ScanPage, StorageError, StorageResult};
+use sa_token_storage_memory::MemoryStorage;
+
+/// 存储操作 | Storage operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StorageOp {
+    Get,
+    Set,
+    SetNx,
+    Delete,
+    Exists,
+    Expire,
+    Ttl,
+    Clear,
+    Mget,
+    Mset,
+    Mdel,
+    Incr,
+    Decr,
+    Keys,
+    ScanKeys,
+}
+
+/// 一次存储调用的记录 | Record of one storage call
+///
+/// `keys` 为调用涉及的键，`keys` / `scan_keys` 记录的是匹配模式，`clear` 为空。
+/// `keys` holds the keys the call touched; the pattern for `keys` /
+/// `scan_keys`, empty for `clear`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageCall {
+    pub op: StorageOp,
+    pub keys: Vec<String>,
+}
+
+#[derive(Default)]
+struct Faults {
+    /// 持续失败的操作 | Operations that keep failing
+    always: HashSet<StorageOp>,
+    /// 剩余失败次数 | Remaining one-off failures
+    next: HashMap<StorageOp, usize>,
+    latency: Option<Duration>,
+}
+
+/// 记录调用、可注入失败与延迟的内存存储 | In-memory storage that records calls and injects failures and latency
+#[derive(Default)]
+pub struct InstrumentedMockStorage {
+    inner: MemoryStorage,
+    calls: Mutex<Vec<StorageCall>>,
+    faults: Mutex<Faults>,
+}
+
+impl InstrumentedMockStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 每次调用前等待 `latency` | Wait `latency` before every call
+    pub fn with_latency(self, latency: Duration) -> Self {
+        self.set_latency(Some(latency));
+        self
+    }
+
+    /// 设置或取消调用延迟 | Set or remove the per-call latency
+    pub fn set_latency(&self, latency: Option<Duration>) {
+        self.faults().latency = latency;
+    }
+
+    /// 此后该操作的每次调用都失败，直到 [`clear_failures`](Self::clear_failures)
+    ///
+    /// Every later call of `op` fails until [`clear_failures`](Self::clear_failures).
+    pub fn fail_on(&self, op: StorageOp) {
+        self.faults().always.insert(op);
+    }
+
+    /// 该操作的下一次调用失败 | The next call of `op` fails
+    pub fn fail_next(&self, op: StorageOp) {
+        self.fail_times(op, 1);
+    }
+
+    /// 该操作接下来的 `times` 次调用失败 | The next `times` calls of `op` fail
+    pub fn fail_times(&self, op: StorageOp, times: usize) {
+        *self.faults().next.entry(op).or_insert(0) += times;
+    }
+
+    /// 取消所有注入的失败，延迟保持不变 | Remove all injected failures; latency is kept
+    pub fn clear_failures(&self) {
+        let mut faults = self.faults();
+        faults.always.clear();
+        faults.next.clear();
+    }
+
+    /// 至今为止的全部调用，按发生顺序 | All calls so far, in order
+    pub fn calls(&self) -> Vec<StorageCall> {
+        self.call_log().clone()
+    }
+
+    /// 某个操作的调用次数 | Number of calls of `op`
+    pub fn count(&self, op: StorageOp) -> usize {
+        self.call_log().iter().filter(|call| call.op == op).count()
+    }
+
+    /// 涉及某个键的调用次数 | Number of calls touching `key`
+    pub fn count_key(&self, key: &str) -> usize {
+        self.call_log().iter().filter(|call| call.keys.iter().any(|k| k == key)).count()
+    }
+
+    /// 清空调用记录，不影响存储的数据 | Forget recorded calls; stored data is kept
+    pub fn clear_calls(&self) {
+        self.call_log().clear();
+    }
+
+    /// 底层的内存存储，读写它不会被记录 | The wrapped memory storage; access to it is not recorded
+    pub fn inner(&self) -> &MemoryStorage {
+        &self.inner
+    }
+
+    fn call_log(&self) -> std::sync::MutexGuard<'_, Vec<StorageCall>> {
+        self.calls.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn faults(&self) -> std::sync::MutexGuard<'_, Faults> {
+        self.faults.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 记录调用，按配置等待并决定是否失败 | Record the call, wait and decide whether it fails
+    async fn enter(&self, op: StorageOp, keys: &[&str]) -> StorageResult<()> {
+        self.call_log().push(StorageCall {
+            op,
+            keys: keys.iter().map(|key| key.to_string()).collect(),
+        });
+
+        let (latency, fail) = {
+            let mut faults = self.faults();
+            let once = match faults.next.get_mut(&op) {
+                Some(remaining) if *remaining > 0 => {
+                    *remaining -= 1;
+                    true
+                }
+                _ => false,
+            };
+            (faults.latency, once || faults.always.contains(&op))
+        };
+        if let Some(latency) = latency {
+            tokio::time::sleep(latency).await;
+        }
+        if fail {
+            return Err(StorageError::ConnectionError(format!("injected failure on {:?}", op)));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SaStorage for InstrumentedMockStorage {
+    async fn get(&self, key: &str) -> StorageResult<Option<String>> {
+        self.enter(StorageOp::Get, &[key]).await?;
+        self.inner.get(key).await
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Option<Duration>) -> StorageResult<()> {
+        self.enter(StorageOp::Set, &[key]).await?;
+        self.inner.set(key, value, ttl).await
+    }
+
+    async fn set_nx(&self, key: &str, value: &str, ttl: Option<Duration>) -> StorageResult<bool> {
+        self.enter(StorageOp::SetNx, &[key]).await?;
+        self.inner.set_nx(key, value, ttl).await
+    }
+
+    async fn delete(&self, key: &str) -> StorageResult<()> {
+        self.enter(StorageOp::Delete, &[key]).await?;
+        self.inner.delete(key).await
+    }
+
+    async fn exists(&self, key: &str) -> StorageResult<bool> {
+        self.enter(StorageOp::Exists, &[key]).await?;
+        self.inner.exists(key).await
+    }
+
+    async fn expire(&self, key: &str, ttl: Duration) -> StorageResult<()> {
+        self.enter(StorageOp::Expire, &[key]).await?;
+        self.inner.expire(key, ttl).await
+    }
+
+    async fn ttl(&self, key: &str) -> StorageResult<KeyTtl> {
+        self.enter(StorageOp::Ttl, &[key]).await?;
+        self.inner.ttl(key).await
+    }
+
+    async fn mget(&self, keys: &[&str]) -> StorageResult<Vec<Option<String>>> {
+        self.enter(StorageOp::Mget, keys).await?;
+        self.inner.mget(keys).await
+    }
+
+    async fn mset(&self, items: &[(&str, &str)], ttl: Option<Duration>) -> StorageResult<()> {
+        let keys: Vec<&str> = items.iter().map(|(key, _)| *key).collect();
+        self.enter(StorageOp::Mset, &keys).await?;
+        self.inner.mset(items, ttl).await
+    }
+
+    async fn mdel(&self, keys: &[&str]) -> StorageResult<()> {
+        self.enter(StorageOp::Mdel, keys).await?;
+        self.inner.mdel(keys).await
+    }
+
+    async fn incr(&self, key: &str) -> StorageResult<i64> {
+        self.enter(StorageOp::Incr, &[key]).await?;
+        self.inner.incr(key).await
+    }
+
+    async fn decr(&self, key: &str) -> StorageResult<i64> {
+        self.enter(StorageOp::Decr, &[key]).await?;
+        self.inner.decr(key).await
+    }
+
+    async fn clear(&self) -> StorageResult<()> {
+        self.enter(StorageOp::Clear, &[]).await?;
+        self.inner.clear().await
+    }
+
+    async fn keys(&self, pattern: &str) -> StorageResult<Vec<String>> {
+        self.enter(StorageOp::Keys, &[pattern]).await?;
+        self.inner.keys(pattern).await
+    }
+
+    async fn scan_keys(&self, pattern: &str, cursor: u64) -> StorageResult<ScanPage> {
+        self.enter(StorageOp::ScanKeys, &[pattern]).await?;
+        self.inner.scan_keys(pattern, cursor).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use sa_token_core::{SaTokenConfig, SaTokenManager};
+
+    #[tokio::test]
+    async fn test_records_calls_of_login() {
+        let storage = Arc::new(InstrumentedMockStorage::new());
+        let manager = SaTokenManager::new(storage.clone(), SaTokenConfig::default());
+
+        let token = manager.login("user_1").await.unwrap();
+        assert!(storage.count(StorageOp::Set) > 0);
+        assert!(storage.count_key(&format!("sa:token:{}", token.as_str())) > 0);
+
+        storage.clear_calls();
+        assert!(manager.is_valid(&token).await);
+        assert!(storage.count(StorageOp::Get) + storage.count(StorageOp::Mget) > 0);
+    }
+
+    #[tokio::test]
+    async fn test_injected_failures_and_latency() {
+        let storage = InstrumentedMockStorage::new().with_latency(Duration::from_millis(20));
+        storage.set("k", "v", None).await.unwrap();
+
+        storage.fail_next(StorageOp::Get);
+        assert!(matches!(storage.get("k").await, Err(StorageError::ConnectionError(_))));
+        assert_eq!(storage.get("k").await.unwrap().as_deref(), Some("v"));
+
+        storage.fail_on(StorageOp::Delete);
+        assert!(storage.delete("k").await.is_err());
+        assert!(storage.delete("k").await.is_err());
+        storage.clear_failures();
+        storage.delete("k").await.unwrap();
+
+        let started = std::time::Instant::now();
+        storage.exists("k").await.unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(20));
+        assert_eq!(storage.count(StorageOp::Delete), 3);
+    }
+}
@@ -0,0 +1,134 @@
+// Author: 金书记
+//
+//! 时间冻结与时间旅行 | Time Freezing and Time Travel
+//!
+//! token 过期、nonce 时间窗口、refresh token、OAuth2 授权码与封禁到期都通过
+//! `sa_token_core::time::now()` 判断，这里的函数会统一影响它们，过期相关的集成测试
+//! 无需真的等待。
+//!
+//! Token expiry, nonce windows, refresh tokens, OAuth2 authorization codes and
+//! ban expiry all read `sa_token_core::time::now()`, so these helpers move them
+//! together and expiry tests no longer sleep.
+//!
+//! 时钟按线程生效，适用于 `#[tokio::test]` 默认的单线程运行时。存储自身的 TTL
+//! 不受影响：拨快后存储里可能仍有数据，但会被判定为已过期。
+//!
+//! The clock is per thread and fits the default current-thread runtime of
+//! `#[tokio::test]`. Storage-side TTLs are not affected: data may still be in
+//! storage after advancing, but it is treated as expired.
+//!
+//! 时钟本身是一个 [`MockClock`]。`freeze()` 返回它，注入 `MemoryStorage::with_clock` 后
+//! 存储的 TTL 也随之移动。
+//!
+//! The clock is a [`MockClock`]. `freeze()` returns it; inject it with
+//! `MemoryStorage::with_clock` and storage TTLs move along too.
+//!
+//! ```rust,ignore
+//! use std::time::Duration;
+//! use sa_token_test_utils::time;
+//!
+//! #[tokio::test]
+//! async fn token_expires() {
+//!     time::freeze();
+//!     let token = manager.login("user_1").await?;
+//!
+//!     time::advance(Duration::from_secs(3601));
+//!     assert!(!manager.is_valid(&token).await);
+//! }
+//! ```
+
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use sa_token_core::MockClock;
+
+/// sa-token 当前看到的时间 | The time sa-token currently sees
+pub fn now() -> DateTime<Utc> {
+    sa_token_core::time::now()
+}
+
+/// 把时间冻结在此刻，返回可注入 manager / 存储的时钟 | Freeze time at this instant; returns a clock to inject
+pub fn freeze() -> MockClock {
+    sa_token_core::time::freeze()
+}
+
+/// 把时间冻结在指定时刻 | Freeze time at `at`
+pub fn freeze_at(at: DateTime<Utc>) -> MockClock {
+    sa_token_core::time::freeze_at(at)
+}
+
+/// 让已注入实例的 `MockClock` 同时驱动 sa-token 的当前时间 | Make an injected `MockClock` drive sa-token's time as well
+pub fn install(clock: MockClock) -> MockClock {
+    sa_token_core::time::install(clock)
+}
+
+/// 拨快时间；未冻结时时间继续流逝 | Move time forward; it keeps flowing unless frozen
+pub fn advance(by: Duration) {
+    let by = chrono::Duration::from_std(by).unwrap_or(chrono::Duration::MAX);
+    sa_token_core::time::advance(by);
+}
+
+/// 恢复真实时间，使用 `--test-threads=1` 时应在测试结束前调用
+///
+/// Back to the wall clock. Call it at the end of a test when running with
+/// `--test-threads=1`, where tests share a thread.
+pub fn reset() {
+    sa_token_core::time::reset();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use sa_token_adapter::storage::SaStorage;
+    use sa_token_core::{NonceManager, OAuth2Manager, SaTokenConfig, SaTokenError, SaTokenManager};
+    use sa_token_storage_memory::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_advance_expires_tokens_and_codes() {
+        freeze();
+        let storage = Arc::new(MemoryStorage::new());
+        let config = SaTokenConfig::builder().timeout(3600).build_config();
+        let manager = SaTokenManager::new(storage.clone(), config);
+        let oauth2 = OAuth2Manager::new(storage.clone());
+
+        let token = manager.login("user_1").await.unwrap();
+        let code = oauth2.generate_authorization_code(
+            "client".into(), "user_1".into(), "https://example.com/cb".into(), vec![],
+        );
+        oauth2.store_authorization_code(&code).await.unwrap();
+
+        advance(Duration::from_secs(3599));
+        assert!(manager.is_valid(&token).await);
+
+        advance(Duration::from_secs(2));
+        assert!(!manager.is_valid(&token).await);
+        assert!(matches!(
+            oauth2.get_authorization_code(&code.code).await,
+            Err(SaTokenError::TokenExpired)
+        ));
+        reset();
+    }
+
+    #[tokio::test]
+    async fn test_frozen_clock_drives_injected_storage() {
+        let clock = freeze();
+        let storage = MemoryStorage::with_clock(Arc::new(clock));
+        storage.set("k", "v", Some(Duration::from_secs(60))).await.unwrap();
+
+        advance(Duration::from_secs(61));
+        assert_eq!(storage.get("k").await.unwrap(), None);
+        reset();
+    }
+
+    #[test]
+    fn test_advance_moves_nonce_window() {
+        freeze();
+        let nonces = NonceManager::new(Arc::new(MemoryStorage::new()), 60);
+        let nonce = nonces.generate();
+        assert!(nonces.check_timestamp(&nonce, 60).unwrap());
+
+        advance(Duration::from_secs(61));
+        assert!(!nonces.check_timestamp(&nonce, 60).unwrap());
+        reset();
+    }
+}
@@ -0,0 +1,54 @@
+// Author: 金书记
+//
+//! `http::Request` 适配器 | `http::Request` adapter
+
+use std::net::SocketAddr;
+use http::Request;
+use sa_token_adapter::context::SaRequest;
+use sa_token_adapter::utils::{parse_cookies, parse_query_string};
+
+/// 通用 HTTP 请求适配器 | Generic HTTP request adapter
+///
+/// 适用于任何基于 `http::Request` 的框架（hyper、tonic、axum 等）。
+/// Works for any framework built on `http::Request` (hyper, tonic, axum, ...).
+pub struct HttpRequestAdapter<'a, T> {
+    request: &'a Request<T>,
+}
+
+impl<'a, T> HttpRequestAdapter<'a, T> {
+    pub fn new(request: &'a Request<T>) -> Self {
+        Self { request }
+    }
+}
+
+impl<T> SaRequest for HttpRequestAdapter<'_, T> {
+    fn get_header(&self, name: &str) -> Option<String> {
+        self.request.headers().get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string())
+    }
+
+    fn get_cookie(&self, name: &str) -> Option<String> {
+        self.request.headers().get(http::header::COOKIE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|cookies| parse_cookies(cookies).remove(name))
+    }
+
+    fn get_param(&self, name: &str) -> Option<String> {
+        self.request.uri().query()
+            .and_then(|query| parse_query_string(query).remove(name))
+    }
+
+    fn get_path(&self) -> String {
+        self.request.uri().path().to_string()
+    }
+
+    fn get_method(&self) -> String {
+        self.request.method().to_string()
+    }
+
+    fn remote_addr(&self) -> Option<String> {
+        self.request.extensions().get::<SocketAddr>()
+            .map(|addr| addr.ip().to_string())
+    }
+}
@@ -0,0 +1,408 @@
+// Author: 金书记
+//
+//! Token 认证层 | Token authentication layer
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use http::{header::SET_COOKIE, Extensions, HeaderValue, Request, Response};
+use tower::{Layer, Service};
+use sa_token_core::{
+    DecisionTrace, SaTokenContext, resolve_client_ip, SaTokenManager, TokenValue,
+    ACCEPT_LANGUAGE_HEADER, DECISION_TRACE_HEADER, REQUEST_ID_HEADER, TIMEZONE_HEADER, SAME_TOKEN_HEADER,
+};
+use crate::adapter::HttpRequestAdapter;
+
+/// 从请求扩展中读取连接地址的函数 | Reads the peer address from request extensions
+///
+/// 各服务器把连接地址放在不同的扩展类型里（axum 的 `ConnectInfo`、tonic 的
+/// `TcpConnectInfo`），由使用方提供读取方式。
+/// Servers store the peer address under different extension types (axum's
+/// `ConnectInfo`, tonic's `TcpConnectInfo`), so the caller says where to look.
+pub type PeerAddrFn = fn(&Extensions) -> Option<SocketAddr>;
+
+/// 从请求扩展中读取路由模式的函数 | Reads the matched route pattern from request extensions
+///
+/// 用于拒绝统计（`SaTokenManager::top_denials`），axum 读取 `MatchedPath`。
+/// 默认不读取，统计中的路由记为 `unknown`。
+/// Feeds the denial metrics (`SaTokenManager::top_denials`); axum reads
+/// `MatchedPath`. Unset by default, so denials are counted under `unknown`.
+pub type RoutePatternFn = fn(&Extensions) -> Option<String>;
+
+/// sa-token 认证层 | sa-token authentication layer
+///
+/// 从请求头、Cookie 或查询参数中提取 token 并校验，通过后向请求扩展写入
+/// `TokenValue`、登录 ID（`String`）和 `SaTokenContext`，并在请求处理期间设置
+/// 任务级上下文，`StpUtil::get_login_id_as_string()` 等方法可直接使用。
+/// 未携带或携带无效 token 的请求照常放行，由后续的检查层决定是否拒绝。
+///
+/// Extracts the token from headers, cookies or the query string and
+/// validates it. On success, `TokenValue`, the login ID (`String`) and the
+/// `SaTokenContext` go into the request extensions, and the task-local context
+/// is set while the request is handled. Requests without a valid token pass
+/// through; later check layers decide whether to reject them.
+#[derive(Clone)]
+pub struct SaTokenLayer {
+    manager: Arc<SaTokenManager>,
+    peer_addr: PeerAddrFn,
+    route_pattern: RoutePatternFn,
+}
+
+impl SaTokenLayer {
+    pub fn new(manager: Arc<SaTokenManager>) -> Self {
+        Self {
+            manager,
+            peer_addr: socket_addr_extension,
+            route_pattern: |_| None,
+        }
+    }
+
+    /// 设置读取连接地址的方式，默认读取 `SocketAddr` 扩展 | Set how the peer address is read; defaults to a `SocketAddr` extension
+    pub fn peer_addr(mut self, peer_addr: PeerAddrFn) -> Self {
+        self.peer_addr = peer_addr;
+        self
+    }
+
+    /// 设置读取路由模式的方式 | Set how the route pattern is read
+    pub fn route_pattern(mut self, route_pattern: RoutePatternFn) -> Self {
+        self.route_pattern = route_pattern;
+        self
+    }
+}
+
+impl<S> Layer<S> for SaTokenLayer {
+    type Service = SaTokenService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        SaTokenService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// sa-token 认证服务 | sa-token authentication service
+#[derive(Clone)]
+pub struct SaTokenService<S> {
+    inner: S,
+    layer: SaTokenLayer,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for SaTokenService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<ReqBody>) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let layer = self.layer.clone();
+
+        Box::pin(async move {
+            let manager = &layer.manager;
+            let mut ctx = SaTokenContext::new();
+            ctx.capture_locale(header(&request, ACCEPT_LANGUAGE_HEADER), header(&request, TIMEZONE_HEADER));
+            ctx.capture_same_token(header(&request, SAME_TOKEN_HEADER));
+            let ip = manager.resolve_client_ip(
+                (layer.peer_addr)(request.extensions()).map(|addr| addr.ip().to_string()).as_deref(),
+                header(&request, "x-forwarded-for"),
+                header(&request, "x-real-ip"),
+            );
+            ctx.capture_client(ip.as_deref(), header(&request, "user-agent"));
+            ctx.route = (layer.route_pattern)(request.extensions());
+            let request_id = request.headers()
+                .get(REQUEST_ID_HEADER)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string);
+            let trace = manager.start_decision_trace(request_id);
+
+            // 从请求中提取 token
+            if let Some((token_str, source)) = extract_token(&request, manager) {
+                tracing::debug!("Sa-Token: extracted token from request: {}", token_str);
+                record_decision(trace.as_ref(), "token_source", source);
+                let token = TokenValue::new(token_str);
+
+                // 验证 token 并获取 token 信息
+                // 注意：get_token_info 内部已经处理了自动续签（如果配置开启）
+                match manager.get_token_info_from(&token, ctx.client_ip.as_deref()).await {
+                    Ok(token_info) => {
+                        record_decision(trace.as_ref(), "validation", "ok");
+                        request.extensions_mut().insert(token.clone());
+
+                        // 受限 token（如密码过期）不写入 login_id，登录检查中间件会拒绝它，
+                        // 只能通过 StpUtil::check_scope_current 访问对应接口
+                        if token_info.is_restricted() {
+                            record_decision(trace.as_ref(), "restricted", "true");
+                        } else {
+                            request.extensions_mut().insert(token_info.login_id.clone());
+                            ctx.login_id = Some(token_info.login_id.clone());
+                        }
+
+                        ctx.token = Some(token);
+                        ctx.token_info = Some(Arc::new(token_info));
+                    }
+                    Err(e) => {
+                        record_decision(trace.as_ref(), "validation", e.to_string());
+                        manager.report_auth_failure(token.as_str(), &e.to_string(), ip.as_deref()).await;
+                        // 记录失败原因，SaCheck 拒绝时返回它而不是 NotLogin
+                        ctx.auth_error = Some(Arc::new(e));
+                    }
+                }
+            } else {
+                record_decision(trace.as_ref(), "token_source", "none");
+            }
+
+            // 开发模式免登录 | Dev bypass
+            if let Some(login_id) = manager.dev_bypass_login_id().filter(|_| ctx.login_id.is_none()) {
+                record_decision(trace.as_ref(), "dev_bypass", &login_id);
+                request.extensions_mut().insert(login_id.to_string());
+                ctx.login_id = Some(login_id.to_string());
+            }
+
+            if let Some(trace) = &trace {
+                if let Some(login_id) = &ctx.login_id {
+                    trace.record("login_id", login_id.as_str());
+                }
+                request.extensions_mut().insert(trace.clone());
+            }
+            request.extensions_mut().insert(ctx.clone());
+            let response_cookie = ctx.clone();
+
+            // 在当前请求的上下文中继续处理
+            let mut response = SaTokenContext::scope(ctx, inner.call(request)).await;
+
+            // 处理期间登录/登出产生的 token Cookie（`is_write_cookie`）
+            if let (Ok(response), Some(cookie)) = (&mut response, response_cookie.take_response_cookie())
+                && let Ok(value) = HeaderValue::from_str(&cookie.header_value())
+            {
+                response.headers_mut().append(SET_COOKIE, value);
+            }
+
+            // 调试模式：返回决策日志并按请求 ID 保存
+            if let Some(trace) = trace {
+                if let Ok(response) = &mut response {
+                    let headers = response.headers_mut();
+                    if let Ok(value) = HeaderValue::from_str(trace.request_id()) {
+                        headers.insert(REQUEST_ID_HEADER, value);
+                    }
+                    if let Ok(value) = HeaderValue::from_str(&trace.header_value()) {
+                        headers.insert(DECISION_TRACE_HEADER, value);
+                    }
+                }
+                manager.finish_decision_trace(&trace);
+            }
+
+            response
+        })
+    }
+}
+
+fn header<'a, T>(request: &'a Request<T>, name: &str) -> Option<&'a str> {
+    request.headers().get(name).and_then(|v| v.to_str().ok())
+}
+
+fn socket_addr_extension(extensions: &Extensions) -> Option<SocketAddr> {
+    extensions.get::<SocketAddr>().copied()
+}
+
+/// 请求来源 IP：优先取 `X-Forwarded-For` 的第一个地址、`X-Real-IP`，否则取连接地址
+/// 
+/// 不考虑受信任代理；认证层使用 `SaTokenManager::resolve_client_ip`，按 `trusted_proxies` 解析。
+pub fn client_ip<T>(request: &Request<T>, peer_addr: PeerAddrFn) -> Option<String> {
+    resolve_client_ip(
+        peer_addr(request.extensions()).map(|addr| addr.ip().to_string()).as_deref(),
+        header(request, "x-forwarded-for"),
+        header(request, "x-real-ip"),
+        &[],
+    )
+}
+
+/// 向决策日志追加一步（未开启调试模式时忽略）
+pub fn record_decision(trace: Option<&DecisionTrace>, stage: &str, result: impl Into<String>) {
+    if let Some(trace) = trace {
+        trace.record(stage, result);
+    }
+}
+
+/// 从请求中提取 Token
+///
+/// 先按优先级调用自定义提取器（`SaTokenConfig::builder().token_extractor()`），再按
+/// `SaTokenConfig::token_sources` 的顺序查找，默认 Header → Authorization → Cookie → Query。
+/// 规则见 [`SaTokenManager::extract_token`]。
+///
+/// # 返回
+/// - `Some((token, source))` - 找到的 token 及其来源
+/// - `None` - 未找到 token
+pub fn extract_token<T>(request: &Request<T>, manager: &SaTokenManager) -> Option<(String, &'static str)> {
+    manager.extract_token(&HttpRequestAdapter::new(request))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::Infallible;
+    use sa_token_core::{SaCheck, SaTokenConfig, SaTokenError, TokenInfo, PASSWORD_CHANGE_SCOPE};
+    use sa_token_test_utils::state::{state, state_with};
+    use tower::{service_fn, ServiceExt};
+
+    fn manager() -> Arc<SaTokenManager> {
+        state().manager
+    }
+
+    async fn echo(request: Request<()>) -> Result<Response<String>, Infallible> {
+        let login_id = request.extensions().get::<String>().cloned().unwrap_or_default();
+        let ctx = request.extensions().get::<SaTokenContext>().cloned().unwrap();
+        assert_eq!(ctx.login_id.as_deref().unwrap_or_default(), login_id);
+        assert_eq!(SaTokenContext::get_current().and_then(|c| c.login_id).unwrap_or_default(), login_id);
+        Ok(Response::new(login_id))
+    }
+
+    /// 需要登录的接口：按 `SaCheck` 的拒绝结果返回状态码与原因 | A login-only route answering with `SaCheck`'s rejection
+    async fn guarded(request: Request<()>) -> Result<Response<String>, Infallible> {
+        let login_id = request.extensions().get::<String>().cloned();
+        let response = match SaCheck::login().check(login_id.as_deref()).await {
+            Ok(()) => Response::new(login_id.unwrap_or_default()),
+            Err(e) => {
+                let (status, body) = SaCheck::rejection(&e);
+                Response::builder().status(status).body(body["message"].as_str().unwrap_or_default().to_string()).unwrap()
+            }
+        };
+        Ok(response)
+    }
+
+    #[tokio::test]
+    async fn test_layer_injects_login_from_header_cookie_and_query() {
+        let manager = manager();
+        let token = manager.login("user_1").await.unwrap();
+        let service = SaTokenLayer::new(manager).layer(service_fn(echo));
+
+        let requests = [
+            Request::builder().header("Authorization", format!("Bearer {}", token)).body(()).unwrap(),
+            Request::builder().header("cookie", format!("theme=dark; sa-token={}", token)).body(()).unwrap(),
+            Request::builder().uri(format!("/?sa-token={}", token)).body(()).unwrap(),
+        ];
+        for request in requests {
+            let response = service.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.body(), "user_1");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_layer_writes_token_cookie_on_login_and_logout() {
+        let config = SaTokenConfig { is_write_cookie: true, cookie_secure: true, ..SaTokenConfig::default() };
+        let manager = state_with(config).manager;
+        let login_manager = manager.clone();
+        let service = SaTokenLayer::new(manager.clone()).layer(service_fn(move |request: Request<()>| {
+            let manager = login_manager.clone();
+            async move {
+                match request.extensions().get::<TokenValue>() {
+                    Some(token) => manager.logout(token).await.unwrap(),
+                    None => { manager.login("user_1").await.unwrap(); }
+                }
+                Ok::<_, Infallible>(Response::builder().header(SET_COOKIE, "theme=dark").body(()).unwrap())
+            }
+        }));
+
+        let response = service.clone().oneshot(Request::new(())).await.unwrap();
+        let cookies: Vec<_> = response.headers().get_all(SET_COOKIE).iter().map(|v| v.to_str().unwrap()).collect();
+        assert_eq!(cookies.len(), 2);
+        let token = cookies[1].split(';').next().unwrap().trim_start_matches("sa-token=").to_string();
+        assert!(manager.is_valid(&TokenValue::new(token.clone())).await);
+        assert!(cookies[1].contains("Path=/") && cookies[1].contains("HttpOnly") && cookies[1].contains("Secure"));
+
+        let request = Request::builder().header("cookie", format!("sa-token={}", token)).body(()).unwrap();
+        let response = service.oneshot(request).await.unwrap();
+        let cleared = response.headers().get_all(SET_COOKIE).iter().next_back().unwrap().to_str().unwrap();
+        assert!(cleared.starts_with("sa-token=;") && cleared.contains("Max-Age=0"));
+    }
+
+    #[tokio::test]
+    async fn test_layer_accepts_configured_schemes() {
+        let config = SaTokenConfig { token_schemes: vec!["Bearer".into(), "Token".into()], ..SaTokenConfig::default() };
+        let manager = state_with(config).manager;
+        let token = manager.login("user_1").await.unwrap();
+        let service = SaTokenLayer::new(manager).layer(service_fn(echo));
+
+        for value in [format!("bearer {}", token), format!("TOKEN  {}", token), format!(" Bearer\t{} ", token)] {
+            let request = Request::builder().header("Authorization", value).body(()).unwrap();
+            assert_eq!(service.clone().oneshot(request).await.unwrap().body(), "user_1");
+        }
+        let request = Request::builder().header("Authorization", format!("Basic {}", token)).body(()).unwrap();
+        assert_eq!(service.oneshot(request).await.unwrap().body(), "");
+    }
+
+    #[tokio::test]
+    async fn test_restricted_token_is_rejected_by_login_check() {
+        let manager = manager();
+        let mut token_info = TokenInfo::new(TokenValue::new(""), "user_1");
+        token_info.scope = Some(vec![PASSWORD_CHANGE_SCOPE.to_string()]);
+        let token = manager.login_with_token_info(token_info).await.unwrap();
+        let service = SaTokenLayer::new(manager).layer(service_fn(guarded));
+
+        let request = Request::builder().header("sa-token", token.as_str()).body(()).unwrap();
+        let response = service.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), 401);
+        assert_eq!(response.body(), &SaTokenError::PasswordExpired.to_string());
+    }
+
+    #[tokio::test]
+    async fn test_replaced_and_kicked_out_tokens_are_rejected_with_reason() {
+        let config = SaTokenConfig { is_concurrent: false, ..Default::default() };
+        let manager = state_with(config).manager;
+        let replaced = manager.login("user_1").await.unwrap();
+        let current = manager.login("user_1").await.unwrap();
+        manager.kick_out_with_reason("user_1", "Signed in elsewhere").await.unwrap();
+        let service = SaTokenLayer::new(manager).layer(service_fn(guarded));
+
+        let request = Request::builder().header("sa-token", replaced.as_str()).body(()).unwrap();
+        let response = service.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), 401);
+        assert_eq!(response.body(), &SaTokenError::Replaced.to_string());
+
+        let request = Request::builder().header("sa-token", current.as_str()).body(()).unwrap();
+        let response = service.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), 401);
+        assert_eq!(response.body(), &SaTokenError::KickedOut("Signed in elsewhere".to_string()).to_string());
+    }
+
+    #[tokio::test]
+    async fn test_token_used_from_another_ip_is_rejected_with_reason() {
+        let config = SaTokenConfig { token_bind_ip: true, ..Default::default() };
+        let manager = state_with(config).manager;
+        let mut login_ctx = SaTokenContext::new();
+        login_ctx.capture_client(Some("203.0.113.9"), None);
+        let token = SaTokenContext::scope(login_ctx, manager.login("user_1")).await.unwrap();
+        let service = SaTokenLayer::new(manager).layer(service_fn(guarded));
+
+        let call_from = |peer: &str| {
+            let mut request = Request::builder().header("sa-token", token.as_str()).body(()).unwrap();
+            request.extensions_mut().insert(peer.parse::<SocketAddr>().unwrap());
+            service.clone().oneshot(request)
+        };
+        assert_eq!(call_from("203.0.113.9:4000").await.unwrap().body(), "user_1");
+
+        let response = call_from("198.51.100.4:4000").await.unwrap();
+        assert_eq!(response.status(), 401);
+        assert_eq!(response.body(), &SaTokenError::IpMismatch.to_string());
+    }
+
+    #[tokio::test]
+    async fn test_layer_passes_requests_without_valid_token() {
+        let service = SaTokenLayer::new(manager()).layer(service_fn(echo));
+
+        let response = service.clone().oneshot(Request::new(())).await.unwrap();
+        assert_eq!(response.body(), "");
+
+        let request = Request::builder().header("sa-token", "expired-or-forged").body(()).unwrap();
+        let response = service.oneshot(request).await.unwrap();
+        assert_eq!(response.body(), "");
+    }
+}
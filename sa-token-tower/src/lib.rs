@@ -0,0 +1,39 @@
+// Author: 金书记
+//
+//! # sa-token-tower
+//!
+//! 与框架无关的 tower 中间件，适用于 hyper、tonic 以及其他基于 `tower::Service`
+//! 和 `http::Request` 的服务；sa-token-plugin-axum 的 `SaTokenLayer` 也基于它实现。
+//!
+//! Framework-agnostic tower middleware for hyper, tonic and any other service
+//! built on `tower::Service` and `http::Request`. The axum plugin's
+//! `SaTokenLayer` is built on it.
+//!
+//! ```rust,ignore
+//! use sa_token_tower::SaTokenLayer;
+//!
+//! let manager = Arc::new(SaTokenConfig::builder().storage(storage).build());
+//!
+//! // tonic
+//! Server::builder()
+//!     .layer(SaTokenLayer::new(manager.clone()))
+//!     .add_service(GreeterServer::new(greeter))
+//!     .serve(addr)
+//!     .await?;
+//!
+//! // hyper + tower
+//! let service = ServiceBuilder::new()
+//!     .layer(SaTokenLayer::new(manager))
+//!     .service_fn(handle);
+//! ```
+//!
+//! 处理函数中通过请求扩展读取 `TokenValue`、登录 ID（`String`）或 `SaTokenContext`，
+//! 也可以直接使用 `StpUtil`。
+//! Handlers read `TokenValue`, the login ID (`String`) or the
+//! `SaTokenContext` from the request extensions, or call `StpUtil` directly.
+
+pub mod adapter;
+pub mod layer;
+
+pub use adapter::HttpRequestAdapter;
+pub use layer::{SaTokenLayer, SaTokenService, PeerAddrFn, RoutePatternFn, extract_token, client_ip, record_decision};
@@ -0,0 +1,310 @@
+// Author: 金书记
+//
+//! # sa-token
+//!
+//! sa-token-rust 的稳定门面 crate | Stable facade for sa-token-rust
+//!
+//! 工作区里的 core / adapter / storage / plugin 各 crate 会随功能演进调整内部模块结构，
+//! 本 crate 只重新导出经过审定的稳定子集，并由 `scripts/check_public_api.sh` 对比
+//! `cargo public-api` 基线守护：这里列出的条目在同一主版本内不会被移除或改名。
+//! 内部 crate 的路径（如 `sa_token_core::manager`）不在此承诺范围内。
+//!
+//! The workspace crates reorganize their modules as features land. This crate
+//! re-exports a reviewed, stable subset, and `scripts/check_public_api.sh`
+//! diffs it against a `cargo public-api` baseline: nothing listed here is
+//! removed or renamed within a major version. Paths into the internal crates
+//! (e.g. `sa_token_core::manager`) carry no such promise.
+//!
+//! ## 快速开始 | Quick start
+//!
+//! ```toml
+//! [dependencies]
+//! sa-token = { version = "0.1.11", features = ["axum"] }          # 默认启用内存存储 | memory storage by default
+//! sa-token = { version = "0.1.11", features = ["axum", "redis"] }
+//! ```
+//!
+//! ```rust,ignore
+//! use std::sync::Arc;
+//! use sa_token::{SaTokenConfig, StpUtil, sa_check_login};
+//! use sa_token::storage::MemoryStorage;
+//! use sa_token::axum::{SaTokenLayer, SaTokenState};
+//!
+//! let state = SaTokenState::builder()
+//!     .storage(Arc::new(MemoryStorage::new()))
+//!     .timeout(7200)
+//!     .build();
+//! let app = Router::new()
+//!     .route("/user", get(user_info))
+//!     .layer(SaTokenLayer::new(state));
+//!
+//! #[sa_check_login]
+//! async fn user_info() -> Result<String, sa_token::SaTokenError> {
+//!     StpUtil::get_login_id_as_string().await
+//! }
+//! ```
+//!
+//! ## Features
+//!
+//! - 存储 | Storage: `memory`（默认 | default）、`redis`、`database`
+//! - 配置文件 | Config files: TOML、JSON（默认 | default）、`yaml`
+//! - 框架 | Frameworks: `axum`、`actix-web`、`poem`、`rocket`、`warp`、`salvo`、`tide`、`gotham`、`ntex`、`tower`
+
+// ============================================================================
+// 核心 | Core
+// ============================================================================
+
+pub use sa_token_core::{
+    SaTokenManager, SaTokenConfig, JsonFieldCase, LoginEviction, StorageConfig, StpUtil, StpLogic, LoginId, LoginOptions, LoginResult,
+    SaTokenError, SaTokenResult, ErrorRenderer, ErrorResponse, DefaultErrorRenderer, ProblemJsonRenderer,
+    TokenValue, TokenInfo, TokenGenerator, DefaultTokenGenerator, SaSession, SaTokenContext, SaTokenState, SaTokenStateBuilder,
+    Clock, SystemClock, MockClock,
+};
+pub use sa_token_core::config::{SaTokenConfigBuilder, TokenStyle};
+
+// 过程宏展开为 `sa_token_core::...` 路径，`use sa_token::*` 时需要该名称可见；不属于稳定 API
+// The macros expand to `sa_token_core::...` paths, so the name must be visible
+// after `use sa_token::*`. Not part of the stable API.
+#[doc(hidden)]
+pub use sa_token_core;
+
+// ============================================================================
+// 权限 | Authorization
+// ============================================================================
+
+pub use sa_token_core::{PermissionChecker, RoleChecker, PermissionMatcher, AuthzKind, SaCheck, CheckMode, DenialCount};
+
+// ============================================================================
+// 事件 | Events
+// ============================================================================
+
+pub use sa_token_core::{SaTokenEvent, SaTokenEventType, SaTokenListener, SaTokenEventBus, LoggingListener, EventDispatchMode, QueueOverflow};
+
+// ============================================================================
+// 存储与适配器接口 | Storage and adapter traits
+// ============================================================================
+
+pub use sa_token_adapter::{SaStorage, SaRequest, SaResponse, FrameworkAdapter, CookieOptions, SameSite, ResponseCookie};
+
+// ============================================================================
+// 过程宏 | Procedural macros
+// ============================================================================
+
+pub use sa_token_macro::{
+    sa_check_login,
+    sa_check_permission,
+    sa_check_role,
+    sa_check_permissions_and,
+    sa_check_permissions_or,
+    sa_check_roles_and,
+    sa_check_roles_or,
+    sa_ignore,
+};
+
+/// JWT 与令牌安全 | JWT and token security
+pub mod token {
+    pub use sa_token_core::{
+        JwtManager, JwtClaims, JwtAlgorithm, JwtKeyStore, JwtSecret,
+        NonceManager, RefreshTokenManager, RefreshResult, TempTokenManager, SignManager, SameTokenManager, ApiKeyManager, ApiKeyInfo, HttpBasicAuth, IpCidr, AuditLogger, AuditRecord, AuditPage, StorageAuditLogger,
+        TokenSource, TokenExtractorSource, TokenExtractor, TokenRequest,
+    };
+}
+
+/// OAuth2 授权 | OAuth2 authorization
+pub mod oauth2 {
+    pub use sa_token_core::{
+        OAuth2Manager, OAuth2Client, AuthorizationCode, AccessToken, OAuth2TokenInfo, PkceMethod,
+        GRANT_TYPE_CLIENT_CREDENTIALS, GRANT_TYPE_PASSWORD, IntrospectionResponse,
+    };
+}
+
+/// SSO 单点登录 | Single sign-on
+pub mod sso {
+    pub use sa_token_core::{
+        SsoManager, SsoServer, SsoClient, SsoConfig, SsoTicket, SsoSession,
+        SsoCredentialValidator, SsoLoginResult,
+    };
+}
+
+/// 在线用户与消息推送 | Online users and message push
+pub mod online {
+    pub use sa_token_core::{OnlineManager, OnlineUser, OnlinePage, PushMessage, MessageType, MessagePusher, InMemoryPusher,
+        SsePusher, SseSubscription, SseFrame};
+}
+
+/// 存储实现 | Storage backends
+pub mod storage {
+    #[cfg(feature = "memory")]
+    pub use sa_token_storage_memory::MemoryStorage;
+    #[cfg(feature = "redis")]
+    pub use sa_token_storage_redis::RedisStorage;
+    #[cfg(feature = "redis-event-bridge")]
+    pub use sa_token_storage_redis::RedisEventBridge;
+    #[cfg(feature = "database")]
+    pub use sa_token_storage_database::DatabaseStorage;
+
+    use std::sync::Arc;
+    use sa_token_core::{SaTokenError, SaTokenResult, StorageConfig};
+    use sa_token_adapter::SaStorage;
+
+    /// 按配置创建存储，对应的存储 feature 未启用时返回 `ConfigError`
+    ///
+    /// Creates the storage selected by `config.storage`. Returns a
+    /// `ConfigError` when the matching storage feature is not enabled.
+    ///
+    /// ```rust,ignore
+    /// let config = SaTokenConfig::from_file("sa-token.toml")?.merge_env()?;
+    /// let storage = sa_token::storage::from_config(&config.storage).await?;
+    /// let manager = config.into_builder().storage(storage).build();
+    /// ```
+    pub async fn from_config(config: &StorageConfig) -> SaTokenResult<Arc<dyn SaStorage>> {
+        match config {
+            #[cfg(feature = "memory")]
+            StorageConfig::Memory => Ok(Arc::new(MemoryStorage::new())),
+            #[cfg(feature = "redis")]
+            StorageConfig::Redis { url, key_prefix } => RedisStorage::new(url, key_prefix.as_str()).await
+                .map(|storage| Arc::new(storage) as Arc<dyn SaStorage>)
+                .map_err(|e| SaTokenError::StorageError(e.to_string())),
+            #[cfg(feature = "database")]
+            StorageConfig::Database { url } => DatabaseStorage::new(url).await
+                .map(|storage| Arc::new(storage) as Arc<dyn SaStorage>)
+                .map_err(|e| SaTokenError::StorageError(e.to_string())),
+            #[allow(unreachable_patterns)]
+            other => Err(SaTokenError::ConfigError(format!(
+                "storage {:?} requires the matching sa-token feature", other
+            ))),
+        }
+    }
+}
+
+// ============================================================================
+// 框架插件 | Framework plugins
+// ============================================================================
+
+/// Axum 集成 | Axum integration
+#[cfg(feature = "axum")]
+pub mod axum {
+    pub use sa_token_plugin_axum::{
+        SaTokenState, SaTokenStateBuilder, SaTokenLayer, SaTokenMiddleware,
+        SaCheckLoginLayer, SaCheckPermissionLayer,
+        SaTokenExtractor, OptionalSaTokenExtractor, LoginIdExtractor,
+        sso_router, oauth2_router,
+    };
+}
+
+/// Actix-web 集成 | Actix-web integration
+#[cfg(feature = "actix-web")]
+pub mod actix_web {
+    pub use sa_token_plugin_actix_web::{
+        SaTokenState, SaTokenStateBuilder, SaTokenLayer, SaTokenMiddleware, SaCheckLoginMiddleware,
+        SaTokenExtractor, OptionalSaTokenExtractor, LoginIdExtractor,
+        sso_scope, oauth2_scope,
+    };
+}
+
+/// Poem 集成 | Poem integration
+#[cfg(feature = "poem")]
+pub mod poem {
+    pub use sa_token_plugin_poem::{
+        SaTokenState, SaTokenStateBuilder, SaTokenLayer, SaTokenMiddleware,
+        SaCheckLoginMiddleware, SaCheckPermissionMiddleware, SaCheckRoleMiddleware,
+        SaTokenExtractor, OptionalSaTokenExtractor, LoginIdExtractor,
+    };
+}
+
+/// Rocket 集成 | Rocket integration
+#[cfg(feature = "rocket")]
+pub mod rocket {
+    pub use sa_token_plugin_rocket::{
+        SaTokenState, SaTokenStateBuilder, SaTokenLayer, SaTokenFairing,
+        SaCheckLoginFairing, SaCheckPermissionFairing, SaCheckRoleFairing,
+        SaTokenGuard, OptionalSaTokenGuard, LoginIdGuard,
+    };
+}
+
+/// Warp 集成 | Warp integration
+#[cfg(feature = "warp")]
+pub mod warp {
+    pub use sa_token_plugin_warp::{
+        SaTokenState, SaTokenStateBuilder, sa_token_filter, sa_check_login_filter,
+        with_auth, with_permission, with_role, handle_rejection, with_token_cookie, TokenData,
+        SaTokenExtractor, OptionalSaTokenExtractor, LoginIdExtractor,
+    };
+}
+
+/// Salvo 集成 | Salvo integration
+#[cfg(feature = "salvo")]
+pub mod salvo {
+    pub use sa_token_plugin_salvo::{
+        SaTokenState, SaTokenStateBuilder, SaTokenLayer,
+        SaTokenExtractor, OptionalSaTokenExtractor, LoginIdExtractor,
+    };
+}
+
+/// Tide 集成 | Tide integration
+#[cfg(feature = "tide")]
+pub mod tide {
+    pub use sa_token_plugin_tide::{
+        SaTokenState, SaTokenStateBuilder, SaTokenLayer,
+        SaTokenExtractor, OptionalSaTokenExtractor, LoginIdExtractor,
+    };
+}
+
+/// Gotham 集成 | Gotham integration
+#[cfg(feature = "gotham")]
+pub mod gotham {
+    pub use sa_token_plugin_gotham::{
+        SaTokenState, SaTokenStateBuilder, SaTokenLayer,
+        SaTokenExtractor, OptionalSaTokenExtractor, LoginIdExtractor,
+    };
+}
+
+/// Ntex 集成 | Ntex integration
+#[cfg(feature = "ntex")]
+pub mod ntex {
+    pub use sa_token_plugin_ntex::{
+        SaTokenState, SaTokenStateBuilder, SaTokenLayer,
+        SaTokenExtractor, OptionalSaTokenExtractor, LoginIdExtractor,
+    };
+}
+
+/// 通用 tower 中间件（hyper、tonic 等）| Generic tower middleware (hyper, tonic, ...)
+#[cfg(feature = "tower")]
+pub mod tower {
+    pub use sa_token_tower::{SaTokenLayer, SaTokenService};
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use super::*;
+
+    #[tokio::test]
+    async fn test_facade_login_flow() {
+        let manager = SaTokenConfig::builder()
+            .storage(Arc::new(storage::MemoryStorage::new()))
+            .build();
+        let token: TokenValue = manager.login("user_1").await.unwrap();
+        assert!(manager.is_valid(&token).await);
+        let info: TokenInfo = manager.get_token_info(&token).await.unwrap();
+        assert_eq!(info.login_id, "user_1");
+    }
+
+    #[tokio::test]
+    async fn test_storage_from_config() {
+        let storage = storage::from_config(&StorageConfig::Memory).await.unwrap();
+        storage.set("k", "v", None).await.unwrap();
+        #[cfg(not(feature = "redis"))]
+        {
+            let redis = StorageConfig::Redis { url: "redis://localhost".into(), key_prefix: "sa-token:".into() };
+            assert!(matches!(storage::from_config(&redis).await, Err(SaTokenError::ConfigError(_))));
+        }
+    }
+
+    #[test]
+    fn test_facade_paths_resolve() {
+        let _ = oauth2::PkceMethod::S256;
+        let _ = AuthzKind::Permission;
+        let _: fn(&str) -> SaTokenResult<()> = |_| Err(SaTokenError::NotLogin);
+        let _ = TokenStyle::Uuid;
+    }
+}
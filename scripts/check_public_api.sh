@@ -0,0 +1,49 @@
+#!/usr/bin/env bash
+#
+# 检查 sa-token 门面 crate 的公开 API 是否与基线一致
+# Check the public API of the sa-token facade crate against the committed baseline.
+#
+# 依赖 | Requires:
+#   cargo install cargo-public-api --locked
+#   rustup toolchain install nightly          # cargo-public-api 需要 nightly rustdoc JSON
+#   cargo install cargo-semver-checks --locked  # 可选 | optional
+#
+# 用法 | Usage:
+#   scripts/check_public_api.sh           # 与基线对比，有差异时失败 | fail on any difference
+#   scripts/check_public_api.sh --bless   # 有意修改 API 后更新基线 | update the baseline after an intended change
+
+set -euo pipefail
+
+WORKSPACE_ROOT="$(cd "$(dirname "${BASH_SOURCE[0]}")/.." && pwd)"
+BASELINE="$WORKSPACE_ROOT/sa-token/public-api.txt"
+CURRENT="$(mktemp)"
+trap 'rm -f "$CURRENT"' EXIT
+
+cd "$WORKSPACE_ROOT"
+cargo public-api --package sa-token --all-features --simplified > "$CURRENT"
+
+if [[ "${1:-}" == "--bless" ]]; then
+  cp "$CURRENT" "$BASELINE"
+  echo "Baseline updated: $BASELINE"
+  exit 0
+fi
+
+if [[ ! -f "$BASELINE" ]]; then
+  echo "Missing baseline $BASELINE; run: scripts/check_public_api.sh --bless" >&2
+  exit 1
+fi
+
+if ! diff -u "$BASELINE" "$CURRENT"; then
+  echo >&2
+  echo "Public API of sa-token changed. Removals and renames need a major version bump;" >&2
+  echo "for intended additions run: scripts/check_public_api.sh --bless" >&2
+  exit 1
+fi
+echo "Public API matches baseline."
+
+# 与 crates.io 上已发布的版本做 semver 检查 | Semver check against the latest published release
+if cargo semver-checks --version > /dev/null 2>&1; then
+  cargo semver-checks check-release --package sa-token --all-features
+else
+  echo "cargo-semver-checks not installed, skipping semver check."
+fi
@@ -28,6 +28,8 @@ publish "$WORKSPACE_ROOT/sa-token-plugin-rocket/Cargo.toml"
 publish "$WORKSPACE_ROOT/sa-token-plugin-salvo/Cargo.toml"
 publish "$WORKSPACE_ROOT/sa-token-plugin-tide/Cargo.toml"
 publish "$WORKSPACE_ROOT/sa-token-plugin-warp/Cargo.toml"
+publish "$WORKSPACE_ROOT/sa-token-tower/Cargo.toml"
+publish "$WORKSPACE_ROOT/sa-token/Cargo.toml"
 publish "$WORKSPACE_ROOT/Cargo.toml"
 
 echo "All crates published."